@@ -9,9 +9,255 @@ use crate::common::{
     DynamicNumber, DynamicString, DynamicStringList,
 };
 
+/// 根组件 ID，Surface 从该组件开始渲染
+pub const ROOT_COMPONENT_ID: &str = "root";
+
 /// 标准组件目录 ID
 pub const STANDARD_CATALOG_ID: &str = "https://a2ui.org/specification/v0_10/standard_catalog.json";
 
+// ============================================================================
+// 组件版本
+// ============================================================================
+
+/// 组件属性在当前目录版本中的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropStatus {
+    /// 正常可用
+    Active,
+    /// 已弃用，仍可使用但应迁移
+    Deprecated {
+        /// 从该组件版本开始弃用
+        since: u32,
+        /// 替代属性
+        replacement: Option<&'static str>,
+    },
+    /// 已移除，客户端不再支持
+    Removed {
+        /// 从该组件版本开始移除
+        since: u32,
+    },
+}
+
+/// 组件属性定义
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropSpec {
+    /// 属性名（camelCase，与 JSON 一致）
+    pub name: &'static str,
+    /// 属性状态
+    pub status: PropStatus,
+}
+
+impl PropSpec {
+    /// 可用属性
+    pub const fn active(name: &'static str) -> Self {
+        Self {
+            name,
+            status: PropStatus::Active,
+        }
+    }
+
+    /// 已弃用属性
+    pub const fn deprecated(
+        name: &'static str,
+        since: u32,
+        replacement: Option<&'static str>,
+    ) -> Self {
+        Self {
+            name,
+            status: PropStatus::Deprecated { since, replacement },
+        }
+    }
+
+    /// 已移除属性
+    pub const fn removed(name: &'static str, since: u32) -> Self {
+        Self {
+            name,
+            status: PropStatus::Removed { since },
+        }
+    }
+}
+
+/// 目录中的组件定义
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentSpec {
+    /// 组件名称（与 `component` 字段一致）
+    pub name: &'static str,
+    /// 组件当前版本
+    pub version: u32,
+    /// 组件专有属性（通用属性见 [`COMMON_PROPS`]）
+    pub props: &'static [PropSpec],
+}
+
+impl ComponentSpec {
+    /// 查找属性定义，包括通用属性
+    pub fn prop(&self, name: &str) -> Option<&PropSpec> {
+        self.props
+            .iter()
+            .chain(COMMON_PROPS)
+            .find(|p| p.name == name)
+    }
+}
+
+/// 所有组件共有的属性
+pub const COMMON_PROPS: &[PropSpec] = &[
+    PropSpec::active("id"),
+    PropSpec::active("component"),
+    PropSpec::active("accessibility"),
+    PropSpec::active("weight"),
+];
+
+/// 标准目录中各组件的版本与属性
+pub const STANDARD_CATALOG_COMPONENTS: &[ComponentSpec] = &[
+    ComponentSpec {
+        name: "Text",
+        version: 1,
+        props: &[PropSpec::active("text"), PropSpec::active("variant")],
+    },
+    ComponentSpec {
+        name: "Image",
+        version: 1,
+        props: &[
+            PropSpec::active("url"),
+            PropSpec::active("fit"),
+            PropSpec::active("variant"),
+        ],
+    },
+    ComponentSpec {
+        name: "Icon",
+        version: 1,
+        props: &[PropSpec::active("name")],
+    },
+    ComponentSpec {
+        name: "Video",
+        version: 1,
+        props: &[PropSpec::active("url")],
+    },
+    ComponentSpec {
+        name: "AudioPlayer",
+        version: 1,
+        props: &[PropSpec::active("url"), PropSpec::active("description")],
+    },
+    ComponentSpec {
+        name: "Row",
+        version: 1,
+        props: &[
+            PropSpec::active("children"),
+            PropSpec::active("justify"),
+            PropSpec::active("align"),
+        ],
+    },
+    ComponentSpec {
+        name: "Column",
+        version: 1,
+        props: &[
+            PropSpec::active("children"),
+            PropSpec::active("justify"),
+            PropSpec::active("align"),
+        ],
+    },
+    ComponentSpec {
+        name: "List",
+        version: 1,
+        props: &[
+            PropSpec::active("children"),
+            PropSpec::active("direction"),
+            PropSpec::active("align"),
+        ],
+    },
+    ComponentSpec {
+        name: "Card",
+        version: 1,
+        props: &[PropSpec::active("child")],
+    },
+    ComponentSpec {
+        name: "Tabs",
+        version: 1,
+        props: &[PropSpec::active("tabs")],
+    },
+    ComponentSpec {
+        name: "Modal",
+        version: 1,
+        props: &[PropSpec::active("trigger"), PropSpec::active("content")],
+    },
+    ComponentSpec {
+        name: "Divider",
+        version: 1,
+        props: &[PropSpec::active("axis")],
+    },
+    ComponentSpec {
+        name: "Button",
+        version: 1,
+        props: &[
+            PropSpec::active("child"),
+            PropSpec::active("action"),
+            PropSpec::active("variant"),
+            PropSpec::active("checks"),
+        ],
+    },
+    ComponentSpec {
+        name: "TextField",
+        version: 1,
+        props: &[
+            PropSpec::active("label"),
+            PropSpec::active("value"),
+            PropSpec::active("variant"),
+            PropSpec::active("checks"),
+        ],
+    },
+    ComponentSpec {
+        name: "CheckBox",
+        version: 1,
+        props: &[
+            PropSpec::active("label"),
+            PropSpec::active("value"),
+            PropSpec::active("checks"),
+        ],
+    },
+    ComponentSpec {
+        name: "ChoicePicker",
+        version: 1,
+        props: &[
+            PropSpec::active("label"),
+            PropSpec::active("options"),
+            PropSpec::active("value"),
+            PropSpec::active("variant"),
+            PropSpec::active("checks"),
+        ],
+    },
+    ComponentSpec {
+        name: "Slider",
+        version: 1,
+        props: &[
+            PropSpec::active("label"),
+            PropSpec::active("min"),
+            PropSpec::active("max"),
+            PropSpec::active("value"),
+            PropSpec::active("checks"),
+        ],
+    },
+    ComponentSpec {
+        name: "DateTimeInput",
+        version: 1,
+        props: &[
+            PropSpec::active("label"),
+            PropSpec::active("value"),
+            PropSpec::active("enableDate"),
+            PropSpec::active("enableTime"),
+            PropSpec::active("min"),
+            PropSpec::active("max"),
+            PropSpec::active("checks"),
+        ],
+    },
+];
+
+/// 在组件目录中查找组件定义
+pub fn find_component_spec<'a>(
+    catalog: &'a [ComponentSpec],
+    name: &str,
+) -> Option<&'a ComponentSpec> {
+    catalog.iter().find(|c| c.name == name)
+}
+
 // ============================================================================
 // 组件通用属性
 // ============================================================================
@@ -82,6 +328,51 @@ impl Component {
             Component::DateTimeInput(c) => &c.common.id,
         }
     }
+
+    /// 获取组件名称（与 `component` 字段一致）
+    pub fn name(&self) -> &'static str {
+        match self {
+            Component::Text(_) => "Text",
+            Component::Image(_) => "Image",
+            Component::Icon(_) => "Icon",
+            Component::Video(_) => "Video",
+            Component::AudioPlayer(_) => "AudioPlayer",
+            Component::Row(_) => "Row",
+            Component::Column(_) => "Column",
+            Component::List(_) => "List",
+            Component::Card(_) => "Card",
+            Component::Tabs(_) => "Tabs",
+            Component::Modal(_) => "Modal",
+            Component::Divider(_) => "Divider",
+            Component::Button(_) => "Button",
+            Component::TextField(_) => "TextField",
+            Component::CheckBox(_) => "CheckBox",
+            Component::ChoicePicker(_) => "ChoicePicker",
+            Component::Slider(_) => "Slider",
+            Component::DateTimeInput(_) => "DateTimeInput",
+        }
+    }
+
+    /// 获取该组件引用的其他组件 ID
+    pub fn references(&self) -> Vec<&str> {
+        match self {
+            Component::Row(c) => child_ids(&c.children),
+            Component::Column(c) => child_ids(&c.children),
+            Component::List(c) => child_ids(&c.children),
+            Component::Card(c) => vec![c.child.as_str()],
+            Component::Button(c) => vec![c.child.as_str()],
+            Component::Tabs(c) => c.tabs.iter().map(|t| t.child.as_str()).collect(),
+            Component::Modal(c) => vec![c.trigger.as_str(), c.content.as_str()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn child_ids(list: &ChildList) -> Vec<&str> {
+    match list {
+        ChildList::Static(ids) => ids.iter().map(String::as_str).collect(),
+        ChildList::Template(template) => vec![template.component_id.as_str()],
+    }
 }
 
 // ============================================================================
@@ -207,3 +207,123 @@ pub struct FunctionAction {
     /// 函数调用
     pub function_call: FunctionCall,
 }
+
+// ============================================================================
+// 错误类型
+// ============================================================================
+
+/// A2UI 统一错误类型
+///
+/// 序列化时以 `code` 字段区分错误类型（如 `SCHEMA_MISMATCH`），其余字段指明出错的组件和属性，
+/// 便于客户端按代码处理。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum A2uiError {
+    /// 组件目录不受支持
+    #[error("不支持的组件目录: {catalog_id}")]
+    #[serde(rename_all = "camelCase")]
+    UnknownCatalog { catalog_id: String },
+
+    /// 组件类型不在目录中
+    #[error("未知组件类型 {component}（组件 {component_id}）")]
+    #[serde(rename_all = "camelCase")]
+    UnknownComponent {
+        component_id: ComponentId,
+        component: String,
+    },
+
+    /// 组件使用了无效的属性（例如已移除的属性）
+    #[error("组件 {component_id} 的属性 {prop} 无效: {reason}")]
+    #[serde(rename_all = "camelCase")]
+    InvalidProp {
+        component_id: ComponentId,
+        component: String,
+        prop: String,
+        reason: String,
+    },
+
+    /// 组件内容与目录定义的结构不符
+    #[error("组件 {component_id}（{component}）结构不符: {reason}")]
+    #[serde(rename_all = "camelCase")]
+    SchemaMismatch {
+        component_id: ComponentId,
+        component: String,
+        prop: Option<String>,
+        reason: String,
+    },
+
+    /// Surface 不存在
+    #[error("Surface 不存在: {surface_id}")]
+    #[serde(rename_all = "camelCase")]
+    UnknownSurface { surface_id: String },
+
+    /// 组件引用了不存在的组件
+    #[error("Surface {surface_id} 中组件 {component_id} 引用了不存在的组件 {reference}")]
+    #[serde(rename_all = "camelCase")]
+    DanglingReference {
+        surface_id: String,
+        component_id: ComponentId,
+        reference: ComponentId,
+    },
+
+    /// 组件 ID 重复
+    #[error("组件 ID 重复: {component_id}")]
+    #[serde(rename_all = "camelCase")]
+    DuplicateComponentId { component_id: ComponentId },
+
+    /// 缺少必填字段
+    #[error("缺少必填字段: {field}")]
+    MissingField { field: String },
+
+    /// JSON Pointer 路径无效
+    #[error("无效的路径: {path}")]
+    InvalidPath { path: String },
+
+    /// 协议版本不匹配
+    #[error("协议版本不匹配: {version}")]
+    VersionMismatch { version: String },
+
+    /// 序列化失败
+    #[error("序列化失败: {message}")]
+    Serialization { message: String },
+}
+
+impl A2uiError {
+    /// 机器可读的错误代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownCatalog { .. } => "UNKNOWN_CATALOG",
+            Self::UnknownComponent { .. } => "UNKNOWN_COMPONENT",
+            Self::InvalidProp { .. } => "INVALID_PROP",
+            Self::SchemaMismatch { .. } => "SCHEMA_MISMATCH",
+            Self::UnknownSurface { .. } => "UNKNOWN_SURFACE",
+            Self::DanglingReference { .. } => "DANGLING_REFERENCE",
+            Self::DuplicateComponentId { .. } => "DUPLICATE_COMPONENT_ID",
+            Self::MissingField { .. } => "MISSING_FIELD",
+            Self::InvalidPath { .. } => "INVALID_PATH",
+            Self::VersionMismatch { .. } => "VERSION_MISMATCH",
+            Self::Serialization { .. } => "SERIALIZATION",
+        }
+    }
+
+    /// 出错的组件 ID
+    pub fn component_id(&self) -> Option<&str> {
+        match self {
+            Self::UnknownComponent { component_id, .. }
+            | Self::InvalidProp { component_id, .. }
+            | Self::SchemaMismatch { component_id, .. }
+            | Self::DanglingReference { component_id, .. }
+            | Self::DuplicateComponentId { component_id } => Some(component_id),
+            _ => None,
+        }
+    }
+
+    /// 出错的属性
+    pub fn prop(&self) -> Option<&str> {
+        match self {
+            Self::InvalidProp { prop, .. } => Some(prop),
+            Self::SchemaMismatch { prop, .. } => prop.as_deref(),
+            _ => None,
+        }
+    }
+}
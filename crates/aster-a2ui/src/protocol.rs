@@ -5,6 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::catalog::Component;
+use crate::common::A2uiError;
 
 /// A2UI 协议版本
 pub const PROTOCOL_VERSION: &str = "v0.10";
@@ -322,6 +323,23 @@ impl ClientCapabilities {
         }
     }
 
+    /// 检查客户端是否支持指定的组件目录
+    pub fn check_catalog(&self, catalog_id: &str) -> Result<(), A2uiError> {
+        let supported = self.supported_catalog_ids.iter().any(|id| id == catalog_id)
+            || self
+                .inline_catalogs
+                .iter()
+                .flatten()
+                .any(|c| c.catalog_id == catalog_id);
+        if supported {
+            Ok(())
+        } else {
+            Err(A2uiError::UnknownCatalog {
+                catalog_id: catalog_id.to_string(),
+            })
+        }
+    }
+
     /// 添加内联目录
     pub fn with_inline_catalog(mut self, catalog: Catalog) -> Self {
         self.inline_catalogs
@@ -352,3 +370,245 @@ impl Default for ClientDataModel {
         Self::new()
     }
 }
+
+// ============================================================================
+// 消息校验
+// ============================================================================
+
+impl ServerMessage {
+    /// 校验消息是否可以发送给客户端
+    pub fn validate(&self) -> Result<(), A2uiError> {
+        if self.version != PROTOCOL_VERSION {
+            return Err(A2uiError::VersionMismatch {
+                version: self.version.clone(),
+            });
+        }
+
+        let surface_id = match &self.content {
+            ServerMessageContent::CreateSurface(m) => {
+                if m.catalog_id.is_empty() {
+                    return Err(missing_field("catalogId"));
+                }
+                &m.surface_id
+            }
+            ServerMessageContent::UpdateComponents(m) => {
+                let mut seen = std::collections::HashSet::new();
+                for component in &m.components {
+                    if component.id().is_empty() {
+                        return Err(missing_field("id"));
+                    }
+                    if !seen.insert(component.id()) {
+                        return Err(A2uiError::DuplicateComponentId {
+                            component_id: component.id().to_string(),
+                        });
+                    }
+                }
+                &m.surface_id
+            }
+            ServerMessageContent::UpdateDataModel(m) => {
+                if let Some(path) = &m.path {
+                    if !path.is_empty() && !path.starts_with('/') {
+                        return Err(A2uiError::InvalidPath { path: path.clone() });
+                    }
+                }
+                &m.surface_id
+            }
+            ServerMessageContent::DeleteSurface(m) => &m.surface_id,
+        };
+        if surface_id.is_empty() {
+            return Err(missing_field("surfaceId"));
+        }
+        Ok(())
+    }
+}
+
+fn missing_field(field: &str) -> A2uiError {
+    A2uiError::MissingField {
+        field: field.to_string(),
+    }
+}
+
+// ============================================================================
+// 批量发送
+// ============================================================================
+
+/// 默认每批最多消息数
+pub const DEFAULT_BATCH_MAX_MESSAGES: usize = 32;
+
+/// 默认每批最大字节数
+pub const DEFAULT_BATCH_MAX_BYTES: usize = 64 * 1024;
+
+/// 批量消息帧，客户端按顺序逐条处理其中的消息
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFrame {
+    /// 协议版本
+    pub version: String,
+    /// 按发送顺序排列的消息
+    pub messages: Vec<ServerMessage>,
+}
+
+impl BatchFrame {
+    /// 序列化为 JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// 从 JSON 解析
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// 拆出其中的消息
+    pub fn unpack(self) -> Vec<ServerMessage> {
+        self.messages
+    }
+}
+
+/// 将多条服务端消息合并为一个帧发送
+///
+/// 每条消息加入前单独校验，无效消息被拒绝而不影响已加入的消息。
+/// 达到消息数或字节数上限时自动产出帧。
+#[derive(Debug, Clone)]
+pub struct MessageBatch {
+    messages: Vec<ServerMessage>,
+    bytes: usize,
+    max_messages: usize,
+    max_bytes: usize,
+}
+
+impl MessageBatch {
+    /// 创建批次，指定消息数和字节数上限
+    pub fn new(max_messages: usize, max_bytes: usize) -> Self {
+        Self {
+            messages: Vec::new(),
+            bytes: 0,
+            max_messages: max_messages.max(1),
+            max_bytes,
+        }
+    }
+
+    /// 添加消息，返回因达到上限而产出的帧（按顺序发送）
+    pub fn push(&mut self, message: ServerMessage) -> Result<Vec<BatchFrame>, A2uiError> {
+        message.validate()?;
+        let size = serde_json::to_vec(&message)
+            .map_err(|e| A2uiError::Serialization {
+                message: e.to_string(),
+            })?
+            .len();
+
+        let mut frames = Vec::new();
+        // 放不下时先发送已有消息，保证帧不超过字节上限
+        if !self.messages.is_empty() && self.bytes + size > self.max_bytes {
+            frames.extend(self.flush());
+        }
+
+        self.messages.push(message);
+        self.bytes += size;
+        if self.messages.len() >= self.max_messages || self.bytes >= self.max_bytes {
+            frames.extend(self.flush());
+        }
+        Ok(frames)
+    }
+
+    /// 产出当前累积的消息，批次为空时返回 None
+    pub fn flush(&mut self) -> Option<BatchFrame> {
+        if self.messages.is_empty() {
+            return None;
+        }
+        self.bytes = 0;
+        Some(BatchFrame {
+            version: PROTOCOL_VERSION.to_string(),
+            messages: std::mem::take(&mut self.messages),
+        })
+    }
+
+    /// 当前累积的消息数
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// 是否没有累积消息
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+impl Default for MessageBatch {
+    fn default() -> Self {
+        Self::new(DEFAULT_BATCH_MAX_MESSAGES, DEFAULT_BATCH_MAX_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::{ComponentCommon, TextComponent};
+    use serde_json::json;
+
+    fn text(id: &str) -> Component {
+        Component::Text(TextComponent {
+            common: ComponentCommon {
+                id: id.to_string(),
+                ..Default::default()
+            },
+            text: "你好".into(),
+            variant: None,
+        })
+    }
+
+    #[test]
+    fn test_batch_round_trip() {
+        let originals = vec![
+            ServerMessage::create_surface("form", crate::catalog::STANDARD_CATALOG_ID),
+            ServerMessage::update_components("form", vec![text("root")]),
+            ServerMessage::update_data_model("form", json!({ "name": "张三" })),
+        ];
+
+        let mut batch = MessageBatch::default();
+        for message in &originals {
+            assert!(batch.push(message.clone()).unwrap().is_empty());
+        }
+
+        // 无效消息被拒绝，不影响已有消息
+        let invalid = ServerMessage::update_components("form", vec![text("a"), text("a")]);
+        assert_eq!(
+            batch.push(invalid),
+            Err(A2uiError::DuplicateComponentId {
+                component_id: "a".to_string()
+            })
+        );
+        assert_eq!(batch.len(), 3);
+
+        let json = batch.flush().unwrap().to_json().unwrap();
+        assert!(batch.is_empty());
+        assert_eq!(BatchFrame::from_json(&json).unwrap().unpack(), originals);
+    }
+
+    #[test]
+    fn test_batch_auto_flush() {
+        let mut batch = MessageBatch::new(2, DEFAULT_BATCH_MAX_BYTES);
+        assert!(batch
+            .push(ServerMessage::delete_surface("a"))
+            .unwrap()
+            .is_empty());
+        let frames = batch.push(ServerMessage::delete_surface("b")).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].messages.len(), 2);
+        assert!(batch.is_empty());
+
+        // 字节上限
+        let mut batch = MessageBatch::new(10, 60);
+        assert!(batch
+            .push(ServerMessage::delete_surface("a"))
+            .unwrap()
+            .is_empty());
+        let frames = batch.push(ServerMessage::delete_surface("b")).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            frames[0].clone().unpack(),
+            vec![ServerMessage::delete_surface("a")]
+        );
+        assert_eq!(batch.len(), 1);
+    }
+}
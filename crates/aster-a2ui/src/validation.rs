@@ -2,8 +2,17 @@
 //!
 //! 提供 JSON Pointer 路径解析和数据模型验证功能
 
+use std::collections::{HashMap, HashSet};
+
 use serde_json::Value;
 
+use crate::catalog::{
+    find_component_spec, Component, ComponentSpec, PropStatus, ROOT_COMPONENT_ID,
+    STANDARD_CATALOG_COMPONENTS, STANDARD_CATALOG_ID,
+};
+use crate::common::{A2uiError, ComponentId};
+use crate::protocol::{ServerMessage, ServerMessageContent};
+
 /// JSON Pointer 路径解析错误
 #[derive(Debug, Clone, PartialEq)]
 pub enum JsonPointerError {
@@ -27,6 +36,17 @@ impl std::fmt::Display for JsonPointerError {
 
 impl std::error::Error for JsonPointerError {}
 
+impl From<JsonPointerError> for A2uiError {
+    fn from(err: JsonPointerError) -> Self {
+        match err {
+            JsonPointerError::PathNotFound(path) | JsonPointerError::InvalidArrayIndex(path) => {
+                A2uiError::InvalidPath { path }
+            }
+            JsonPointerError::InvalidFormat(msg) => A2uiError::InvalidPath { path: msg },
+        }
+    }
+}
+
 /// 解析 JSON Pointer 路径并获取值
 ///
 /// 支持绝对路径（以 `/` 开头）和相对路径
@@ -159,6 +179,369 @@ pub fn set_at_pointer(
     Ok(())
 }
 
+// ============================================================================
+// 组件目录版本检查
+// ============================================================================
+
+/// 目录检查问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogIssueSeverity {
+    /// 使用了已弃用的属性，仍可渲染
+    Warning,
+    /// 使用了已移除的属性，客户端无法渲染
+    Error,
+}
+
+/// 组件属性与目录版本不兼容的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogIssue {
+    /// 严重程度
+    pub severity: CatalogIssueSeverity,
+    /// 组件 ID
+    pub component_id: String,
+    /// 组件名称
+    pub component: String,
+    /// 属性名
+    pub prop: String,
+    /// 问题描述
+    pub message: String,
+}
+
+impl CatalogIssue {
+    /// 转换为统一错误类型
+    pub fn to_error(&self) -> A2uiError {
+        A2uiError::InvalidProp {
+            component_id: self.component_id.clone(),
+            component: self.component.clone(),
+            prop: self.prop.clone(),
+            reason: self.message.clone(),
+        }
+    }
+}
+
+/// 检查组件是否使用了当前目录版本中已弃用或已移除的属性
+///
+/// 组件以 JSON 形式传入，因为按旧目录构建的 Surface 可能包含当前类型中已不存在的属性。
+/// 目录中没有的组件和属性不做检查。
+pub fn validate_catalog_versions(
+    components: &[Value],
+    catalog: &[ComponentSpec],
+) -> Vec<CatalogIssue> {
+    let mut issues = Vec::new();
+
+    for component in components {
+        let Some(props) = component.as_object() else {
+            continue;
+        };
+        let Some(spec) = props
+            .get("component")
+            .and_then(Value::as_str)
+            .and_then(|name| find_component_spec(catalog, name))
+        else {
+            continue;
+        };
+        let component_id = props.get("id").and_then(Value::as_str).unwrap_or_default();
+
+        for prop in props.keys() {
+            let Some(prop_spec) = spec.prop(prop) else {
+                continue;
+            };
+            let (severity, message) = match prop_spec.status {
+                PropStatus::Active => continue,
+                PropStatus::Deprecated { since, replacement } => (
+                    CatalogIssueSeverity::Warning,
+                    match replacement {
+                        Some(replacement) => format!(
+                            "{} 的属性 {} 自 v{} 起已弃用，请改用 {}",
+                            spec.name, prop, since, replacement
+                        ),
+                        None => format!("{} 的属性 {} 自 v{} 起已弃用", spec.name, prop, since),
+                    },
+                ),
+                PropStatus::Removed { since } => (
+                    CatalogIssueSeverity::Error,
+                    format!(
+                        "{} 的属性 {} 已在 v{} 中移除（当前版本 v{}）",
+                        spec.name, prop, since, spec.version
+                    ),
+                ),
+            };
+            issues.push(CatalogIssue {
+                severity,
+                component_id: component_id.to_string(),
+                component: spec.name.to_string(),
+                prop: prop.clone(),
+                message,
+            });
+        }
+    }
+
+    issues
+}
+
+/// 将 JSON 组件解析为目录中的组件类型
+///
+/// 解析失败时返回 `SchemaMismatch`，并尽量指出出错的属性。
+pub fn parse_component(value: &Value, catalog: &[ComponentSpec]) -> Result<Component, A2uiError> {
+    let component_id = value
+        .get("id")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let Some(component) = value.get("component").and_then(Value::as_str) else {
+        return Err(A2uiError::MissingField {
+            field: "component".to_string(),
+        });
+    };
+    if find_component_spec(catalog, component).is_none() {
+        return Err(A2uiError::UnknownComponent {
+            component_id,
+            component: component.to_string(),
+        });
+    }
+
+    serde_json::from_value::<Component>(value.clone()).map_err(|err| A2uiError::SchemaMismatch {
+        component_id,
+        component: component.to_string(),
+        prop: offending_prop(value, &err.to_string()),
+        reason: err.to_string(),
+    })
+}
+
+/// 找出导致解析失败的属性
+///
+/// 缺失字段直接从错误信息中取得；类型错误时，错误信息中会带有出错的值，
+/// 只有一个顶层属性的值与之对应时才认定为该属性。
+fn offending_prop(value: &Value, error: &str) -> Option<String> {
+    if let Some(field) = missing_field(error) {
+        return Some(field);
+    }
+    let candidates: Vec<&String> = value
+        .as_object()?
+        .iter()
+        .filter(|(key, _)| *key != "component")
+        .filter(|(_, v)| error.contains(&unexpected_value(v)))
+        .map(|(key, _)| key)
+        .collect();
+    match candidates.as_slice() {
+        [prop] => Some(prop.to_string()),
+        _ => None,
+    }
+}
+
+/// 值在 serde 类型错误信息中的写法
+fn unexpected_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("boolean `{}`", b),
+        Value::Number(n) if n.is_f64() => format!("floating point `{}`", n),
+        Value::Number(n) => format!("integer `{}`", n),
+        Value::String(s) => format!("string {:?}", s),
+        Value::Array(_) => "sequence".to_string(),
+        Value::Object(_) => "map".to_string(),
+    }
+}
+
+fn missing_field(error: &str) -> Option<String> {
+    let rest = error.strip_prefix("missing field `")?;
+    rest.split('`').next().map(str::to_string)
+}
+
+// ============================================================================
+// Surface 引用完整性
+// ============================================================================
+
+/// 跟踪各 Surface 的组件图，拒绝破坏引用完整性的增量更新
+///
+/// 在根组件（[`ROOT_COMPONENT_ID`]）出现之前，Surface 视为仍在构建中，允许引用尚未发送的组件；
+/// 之后的每次更新都不能引用不存在的组件。从根组件不可达的组件会被回收，而不是拒绝更新。
+/// 被拒绝的更新不会改变已跟踪的状态。
+#[derive(Debug, Clone, Default)]
+pub struct SurfaceValidator {
+    surfaces: HashMap<String, HashMap<ComponentId, Component>>,
+}
+
+impl SurfaceValidator {
+    /// 创建空的验证器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 校验消息并应用到跟踪的状态
+    pub fn apply(&mut self, message: &ServerMessage) -> Result<(), A2uiError> {
+        message.validate()?;
+
+        match &message.content {
+            ServerMessageContent::CreateSurface(m) => {
+                self.surfaces.insert(m.surface_id.clone(), HashMap::new());
+            }
+            ServerMessageContent::UpdateComponents(m) => {
+                let current =
+                    self.surfaces
+                        .get(&m.surface_id)
+                        .ok_or_else(|| A2uiError::UnknownSurface {
+                            surface_id: m.surface_id.clone(),
+                        })?;
+                let mut next = current.clone();
+                for component in &m.components {
+                    next.insert(component.id().to_string(), component.clone());
+                }
+                check_integrity(&m.surface_id, &mut next)?;
+                self.surfaces.insert(m.surface_id.clone(), next);
+            }
+            ServerMessageContent::UpdateDataModel(m) => {
+                if !self.surfaces.contains_key(&m.surface_id) {
+                    return Err(A2uiError::UnknownSurface {
+                        surface_id: m.surface_id.clone(),
+                    });
+                }
+            }
+            ServerMessageContent::DeleteSurface(m) => {
+                self.surfaces.remove(&m.surface_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取 Surface 当前的组件
+    pub fn components(&self, surface_id: &str) -> Option<&HashMap<ComponentId, Component>> {
+        self.surfaces.get(surface_id)
+    }
+}
+
+/// 拒绝悬空引用，并回收从根组件不可达的组件
+fn check_integrity(
+    surface_id: &str,
+    components: &mut HashMap<ComponentId, Component>,
+) -> Result<(), A2uiError> {
+    if !components.contains_key(ROOT_COMPONENT_ID) {
+        return Ok(());
+    }
+
+    // 按 ID 排序，使报告的错误稳定
+    let mut ids: Vec<&ComponentId> = components.keys().collect();
+    ids.sort();
+
+    for id in &ids {
+        for reference in components[*id].references() {
+            if !components.contains_key(reference) {
+                return Err(A2uiError::DanglingReference {
+                    surface_id: surface_id.to_string(),
+                    component_id: id.to_string(),
+                    reference: reference.to_string(),
+                });
+            }
+        }
+    }
+
+    let mut reachable: HashSet<ComponentId> = HashSet::new();
+    let mut stack = vec![ROOT_COMPONENT_ID.to_string()];
+    while let Some(id) = stack.pop() {
+        if !reachable.contains(&id) {
+            stack.extend(components[&id].references().into_iter().map(str::to_string));
+            reachable.insert(id);
+        }
+    }
+    components.retain(|id, _| reachable.contains(id));
+    Ok(())
+}
+
+// ============================================================================
+// 流式验证
+// ============================================================================
+
+/// 流式验证失败的帧
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("第 {index} 帧无效: {error}")]
+pub struct FrameError {
+    /// 帧序号（从 0 开始）
+    pub index: usize,
+    /// 失败原因
+    pub error: A2uiError,
+}
+
+/// 逐帧验证服务端消息，遇到第一个无效帧即失败
+///
+/// 在帧产生时立即验证，结合组件目录和 Surface 组件图检查更新。
+/// 失败后验证器停止，后续调用都返回同一个错误，调用方可以据此提前结束构建。
+#[derive(Debug, Clone)]
+pub struct StreamingValidator {
+    catalog_id: String,
+    catalog: &'static [ComponentSpec],
+    surfaces: SurfaceValidator,
+    next_index: usize,
+    failure: Option<FrameError>,
+}
+
+impl StreamingValidator {
+    /// 创建验证器，Surface 只能使用指定的组件目录
+    pub fn new(catalog_id: impl Into<String>, catalog: &'static [ComponentSpec]) -> Self {
+        Self {
+            catalog_id: catalog_id.into(),
+            catalog,
+            surfaces: SurfaceValidator::new(),
+            next_index: 0,
+            failure: None,
+        }
+    }
+
+    /// 使用标准组件目录
+    pub fn standard() -> Self {
+        Self::new(STANDARD_CATALOG_ID, STANDARD_CATALOG_COMPONENTS)
+    }
+
+    /// 验证下一帧
+    pub fn push(&mut self, message: &ServerMessage) -> Result<(), FrameError> {
+        if let Some(failure) = &self.failure {
+            return Err(failure.clone());
+        }
+
+        let index = self.next_index;
+        self.next_index += 1;
+        self.check(message).map_err(|error| {
+            let failure = FrameError { index, error };
+            self.failure = Some(failure.clone());
+            failure
+        })
+    }
+
+    /// 已验证通过的帧数
+    pub fn frames_validated(&self) -> usize {
+        if self.failure.is_some() {
+            self.next_index - 1
+        } else {
+            self.next_index
+        }
+    }
+
+    /// 第一个无效帧，如果有
+    pub fn failure(&self) -> Option<&FrameError> {
+        self.failure.as_ref()
+    }
+
+    fn check(&mut self, message: &ServerMessage) -> Result<(), A2uiError> {
+        match &message.content {
+            ServerMessageContent::CreateSurface(m) if m.catalog_id != self.catalog_id => {
+                return Err(A2uiError::UnknownCatalog {
+                    catalog_id: m.catalog_id.clone(),
+                });
+            }
+            ServerMessageContent::UpdateComponents(m) => {
+                for component in &m.components {
+                    if find_component_spec(self.catalog, component.name()).is_none() {
+                        return Err(A2uiError::UnknownComponent {
+                            component_id: component.id().to_string(),
+                            component: component.name().to_string(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.surfaces.apply(message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,4 +573,152 @@ mod tests {
         set_at_pointer(&mut data, "/items/0", json!("first")).unwrap();
         assert_eq!(data["items"][0], "first");
     }
+
+    #[test]
+    fn test_parse_component_schema_mismatch() {
+        let value =
+            json!({ "id": "slider", "component": "Slider", "min": "zero", "max": 10, "value": 5 });
+        let err = parse_component(&value, STANDARD_CATALOG_COMPONENTS).unwrap_err();
+
+        assert_eq!(err.code(), "SCHEMA_MISMATCH");
+        assert_eq!(err.component_id(), Some("slider"));
+        assert_eq!(err.prop(), Some("min"));
+        let payload = serde_json::to_value(&err).unwrap();
+        assert_eq!(payload["code"], "SCHEMA_MISMATCH");
+        assert_eq!(payload["componentId"], "slider");
+        assert_eq!(payload["component"], "Slider");
+        assert_eq!(payload["prop"], "min");
+
+        let missing = json!({ "id": "title", "component": "Text" });
+        let err = parse_component(&missing, STANDARD_CATALOG_COMPONENTS).unwrap_err();
+        assert_eq!(err.prop(), Some("text"));
+
+        let unknown = json!({ "id": "map", "component": "Map" });
+        let err = parse_component(&unknown, STANDARD_CATALOG_COMPONENTS).unwrap_err();
+        assert_eq!(err.code(), "UNKNOWN_COMPONENT");
+
+        let ok = json!({ "id": "title", "component": "Text", "text": "标题" });
+        assert!(parse_component(&ok, STANDARD_CATALOG_COMPONENTS).is_ok());
+    }
+
+    #[test]
+    fn test_surface_update_rejects_broken_references() {
+        use crate::catalog::{ChildList, ColumnComponent, ComponentCommon, TextComponent};
+
+        let common = |id: &str| ComponentCommon {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        let column = |children: &[&str]| {
+            Component::Column(ColumnComponent {
+                common: common(ROOT_COMPONENT_ID),
+                children: ChildList::Static(children.iter().map(|c| c.to_string()).collect()),
+                justify: None,
+                align: None,
+            })
+        };
+        let text = |id: &str| {
+            Component::Text(TextComponent {
+                common: common(id),
+                text: "内容".into(),
+                variant: None,
+            })
+        };
+
+        let mut validator = SurfaceValidator::new();
+        validator
+            .apply(&ServerMessage::create_surface("form", "catalog"))
+            .unwrap();
+        validator
+            .apply(&ServerMessage::update_components(
+                "form",
+                vec![column(&["title", "body"]), text("title"), text("body")],
+            ))
+            .unwrap();
+
+        // 根组件不再引用 body，body 被回收
+        validator
+            .apply(&ServerMessage::update_components(
+                "form",
+                vec![column(&["title"])],
+            ))
+            .unwrap();
+        assert!(!validator.components("form").unwrap().contains_key("body"));
+
+        // 引用不存在的组件
+        let err = validator
+            .apply(&ServerMessage::update_components(
+                "form",
+                vec![column(&["title", "footer"])],
+            ))
+            .unwrap_err();
+        assert_eq!(err.code(), "DANGLING_REFERENCE");
+        assert!(err.to_string().contains("footer"));
+
+        // 被拒绝的更新不改变状态
+        let root = &validator.components("form").unwrap()[ROOT_COMPONENT_ID];
+        assert_eq!(root.references(), vec!["title"]);
+    }
+
+    #[test]
+    fn test_streaming_validator_fails_at_first_invalid_frame() {
+        let mut validator = StreamingValidator::standard();
+
+        validator
+            .push(&ServerMessage::create_surface("form", STANDARD_CATALOG_ID))
+            .unwrap();
+        // 更新不存在的 Surface
+        let err = validator
+            .push(&ServerMessage::update_data_model("other", json!({})))
+            .unwrap_err();
+
+        assert_eq!(err.index, 1);
+        assert_eq!(err.error.code(), "UNKNOWN_SURFACE");
+        assert_eq!(validator.frames_validated(), 1);
+
+        // 失败后不再继续验证
+        let again = validator
+            .push(&ServerMessage::delete_surface("form"))
+            .unwrap_err();
+        assert_eq!(again, err);
+    }
+
+    #[test]
+    fn test_validate_catalog_versions() {
+        use crate::catalog::PropSpec;
+
+        const CATALOG: &[ComponentSpec] = &[ComponentSpec {
+            name: "Text",
+            version: 3,
+            props: &[
+                PropSpec::active("text"),
+                PropSpec::active("variant"),
+                PropSpec::deprecated("style", 2, Some("variant")),
+                PropSpec::removed("markdown", 3),
+            ],
+        }];
+
+        let components = vec![
+            json!({ "id": "title", "component": "Text", "text": "标题", "style": "h1" }),
+            json!({ "id": "body", "component": "Text", "text": "正文", "markdown": true }),
+            json!({ "id": "ok", "component": "Text", "text": "正常", "variant": "body" }),
+        ];
+        let issues = validate_catalog_versions(&components, CATALOG);
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].severity, CatalogIssueSeverity::Warning);
+        assert_eq!(issues[0].component_id, "title");
+        assert_eq!(issues[0].prop, "style");
+        assert!(issues[0].message.contains("variant"));
+        assert_eq!(issues[1].severity, CatalogIssueSeverity::Error);
+        assert_eq!(issues[1].component_id, "body");
+        assert_eq!(issues[1].prop, "markdown");
+        assert_eq!(issues[1].to_error().code(), "INVALID_PROP");
+
+        // 标准目录中的组件都声明了版本
+        assert!(STANDARD_CATALOG_COMPONENTS.iter().all(|c| c.version >= 1));
+        assert!(
+            validate_catalog_versions(&components[2..], STANDARD_CATALOG_COMPONENTS).is_empty()
+        );
+    }
 }
@@ -286,6 +286,7 @@ impl AsterAcpAgent {
             toolshim: false,
             toolshim_model: None,
             fast_model: None,
+            json_mode: false,
         };
         let provider = create(&provider_name, model_config).await?;
 
@@ -84,6 +84,7 @@ pub async fn handle_schedule_add(
         last_run: None,
         currently_running: false,
         paused: false,
+        paused_by_pause_all: false,
         current_session_id: None,
         process_start_time: None,
     };
@@ -8,7 +8,7 @@ use tracing_subscriber::{
     Registry,
 };
 
-use aster::tracing::{langfuse_layer, otlp_layer};
+use aster::tracing::{langfuse_layer, otlp_layer, ChromeTraceLayer};
 use aster_bench::bench_session::BenchAgentError;
 use aster_bench::error_capture::ErrorCaptureLayer;
 
@@ -115,6 +115,13 @@ fn setup_logging_internal(
                 layers.push(langfuse.with_filter(LevelFilter::DEBUG).boxed());
             }
 
+            // Span timings for `/trace`, when enabled with ASTER_TRACE_SPANS
+            if !force {
+                if let Some(chrome_trace) = ChromeTraceLayer::global_if_enabled() {
+                    layers.push(chrome_trace.clone().with_filter(LevelFilter::INFO).boxed());
+                }
+            }
+
             // Build the subscriber
             let subscriber = Registry::default().with(layers);
 
@@ -134,6 +134,7 @@ impl AsterCompleter {
             "/prompt",
             "/mode",
             "/recipe",
+            "/trace",
         ];
 
         // Find commands that match the prefix
@@ -21,6 +21,7 @@ pub enum InputResult {
     Clear,
     Recipe(Option<String>),
     Compact,
+    Trace(Option<String>),
 }
 
 #[derive(Debug)]
@@ -122,6 +123,7 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
     const CMD_COMPACT: &str = "/compact";
+    const CMD_TRACE: &str = "/trace";
     const CMD_SUMMARIZE_DEPRECATED: &str = "/summarize";
 
     match input {
@@ -185,6 +187,12 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_COMPACT => Some(InputResult::Compact),
+        s if s == CMD_TRACE || s.starts_with("/trace ") => {
+            let filepath = s[CMD_TRACE.len()..].trim();
+            Some(InputResult::Trace(
+                (!filepath.is_empty()).then(|| filepath.to_string()),
+            ))
+        }
         s if s == CMD_SUMMARIZE_DEPRECATED => {
             println!("{}", console::style("⚠️  Note: /summarize has been renamed to /compact and will be removed in a future release.").yellow());
             Some(InputResult::Compact)
@@ -314,6 +322,8 @@ fn print_help() {
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
 /compact - Compact the current conversation to reduce context length while preserving key information.
+/trace [filepath] - Save the timeline of this session (tool calls, provider requests, subagents) as Chrome trace JSON.
+                    Open it in chrome://tracing or Perfetto. If no filepath is provided, it will be saved to ./trace.json.
 /? or /help - Display this help message
 /clear - Clears the current chat history
 
@@ -370,6 +380,18 @@ mod tests {
             panic!("Expected AddBuiltin");
         }
 
+        // Test trace command
+        assert!(matches!(
+            handle_slash_command("/trace"),
+            Some(InputResult::Trace(None))
+        ));
+        if let Some(InputResult::Trace(Some(path))) = handle_slash_command("/trace out.json") {
+            assert_eq!(path, "out.json");
+        } else {
+            panic!("Expected Trace");
+        }
+        assert!(handle_slash_command("/tracex").is_none());
+
         // Test unknown commands
         assert!(handle_slash_command("/unknown").is_none());
     }
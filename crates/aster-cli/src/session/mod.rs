@@ -33,6 +33,8 @@ use aster::agents::types::RetryConfig;
 use aster::agents::{Agent, SessionConfig, COMPACT_TRIGGERS};
 use aster::config::{AsterMode, Config};
 use aster::session::SessionManager;
+use aster::tracing::chrome_trace::TRACE_SPANS_ENV;
+use aster::tracing::ChromeTraceLayer;
 use completion::AsterCompleter;
 use input::InputResult;
 use rmcp::model::PromptMessage;
@@ -546,6 +548,10 @@ impl CliSession {
                 history.save(editor);
                 self.handle_compact().await?;
             }
+            InputResult::Trace(filepath_opt) => {
+                history.save(editor);
+                self.handle_trace(filepath_opt);
+            }
         }
         Ok(())
     }
@@ -723,6 +729,36 @@ impl CliSession {
         }
     }
 
+    fn handle_trace(&self, filepath_opt: Option<String>) {
+        if !ChromeTraceLayer::is_enabled() {
+            println!(
+                "{}",
+                console::style(format!(
+                    "Span recording is off; restart with {}=1 to use /trace",
+                    TRACE_SPANS_ENV
+                ))
+                .yellow()
+            );
+            return;
+        }
+        let filepath = filepath_opt.as_deref().unwrap_or("trace.json");
+        let result = std::fs::File::create(filepath).and_then(|file| {
+            ChromeTraceLayer::global()
+                .export_session_chrome_trace(&self.session_id, std::io::BufWriter::new(file))
+        });
+        match result {
+            Ok(()) => println!(
+                "{}",
+                console::style(format!("Saved session trace to {}", filepath)).green()
+            ),
+            Err(e) => println!(
+                "{}: {}",
+                console::style("Failed to save session trace").red(),
+                e
+            ),
+        }
+    }
+
     async fn handle_compact(&mut self) -> Result<()> {
         let prompt = "Are you sure you want to compact this conversation? This will condense the message history.";
         let should_summarize = match cliclack::confirm(prompt).initial_value(true).interact() {
@@ -5,7 +5,7 @@ use tracing_subscriber::{
     Registry,
 };
 
-use aster::tracing::{langfuse_layer, otlp_layer};
+use aster::tracing::{langfuse_layer, otlp_layer, ChromeTraceLayer};
 
 /// Sets up the logging infrastructure for the application.
 /// This includes:
@@ -76,6 +76,10 @@ pub fn setup_logging(name: Option<&str>) -> Result<()> {
         layers.push(langfuse.with_filter(LevelFilter::DEBUG).boxed());
     }
 
+    if let Some(chrome_trace) = ChromeTraceLayer::global_if_enabled() {
+        layers.push(chrome_trace.clone().with_filter(LevelFilter::INFO).boxed());
+    }
+
     let subscriber = Registry::default().with(layers);
 
     subscriber.try_init()?;
@@ -101,6 +101,7 @@ async fn create_schedule(
         last_run: None,
         currently_running: false,
         paused: false,
+        paused_by_pause_all: false,
         current_session_id: None,
         process_start_time: None,
     };
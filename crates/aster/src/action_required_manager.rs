@@ -1,16 +1,62 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use tokio::time::timeout;
 use tracing::warn;
 use uuid::Uuid;
 
 use crate::conversation::message::{Message, MessageContent};
 
+/// How urgent an action is; higher severities are surfaced first.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionSeverity {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+/// Auto-resolution applied when nobody answers an action in time.
+#[derive(Debug, Clone)]
+pub struct ActionExpiry {
+    pub after: Duration,
+    pub default_response: Value,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ActionRequestOptions {
+    pub severity: ActionSeverity,
+    pub expiry: Option<ActionExpiry>,
+}
+
+/// Snapshot of a request still waiting for user input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAction {
+    pub id: String,
+    pub message: String,
+    pub severity: ActionSeverity,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionRequiredEvent {
+    Resolved { id: String },
+    Expired { id: String, default_response: Value },
+}
+
 struct PendingRequest {
+    info: PendingAction,
     response_tx: Option<tokio::sync::oneshot::Sender<Value>>,
 }
 
@@ -18,15 +64,18 @@ pub struct ActionRequiredManager {
     pending: Arc<RwLock<HashMap<String, Arc<Mutex<PendingRequest>>>>>,
     request_tx: mpsc::UnboundedSender<Message>,
     pub request_rx: Mutex<mpsc::UnboundedReceiver<Message>>,
+    event_tx: broadcast::Sender<ActionRequiredEvent>,
 }
 
 impl ActionRequiredManager {
     fn new() -> Self {
         let (request_tx, request_rx) = mpsc::unbounded_channel();
+        let (event_tx, _) = broadcast::channel(64);
         Self {
             pending: Arc::new(RwLock::new(HashMap::new())),
             request_tx,
             request_rx: Mutex::new(request_rx),
+            event_tx,
         }
     }
 
@@ -36,15 +85,56 @@ impl ActionRequiredManager {
         &INSTANCE
     }
 
+    pub fn subscribe(&self) -> broadcast::Receiver<ActionRequiredEvent> {
+        self.event_tx.subscribe()
+    }
+
     pub async fn request_and_wait(
         &self,
         message: String,
         schema: Value,
         timeout_duration: Duration,
+    ) -> Result<Value> {
+        self.request_and_wait_with_options(
+            message,
+            schema,
+            timeout_duration,
+            ActionRequestOptions::default(),
+        )
+        .await
+    }
+
+    /// Like [`Self::request_and_wait`], but with a severity used to order
+    /// [`Self::pending`] and an optional expiry that resolves the request to
+    /// a default response instead of leaving the caller blocked. An expiry
+    /// longer than `timeout_duration` is clamped to it.
+    pub async fn request_and_wait_with_options(
+        &self,
+        message: String,
+        schema: Value,
+        timeout_duration: Duration,
+        options: ActionRequestOptions,
     ) -> Result<Value> {
         let id = Uuid::new_v4().to_string();
         let (tx, rx) = tokio::sync::oneshot::channel();
+        let now = Utc::now();
+        let wait = match &options.expiry {
+            Some(expiry) => expiry.after.min(timeout_duration),
+            None => timeout_duration,
+        };
+        let expires_at = options
+            .expiry
+            .as_ref()
+            .and_then(|_| chrono::Duration::from_std(wait).ok())
+            .map(|after| now + after);
         let pending_request = PendingRequest {
+            info: PendingAction {
+                id: id.clone(),
+                message: message.clone(),
+                severity: options.severity,
+                created_at: now,
+                expires_at,
+            },
             response_tx: Some(tx),
         };
 
@@ -61,16 +151,25 @@ impl ActionRequiredManager {
             warn!("Failed to send action required message: {}", e);
         }
 
-        let result = match timeout(timeout_duration, rx).await {
+        let result = match timeout(wait, rx).await {
             Ok(Ok(user_data)) => Ok(user_data),
             Ok(Err(_)) => {
                 warn!("Response channel closed for request: {}", id);
                 Err(anyhow::anyhow!("Response channel closed"))
             }
-            Err(_) => {
-                warn!("Timeout waiting for response: {}", id);
-                Err(anyhow::anyhow!("Timeout waiting for user response"))
-            }
+            Err(_) => match options.expiry {
+                Some(expiry) => {
+                    let _ = self.event_tx.send(ActionRequiredEvent::Expired {
+                        id: id.clone(),
+                        default_response: expiry.default_response.clone(),
+                    });
+                    Ok(expiry.default_response)
+                }
+                None => {
+                    warn!("Timeout waiting for response: {}", id);
+                    Err(anyhow::anyhow!("Timeout waiting for user response"))
+                }
+            },
         };
 
         self.pending.write().await.remove(&id);
@@ -78,22 +177,170 @@ impl ActionRequiredManager {
         result
     }
 
-    pub async fn submit_response(&self, request_id: String, user_data: Value) -> Result<()> {
+    /// Pending requests, most severe first and oldest first within a severity.
+    pub async fn pending(&self) -> Vec<PendingAction> {
+        let requests: Vec<_> = self.pending.read().await.values().cloned().collect();
+        let mut pending = Vec::with_capacity(requests.len());
+        for request in requests {
+            pending.push(request.lock().await.info.clone());
+        }
+        pending.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.created_at.cmp(&b.created_at))
+        });
+        pending
+    }
+
+    pub async fn resolve(&self, request_id: &str, response: Value) -> Result<()> {
         let pending_arc = {
             let pending = self.pending.read().await;
             pending
-                .get(&request_id)
+                .get(request_id)
                 .cloned()
                 .ok_or_else(|| anyhow::anyhow!("Request not found: {}", request_id))?
         };
 
         let mut pending = pending_arc.lock().await;
         if let Some(tx) = pending.response_tx.take() {
-            if tx.send(user_data).is_err() {
+            if tx.send(response).is_err() {
                 warn!("Failed to send response through oneshot channel");
+            } else {
+                let _ = self.event_tx.send(ActionRequiredEvent::Resolved {
+                    id: request_id.to_string(),
+                });
             }
         }
 
         Ok(())
     }
+
+    pub async fn submit_response(&self, request_id: String, user_data: Value) -> Result<()> {
+        self.resolve(&request_id, user_data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(severity: ActionSeverity) -> ActionRequestOptions {
+        ActionRequestOptions {
+            severity,
+            expiry: None,
+        }
+    }
+
+    async fn wait_for_pending(manager: &ActionRequiredManager, count: usize) {
+        while manager.pending().await.len() < count {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_sorted_by_severity() {
+        let manager = Arc::new(ActionRequiredManager::new());
+        let mut handles = Vec::new();
+        for (i, severity) in [
+            ActionSeverity::Low,
+            ActionSeverity::Critical,
+            ActionSeverity::Normal,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let task_manager = manager.clone();
+            handles.push(tokio::spawn(async move {
+                task_manager
+                    .request_and_wait_with_options(
+                        format!("request {}", i),
+                        json!({}),
+                        Duration::from_secs(5),
+                        options(severity),
+                    )
+                    .await
+            }));
+            wait_for_pending(&manager, i + 1).await;
+        }
+
+        let pending = manager.pending().await;
+        let severities: Vec<_> = pending.iter().map(|p| p.severity).collect();
+        assert_eq!(
+            severities,
+            vec![
+                ActionSeverity::Critical,
+                ActionSeverity::Normal,
+                ActionSeverity::Low
+            ]
+        );
+
+        for p in &pending {
+            manager.resolve(&p.id, json!({"ok": true})).await.unwrap();
+        }
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().unwrap(), json!({"ok": true}));
+        }
+        assert!(manager.pending().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired_action_resolves_to_default() {
+        let manager = ActionRequiredManager::new();
+        let mut events = manager.subscribe();
+
+        let result = manager
+            .request_and_wait_with_options(
+                "Allow write?".to_string(),
+                json!({}),
+                Duration::from_secs(5),
+                ActionRequestOptions {
+                    severity: ActionSeverity::High,
+                    expiry: Some(ActionExpiry {
+                        after: Duration::from_millis(10),
+                        default_response: json!({"approved": false}),
+                    }),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result, json!({"approved": false}));
+        match events.recv().await.unwrap() {
+            ActionRequiredEvent::Expired {
+                default_response, ..
+            } => assert_eq!(default_response, json!({"approved": false})),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(manager.pending().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expiry_is_clamped_to_timeout() {
+        let manager = ActionRequiredManager::new();
+
+        let result = manager
+            .request_and_wait_with_options(
+                "Allow write?".to_string(),
+                json!({}),
+                Duration::from_millis(10),
+                ActionRequestOptions {
+                    severity: ActionSeverity::Normal,
+                    expiry: Some(ActionExpiry {
+                        after: Duration::from_secs(60),
+                        default_response: json!({"approved": false}),
+                    }),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result, json!({"approved": false}));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_unknown_request() {
+        let manager = ActionRequiredManager::new();
+        assert!(manager.resolve("missing", json!({})).await.is_err());
+    }
 }
@@ -10,13 +10,16 @@ use uuid::Uuid;
 
 use super::final_output_tool::FinalOutputTool;
 use super::platform_tools;
+use super::reply_parts::validate_tool_requests;
 use super::tool_execution::{ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE, DECLINED_RESPONSE};
+use super::tool_hints::ToolHints;
 use crate::action_required_manager::ActionRequiredManager;
 use crate::agents::error_handling::OverflowHandler;
 use crate::agents::extension::{ExtensionConfig, ExtensionResult, ToolInfo};
 use crate::agents::extension_manager::{get_parameter_names, ExtensionManager};
 use crate::agents::extension_manager_extension::MANAGE_EXTENSIONS_TOOL_NAME_COMPLETE;
 use crate::agents::final_output_tool::{FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_OUTPUT_TOOL_NAME};
+use crate::agents::mcp_client::user_sampling_approval;
 use crate::agents::platform_tools::PLATFORM_MANAGE_SCHEDULE_TOOL_NAME;
 use crate::agents::prompt_manager::PromptManager;
 use crate::agents::retry::{RetryManager, RetryResult};
@@ -28,7 +31,8 @@ use crate::agents::types::SessionConfig;
 use crate::agents::types::{FrontendTool, SharedProvider, ToolResultReceiver};
 use crate::config::{get_enabled_extensions, AsterMode, Config};
 use crate::context_mgmt::{
-    check_if_compaction_needed, compact_messages, DEFAULT_COMPACTION_THRESHOLD,
+    check_if_compaction_needed, compact_messages_restorable, ArchivedTurns, CompactionArchive,
+    DEFAULT_COMPACTION_THRESHOLD,
 };
 use crate::conversation::message::{
     ActionRequiredData, Message, MessageContent, ProviderMetadata, SystemNotificationType,
@@ -41,7 +45,9 @@ use crate::permission::permission_judge::PermissionCheckResult;
 use crate::permission::PermissionConfirmation;
 use crate::providers::base::Provider;
 use crate::providers::errors::ProviderError;
+use crate::providers::stream_retry::StreamRetryEvent;
 use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
+use crate::rules::{ProjectRules, RulesManager};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::security::security_inspector::SecurityInspector;
 use crate::session::extension_data::{EnabledExtensionsState, ExtensionState};
@@ -58,7 +64,7 @@ use rmcp::model::{
     ServerNotification, Tool,
 };
 use serde_json::Value;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, warn};
 
@@ -104,6 +110,12 @@ pub struct Agent {
     pub(super) tool_registry: Arc<RwLock<ToolRegistry>>,
     /// Shared file read history for file tools
     pub(super) file_read_history: SharedFileReadHistory,
+    /// Hints surfaced from patterns in recent tool results
+    pub(super) tool_hints: Mutex<ToolHints>,
+    /// Directory rule profiles for the session working directory
+    pub(super) rules_manager: Mutex<Option<RulesManager>>,
+    /// Retry events from every provider stream this agent opens
+    pub(super) stream_retry_events: broadcast::Sender<StreamRetryEvent>,
 
     /// 可选的 session 存储
     ///
@@ -174,7 +186,10 @@ impl Agent {
 
         Self {
             provider: provider.clone(),
-            extension_manager: Arc::new(ExtensionManager::new(provider.clone())),
+            extension_manager: Arc::new(
+                ExtensionManager::new(provider.clone())
+                    .with_sampling_approval(user_sampling_approval()),
+            ),
             sub_recipes: Mutex::new(HashMap::new()),
             final_output_tool: Arc::new(Mutex::new(None)),
             frontend_tools: Mutex::new(HashMap::new()),
@@ -189,6 +204,9 @@ impl Agent {
             tool_inspection_manager: Self::create_default_tool_inspection_manager(),
             tool_registry: Arc::new(RwLock::new(tool_registry)),
             file_read_history,
+            tool_hints: Mutex::new(ToolHints::load_default()),
+            rules_manager: Mutex::new(None),
+            stream_retry_events: broadcast::channel(16).0,
             session_store: None, // 默认使用全局 SessionManager
         }
     }
@@ -213,6 +231,40 @@ impl Agent {
         self.session_store.as_ref()
     }
 
+    /// Subscribe to `Retrying` / `Resumed` / `Abandoned` events of the
+    /// provider streams opened by this agent
+    pub fn subscribe_stream_retry_events(&self) -> broadcast::Receiver<StreamRetryEvent> {
+        self.stream_retry_events.subscribe()
+    }
+
+    /// Stop surfacing a hint; the dismissal is persisted across sessions.
+    pub async fn dismiss_hint(&self, hint_id: &str) -> Result<()> {
+        self.tool_hints.lock().await.dismiss(hint_id)
+    }
+
+    /// Rules for `working_dir`, re-evaluating directory profiles when the
+    /// session has moved to another directory.
+    pub async fn project_rules(&self, working_dir: &std::path::Path) -> ProjectRules {
+        let mut rules_manager = self.rules_manager.lock().await;
+        match rules_manager.as_mut() {
+            Some(manager) if manager.working_dir() != working_dir => {
+                if manager.set_working_dir(working_dir) {
+                    debug!(
+                        "Project rules changed for {}, active profile: {:?}",
+                        working_dir.display(),
+                        manager.rules().active_profile
+                    );
+                }
+            }
+            Some(_) => {}
+            None => *rules_manager = Some(RulesManager::new(working_dir)),
+        }
+        rules_manager
+            .as_ref()
+            .map(|manager| manager.rules().clone())
+            .unwrap_or_default()
+    }
+
     /// 设置 Agent 身份配置（Builder 模式）
     ///
     /// 允许应用层完全控制 Agent 的身份，包括名称、语言、描述等。
@@ -271,7 +323,10 @@ impl Agent {
 
         Self {
             provider: provider.clone(),
-            extension_manager: Arc::new(ExtensionManager::new(provider.clone())),
+            extension_manager: Arc::new(
+                ExtensionManager::new(provider.clone())
+                    .with_sampling_approval(user_sampling_approval()),
+            ),
             sub_recipes: Mutex::new(HashMap::new()),
             final_output_tool: Arc::new(Mutex::new(None)),
             frontend_tools: Mutex::new(HashMap::new()),
@@ -286,6 +341,9 @@ impl Agent {
             tool_inspection_manager: Self::create_default_tool_inspection_manager(),
             tool_registry: Arc::new(RwLock::new(tool_registry)),
             file_read_history,
+            tool_hints: Mutex::new(ToolHints::load_default()),
+            rules_manager: Mutex::new(None),
+            stream_retry_events: broadcast::channel(16).0,
             session_store: None,
         }
     }
@@ -844,6 +902,39 @@ impl Agent {
         Ok(())
     }
 
+    /// 保存被自动压缩替换的原始对话，供之后展开
+    async fn archive_compacted_turns(
+        &self,
+        session_id: &str,
+        marker_id: String,
+        turns: ArchivedTurns,
+    ) -> Result<()> {
+        let mut session_data = self.store_get_session(session_id, false).await?;
+        let mut archive = CompactionArchive::from_extension_data(&session_data.extension_data)
+            .unwrap_or_default();
+        archive.insert(marker_id, turns);
+        archive.to_extension_data(&mut session_data.extension_data)?;
+        self.store_update_extension_data(session_id, session_data.extension_data)
+            .await
+    }
+
+    /// 展开压缩标记，返回恢复了原始对话的历史
+    ///
+    /// 只读取，不修改 session 中保存的对话。
+    pub async fn expand_compaction(
+        &self,
+        session_id: &str,
+        marker_id: &str,
+    ) -> Result<Conversation> {
+        let session = self.store_get_session(session_id, true).await?;
+        let conversation = session
+            .conversation
+            .ok_or_else(|| anyhow!("Session {} has no conversation", session_id))?;
+        let archive =
+            CompactionArchive::from_extension_data(&session.extension_data).unwrap_or_default();
+        crate::context_mgmt::expand_compaction(&conversation, &archive, marker_id)
+    }
+
     pub async fn add_extension(&self, extension: ExtensionConfig) -> ExtensionResult<()> {
         match &extension {
             ExtensionConfig::Frontend {
@@ -986,7 +1077,10 @@ impl Agent {
         }
     }
 
-    #[instrument(skip(self, user_message, session_config), fields(user_message))]
+    #[instrument(
+        skip(self, user_message, session_config),
+        fields(user_message, session_id = %session_config.id)
+    )]
     pub async fn reply(
         &self,
         user_message: Message,
@@ -1144,8 +1238,9 @@ impl Agent {
                     )
                 );
 
-                match compact_messages(self.provider().await?.as_ref(), &conversation_to_compact, false).await {
-                    Ok((compacted_conversation, summarization_usage)) => {
+                match compact_messages_restorable(self.provider().await?.as_ref(), &conversation_to_compact, false).await {
+                    Ok((compacted_conversation, summarization_usage, marker_id, archived)) => {
+                        self.archive_compacted_turns(&session_config.id, marker_id, archived).await?;
                         self.store_replace_conversation(&session_config.id, &compacted_conversation).await?;
                         Self::update_session_metrics(&session_config, &summarization_usage, true, self.session_store.as_ref()).await?;
 
@@ -1250,6 +1345,7 @@ impl Agent {
                     conversation_with_moim.messages(),
                     &tools,
                     &toolshim_tools,
+                    self.stream_retry_events.clone(),
                 ).await?;
 
                 let mut no_tools_called = true;
@@ -1297,11 +1393,15 @@ impl Agent {
                                     remaining_requests,
                                     filtered_response,
                                 } = self.categorize_tools(&response, &tools).await;
+                                let (remaining_requests, invalid_requests) =
+                                    validate_tool_requests(remaining_requests, &tools);
 
                                 yield AgentEvent::Message(filtered_response.clone());
                                 tokio::task::yield_now().await;
 
-                                let num_tool_requests = frontend_requests.len() + remaining_requests.len();
+                                let num_tool_requests = frontend_requests.len()
+                                    + remaining_requests.len()
+                                    + invalid_requests.len();
                                 if num_tool_requests == 0 {
                                     messages_to_add.push(response.clone());
                                     continue;
@@ -1315,7 +1415,13 @@ impl Agent {
 
                                 let mut request_to_response_map = HashMap::new();
                                 let mut request_metadata: HashMap<String, Option<ProviderMetadata>> = HashMap::new();
-                                for (idx, request) in frontend_requests.iter().chain(remaining_requests.iter()).enumerate() {
+                                let all_requests = || {
+                                    frontend_requests
+                                        .iter()
+                                        .chain(remaining_requests.iter())
+                                        .chain(invalid_requests.iter().map(|(request, _)| request))
+                                };
+                                for (idx, request) in all_requests().enumerate() {
                                     request_to_response_map.insert(request.id.clone(), tool_response_messages[idx].clone());
                                     request_metadata.insert(request.id.clone(), request.metadata.clone());
                                 }
@@ -1330,6 +1436,19 @@ impl Agent {
                                         yield AgentEvent::Message(msg);
                                     }
                                 }
+
+                                // Answer calls with invalid arguments so the model can correct them
+                                for (request, error) in &invalid_requests {
+                                    if let Some(response_msg) = request_to_response_map.get(&request.id) {
+                                        let mut response = response_msg.lock().await;
+                                        *response = response.clone().with_tool_response_with_metadata(
+                                            request.id.clone(),
+                                            Err(error.clone()),
+                                            request.metadata.as_ref(),
+                                        );
+                                    }
+                                }
+
                                 if aster_mode == AsterMode::Chat {
                                     // Skip all remaining tool calls in chat mode
                                     for request in remaining_requests.iter() {
@@ -1417,6 +1536,12 @@ impl Agent {
 
                                     let mut combined = stream::select_all(with_id);
                                     let mut all_install_successful = true;
+                                    let tool_calls: HashMap<String, CallToolRequestParam> = remaining_requests
+                                        .iter()
+                                        .filter_map(|request| {
+                                            request.tool_call.as_ref().ok().map(|call| (request.id.clone(), call.clone()))
+                                        })
+                                        .collect();
 
                                     while let Some((request_id, item)) = combined.next().await {
                                         if is_token_cancelled(&cancel_token) {
@@ -1434,6 +1559,10 @@ impl Agent {
                                                 {
                                                     all_install_successful = false;
                                                 }
+                                                let output = match tool_calls.get(&request_id) {
+                                                    Some(tool_call) => self.tool_hints.lock().await.annotate(tool_call, output),
+                                                    None => output,
+                                                };
                                                 if let Some(response_msg) = request_to_response_map.get(&request_id) {
                                                     let metadata = request_metadata.get(&request_id).and_then(|m| m.as_ref());
                                                     let mut response = response_msg.lock().await;
@@ -1474,7 +1603,7 @@ impl Agent {
                                     messages_to_add.push(thinking_msg);
                                 }
 
-                                for (idx, request) in frontend_requests.iter().chain(remaining_requests.iter()).enumerate() {
+                                for (idx, request) in all_requests().enumerate() {
                                     if request.tool_call.is_ok() {
                                         let request_msg = Message::assistant()
                                             .with_id(format!("msg_{}", Uuid::new_v4()))
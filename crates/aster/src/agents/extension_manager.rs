@@ -34,7 +34,7 @@ use super::tool_execution::ToolCallResult;
 use super::types::SharedProvider;
 use crate::agents::extension::{Envs, ProcessExit};
 use crate::agents::extension_malware_check;
-use crate::agents::mcp_client::{McpClient, McpClientTrait};
+use crate::agents::mcp_client::{McpClient, McpClientTrait, SamplingApprovalCallback};
 use crate::config::search_path::SearchPaths;
 use crate::config::{get_all_extensions, Config};
 use crate::oauth::oauth_flow;
@@ -96,6 +96,7 @@ pub struct ExtensionManager {
     extensions: Mutex<HashMap<String, Extension>>,
     context: Mutex<PlatformExtensionContext>,
     provider: SharedProvider,
+    sampling_approval: Option<SamplingApprovalCallback>,
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -219,6 +220,7 @@ async fn child_process_client(
     mut command: Command,
     timeout: &Option<u64>,
     provider: SharedProvider,
+    sampling_approval: Option<SamplingApprovalCallback>,
 ) -> ExtensionResult<McpClient> {
     #[cfg(unix)]
     command.process_group(0);
@@ -241,10 +243,11 @@ async fn child_process_client(
         Ok::<String, std::io::Error>(String::from_utf8_lossy(&all_stderr).into())
     });
 
-    let client_result = McpClient::connect(
+    let client_result = McpClient::connect_with_sampling_approval(
         transport,
         Duration::from_secs(timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT)),
         provider,
+        sampling_approval,
     )
     .await;
 
@@ -371,6 +374,7 @@ async fn create_streamable_http_client(
     name: &str,
     all_envs: &HashMap<String, String>,
     provider: SharedProvider,
+    sampling_approval: Option<SamplingApprovalCallback>,
 ) -> ExtensionResult<Box<dyn McpClientTrait>> {
     let mut default_headers = HeaderMap::new();
     for (key, value) in headers {
@@ -400,7 +404,13 @@ async fn create_streamable_http_client(
     let timeout_duration =
         Duration::from_secs(timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT));
 
-    let client_res = McpClient::connect(transport, timeout_duration, provider.clone()).await;
+    let client_res = McpClient::connect_with_sampling_approval(
+        transport,
+        timeout_duration,
+        provider.clone(),
+        sampling_approval.clone(),
+    )
+    .await;
 
     if extract_auth_error(&client_res).is_some() {
         let am = oauth_flow(&uri.to_string(), &name.to_string())
@@ -415,7 +425,13 @@ async fn create_streamable_http_client(
             },
         );
         Ok(Box::new(
-            McpClient::connect(transport, timeout_duration, provider).await?,
+            McpClient::connect_with_sampling_approval(
+                transport,
+                timeout_duration,
+                provider,
+                sampling_approval,
+            )
+            .await?,
         ))
     } else {
         Ok(Box::new(client_res?))
@@ -428,6 +444,7 @@ async fn create_stdio_client(
     all_envs: HashMap<String, String>,
     timeout: &Option<u64>,
     provider: SharedProvider,
+    sampling_approval: Option<SamplingApprovalCallback>,
 ) -> ExtensionResult<Box<dyn McpClientTrait>> {
     extension_malware_check::deny_if_malicious_cmd_args(cmd, args).await?;
 
@@ -437,7 +454,7 @@ async fn create_stdio_client(
     });
 
     Ok(Box::new(
-        child_process_client(command, timeout, provider).await?,
+        child_process_client(command, timeout, provider, sampling_approval).await?,
     ))
 }
 
@@ -450,9 +467,17 @@ impl ExtensionManager {
                 extension_manager: None,
             }),
             provider,
+            sampling_approval: None,
         }
     }
 
+    /// Ask `approval` before serving sampling requests from extensions added
+    /// afterwards. Without it every sampling request is rejected.
+    pub fn with_sampling_approval(mut self, approval: SamplingApprovalCallback) -> Self {
+        self.sampling_approval = Some(approval);
+        self
+    }
+
     /// Create a new ExtensionManager with no provider (useful for tests)
     pub fn new_without_provider() -> Self {
         Self::new(Arc::new(Mutex::new(None)))
@@ -507,6 +532,7 @@ impl ExtensionManager {
                     name,
                     &all_envs,
                     self.provider.clone(),
+                    self.sampling_approval.clone(),
                 )
                 .await?
             }
@@ -519,7 +545,15 @@ impl ExtensionManager {
                 ..
             } => {
                 let all_envs = merge_environments(envs, env_keys, &sanitized_name).await?;
-                create_stdio_client(cmd, args, all_envs, timeout, self.provider.clone()).await?
+                create_stdio_client(
+                    cmd,
+                    args,
+                    all_envs,
+                    timeout,
+                    self.provider.clone(),
+                    self.sampling_approval.clone(),
+                )
+                .await?
             }
             ExtensionConfig::Builtin { name, timeout, .. } => {
                 let cmd = std::env::current_exe()
@@ -540,7 +574,15 @@ impl ExtensionManager {
                 let command = Command::new(cmd).configure(|command| {
                     command.arg("mcp").arg(name);
                 });
-                Box::new(child_process_client(command, timeout, self.provider.clone()).await?)
+                Box::new(
+                    child_process_client(
+                        command,
+                        timeout,
+                        self.provider.clone(),
+                        self.sampling_approval.clone(),
+                    )
+                    .await?,
+                )
             }
             ExtensionConfig::Platform { name, .. } => {
                 let normalized_key = normalize(name.clone());
@@ -572,7 +614,15 @@ impl ExtensionManager {
                     command.arg("python").arg(file_path.to_str().unwrap());
                 });
 
-                Box::new(child_process_client(command, timeout, self.provider.clone()).await?)
+                Box::new(
+                    child_process_client(
+                        command,
+                        timeout,
+                        self.provider.clone(),
+                        self.sampling_approval.clone(),
+                    )
+                    .await?,
+                )
             }
             ExtensionConfig::Frontend { .. } => {
                 return Err(ExtensionError::ConfigError(
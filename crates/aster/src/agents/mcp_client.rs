@@ -1,6 +1,7 @@
 use crate::action_required_manager::ActionRequiredManager;
 use crate::agents::types::SharedProvider;
 use crate::session_context::SESSION_ID_HEADER;
+use futures::future::BoxFuture;
 use rmcp::model::{
     Content, CreateElicitationRequestParam, CreateElicitationResult, ElicitationAction, ErrorCode,
     JsonObject,
@@ -14,9 +15,10 @@ use rmcp::{
         GetPromptRequestParam, GetPromptResult, Implementation, InitializeResult,
         ListPromptsRequest, ListPromptsResult, ListResourcesRequest, ListResourcesResult,
         ListToolsRequest, ListToolsResult, LoggingMessageNotification,
-        LoggingMessageNotificationMethod, PaginatedRequestParam, ProgressNotification,
-        ProgressNotificationMethod, ProtocolVersion, ReadResourceRequest, ReadResourceRequestParam,
-        ReadResourceResult, RequestId, Role, SamplingMessage, ServerNotification, ServerResult,
+        LoggingMessageNotificationMethod, ModelPreferences, PaginatedRequestParam,
+        ProgressNotification, ProgressNotificationMethod, ProtocolVersion, ReadResourceRequest,
+        ReadResourceRequestParam, ReadResourceResult, RequestId, Role, SamplingMessage,
+        ServerNotification, ServerResult,
     },
     service::{
         ClientInitializeError, PeerRequestOptions, RequestContext, RequestHandle, RunningService,
@@ -37,6 +39,68 @@ pub type BoxError = Box<dyn std::error::Error + Sync + Send>;
 
 pub type Error = rmcp::ServiceError;
 
+/// Error code returned to the server when the user rejects a sampling request
+pub const SAMPLING_REJECTED_ERROR_CODE: ErrorCode = ErrorCode(-1);
+
+/// A `sampling/createMessage` request from a server, shown to the user before
+/// any tokens are spent on it
+#[derive(Debug, Clone)]
+pub struct SamplingApprovalRequest {
+    pub server_name: Option<String>,
+    pub messages: Vec<SamplingMessage>,
+    pub model_preferences: Option<ModelPreferences>,
+    pub system_prompt: Option<String>,
+    pub max_tokens: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingDecision {
+    Approve,
+    Deny,
+}
+
+pub type SamplingApprovalCallback =
+    Arc<dyn Fn(SamplingApprovalRequest) -> BoxFuture<'static, SamplingDecision> + Send + Sync>;
+
+/// Approval handler that asks the user through the [`ActionRequiredManager`].
+/// Anything other than an explicit approval, including a timeout, denies.
+pub fn user_sampling_approval() -> SamplingApprovalCallback {
+    Arc::new(|request: SamplingApprovalRequest| {
+        Box::pin(async move {
+            let server = request.server_name.as_deref().unwrap_or("An extension");
+            let mut message = format!(
+                "{} wants to sample the model ({} message(s), max {} tokens).",
+                server,
+                request.messages.len(),
+                request.max_tokens
+            );
+            if let Some(system_prompt) = &request.system_prompt {
+                message.push_str(&format!("\nSystem prompt: {}", system_prompt));
+            }
+            let schema = serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "approve": {
+                        "type": "boolean",
+                        "description": "Allow this sampling request"
+                    }
+                },
+                "required": ["approve"]
+            });
+
+            match ActionRequiredManager::global()
+                .request_and_wait(message, schema, Duration::from_secs(300))
+                .await
+            {
+                Ok(response) if response.get("approve") == Some(&Value::Bool(true)) => {
+                    SamplingDecision::Approve
+                }
+                _ => SamplingDecision::Deny,
+            }
+        }) as BoxFuture<'static, SamplingDecision>
+    })
+}
+
 #[async_trait::async_trait]
 pub trait McpClientTrait: Send + Sync {
     async fn list_resources(
@@ -89,6 +153,7 @@ pub trait McpClientTrait: Send + Sync {
 pub struct AsterClient {
     notification_handlers: Arc<Mutex<Vec<Sender<ServerNotification>>>>,
     provider: SharedProvider,
+    sampling_approval: Option<SamplingApprovalCallback>,
 }
 
 impl AsterClient {
@@ -99,6 +164,44 @@ impl AsterClient {
         AsterClient {
             notification_handlers: handlers,
             provider,
+            sampling_approval: None,
+        }
+    }
+
+    /// Route every sampling request through `approval` before calling the model
+    pub fn with_sampling_approval(mut self, approval: SamplingApprovalCallback) -> Self {
+        self.sampling_approval = Some(approval);
+        self
+    }
+
+    async fn check_sampling_approval(
+        &self,
+        server_name: Option<String>,
+        params: &CreateMessageRequestParam,
+    ) -> Result<(), ErrorData> {
+        let Some(approval) = &self.sampling_approval else {
+            return Err(ErrorData::new(
+                SAMPLING_REJECTED_ERROR_CODE,
+                "Sampling requests are not allowed without an approval handler",
+                None,
+            ));
+        };
+
+        let request = SamplingApprovalRequest {
+            server_name,
+            messages: params.messages.clone(),
+            model_preferences: params.model_preferences.clone(),
+            system_prompt: params.system_prompt.clone(),
+            max_tokens: params.max_tokens,
+        };
+
+        match approval(request).await {
+            SamplingDecision::Approve => Ok(()),
+            SamplingDecision::Deny => Err(ErrorData::new(
+                SAMPLING_REJECTED_ERROR_CODE,
+                "User rejected sampling request",
+                None,
+            )),
         }
     }
 }
@@ -147,8 +250,14 @@ impl ClientHandler for AsterClient {
     async fn create_message(
         &self,
         params: CreateMessageRequestParam,
-        _context: RequestContext<RoleClient>,
+        context: RequestContext<RoleClient>,
     ) -> Result<CreateMessageResult, ErrorData> {
+        let server_name = context
+            .peer
+            .peer_info()
+            .map(|info| info.server_info.name.clone());
+        self.check_sampling_approval(server_name, &params).await?;
+
         let provider = self
             .provider
             .lock()
@@ -318,6 +427,20 @@ impl McpClient {
         timeout: std::time::Duration,
         provider: SharedProvider,
     ) -> Result<Self, ClientInitializeError>
+    where
+        T: IntoTransport<RoleClient, E, A>,
+        E: std::error::Error + From<std::io::Error> + Send + Sync + 'static,
+    {
+        Self::connect_with_sampling_approval(transport, timeout, provider, None).await
+    }
+
+    /// Connect, asking `sampling_approval` before serving any sampling request
+    pub async fn connect_with_sampling_approval<T, E, A>(
+        transport: T,
+        timeout: std::time::Duration,
+        provider: SharedProvider,
+        sampling_approval: Option<SamplingApprovalCallback>,
+    ) -> Result<Self, ClientInitializeError>
     where
         T: IntoTransport<RoleClient, E, A>,
         E: std::error::Error + From<std::io::Error> + Send + Sync + 'static,
@@ -325,7 +448,10 @@ impl McpClient {
         let notification_subscribers =
             Arc::new(Mutex::new(Vec::<mpsc::Sender<ServerNotification>>::new()));
 
-        let client = AsterClient::new(notification_subscribers.clone(), provider);
+        let mut client = AsterClient::new(notification_subscribers.clone(), provider);
+        if let Some(approval) = sampling_approval {
+            client = client.with_sampling_approval(approval);
+        }
         let client: rmcp::service::RunningService<rmcp::RoleClient, AsterClient> =
             client.serve(transport).await?;
         let server_info = client.peer_info().cloned();
@@ -665,4 +791,82 @@ mod tests {
         })
         .await;
     }
+
+    fn sampling_params() -> CreateMessageRequestParam {
+        CreateMessageRequestParam {
+            messages: vec![SamplingMessage {
+                role: Role::User,
+                content: Content::text("Summarize the repo"),
+            }],
+            model_preferences: Some(ModelPreferences {
+                hints: Some(vec![rmcp::model::ModelHint {
+                    name: Some("claude-3-haiku".to_string()),
+                }]),
+                cost_priority: None,
+                speed_priority: None,
+                intelligence_priority: None,
+            }),
+            system_prompt: None,
+            include_context: None,
+            temperature: None,
+            max_tokens: 256,
+            stop_sequences: None,
+            metadata: None,
+        }
+    }
+
+    fn client_with_decision(
+        decision: SamplingDecision,
+        seen: Arc<std::sync::Mutex<Vec<SamplingApprovalRequest>>>,
+    ) -> AsterClient {
+        let provider: SharedProvider = Arc::new(Mutex::new(None));
+        AsterClient::new(Arc::new(Mutex::new(Vec::new())), provider).with_sampling_approval(
+            Arc::new(move |request| {
+                seen.lock().unwrap().push(request);
+                Box::pin(async move { decision })
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sampling_approved() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let client = client_with_decision(SamplingDecision::Approve, seen.clone());
+
+        let result = client
+            .check_sampling_approval(Some("docs".to_string()), &sampling_params())
+            .await;
+        assert!(result.is_ok());
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].server_name.as_deref(), Some("docs"));
+        assert_eq!(seen[0].messages.len(), 1);
+        let hints = seen[0].model_preferences.as_ref().unwrap().hints.as_ref();
+        assert_eq!(hints.unwrap()[0].name.as_deref(), Some("claude-3-haiku"));
+    }
+
+    #[tokio::test]
+    async fn test_sampling_denied() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let client = client_with_decision(SamplingDecision::Deny, seen.clone());
+
+        let err = client
+            .check_sampling_approval(None, &sampling_params())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, SAMPLING_REJECTED_ERROR_CODE);
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_without_approval_is_rejected() {
+        let client = AsterClient::new(Arc::new(Mutex::new(Vec::new())), Arc::new(Mutex::new(None)));
+
+        let err = client
+            .check_sampling_approval(Some("docs".to_string()), &sampling_params())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, SAMPLING_REJECTED_ERROR_CODE);
+    }
 }
@@ -23,6 +23,7 @@ mod subagent_task_config;
 pub mod subagent_tool;
 pub(crate) mod todo_extension;
 mod tool_execution;
+mod tool_hints;
 pub mod types;
 
 /// SubAgent 调度器模块
@@ -15,6 +15,7 @@ use std::collections::HashMap;
 use super::identity::AgentIdentity;
 use crate::agents::extension::ExtensionInfo;
 use crate::hints::load_hints::{load_hint_files, AGENTS_MD_FILENAME, ASTER_HINTS_FILENAME};
+use crate::rules::{generate_system_prompt_addition, ProjectRules};
 use crate::{
     config::{AsterMode, Config},
     prompt_template,
@@ -79,6 +80,7 @@ pub struct SystemPromptBuilder<'a, M> {
     extension_tool_count: Option<(usize, usize)>,
     subagents_enabled: bool,
     hints: Option<String>,
+    project_rules: Option<String>,
     code_execution_mode: bool,
     session_prompt: Option<String>,
 }
@@ -142,6 +144,15 @@ impl<'a> SystemPromptBuilder<'a, PromptManager> {
         self
     }
 
+    /// Add the rules of the active directory profile to the prompt
+    pub fn with_project_rules(mut self, rules: &ProjectRules) -> Self {
+        let addition = generate_system_prompt_addition(rules);
+        if !addition.trim().is_empty() {
+            self.project_rules = Some(addition);
+        }
+        self
+    }
+
     pub fn with_enable_subagents(mut self, subagents_enabled: bool) -> Self {
         self.subagents_enabled = subagents_enabled;
         self
@@ -216,6 +227,10 @@ impl<'a> SystemPromptBuilder<'a, PromptManager> {
             system_prompt_extras.push(hints);
         }
 
+        if let Some(project_rules) = self.project_rules {
+            system_prompt_extras.push(project_rules);
+        }
+
         if aster_mode == AsterMode::Chat {
             system_prompt_extras.push(
                 "Right now you are in the chat only mode, no access to any tool use and system."
@@ -395,6 +410,7 @@ impl PromptManager {
             extension_tool_count: None,
             subagents_enabled: false,
             hints: None,
+            project_rules: None,
             code_execution_mode: false,
             session_prompt: None,
         }
@@ -495,6 +511,26 @@ mod tests {
         assert!(result.contains("hidden instructions"));
     }
 
+    #[test]
+    fn test_build_system_prompt_includes_project_rules() {
+        let manager = PromptManager::new();
+        let rules = ProjectRules {
+            instructions: Some("Use tabs for indentation".to_string()),
+            active_profile: Some("work".to_string()),
+            ..Default::default()
+        };
+
+        let result = manager.builder().with_project_rules(&rules).build();
+        assert!(result.contains("## Project Instructions"));
+        assert!(result.contains("Use tabs for indentation"));
+
+        let empty = manager
+            .builder()
+            .with_project_rules(&ProjectRules::default())
+            .build();
+        assert!(!empty.contains("## Project Instructions"));
+    }
+
     #[test]
     fn test_basic() {
         let manager = PromptManager::with_timestamp(DateTime::<Utc>::from_timestamp(0, 0).unwrap());
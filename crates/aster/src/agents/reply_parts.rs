@@ -4,6 +4,7 @@ use std::sync::Arc;
 use async_stream::try_stream;
 use futures::stream::StreamExt;
 use serde_json::{json, Value};
+use tokio::sync::broadcast;
 use tracing::debug;
 
 use super::super::agents::Agent;
@@ -11,6 +12,7 @@ use crate::conversation::message::{Message, MessageContent, ToolRequest};
 use crate::conversation::Conversation;
 use crate::providers::base::{stream_from_single_message, MessageStream, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
+use crate::providers::stream_retry::{StreamRetry, StreamRetryEvent, StreamRetryPolicy};
 use crate::providers::toolshim::{
     augment_message_with_tool_calls, convert_tool_messages_to_text,
     modify_system_prompt_for_tool_json, OllamaInterpreter,
@@ -20,7 +22,8 @@ use crate::agents::code_execution_extension::EXTENSION_NAME as CODE_EXECUTION_EX
 #[cfg(test)]
 use crate::session::SessionType;
 use crate::session::{SessionManager, SessionStore, TokenStatsUpdate};
-use rmcp::model::Tool;
+use crate::tools::{coerce_to_schema, validate_against_schema};
+use rmcp::model::{ErrorCode, ErrorData, Tool};
 
 fn coerce_value(s: &str, schema: &Value) -> Value {
     let type_str = schema.get("type");
@@ -95,6 +98,57 @@ fn coerce_tool_arguments(
     Some(coerced)
 }
 
+/// Split tool requests into those whose arguments match the tool's input
+/// schema and those that don't, paired with the error to send back
+///
+/// Arguments are coerced to the schema first, as the tool registry does, so
+/// lossless mismatches such as `"5"` for an integer are fixed rather than
+/// rejected. Invalid requests are answered with the error instead of being
+/// executed so the model can correct its arguments. Requests for unknown
+/// tools or with unparseable arguments are left for dispatch to report.
+pub(crate) fn validate_tool_requests(
+    requests: Vec<ToolRequest>,
+    tools: &[Tool],
+) -> (Vec<ToolRequest>, Vec<(ToolRequest, ErrorData)>) {
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+
+    for mut request in requests {
+        let error = match &mut request.tool_call {
+            Ok(tool_call) => tools
+                .iter()
+                .find(|t| t.name == tool_call.name)
+                .and_then(|tool| {
+                    let schema = Value::Object(tool.input_schema.as_ref().clone());
+                    let mut params = Value::Object(tool_call.arguments.clone().unwrap_or_default());
+                    if !coerce_to_schema(&schema, &mut params).is_empty() {
+                        if let Value::Object(arguments) = &params {
+                            tool_call.arguments = Some(arguments.clone());
+                        }
+                    }
+                    validate_against_schema(&schema, &params).err()
+                })
+                .map(|e| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        format!(
+                            "{}. The tool was not run; call '{}' again with arguments that match its input schema.",
+                            e, tool_call.name
+                        ),
+                        None,
+                    )
+                }),
+            Err(_) => None,
+        };
+        match error {
+            Some(error) => invalid.push((request, error)),
+            None => valid.push(request),
+        }
+    }
+
+    (valid, invalid)
+}
+
 async fn toolshim_postprocess(
     response: Message,
     toolshim_tools: &[Tool],
@@ -144,6 +198,7 @@ impl Agent {
         let provider = self.provider().await?;
         let model_config = provider.get_model_config();
 
+        let project_rules = self.project_rules(working_dir).await;
         let prompt_manager = self.prompt_manager.lock().await;
         let mut system_prompt = prompt_manager
             .builder()
@@ -152,6 +207,7 @@ impl Agent {
             .with_extension_and_tool_counts(extension_count, tool_count)
             .with_code_execution_mode(code_execution_active)
             .with_hints(working_dir)
+            .with_project_rules(&project_rules)
             .with_enable_subagents(self.subagents_enabled().await)
             .with_session_prompt(session_prompt.map(|s| s.to_string()))
             .build();
@@ -171,13 +227,15 @@ impl Agent {
     }
 
     /// Stream a response from the LLM provider.
-    /// Handles toolshim transformations if needed
+    /// Handles toolshim transformations if needed; stream retry events are
+    /// published on `retry_events`
     pub(crate) async fn stream_response_from_provider(
         provider: Arc<dyn Provider>,
         system_prompt: &str,
         messages: &[Message],
         tools: &[Tool],
         toolshim_tools: &[Tool],
+        retry_events: broadcast::Sender<StreamRetryEvent>,
     ) -> Result<MessageStream, ProviderError> {
         let config = provider.get_model_config();
 
@@ -198,13 +256,14 @@ impl Agent {
         // so they can be handled by the existing error handling logic in the agent
         let stream_result = if provider.supports_streaming() {
             debug!("WAITING_LLM_STREAM_START");
-            let result = provider
-                .stream(
-                    system_prompt.as_str(),
-                    messages_for_provider.messages(),
-                    &tools,
-                )
-                .await;
+            let retry = StreamRetry::new(provider.retry_config(), StreamRetryPolicy::from_config())
+                .with_event_sender(retry_events);
+            let result = Ok(retry.stream(
+                provider.clone(),
+                system_prompt.clone(),
+                messages_for_provider.messages().to_vec(),
+                tools.clone(),
+            ));
             debug!("WAITING_LLM_STREAM_END");
             result
         } else {
@@ -237,7 +296,8 @@ impl Agent {
         };
 
         Ok(Box::pin(try_stream! {
-            while let Some(Ok((mut message, usage))) = stream.next().await {
+            while let Some(item) = stream.next().await {
+                let (mut message, usage) = item?;
                 // Store the model information in the global store
                 if let Some(usage) = usage.as_ref() {
                     crate::providers::base::set_current_model(&usage.model);
@@ -591,4 +651,68 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_streamed_tool_call_with_invalid_args_gets_correction() {
+        use rmcp::model::CallToolRequestParam;
+
+        let tools = vec![Tool::new(
+            "developer__read".to_string(),
+            "Read a file".to_string(),
+            object!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "limit": { "type": "integer" }
+                },
+                "required": ["path"]
+            }),
+        )];
+        let request = |id: &str, fragments: &[&str]| {
+            // Arguments arrive as fragments and are parsed once the block closes
+            let arguments = serde_json::from_str(&fragments.concat()).unwrap();
+            let message = Message::assistant().with_tool_request(
+                id,
+                Ok(CallToolRequestParam {
+                    name: "developer__read".into(),
+                    arguments: Some(arguments),
+                }),
+            );
+            match &message.content[0] {
+                MessageContent::ToolRequest(request) => request.clone(),
+                _ => unreachable!(),
+            }
+        };
+
+        let (valid, invalid) = validate_tool_requests(
+            vec![
+                request("good", &["{\"path\": ", "\"src/main.rs\"}"]),
+                request("coerced", &["{\"path\": \"a\", ", "\"limit\": \"10\"}"]),
+                request("bad", &["{\"path\": 4", "2, \"limit\": ", "\"ten\"}"]),
+            ],
+            &tools,
+        );
+
+        assert_eq!(valid.len(), 2);
+        assert_eq!(valid[0].id, "good");
+        assert_eq!(valid[1].id, "coerced");
+        let arguments = valid[1].tool_call.as_ref().unwrap().arguments.as_ref();
+        assert_eq!(arguments.unwrap()["limit"], 10);
+        assert_eq!(invalid.len(), 1);
+        let (bad, error) = &invalid[0];
+        assert_eq!(bad.id, "bad");
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("'/path'"), "{}", error.message);
+        assert!(error.message.contains("'/limit'"), "{}", error.message);
+        assert!(error.message.contains("The tool was not run"));
+
+        let correction = Message::user().with_tool_response(bad.id.clone(), Err(error.clone()));
+        match &correction.content[0] {
+            MessageContent::ToolResponse(response) => {
+                assert_eq!(response.id, "bad");
+                assert!(response.tool_result.is_err());
+            }
+            _ => panic!("expected a tool response"),
+        }
+    }
 }
@@ -160,6 +160,7 @@ impl Agent {
             last_run: None,
             currently_running: false,
             paused: false,
+            paused_by_pause_all: false,
             current_session_id: None,
             process_start_time: None,
         };
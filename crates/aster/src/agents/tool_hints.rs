@@ -0,0 +1,165 @@
+use anyhow::Result;
+use rmcp::model::{CallToolRequestParam, CallToolResult, Content, ErrorData};
+
+use crate::hints::{Hint, HintRanker, HintTrigger, ToolActivity};
+
+const MAX_HINTS_PER_RESULT: usize = 2;
+
+/// Feeds tool results to the hint triggers and attaches the top ranked hints
+/// to the result, so the model sees them next to the failure that caused them.
+pub struct ToolHints {
+    trigger: HintTrigger,
+    ranker: HintRanker,
+}
+
+impl ToolHints {
+    pub fn new(trigger: HintTrigger, ranker: HintRanker) -> Self {
+        Self { trigger, ranker }
+    }
+
+    pub fn load_default() -> Self {
+        Self::new(HintTrigger::load_default(), HintRanker::load_default())
+    }
+
+    pub fn dismiss(&mut self, hint_id: &str) -> Result<()> {
+        self.ranker.dismiss(hint_id)
+    }
+
+    pub fn annotate(
+        &mut self,
+        tool_call: &CallToolRequestParam,
+        output: Result<CallToolResult, ErrorData>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let fired = self.trigger.observe(activity_for(tool_call, &output));
+        let hints = self.ranker.rank(fired, MAX_HINTS_PER_RESULT);
+        if hints.is_empty() {
+            return output;
+        }
+
+        let note = format_hints(&hints);
+        match output {
+            Ok(mut result) => {
+                result.content.push(Content::text(note));
+                Ok(result)
+            }
+            Err(error) => Err(ErrorData::new(
+                error.code,
+                format!("{}\n\n{}", error.message, note),
+                error.data,
+            )),
+        }
+    }
+}
+
+fn activity_for(
+    tool_call: &CallToolRequestParam,
+    output: &Result<CallToolResult, ErrorData>,
+) -> ToolActivity {
+    let mut activity = match output {
+        Err(error) => ToolActivity::failure(tool_call.name.to_string(), error.message.to_string()),
+        Ok(result) if result.is_error == Some(true) => {
+            let text = result
+                .content
+                .iter()
+                .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ToolActivity::failure(tool_call.name.to_string(), text)
+        }
+        Ok(_) => ToolActivity::success(tool_call.name.to_string()),
+    };
+
+    let target = tool_call.arguments.as_ref().and_then(|args| {
+        ["path", "file_path"]
+            .iter()
+            .find_map(|key| args.get(*key).and_then(|v| v.as_str()))
+    });
+    if let Some(target) = target {
+        activity = activity.with_target(target);
+    }
+    activity
+}
+
+fn format_hints(hints: &[Hint]) -> String {
+    hints
+        .iter()
+        .map(|hint| format!("Hint: {}", hint.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::ErrorCode;
+    use serde_json::json;
+
+    fn edit_call(path: &str) -> CallToolRequestParam {
+        CallToolRequestParam {
+            name: "edit".into(),
+            arguments: json!({ "path": path, "old_str": "a", "new_str": "b" })
+                .as_object()
+                .cloned(),
+        }
+    }
+
+    fn not_read(path: &str) -> Result<CallToolResult, ErrorData> {
+        Err(ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Execution failed: File has not been read: {path}. Read the file first before editing."),
+            None,
+        ))
+    }
+
+    fn tool_hints() -> ToolHints {
+        ToolHints::new(HintTrigger::with_default_rules(), HintRanker::new())
+    }
+
+    #[test]
+    fn test_repeated_failures_get_hint_once() {
+        let mut hints = tool_hints();
+        let call = edit_call("src/main.rs");
+
+        let messages: Vec<String> = (0..3)
+            .map(|_| {
+                hints
+                    .annotate(&call, not_read("src/main.rs"))
+                    .unwrap_err()
+                    .message
+                    .to_string()
+            })
+            .collect();
+
+        assert!(!messages[0].contains("Hint:"));
+        assert!(messages[1].contains("Hint: Edits to this file keep failing"));
+        assert!(!messages[2].contains("Hint:"));
+    }
+
+    #[test]
+    fn test_successful_results_are_untouched() {
+        let mut hints = tool_hints();
+        let call = edit_call("src/main.rs");
+
+        for _ in 0..3 {
+            let result = hints
+                .annotate(
+                    &call,
+                    Ok(CallToolResult::success(vec![Content::text("ok")])),
+                )
+                .unwrap();
+            assert_eq!(result.content.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_dismissed_hint_is_not_attached() {
+        let mut hints = tool_hints();
+        hints.dismiss("read-before-edit").unwrap();
+        let call = edit_call("src/main.rs");
+
+        for _ in 0..3 {
+            let error = hints.annotate(&call, not_read("src/main.rs")).unwrap_err();
+            assert!(!error.message.contains("Hint:"));
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use super::manifest::{validate_manifest, AppManifest, ManifestError};
+
+/// File name of the manifest at the root of every app directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// A reason an app could not be installed
+#[derive(Debug, Error)]
+pub enum AppInstallError {
+    #[error("cannot read manifest {path}: {message}")]
+    ManifestUnreadable { path: PathBuf, message: String },
+
+    #[error("app `{app}` failed manifest validation:\n{}", format_errors(.errors))]
+    InvalidManifest {
+        app: String,
+        errors: Vec<ManifestError>,
+    },
+
+    #[error("app name `{0}` cannot be used as a directory name")]
+    InvalidName(String),
+
+    #[error("cannot install app `{app}`: {message}")]
+    Io { app: String, message: String },
+}
+
+fn format_errors(errors: &[ManifestError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("  - {}", e))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Read the manifest of an app directory
+pub fn read_manifest(app_dir: &Path) -> Result<AppManifest, AppInstallError> {
+    let path = app_dir.join(MANIFEST_FILE);
+    let unreadable = |message: String| AppInstallError::ManifestUnreadable {
+        path: path.clone(),
+        message,
+    };
+    let content = fs::read_to_string(&path).map_err(|e| unreadable(e.to_string()))?;
+    serde_json::from_str(&content).map_err(|e| unreadable(e.to_string()))
+}
+
+/// Names and versions of the apps installed in `apps_dir`
+///
+/// Directories without a readable manifest are ignored.
+pub fn installed_apps(apps_dir: &Path) -> HashMap<String, String> {
+    let Ok(entries) = fs::read_dir(apps_dir) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| read_manifest(&e.path()).ok())
+        .map(|m| (m.name, m.version))
+        .collect()
+}
+
+/// Install the app in `source_dir` into `apps_dir/<name>`
+///
+/// The manifest is validated against the apps already installed before
+/// anything is copied, so a rejected app leaves `apps_dir` untouched. An
+/// installed app with the same name is replaced.
+pub fn install_app(source_dir: &Path, apps_dir: &Path) -> Result<AppManifest, AppInstallError> {
+    let manifest = read_manifest(source_dir)?;
+    let mut installed = installed_apps(apps_dir);
+    installed.remove(&manifest.name);
+    validate_manifest(&manifest, &installed).map_err(|errors| {
+        AppInstallError::InvalidManifest {
+            app: manifest.name.clone(),
+            errors,
+        }
+    })?;
+
+    let name = manifest.name.as_str();
+    if name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(AppInstallError::InvalidName(name.to_string()));
+    }
+
+    let io_error = |e: std::io::Error| AppInstallError::Io {
+        app: name.to_string(),
+        message: e.to_string(),
+    };
+    let target = apps_dir.join(name);
+    let staging = apps_dir.join(format!(".{}.installing", name));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(io_error)?;
+    }
+    copy_dir(source_dir, &staging).map_err(io_error)?;
+    if target.exists() {
+        fs::remove_dir_all(&target).map_err(io_error)?;
+    }
+    fs::rename(&staging, &target).map_err(io_error)?;
+
+    Ok(manifest)
+}
+
+fn copy_dir(src: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dest_path = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest_path)?;
+        } else {
+            fs::copy(entry.path(), &dest_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_app(dir: &Path, manifest: serde_json::Value) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(MANIFEST_FILE), manifest.to_string()).unwrap();
+        fs::write(dir.join("index.html"), "<html></html>").unwrap();
+    }
+
+    fn dashboard(permissions: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "name": "dashboard",
+            "version": "1.0.0",
+            "permissions": permissions,
+            "entryPoints": [
+                { "name": "main", "uri": "ui://dashboard/index.html", "permissions": ["storage"] }
+            ],
+            "dependencies": { "charts": "^2.1.0" }
+        })
+    }
+
+    #[test]
+    fn test_install_validates_against_installed_apps() {
+        let temp = tempfile::tempdir().unwrap();
+        let apps_dir = temp.path().join("apps");
+        let source = temp.path().join("src");
+        write_app(&source, dashboard(&["storage"]));
+
+        // The dependency is not installed yet
+        let err = install_app(&source, &apps_dir).unwrap_err();
+        let AppInstallError::InvalidManifest { errors, .. } = &err else {
+            panic!("unexpected error: {}", err);
+        };
+        assert!(matches!(
+            &errors[0],
+            ManifestError::MissingDependency { name, .. } if name == "charts"
+        ));
+        assert!(!apps_dir.join("dashboard").exists());
+
+        write_app(
+            &apps_dir.join("charts"),
+            serde_json::json!({
+                "name": "charts",
+                "version": "2.3.0",
+                "entryPoints": [{ "name": "main", "uri": "ui://charts/index.html" }]
+            }),
+        );
+        let manifest = install_app(&source, &apps_dir).unwrap();
+        assert_eq!(manifest.name, "dashboard");
+        assert!(apps_dir.join("dashboard").join("index.html").exists());
+        assert_eq!(
+            installed_apps(&apps_dir)
+                .get("dashboard")
+                .map(String::as_str),
+            Some("1.0.0")
+        );
+    }
+
+    #[test]
+    fn test_install_rejects_undeclared_permission() {
+        let temp = tempfile::tempdir().unwrap();
+        let apps_dir = temp.path().join("apps");
+        let source = temp.path().join("src");
+        let mut manifest = dashboard(&[]);
+        manifest["dependencies"] = serde_json::json!({});
+        write_app(&source, manifest);
+
+        let err = install_app(&source, &apps_dir).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("requires permission `storage` which the manifest does not declare"));
+        assert!(installed_apps(&apps_dir).is_empty());
+    }
+}
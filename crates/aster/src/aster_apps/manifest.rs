@@ -0,0 +1,347 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::resource::CspMetadata;
+use crate::plugins::{Version, VersionChecker};
+
+/// Capabilities an app can request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppPermission {
+    /// Outgoing network connections (fetch, XHR, WebSocket)
+    Network,
+    ClipboardRead,
+    ClipboardWrite,
+    /// Persistent key-value storage
+    Storage,
+    /// Calling tools exposed to the host
+    Tools,
+    Notifications,
+    /// Opening external links in the browser
+    OpenLinks,
+}
+
+impl AppPermission {
+    pub const ALL: [AppPermission; 7] = [
+        AppPermission::Network,
+        AppPermission::ClipboardRead,
+        AppPermission::ClipboardWrite,
+        AppPermission::Storage,
+        AppPermission::Tools,
+        AppPermission::Notifications,
+        AppPermission::OpenLinks,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppPermission::Network => "network",
+            AppPermission::ClipboardRead => "clipboard-read",
+            AppPermission::ClipboardWrite => "clipboard-write",
+            AppPermission::Storage => "storage",
+            AppPermission::Tools => "tools",
+            AppPermission::Notifications => "notifications",
+            AppPermission::OpenLinks => "open-links",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_str() == value)
+    }
+}
+
+/// An entry point the host can open
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppEntryPoint {
+    pub name: String,
+    /// URI of the UI resource (must use ui:// scheme)
+    pub uri: String,
+    /// Permissions this entry point needs; each must also be declared by the manifest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+}
+
+/// App manifest checked before install
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Permissions requested by the app
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub entry_points: Vec<AppEntryPoint>,
+    /// Other apps this app depends on, mapped to a version range (e.g. ^1.2.0)
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csp: Option<CspMetadata>,
+}
+
+/// A reason a manifest was rejected
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum ManifestError {
+    #[error("manifest field `{0}` is required")]
+    MissingField(&'static str),
+
+    #[error("version `{0}` is not a valid MAJOR.MINOR.PATCH version")]
+    InvalidVersion(String),
+
+    #[error("unknown permission `{permission}`; valid permissions are: {valid}")]
+    UnknownPermission { permission: String, valid: String },
+
+    #[error("manifest declares no entry points; add at least one with a ui:// uri")]
+    NoEntryPoints,
+
+    #[error("entry point `{0}` is declared more than once; entry point names must be unique")]
+    DuplicateEntryPoint(String),
+
+    #[error("entry point `{name}` has uri `{uri}`; entry point uris must use the ui:// scheme")]
+    InvalidEntryPointUri { name: String, uri: String },
+
+    #[error(
+        "entry point `{entry_point}` requires permission `{permission}` which the manifest does not declare; add it to `permissions`"
+    )]
+    UndeclaredPermission {
+        entry_point: String,
+        permission: String,
+    },
+
+    #[error("csp connect domains ({0}) require the `network` permission; add it to `permissions`")]
+    UndeclaredNetworkAccess(String),
+
+    #[error("app `{0}` cannot depend on itself")]
+    SelfDependency(String),
+
+    #[error("dependency `{name}` has invalid version range `{range}`")]
+    InvalidDependencyRange { name: String, range: String },
+
+    #[error("dependency `{name}` ({range}) is not installed; install it before this app")]
+    MissingDependency { name: String, range: String },
+
+    #[error("dependency `{name}` requires {range} but {installed} is installed; upgrade `{name}`")]
+    IncompatibleDependency {
+        name: String,
+        range: String,
+        installed: String,
+    },
+}
+
+/// Version ranges accepted by [`VersionChecker::satisfies`]
+fn is_valid_range(range: &str) -> bool {
+    if range == "*" || range == "latest" {
+        return true;
+    }
+    let version = ["^", "~", ">=", "<=", ">", "<"]
+        .iter()
+        .find_map(|prefix| range.strip_prefix(prefix))
+        .unwrap_or(range);
+    Version::parse(version).is_some()
+}
+
+/// Validate an app manifest before install
+///
+/// `installed` maps installed app names to their versions. All problems are
+/// collected so the author can fix them in one pass.
+pub fn validate_manifest(
+    manifest: &AppManifest,
+    installed: &HashMap<String, String>,
+) -> Result<(), Vec<ManifestError>> {
+    let mut errors = Vec::new();
+
+    if manifest.name.trim().is_empty() {
+        errors.push(ManifestError::MissingField("name"));
+    }
+    if manifest.version.trim().is_empty() {
+        errors.push(ManifestError::MissingField("version"));
+    } else if Version::parse(&manifest.version).is_none() {
+        errors.push(ManifestError::InvalidVersion(manifest.version.clone()));
+    }
+
+    // Permissions
+    let valid = AppPermission::ALL
+        .iter()
+        .map(|p| p.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut declared = HashSet::new();
+    for permission in &manifest.permissions {
+        match AppPermission::parse(permission) {
+            Some(p) => {
+                declared.insert(p);
+            }
+            None => errors.push(ManifestError::UnknownPermission {
+                permission: permission.clone(),
+                valid: valid.clone(),
+            }),
+        }
+    }
+
+    let connect_domains = manifest
+        .csp
+        .as_ref()
+        .and_then(|csp| csp.connect_domains.as_ref())
+        .filter(|domains| !domains.is_empty());
+    if let Some(domains) = connect_domains {
+        if !declared.contains(&AppPermission::Network) {
+            errors.push(ManifestError::UndeclaredNetworkAccess(domains.join(", ")));
+        }
+    }
+
+    // Entry points
+    if manifest.entry_points.is_empty() {
+        errors.push(ManifestError::NoEntryPoints);
+    }
+    let mut names = HashSet::new();
+    for entry in &manifest.entry_points {
+        if !names.insert(entry.name.as_str()) {
+            errors.push(ManifestError::DuplicateEntryPoint(entry.name.clone()));
+        }
+        let has_path = entry
+            .uri
+            .strip_prefix("ui://")
+            .is_some_and(|path| !path.is_empty());
+        if !has_path {
+            errors.push(ManifestError::InvalidEntryPointUri {
+                name: entry.name.clone(),
+                uri: entry.uri.clone(),
+            });
+        }
+        for permission in &entry.permissions {
+            match AppPermission::parse(permission) {
+                Some(p) if declared.contains(&p) => {}
+                Some(_) => errors.push(ManifestError::UndeclaredPermission {
+                    entry_point: entry.name.clone(),
+                    permission: permission.clone(),
+                }),
+                None => errors.push(ManifestError::UnknownPermission {
+                    permission: permission.clone(),
+                    valid: valid.clone(),
+                }),
+            }
+        }
+    }
+
+    // Dependencies
+    for (name, range) in &manifest.dependencies {
+        if *name == manifest.name {
+            errors.push(ManifestError::SelfDependency(name.clone()));
+            continue;
+        }
+        if !is_valid_range(range) {
+            errors.push(ManifestError::InvalidDependencyRange {
+                name: name.clone(),
+                range: range.clone(),
+            });
+            continue;
+        }
+        match installed.get(name) {
+            None => errors.push(ManifestError::MissingDependency {
+                name: name.clone(),
+                range: range.clone(),
+            }),
+            Some(version) if !VersionChecker::satisfies(version, range) => {
+                errors.push(ManifestError::IncompatibleDependency {
+                    name: name.clone(),
+                    range: range.clone(),
+                    installed: version.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> AppManifest {
+        serde_json::from_value(serde_json::json!({
+            "name": "dashboard",
+            "version": "1.0.0",
+            "permissions": ["network", "storage"],
+            "entryPoints": [
+                { "name": "main", "uri": "ui://dashboard/index.html", "permissions": ["storage"] }
+            ],
+            "dependencies": { "charts": "^2.1.0" },
+            "csp": { "connectDomains": ["api.example.com"] }
+        }))
+        .unwrap()
+    }
+
+    fn installed() -> HashMap<String, String> {
+        HashMap::from([("charts".to_string(), "2.3.0".to_string())])
+    }
+
+    #[test]
+    fn test_valid_manifest() {
+        assert_eq!(validate_manifest(&manifest(), &installed()), Ok(()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_permission() {
+        let mut manifest = manifest();
+        manifest.permissions = vec!["storage".to_string(), "filesystem".to_string()];
+        manifest.entry_points[0]
+            .permissions
+            .push("tools".to_string());
+
+        let errors = validate_manifest(&manifest, &installed()).unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            &errors[0],
+            ManifestError::UnknownPermission { permission, .. } if permission == "filesystem"
+        ));
+        assert!(errors[0].to_string().contains("clipboard-read"));
+        assert_eq!(
+            errors[1],
+            ManifestError::UndeclaredNetworkAccess("api.example.com".to_string())
+        );
+        assert_eq!(
+            errors[2],
+            ManifestError::UndeclaredPermission {
+                entry_point: "main".to_string(),
+                permission: "tools".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_missing_and_incompatible_dependencies() {
+        let mut manifest = manifest();
+        manifest
+            .dependencies
+            .insert("auth".to_string(), "~1.0.0".to_string());
+
+        let mut installed = installed();
+        installed.insert("charts".to_string(), "3.0.0".to_string());
+
+        let errors = validate_manifest(&manifest, &installed).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                ManifestError::MissingDependency {
+                    name: "auth".to_string(),
+                    range: "~1.0.0".to_string(),
+                },
+                ManifestError::IncompatibleDependency {
+                    name: "charts".to_string(),
+                    range: "^2.1.0".to_string(),
+                    installed: "3.0.0".to_string(),
+                },
+            ]
+        );
+    }
+}
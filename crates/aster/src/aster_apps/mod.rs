@@ -4,6 +4,12 @@
 //! which are UI resources that can be rendered in an MCP server or native
 //! aster apps, or something in between.
 
+pub mod install;
+pub mod manifest;
 pub mod resource;
+pub mod runtime;
 
+pub use install::{install_app, installed_apps, read_manifest, AppInstallError, MANIFEST_FILE};
+pub use manifest::{validate_manifest, AppEntryPoint, AppManifest, AppPermission, ManifestError};
 pub use resource::{CspMetadata, McpAppResource, ResourceMetadata, UiMetadata};
+pub use runtime::{AppRunError, AppRunOutput, AppRuntime, APP_DATA_DIR};
@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::manifest::{AppManifest, AppPermission};
+use crate::sandbox::{
+    detect_best_sandbox, process_tree_usage, spawn_in_sandbox_strict, ResourceLimitError,
+    ResourceLimiter, ResourceLimits, ResourceUsage, SandboxConfig, SandboxType,
+};
+
+/// Name of the directory inside the app directory that apps with the
+/// `storage` permission may write to
+pub const APP_DATA_DIR: &str = "data";
+
+/// Output of an app that ran to completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRunOutput {
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    pub sandbox_type: SandboxType,
+    /// Highest resource usage observed while the app was running
+    pub peak_usage: ResourceUsage,
+    pub duration_ms: u64,
+}
+
+/// A reason an app run failed
+#[derive(Debug, Error)]
+pub enum AppRunError {
+    #[error("app `{app}` cannot run: no sandbox is available on this host")]
+    NoSandbox { app: String },
+
+    #[error("app `{app}` could not be started: {message}")]
+    Spawn { app: String, message: String },
+
+    #[error("app `{app}` was terminated: {reason}")]
+    LimitExceeded {
+        app: String,
+        reason: ResourceLimitError,
+    },
+
+    #[error("app `{app}` failed while running: {message}")]
+    Io { app: String, message: String },
+}
+
+/// Runs apps inside the sandbox with per-app resource limits
+///
+/// Filesystem and network access are derived from the permissions declared in
+/// the app's manifest. Apps never run outside a sandbox: without a usable one
+/// `run` fails. Resource usage of the app and all of its child processes is
+/// polled while it runs; the app's whole process group is killed as soon as a
+/// limit is exceeded.
+pub struct AppRuntime {
+    sandbox_type: SandboxType,
+    limits: ResourceLimits,
+    poll_interval: Duration,
+}
+
+impl AppRuntime {
+    /// Create a runtime using the best sandbox available on this host
+    pub fn new(limits: ResourceLimits) -> Self {
+        Self {
+            sandbox_type: detect_best_sandbox(),
+            limits,
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+
+    pub fn with_sandbox_type(mut self, sandbox_type: SandboxType) -> Self {
+        self.sandbox_type = sandbox_type;
+        self
+    }
+
+    /// How often resource usage is checked
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Build the sandbox policy for an app
+    ///
+    /// The app directory is always readable. Network access requires the
+    /// `network` permission and only apps with the `storage` permission get a
+    /// writable data directory; nothing else on the host is writable.
+    pub fn sandbox_config(&self, manifest: &AppManifest, app_dir: &Path) -> SandboxConfig {
+        let permissions: HashSet<AppPermission> = manifest
+            .permissions
+            .iter()
+            .filter_map(|p| AppPermission::parse(p))
+            .collect();
+
+        let mut config = SandboxConfig {
+            enabled: true,
+            sandbox_type: self.sandbox_type,
+            allowed_paths: vec![app_dir.to_path_buf()],
+            network_access: permissions.contains(&AppPermission::Network),
+            writable_paths: Vec::new(),
+            resource_limits: Some(self.limits.clone()),
+            ..Default::default()
+        };
+        config.read_only_paths.push(app_dir.to_path_buf());
+        if permissions.contains(&AppPermission::Storage) {
+            config.writable_paths.push(app_dir.join(APP_DATA_DIR));
+        }
+        config
+    }
+
+    /// Run an app command until it exits or violates its limits
+    pub async fn run(
+        &self,
+        manifest: &AppManifest,
+        app_dir: &Path,
+        command: &str,
+        args: &[String],
+    ) -> Result<AppRunOutput, AppRunError> {
+        let app = manifest.name.clone();
+        if self.sandbox_type == SandboxType::None {
+            return Err(AppRunError::NoSandbox { app });
+        }
+        let config = self.sandbox_config(manifest, app_dir);
+        for path in &config.writable_paths {
+            std::fs::create_dir_all(path).map_err(|e| AppRunError::Spawn {
+                app: app.clone(),
+                message: format!("cannot create {}: {}", path.display(), e),
+            })?;
+        }
+
+        let (mut child, sandbox_type) =
+            spawn_in_sandbox_strict(command, args, &config).map_err(|e| AppRunError::Spawn {
+                app: app.clone(),
+                message: e.to_string(),
+            })?;
+        let pid = child.id();
+        let stdout = tokio::spawn(read_pipe(child.stdout.take()));
+        let stderr = tokio::spawn(read_pipe(child.stderr.take()));
+
+        let start = Instant::now();
+        let mut limiter = ResourceLimiter::from_limits(&self.limits);
+        limiter.start();
+        let mut peak = ResourceUsage::default();
+        let mut interval = tokio::time::interval(self.poll_interval);
+
+        let exited = wait_for_exit(pid);
+        tokio::pin!(exited);
+        loop {
+            tokio::select! {
+                _ = &mut exited => break,
+                _ = interval.tick() => {
+                    let mut usage = tokio::task::spawn_blocking(move || pid.and_then(process_tree_usage))
+                        .await
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default();
+                    usage.execution_time_ms = start.elapsed().as_millis() as u64;
+                    peak.memory_bytes = peak.memory_bytes.max(usage.memory_bytes);
+                    peak.process_count = peak.process_count.max(usage.process_count);
+                    peak.file_descriptors = peak.file_descriptors.max(usage.file_descriptors);
+
+                    if let Err(reason) = limiter.check_limits(&usage) {
+                        tracing::warn!("Terminating app {}: {}", app, reason);
+                        kill_process_group(pid);
+                        let _ = child.kill().await;
+                        stdout.abort();
+                        stderr.abort();
+                        return Err(AppRunError::LimitExceeded { app, reason });
+                    }
+                }
+            }
+        }
+        // Background processes the app left behind; the app is not reaped yet,
+        // so its process group id cannot have been reused
+        kill_process_group(pid);
+        let status = child.wait().await.map_err(|e| AppRunError::Io {
+            app: app.clone(),
+            message: e.to_string(),
+        })?;
+
+        peak.execution_time_ms = start.elapsed().as_millis() as u64;
+        Ok(AppRunOutput {
+            exit_code: status.code().unwrap_or(1),
+            stdout: stdout.await.unwrap_or_default(),
+            stderr: stderr.await.unwrap_or_default(),
+            sandbox_type,
+            peak_usage: peak,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+/// Wait until the app exits without reaping it
+///
+/// An exited but unreaped process keeps its pid, and with it the id of the
+/// process group it leads, so the group can still be signalled safely until
+/// `Child::wait` reaps it.
+#[cfg(unix)]
+async fn wait_for_exit(pid: Option<u32>) {
+    let Some(pid) = pid else {
+        return;
+    };
+    let _ = tokio::task::spawn_blocking(move || loop {
+        // SAFETY: siginfo_t is plain data and waitid only writes into it
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let flags = libc::WEXITED | libc::WNOWAIT;
+        let rc = unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, flags) };
+        if rc == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::EINTR) {
+            break;
+        }
+    })
+    .await;
+}
+
+/// Strict sandboxes only exist on unix, so there is no app to wait for here
+#[cfg(not(unix))]
+async fn wait_for_exit(_pid: Option<u32>) {}
+
+/// Kill every process in the app's process group
+///
+/// The app leads its own process group (see `spawn_in_sandbox_strict`). Call
+/// this before the app is reaped: the group id is only guaranteed not to be
+/// reused while the leader or another member still exists.
+fn kill_process_group(pid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        // SAFETY: kill has no memory-safety preconditions
+        unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) };
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+async fn read_pipe(pipe: Option<impl AsyncRead + Unpin>) -> String {
+    let mut buf = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut buf).await;
+    }
+    String::from_utf8_lossy(&buf).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn manifest(permissions: &[&str]) -> AppManifest {
+        AppManifest {
+            name: "hungry".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            entry_points: Vec::new(),
+            dependencies: BTreeMap::new(),
+            csp: None,
+        }
+    }
+
+    #[test]
+    fn test_sandbox_config_follows_permissions() {
+        let runtime =
+            AppRuntime::new(ResourceLimits::default()).with_sandbox_type(SandboxType::Bubblewrap);
+        let app_dir = Path::new("/opt/apps/hungry");
+
+        let config = runtime.sandbox_config(&manifest(&[]), app_dir);
+        assert!(!config.network_access);
+        assert!(config.writable_paths.is_empty());
+        assert!(config.read_only_paths.contains(&app_dir.to_path_buf()));
+
+        let config = runtime.sandbox_config(&manifest(&["network", "storage"]), app_dir);
+        assert!(config.network_access);
+        assert_eq!(config.writable_paths, vec![app_dir.join(APP_DATA_DIR)]);
+    }
+
+    #[tokio::test]
+    async fn test_app_without_sandbox_is_refused() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let runtime =
+            AppRuntime::new(ResourceLimits::default()).with_sandbox_type(SandboxType::None);
+
+        let err = runtime
+            .run(&manifest(&[]), app_dir.path(), "true", &[])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppRunError::NoSandbox { .. }), "{err}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_app_exceeding_memory_limit_is_killed() {
+        if !crate::sandbox::get_sandbox_capabilities().bubblewrap {
+            eprintln!("skipping: bubblewrap is not usable");
+            return;
+        }
+        let app_dir = tempfile::tempdir().unwrap();
+        let limits = ResourceLimits {
+            max_memory: Some(32 * 1024 * 1024),
+            // Safety net so the test cannot hang
+            max_execution_time: Some(20_000),
+            ..Default::default()
+        };
+        let runtime = AppRuntime::new(limits)
+            .with_sandbox_type(SandboxType::Bubblewrap)
+            .with_poll_interval(Duration::from_millis(10));
+
+        // Doubles a string every 50ms until it is killed
+        let script = "x=0123456789abcdef; while :; do x=\"$x$x\"; sleep 0.05; done";
+        let err = runtime
+            .run(
+                &manifest(&[]),
+                app_dir.path(),
+                "sh",
+                &["-c".to_string(), script.to_string()],
+            )
+            .await
+            .unwrap_err();
+
+        match &err {
+            AppRunError::LimitExceeded {
+                app,
+                reason: ResourceLimitError::MemoryExceeded { used, limit },
+            } => {
+                assert_eq!(app, "hungry");
+                assert_eq!(*limit, 32 * 1024 * 1024);
+                assert!(used > limit);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(err.to_string().starts_with("app `hungry` was terminated"));
+    }
+}
@@ -4,6 +4,7 @@
 //!
 //! # 功能
 //! - 任务状态持久化
+//! - 任务结果按保留期持久化
 //! - Agent 状态持久化
 //! - 自动过期清理
 //! - 导入/导出功能
@@ -12,8 +13,10 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use tokio::fs;
 
+use super::task_queue::QueuedTask;
 use super::types::{
-    AgentStats, PersistedAgentState, PersistedTaskState, PersistenceStats, TaskStats, TaskType,
+    AgentStats, PersistedAgentState, PersistedTaskResult, PersistedTaskState, PersistenceStats,
+    TaskResultLookup, TaskStats, TaskStatus, TaskType,
 };
 
 /// 持久化配置
@@ -22,6 +25,8 @@ pub struct PersistenceOptions {
     pub storage_dir: PathBuf,
     pub auto_restore: bool,
     pub expiry_time_ms: u64,
+    /// 任务结果的保留时间
+    pub result_ttl_ms: u64,
     pub compress: bool,
 }
 
@@ -32,6 +37,7 @@ impl Default for PersistenceOptions {
             storage_dir: home.join(".aster").join("background-tasks"),
             auto_restore: true,
             expiry_time_ms: 86_400_000, // 24 小时
+            result_ttl_ms: 3_600_000,   // 1 小时
             compress: false,
         }
     }
@@ -124,6 +130,122 @@ impl PersistenceManager {
         age > self.options.expiry_time_ms
     }
 
+    /// 获取任务结果文件路径
+    fn get_result_file_path(&self, task_id: &str) -> PathBuf {
+        self.storage_dir
+            .join("results")
+            .join(format!("{}.json", task_id))
+    }
+
+    /// 保存已结束任务的结果，保留 `result_ttl_ms`
+    pub async fn save_result(&self, task: &QueuedTask) -> Result<(), String> {
+        if !matches!(task.status, TaskStatus::Completed | TaskStatus::Failed) {
+            return Err(format!("Task {} has not finished", task.id));
+        }
+
+        let completed_at = task
+            .end_time
+            .unwrap_or_else(chrono::Utc::now)
+            .timestamp_millis();
+        let record = PersistedTaskResult {
+            task_id: task.id.clone(),
+            status: task.status,
+            result: task.result.clone(),
+            error: task.error.clone(),
+            completed_at,
+            expires_at: completed_at + self.options.result_ttl_ms as i64,
+        };
+        self.write_result(&record).await
+    }
+
+    async fn write_result(&self, record: &PersistedTaskResult) -> Result<(), String> {
+        let file_path = self.get_result_file_path(&record.task_id);
+        if let Some(dir) = file_path.parent() {
+            fs::create_dir_all(dir)
+                .await
+                .map_err(|e| format!("Failed to create result directory: {}", e))?;
+        }
+
+        let data = serde_json::to_string_pretty(record)
+            .map_err(|e| format!("Failed to serialize task result: {}", e))?;
+        fs::write(&file_path, data)
+            .await
+            .map_err(|e| format!("Failed to write task result file: {}", e))
+    }
+
+    /// 按任务 ID 获取结果
+    pub async fn get_result(&self, task_id: &str) -> TaskResultLookup {
+        self.get_result_at(task_id, chrono::Utc::now().timestamp_millis())
+            .await
+    }
+
+    async fn get_result_at(&self, task_id: &str, now: i64) -> TaskResultLookup {
+        let file_path = self.get_result_file_path(task_id);
+        let Ok(data) = fs::read_to_string(&file_path).await else {
+            return TaskResultLookup::Unknown;
+        };
+        let Ok(record) = serde_json::from_str::<PersistedTaskResult>(&data) else {
+            return TaskResultLookup::Unknown;
+        };
+
+        if now < record.expires_at {
+            return TaskResultLookup::Available(record);
+        }
+
+        let expired_at = record.expires_at;
+        self.collect_result(record, now).await;
+        TaskResultLookup::Expired { expired_at }
+    }
+
+    /// 回收过期结果：先丢弃结果内容，再过 `expiry_time_ms` 后删除记录
+    ///
+    /// 返回是否有改动
+    async fn collect_result(&self, mut record: PersistedTaskResult, now: i64) -> bool {
+        if now < record.expires_at {
+            return false;
+        }
+
+        if now - record.expires_at > self.options.expiry_time_ms as i64 {
+            return fs::remove_file(self.get_result_file_path(&record.task_id))
+                .await
+                .is_ok();
+        }
+
+        if record.result.is_none() && record.error.is_none() {
+            return false;
+        }
+        record.result = None;
+        record.error = None;
+        self.write_result(&record).await.is_ok()
+    }
+
+    /// 回收所有过期的任务结果
+    pub async fn cleanup_expired_results(&self) -> usize {
+        self.cleanup_expired_results_at(chrono::Utc::now().timestamp_millis())
+            .await
+    }
+
+    async fn cleanup_expired_results_at(&self, now: i64) -> usize {
+        let mut entries = match fs::read_dir(self.storage_dir.join("results")).await {
+            Ok(e) => e,
+            Err(_) => return 0,
+        };
+
+        let mut cleaned = 0;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(data) = fs::read_to_string(entry.path()).await else {
+                continue;
+            };
+            if let Ok(record) = serde_json::from_str::<PersistedTaskResult>(&data) {
+                if self.collect_result(record, now).await {
+                    cleaned += 1;
+                }
+            }
+        }
+
+        cleaned
+    }
+
     /// 保存 Agent 状态
     pub async fn save_agent(&self, agent: &PersistedAgentState) -> Result<(), String> {
         let agent_dir = self
@@ -239,7 +361,7 @@ impl PersistenceManager {
         agents
     }
 
-    /// 清理过期的任务
+    /// 清理过期的任务和任务结果
     pub async fn cleanup_expired(&self) -> usize {
         let tasks = self.list_tasks(None).await;
         let mut cleaned = 0;
@@ -250,7 +372,7 @@ impl PersistenceManager {
             }
         }
 
-        cleaned
+        cleaned + self.cleanup_expired_results().await
     }
 
     /// 清理已完成的任务
@@ -269,11 +391,16 @@ impl PersistenceManager {
         cleaned
     }
 
-    /// 清除所有任务
+    /// 清除所有任务和任务结果
     pub async fn clear_all(&self) -> usize {
+        Self::remove_json_files(&self.storage_dir).await
+            + Self::remove_json_files(&self.storage_dir.join("results")).await
+    }
+
+    async fn remove_json_files(dir: &std::path::Path) -> usize {
         let mut cleared = 0;
 
-        let mut entries = match fs::read_dir(&self.storage_dir).await {
+        let mut entries = match fs::read_dir(dir).await {
             Ok(e) => e,
             Err(_) => return cleared,
         };
@@ -318,3 +445,108 @@ impl PersistenceManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::{SimpleTaskQueue, TaskPriority, TaskQueueOptions};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_task_result_expires_after_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = PersistenceOptions {
+            storage_dir: dir.path().to_path_buf(),
+            result_ttl_ms: 60_000,
+            ..Default::default()
+        };
+        let manager = PersistenceManager::new(options.clone()).await.unwrap();
+
+        let mut task = QueuedTask::new(
+            "index-1",
+            TaskPriority::Low,
+            Box::new(|| {
+                Box::pin(async { Ok(serde_json::Value::Null) })
+                    as Pin<Box<dyn Future<Output = Result<serde_json::Value, String>> + Send>>
+            }),
+        );
+        assert!(manager.save_result(&task).await.is_err());
+
+        task.status = TaskStatus::Completed;
+        task.result = Some(serde_json::json!({ "files": 3 }));
+        task.end_time = Some(chrono::Utc::now());
+        manager.save_result(&task).await.unwrap();
+
+        // 结果在重启后仍然可以取到
+        let restarted = PersistenceManager::new(options).await.unwrap();
+        let TaskResultLookup::Available(record) = restarted.get_result("index-1").await else {
+            panic!("result should be available");
+        };
+        assert_eq!(record.result, Some(serde_json::json!({ "files": 3 })));
+        assert_eq!(
+            restarted.get_result("unknown").await,
+            TaskResultLookup::Unknown
+        );
+
+        let expired = TaskResultLookup::Expired {
+            expired_at: record.expires_at,
+        };
+        let after_ttl = record.expires_at + 1;
+        assert_eq!(restarted.get_result_at("index-1", after_ttl).await, expired);
+        // 过期后结果内容已被丢弃，只保留记录
+        let data = std::fs::read_to_string(restarted.get_result_file_path("index-1")).unwrap();
+        assert!(!data.contains("files"));
+        assert_eq!(restarted.get_result_at("index-1", after_ttl).await, expired);
+
+        let much_later = record.expires_at + restarted.options.expiry_time_ms as i64 + 1;
+        assert_eq!(restarted.cleanup_expired_results_at(much_later).await, 1);
+        assert_eq!(
+            restarted.get_result("index-1").await,
+            TaskResultLookup::Unknown
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queue_persists_results_and_clear_all_removes_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = PersistenceOptions {
+            storage_dir: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let manager = Arc::new(PersistenceManager::new(options).await.unwrap());
+
+        let mut queue = SimpleTaskQueue::new(TaskQueueOptions::default());
+        queue.set_persistence(Arc::clone(&manager));
+        queue
+            .enqueue(QueuedTask::new(
+                "report-1",
+                TaskPriority::Normal,
+                Box::new(|| {
+                    Box::pin(async { Ok(serde_json::json!({ "pages": 2 })) })
+                        as Pin<Box<dyn Future<Output = Result<serde_json::Value, String>> + Send>>
+                }),
+            ))
+            .await;
+
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while queue.get_status().await.completed < 1 {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("task completes");
+
+        let TaskResultLookup::Available(record) = manager.get_result("report-1").await else {
+            panic!("queue should persist the result");
+        };
+        assert_eq!(record.result, Some(serde_json::json!({ "pages": 2 })));
+
+        assert_eq!(manager.clear_all().await, 1);
+        assert_eq!(
+            manager.get_result("report-1").await,
+            TaskResultLookup::Unknown
+        );
+    }
+}
@@ -4,9 +4,11 @@
 //!
 //! # 功能
 //! - FIFO 队列
-//! - 优先级支持 (high/normal/low)
+//! - 优先级调度 (high/normal/low)，新的高优先级任务插队但不中断运行中的任务
+//! - 老化：等待中被后来的任务超过若干次后提升优先级，避免低优先级任务饿死
 //! - 并发控制
 //! - 状态管理
+//! - 结束任务的结果可交给 `PersistenceManager` 按保留期持久化
 
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -15,6 +17,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
+use super::persistence::PersistenceManager;
 use super::types::{QueueStatus, TaskPriority, TaskStatus, TaskType};
 
 /// 任务执行函数类型
@@ -37,17 +40,62 @@ pub struct QueuedTask {
     pub status: TaskStatus,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// 排队期间被后入队的任务抢先调度的次数（用于老化）
+    pub passed_over: usize,
+}
+
+impl QueuedTask {
+    /// 创建待入队的任务
+    pub fn new(id: impl Into<String>, priority: TaskPriority, execute: TaskExecutor) -> Self {
+        Self {
+            id: id.into(),
+            task_type: TaskType::default(),
+            priority,
+            execute: Some(execute),
+            enqueue_time: Utc::now(),
+            start_time: None,
+            end_time: None,
+            metadata: None,
+            status: TaskStatus::Pending,
+            result: None,
+            error: None,
+            passed_over: 0,
+        }
+    }
+
+    /// 不含执行器的任务副本（用于在释放锁之后触发回调）
+    fn snapshot(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            task_type: self.task_type,
+            priority: self.priority,
+            execute: None,
+            enqueue_time: self.enqueue_time,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            metadata: self.metadata.clone(),
+            status: self.status,
+            result: self.result.clone(),
+            error: self.error.clone(),
+            passed_over: self.passed_over,
+        }
+    }
 }
 
 /// 任务队列配置
 #[derive(Debug, Clone)]
 pub struct TaskQueueOptions {
     pub max_concurrent: usize,
+    /// 任务每被抢先调度这么多次，优先级提升一级（0 表示不老化）
+    pub aging_threshold: usize,
 }
 
 impl Default for TaskQueueOptions {
     fn default() -> Self {
-        Self { max_concurrent: 10 }
+        Self {
+            max_concurrent: 10,
+            aging_threshold: 8,
+        }
     }
 }
 
@@ -61,9 +109,130 @@ pub struct SimpleTaskQueue {
     completed: Arc<RwLock<HashMap<String, QueuedTask>>>,
     failed: Arc<RwLock<HashMap<String, QueuedTask>>>,
     max_concurrent: usize,
+    aging_threshold: usize,
     on_task_start: Option<TaskCallback>,
     on_task_complete: Option<TaskCallback>,
     on_task_failed: Option<TaskCallback>,
+    persistence: Option<Arc<PersistenceManager>>,
+}
+
+/// 调度器：持有队列共享状态，供运行任务的工作协程使用
+#[derive(Clone)]
+struct Dispatcher {
+    queue: Arc<Mutex<Vec<QueuedTask>>>,
+    running: Arc<RwLock<HashMap<String, QueuedTask>>>,
+    completed: Arc<RwLock<HashMap<String, QueuedTask>>>,
+    failed: Arc<RwLock<HashMap<String, QueuedTask>>>,
+    max_concurrent: usize,
+    aging_threshold: usize,
+    on_task_start: Option<TaskCallback>,
+    on_task_complete: Option<TaskCallback>,
+    on_task_failed: Option<TaskCallback>,
+    persistence: Option<Arc<PersistenceManager>>,
+}
+
+impl Dispatcher {
+    /// 老化后的优先级排序值（越小越优先）
+    fn effective_order(&self, task: &QueuedTask) -> u8 {
+        if self.aging_threshold == 0 {
+            return task.priority.order();
+        }
+        let boost = (task.passed_over / self.aging_threshold).min(u8::MAX as usize) as u8;
+        task.priority.order().saturating_sub(boost)
+    }
+
+    /// 有空闲槽位时取出优先级最高的任务并标记为运行中
+    ///
+    /// 同一优先级按入队顺序调度；被抢先的更早入队的任务累计老化次数。
+    /// 开始回调在释放队列锁之后触发，回调中可以再访问队列。
+    async fn start_next(&self) -> Option<(String, Option<TaskExecutor>)> {
+        let mut queue = self.queue.lock().await;
+        let mut running = self.running.write().await;
+        if running.len() >= self.max_concurrent {
+            return None;
+        }
+
+        let index = queue
+            .iter()
+            .enumerate()
+            .min_by_key(|(i, task)| (self.effective_order(task), *i))
+            .map(|(i, _)| i)?;
+        let mut task = queue.remove(index);
+        for skipped in queue.iter_mut().take(index) {
+            skipped.passed_over += 1;
+        }
+
+        // 更新任务状态
+        task.status = TaskStatus::Running;
+        task.start_time = Some(Utc::now());
+        let task_id = task.id.clone();
+        let started = self.on_task_start.as_ref().map(|_| task.snapshot());
+
+        // 取出执行器
+        let executor = task.execute.take();
+        running.insert(task_id.clone(), task);
+        drop(running);
+        drop(queue);
+
+        // 触发回调
+        if let (Some(callback), Some(task)) = (&self.on_task_start, started) {
+            callback(&task);
+        }
+        Some((task_id, executor))
+    }
+
+    /// 记录任务结果
+    async fn finish(&self, task_id: &str, result: Result<serde_json::Value, String>) {
+        let Some(mut task) = self.running.write().await.remove(task_id) else {
+            return;
+        };
+        task.end_time = Some(Utc::now());
+
+        match result {
+            Ok(value) => {
+                task.result = Some(value);
+                task.status = TaskStatus::Completed;
+                if let Some(ref cb) = self.on_task_complete {
+                    cb(&task);
+                }
+                self.save_result(&task).await;
+                self.completed
+                    .write()
+                    .await
+                    .insert(task_id.to_string(), task);
+            }
+            Err(e) => {
+                task.error = Some(e);
+                task.status = TaskStatus::Failed;
+                if let Some(ref cb) = self.on_task_failed {
+                    cb(&task);
+                }
+                self.save_result(&task).await;
+                self.failed.write().await.insert(task_id.to_string(), task);
+            }
+        }
+    }
+
+    /// 持久化已结束任务的结果；失败只记录日志，不影响任务状态
+    async fn save_result(&self, task: &QueuedTask) {
+        if let Some(ref persistence) = self.persistence {
+            if let Err(e) = persistence.save_result(task).await {
+                tracing::warn!("Failed to persist result of task {}: {}", task.id, e);
+            }
+        }
+    }
+
+    /// 执行任务，完成后继续调度队列中的下一个任务，直到没有可调度的任务
+    async fn run_worker(self, first: (String, Option<TaskExecutor>)) {
+        let mut next = Some(first);
+        while let Some((task_id, executor)) = next {
+            if let Some(exec) = executor {
+                let result = exec().await;
+                self.finish(&task_id, result).await;
+            }
+            next = self.start_next().await;
+        }
+    }
 }
 
 impl SimpleTaskQueue {
@@ -75,9 +244,11 @@ impl SimpleTaskQueue {
             completed: Arc::new(RwLock::new(HashMap::new())),
             failed: Arc::new(RwLock::new(HashMap::new())),
             max_concurrent: options.max_concurrent,
+            aging_threshold: options.aging_threshold,
             on_task_start: None,
             on_task_complete: None,
             on_task_failed: None,
+            persistence: None,
         }
     }
 
@@ -96,22 +267,20 @@ impl SimpleTaskQueue {
         self.on_task_failed = Some(callback);
     }
 
+    /// 设置结果持久化，任务结束时保存其结果
+    pub fn set_persistence(&mut self, persistence: Arc<PersistenceManager>) {
+        self.persistence = Some(persistence);
+    }
+
     /// 添加任务到队列
     pub async fn enqueue(&self, mut task: QueuedTask) -> String {
         task.status = TaskStatus::Pending;
         task.enqueue_time = Utc::now();
+        task.passed_over = 0;
         let task_id = task.id.clone();
 
-        let mut queue = self.queue.lock().await;
-
-        // 按优先级插入
-        let insert_index = queue
-            .iter()
-            .position(|t| t.priority.order() > task.priority.order())
-            .unwrap_or(queue.len());
-
-        queue.insert(insert_index, task);
-        drop(queue);
+        // 队列按入队顺序保存，调度时再按优先级选择
+        self.queue.lock().await.push(task);
 
         // 尝试处理下一个任务
         self.process_next().await;
@@ -119,69 +288,26 @@ impl SimpleTaskQueue {
         task_id
     }
 
-    /// 处理队列中的下一个任务
-    async fn process_next(&self) {
-        let running_count = self.running.read().await.len();
-        if running_count >= self.max_concurrent {
-            return;
-        }
-
-        let mut queue = self.queue.lock().await;
-        if queue.is_empty() {
-            return;
-        }
-
-        let mut task = queue.remove(0);
-        drop(queue);
-
-        // 更新任务状态
-        task.status = TaskStatus::Running;
-        task.start_time = Some(Utc::now());
-        let task_id = task.id.clone();
-
-        // 触发回调
-        if let Some(ref callback) = self.on_task_start {
-            callback(&task);
+    fn dispatcher(&self) -> Dispatcher {
+        Dispatcher {
+            queue: Arc::clone(&self.queue),
+            running: Arc::clone(&self.running),
+            completed: Arc::clone(&self.completed),
+            failed: Arc::clone(&self.failed),
+            max_concurrent: self.max_concurrent,
+            aging_threshold: self.aging_threshold,
+            on_task_start: self.on_task_start.clone(),
+            on_task_complete: self.on_task_complete.clone(),
+            on_task_failed: self.on_task_failed.clone(),
+            persistence: self.persistence.clone(),
         }
+    }
 
-        // 取出执行器
-        let executor = task.execute.take();
-        self.running.write().await.insert(task_id.clone(), task);
-
-        // 执行任务
-        if let Some(exec) = executor {
-            let running = Arc::clone(&self.running);
-            let completed = Arc::clone(&self.completed);
-            let failed = Arc::clone(&self.failed);
-            let on_complete = self.on_task_complete.clone();
-            let on_failed = self.on_task_failed.clone();
-
-            tokio::spawn(async move {
-                let result = exec().await;
-
-                if let Some(mut task) = running.write().await.remove(&task_id) {
-                    task.end_time = Some(Utc::now());
-
-                    match result {
-                        Ok(value) => {
-                            task.result = Some(value);
-                            task.status = TaskStatus::Completed;
-                            if let Some(cb) = on_complete {
-                                cb(&task);
-                            }
-                            completed.write().await.insert(task_id, task);
-                        }
-                        Err(e) => {
-                            task.error = Some(e);
-                            task.status = TaskStatus::Failed;
-                            if let Some(cb) = on_failed {
-                                cb(&task);
-                            }
-                            failed.write().await.insert(task_id, task);
-                        }
-                    }
-                }
-            });
+    /// 处理队列中的下一个任务
+    async fn process_next(&self) {
+        let dispatcher = self.dispatcher();
+        if let Some(first) = dispatcher.start_next().await {
+            tokio::spawn(dispatcher.run_worker(first));
         }
     }
 
@@ -272,3 +398,111 @@ impl SimpleTaskQueue {
         counts
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    fn task(id: &str, priority: TaskPriority, gate: Option<Arc<Notify>>) -> QueuedTask {
+        QueuedTask::new(
+            id,
+            priority,
+            Box::new(move || {
+                Box::pin(async move {
+                    if let Some(gate) = gate {
+                        gate.notified().await;
+                    }
+                    Ok(serde_json::Value::Null)
+                })
+                    as Pin<Box<dyn Future<Output = Result<serde_json::Value, String>> + Send>>
+            }),
+        )
+    }
+
+    /// 先用一个阻塞任务占住唯一的槽位，入队其余任务后放行，返回调度顺序
+    async fn dispatch_order(aging_threshold: usize, tasks: &[(&str, TaskPriority)]) -> Vec<String> {
+        let mut queue = SimpleTaskQueue::new(TaskQueueOptions {
+            max_concurrent: 1,
+            aging_threshold,
+        });
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&started);
+        queue.set_on_task_start(Arc::new(move |task: &QueuedTask| {
+            recorder.lock().unwrap().push(task.id.clone());
+        }));
+
+        let gate = Arc::new(Notify::new());
+        queue
+            .enqueue(task("blocker", TaskPriority::Low, Some(Arc::clone(&gate))))
+            .await;
+        for (id, priority) in tasks {
+            queue.enqueue(task(id, *priority, None)).await;
+        }
+        gate.notify_one();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while queue.get_status().await.completed < tasks.len() + 1 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("all tasks complete");
+
+        let order = started.lock().unwrap();
+        order.clone()
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_in_priority_order_with_aging() {
+        use TaskPriority::*;
+
+        let order = dispatch_order(
+            100,
+            &[
+                ("l1", Low),
+                ("n1", Normal),
+                ("h1", High),
+                ("l2", Low),
+                ("h2", High),
+            ],
+        )
+        .await;
+        assert_eq!(order, ["blocker", "h1", "h2", "n1", "l1", "l2"]);
+
+        // 每被抢先两次提升一级：l1 被 h1..h4 抢先后与 high 同级，且入队更早
+        let order = dispatch_order(
+            2,
+            &[
+                ("l1", Low),
+                ("h1", High),
+                ("h2", High),
+                ("h3", High),
+                ("h4", High),
+                ("h5", High),
+                ("h6", High),
+            ],
+        )
+        .await;
+        assert_eq!(order, ["blocker", "h1", "h2", "h3", "h4", "l1", "h5", "h6"]);
+    }
+
+    #[tokio::test]
+    async fn test_start_callback_runs_without_queue_locks() {
+        let mut queue = SimpleTaskQueue::new(TaskQueueOptions::default());
+        let pending = Arc::clone(&queue.queue);
+        let running = Arc::clone(&queue.running);
+        let unlocked = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&unlocked);
+        queue.set_on_task_start(Arc::new(move |task: &QueuedTask| {
+            assert_eq!(task.status, TaskStatus::Running);
+            let free = pending.try_lock().is_ok() && running.try_write().is_ok();
+            recorder.lock().unwrap().push(free);
+        }));
+
+        queue.enqueue(task("t1", TaskPriority::Normal, None)).await;
+
+        assert_eq!(*unlocked.lock().unwrap(), vec![true]);
+    }
+}
@@ -141,6 +141,30 @@ pub struct PersistedTaskState {
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// 持久化的任务结果
+///
+/// 结果保留到 `expires_at`，之后只保留不含结果的记录以便区分“已过期”和“未知”。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedTaskResult {
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub completed_at: i64,
+    pub expires_at: i64,
+}
+
+/// 按任务 ID 查询结果的返回值
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskResultLookup {
+    /// 结果仍在保留期内
+    Available(PersistedTaskResult),
+    /// 结果已超过保留期被回收
+    Expired { expired_at: i64 },
+    /// 没有该任务的结果
+    Unknown,
+}
+
 /// 持久化的 Agent 状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistedAgentState {
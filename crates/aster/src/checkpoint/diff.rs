@@ -33,9 +33,11 @@ impl DiffEngine {
     }
 
     /// 计算两个字符串之间的 diff
+    ///
+    /// 每行保留自身的行尾（`\n` 或 `\r\n`），应用 diff 后可逐字节还原新内容
     pub fn calculate_diff(&self, old_content: &str, new_content: &str) -> String {
-        let old_lines: Vec<&str> = old_content.lines().collect();
-        let new_lines: Vec<&str> = new_content.lines().collect();
+        let old_lines: Vec<&str> = old_content.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new_content.split_inclusive('\n').collect();
 
         let lcs = self.longest_common_subsequence(&old_lines, &new_lines);
         let mut diff: Vec<DiffEntry> = Vec::new();
@@ -117,7 +119,15 @@ impl DiffEngine {
             }
         }
 
-        result.join("\n")
+        // 旧格式的 diff 不保留行尾，只能按换行符拼接
+        let legacy = result
+            .split_last()
+            .is_some_and(|(_, init)| init.iter().any(|line| !line.ends_with('\n')));
+        if legacy {
+            result.join("\n")
+        } else {
+            result.concat()
+        }
     }
 
     /// 最长公共子序列算法
@@ -76,6 +76,15 @@ impl CheckpointManager {
         }
     }
 
+    /// 使用指定存储创建检查点管理器
+    pub fn with_storage(storage: CheckpointStorage) -> Self {
+        Self {
+            session: Arc::new(RwLock::new(None)),
+            storage,
+            diff_engine: DiffEngine::new(),
+        }
+    }
+
     /// 初始化检查点系统
     pub async fn init(
         &self,
@@ -183,6 +192,7 @@ impl CheckpointManager {
             compressed: Some(compressed),
             metadata,
             tags: opts.tags,
+            pinned: opts.pinned,
         };
 
         // 添加到会话
@@ -479,6 +489,7 @@ impl CheckpointManager {
                 .enumerate()
                 .map(|(idx, cp)| CheckpointHistoryItem {
                     index: idx,
+                    id: self.storage.checkpoint_id(cp),
                     timestamp: cp.timestamp,
                     hash: cp.hash.clone(),
                     name: cp.name.clone(),
@@ -541,6 +552,66 @@ impl CheckpointManager {
         }
     }
 
+    /// 固定或取消固定检查点
+    pub async fn set_checkpoint_pinned(
+        &self,
+        file_path: &str,
+        index: usize,
+        pinned: bool,
+    ) -> CheckpointResult {
+        let mut session_guard = self.session.write().await;
+        let session = match session_guard.as_mut() {
+            Some(s) => s,
+            None => return CheckpointResult::err("No active checkpoint session"),
+        };
+
+        let absolute_path = std::path::Path::new(file_path)
+            .canonicalize()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| file_path.to_string());
+
+        let checkpoint = match session
+            .checkpoints
+            .get_mut(&absolute_path)
+            .and_then(|c| c.get_mut(index))
+        {
+            Some(c) => c,
+            None => return CheckpointResult::err("Invalid checkpoint index"),
+        };
+        checkpoint.pinned = Some(pinned);
+
+        if let Err(e) = self.storage.save_checkpoint(&session.id, checkpoint).await {
+            return CheckpointResult::err(e);
+        }
+
+        CheckpointResult::ok(if pinned {
+            "Checkpoint pinned"
+        } else {
+            "Checkpoint unpinned"
+        })
+    }
+
+    /// 按保留策略回收当前会话的检查点
+    pub async fn collect_garbage(
+        &self,
+        policy: &CheckpointRetentionPolicy,
+    ) -> Result<CheckpointGcReport, String> {
+        let mut session_guard = self.session.write().await;
+        let session = session_guard
+            .as_mut()
+            .ok_or_else(|| "No active checkpoint session".to_string())?;
+        self.storage.collect_garbage(session, policy).await
+    }
+
+    /// 校验当前会话中的检查点及其增量链
+    pub async fn verify(&self, checkpoint_id: &str) -> Result<CheckpointVerification, String> {
+        let session_id = match self.session.read().await.as_ref() {
+            Some(s) => s.id.clone(),
+            None => return Err("No active checkpoint session".to_string()),
+        };
+        self.storage.verify(&session_id, checkpoint_id).await
+    }
+
     /// 结束会话
     pub async fn end_session(&self) {
         *self.session.write().await = None;
@@ -554,6 +625,7 @@ pub struct CreateCheckpointOptions {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub force_full_content: Option<bool>,
+    pub pinned: Option<bool>,
 }
 
 /// 生成会话 ID
@@ -567,7 +639,7 @@ fn generate_session_id() -> String {
 }
 
 /// 获取内容哈希
-fn get_content_hash(content: &str) -> String {
+pub(crate) fn get_content_hash(content: &str) -> String {
     use sha2::{Digest, Sha256};
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
@@ -2,12 +2,24 @@
 //!
 //! 负责检查点的磁盘存储、加载和清理
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tokio::fs;
 
-use super::session::CheckpointSession;
+use super::diff::DiffEngine;
+use super::session::{get_content_hash, CheckpointSession};
 use super::types::*;
 
+/// 校验增量链时的重建状态
+enum ChainState {
+    /// 尚未遇到完整内容的检查点
+    NoBase,
+    /// 链在该检查点处损坏
+    Broken(String),
+    /// 上一个检查点的完整内容
+    Content(String),
+}
+
 /// 检查点存储
 pub struct CheckpointStorage {
     checkpoint_dir: PathBuf,
@@ -22,6 +34,11 @@ impl CheckpointStorage {
         }
     }
 
+    /// 使用指定目录创建存储管理器
+    pub fn with_dir(checkpoint_dir: PathBuf) -> Self {
+        Self { checkpoint_dir }
+    }
+
     /// 确保检查点目录存在
     pub async fn ensure_checkpoint_dir(&self) -> Result<(), String> {
         if !self.checkpoint_dir.exists() {
@@ -37,6 +54,21 @@ impl CheckpointStorage {
         self.checkpoint_dir.join(session_id)
     }
 
+    /// 获取检查点 ID（`<路径哈希>-<时间戳>`，即检查点文件名）
+    pub fn checkpoint_id(&self, checkpoint: &FileCheckpoint) -> String {
+        format!(
+            "{}-{}",
+            self.get_path_hash(&checkpoint.path),
+            checkpoint.timestamp
+        )
+    }
+
+    /// 获取检查点文件路径
+    fn get_checkpoint_file(&self, session_id: &str, checkpoint: &FileCheckpoint) -> PathBuf {
+        self.get_session_dir(session_id)
+            .join(format!("{}.json", self.checkpoint_id(checkpoint)))
+    }
+
     /// 保存检查点到磁盘
     pub async fn save_checkpoint(
         &self,
@@ -50,9 +82,7 @@ impl CheckpointStorage {
                 .map_err(|e| format!("Failed to create session directory: {}", e))?;
         }
 
-        let file_hash = self.get_path_hash(&checkpoint.path);
-        let checkpoint_file =
-            session_dir.join(format!("{}-{}.json", file_hash, checkpoint.timestamp));
+        let checkpoint_file = self.get_checkpoint_file(session_id, checkpoint);
 
         let data = serde_json::to_string_pretty(checkpoint)
             .map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
@@ -141,6 +171,288 @@ impl CheckpointStorage {
         }
     }
 
+    /// 按保留策略回收会话中的检查点
+    ///
+    /// 固定的检查点和当前恢复点（`current_index`）始终保留。
+    /// 保留的增量检查点若依赖被删除的检查点，会先改写为完整内容，保证仍可恢复。
+    /// 回收在会话副本上进行，改写全部成功后才更新 `session` 并删除文件；
+    /// 出错时 `session` 保持不变。
+    pub async fn collect_garbage(
+        &self,
+        session: &mut CheckpointSession,
+        policy: &CheckpointRetentionPolicy,
+    ) -> Result<CheckpointGcReport, String> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let cutoff = policy
+            .keep_within
+            .map(|d| now.saturating_sub(d.as_millis() as i64));
+        let diff_engine = DiffEngine::new();
+        let mut report = CheckpointGcReport::default();
+        let mut freed: i64 = 0;
+        let mut checkpoints_by_path = session.checkpoints.clone();
+        let mut current_index = session.current_index.clone();
+        let mut removals = Vec::new();
+
+        for (path, checkpoints) in checkpoints_by_path.iter_mut() {
+            let len = checkpoints.len();
+            let current = current_index.get(path).copied();
+            let retain: Vec<bool> = checkpoints
+                .iter()
+                .enumerate()
+                .map(|(i, checkpoint)| {
+                    current == Some(i)
+                        || checkpoint.pinned.unwrap_or(false)
+                        || policy.keep_last.is_some_and(|n| i + n >= len)
+                        || cutoff.is_some_and(|cutoff| checkpoint.timestamp >= cutoff)
+                        || checkpoint.tags.as_ref().is_some_and(|tags| {
+                            tags.iter().any(|tag| policy.keep_tags.contains(tag))
+                        })
+                })
+                .collect();
+
+            if retain.iter().all(|&r| r) {
+                report.retained += len;
+                continue;
+            }
+
+            let mut kept = Vec::new();
+            let mut content: Option<String> = None;
+            for (i, mut checkpoint) in std::mem::take(checkpoints).into_iter().enumerate() {
+                // 按顺序重建每个检查点的完整内容
+                content = match (&checkpoint.content, &checkpoint.diff) {
+                    (Some(c), _) if checkpoint.compressed.unwrap_or(false) => {
+                        Some(self.decompress_content(c))
+                    }
+                    (Some(c), _) => Some(c.clone()),
+                    (None, Some(diff)) => content.map(|c| diff_engine.apply_diff(&c, diff)),
+                    (None, None) => content,
+                };
+
+                let file = self.get_checkpoint_file(&session.id, &checkpoint);
+                let old_size = fs::metadata(&file).await.map(|m| m.len()).unwrap_or(0);
+
+                if !retain[i] {
+                    removals.push((file, old_size));
+                    continue;
+                }
+
+                // 前一个检查点被删除时，增量检查点改写为完整内容
+                if checkpoint.content.is_none() && i > 0 && !retain[i - 1] {
+                    let full = content
+                        .clone()
+                        .ok_or_else(|| "Failed to reconstruct checkpoint content".to_string())?;
+                    let compressed = full.len() > COMPRESSION_THRESHOLD_BYTES;
+                    checkpoint.content = Some(if compressed {
+                        self.compress_content(&full)
+                    } else {
+                        full
+                    });
+                    checkpoint.compressed = Some(compressed);
+                    checkpoint.diff = None;
+
+                    self.save_checkpoint(&session.id, &checkpoint).await?;
+                    let new_size = fs::metadata(&file).await.map(|m| m.len()).unwrap_or(0);
+                    freed += old_size as i64 - new_size as i64;
+                }
+
+                if current == Some(i) {
+                    current_index.insert(path.clone(), kept.len());
+                }
+                kept.push(checkpoint);
+            }
+
+            report.retained += kept.len();
+            *checkpoints = kept;
+        }
+
+        session.checkpoints = checkpoints_by_path;
+        session.current_index = current_index;
+
+        // 会话已不再引用这些文件，删除失败只留下孤立文件
+        for (file, size) in removals {
+            match fs::remove_file(&file).await {
+                Ok(()) => {
+                    freed += size as i64;
+                    report.removed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to remove checkpoint file {}: {}", file.display(), e)
+                }
+            }
+        }
+
+        report.freed_bytes = freed.max(0) as u64;
+        Ok(report)
+    }
+
+    /// 校验单个检查点，包括它依赖的整条增量链
+    pub async fn verify(
+        &self,
+        session_id: &str,
+        checkpoint_id: &str,
+    ) -> Result<CheckpointVerification, String> {
+        self.verify_session(session_id)
+            .await?
+            .checkpoints
+            .into_iter()
+            .find(|c| c.id == checkpoint_id)
+            .ok_or_else(|| format!("Checkpoint not found: {}", checkpoint_id))
+    }
+
+    /// 校验会话的所有检查点
+    ///
+    /// 直接读取磁盘文件，因此也能发现 `load_session` 会静默跳过的损坏文件。
+    pub async fn verify_session(&self, session_id: &str) -> Result<CheckpointVerifyReport, String> {
+        let session_dir = self.get_session_dir(session_id);
+        if !session_dir.exists() {
+            return Err("Session not found".to_string());
+        }
+
+        let mut entries = fs::read_dir(&session_dir)
+            .await
+            .map_err(|e| format!("Failed to read session directory: {}", e))?;
+
+        // 按路径哈希分组，组内按时间戳排序
+        let mut chains: BTreeMap<String, BTreeMap<i64, Result<FileCheckpoint, String>>> =
+            BTreeMap::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if !path.extension().is_some_and(|e| e == "json") {
+                continue;
+            }
+            let Some((path_hash, timestamp)) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.rsplit_once('-'))
+                .and_then(|(h, t)| Some((h.to_string(), t.parse::<i64>().ok()?)))
+            else {
+                continue;
+            };
+
+            let checkpoint = fs::read_to_string(&path)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|data| {
+                    serde_json::from_str::<FileCheckpoint>(&data).map_err(|e| e.to_string())
+                });
+            chains
+                .entry(path_hash)
+                .or_default()
+                .insert(timestamp, checkpoint);
+        }
+
+        let diff_engine = DiffEngine::new();
+        let mut checkpoints = Vec::new();
+        for (path_hash, chain) in chains {
+            let mut state = ChainState::NoBase;
+            for (timestamp, checkpoint) in chain {
+                let id = format!("{}-{}", path_hash, timestamp);
+                let (path, corruption) = match checkpoint {
+                    Err(error) => (None, Some(CheckpointCorruption::Unreadable { error })),
+                    Ok(checkpoint) => {
+                        let corruption =
+                            self.verify_in_chain(&checkpoint, &mut state, &diff_engine);
+                        (Some(checkpoint.path), corruption)
+                    }
+                };
+
+                // 自身损坏的检查点会使后续增量检查点无法恢复
+                if matches!(
+                    corruption,
+                    Some(CheckpointCorruption::Unreadable { .. })
+                        | Some(CheckpointCorruption::HashMismatch { .. })
+                ) {
+                    state = ChainState::Broken(id.clone());
+                }
+
+                checkpoints.push(CheckpointVerification {
+                    id,
+                    path,
+                    timestamp,
+                    corruption,
+                });
+            }
+        }
+
+        Ok(CheckpointVerifyReport {
+            session_id: session_id.to_string(),
+            checkpoints,
+            quarantined: 0,
+        })
+    }
+
+    /// 在增量链中重建并校验检查点，成功时更新链状态
+    fn verify_in_chain(
+        &self,
+        checkpoint: &FileCheckpoint,
+        state: &mut ChainState,
+        diff_engine: &DiffEngine,
+    ) -> Option<CheckpointCorruption> {
+        let content = match (&checkpoint.content, &checkpoint.diff, &*state) {
+            (Some(c), _, _) if checkpoint.compressed.unwrap_or(false) => self.decompress_content(c),
+            (Some(c), _, _) => c.clone(),
+            (None, _, ChainState::NoBase) => return Some(CheckpointCorruption::MissingBase),
+            (None, _, ChainState::Broken(id)) => {
+                return Some(CheckpointCorruption::BrokenChain {
+                    broken_at: id.clone(),
+                })
+            }
+            (None, Some(diff), ChainState::Content(prev)) => diff_engine.apply_diff(prev, diff),
+            (None, None, ChainState::Content(prev)) => prev.clone(),
+        };
+
+        let actual = get_content_hash(&content);
+        if actual != checkpoint.hash {
+            return Some(CheckpointCorruption::HashMismatch {
+                expected: checkpoint.hash.clone(),
+                actual,
+            });
+        }
+
+        *state = ChainState::Content(content);
+        None
+    }
+
+    /// 校验所有会话，并把损坏的检查点移到隔离目录
+    ///
+    /// 适合在启动时调用，避免用户恢复时才发现检查点损坏。
+    pub async fn verify_all(&self) -> Result<Vec<CheckpointVerifyReport>, String> {
+        let mut reports = Vec::new();
+        let mut entries = match fs::read_dir(&self.checkpoint_dir).await {
+            Ok(entries) => entries,
+            Err(_) => return Ok(reports),
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Some(session_id) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !path.is_dir() || session_id == QUARANTINE_DIR {
+                continue;
+            }
+
+            let mut report = self.verify_session(session_id).await?;
+            let corrupted: Vec<String> = report.corrupted().map(|c| c.id.clone()).collect();
+            if !corrupted.is_empty() {
+                let quarantine_dir = self.checkpoint_dir.join(QUARANTINE_DIR).join(session_id);
+                fs::create_dir_all(&quarantine_dir)
+                    .await
+                    .map_err(|e| format!("Failed to create quarantine directory: {}", e))?;
+                for id in corrupted {
+                    let file_name = format!("{}.json", id);
+                    fs::rename(path.join(&file_name), quarantine_dir.join(&file_name))
+                        .await
+                        .map_err(|e| format!("Failed to quarantine checkpoint {}: {}", id, e))?;
+                    report.quarantined += 1;
+                }
+            }
+            reports.push(report);
+        }
+
+        Ok(reports)
+    }
+
     /// 压缩内容（简化实现，使用 base64 编码）
     pub fn compress_content(&self, content: &str) -> String {
         use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
@@ -33,6 +33,7 @@ mod types_tests {
             compressed: Some(false),
             metadata: None,
             tags: Some(vec!["test".to_string()]),
+            pinned: None,
         };
 
         assert_eq!(checkpoint.path, "/test/file.rs");
@@ -102,6 +103,7 @@ mod types_tests {
     fn test_checkpoint_history_item() {
         let item = CheckpointHistoryItem {
             index: 0,
+            id: "abc123-1234567890".to_string(),
             timestamp: 1234567890,
             hash: "abc123".to_string(),
             name: Some("Test".to_string()),
@@ -216,6 +218,7 @@ mod types_tests {
             compressed: None,
             metadata: None,
             tags: None,
+            pinned: None,
         };
 
         let json = serde_json::to_string(&checkpoint).unwrap();
@@ -328,6 +331,27 @@ mod diff_tests {
         assert_eq!(result, new);
     }
 
+    #[test]
+    fn test_apply_diff_preserves_line_endings() {
+        let engine = DiffEngine::new();
+        let old = "line1\r\nline2\r\n";
+        let new = "line1\r\nchanged\r\nline3";
+
+        let diff = engine.calculate_diff(old, new);
+        assert_eq!(engine.apply_diff(old, &diff), new);
+
+        let diff = engine.calculate_diff(new, old);
+        assert_eq!(engine.apply_diff(new, &diff), old);
+    }
+
+    #[test]
+    fn test_apply_legacy_diff() {
+        let engine = DiffEngine::new();
+        let diff = r#"[{"op":"eq","line":"line1","num":0},{"op":"add","line":"line2","num":1}]"#;
+
+        assert_eq!(engine.apply_diff("line1", diff), "line1\nline2");
+    }
+
     #[test]
     fn test_apply_diff_invalid_json() {
         let engine = DiffEngine::new();
@@ -502,6 +526,7 @@ mod session_tests {
                 compressed: None,
                 metadata: None,
                 tags: None,
+                pinned: None,
             }],
         );
 
@@ -593,3 +618,262 @@ mod session_tests {
         assert!(options.force_full_content.is_none());
     }
 }
+
+// ============================================================================
+// 垃圾回收测试
+// ============================================================================
+
+#[cfg(test)]
+mod gc_tests {
+    use super::*;
+    use std::time::Duration;
+
+    const FILE: &str = "/gc-test/file.rs";
+
+    fn content_at(index: usize) -> String {
+        (0..=index)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 创建 12 个检查点：首个为完整内容，其余为增量；前 8 个创建于两天前
+    async fn build_session(storage: &CheckpointStorage) -> CheckpointSession {
+        let now = chrono::Utc::now().timestamp_millis();
+        let engine = DiffEngine::new();
+        let mut session = CheckpointSession::new(Some("gc-session".to_string()), "/".into(), 5);
+
+        let checkpoints: Vec<FileCheckpoint> = (0..12)
+            .map(|i| FileCheckpoint {
+                path: FILE.to_string(),
+                content: (i == 0).then(|| content_at(0)),
+                diff: (i > 0).then(|| engine.calculate_diff(&content_at(i - 1), &content_at(i))),
+                hash: format!("hash-{}", i),
+                timestamp: if i < 8 {
+                    now - 48 * 3_600_000 + i as i64 * 1000
+                } else {
+                    now - 1_800_000 + i as i64 * 1000
+                },
+                name: None,
+                description: None,
+                git_commit: None,
+                edit_count: None,
+                compressed: Some(false),
+                metadata: None,
+                tags: (i == 3).then(|| vec!["release".to_string()]),
+                pinned: (i == 1).then_some(true),
+            })
+            .collect();
+
+        for checkpoint in &checkpoints {
+            storage
+                .save_checkpoint(&session.id, checkpoint)
+                .await
+                .unwrap();
+        }
+        session.checkpoints.insert(FILE.to_string(), checkpoints);
+        // 当前恢复点
+        session.current_index.insert(FILE.to_string(), 5);
+        session
+    }
+
+    fn retained_hashes(session: &CheckpointSession) -> Vec<String> {
+        session.checkpoints[FILE]
+            .iter()
+            .map(|c| c.hash.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_gc_keeps_exactly_retained_set() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = CheckpointStorage::with_dir(temp_dir.path().to_path_buf());
+        let mut session = build_session(&storage).await;
+
+        let policy = CheckpointRetentionPolicy {
+            keep_last: Some(2),
+            keep_within: Some(Duration::from_secs(3600)),
+            keep_tags: vec!["release".to_string()],
+        };
+        let report = storage
+            .collect_garbage(&mut session, &policy)
+            .await
+            .unwrap();
+
+        // 固定(1)、标签(3)、恢复点(5)、一小时内(8..12)
+        let expected = [1, 3, 5, 8, 9, 10, 11];
+        assert_eq!(
+            retained_hashes(&session),
+            expected
+                .iter()
+                .map(|i| format!("hash-{}", i))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(report.removed, 5);
+        assert_eq!(report.retained, expected.len());
+        assert!(report.freed_bytes > 0);
+        assert_eq!(session.get_current_index(FILE), Some(2));
+
+        let session_dir = temp_dir.path().join("gc-session");
+        assert_eq!(std::fs::read_dir(&session_dir).unwrap().count(), 7);
+
+        // 保留的检查点仍可恢复为原内容
+        let manager = CheckpointManager::with_storage(CheckpointStorage::with_dir(
+            temp_dir.path().to_path_buf(),
+        ));
+        manager
+            .init(Some("gc-session".to_string()), 5)
+            .await
+            .unwrap();
+        for (index, original) in expected.iter().enumerate() {
+            let result = manager
+                .restore_checkpoint(
+                    FILE,
+                    Some(index),
+                    Some(CheckpointRestoreOptions {
+                        dry_run: Some(true),
+                        ..Default::default()
+                    }),
+                )
+                .await;
+            assert_eq!(result.content, Some(content_at(*original)));
+        }
+
+        // 仅按数量保留时，固定的检查点和恢复点仍受保护
+        let report = storage
+            .collect_garbage(
+                &mut session,
+                &CheckpointRetentionPolicy {
+                    keep_last: Some(1),
+                    keep_within: None,
+                    keep_tags: Vec::new(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            retained_hashes(&session),
+            vec!["hash-1", "hash-5", "hash-11"]
+        );
+        assert_eq!(report.removed, 4);
+        assert_eq!(session.get_current_index(FILE), Some(1));
+    }
+}
+
+// ============================================================================
+// 完整性校验测试
+// ============================================================================
+
+#[cfg(test)]
+mod verify_tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// 通过管理器为同一文件创建 4 个检查点：首个为完整内容，其余为增量
+    async fn create_checkpoints(root: &std::path::Path) -> (CheckpointManager, Vec<String>) {
+        let manager =
+            CheckpointManager::with_storage(CheckpointStorage::with_dir(root.join("checkpoints")));
+        manager
+            .init(Some("verify-session".to_string()), 5)
+            .await
+            .unwrap();
+
+        let file = root.join("main.rs");
+        for i in 0..4 {
+            let content: String = (0..=i).map(|j| format!("line {}\n", j)).collect();
+            std::fs::write(&file, content).unwrap();
+            manager
+                .create_checkpoint(file.to_str().unwrap(), None)
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let ids = manager
+            .get_checkpoint_history(file.to_str().unwrap())
+            .await
+            .checkpoints
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        (manager, ids)
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_corrupted_checkpoint() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (manager, ids) = create_checkpoints(temp_dir.path()).await;
+        assert_eq!(ids.len(), 4);
+
+        let storage = CheckpointStorage::with_dir(temp_dir.path().join("checkpoints"));
+        let report = storage.verify_session("verify-session").await.unwrap();
+        assert!(report.is_intact());
+        assert_eq!(report.checkpoints.len(), 4);
+
+        // 损坏第三个检查点（增量）
+        let session_dir = temp_dir.path().join("checkpoints").join("verify-session");
+        std::fs::write(session_dir.join(format!("{}.json", ids[2])), "{ truncated").unwrap();
+
+        assert!(manager.verify(&ids[1]).await.unwrap().is_valid());
+        assert!(matches!(
+            manager.verify(&ids[2]).await.unwrap().corruption,
+            Some(CheckpointCorruption::Unreadable { .. })
+        ));
+        assert_eq!(
+            manager.verify(&ids[3]).await.unwrap().corruption,
+            Some(CheckpointCorruption::BrokenChain {
+                broken_at: ids[2].clone()
+            })
+        );
+
+        // 篡改完整内容检查点的内容
+        let base_file = session_dir.join(format!("{}.json", ids[0]));
+        let mut base: FileCheckpoint =
+            serde_json::from_str(&std::fs::read_to_string(&base_file).unwrap()).unwrap();
+        base.content = Some("tampered\n".to_string());
+        std::fs::write(&base_file, serde_json::to_string(&base).unwrap()).unwrap();
+
+        let report = storage.verify_session("verify-session").await.unwrap();
+        assert!(matches!(
+            report.checkpoints[0].corruption,
+            Some(CheckpointCorruption::HashMismatch { .. })
+        ));
+        assert_eq!(report.corrupted().count(), 4);
+
+        // verify_all 隔离所有损坏的检查点
+        let reports = storage.verify_all().await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].quarantined, 4);
+        assert_eq!(std::fs::read_dir(&session_dir).unwrap().count(), 0);
+        let quarantine_dir = temp_dir
+            .path()
+            .join("checkpoints")
+            .join(QUARANTINE_DIR)
+            .join("verify-session");
+        assert_eq!(std::fs::read_dir(quarantine_dir).unwrap().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_verify_compares_exact_bytes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (_manager, ids) = create_checkpoints(temp_dir.path()).await;
+        let storage = CheckpointStorage::with_dir(temp_dir.path().join("checkpoints"));
+
+        // 哈希对应去掉末尾换行符的内容，只有行尾不同也视为不匹配
+        let session_dir = temp_dir.path().join("checkpoints").join("verify-session");
+        let last_file = session_dir.join(format!("{}.json", ids[3]));
+        let mut last: FileCheckpoint =
+            serde_json::from_str(&std::fs::read_to_string(&last_file).unwrap()).unwrap();
+        last.hash = get_content_hash("line 0\nline 1\nline 2\nline 3");
+        std::fs::write(&last_file, serde_json::to_string(&last).unwrap()).unwrap();
+
+        let report = storage.verify_session("verify-session").await.unwrap();
+        assert!(report.checkpoints[..3]
+            .iter()
+            .all(|c| c.corruption.is_none()));
+        assert!(matches!(
+            report.checkpoints[3].corruption,
+            Some(CheckpointCorruption::HashMismatch { .. })
+        ));
+    }
+}
@@ -31,6 +31,8 @@ pub struct FileCheckpoint {
     pub metadata: Option<FileMetadata>,
     /// 用户定义标签
     pub tags: Option<Vec<String>>,
+    /// 是否已固定（固定的检查点不会被垃圾回收）
+    pub pinned: Option<bool>,
 }
 
 /// 文件元数据
@@ -79,10 +81,101 @@ pub struct CheckpointStats {
     pub compression_ratio: Option<f64>,
 }
 
+/// 检查点保留策略
+///
+/// 满足任一条件的检查点会被保留；固定的检查点和当前恢复点始终保留
+#[derive(Debug, Clone)]
+pub struct CheckpointRetentionPolicy {
+    /// 每个文件保留最近的 N 个检查点
+    pub keep_last: Option<usize>,
+    /// 保留该时长内创建的所有检查点
+    pub keep_within: Option<std::time::Duration>,
+    /// 保留带有任一标签的检查点
+    pub keep_tags: Vec<String>,
+}
+
+impl Default for CheckpointRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: Some(MAX_CHECKPOINTS_PER_FILE),
+            keep_within: None,
+            keep_tags: Vec::new(),
+        }
+    }
+}
+
+/// 检查点垃圾回收结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckpointGcReport {
+    /// 删除的检查点数量
+    pub removed: usize,
+    /// 保留的检查点数量
+    pub retained: usize,
+    /// 回收的磁盘空间（字节）
+    pub freed_bytes: u64,
+}
+
+/// 检查点损坏类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CheckpointCorruption {
+    /// 检查点文件无法读取或解析
+    Unreadable { error: String },
+    /// 重建内容的哈希与记录的哈希不一致
+    HashMismatch { expected: String, actual: String },
+    /// 增量检查点之前没有完整内容的检查点
+    MissingBase,
+    /// 增量链中更早的检查点已损坏
+    BrokenChain { broken_at: String },
+}
+
+/// 单个检查点的校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointVerification {
+    /// 检查点 ID（`<路径哈希>-<时间戳>`）
+    pub id: String,
+    /// 文件路径，检查点无法解析时为 None
+    pub path: Option<String>,
+    pub timestamp: i64,
+    /// 损坏原因，完好时为 None
+    pub corruption: Option<CheckpointCorruption>,
+}
+
+impl CheckpointVerification {
+    /// 检查点是否完好
+    pub fn is_valid(&self) -> bool {
+        self.corruption.is_none()
+    }
+}
+
+/// 会话的检查点校验报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointVerifyReport {
+    pub session_id: String,
+    /// 每个检查点的校验结果，按文件和时间排序
+    pub checkpoints: Vec<CheckpointVerification>,
+    /// 被隔离的检查点数量
+    pub quarantined: usize,
+}
+
+impl CheckpointVerifyReport {
+    /// 已损坏的检查点
+    pub fn corrupted(&self) -> impl Iterator<Item = &CheckpointVerification> {
+        self.checkpoints.iter().filter(|c| !c.is_valid())
+    }
+
+    /// 所有检查点是否完好
+    pub fn is_intact(&self) -> bool {
+        self.checkpoints.iter().all(|c| c.is_valid())
+    }
+}
+
 /// 检查点历史记录项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointHistoryItem {
     pub index: usize,
+    /// 检查点 ID，可用于校验
+    pub id: String,
     pub timestamp: i64,
     pub hash: String,
     pub name: Option<String>,
@@ -169,3 +262,5 @@ pub const CHECKPOINT_RETENTION_DAYS: u64 = 30;
 pub const DEFAULT_AUTO_CHECKPOINT_INTERVAL: u32 = 5;
 pub const MAX_STORAGE_SIZE_MB: u64 = 500;
 pub const COMPRESSION_THRESHOLD_BYTES: usize = 1024;
+/// 隔离损坏检查点的目录名（位于检查点根目录下）
+pub const QUARANTINE_DIR: &str = "quarantine";
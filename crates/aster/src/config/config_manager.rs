@@ -2,6 +2,7 @@
 //!
 //! 增强版配置管理器，支持多源配置合并、来源追踪、热重载等功能
 
+use super::migration::{ConfigMigrator, MigrationReport};
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
@@ -13,11 +14,14 @@ use std::sync::Arc;
 use std::time::SystemTime;
 
 /// 配置重载回调函数类型
-pub(crate) type ConfigReloadCallback = Box<dyn Fn(&HashMap<String, Value>) + Send + Sync>;
+pub(crate) type ConfigReloadCallback = Box<dyn Fn(&ConfigChangeEvent) + Send + Sync>;
 
 /// 配置重载回调列表类型
 pub(crate) type ConfigReloadCallbackList = Arc<RwLock<Vec<ConfigReloadCallback>>>;
 
+/// 需要重启才能生效的配置项，热重载时保持旧值
+pub const RESTART_REQUIRED_KEYS: &[&str] = &["api_provider", "api_key", "proxy", "mcp_servers"];
+
 /// 配置来源
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -83,6 +87,115 @@ pub struct ConfigKeySource {
     pub overridden_by: Vec<ConfigSource>,
 }
 
+/// 写入被企业策略锁定的配置项
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("配置项 {key} 被企业策略锁定，无法修改")]
+pub struct ConfigLockedError {
+    /// 配置键
+    pub key: String,
+}
+
+/// 单个配置项的变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigKeyChange {
+    /// 配置键
+    pub key: String,
+    /// 旧值（新增时为 None）
+    pub old_value: Option<Value>,
+    /// 新值（删除时为 None）
+    pub new_value: Option<Value>,
+}
+
+/// 配置变更事件
+#[derive(Debug, Clone, Default)]
+pub struct ConfigChangeEvent {
+    /// 所有发生变化的配置项（按键排序）
+    pub changes: Vec<ConfigKeyChange>,
+    /// 已生效的配置项
+    pub applied: Vec<String>,
+    /// 需要重启才能生效的配置项
+    pub restart_required: Vec<String>,
+}
+
+impl ConfigChangeEvent {
+    /// 是否没有任何变化
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// 比较两份配置，返回按键排序的变化列表
+fn diff_configs(
+    old: &HashMap<String, Value>,
+    new: &HashMap<String, Value>,
+) -> Vec<ConfigKeyChange> {
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| ConfigKeyChange {
+            key: key.clone(),
+            old_value: old.get(key).cloned(),
+            new_value: new.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// 将 `key` 在 `layer` 中的记录恢复为 `old` 中的记录（不存在则删除）
+fn restore_key<T: Clone>(layer: &RwLock<HashMap<String, T>>, old: &HashMap<String, T>, key: &str) {
+    let mut layer = layer.write();
+    match old.get(key) {
+        Some(value) => layer.insert(key.to_string(), value.clone()),
+        None => layer.remove(key),
+    };
+}
+
+/// 校验已知配置项的取值
+fn validate_config(config: &HashMap<String, Value>) -> Result<(), String> {
+    for key in ["model", "theme", "api_provider"] {
+        if let Some(value) = config.get(key) {
+            if !value.is_string() {
+                return Err(format!("{} 必须是字符串", key));
+            }
+        }
+    }
+    if let Some(value) = config.get("max_tokens") {
+        if !value.as_u64().is_some_and(|v| v > 0) {
+            return Err("max_tokens 必须是正整数".to_string());
+        }
+    }
+    if let Some(value) = config.get("temperature") {
+        if !value.as_f64().is_some_and(|v| (0.0..=2.0).contains(&v)) {
+            return Err("temperature 必须是 0 到 2 之间的数字".to_string());
+        }
+    }
+    if let Some(value) = config.get("enable_telemetry") {
+        if !value.is_boolean() {
+            return Err("enable_telemetry 必须是布尔值".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 配置项生效值的来源说明
+#[derive(Debug, Clone)]
+pub struct ConfigExplanation {
+    /// 配置键
+    pub key: String,
+    /// 生效值
+    pub value: Value,
+    /// 生效值的来源
+    pub source: ConfigSource,
+    /// 生效值的来源路径
+    pub source_path: Option<PathBuf>,
+    /// 是否被企业策略锁定（用户配置、环境变量和命令行均无法覆盖）
+    pub locked: bool,
+    /// 按优先级从低到高排列的覆盖链，最后一项为生效值
+    pub chain: Vec<ConfigKeySource>,
+}
+
 /// 企业策略配置
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EnterprisePolicyConfig {
@@ -126,6 +239,8 @@ pub struct ConfigManagerOptions {
     pub debug_mode: bool,
     /// CLI 标志
     pub cli_flags: HashMap<String, Value>,
+    /// 配置迁移器（默认升级到当前配置版本）
+    pub migrator: Option<ConfigMigrator>,
 }
 
 /// 配置管理器
@@ -149,7 +264,7 @@ pub struct ConfigManager {
     config_sources: RwLock<HashMap<String, ConfigSource>>,
     /// 配置来源路径映射
     config_source_paths: RwLock<HashMap<String, PathBuf>>,
-    /// 配置覆盖历史
+    /// 配置覆盖历史：每个配置项按加载顺序记录的全部取值
     config_history: RwLock<HashMap<String, Vec<ConfigKeySource>>>,
     /// 已加载的配置源
     loaded_sources: RwLock<Vec<ConfigSourceInfo>>,
@@ -163,6 +278,10 @@ pub struct ConfigManager {
     cli_flags: HashMap<String, Value>,
     /// 调试模式
     debug_mode: bool,
+    /// 配置迁移器
+    migrator: ConfigMigrator,
+    /// 已执行的配置迁移
+    migration_reports: RwLock<Vec<MigrationReport>>,
 }
 
 impl ConfigManager {
@@ -200,7 +319,7 @@ impl ConfigManager {
                 .map(|v| v == "true")
                 .unwrap_or(false);
 
-        let mut manager = Self {
+        let manager = Self {
             global_config_dir,
             user_config_file,
             project_config_file,
@@ -217,6 +336,8 @@ impl ConfigManager {
             reload_callbacks: Arc::new(RwLock::new(Vec::new())),
             cli_flags: options.cli_flags,
             debug_mode,
+            migrator: options.migrator.unwrap_or_default(),
+            migration_reports: RwLock::new(Vec::new()),
         };
 
         manager.load_and_merge_config();
@@ -233,7 +354,7 @@ impl ConfigManager {
     /// 5. envSettings - 环境变量
     /// 6. flagSettings - 命令行标志
     /// 7. policySettings - 企业策略（最高优先级）
-    fn load_and_merge_config(&mut self) {
+    fn load_and_merge_config(&self) {
         self.config_sources.write().clear();
         self.config_source_paths.write().clear();
         self.config_history.write().clear();
@@ -254,8 +375,10 @@ impl ConfigManager {
             loaded_at: Some(load_time),
         });
 
-        // 2. 加载企业策略默认值
-        if let Some(policy) = self.load_enterprise_policy() {
+        // 2. 加载企业策略默认值（先保存策略，后续各配置源据此识别被锁定的键）
+        let policy = self.load_enterprise_policy();
+        *self.enterprise_policy.write() = policy.clone();
+        if let Some(policy) = policy {
             if !policy.defaults.is_empty() {
                 self.merge_config(
                     &mut config,
@@ -265,7 +388,6 @@ impl ConfigManager {
                 );
                 self.debug_log("加载企业策略默认值");
             }
-            *self.enterprise_policy.write() = Some(policy);
         }
 
         // 3. 用户配置
@@ -278,7 +400,7 @@ impl ConfigManager {
             loaded_at: Some(load_time),
         });
         if user_exists {
-            if let Some(user_config) = self.load_config_file(&self.user_config_file) {
+            if let Some(user_config) = self.load_migrated_config_file(&self.user_config_file) {
                 self.merge_config(
                     &mut config,
                     &user_config,
@@ -299,7 +421,8 @@ impl ConfigManager {
             loaded_at: Some(load_time),
         });
         if project_exists {
-            if let Some(project_config) = self.load_config_file(&self.project_config_file) {
+            if let Some(project_config) = self.load_migrated_config_file(&self.project_config_file)
+            {
                 self.merge_config(
                     &mut config,
                     &project_config,
@@ -320,7 +443,7 @@ impl ConfigManager {
             loaded_at: Some(load_time),
         });
         if local_exists {
-            if let Some(local_config) = self.load_config_file(&self.local_config_file) {
+            if let Some(local_config) = self.load_migrated_config_file(&self.local_config_file) {
                 self.merge_config(
                     &mut config,
                     &local_config,
@@ -380,23 +503,27 @@ impl ConfigManager {
         }
 
         // 9. 企业策略强制设置（最高优先级）
-        if let Some(ref policy) = *self.enterprise_policy.read() {
-            if !policy.enforced.is_empty() {
-                self.merge_config(
-                    &mut config,
-                    &policy.enforced,
-                    ConfigSource::PolicySettings,
-                    Some(&self.policy_config_file.clone()),
-                );
-                self.loaded_sources.write().push(ConfigSourceInfo {
-                    source: ConfigSource::PolicySettings,
-                    path: Some(self.policy_config_file.clone()),
-                    priority: ConfigSource::PolicySettings.priority(),
-                    exists: true,
-                    loaded_at: Some(load_time),
-                });
-                self.debug_log("应用企业策略强制设置");
-            }
+        let enforced = self
+            .enterprise_policy
+            .read()
+            .as_ref()
+            .map(|policy| policy.enforced.clone())
+            .unwrap_or_default();
+        if !enforced.is_empty() {
+            self.merge_config(
+                &mut config,
+                &enforced,
+                ConfigSource::PolicySettings,
+                Some(&self.policy_config_file.clone()),
+            );
+            self.loaded_sources.write().push(ConfigSourceInfo {
+                source: ConfigSource::PolicySettings,
+                path: Some(self.policy_config_file.clone()),
+                priority: ConfigSource::PolicySettings.priority(),
+                exists: true,
+                loaded_at: Some(load_time),
+            });
+            self.debug_log("应用企业策略强制设置");
         }
 
         *self.merged_config.write() = config;
@@ -504,6 +631,45 @@ impl ConfigManager {
         }
     }
 
+    /// 加载配置文件，版本过旧时先迁移并写回（写回前备份原文件）
+    fn load_migrated_config_file(&self, path: &Path) -> Option<HashMap<String, Value>> {
+        let mut config = self.load_config_file(path)?;
+
+        let mut report = match self.migrator.migrate(&mut config) {
+            Ok(report) if report.migrated() => report,
+            Ok(_) => return Some(config),
+            Err(e) => {
+                tracing::warn!("配置迁移失败: {:?}, 错误: {}", path, e);
+                return Some(config);
+            }
+        };
+
+        report.path = Some(path.to_path_buf());
+        match self.backup_config(path) {
+            Ok(backup_path) => {
+                report.backup_path = backup_path;
+                let written = serde_yaml::to_string(&config)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                    .and_then(|yaml| fs::write(path, yaml));
+                if let Err(e) = written {
+                    tracing::warn!("写入迁移后的配置失败: {:?}, 错误: {}", path, e);
+                }
+            }
+            Err(e) => {
+                tracing::warn!("备份配置失败，跳过写回迁移结果: {:?}, 错误: {}", path, e);
+            }
+        }
+
+        tracing::info!(
+            "配置已从版本 {} 迁移到 {}: {:?}",
+            report.from_version,
+            report.to_version,
+            path
+        );
+        self.migration_reports.write().push(report);
+        Some(config)
+    }
+
     /// 加载企业策略配置
     fn load_enterprise_policy(&self) -> Option<EnterprisePolicyConfig> {
         if !self.policy_config_file.exists() {
@@ -541,18 +707,12 @@ impl ConfigManager {
         source_path: Option<&PathBuf>,
     ) {
         for (key, value) in override_config {
-            // 追踪覆盖历史
-            if let Some(prev_source) = self.config_sources.read().get(key) {
-                if *prev_source != source {
-                    let mut history = self.config_history.write();
-                    let entry = history.entry(key.clone()).or_default();
-                    entry.push(ConfigKeySource {
-                        key: key.clone(),
-                        value: value.clone(),
-                        source,
-                        source_path: source_path.cloned(),
-                        overridden_by: vec![*prev_source],
-                    });
+            // 被策略锁定的键：记录冲突，最终由策略强制值覆盖
+            let enforced = self.enforced_value(key);
+            let is_policy_value = source == ConfigSource::PolicySettings && enforced.is_some();
+            if let Some(ref enforced) = enforced {
+                if !is_policy_value && enforced != value {
+                    tracing::warn!("配置项 {} 被企业策略锁定，忽略来自 {:?} 的值", key, source);
                 }
             }
 
@@ -564,8 +724,14 @@ impl ConfigManager {
                     .insert(key.clone(), path.clone());
             }
 
-            // 深度合并
-            base.insert(key.clone(), self.deep_merge(base.get(key), value));
+            // 深度合并（策略强制值整体替换，不与低优先级的值合并）
+            let merged = if is_policy_value {
+                value.clone()
+            } else {
+                self.deep_merge(base.get(key), value)
+            };
+            self.record_history(key, &merged, source, source_path);
+            base.insert(key.clone(), merged);
         }
     }
 
@@ -591,7 +757,8 @@ impl ConfigManager {
         source: ConfigSource,
         source_path: Option<&PathBuf>,
     ) {
-        for key in config.keys() {
+        for (key, value) in config {
+            self.record_history(key, value, source, source_path);
             self.config_sources.write().insert(key.clone(), source);
             if let Some(path) = source_path {
                 self.config_source_paths
@@ -601,6 +768,28 @@ impl ConfigManager {
         }
     }
 
+    /// 记录配置项的一次取值，并标记此前的取值被覆盖
+    fn record_history(
+        &self,
+        key: &str,
+        value: &Value,
+        source: ConfigSource,
+        source_path: Option<&PathBuf>,
+    ) {
+        let mut history = self.config_history.write();
+        let entries = history.entry(key.to_string()).or_default();
+        for entry in entries.iter_mut() {
+            entry.overridden_by.push(source);
+        }
+        entries.push(ConfigKeySource {
+            key: key.to_string(),
+            value: value.clone(),
+            source,
+            source_path: source_path.cloned(),
+            overridden_by: Vec::new(),
+        });
+    }
+
     /// 调试日志
     fn debug_log(&self, message: &str) {
         if self.debug_mode {
@@ -661,13 +850,20 @@ impl ConfigManager {
         self.merged_config.read().get(key).cloned()
     }
 
-    /// 设置配置项
-    pub fn set<T: Serialize>(&self, key: &str, value: T) {
+    /// 设置配置项，被企业策略锁定的键会被拒绝
+    pub fn set<T: Serialize>(&self, key: &str, value: T) -> Result<(), ConfigLockedError> {
+        if self.is_enforced_by_policy(key) {
+            tracing::warn!("配置项 {} 被企业策略锁定，拒绝修改", key);
+            return Err(ConfigLockedError {
+                key: key.to_string(),
+            });
+        }
         if let Ok(json_value) = serde_json::to_value(value) {
             self.merged_config
                 .write()
                 .insert(key.to_string(), json_value);
         }
+        Ok(())
     }
 
     /// 获取所有配置
@@ -706,7 +902,7 @@ impl ConfigManager {
         self.loaded_sources.read().clone()
     }
 
-    /// 获取配置项的覆盖历史
+    /// 获取配置项的覆盖历史（按加载顺序，每项记录覆盖它的配置源）
     pub fn get_config_history(&self, key: &str) -> Vec<ConfigKeySource> {
         self.config_history
             .read()
@@ -715,6 +911,37 @@ impl ConfigManager {
             .unwrap_or_default()
     }
 
+    /// 解释配置项生效值的来源及完整覆盖链
+    ///
+    /// 运行时通过 `set` 修改的值不属于任何配置源，不会出现在覆盖链中
+    pub fn explain(&self, key: &str) -> Option<ConfigExplanation> {
+        let value = self.get_value(key)?;
+        let chain = self.get_config_history(key);
+        let source = self
+            .config_sources
+            .read()
+            .get(key)
+            .copied()
+            .unwrap_or(ConfigSource::Default);
+
+        Some(ConfigExplanation {
+            key: key.to_string(),
+            value,
+            source,
+            source_path: self.config_source_paths.read().get(key).cloned(),
+            locked: self.is_enforced_by_policy(key),
+            chain,
+        })
+    }
+
+    /// 获取企业策略对配置项的强制值
+    fn enforced_value(&self, key: &str) -> Option<Value> {
+        self.enterprise_policy
+            .read()
+            .as_ref()
+            .and_then(|p| p.enforced.get(key).cloned())
+    }
+
     /// 检查配置项是否被企业策略强制
     pub fn is_enforced_by_policy(&self, key: &str) -> bool {
         self.enterprise_policy
@@ -766,7 +993,14 @@ impl ConfigManager {
     /// 保存到用户配置文件
     pub fn save(&self, config: Option<&HashMap<String, Value>>) -> Result<(), std::io::Error> {
         if let Some(cfg) = config {
-            self.merged_config.write().extend(cfg.clone());
+            let mut merged = self.merged_config.write();
+            for (key, value) in cfg {
+                if self.is_enforced_by_policy(key) {
+                    tracing::warn!("配置项 {} 被企业策略锁定，无法覆盖", key);
+                    continue;
+                }
+                merged.insert(key.clone(), value.clone());
+            }
         }
 
         if let Some(parent) = self.user_config_file.parent() {
@@ -827,19 +1061,85 @@ impl ConfigManager {
         fs::write(&self.project_config_file, yaml)
     }
 
-    /// 重新加载配置
+    /// 重新加载配置（所有变化立即生效，包括需要重启的配置项）
     pub fn reload(&mut self) {
+        let old_config = self.merged_config.read().clone();
         self.load_and_merge_config();
-        let config = self.merged_config.read().clone();
+        let changes = diff_configs(&old_config, &self.merged_config.read());
+
+        let event = ConfigChangeEvent {
+            applied: changes.iter().map(|c| c.key.clone()).collect(),
+            changes,
+            restart_required: Vec::new(),
+        };
+        self.notify_change(&event);
+    }
+
+    /// 热重载配置
+    ///
+    /// 先校验所有配置文件，任一文件无法解析或取值非法时保留当前配置并返回错误；
+    /// 需要重启的配置项（见 `RESTART_REQUIRED_KEYS`）的值及来源记录都保持旧值，仅在事件中列出
+    pub fn hot_reload(&self) -> Result<ConfigChangeEvent, String> {
+        for path in [
+            &self.user_config_file,
+            &self.project_config_file,
+            &self.local_config_file,
+        ] {
+            if !path.exists() {
+                continue;
+            }
+            let file_config = self
+                .load_config_file(path)
+                .ok_or_else(|| format!("无法解析配置文件: {:?}", path))?;
+            validate_config(&file_config).map_err(|e| format!("{:?}: {}", path, e))?;
+        }
+
+        let old_config = self.merged_config.read().clone();
+        let old_sources = self.config_sources.read().clone();
+        let old_source_paths = self.config_source_paths.read().clone();
+        let old_history = self.config_history.read().clone();
+        self.load_and_merge_config();
+
+        let changes = diff_configs(&old_config, &self.merged_config.read());
+        let mut applied = Vec::new();
+        let mut restart_required = Vec::new();
+        for change in &changes {
+            if RESTART_REQUIRED_KEYS.contains(&change.key.as_str()) {
+                restore_key(&self.merged_config, &old_config, &change.key);
+                restore_key(&self.config_sources, &old_sources, &change.key);
+                restore_key(&self.config_source_paths, &old_source_paths, &change.key);
+                restore_key(&self.config_history, &old_history, &change.key);
+                restart_required.push(change.key.clone());
+            } else {
+                applied.push(change.key.clone());
+            }
+        }
+
+        if !restart_required.is_empty() {
+            tracing::info!("以下配置项需要重启后生效: {}", restart_required.join(", "));
+        }
+
+        let event = ConfigChangeEvent {
+            changes,
+            applied,
+            restart_required,
+        };
+        if !event.is_empty() {
+            self.notify_change(&event);
+        }
+        Ok(event)
+    }
+
+    fn notify_change(&self, event: &ConfigChangeEvent) {
         for callback in self.reload_callbacks.read().iter() {
-            callback(&config);
+            callback(event);
         }
     }
 
-    /// 监听配置变化
-    pub fn watch<F>(&self, callback: F) -> Result<(), notify::Error>
+    /// 监听配置文件变化并热重载，每次生效的变更都会通过回调通知
+    pub fn watch<F>(self: &Arc<Self>, callback: F) -> Result<(), notify::Error>
     where
-        F: Fn(&HashMap<String, Value>) + Send + Sync + 'static,
+        F: Fn(&ConfigChangeEvent) + Send + Sync + 'static,
     {
         self.reload_callbacks.write().push(Box::new(callback));
 
@@ -848,45 +1148,50 @@ impl ConfigManager {
             return Ok(());
         }
 
-        let callbacks = self.reload_callbacks.clone();
-        let user_file = self.user_config_file.clone();
-        let project_file = self.project_config_file.clone();
-        let local_file = self.local_config_file.clone();
-
-        let watcher = notify::recommended_watcher(move |res: Result<Event, _>| {
-            if let Ok(event) = res {
-                if event.kind.is_modify() {
-                    // 简化：触发回调
-                    let cbs = callbacks.read();
-                    for cb in cbs.iter() {
-                        cb(&HashMap::new()); // 实际应重新加载
-                    }
+        let files = vec![
+            self.user_config_file.clone(),
+            self.project_config_file.clone(),
+            self.local_config_file.clone(),
+        ];
+        let manager = Arc::downgrade(self);
+        let watched_files = files.clone();
+
+        let mut watcher = notify::recommended_watcher(move |res: Result<Event, _>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if !(event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove()) {
+                return;
+            }
+            if !event.paths.iter().any(|p| watched_files.contains(p)) {
+                return;
+            }
+            if let Some(manager) = manager.upgrade() {
+                if let Err(e) = manager.hot_reload() {
+                    tracing::warn!("配置重载失败，保留当前配置: {}", e);
                 }
             }
         })?;
 
-        // 监听配置文件
-        let mut w = watcher;
-        if user_file.exists() {
-            let _ = w.watch(&user_file, RecursiveMode::NonRecursive);
-        }
-        if project_file.exists() {
-            let _ = w.watch(&project_file, RecursiveMode::NonRecursive);
-        }
-        if local_file.exists() {
-            let _ = w.watch(&local_file, RecursiveMode::NonRecursive);
+        // 监听配置文件所在目录，编辑器以替换方式保存文件时也能收到事件
+        let mut dirs: Vec<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+        dirs.dedup();
+        for dir in dirs {
+            if dir.exists() {
+                let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+            }
         }
 
-        *watcher_guard = Some(w);
+        *watcher_guard = Some(watcher);
         Ok(())
     }
 
     // ============ 备份和恢复 ============
 
-    /// 备份配置文件
-    fn backup_config(&self, file_path: &Path) -> Result<(), std::io::Error> {
+    /// 备份配置文件，返回备份路径（文件不存在时返回 None）
+    fn backup_config(&self, file_path: &Path) -> Result<Option<PathBuf>, std::io::Error> {
         if !file_path.exists() {
-            return Ok(());
+            return Ok(None);
         }
 
         let backup_dir = file_path
@@ -905,7 +1210,7 @@ impl ConfigManager {
 
         fs::copy(file_path, &backup_path)?;
         self.clean_old_backups(&backup_dir, filename)?;
-        Ok(())
+        Ok(Some(backup_path))
     }
 
     /// 清理旧备份（保留最近10个）
@@ -923,6 +1228,11 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// 获取本次加载中执行的配置迁移
+    pub fn get_migration_reports(&self) -> Vec<MigrationReport> {
+        self.migration_reports.read().clone()
+    }
+
     /// 列出可用备份
     pub fn list_backups(&self, config_type: &str) -> Vec<String> {
         let config_file = match config_type {
@@ -1049,9 +1359,16 @@ impl ConfigManager {
 
     /// 导入配置
     pub fn import(&mut self, config_json: &str) -> Result<(), String> {
-        let config: HashMap<String, Value> =
+        let mut config: HashMap<String, Value> =
             serde_json::from_str(config_json).map_err(|e| format!("JSON 解析失败: {}", e))?;
 
+        // 导入的值不能覆盖企业策略强制项
+        if let Some(ref policy) = *self.enterprise_policy.read() {
+            for (key, value) in &policy.enforced {
+                config.insert(key.clone(), value.clone());
+            }
+        }
+
         *self.merged_config.write() = config;
         self.save(None).map_err(|e| format!("保存失败: {}", e))?;
         Ok(())
@@ -1086,7 +1403,7 @@ mod tests {
     #[test]
     fn test_set_and_get() {
         let manager = ConfigManager::default();
-        manager.set("test_key", "test_value");
+        manager.set("test_key", "test_value").unwrap();
         let value: Option<String> = manager.get("test_key");
         assert_eq!(value, Some("test_value".to_string()));
     }
@@ -1114,6 +1431,278 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_project_config_migrated_on_load() {
+        fn rename_model_key(config: &mut HashMap<String, Value>) -> Result<(), String> {
+            if let Some(model) = config.remove("default_model") {
+                config.insert("model".to_string(), model);
+            }
+            Ok(())
+        }
+        fn nest_tool_settings(config: &mut HashMap<String, Value>) -> Result<(), String> {
+            if let Some(timeout) = config.remove("tool_timeout") {
+                config.insert(
+                    "tools".to_string(),
+                    serde_json::json!({ "timeout": timeout }),
+                );
+            }
+            Ok(())
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let project_file = temp_dir.path().join(".aster").join("settings.yaml");
+        fs::create_dir_all(project_file.parent().unwrap()).unwrap();
+        fs::write(
+            &project_file,
+            "default_model: claude-3-opus\ntool_timeout: 30\ncustom_key: kept\n",
+        )
+        .unwrap();
+
+        let migrator = ConfigMigrator::new(3)
+            .with_migration(1, "rename default_model to model", rename_model_key)
+            .with_migration(2, "nest tool settings", nest_tool_settings);
+        let manager = ConfigManager::new(ConfigManagerOptions {
+            working_directory: Some(temp_dir.path().to_path_buf()),
+            migrator: Some(migrator),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            manager.get::<String>("model"),
+            Some("claude-3-opus".to_string())
+        );
+        assert_eq!(
+            manager.get_value("tools"),
+            Some(serde_json::json!({ "timeout": 30 }))
+        );
+        assert_eq!(
+            manager.get::<String>("custom_key"),
+            Some("kept".to_string())
+        );
+
+        let reports = manager.get_migration_reports();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].path.as_deref(), Some(project_file.as_path()));
+        assert_eq!((reports[0].from_version, reports[0].to_version), (1, 3));
+        assert_eq!(reports[0].applied.len(), 2);
+
+        // 原文件已备份，迁移结果已写回
+        let backup = reports[0].backup_path.clone().unwrap();
+        assert!(fs::read_to_string(backup)
+            .unwrap()
+            .contains("default_model"));
+        let migrated = manager.load_config_file(&project_file).unwrap();
+        assert_eq!(migrated.get("config_version"), Some(&Value::from(3)));
+        assert!(!migrated.contains_key("default_model"));
+    }
+
+    #[test]
+    fn test_explain_reports_override_chain() {
+        let config_dir = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let project_file = temp_dir.path().join(".aster").join("settings.yaml");
+        fs::create_dir_all(project_file.parent().unwrap()).unwrap();
+        fs::write(&project_file, "max_tokens: 8192\n").unwrap();
+
+        temp_env::with_vars(
+            [
+                ("ASTER_CONFIG_DIR", Some(config_dir.path().as_os_str())),
+                ("ASTER_MAX_TOKENS", Some(std::ffi::OsStr::new("16384"))),
+            ],
+            || {
+                let manager = ConfigManager::new(ConfigManagerOptions {
+                    working_directory: Some(temp_dir.path().to_path_buf()),
+                    ..Default::default()
+                });
+
+                let explanation = manager.explain("max_tokens").unwrap();
+                assert_eq!(explanation.value, Value::from(16384));
+                assert_eq!(explanation.source, ConfigSource::EnvSettings);
+                assert!(!explanation.locked);
+
+                let chain: Vec<_> = explanation
+                    .chain
+                    .iter()
+                    .map(|entry| (entry.source, entry.value.clone()))
+                    .collect();
+                assert_eq!(
+                    chain,
+                    vec![
+                        (ConfigSource::Default, Value::from(4096)),
+                        (ConfigSource::ProjectSettings, Value::from(8192)),
+                        (ConfigSource::EnvSettings, Value::from(16384)),
+                    ]
+                );
+
+                let project = explanation
+                    .chain
+                    .iter()
+                    .find(|entry| entry.source == ConfigSource::ProjectSettings)
+                    .unwrap();
+                assert_eq!(project.source_path.as_deref(), Some(project_file.as_path()));
+                assert_eq!(project.overridden_by, vec![ConfigSource::EnvSettings]);
+                assert!(explanation.chain.last().unwrap().overridden_by.is_empty());
+                assert_eq!(
+                    manager.get_config_history("max_tokens").len(),
+                    explanation.chain.len()
+                );
+            },
+        );
+
+        assert!(ConfigManager::default().explain("no_such_key").is_none());
+    }
+
+    #[test]
+    fn test_explain_flags_policy_lock() {
+        let manager = ConfigManager::default();
+        let mut enforced = HashMap::new();
+        enforced.insert("model".to_string(), Value::from("approved-model"));
+        *manager.enterprise_policy.write() = Some(EnterprisePolicyConfig {
+            enforced: enforced.clone(),
+            ..Default::default()
+        });
+        let mut config = manager.merged_config.read().clone();
+        manager.merge_config(&mut config, &enforced, ConfigSource::PolicySettings, None);
+        *manager.merged_config.write() = config;
+
+        let explanation = manager.explain("model").unwrap();
+        assert!(explanation.locked);
+        assert_eq!(explanation.source, ConfigSource::PolicySettings);
+        assert_eq!(explanation.value, Value::from("approved-model"));
+    }
+
+    #[test]
+    fn test_policy_locked_key_ignores_env_override() {
+        let config_dir = TempDir::new().unwrap();
+        let work_dir = TempDir::new().unwrap();
+        fs::write(
+            config_dir.path().join("managed_settings.yaml"),
+            "enforced:\n  enable_telemetry: false\n  allowed_providers: [anthropic]\n",
+        )
+        .unwrap();
+        fs::create_dir_all(work_dir.path().join(".aster")).unwrap();
+        fs::write(
+            work_dir.path().join(".aster").join("settings.yaml"),
+            "allowed_providers: [anthropic, openai]\n",
+        )
+        .unwrap();
+
+        temp_env::with_vars(
+            [
+                ("ASTER_CONFIG_DIR", Some(config_dir.path().as_os_str())),
+                ("ASTER_ENABLE_TELEMETRY", Some(std::ffi::OsStr::new("true"))),
+            ],
+            || {
+                let manager = ConfigManager::new(ConfigManagerOptions {
+                    working_directory: Some(work_dir.path().to_path_buf()),
+                    ..Default::default()
+                });
+
+                assert_eq!(manager.get::<bool>("enable_telemetry"), Some(false));
+                assert_eq!(
+                    manager.get::<Vec<String>>("allowed_providers"),
+                    Some(vec!["anthropic".to_string()])
+                );
+
+                let explanation = manager.explain("enable_telemetry").unwrap();
+                assert!(explanation.locked);
+                assert_eq!(explanation.source, ConfigSource::PolicySettings);
+                assert!(explanation
+                    .chain
+                    .iter()
+                    .any(|entry| entry.source == ConfigSource::EnvSettings));
+
+                let err = manager.set("enable_telemetry", true).unwrap_err();
+                assert_eq!(err.key, "enable_telemetry");
+                assert_eq!(manager.get::<bool>("enable_telemetry"), Some(false));
+                assert!(manager.set("theme", "dark").is_ok());
+            },
+        );
+    }
+
+    fn project_manager(settings: &str) -> (TempDir, PathBuf, ConfigManager) {
+        let temp_dir = TempDir::new().unwrap();
+        let project_file = temp_dir.path().join(".aster").join("settings.yaml");
+        fs::create_dir_all(project_file.parent().unwrap()).unwrap();
+        fs::write(&project_file, settings).unwrap();
+        let manager = ConfigManager::new(ConfigManagerOptions {
+            working_directory: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        });
+        (temp_dir, project_file, manager)
+    }
+
+    #[test]
+    fn test_watch_reports_changed_key() {
+        let (_temp_dir, project_file, manager) = project_manager("theme: light\n");
+        let manager = Arc::new(manager);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        manager
+            .watch(move |event| {
+                let _ = tx.lock().unwrap().send(event.clone());
+            })
+            .unwrap();
+
+        fs::write(
+            &project_file,
+            "theme: dark\nproxy: http://proxy.local:8080\n",
+        )
+        .unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let event = loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let event = rx.recv_timeout(remaining).expect("no reload event");
+            if event.changes.iter().any(|c| c.key == "theme") {
+                break event;
+            }
+        };
+
+        let theme = event.changes.iter().find(|c| c.key == "theme").unwrap();
+        assert_eq!(theme.old_value, Some(Value::from("light")));
+        assert_eq!(theme.new_value, Some(Value::from("dark")));
+        assert!(event.applied.contains(&"theme".to_string()));
+        assert_eq!(manager.get::<String>("theme"), Some("dark".to_string()));
+
+        // 需要重启的配置项只在事件中列出，值和来源记录都不立即生效
+        assert_eq!(event.restart_required, vec!["proxy".to_string()]);
+        assert!(!event.applied.contains(&"proxy".to_string()));
+        let proxy = Some(Value::from("http://proxy.local:8080"));
+        assert_ne!(manager.get_value("proxy"), proxy);
+        assert_ne!(
+            manager.get_config_source("proxy"),
+            Some(ConfigSource::ProjectSettings)
+        );
+        assert!(manager
+            .explain("proxy")
+            .is_none_or(|explanation| explanation
+                .chain
+                .iter()
+                .all(|e| Some(&e.value) != proxy.as_ref())));
+    }
+
+    #[test]
+    fn test_hot_reload_rejects_invalid_config() {
+        let (_temp_dir, project_file, manager) = project_manager("max_tokens: 8192\n");
+
+        fs::write(&project_file, "max_tokens: -5\ntheme: dark\n").unwrap();
+        assert!(manager.hot_reload().is_err());
+        assert_eq!(manager.get::<i64>("max_tokens"), Some(8192));
+        assert_ne!(manager.get::<String>("theme"), Some("dark".to_string()));
+
+        fs::write(&project_file, "max_tokens: [unclosed\n").unwrap();
+        assert!(manager.hot_reload().is_err());
+        assert_eq!(manager.get::<i64>("max_tokens"), Some(8192));
+
+        fs::write(&project_file, "max_tokens: 1024\n").unwrap();
+        let event = manager.hot_reload().unwrap();
+        assert_eq!(event.applied, vec!["max_tokens".to_string()]);
+        assert!(event.restart_required.is_empty());
+        assert_eq!(manager.get::<i64>("max_tokens"), Some(1024));
+    }
+
     #[test]
     fn test_mask_sensitive_fields() {
         let manager = ConfigManager::default();
@@ -1,58 +1,262 @@
 use super::base::Config;
 use anyhow::Result;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::fmt;
 
-/// It is the ground truth for init experiments. The experiment names in users' experiment list but not
-/// in the list will be remove from user list; The experiment names in the ground-truth list but not
-/// in users' experiment list will be added to user list with default value false;
+/// An experiment and the percentage of installs it is rolled out to (0-100)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Experiment {
+    pub name: &'static str,
+    pub rollout_percentage: u8,
+}
+
+/// It is the ground truth for experiments. Overrides in the user's experiment list for names not
+/// in this list are removed; experiments without an override follow their rollout percentage.
 /// TODO: keep this up to date with the experimental-features.md documentation page
-const ALL_EXPERIMENTS: &[(&str, bool)] = &[];
+const ALL_EXPERIMENTS: &[Experiment] = &[];
+
+/// Why an experiment is on or off for this install
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExperimentReason {
+    /// The user forced the experiment on, regardless of rollout
+    ForcedOn,
+    /// The user forced the experiment off, regardless of rollout
+    ForcedOff,
+    /// No override; enabled when the install's bucket falls below the rollout percentage
+    Rollout { percentage: u8, bucket: u8 },
+    /// The experiment is not defined
+    Unknown,
+}
+
+/// The evaluated state of an experiment for this install
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExperimentExplanation {
+    pub name: String,
+    pub enabled: bool,
+    pub reason: ExperimentReason,
+}
+
+impl fmt::Display for ExperimentExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.enabled { "enabled" } else { "disabled" };
+        match &self.reason {
+            ExperimentReason::ForcedOn => {
+                write!(f, "{} is {}: forced on by override", self.name, state)
+            }
+            ExperimentReason::ForcedOff => {
+                write!(f, "{} is {}: forced off by override", self.name, state)
+            }
+            ExperimentReason::Rollout { percentage, bucket } => write!(
+                f,
+                "{} is {}: install bucket {} is {} the {}% rollout",
+                self.name,
+                state,
+                bucket,
+                if self.enabled { "within" } else { "outside" },
+                percentage
+            ),
+            ExperimentReason::Unknown => {
+                write!(f, "{} is {}: unknown experiment", self.name, state)
+            }
+        }
+    }
+}
 
 /// Experiment configuration management
 pub struct ExperimentManager;
 
 impl ExperimentManager {
-    /// Get all experiments and their configurations
+    /// Get all experiments and whether each is enabled for this install
     ///
-    /// - Ensures the user's experiment list is synchronized with `ALL_EXPERIMENTS`.
-    /// - Adds missing experiments from `ALL_EXPERIMENTS` with the default value.
-    /// - Removes experiments not in `ALL_EXPERIMENTS`.
+    /// - Removes overrides for experiments not in `ALL_EXPERIMENTS`.
+    /// - Overrides win; otherwise the experiment follows its rollout percentage.
     pub fn get_all() -> Result<Vec<(String, bool)>> {
-        let config = Config::global();
-        let mut experiments: HashMap<String, bool> =
-            config.get_param("experiments").unwrap_or_default();
-        Self::refresh_experiments(&mut experiments);
+        let overrides = Self::overrides();
+        let install_id = crate::posthog::installation_id();
 
-        Ok(experiments.into_iter().collect())
+        Ok(ALL_EXPERIMENTS
+            .iter()
+            .map(|experiment| {
+                let explanation = Self::evaluate(experiment, &overrides, &install_id);
+                (explanation.name, explanation.enabled)
+            })
+            .collect())
     }
 
-    /// Enable or disable an experiment
+    /// Force an experiment on or off for this user, overriding its rollout
     pub fn set_enabled(name: &str, enabled: bool) -> Result<()> {
-        let config = Config::global();
-        let mut experiments: HashMap<String, bool> = config
-            .get_param("experiments")
-            .unwrap_or_else(|_| HashMap::new());
-        Self::refresh_experiments(&mut experiments);
-        experiments.insert(name.to_string(), enabled);
+        let mut overrides = Self::overrides();
+        overrides.insert(name.to_string(), enabled);
 
-        config.set_param("experiments", experiments)?;
+        Config::global().set_param("experiments", overrides)?;
+        Ok(())
+    }
+
+    /// Remove the user's override so the experiment follows its rollout again
+    pub fn clear_override(name: &str) -> Result<()> {
+        let mut overrides = Self::overrides();
+        overrides.remove(name);
+
+        Config::global().set_param("experiments", overrides)?;
         Ok(())
     }
 
     /// Check if an experiment is enabled
     pub fn is_enabled(name: &str) -> Result<bool> {
-        let experiments = Self::get_all()?;
-        let experiments_map: HashMap<String, bool> = experiments.into_iter().collect();
-        Ok(*experiments_map.get(name).unwrap_or(&false))
+        Ok(Self::explain(name)?.enabled)
     }
 
-    fn refresh_experiments(experiments: &mut HashMap<String, bool>) {
-        // Add missing experiments from `ALL_EXPERIMENTS`
-        for &(key, default_value) in ALL_EXPERIMENTS {
-            experiments.entry(key.to_string()).or_insert(default_value);
+    /// Describe why an experiment is enabled or disabled for this install
+    pub fn explain(name: &str) -> Result<ExperimentExplanation> {
+        Ok(Self::explain_with(
+            ALL_EXPERIMENTS,
+            name,
+            &Self::overrides(),
+            &crate::posthog::installation_id(),
+        ))
+    }
+
+    fn explain_with(
+        experiments: &[Experiment],
+        name: &str,
+        overrides: &HashMap<String, bool>,
+        install_id: &str,
+    ) -> ExperimentExplanation {
+        match experiments.iter().find(|e| e.name == name) {
+            Some(experiment) => Self::evaluate(experiment, overrides, install_id),
+            None => ExperimentExplanation {
+                name: name.to_string(),
+                enabled: false,
+                reason: ExperimentReason::Unknown,
+            },
         }
+    }
+
+    fn evaluate(
+        experiment: &Experiment,
+        overrides: &HashMap<String, bool>,
+        install_id: &str,
+    ) -> ExperimentExplanation {
+        let (enabled, reason) = match overrides.get(experiment.name) {
+            Some(true) => (true, ExperimentReason::ForcedOn),
+            Some(false) => (false, ExperimentReason::ForcedOff),
+            None => {
+                let bucket = Self::bucket(experiment.name, install_id);
+                (
+                    bucket < experiment.rollout_percentage,
+                    ExperimentReason::Rollout {
+                        percentage: experiment.rollout_percentage,
+                        bucket,
+                    },
+                )
+            }
+        };
 
+        ExperimentExplanation {
+            name: experiment.name.to_string(),
+            enabled,
+            reason,
+        }
+    }
+
+    /// Deterministic bucket in 0..100 for an experiment and install id
+    fn bucket(name: &str, install_id: &str) -> u8 {
+        let digest = Sha256::digest(format!("{}:{}", name, install_id).as_bytes());
+        let value = u64::from_be_bytes(digest[..8].try_into().expect("digest has 8 bytes"));
+        (value % 100) as u8
+    }
+
+    fn overrides() -> HashMap<String, bool> {
+        let mut overrides: HashMap<String, bool> = Config::global()
+            .get_param("experiments")
+            .unwrap_or_default();
+        Self::refresh_experiments(&mut overrides);
+        overrides
+    }
+
+    fn refresh_experiments(experiments: &mut HashMap<String, bool>) {
         // Remove experiments not present in `ALL_EXPERIMENTS`
-        experiments.retain(|key, _| ALL_EXPERIMENTS.iter().any(|(k, _)| k == key));
+        experiments.retain(|key, _| ALL_EXPERIMENTS.iter().any(|e| e.name == key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPERIMENTS: &[Experiment] = &[
+        Experiment {
+            name: "everyone",
+            rollout_percentage: 100,
+        },
+        Experiment {
+            name: "nobody",
+            rollout_percentage: 0,
+        },
+    ];
+
+    #[test]
+    fn test_overrides_beat_rollout() {
+        let mut overrides = HashMap::new();
+        overrides.insert("everyone".to_string(), false);
+        overrides.insert("nobody".to_string(), true);
+
+        let everyone = ExperimentManager::explain_with(EXPERIMENTS, "everyone", &overrides, "id");
+        assert!(!everyone.enabled);
+        assert_eq!(everyone.reason, ExperimentReason::ForcedOff);
+
+        let nobody = ExperimentManager::explain_with(EXPERIMENTS, "nobody", &overrides, "id");
+        assert!(nobody.enabled);
+        assert_eq!(nobody.reason, ExperimentReason::ForcedOn);
+        assert_eq!(
+            nobody.to_string(),
+            "nobody is enabled: forced on by override"
+        );
+    }
+
+    #[test]
+    fn test_rollout_without_overrides() {
+        let overrides = HashMap::new();
+        for install_id in ["a", "b", "c", "d"] {
+            let everyone =
+                ExperimentManager::explain_with(EXPERIMENTS, "everyone", &overrides, install_id);
+            assert!(everyone.enabled);
+            assert!(matches!(
+                everyone.reason,
+                ExperimentReason::Rollout {
+                    percentage: 100,
+                    ..
+                }
+            ));
+            assert!(
+                !ExperimentManager::explain_with(EXPERIMENTS, "nobody", &overrides, install_id)
+                    .enabled
+            );
+        }
+
+        let unknown = ExperimentManager::explain_with(EXPERIMENTS, "missing", &overrides, "a");
+        assert!(!unknown.enabled);
+        assert_eq!(unknown.reason, ExperimentReason::Unknown);
+    }
+
+    #[test]
+    fn test_bucket_is_deterministic() {
+        let bucket = ExperimentManager::bucket("exp", "install-1");
+        assert!(bucket < 100);
+        assert_eq!(bucket, ExperimentManager::bucket("exp", "install-1"));
+
+        let partial = Experiment {
+            name: "exp",
+            rollout_percentage: bucket + 1,
+        };
+        let explanation = ExperimentManager::evaluate(&partial, &HashMap::new(), "install-1");
+        assert!(explanation.enabled);
+        let partial = Experiment {
+            name: "exp",
+            rollout_percentage: bucket,
+        };
+        let explanation = ExperimentManager::evaluate(&partial, &HashMap::new(), "install-1");
+        assert!(!explanation.enabled);
     }
 }
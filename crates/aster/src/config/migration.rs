@@ -0,0 +1,269 @@
+//! 配置迁移
+//!
+//! 按版本顺序执行迁移函数，将旧版本配置升级到当前版本，未被迁移触及的键原样保留
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// 配置文件中记录版本号的键
+pub const CONFIG_VERSION_KEY: &str = "config_version";
+
+/// 当前配置版本
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// 迁移函数：原地修改配置
+pub type ConfigMigrationFn = fn(&mut HashMap<String, Value>) -> Result<(), String>;
+
+/// 单个迁移步骤（从 `from_version` 升级到 `from_version + 1`）
+#[derive(Debug, Clone)]
+pub struct ConfigMigration {
+    /// 起始版本
+    pub from_version: u32,
+    /// 迁移说明
+    pub description: &'static str,
+    /// 迁移函数
+    pub migrate: ConfigMigrationFn,
+}
+
+/// 已执行的迁移
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AppliedMigration {
+    /// 起始版本
+    pub from_version: u32,
+    /// 目标版本
+    pub to_version: u32,
+    /// 迁移说明
+    pub description: String,
+}
+
+/// 迁移报告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// 配置文件路径（内存迁移时为 None）
+    pub path: Option<PathBuf>,
+    /// 迁移前版本
+    pub from_version: u32,
+    /// 迁移后版本
+    pub to_version: u32,
+    /// 按顺序执行的迁移
+    pub applied: Vec<AppliedMigration>,
+    /// 迁移前文件的备份路径
+    pub backup_path: Option<PathBuf>,
+}
+
+impl MigrationReport {
+    /// 是否执行了迁移
+    pub fn migrated(&self) -> bool {
+        !self.applied.is_empty()
+    }
+}
+
+/// 迁移错误
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigMigrationError {
+    /// 配置版本高于当前支持的版本
+    #[error("配置版本 {found} 高于支持的版本 {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// 缺少某个版本的迁移
+    #[error("缺少从版本 {0} 开始的迁移")]
+    MissingMigration(u32),
+    /// 迁移函数执行失败
+    #[error("从版本 {from_version} 开始的迁移失败: {message}")]
+    Failed { from_version: u32, message: String },
+}
+
+/// 配置迁移器
+#[derive(Debug, Clone)]
+pub struct ConfigMigrator {
+    target_version: u32,
+    migrations: Vec<ConfigMigration>,
+}
+
+impl Default for ConfigMigrator {
+    fn default() -> Self {
+        Self::new(CURRENT_CONFIG_VERSION)
+    }
+}
+
+impl ConfigMigrator {
+    /// 创建迁移器，目标版本为 `target_version`
+    pub fn new(target_version: u32) -> Self {
+        Self {
+            target_version,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// 注册迁移步骤
+    pub fn with_migration(
+        mut self,
+        from_version: u32,
+        description: &'static str,
+        migrate: ConfigMigrationFn,
+    ) -> Self {
+        self.migrations.push(ConfigMigration {
+            from_version,
+            description,
+            migrate,
+        });
+        self.migrations.sort_by_key(|m| m.from_version);
+        self
+    }
+
+    /// 目标版本
+    pub fn target_version(&self) -> u32 {
+        self.target_version
+    }
+
+    /// 读取配置版本，未记录版本时视为 1
+    pub fn config_version(config: &HashMap<String, Value>) -> u32 {
+        config
+            .get(CONFIG_VERSION_KEY)
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or(1)
+    }
+
+    /// 原地迁移配置到目标版本
+    ///
+    /// 任一迁移失败时配置保持原样
+    pub fn migrate(
+        &self,
+        config: &mut HashMap<String, Value>,
+    ) -> Result<MigrationReport, ConfigMigrationError> {
+        let from_version = Self::config_version(config);
+        if from_version > self.target_version {
+            return Err(ConfigMigrationError::UnsupportedVersion {
+                found: from_version,
+                supported: self.target_version,
+            });
+        }
+
+        let mut migrated = config.clone();
+        let mut applied = Vec::new();
+        let mut version = from_version;
+        while version < self.target_version {
+            let migration = self
+                .migrations
+                .iter()
+                .find(|m| m.from_version == version)
+                .ok_or(ConfigMigrationError::MissingMigration(version))?;
+
+            (migration.migrate)(&mut migrated).map_err(|message| ConfigMigrationError::Failed {
+                from_version: version,
+                message,
+            })?;
+
+            version += 1;
+            migrated.insert(CONFIG_VERSION_KEY.to_string(), Value::from(version));
+            applied.push(AppliedMigration {
+                from_version: version - 1,
+                to_version: version,
+                description: migration.description.to_string(),
+            });
+        }
+
+        *config = migrated;
+        Ok(MigrationReport {
+            path: None,
+            from_version,
+            to_version: version,
+            applied,
+            backup_path: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_model_key(config: &mut HashMap<String, Value>) -> Result<(), String> {
+        if let Some(model) = config.remove("default_model") {
+            config.insert("model".to_string(), model);
+        }
+        Ok(())
+    }
+
+    fn nest_provider_settings(config: &mut HashMap<String, Value>) -> Result<(), String> {
+        let host = config.remove("provider_host");
+        let model = config.remove("model");
+        config.insert(
+            "provider".to_string(),
+            json!({ "host": host, "model": model }),
+        );
+        Ok(())
+    }
+
+    fn test_migrator() -> ConfigMigrator {
+        ConfigMigrator::new(3)
+            .with_migration(2, "nest provider settings", nest_provider_settings)
+            .with_migration(1, "rename default_model to model", rename_model_key)
+    }
+
+    #[test]
+    fn test_migrate_v1_to_v3() {
+        let mut config: HashMap<String, Value> = serde_json::from_value(json!({
+            "default_model": "claude-3",
+            "provider_host": "https://api.example.com",
+            "custom_key": { "keep": true }
+        }))
+        .unwrap();
+
+        let report = test_migrator().migrate(&mut config).unwrap();
+
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.to_version, 3);
+        let descriptions: Vec<_> = report
+            .applied
+            .iter()
+            .map(|m| m.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec!["rename default_model to model", "nest provider settings"]
+        );
+        assert_eq!(config[CONFIG_VERSION_KEY], json!(3));
+        assert_eq!(
+            config["provider"],
+            json!({ "host": "https://api.example.com", "model": "claude-3" })
+        );
+        assert_eq!(config["custom_key"], json!({ "keep": true }));
+        assert!(!config.contains_key("default_model"));
+    }
+
+    #[test]
+    fn test_migrate_current_version_is_noop() {
+        let mut config: HashMap<String, Value> =
+            serde_json::from_value(json!({ "config_version": 3, "a": 1 })).unwrap();
+        let report = test_migrator().migrate(&mut config).unwrap();
+        assert!(!report.migrated());
+        assert_eq!(config.len(), 2);
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_version() {
+        let mut config: HashMap<String, Value> =
+            serde_json::from_value(json!({ "config_version": 4 })).unwrap();
+        assert!(matches!(
+            test_migrator().migrate(&mut config),
+            Err(ConfigMigrationError::UnsupportedVersion { found: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_failed_migration_leaves_config_untouched() {
+        let migrator = ConfigMigrator::new(3)
+            .with_migration(1, "rename default_model to model", rename_model_key)
+            .with_migration(2, "always fails", |_| Err("boom".to_string()));
+        let mut config: HashMap<String, Value> =
+            serde_json::from_value(json!({ "default_model": "claude-3" })).unwrap();
+
+        let err = migrator.migrate(&mut config).unwrap_err();
+        assert!(err.to_string().contains("boom"));
+        assert!(config.contains_key("default_model"));
+    }
+}
@@ -6,6 +6,7 @@ pub mod config_manager;
 pub mod declarative_providers;
 mod experiments;
 pub mod extensions;
+pub mod migration;
 pub mod paths;
 pub mod permission;
 pub mod search_path;
@@ -16,7 +17,7 @@ pub use crate::agents::ExtensionConfig;
 pub use aster_mode::AsterMode;
 pub use base::{Config, ConfigError};
 pub use declarative_providers::DeclarativeProviderConfig;
-pub use experiments::ExperimentManager;
+pub use experiments::{Experiment, ExperimentExplanation, ExperimentManager, ExperimentReason};
 pub use extensions::{
     get_all_extension_names, get_all_extensions, get_enabled_extensions, get_extension_by_name,
     get_warnings, is_extension_enabled, remove_extension, set_extension, set_extension_enabled,
@@ -31,10 +32,15 @@ pub use config_command::{
     create_config_command, ConfigCommand, ConfigDisplayOptions, ConfigFormat,
 };
 pub use config_manager::{
-    ConfigKeySource, ConfigManager, ConfigManagerOptions, ConfigSource, ConfigSourceInfo,
-    EnterprisePolicyConfig, PolicyMetadata,
+    ConfigChangeEvent, ConfigExplanation, ConfigKeyChange, ConfigKeySource, ConfigLockedError,
+    ConfigManager, ConfigManagerOptions, ConfigSource, ConfigSourceInfo, EnterprisePolicyConfig,
+    PolicyMetadata, RESTART_REQUIRED_KEYS,
 };
 pub use extensions::DEFAULT_DISPLAY_NAME;
 pub use extensions::DEFAULT_EXTENSION;
 pub use extensions::DEFAULT_EXTENSION_DESCRIPTION;
 pub use extensions::DEFAULT_EXTENSION_TIMEOUT;
+pub use migration::{
+    AppliedMigration, ConfigMigration, ConfigMigrationError, ConfigMigrator, MigrationReport,
+    CONFIG_VERSION_KEY, CURRENT_CONFIG_VERSION,
+};
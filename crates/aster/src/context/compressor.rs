@@ -20,9 +20,12 @@
 
 use crate::context::pruner::ProgressivePruner;
 use crate::context::token_estimator::TokenEstimator;
-use crate::context::types::{CodeBlock, CompressionConfig, CompressionResult, PruningConfig};
+use crate::context::types::{
+    CodeBlock, CompressionConfig, CompressionResult, PruningConfig, ToolOutputFormat,
+};
 use crate::conversation::message::{Message, MessageContent};
 use regex::Regex;
+use serde_json::Value;
 use std::sync::LazyLock;
 
 // ============================================================================
@@ -53,6 +56,17 @@ const OMISSION_MARKER: &str = "\n... [content omitted] ...\n";
 static CODE_BLOCK_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"```(\w*)\n([\s\S]*?)```").expect("Invalid code block regex"));
 
+/// Regex for detecting stack frame lines (Java/JS, Python, Rust, gdb)
+static STACK_FRAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*(?:at\s|File\s"|#\d+\s|\d+:\s)"#).expect("Invalid stack frame regex")
+});
+
+/// (max array items, max string chars) tried in order when shrinking JSON
+const JSON_SHRINK_LEVELS: [(usize, usize); 4] = [(10, 200), (5, 100), (3, 50), (1, 20)];
+
+/// Column delimiters recognized when sniffing tabular output
+const TABLE_DELIMITERS: [char; 3] = ['|', '\t', ','];
+
 /// Regex for detecting file paths
 static FILE_PATH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?:^|\s)([./~]?(?:[\w.-]+/)+[\w.-]+\.\w+)").expect("Invalid file path regex")
@@ -308,6 +322,242 @@ impl MessageCompressor {
         }
     }
 
+    // ========================================================================
+    // Structured Tool Output Compression
+    // ========================================================================
+
+    /// Compress tool output while keeping its structure.
+    ///
+    /// Instead of cutting the text in the middle, the compression strategy
+    /// depends on the format:
+    ///
+    /// - JSON keeps every key but shortens long arrays and strings, replacing
+    ///   the rest with a `"...N more"` marker
+    /// - Tables keep the header and as many leading rows as fit
+    /// - Stack traces keep the error message and the top frames
+    /// - Plain text falls back to [`Self::compress_tool_output`]
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The tool output content to compress
+    /// * `max_chars` - Maximum characters to keep
+    /// * `format_hint` - The format if known; sniffed from the content otherwise
+    ///
+    /// # Returns
+    ///
+    /// The compressed content. If within limits, returns unchanged.
+    pub fn compress_structured_tool_output(
+        content: &str,
+        max_chars: usize,
+        format_hint: Option<ToolOutputFormat>,
+    ) -> String {
+        if content.len() <= max_chars {
+            return content.to_string();
+        }
+
+        match format_hint.unwrap_or_else(|| Self::detect_tool_output_format(content)) {
+            ToolOutputFormat::Json => Self::compress_json_output(content, max_chars),
+            ToolOutputFormat::Table => Self::compress_table_output(content, max_chars),
+            ToolOutputFormat::StackTrace => Self::compress_stack_trace(content, max_chars),
+            ToolOutputFormat::Plain => Self::compress_tool_output(content, max_chars),
+        }
+    }
+
+    /// Guess the format of a tool output.
+    pub fn detect_tool_output_format(content: &str) -> ToolOutputFormat {
+        let trimmed = content.trim_start();
+        if (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && serde_json::from_str::<Value>(content).is_ok()
+        {
+            return ToolOutputFormat::Json;
+        }
+
+        let lines: Vec<&str> = content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .take(20)
+            .collect();
+
+        let frames = lines
+            .iter()
+            .filter(|l| STACK_FRAME_REGEX.is_match(l))
+            .count();
+        let python_traceback = lines.iter().any(|l| {
+            l.trim_start()
+                .starts_with("Traceback (most recent call last):")
+        });
+        if frames >= 3 || (python_traceback && frames > 0) {
+            return ToolOutputFormat::StackTrace;
+        }
+
+        if lines.len() >= 3 {
+            let is_table = TABLE_DELIMITERS.iter().any(|&delimiter| {
+                let columns = lines[0].matches(delimiter).count();
+                let consistent = lines
+                    .iter()
+                    .filter(|l| l.matches(delimiter).count() == columns)
+                    .count();
+                columns > 0 && consistent * 5 >= lines.len() * 4
+            });
+            if is_table {
+                return ToolOutputFormat::Table;
+            }
+        }
+
+        ToolOutputFormat::Plain
+    }
+
+    /// Shorten arrays and strings until the JSON fits.
+    fn compress_json_output(content: &str, max_chars: usize) -> String {
+        let Ok(value) = serde_json::from_str::<Value>(content) else {
+            return Self::compress_tool_output(content, max_chars);
+        };
+
+        let mut smallest = String::new();
+        for (max_items, max_string) in JSON_SHRINK_LEVELS {
+            smallest = Self::shrink_json(&value, max_items, max_string).to_string();
+            if smallest.len() <= max_chars {
+                return smallest;
+            }
+        }
+        // Even the smallest shape is too large
+        Self::compress_tool_output(&smallest, max_chars)
+    }
+
+    fn shrink_json(value: &Value, max_items: usize, max_string: usize) -> Value {
+        match value {
+            Value::Array(items) => {
+                let mut kept: Vec<Value> = items
+                    .iter()
+                    .take(max_items)
+                    .map(|v| Self::shrink_json(v, max_items, max_string))
+                    .collect();
+                if items.len() > max_items {
+                    kept.push(Value::String(format!(
+                        "...{} more",
+                        items.len() - max_items
+                    )));
+                }
+                Value::Array(kept)
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), Self::shrink_json(v, max_items, max_string)))
+                    .collect(),
+            ),
+            Value::String(s) => {
+                let chars = s.chars().count();
+                if chars > max_string {
+                    let head: String = s.chars().take(max_string).collect();
+                    Value::String(format!("{}...{} more", head, chars - max_string))
+                } else {
+                    value.clone()
+                }
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Keep the header and as many leading rows as fit.
+    fn compress_table_output(content: &str, max_chars: usize) -> String {
+        let lines: Vec<&str> = content.lines().collect();
+
+        // The header ends at the first separator line (`|---|`, `+---+`), if any
+        let header_len = lines
+            .iter()
+            .take(3)
+            .rposition(|l| Self::is_table_separator(l))
+            .map(|i| i + 1)
+            .unwrap_or(1)
+            .min(lines.len());
+        let (header, rows) = lines.split_at(header_len);
+
+        let mut result = header.join("\n");
+        // Leave room for the omission marker
+        if result.len() + 40 > max_chars {
+            return Self::compress_tool_output(content, max_chars);
+        }
+        let mut kept = 0;
+        for row in rows {
+            if result.len() + row.len() + 40 > max_chars {
+                break;
+            }
+            result.push('\n');
+            result.push_str(row);
+            kept += 1;
+        }
+
+        if kept < rows.len() {
+            result.push_str(&format!("\n... [{} more rows] ...", rows.len() - kept));
+        }
+        result
+    }
+
+    fn is_table_separator(line: &str) -> bool {
+        let trimmed = line.trim();
+        trimmed.contains('-') && trimmed.chars().all(|c| "-+|=: \t".contains(c))
+    }
+
+    /// Keep the error message and the top frames.
+    fn compress_stack_trace(content: &str, max_chars: usize) -> String {
+        let lines: Vec<&str> = content.lines().collect();
+        let Some(first_frame) = lines.iter().position(|l| STACK_FRAME_REGEX.is_match(l)) else {
+            return Self::compress_tool_output(content, max_chars);
+        };
+        // Lines after the last frame usually carry the error (Python) or a cause
+        let last_frame = lines
+            .iter()
+            .rposition(|l| STACK_FRAME_REGEX.is_match(l))
+            .unwrap_or(first_frame);
+
+        // The message and trailer each get at most a quarter of the budget
+        let message = Self::bound_part(lines[..first_frame].join("\n"), max_chars / 4);
+        let trailer = Self::bound_part(lines[last_frame + 1..].join("\n"), max_chars / 4);
+        let frames = &lines[first_frame..=last_frame];
+
+        let mut kept = Vec::new();
+        let mut used = message.len() + trailer.len() + 40;
+        for frame in frames {
+            if used + frame.len() + 1 > max_chars && !kept.is_empty() {
+                break;
+            }
+            used += frame.len() + 1;
+            kept.push(*frame);
+        }
+
+        let mut result = message;
+        for frame in &kept {
+            if !result.is_empty() {
+                result.push('\n');
+            }
+            result.push_str(frame);
+        }
+        if kept.len() < frames.len() {
+            result.push_str(&format!(
+                "\n... [{} more frames] ...",
+                frames.len() - kept.len()
+            ));
+        }
+        if !trailer.is_empty() {
+            result.push('\n');
+            result.push_str(&trailer);
+        }
+        // A single huge frame can still overflow
+        if result.len() > max_chars {
+            return Self::compress_tool_output(content, max_chars);
+        }
+        result
+    }
+
+    /// Shorten a block of text to roughly `budget` characters.
+    fn bound_part(text: String, budget: usize) -> String {
+        if text.len() <= budget {
+            text
+        } else {
+            Self::compress_tool_output(&text, budget.saturating_sub(40))
+        }
+    }
+
     /// Extract file path references from text.
     ///
     /// Detects file paths in various formats (relative, absolute, home-relative).
@@ -391,9 +641,10 @@ impl MessageCompressor {
                     .iter()
                     .map(|c| {
                         if let RawContent::Text(text) = &c.raw {
-                            let compressed = Self::compress_tool_output(
+                            let compressed = Self::compress_structured_tool_output(
                                 &text.text,
                                 config.tool_output_max_chars,
+                                None,
                             );
                             Content {
                                 raw: RawContent::Text(RawTextContent {
@@ -768,6 +1019,137 @@ print("world")
         assert!(result.ends_with("AAAA"));
     }
 
+    #[test]
+    fn test_compress_json_output_keeps_shape() {
+        let items: Vec<Value> = (0..200)
+            .map(|i| {
+                serde_json::json!({
+                    "id": i,
+                    "name": format!("item-{}", i),
+                    "description": "a very long description ".repeat(20),
+                })
+            })
+            .collect();
+        let content = serde_json::json!({ "total": 200, "items": items }).to_string();
+
+        assert_eq!(
+            MessageCompressor::detect_tool_output_format(&content),
+            ToolOutputFormat::Json
+        );
+        let result = MessageCompressor::compress_structured_tool_output(&content, 2000, None);
+
+        assert!(result.len() <= 2000);
+        let value: Value = serde_json::from_str(&result).expect("still valid JSON");
+        assert_eq!(value["total"], 200);
+        let kept = value["items"].as_array().unwrap();
+        assert_eq!(kept[0]["name"], "item-0");
+        assert!(kept[0]["description"].as_str().unwrap().contains(" more"));
+        let marker = kept.last().unwrap().as_str().unwrap();
+        assert_eq!(marker, format!("...{} more", 200 - (kept.len() - 1)));
+    }
+
+    #[test]
+    fn test_compress_table_output_keeps_header() {
+        let mut content = String::from("| name | status | age |\n|------|--------|-----|\n");
+        for i in 0..300 {
+            content.push_str(&format!("| pod-{} | Running | {}m |\n", i, i));
+        }
+
+        assert_eq!(
+            MessageCompressor::detect_tool_output_format(&content),
+            ToolOutputFormat::Table
+        );
+        let result = MessageCompressor::compress_structured_tool_output(&content, 500, None);
+
+        assert!(result.len() <= 500);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines[0], "| name | status | age |");
+        assert_eq!(lines[1], "|------|--------|-----|");
+        assert_eq!(lines[2], "| pod-0 | Running | 0m |");
+        let shown = lines.len() - 3;
+        assert_eq!(
+            *lines.last().unwrap(),
+            format!("... [{} more rows] ...", 300 - shown)
+        );
+
+        // CSV without a separator line, given as a hint
+        let csv: String = std::iter::once("id,name\n".to_string())
+            .chain((0..300).map(|i| format!("{},user-{}\n", i, i)))
+            .collect();
+        let result = MessageCompressor::compress_structured_tool_output(
+            &csv,
+            200,
+            Some(ToolOutputFormat::Table),
+        );
+        assert!(result.starts_with("id,name\n0,user-0\n"));
+        assert!(result.ends_with("more rows] ..."));
+
+        // A header wider than the budget falls back to plain compression
+        let wide: String = std::iter::once(format!("{}\n", vec!["column"; 500].join(",")))
+            .chain((0..10).map(|i| format!("{}\n", vec![i.to_string(); 500].join(","))))
+            .collect();
+        let result = MessageCompressor::compress_structured_tool_output(
+            &wide,
+            200,
+            Some(ToolOutputFormat::Table),
+        );
+        assert!(result.contains("characters omitted"));
+        assert!(result.len() < 300);
+    }
+
+    #[test]
+    fn test_compress_stack_trace_keeps_top_frames() {
+        let mut content = String::from("java.lang.IllegalStateException: boom\n");
+        for i in 0..200 {
+            content.push_str(&format!(
+                "\tat com.example.Service.call{}(Service.java:{})\n",
+                i, i
+            ));
+        }
+
+        assert_eq!(
+            MessageCompressor::detect_tool_output_format(&content),
+            ToolOutputFormat::StackTrace
+        );
+        let result = MessageCompressor::compress_structured_tool_output(&content, 400, None);
+
+        assert!(result.len() <= 400);
+        assert!(result
+            .starts_with("java.lang.IllegalStateException: boom\n\tat com.example.Service.call0("));
+        assert!(result.ends_with("more frames] ..."));
+    }
+
+    #[test]
+    fn test_compress_stack_trace_bounds_message_and_trailer() {
+        let mut content = format!("Error: {}\n", "x".repeat(5000));
+        for i in 0..20 {
+            content.push_str(&format!("    at handler{} (server.js:{}:7)\n", i, i));
+        }
+        content.push_str(&format!("Caused by: {}\n", "y".repeat(5000)));
+
+        let result = MessageCompressor::compress_structured_tool_output(&content, 1000, None);
+
+        assert!(result.len() <= 1000, "{}", result.len());
+        assert!(result.contains("at handler0 (server.js:0:7)"));
+    }
+
+    #[test]
+    fn test_traceback_mention_is_not_a_stack_trace() {
+        let content =
+            "The docs explain how to read a \"Traceback (most recent call last)\" header.\n\
+                       It lists the innermost call last.\n";
+        assert_eq!(
+            MessageCompressor::detect_tool_output_format(content),
+            ToolOutputFormat::Plain
+        );
+
+        let traceback = "Traceback (most recent call last):\n  File \"app.py\", line 3, in <module>\n    main()\nValueError: bad\n";
+        assert_eq!(
+            MessageCompressor::detect_tool_output_format(traceback),
+            ToolOutputFormat::StackTrace
+        );
+    }
+
     #[test]
     fn test_extract_file_references() {
         let text = "Check src/main.rs and ./lib/utils.ts for details";
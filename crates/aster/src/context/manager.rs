@@ -30,8 +30,9 @@ use crate::context::compressor::MessageCompressor;
 use crate::context::summarizer::{Summarizer, SummarizerClient, DEFAULT_SUMMARY_BUDGET};
 use crate::context::token_estimator::TokenEstimator;
 use crate::context::types::{
-    CompressionConfig, CompressionDetails, CompressionResult, ContextConfig, ContextError,
-    ContextExport, ContextStats, ContextUsage, ConversationTurn, TokenUsage,
+    AdaptiveCompressionReport, CompressionConfig, CompressionDetails, CompressionResult,
+    CompressionStep, CompressionStrategy, ContextConfig, ContextError, ContextExport, ContextStats,
+    ContextUsage, ConversationTurn, TokenUsage,
 };
 use crate::conversation::message::{Message, MessageContent};
 use std::sync::Arc;
@@ -46,6 +47,12 @@ const TOOL_REFERENCE_PLACEHOLDER: &str = "[Tool reference collapsed]";
 /// Summary message prefix
 const SUMMARY_PREFIX: &str = "[Previous conversation summary]\n";
 
+/// Shortest code block limit adaptive compression goes down to
+const MIN_CODE_BLOCK_LINES: usize = 6;
+
+/// Shortest tool output limit adaptive compression goes down to
+const MIN_TOOL_OUTPUT_CHARS: usize = 200;
+
 // ============================================================================
 // EnhancedContextManager
 // ============================================================================
@@ -65,6 +72,9 @@ pub struct EnhancedContextManager {
     /// System prompt for the conversation
     system_prompt: String,
 
+    /// Messages kept in context regardless of compression
+    pinned: Vec<Message>,
+
     /// Number of compression operations performed
     compression_count: usize,
 
@@ -94,6 +104,7 @@ impl EnhancedContextManager {
             config,
             turns: Vec::new(),
             system_prompt: String::new(),
+            pinned: Vec::new(),
             compression_count: 0,
             saved_tokens: 0,
             summarizer_client: None,
@@ -119,6 +130,23 @@ impl EnhancedContextManager {
         &self.system_prompt
     }
 
+    /// Pin a message so it is always sent, right after the system prompt.
+    ///
+    /// Pinned messages are never compressed or summarized.
+    pub fn pin_message(&mut self, message: Message) {
+        self.pinned.push(message);
+    }
+
+    /// Get the pinned messages.
+    pub fn pinned_messages(&self) -> &[Message] {
+        &self.pinned
+    }
+
+    /// Remove all pinned messages.
+    pub fn clear_pinned(&mut self) {
+        self.pinned.clear();
+    }
+
     /// Set the summarizer client for AI-powered summarization.
     ///
     /// # Arguments
@@ -211,6 +239,29 @@ impl EnhancedContextManager {
         &mut self.turns
     }
 
+    /// Estimate API usage for turns that have none.
+    ///
+    /// Turns recorded without usage (e.g. from older sessions) get input and
+    /// output counts from the token estimator and are flagged as estimated.
+    /// Turns that already have usage are left untouched, so calling this
+    /// repeatedly is a no-op.
+    ///
+    /// # Returns
+    ///
+    /// The number of turns that were filled in.
+    pub fn backfill_token_counts(&mut self) -> usize {
+        let mut filled = 0;
+        for turn in self.turns.iter_mut().filter(|t| t.api_usage.is_none()) {
+            turn.api_usage = Some(TokenUsage::new(
+                TokenEstimator::estimate_message_tokens(&turn.user),
+                TokenEstimator::estimate_message_tokens(&turn.assistant),
+            ));
+            turn.usage_estimated = true;
+            filled += 1;
+        }
+        filled
+    }
+
     // ========================================================================
     // Message Retrieval (Task 14.1)
     // ========================================================================
@@ -219,8 +270,9 @@ impl EnhancedContextManager {
     ///
     /// Returns messages in the correct order for sending to an LLM:
     /// 1. System prompt (if set)
-    /// 2. Summary of old turns (if any are summarized)
-    /// 3. All conversation turns (user/assistant pairs)
+    /// 2. Pinned messages
+    /// 3. Summary of old turns (if any are summarized)
+    /// 4. All conversation turns (user/assistant pairs)
     ///
     /// # Returns
     ///
@@ -233,6 +285,8 @@ impl EnhancedContextManager {
             messages.push(Message::user().with_text(&self.system_prompt));
         }
 
+        messages.extend(self.pinned.iter().cloned());
+
         // Check if we have any summarized turns
         let summarized_turns: Vec<&ConversationTurn> =
             self.turns.iter().filter(|t| t.summarized).collect();
@@ -283,8 +337,9 @@ impl EnhancedContextManager {
     /// Includes system prompt tokens and all turn tokens.
     pub fn get_used_tokens(&self) -> usize {
         let system_tokens = TokenEstimator::estimate_tokens(&self.system_prompt);
+        let pinned_tokens = TokenEstimator::estimate_total_tokens(&self.pinned);
         let turn_tokens: usize = self.turns.iter().map(|t| t.token_estimate).sum();
-        system_tokens + turn_tokens
+        system_tokens + pinned_tokens + turn_tokens
     }
 
     /// Get the number of available tokens (max - used).
@@ -364,17 +419,7 @@ impl EnhancedContextManager {
             .collect();
 
         // Generate summary
-        let summary = if self.has_summarizer_client() {
-            let client = self.summarizer_client.as_ref().unwrap();
-            Summarizer::generate_ai_summary(
-                &turns_for_summary,
-                client.as_ref(),
-                DEFAULT_SUMMARY_BUDGET,
-            )
-            .await?
-        } else {
-            Summarizer::create_simple_summary(&turns_for_summary)
-        };
+        let summary = self.generate_summary(&turns_for_summary).await?;
 
         // Calculate tokens saved
         let original_tokens: usize = turns_for_summary.iter().map(|t| t.token_estimate).sum();
@@ -393,18 +438,165 @@ impl EnhancedContextManager {
         Ok(())
     }
 
+    /// Summarize turns with AI if available, otherwise with simple extraction.
+    async fn generate_summary(&self, turns: &[ConversationTurn]) -> Result<String, ContextError> {
+        if self.has_summarizer_client() {
+            let client = self.summarizer_client.as_ref().unwrap();
+            Summarizer::generate_ai_summary(turns, client.as_ref(), DEFAULT_SUMMARY_BUDGET).await
+        } else {
+            Ok(Summarizer::create_simple_summary(turns))
+        }
+    }
+
+    // ========================================================================
+    // Adaptive Compression
+    // ========================================================================
+
+    /// Compress the context until it uses at most `target_tokens`.
+    ///
+    /// Strategies are applied cheapest first, each at increasing strength:
+    /// long code blocks are shortened, then tool outputs are truncated, then
+    /// the oldest turns are summarized one at a time. The system prompt,
+    /// pinned messages and the most recent turn are never summarized; if the
+    /// target cannot be reached without them, compression stops early and
+    /// the report has `reached_target: false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_tokens` - Token budget to compress to
+    ///
+    /// # Returns
+    ///
+    /// A report with every step that reclaimed tokens.
+    pub async fn compress_to(
+        &mut self,
+        target_tokens: usize,
+    ) -> Result<AdaptiveCompressionReport, ContextError> {
+        let initial_tokens = self.get_used_tokens();
+        let mut steps = Vec::new();
+
+        // Nothing can get below the system prompt and pinned messages
+        let floor = TokenEstimator::estimate_tokens(&self.system_prompt)
+            + TokenEstimator::estimate_total_tokens(&self.pinned);
+
+        if floor <= target_tokens {
+            for max_lines in
+                Self::strength_levels(self.config.code_block_max_lines, MIN_CODE_BLOCK_LINES)
+            {
+                if self.get_used_tokens() <= target_tokens {
+                    break;
+                }
+                let before = self.get_used_tokens();
+                self.compress_turns(&CompressionConfig {
+                    code_block_max_lines: max_lines,
+                    tool_output_max_chars: usize::MAX,
+                    ..Default::default()
+                });
+                self.record_step(CompressionStrategy::CodeBlocks, before, &mut steps);
+            }
+
+            for max_chars in
+                Self::strength_levels(self.config.tool_output_max_chars, MIN_TOOL_OUTPUT_CHARS)
+            {
+                if self.get_used_tokens() <= target_tokens {
+                    break;
+                }
+                let before = self.get_used_tokens();
+                self.compress_turns(&CompressionConfig {
+                    code_block_max_lines: usize::MAX,
+                    tool_output_max_chars: max_chars,
+                    ..Default::default()
+                });
+                self.record_step(CompressionStrategy::ToolOutput, before, &mut steps);
+            }
+
+            while self.get_used_tokens() > target_tokens {
+                let keep_from = self.turns.len().saturating_sub(1);
+                let Some(index) = self.turns[..keep_from].iter().position(|t| !t.summarized) else {
+                    break;
+                };
+                let before = self.get_used_tokens();
+                let turn = self.turns[index].clone();
+                let summary = self.generate_summary(std::slice::from_ref(&turn)).await?;
+                let summary_tokens = TokenEstimator::estimate_tokens(&summary);
+                self.turns[index].mark_summarized(summary, summary_tokens);
+                self.record_step(CompressionStrategy::Summarization, before, &mut steps);
+            }
+        }
+
+        let final_tokens = self.get_used_tokens();
+        Ok(AdaptiveCompressionReport {
+            target_tokens,
+            initial_tokens,
+            final_tokens,
+            steps,
+            reached_target: final_tokens <= target_tokens,
+        })
+    }
+
+    /// Limits from `start` down to `min`, halving each time.
+    fn strength_levels(start: usize, min: usize) -> Vec<usize> {
+        let mut levels = vec![start.max(min)];
+        let mut level = start / 2;
+        while level >= min {
+            levels.push(level);
+            level /= 2;
+        }
+        levels
+    }
+
+    /// Compress every unsummarized turn with the given limits.
+    fn compress_turns(&mut self, config: &CompressionConfig) {
+        for turn in self.turns.iter_mut().filter(|t| !t.summarized) {
+            let user = MessageCompressor::compress_message(&turn.user, config);
+            let assistant = MessageCompressor::compress_message(&turn.assistant, config);
+            let tokens = TokenEstimator::estimate_message_tokens(&user)
+                + TokenEstimator::estimate_message_tokens(&assistant);
+            if tokens < turn.token_estimate {
+                turn.user = user;
+                turn.assistant = assistant;
+                turn.mark_compressed(tokens);
+            }
+        }
+    }
+
+    /// Record a step if it reclaimed tokens.
+    fn record_step(
+        &mut self,
+        strategy: CompressionStrategy,
+        tokens_before: usize,
+        steps: &mut Vec<CompressionStep>,
+    ) {
+        let tokens_after = self.get_used_tokens();
+        if tokens_after < tokens_before {
+            self.compression_count += 1;
+            self.saved_tokens += tokens_before - tokens_after;
+            steps.push(CompressionStep {
+                strategy,
+                tokens_before,
+                tokens_after,
+            });
+        }
+    }
+
     // ========================================================================
     // Export/Import (Task 14.4)
     // ========================================================================
 
     /// Export the context state for persistence.
     ///
+    /// The export holds everything needed to continue the conversation in a
+    /// fresh manager: system prompt, pinned messages, turns with their usage,
+    /// configuration and compression statistics. Use `ContextExport::to_json`
+    /// to hand it to another session or machine.
+    ///
     /// # Returns
     ///
-    /// A ContextExport struct that can be serialized.
+    /// A versioned ContextExport struct that can be serialized.
     pub fn export(&self) -> ContextExport {
         ContextExport::new(
             self.system_prompt.clone(),
+            self.pinned.clone(),
             self.turns.clone(),
             self.config.clone(),
             self.compression_count,
@@ -414,33 +606,43 @@ impl EnhancedContextManager {
 
     /// Import context state from an export.
     ///
-    /// Replaces the current state with the imported data.
+    /// Replaces the current state with the imported data. The export is
+    /// validated first; on error the current state is left unchanged.
     ///
     /// # Arguments
     ///
     /// * `data` - The exported context data to import
-    pub fn import(&mut self, data: ContextExport) {
+    ///
+    /// # Errors
+    ///
+    /// `ContextError::IncompatibleExport` if the export has another format
+    /// version or malformed turns.
+    pub fn import(&mut self, data: ContextExport) -> Result<(), ContextError> {
+        data.validate()?;
         self.system_prompt = data.system_prompt;
+        self.pinned = data.pinned;
         self.turns = data.turns;
         self.config = data.config;
         self.compression_count = data.compression_count;
         self.saved_tokens = data.saved_tokens;
+        Ok(())
     }
 
     /// Clear all conversation history.
     ///
-    /// Resets turns and statistics but preserves configuration
-    /// and system prompt.
+    /// Resets turns and statistics but preserves configuration,
+    /// system prompt and pinned messages.
     pub fn clear(&mut self) {
         self.turns.clear();
         self.compression_count = 0;
         self.saved_tokens = 0;
     }
 
-    /// Clear everything including system prompt.
+    /// Clear everything including system prompt and pinned messages.
     pub fn reset(&mut self) {
         self.clear();
         self.system_prompt.clear();
+        self.pinned.clear();
     }
 
     // ========================================================================
@@ -749,6 +951,37 @@ mod tests {
         assert_eq!(turn.api_usage.as_ref().unwrap().input_tokens, 10);
     }
 
+    #[test]
+    fn test_backfill_token_counts() {
+        let mut manager = EnhancedContextManager::default();
+        manager.add_turn(
+            create_test_message("Hello", true),
+            create_test_message("Hi there!", false),
+            Some(TokenUsage::new(10, 20)),
+        );
+        manager.add_turn(
+            create_test_message("How are you?", true),
+            create_test_message("Doing well, thanks for asking.", false),
+            None,
+        );
+
+        assert_eq!(manager.backfill_token_counts(), 1);
+
+        let counted = &manager.turns()[0];
+        assert!(counted.has_authoritative_usage());
+        assert_eq!(counted.api_usage, Some(TokenUsage::new(10, 20)));
+
+        let filled = &manager.turns()[1];
+        assert!(filled.usage_estimated);
+        assert!(!filled.has_authoritative_usage());
+        let usage = filled.api_usage.as_ref().unwrap();
+        assert!(usage.input_tokens > 0);
+        assert!(usage.output_tokens > usage.input_tokens);
+
+        // Already filled turns are skipped
+        assert_eq!(manager.backfill_token_counts(), 0);
+    }
+
     #[test]
     fn test_get_messages_empty() {
         let manager = EnhancedContextManager::default();
@@ -825,12 +1058,133 @@ mod tests {
 
         // Import into new manager
         let mut new_manager = EnhancedContextManager::default();
-        new_manager.import(export);
+        new_manager.import(export).unwrap();
 
         assert_eq!(new_manager.system_prompt(), "Test prompt");
         assert_eq!(new_manager.turn_count(), 1);
     }
 
+    #[test]
+    fn test_export_json_round_trip_into_fresh_manager() {
+        let mut manager = EnhancedContextManager::default();
+        manager.set_system_prompt("You are a helpful assistant.");
+        manager.pin_message(create_test_message("Always answer in English.", true));
+        manager.add_turn(
+            create_test_message("What is Rust?", true),
+            create_test_message("A systems programming language.", false),
+            Some(TokenUsage::new(120, 30)),
+        );
+        manager.add_turn(
+            create_test_message("Show an example", true),
+            create_test_message("fn main() {}", false),
+            None,
+        );
+        manager.turns_mut()[0].summarized = true;
+        manager.turns_mut()[0].summary = Some("Asked what Rust is.".to_string());
+
+        let json = manager.export().to_json().unwrap();
+
+        let mut restored = EnhancedContextManager::default();
+        restored
+            .import(ContextExport::from_json(&json).unwrap())
+            .unwrap();
+
+        assert_eq!(restored.get_messages(), manager.get_messages());
+        assert_eq!(restored.pinned_messages(), manager.pinned_messages());
+        assert_eq!(
+            restored.turns()[0]
+                .api_usage
+                .as_ref()
+                .map(|u| u.input_tokens),
+            Some(120)
+        );
+        assert_eq!(restored.get_used_tokens(), manager.get_used_tokens());
+    }
+
+    #[test]
+    fn test_import_rejects_incompatible_version() {
+        let mut manager = EnhancedContextManager::default();
+        manager.add_turn(
+            create_test_message("Hello", true),
+            create_test_message("Hi!", false),
+            None,
+        );
+        let mut value: serde_json::Value =
+            serde_json::from_str(&manager.export().to_json().unwrap()).unwrap();
+        value["version"] = serde_json::json!(99);
+
+        let err = ContextExport::from_json(&value.to_string()).unwrap_err();
+        assert!(matches!(err, ContextError::IncompatibleExport(_)));
+
+        let mut export = manager.export();
+        export.version = 99;
+        let mut target = EnhancedContextManager::default();
+        target.set_system_prompt("unchanged");
+        assert!(target.import(export).is_err());
+        assert_eq!(target.system_prompt(), "unchanged");
+        assert_eq!(target.turn_count(), 0);
+    }
+
+    fn over_budget_manager() -> EnhancedContextManager {
+        use rmcp::model::{CallToolResult, Content};
+
+        let mut manager = EnhancedContextManager::new(ContextConfig {
+            enable_incremental_compression: false,
+            ..Default::default()
+        });
+        manager.set_system_prompt("You are a helpful assistant.");
+        for i in 0..4 {
+            let log = format!("build step {} finished\n", i).repeat(1000);
+            let user = Message::user().with_tool_response(
+                format!("call_{}", i),
+                Ok(CallToolResult::success(vec![Content::text(log)])),
+            );
+            let code: String = (0..200)
+                .map(|n| format!("    let value_{} = compute({});\n", n, n))
+                .collect();
+            let assistant = create_test_message(&format!("```rust\n{}```", code), false);
+            manager.add_turn(user, assistant, None);
+        }
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_compress_to_converges_under_target() {
+        let mut manager = over_budget_manager();
+        let initial = manager.get_used_tokens();
+        let target = initial / 10;
+
+        let report = manager.compress_to(target).await.unwrap();
+
+        assert!(report.reached_target);
+        assert_eq!(report.initial_tokens, initial);
+        assert!(report.final_tokens <= target);
+        assert_eq!(manager.get_used_tokens(), report.final_tokens);
+        // Cheapest strategy first, and every recorded step reclaimed tokens
+        assert_eq!(report.steps[0].strategy, CompressionStrategy::CodeBlocks);
+        assert!(report
+            .steps
+            .iter()
+            .any(|s| s.strategy == CompressionStrategy::ToolOutput));
+        assert!(report.steps.iter().all(|s| s.tokens_reclaimed() > 0));
+        assert_eq!(manager.get_stats().compression_count, report.steps.len());
+        assert!(!manager.turns().last().unwrap().summarized);
+    }
+
+    #[tokio::test]
+    async fn test_compress_to_stops_before_pinned_content() {
+        let mut manager = over_budget_manager();
+        manager.pin_message(create_test_message(&"project rules ".repeat(500), true));
+        let before = manager.get_used_tokens();
+
+        let report = manager.compress_to(100).await.unwrap();
+
+        assert!(!report.reached_target);
+        assert!(report.steps.is_empty());
+        assert_eq!(manager.get_used_tokens(), before);
+        assert_eq!(manager.pinned_messages().len(), 1);
+    }
+
     #[test]
     fn test_clear() {
         let mut manager = EnhancedContextManager::default();
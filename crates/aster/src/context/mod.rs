@@ -142,6 +142,8 @@ pub use manager::EnhancedContextManager;
 // ============================================================================
 
 pub use types::{
+    // Adaptive compression types
+    AdaptiveCompressionReport,
     // File mention types
     AgentsMdConfig,
     // Cache types
@@ -155,6 +157,8 @@ pub use types::{
     CompressionConfig,
     CompressionDetails,
     CompressionResult,
+    CompressionStep,
+    CompressionStrategy,
     // Core types
     ContextConfig,
     ContextError,
@@ -173,11 +177,13 @@ pub use types::{
     PruningLevel,
     ResolvedFile,
     TokenUsage,
+    ToolOutputFormat,
     // Constants from types module
     CHARS_PER_TOKEN_ASIAN,
     CHARS_PER_TOKEN_CODE,
     CHARS_PER_TOKEN_DEFAULT,
     CODE_BLOCK_MAX_LINES,
+    CONTEXT_EXPORT_VERSION,
     FILE_CONTENT_MAX_CHARS,
     TOOL_OUTPUT_MAX_CHARS,
 };
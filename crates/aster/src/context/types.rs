@@ -5,6 +5,7 @@
 //! conversation turns, and error handling.
 
 use crate::conversation::message::Message;
+use rmcp::model::Role;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
@@ -35,6 +36,13 @@ pub const TOOL_OUTPUT_MAX_CHARS: usize = 2000;
 /// Maximum characters for file content before compression
 pub const FILE_CONTENT_MAX_CHARS: usize = 1500;
 
+// ============================================================================
+// Export Constants
+// ============================================================================
+
+/// Format version written by `ContextExport`; other versions are rejected on import
+pub const CONTEXT_EXPORT_VERSION: u32 = 1;
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -65,6 +73,10 @@ pub enum ContextError {
     /// Token limit exceeded
     #[error("Token limit exceeded: {0}")]
     TokenLimitExceeded(String),
+
+    /// Exported context cannot be imported
+    #[error("Incompatible context export: {0}")]
+    IncompatibleExport(String),
 }
 
 impl From<serde_json::Error> for ContextError {
@@ -254,6 +266,10 @@ pub struct ConversationTurn {
 
     /// API usage statistics for this turn
     pub api_usage: Option<TokenUsage>,
+
+    /// Whether `api_usage` was estimated locally rather than reported by the API
+    #[serde(default)]
+    pub usage_estimated: bool,
 }
 
 impl ConversationTurn {
@@ -269,15 +285,22 @@ impl ConversationTurn {
             summary: None,
             compressed: false,
             api_usage: None,
+            usage_estimated: false,
         }
     }
 
     /// Create a turn with API usage statistics
     pub fn with_api_usage(mut self, usage: TokenUsage) -> Self {
         self.api_usage = Some(usage);
+        self.usage_estimated = false;
         self
     }
 
+    /// Check whether this turn has usage reported by the API
+    pub fn has_authoritative_usage(&self) -> bool {
+        self.api_usage.is_some() && !self.usage_estimated
+    }
+
     /// Mark this turn as summarized with the given summary
     pub fn mark_summarized(&mut self, summary: String, new_token_estimate: usize) {
         self.summarized = true;
@@ -381,9 +404,16 @@ impl ContextUsage {
 /// Used for persisting context to disk or transferring between sessions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextExport {
+    /// Format version, see [`CONTEXT_EXPORT_VERSION`]
+    pub version: u32,
+
     /// The system prompt
     pub system_prompt: String,
 
+    /// Messages pinned to the context
+    #[serde(default)]
+    pub pinned: Vec<Message>,
+
     /// All conversation turns
     pub turns: Vec<ConversationTurn>,
 
@@ -401,19 +431,72 @@ impl ContextExport {
     /// Create a new ContextExport
     pub fn new(
         system_prompt: String,
+        pinned: Vec<Message>,
         turns: Vec<ConversationTurn>,
         config: ContextConfig,
         compression_count: usize,
         saved_tokens: usize,
     ) -> Self {
         Self {
+            version: CONTEXT_EXPORT_VERSION,
             system_prompt,
+            pinned,
             turns,
             config,
             compression_count,
             saved_tokens,
         }
     }
+
+    /// Serialize the export to JSON
+    pub fn to_json(&self) -> Result<String, ContextError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse an export from JSON, rejecting incompatible versions
+    ///
+    /// The version is checked before the rest of the document is parsed, so
+    /// exports from other format versions report a version error rather than
+    /// a parse error.
+    pub fn from_json(json: &str) -> Result<Self, ContextError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        match value.get("version").and_then(|v| v.as_u64()) {
+            Some(version) if version == u64::from(CONTEXT_EXPORT_VERSION) => {}
+            Some(version) => {
+                return Err(ContextError::IncompatibleExport(format!(
+                    "version {} is not supported (expected {})",
+                    version, CONTEXT_EXPORT_VERSION
+                )))
+            }
+            None => {
+                return Err(ContextError::IncompatibleExport(
+                    "missing format version".to_string(),
+                ))
+            }
+        }
+        let export: Self = serde_json::from_value(value)?;
+        export.validate()?;
+        Ok(export)
+    }
+
+    /// Check that the export can be imported
+    pub fn validate(&self) -> Result<(), ContextError> {
+        if self.version != CONTEXT_EXPORT_VERSION {
+            return Err(ContextError::IncompatibleExport(format!(
+                "version {} is not supported (expected {})",
+                self.version, CONTEXT_EXPORT_VERSION
+            )));
+        }
+        for (i, turn) in self.turns.iter().enumerate() {
+            if turn.user.role != Role::User || turn.assistant.role != Role::Assistant {
+                return Err(ContextError::IncompatibleExport(format!(
+                    "turn {} is not a user/assistant pair",
+                    i
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -463,6 +546,57 @@ impl CompressionResult {
     }
 }
 
+/// A strategy used by adaptive compression, cheapest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionStrategy {
+    /// Keep the head and tail of long code blocks
+    CodeBlocks,
+    /// Truncate long tool outputs
+    ToolOutput,
+    /// Replace the oldest turns with a summary
+    Summarization,
+}
+
+/// One step of adaptive compression.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressionStep {
+    /// Strategy applied in this step
+    pub strategy: CompressionStrategy,
+
+    /// Tokens in use before the step
+    pub tokens_before: usize,
+
+    /// Tokens in use after the step
+    pub tokens_after: usize,
+}
+
+impl CompressionStep {
+    /// Tokens reclaimed by this step
+    pub fn tokens_reclaimed(&self) -> usize {
+        self.tokens_before.saturating_sub(self.tokens_after)
+    }
+}
+
+/// Outcome of compressing the context towards a token target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveCompressionReport {
+    /// Requested token target
+    pub target_tokens: usize,
+
+    /// Tokens in use before compression
+    pub initial_tokens: usize,
+
+    /// Tokens in use after compression
+    pub final_tokens: usize,
+
+    /// Steps that reclaimed tokens, in the order applied
+    pub steps: Vec<CompressionStep>,
+
+    /// Whether usage ended at or below the target
+    pub reached_target: bool,
+}
+
 /// Detailed compression information.
 #[derive(Debug, Clone, Default)]
 pub struct CompressionDetails {
@@ -932,6 +1066,20 @@ impl CodeBlock {
     }
 }
 
+/// Structure of a tool output, used to pick a compression strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOutputFormat {
+    /// A JSON document
+    Json,
+    /// Rows with a header line (markdown, CSV, TSV)
+    Table,
+    /// An error followed by stack frames
+    StackTrace,
+    /// Anything else
+    Plain,
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
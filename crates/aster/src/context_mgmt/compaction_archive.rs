@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::compact_messages;
+use crate::conversation::message::{Message, MessageContent, SystemNotificationType};
+use crate::conversation::Conversation;
+use crate::providers::base::{Provider, ProviderUsage};
+use crate::session::extension_data::ExtensionState;
+
+/// Prefix of the notification that identifies a compaction marker
+const COMPACTION_MARKER_PREFIX: &str = "Compacted conversation history: ";
+
+/// Original turns replaced by a compaction marker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedTurns {
+    /// The messages as they were before compaction
+    pub messages: Vec<Message>,
+    /// Number of messages, starting at the marker, that compaction produced
+    pub replaced: usize,
+}
+
+/// Original turns of every compaction in a session, keyed by marker id
+///
+/// Stored in the session's extension data so compacted history survives
+/// restarts and can be expanded with [`expand_compaction`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactionArchive {
+    pub entries: HashMap<String, ArchivedTurns>,
+}
+
+impl ExtensionState for CompactionArchive {
+    const EXTENSION_NAME: &'static str = "compaction_archive";
+    const VERSION: &'static str = "v0";
+}
+
+impl CompactionArchive {
+    pub fn insert(&mut self, marker_id: String, turns: ArchivedTurns) {
+        self.entries.insert(marker_id, turns);
+    }
+
+    pub fn get(&self, marker_id: &str) -> Option<&ArchivedTurns> {
+        self.entries.get(marker_id)
+    }
+}
+
+/// The marker id of a message, if it is a compaction marker
+pub fn compaction_marker_id(message: &Message) -> Option<&str> {
+    message.content.iter().find_map(|content| match content {
+        MessageContent::SystemNotification(notification) => notification
+            .msg
+            .strip_prefix(COMPACTION_MARKER_PREFIX)
+            .and_then(|rest| rest.split_whitespace().next()),
+        _ => None,
+    })
+}
+
+/// Compact messages, replacing the original turns with a marker and a summary
+///
+/// Unlike [`compact_messages`], the original messages are removed from the
+/// conversation instead of being kept as user-visible history. The marker is a
+/// user-only notification carrying its id, so the id never reaches the model
+/// or a provider; the summary that follows it is what the agent sees. The
+/// originals are returned so the caller can store them in a
+/// [`CompactionArchive`].
+///
+/// # Returns
+/// * The compacted conversation, the summarization usage, the marker id and
+///   the archived original turns
+pub async fn compact_messages_restorable(
+    provider: &dyn Provider,
+    conversation: &Conversation,
+    manual_compact: bool,
+) -> Result<(Conversation, ProviderUsage, String, ArchivedTurns)> {
+    let (compacted, usage) = compact_messages(provider, conversation, manual_compact).await?;
+
+    // compact_messages keeps every original message and appends its own
+    let originals = conversation.messages();
+    let mut produced = compacted.messages()[originals.len()..].to_vec();
+    if produced.is_empty() {
+        return Err(anyhow!("Compaction produced no summary"));
+    }
+
+    let marker_id = format!("cmp_{}", Uuid::new_v4());
+    let marker = Message::assistant().with_system_notification(
+        SystemNotificationType::InlineMessage,
+        format!(
+            "{}{} ({} messages)",
+            COMPACTION_MARKER_PREFIX,
+            marker_id,
+            originals.len()
+        ),
+    );
+    produced.insert(0, marker);
+
+    let archived = ArchivedTurns {
+        messages: originals.clone(),
+        replaced: produced.len(),
+    };
+    Ok((
+        Conversation::new_unvalidated(produced),
+        usage,
+        marker_id,
+        archived,
+    ))
+}
+
+/// Restore the original turns of a compaction marker
+///
+/// The marker and the messages compaction added after it are replaced by the
+/// archived originals; messages added to the conversation since then are kept.
+/// A marker that a later compaction archived in turn is reached by expanding
+/// the enclosing compactions first.
+pub fn expand_compaction(
+    conversation: &Conversation,
+    archive: &CompactionArchive,
+    marker_id: &str,
+) -> Result<Conversation> {
+    expand_nested(conversation, archive, marker_id, archive.entries.len())
+}
+
+fn expand_nested(
+    conversation: &Conversation,
+    archive: &CompactionArchive,
+    marker_id: &str,
+    depth: usize,
+) -> Result<Conversation> {
+    let turns = archive
+        .get(marker_id)
+        .ok_or_else(|| anyhow!("No archived turns for compaction marker {}", marker_id))?;
+    let messages = conversation.messages();
+    let Some(start) = messages
+        .iter()
+        .position(|m| compaction_marker_id(m) == Some(marker_id))
+    else {
+        let not_found = || anyhow!("Compaction marker {} is not in the conversation", marker_id);
+        if depth == 0 {
+            return Err(not_found());
+        }
+        let (outer_id, _) = archive
+            .entries
+            .iter()
+            .find(|(_, outer)| {
+                outer
+                    .messages
+                    .iter()
+                    .any(|m| compaction_marker_id(m) == Some(marker_id))
+            })
+            .ok_or_else(not_found)?;
+        let expanded = expand_nested(conversation, archive, outer_id, depth - 1)?;
+        return expand_nested(&expanded, archive, marker_id, depth - 1);
+    };
+    let end = (start + turns.replaced).min(messages.len());
+
+    let mut expanded = messages[..start].to_vec();
+    expanded.extend(turns.messages.iter().cloned());
+    expanded.extend(messages[end..].iter().cloned());
+    Ok(Conversation::new_unvalidated(expanded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{ProviderMetadata, Usage};
+    use crate::providers::errors::ProviderError;
+    use async_trait::async_trait;
+    use rmcp::model::Tool;
+
+    struct SummaryProvider;
+
+    #[async_trait]
+    impl Provider for SummaryProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "summary"
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text("<summary of the design discussion>"),
+                ProviderUsage::new("mock-model".to_string(), Usage::default()),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("mock-model")
+        }
+    }
+
+    fn texts(conversation: &Conversation) -> Vec<String> {
+        conversation
+            .messages()
+            .iter()
+            .map(|m| m.as_concat_text())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_compact_then_expand_restores_originals() {
+        let original = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Should the cache be per session?"),
+            Message::assistant().with_text("Per session, keyed by request hash."),
+            Message::user().with_text("And the TTL?"),
+            Message::assistant().with_text("Five minutes."),
+            Message::user().with_text("Now write the tests."),
+        ]);
+
+        let (compacted, _usage, marker_id, archived) =
+            compact_messages_restorable(&SummaryProvider, &original, false)
+                .await
+                .unwrap();
+
+        // The originals are gone; the marker is only shown to the user and
+        // the agent sees the summary instead
+        let marker = &compacted.messages()[0];
+        assert_eq!(compaction_marker_id(marker), Some(marker_id.as_str()));
+        assert!(marker.is_user_visible() && !marker.is_agent_visible());
+        let agent_visible: Vec<&Message> = compacted
+            .messages()
+            .iter()
+            .filter(|m| m.is_agent_visible())
+            .collect();
+        assert!(agent_visible
+            .iter()
+            .all(|m| compaction_marker_id(m).is_none()));
+        assert!(agent_visible[0]
+            .as_concat_text()
+            .starts_with("<summary of the design discussion>"));
+        assert!(!texts(&compacted).contains(&"Five minutes.".to_string()));
+
+        let mut archive = CompactionArchive::default();
+        archive.insert(marker_id.clone(), archived);
+
+        // The conversation continues after compaction
+        let mut messages = compacted.messages().clone();
+        messages.push(Message::assistant().with_text("Here are the tests."));
+        let continued = Conversation::new_unvalidated(messages);
+
+        let expanded = expand_compaction(&continued, &archive, &marker_id).unwrap();
+        let mut expected = texts(&original);
+        expected.push("Here are the tests.".to_string());
+        assert_eq!(texts(&expanded), expected);
+        assert_eq!(&expanded.messages()[..5], original.messages().as_slice());
+
+        assert!(expand_compaction(&continued, &archive, "cmp_unknown").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expand_marker_archived_by_a_later_compaction() {
+        let original = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Which database?"),
+            Message::assistant().with_text("Postgres."),
+        ]);
+        let mut archive = CompactionArchive::default();
+
+        let (first, _, first_id, archived) =
+            compact_messages_restorable(&SummaryProvider, &original, false)
+                .await
+                .unwrap();
+        archive.insert(first_id.clone(), archived);
+
+        let mut messages = first.messages().clone();
+        messages.push(Message::user().with_text("And the schema?"));
+        messages.push(Message::assistant().with_text("One table per tenant."));
+        let continued = Conversation::new_unvalidated(messages);
+
+        let (second, _, second_id, archived) =
+            compact_messages_restorable(&SummaryProvider, &continued, false)
+                .await
+                .unwrap();
+        archive.insert(second_id, archived);
+        assert!(second
+            .messages()
+            .iter()
+            .all(|m| compaction_marker_id(m) != Some(first_id.as_str())));
+
+        let expanded = expand_compaction(&second, &archive, &first_id).unwrap();
+        let expanded = texts(&expanded);
+        assert_eq!(&expanded[..2], texts(&original).as_slice());
+        assert!(expanded.contains(&"One table per tenant.".to_string()));
+    }
+}
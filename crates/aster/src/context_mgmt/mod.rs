@@ -10,6 +10,12 @@ use rmcp::model::Role;
 use serde::Serialize;
 use tracing::{debug, info};
 
+pub mod compaction_archive;
+pub use compaction_archive::{
+    compact_messages_restorable, compaction_marker_id, expand_compaction, ArchivedTurns,
+    CompactionArchive,
+};
+
 pub const DEFAULT_COMPACTION_THRESHOLD: f64 = 0.8;
 
 const CONVERSATION_CONTINUATION_TEXT: &str =
@@ -444,6 +450,7 @@ mod tests {
                     toolshim: false,
                     toolshim_model: None,
                     fast_model: None,
+                    json_mode: false,
                 },
                 max_tool_responses: None,
             }
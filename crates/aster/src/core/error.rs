@@ -0,0 +1,145 @@
+//! 统一错误类型
+//!
+//! 汇总各模块的错误类型，便于在集成边界使用单一的 `Result<_, AsterError>`。
+//! 原始错误作为 source 保留，Display 输出完整的错误链。
+
+use std::error::Error as StdError;
+use thiserror::Error;
+
+use crate::config::ConfigError;
+use crate::context::ContextError;
+use crate::mcp::McpError;
+use crate::providers::errors::ProviderError;
+use crate::skills::SkillError;
+use crate::tools::ToolError;
+
+/// 统一结果类型
+pub type AsterResult<T> = std::result::Result<T, AsterError>;
+
+/// 框架顶层错误
+#[derive(Debug, Error)]
+pub enum AsterError {
+    #[error("{}", format_chain("Tool error", .0))]
+    Tool(#[from] ToolError),
+
+    #[error("{}", format_chain("MCP error", .0))]
+    Mcp(#[from] McpError),
+
+    #[error("{}", format_chain("Context error", .0))]
+    Context(#[from] ContextError),
+
+    #[error("{}", format_chain("Provider error", .0))]
+    Provider(#[from] ProviderError),
+
+    #[error("{}", format_chain("Config error", .0))]
+    Config(#[from] ConfigError),
+
+    #[error("{}", format_chain("Skill error", .0))]
+    Skill(#[from] SkillError),
+
+    #[error("{}", format_chain("IO error", .0))]
+    Io(#[from] std::io::Error),
+
+    #[error("{}", format_chain("Serialization error", .0))]
+    Serialization(#[from] serde_json::Error),
+
+    /// 附加了上下文说明的错误
+    #[error("{}", format_chain(.message, .source.as_ref()))]
+    WithContext {
+        message: String,
+        #[source]
+        source: Box<AsterError>,
+    },
+
+    /// 其他错误（如 anyhow::Error）
+    #[error("{}", format_chain("Error", .source.as_ref()))]
+    Other {
+        #[source]
+        source: Box<dyn StdError + Send + Sync>,
+    },
+}
+
+impl AsterError {
+    /// 为错误附加上下文说明，原错误作为 source 保留
+    pub fn context(self, message: impl Into<String>) -> Self {
+        Self::WithContext {
+            message: message.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// 去掉上下文包装后的根错误
+    pub fn root(&self) -> &AsterError {
+        match self {
+            Self::WithContext { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// 错误链中每一层的描述，从外到内
+    pub fn chain(&self) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut current: Option<&(dyn StdError + 'static)> = Some(self);
+        while let Some(err) = current {
+            messages.push(match err.downcast_ref::<AsterError>() {
+                Some(aster) => aster.label().to_string(),
+                None => err.to_string(),
+            });
+            current = err.source();
+        }
+        messages
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            Self::Tool(_) => "Tool error",
+            Self::Mcp(_) => "MCP error",
+            Self::Context(_) => "Context error",
+            Self::Provider(_) => "Provider error",
+            Self::Config(_) => "Config error",
+            Self::Skill(_) => "Skill error",
+            Self::Io(_) => "IO error",
+            Self::Serialization(_) => "Serialization error",
+            Self::WithContext { message, .. } => message,
+            Self::Other { .. } => "Error",
+        }
+    }
+}
+
+impl From<anyhow::Error> for AsterError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::Other { source: err.into() }
+    }
+}
+
+/// 为 `Result` 附加上下文
+pub trait AsterResultExt<T> {
+    fn context(self, message: impl Into<String>) -> AsterResult<T>;
+}
+
+impl<T, E: Into<AsterError>> AsterResultExt<T> for std::result::Result<T, E> {
+    fn context(self, message: impl Into<String>) -> AsterResult<T> {
+        self.map_err(|e| e.into().context(message))
+    }
+}
+
+/// 拼接错误链，内层消息已包含在外层中时跳过
+fn format_chain(label: &str, err: &(dyn StdError + 'static)) -> String {
+    let mut output = format!("{}: {}", label, err);
+    // 嵌套的 AsterError 已输出自身的错误链
+    if err.is::<AsterError>() {
+        return output;
+    }
+    let mut last = err.to_string();
+    let mut current = err.source();
+    while let Some(source) = current {
+        let message = source.to_string();
+        if !last.ends_with(&message) {
+            output.push_str(": ");
+            output.push_str(&message);
+        }
+        last = message;
+        current = source.source();
+    }
+    output
+}
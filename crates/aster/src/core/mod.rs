@@ -1,11 +1,15 @@
 //! 核心模块
 //!
-//! 提供后台任务、重试逻辑等核心功能
+//! 提供后台任务、重试逻辑、统一错误类型等核心功能
 
 mod background_tasks;
+mod error;
+mod retry;
 mod retry_logic;
 
 pub use background_tasks::*;
+pub use error::*;
+pub use retry::*;
 pub use retry_logic::*;
 
 #[cfg(test)]
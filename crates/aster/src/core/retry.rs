@@ -0,0 +1,192 @@
+//! 通用重试封装
+//!
+//! 按 `RetryStrategy` 重试幂等的异步操作，区分可重试与不可重试错误，
+//! 支持取消，失败时保留尝试次数和最后一次错误。
+
+use std::future::Future;
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use super::error::AsterError;
+use crate::agents::error_handling::UnifiedRetryConfig;
+use crate::mcp::McpError;
+use crate::providers::errors::ProviderError;
+use crate::tools::ToolError;
+
+/// 可判断是否值得重试的错误
+pub trait Retryable {
+    fn is_retryable(&self) -> bool;
+}
+
+impl Retryable for ToolError {
+    fn is_retryable(&self) -> bool {
+        ToolError::is_retryable(self)
+    }
+}
+
+impl Retryable for McpError {
+    fn is_retryable(&self) -> bool {
+        McpError::is_retryable(self)
+    }
+}
+
+impl Retryable for ProviderError {
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::RateLimitExceeded { .. } | ProviderError::ServerError(_)
+        )
+    }
+}
+
+impl Retryable for AsterError {
+    fn is_retryable(&self) -> bool {
+        match self.root() {
+            AsterError::Tool(e) => e.is_retryable(),
+            AsterError::Mcp(e) => e.is_retryable(),
+            AsterError::Provider(e) => e.is_retryable(),
+            AsterError::Io(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// 当前尝试的元信息，传给被重试的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryAttempt {
+    /// 当前尝试次数（从 1 开始）
+    pub attempt: u32,
+    /// 最多尝试次数（首次 + 重试）
+    pub max_attempts: u32,
+}
+
+impl RetryAttempt {
+    /// 是否为最后一次尝试
+    pub fn is_last(&self) -> bool {
+        self.attempt >= self.max_attempts
+    }
+}
+
+/// 重试最终失败
+#[derive(Debug, Error)]
+pub enum RetryError<E> {
+    #[error("Not retryable (attempt {attempts}): {last_error}")]
+    NotRetryable {
+        attempts: u32,
+        #[source]
+        last_error: E,
+    },
+    #[error("Failed after {attempts} attempts: {last_error}")]
+    Exhausted {
+        attempts: u32,
+        #[source]
+        last_error: E,
+    },
+    #[error("Cancelled after {attempts} attempts")]
+    Cancelled {
+        attempts: u32,
+        last_error: Option<E>,
+    },
+}
+
+impl<E> RetryError<E> {
+    /// 已执行的尝试次数
+    pub fn attempts(&self) -> u32 {
+        match self {
+            Self::NotRetryable { attempts, .. }
+            | Self::Exhausted { attempts, .. }
+            | Self::Cancelled { attempts, .. } => *attempts,
+        }
+    }
+
+    /// 最后一次错误
+    pub fn last_error(&self) -> Option<&E> {
+        match self {
+            Self::NotRetryable { last_error, .. } | Self::Exhausted { last_error, .. } => {
+                Some(last_error)
+            }
+            Self::Cancelled { last_error, .. } => last_error.as_ref(),
+        }
+    }
+
+    /// 取出最后一次错误
+    pub fn into_last_error(self) -> Option<E> {
+        match self {
+            Self::NotRetryable { last_error, .. } | Self::Exhausted { last_error, .. } => {
+                Some(last_error)
+            }
+            Self::Cancelled { last_error, .. } => last_error,
+        }
+    }
+}
+
+/// 按策略重试异步操作
+///
+/// 首次执行后最多重试 `policy.max_retries` 次，间隔由 `policy.calculate_delay` 决定。
+/// 不可重试的错误立即返回；取消令牌触发时中断当前尝试或等待。
+pub async fn retry_with<T, E, F, Fut>(
+    mut op: F,
+    policy: &UnifiedRetryConfig,
+    cancel_token: Option<&CancellationToken>,
+) -> Result<T, RetryError<E>>
+where
+    F: FnMut(RetryAttempt) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Retryable,
+{
+    let never_cancelled = CancellationToken::new();
+    let cancel_token = cancel_token.unwrap_or(&never_cancelled);
+    let max_attempts = policy.max_retries.saturating_add(1);
+    let mut last_error = None;
+
+    for attempt in 1..=max_attempts {
+        if cancel_token.is_cancelled() {
+            return Err(RetryError::Cancelled {
+                attempts: attempt - 1,
+                last_error,
+            });
+        }
+
+        let result = tokio::select! {
+            result = op(RetryAttempt { attempt, max_attempts }) => result,
+            _ = cancel_token.cancelled() => {
+                return Err(RetryError::Cancelled { attempts: attempt, last_error });
+            }
+        };
+
+        let error = match result {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        if !error.is_retryable() {
+            return Err(RetryError::NotRetryable {
+                attempts: attempt,
+                last_error: error,
+            });
+        }
+        if attempt == max_attempts {
+            return Err(RetryError::Exhausted {
+                attempts: attempt,
+                last_error: error,
+            });
+        }
+
+        let delay = policy.calculate_delay(attempt - 1);
+        tracing::debug!(
+            "第 {}/{} 次尝试失败，{:?} 后重试",
+            attempt,
+            max_attempts,
+            delay
+        );
+        last_error = Some(error);
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancel_token.cancelled() => {
+                return Err(RetryError::Cancelled { attempts: attempt, last_error });
+            }
+        }
+    }
+
+    unreachable!("max_attempts is at least 1")
+}
@@ -188,3 +188,177 @@ fn test_overflow_recovery_options_default() {
     assert_eq!(options.max_thinking_tokens, 0);
     assert_eq!(options.max_retries, 3);
 }
+
+// ============ Error Tests ============
+
+fn io_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::NotFound, "missing.txt")
+}
+
+#[test]
+fn test_aster_error_from_module_errors() {
+    let tool: AsterError = crate::tools::ToolError::not_found("bash").into();
+    assert!(matches!(tool, AsterError::Tool(_)));
+    assert_eq!(tool.to_string(), "Tool error: Tool not found: bash");
+
+    let mcp: AsterError = crate::mcp::McpError::protocol("bad handshake").into();
+    assert!(matches!(mcp, AsterError::Mcp(_)));
+    assert_eq!(mcp.to_string(), "MCP error: Protocol error: bad handshake");
+
+    let context: AsterError =
+        crate::context::ContextError::TokenLimitExceeded("200000".to_string()).into();
+    assert!(matches!(context, AsterError::Context(_)));
+
+    let provider: AsterError =
+        crate::providers::errors::ProviderError::ServerError("502".to_string()).into();
+    assert!(matches!(provider, AsterError::Provider(_)));
+    assert_eq!(provider.to_string(), "Provider error: Server error: 502");
+
+    let config: AsterError = crate::config::ConfigError::NotFound("model".to_string()).into();
+    assert!(matches!(config, AsterError::Config(_)));
+
+    let skill: AsterError = crate::skills::SkillError::NotImplemented("agent".to_string()).into();
+    assert!(matches!(skill, AsterError::Skill(_)));
+
+    let json: AsterError = serde_json::from_str::<serde_json::Value>("{")
+        .unwrap_err()
+        .into();
+    assert!(matches!(json, AsterError::Serialization(_)));
+
+    let other: AsterError = anyhow::anyhow!("plain failure").into();
+    assert_eq!(other.to_string(), "Error: plain failure");
+}
+
+#[test]
+fn test_aster_error_preserves_source_chain() {
+    use std::error::Error;
+
+    let mcp = crate::mcp::McpError::connection_with_source("connect failed", io_error());
+    let err: AsterError = mcp.into();
+    assert_eq!(
+        err.to_string(),
+        "MCP error: Connection error: connect failed: missing.txt"
+    );
+
+    let source = err.source().unwrap();
+    assert!(source.downcast_ref::<crate::mcp::McpError>().is_some());
+    let root = source.source().unwrap();
+    assert_eq!(
+        root.downcast_ref::<std::io::Error>().unwrap().kind(),
+        std::io::ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn test_aster_error_context_chain() {
+    let result: Result<(), crate::tools::ToolError> = Err(crate::tools::ToolError::Io(io_error()));
+    let err = result.context("loading workspace").unwrap_err();
+
+    // 内层已包含的消息不会重复
+    assert_eq!(
+        err.to_string(),
+        "loading workspace: Tool error: IO error: missing.txt"
+    );
+    assert_eq!(
+        err.chain(),
+        vec![
+            "loading workspace",
+            "Tool error",
+            "IO error: missing.txt",
+            "missing.txt"
+        ]
+    );
+    assert!(matches!(err.root(), AsterError::Tool(_)));
+
+    let anyhow_err = anyhow::anyhow!("disk full").context("writing checkpoint");
+    let err = AsterError::from(anyhow_err).context("saving session");
+    assert_eq!(
+        err.to_string(),
+        "saving session: Error: writing checkpoint: disk full"
+    );
+}
+
+// ============ Retry Tests ============
+
+fn fast_retry_policy() -> crate::agents::error_handling::UnifiedRetryConfig {
+    crate::agents::error_handling::UnifiedRetryConfig::new(3, std::time::Duration::from_millis(1))
+        .with_strategy(crate::agents::error_handling::RetryStrategy::Fixed)
+}
+
+#[tokio::test]
+async fn test_retry_with_succeeds_on_third_attempt() {
+    let calls = std::sync::atomic::AtomicU32::new(0);
+    let result = retry_with(
+        |attempt| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move {
+                if attempt.attempt < 3 {
+                    Err(crate::tools::ToolError::Timeout(
+                        std::time::Duration::from_secs(1),
+                    ))
+                } else {
+                    Ok(attempt.attempt)
+                }
+            }
+        },
+        &fast_retry_policy(),
+        None,
+    )
+    .await;
+
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_retry_with_stops_on_non_retryable_error() {
+    let result: Result<(), _> = retry_with(
+        |_| async { Err(crate::tools::ToolError::permission_denied("rm -rf /")) },
+        &fast_retry_policy(),
+        None,
+    )
+    .await;
+
+    let err = result.unwrap_err();
+    assert!(matches!(err, RetryError::NotRetryable { attempts: 1, .. }));
+    assert!(err.last_error().unwrap().is_permission_error());
+}
+
+#[tokio::test]
+async fn test_retry_with_exhausted_keeps_last_error() {
+    let result: Result<(), _> = retry_with(
+        |attempt| async move {
+            Err(AsterError::from(std::io::Error::other(format!(
+                "attempt {}",
+                attempt.attempt
+            ))))
+        },
+        &fast_retry_policy(),
+        None,
+    )
+    .await;
+
+    let err = result.unwrap_err();
+    assert_eq!(err.attempts(), 4);
+    assert!(matches!(err, RetryError::Exhausted { .. }));
+    assert_eq!(
+        err.to_string(),
+        "Failed after 4 attempts: IO error: attempt 4"
+    );
+}
+
+#[tokio::test]
+async fn test_retry_with_honors_cancellation() {
+    let token = tokio_util::sync::CancellationToken::new();
+    token.cancel();
+    let result: Result<(), RetryError<crate::tools::ToolError>> =
+        retry_with(|_| async { Ok(()) }, &fast_retry_policy(), Some(&token)).await;
+
+    assert!(matches!(
+        result,
+        Err(RetryError::Cancelled {
+            attempts: 0,
+            last_error: None
+        })
+    ));
+}
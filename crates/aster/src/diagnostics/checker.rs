@@ -263,9 +263,9 @@ impl DiagnosticChecker {
 /// 运行所有诊断检查
 pub fn run_diagnostics() -> Vec<DiagnosticCheck> {
     use super::network::NetworkChecker;
-    use super::system::SystemChecker;
+    use super::system::{DiskUsageConfig, SystemChecker};
 
-    vec![
+    let mut checks = vec![
         // 环境检查
         DiagnosticChecker::check_git(),
         DiagnosticChecker::check_ripgrep(),
@@ -282,14 +282,19 @@ pub fn run_diagnostics() -> Vec<DiagnosticCheck> {
         // 网络检查
         NetworkChecker::check_proxy_configuration(),
         NetworkChecker::check_ssl_certificates(),
-    ]
+    ];
+
+    // 磁盘占用检查
+    checks.extend(SystemChecker::check_disk_usage(&DiskUsageConfig::default()));
+
+    checks
 }
 
 /// 运行所有诊断检查（包括异步检查）
 #[allow(dead_code)]
 pub async fn run_diagnostics_async() -> Vec<DiagnosticCheck> {
     use super::network::NetworkChecker;
-    use super::system::SystemChecker;
+    use super::system::{DiskUsageConfig, SystemChecker};
 
     let mut checks = vec![
         // 环境检查
@@ -310,6 +315,9 @@ pub async fn run_diagnostics_async() -> Vec<DiagnosticCheck> {
         NetworkChecker::check_ssl_certificates(),
     ];
 
+    // 磁盘占用检查
+    checks.extend(SystemChecker::check_disk_usage(&DiskUsageConfig::default()));
+
     // 异步网络检查
     checks.push(NetworkChecker::check_api_connectivity().await);
     checks.push(NetworkChecker::check_network_connectivity().await);
@@ -4,6 +4,7 @@
 
 use super::checker::{CheckStatus, DiagnosticCheck};
 use super::report::DiagnosticReport;
+use super::system::{CleanupAction, DiskUsageConfig, SystemChecker};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -105,6 +106,8 @@ impl AutoFixer {
             "文件权限" | "会话目录" | "缓存目录" | "配置目录" => {
                 Self::fix_directory_issue(check)
             }
+            "目录占用" => Self::fix_disk_usage(check),
+            "剩余空间" => Self::free_disk_space(&DiskUsageConfig::default()),
             _ => {
                 // 无法自动修复
                 if let Some(ref fix) = check.fix {
@@ -116,18 +119,20 @@ impl AutoFixer {
         }
     }
 
-    fn fix_directory_issue(check: &DiagnosticCheck) -> Result<String, String> {
-        // 从详情中提取路径
-        let path = check
+    /// 从详情中提取路径
+    fn extract_path(check: &DiagnosticCheck) -> Option<&str> {
+        check
             .details
             .as_ref()
             .and_then(|d| {
                 d.strip_prefix("路径: ")
                     .or_else(|| d.strip_prefix("Path: "))
             })
-            .map(|s| s.trim());
+            .map(|s| s.trim())
+    }
 
-        if let Some(path_str) = path {
+    fn fix_directory_issue(check: &DiagnosticCheck) -> Result<String, String> {
+        if let Some(path_str) = Self::extract_path(check) {
             let path = Path::new(path_str);
             if !path.exists() {
                 match std::fs::create_dir_all(path) {
@@ -141,6 +146,58 @@ impl AutoFixer {
             Err(format!("{}: 无法确定目录路径", check.name))
         }
     }
+
+    /// 按目录的清理方式清理超限目录
+    fn fix_disk_usage(check: &DiagnosticCheck) -> Result<String, String> {
+        let path =
+            Self::extract_path(check).ok_or_else(|| format!("{}: 无法确定目录路径", check.name))?;
+        let config = DiskUsageConfig::default();
+        let target = config
+            .targets
+            .iter()
+            .find(|t| t.path == Path::new(path))
+            .ok_or_else(|| format!("{}: {} 不是可清理的目录", check.name, path))?;
+
+        let freed = SystemChecker::cleanup_directory(&target.path, target.cleanup)?;
+        Ok(format!(
+            "已清理{}目录，释放 {:.2} MB",
+            target.name,
+            freed as f64 / (1024.0 * 1024.0)
+        ))
+    }
+
+    /// 清理所有可自动清理的目录
+    ///
+    /// 某个目录清理失败时继续清理其余目录，最后汇总失败的目录。
+    fn free_disk_space(config: &DiskUsageConfig) -> Result<String, String> {
+        let mut freed = 0u64;
+        let mut cleaned = Vec::new();
+        let mut errors = Vec::new();
+        for target in config
+            .targets
+            .iter()
+            .filter(|t| t.cleanup != CleanupAction::Manual)
+        {
+            match SystemChecker::cleanup_directory(&target.path, target.cleanup) {
+                Ok(size) => {
+                    freed += size;
+                    cleaned.push(target.name.as_str());
+                }
+                Err(e) => errors.push(format!("{}: {}", target.name, e)),
+            }
+        }
+
+        let summary = format!(
+            "已清理{}，释放 {:.2} MB",
+            cleaned.join("、"),
+            freed as f64 / (1024.0 * 1024.0)
+        );
+        if errors.is_empty() {
+            Ok(summary)
+        } else {
+            Err(format!("{}；清理失败: {}", summary, errors.join("；")))
+        }
+    }
 }
 
 /// 快速健康检查（最小检查集）
@@ -285,6 +342,33 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp_path);
     }
 
+    #[test]
+    fn test_free_disk_space_continues_after_failure() {
+        use crate::diagnostics::system::DiskUsageTarget;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        // 指向文件的目标无法读取目录，清理会失败
+        let broken = temp_dir.path().join("not-a-dir");
+        std::fs::write(&broken, b"x").unwrap();
+        let cache = temp_dir.path().join("cache");
+        std::fs::create_dir_all(&cache).unwrap();
+        std::fs::write(cache.join("blob"), vec![0u8; 64]).unwrap();
+
+        let config = DiskUsageConfig {
+            targets: vec![
+                DiskUsageTarget::new("日志", &broken, CleanupAction::Clear),
+                DiskUsageTarget::new("缓存", &cache, CleanupAction::Clear),
+            ],
+            max_dir_size: 0,
+            min_free_space: 0,
+        };
+
+        let err = AutoFixer::free_disk_space(&config).unwrap_err();
+        assert!(err.contains("已清理缓存"));
+        assert!(err.contains("清理失败: 日志"));
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 0);
+    }
+
     #[tokio::test]
     async fn test_quick_health_check() {
         let (healthy, issues) = quick_health_check().await;
@@ -22,4 +22,6 @@ pub use health::{
 };
 pub use network::NetworkChecker;
 pub use report::{format_diagnostic_report, DiagnosticOptions, DiagnosticReport, SystemInfo};
-pub use system::SystemChecker;
+pub use system::{
+    CleanupAction, DirectoryUsage, DiskUsageConfig, DiskUsageReport, DiskUsageTarget, SystemChecker,
+};
@@ -3,6 +3,119 @@
 //! 提供 CPU、内存、磁盘等系统资源检查
 
 use super::checker::DiagnosticCheck;
+use crate::config::paths::Paths;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// 目录清理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CleanupAction {
+    /// 清空目录内容
+    Clear,
+    /// 删除超过指定天数未修改的条目
+    RemoveOlderThan(u32),
+    /// 不自动清理
+    Manual,
+}
+
+/// 需要统计占用的目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageTarget {
+    /// 显示名称
+    pub name: String,
+    /// 目录路径
+    pub path: PathBuf,
+    /// 超限时的清理方式
+    pub cleanup: CleanupAction,
+}
+
+impl DiskUsageTarget {
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>, cleanup: CleanupAction) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            cleanup,
+        }
+    }
+}
+
+/// 磁盘占用检查配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageConfig {
+    /// 统计的目录
+    pub targets: Vec<DiskUsageTarget>,
+    /// 单个目录的大小上限（字节）
+    pub max_dir_size: u64,
+    /// 剩余空间低于此值时警告（字节）
+    pub min_free_space: u64,
+}
+
+impl Default for DiskUsageConfig {
+    fn default() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let cache_dir = dirs::cache_dir()
+            .map(|p| p.join("aster"))
+            .unwrap_or_else(|| home.join(".cache").join("aster"));
+
+        Self {
+            targets: vec![
+                DiskUsageTarget::new(
+                    "会话",
+                    Paths::in_data_dir("sessions"),
+                    CleanupAction::Manual,
+                ),
+                DiskUsageTarget::new(
+                    "检查点",
+                    home.join(".aster").join("checkpoints"),
+                    CleanupAction::RemoveOlderThan(30),
+                ),
+                DiskUsageTarget::new("缓存", cache_dir, CleanupAction::Clear),
+                DiskUsageTarget::new(
+                    "日志",
+                    Paths::in_state_dir("logs"),
+                    CleanupAction::RemoveOlderThan(14),
+                ),
+            ],
+            max_dir_size: 500 * 1024 * 1024,
+            min_free_space: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// 单个目录的占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryUsage {
+    pub name: String,
+    pub path: PathBuf,
+    /// 总大小（字节）
+    pub size: u64,
+    /// 文件数
+    pub files: usize,
+    pub cleanup: CleanupAction,
+    /// 目录所在磁盘的剩余空间（字节），无法获取时为 None
+    pub free_space: Option<u64>,
+}
+
+/// 磁盘占用统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageReport {
+    pub directories: Vec<DirectoryUsage>,
+    /// 所有目录的总大小（字节）
+    pub total_size: u64,
+    /// 各目录所在磁盘中最小的剩余空间（字节），均无法获取时为 None
+    pub free_space: Option<u64>,
+}
+
+/// 格式化字节数
+fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= 1024.0 * MB {
+        format!("{:.2} GB", bytes as f64 / (1024.0 * MB))
+    } else {
+        format!("{:.2} MB", bytes as f64 / MB)
+    }
+}
 
 /// 系统检查器
 pub struct SystemChecker;
@@ -134,20 +247,213 @@ impl SystemChecker {
 
     /// 计算目录大小
     fn calculate_dir_size(path: &std::path::Path) -> u64 {
+        Self::scan_dir(path).0
+    }
+
+    /// 统计目录的总大小和文件数（不跟随符号链接）
+    fn scan_dir(path: &Path) -> (u64, usize) {
         let mut size = 0u64;
+        let mut files = 0usize;
 
         if let Ok(entries) = std::fs::read_dir(path) {
             for entry in entries.filter_map(|e| e.ok()) {
-                let path = entry.path();
-                if path.is_dir() {
-                    size += Self::calculate_dir_size(&path);
-                } else if let Ok(metadata) = path.metadata() {
+                let Ok(metadata) = entry.path().symlink_metadata() else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    let (dir_size, dir_files) = Self::scan_dir(&entry.path());
+                    size += dir_size;
+                    files += dir_files;
+                } else {
                     size += metadata.len();
+                    files += 1;
+                }
+            }
+        }
+
+        (size, files)
+    }
+
+    /// 获取路径所在磁盘的剩余空间（字节）
+    #[cfg(unix)]
+    fn free_space(path: &Path) -> Option<u64> {
+        // 目录可能尚未创建，使用最近的已存在上级目录
+        let existing = path.ancestors().find(|p| p.exists())?;
+        let output = std::process::Command::new("df")
+            .arg("-Pk")
+            .arg(existing)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let available_kb: u64 = stdout
+            .lines()
+            .nth(1)?
+            .split_whitespace()
+            .nth(3)?
+            .parse()
+            .ok()?;
+        Some(available_kb * 1024)
+    }
+
+    #[cfg(not(unix))]
+    fn free_space(_path: &Path) -> Option<u64> {
+        None
+    }
+
+    /// 统计各目录的磁盘占用
+    pub fn disk_usage(config: &DiskUsageConfig) -> DiskUsageReport {
+        let directories: Vec<DirectoryUsage> = config
+            .targets
+            .iter()
+            .map(|target| {
+                let (size, files) = Self::scan_dir(&target.path);
+                DirectoryUsage {
+                    name: target.name.clone(),
+                    path: target.path.clone(),
+                    size,
+                    files,
+                    cleanup: target.cleanup,
+                    free_space: Self::free_space(&target.path),
+                }
+            })
+            .collect();
+
+        DiskUsageReport {
+            total_size: directories.iter().map(|d| d.size).sum(),
+            free_space: directories.iter().filter_map(|d| d.free_space).min(),
+            directories,
+        }
+    }
+
+    /// 检查磁盘占用
+    ///
+    /// 返回占用明细，以及超过大小上限的目录和剩余空间不足的警告。
+    /// 目录警告的名称为 "目录占用"、剩余空间警告为 "剩余空间"，均可由 `AutoFixer` 清理。
+    pub fn check_disk_usage(config: &DiskUsageConfig) -> Vec<DiagnosticCheck> {
+        let report = Self::disk_usage(config);
+        let breakdown = report
+            .directories
+            .iter()
+            .map(|d| {
+                format!(
+                    "{}: {} ({} 个文件) {}",
+                    d.name,
+                    format_size(d.size),
+                    d.files,
+                    d.path.display()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut checks = vec![DiagnosticCheck::pass(
+            "磁盘占用",
+            format!("共 {}", format_size(report.total_size)),
+        )
+        .with_details(breakdown)];
+
+        for dir in report
+            .directories
+            .iter()
+            .filter(|d| d.size > config.max_dir_size)
+        {
+            let fix = match dir.cleanup {
+                CleanupAction::Clear => format!("运行自动修复清空{}目录", dir.name),
+                CleanupAction::RemoveOlderThan(days) => {
+                    format!("运行自动修复删除 {} 天前的{}", days, dir.name)
+                }
+                CleanupAction::Manual => format!("请手动清理: {}", dir.path.display()),
+            };
+            checks.push(
+                DiagnosticCheck::warn(
+                    "目录占用",
+                    format!(
+                        "{}目录占用 {}，超过上限 {}",
+                        dir.name,
+                        format_size(dir.size),
+                        format_size(config.max_dir_size)
+                    ),
+                )
+                .with_details(format!("路径: {}", dir.path.display()))
+                .with_fix(fix),
+            );
+        }
+
+        if let Some(free) = report.free_space {
+            if free < config.min_free_space {
+                let low = report
+                    .directories
+                    .iter()
+                    .filter(|d| d.free_space.is_some_and(|f| f < config.min_free_space))
+                    .map(|d| d.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("、");
+                checks.push(
+                    DiagnosticCheck::warn(
+                        "剩余空间",
+                        format!("磁盘剩余空间不足: {}", format_size(free)),
+                    )
+                    .with_details(format!(
+                        "空间不足的目录: {}，建议至少保留 {}",
+                        low,
+                        format_size(config.min_free_space)
+                    ))
+                    .with_fix("运行自动修复清理缓存、过期日志和检查点"),
+                );
+            }
+        }
+
+        checks
+    }
+
+    /// 按清理方式清理目录，返回释放的字节数
+    pub fn cleanup_directory(path: &Path, action: CleanupAction) -> Result<u64, String> {
+        let cutoff = match action {
+            CleanupAction::Manual => {
+                return Err(format!("{} 需要手动清理", path.display()));
+            }
+            CleanupAction::Clear => None,
+            CleanupAction::RemoveOlderThan(days) => {
+                Some(SystemTime::now() - Duration::from_secs(days as u64 * 24 * 60 * 60))
+            }
+        };
+
+        let entries = match std::fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(format!("无法读取目录 {}: {}", path.display(), e)),
+        };
+
+        let mut freed = 0u64;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            let Ok(metadata) = entry_path.symlink_metadata() else {
+                continue;
+            };
+            if let Some(cutoff) = cutoff {
+                if metadata.modified().map(|m| m >= cutoff).unwrap_or(true) {
+                    continue;
                 }
             }
+
+            let (size, result) = if metadata.is_dir() {
+                (
+                    Self::scan_dir(&entry_path).0,
+                    std::fs::remove_dir_all(&entry_path),
+                )
+            } else {
+                (metadata.len(), std::fs::remove_file(&entry_path))
+            };
+            match result {
+                Ok(()) => freed += size,
+                Err(e) => tracing::warn!("清理 {} 失败: {}", entry_path.display(), e),
+            }
         }
 
-        size
+        Ok(freed)
     }
 
     /// 检查 MCP 服务器配置
@@ -241,6 +547,59 @@ mod tests {
         let _ = size;
     }
 
+    #[test]
+    fn test_disk_usage_breakdown() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let sessions = root.join("sessions");
+        let cache = root.join("cache");
+        std::fs::create_dir_all(sessions.join("archive")).unwrap();
+        std::fs::create_dir_all(cache.join("a").join("b")).unwrap();
+        std::fs::write(sessions.join("s1.jsonl"), vec![0u8; 100]).unwrap();
+        std::fs::write(sessions.join("archive").join("s0.jsonl"), vec![0u8; 200]).unwrap();
+        std::fs::write(cache.join("a").join("b").join("blob"), vec![0u8; 1000]).unwrap();
+        std::fs::write(cache.join("index"), vec![0u8; 24]).unwrap();
+
+        let config = DiskUsageConfig {
+            targets: vec![
+                DiskUsageTarget::new("会话", &sessions, CleanupAction::Manual),
+                DiskUsageTarget::new("缓存", &cache, CleanupAction::Clear),
+                DiskUsageTarget::new(
+                    "日志",
+                    root.join("logs"),
+                    CleanupAction::RemoveOlderThan(14),
+                ),
+            ],
+            max_dir_size: 500,
+            min_free_space: 0,
+        };
+
+        let report = SystemChecker::disk_usage(&config);
+        let sizes: Vec<(u64, usize)> = report
+            .directories
+            .iter()
+            .map(|d| (d.size, d.files))
+            .collect();
+        assert_eq!(sizes, vec![(300, 2), (1024, 2), (0, 0)]);
+        assert_eq!(report.total_size, 1324);
+        assert_eq!(report.total_size, SystemChecker::calculate_dir_size(root));
+
+        let checks = SystemChecker::check_disk_usage(&config);
+        assert_eq!(checks[0].name, "磁盘占用");
+        assert!(checks[0].details.as_ref().unwrap().contains("缓存: "));
+        let oversized: Vec<_> = checks.iter().filter(|c| c.name == "目录占用").collect();
+        assert_eq!(oversized.len(), 1);
+        assert_eq!(
+            oversized[0].details.as_deref(),
+            Some(format!("路径: {}", cache.display()).as_str())
+        );
+
+        let freed = SystemChecker::cleanup_directory(&cache, CleanupAction::Clear).unwrap();
+        assert_eq!(freed, 1024);
+        assert_eq!(SystemChecker::calculate_dir_size(&cache), 0);
+        assert!(SystemChecker::cleanup_directory(&sessions, CleanupAction::Manual).is_err());
+    }
+
     #[test]
     fn test_calculate_dir_size_nonexistent() {
         let path = std::path::Path::new("/nonexistent/path/12345");
@@ -0,0 +1,510 @@
+//! 提交信息生成
+//!
+//! 读取暂存区的差异，交给模型总结，生成 Conventional Commits 格式的提交信息
+//! 建议。提交类型（feat/fix/docs 等）先根据变更文件推断，作为模型的提示和回退值。
+//! 传给模型的差异有长度上限，超出时截断并在提示中注明。生成的信息只是建议，
+//! 调用方可以修改后再提交。
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use super::diff::{diff_stat, diff_stream, DiffOptions, DiffStat};
+use crate::conversation::message::Message;
+use crate::providers::base::Provider;
+
+/// 默认传给模型的差异字符数上限
+pub const DEFAULT_MAX_COMMIT_DIFF_CHARS: usize = 12_000;
+
+/// 提交信息生成的系统提示
+pub const COMMIT_MESSAGE_SYSTEM_PROMPT: &str =
+    "You write git commit messages in the Conventional Commits format.\n\
+     Reply with only the commit message: a header line `type(scope): subject` \
+     (scope optional, subject in imperative mood, at most 72 characters), \
+     optionally followed by a blank line and a short body explaining why.\n\
+     Allowed types: feat, fix, docs, style, refactor, perf, test, build, ci, chore.";
+
+static HEADER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([a-zA-Z]+)(?:\(([^)]+)\))?(!)?:\s*(.+)$").expect("valid commit header regex")
+});
+
+/// Conventional Commits 提交类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitType {
+    Feat,
+    Fix,
+    Docs,
+    Style,
+    Refactor,
+    Perf,
+    Test,
+    Build,
+    Ci,
+    Chore,
+}
+
+impl CommitType {
+    /// 类型前缀
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Feat => "feat",
+            Self::Fix => "fix",
+            Self::Docs => "docs",
+            Self::Style => "style",
+            Self::Refactor => "refactor",
+            Self::Perf => "perf",
+            Self::Test => "test",
+            Self::Build => "build",
+            Self::Ci => "ci",
+            Self::Chore => "chore",
+        }
+    }
+
+    /// 从类型前缀解析（不区分大小写）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "feat" => Some(Self::Feat),
+            "fix" => Some(Self::Fix),
+            "docs" => Some(Self::Docs),
+            "style" => Some(Self::Style),
+            "refactor" => Some(Self::Refactor),
+            "perf" => Some(Self::Perf),
+            "test" => Some(Self::Test),
+            "build" => Some(Self::Build),
+            "ci" => Some(Self::Ci),
+            "chore" => Some(Self::Chore),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CommitType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 提交信息建议
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitMessageSuggestion {
+    /// 提交类型
+    pub commit_type: CommitType,
+    /// 作用范围
+    pub scope: Option<String>,
+    /// 是否为不兼容变更（`!`）
+    pub breaking: bool,
+    /// 标题
+    pub subject: String,
+    /// 正文
+    pub body: Option<String>,
+    /// 传给模型的差异是否被截断
+    pub diff_truncated: bool,
+    /// 暂存的文件及增删行数
+    pub files: Vec<DiffStat>,
+}
+
+impl CommitMessageSuggestion {
+    /// 标题行，如 `feat(git): add diff streaming`
+    pub fn header(&self) -> String {
+        let scope = self
+            .scope
+            .as_ref()
+            .map(|s| format!("({})", s))
+            .unwrap_or_default();
+        let breaking = if self.breaking { "!" } else { "" };
+        format!(
+            "{}{}{}: {}",
+            self.commit_type, scope, breaking, self.subject
+        )
+    }
+
+    /// 完整提交信息
+    pub fn message(&self) -> String {
+        match &self.body {
+            Some(body) => format!("{}\n\n{}", self.header(), body),
+            None => self.header(),
+        }
+    }
+}
+
+impl fmt::Display for CommitMessageSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+/// 根据变更文件推断提交类型
+pub fn infer_commit_type(files: &[DiffStat]) -> CommitType {
+    let all = |pred: fn(&str) -> bool| !files.is_empty() && files.iter().all(|f| pred(&f.path));
+
+    if all(is_docs_path) {
+        CommitType::Docs
+    } else if all(is_test_path) {
+        CommitType::Test
+    } else if all(|p| p.starts_with(".github/") || p.starts_with(".gitlab-ci")) {
+        CommitType::Ci
+    } else if all(is_build_path) {
+        CommitType::Build
+    } else if files.iter().any(|f| f.deletions == 0 && f.additions > 0) {
+        // 只有新增内容的文件，多半是新功能
+        CommitType::Feat
+    } else {
+        CommitType::Fix
+    }
+}
+
+fn is_docs_path(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    // .txt 常见于测试数据和依赖清单（如 requirements.txt），不算文档
+    lower.starts_with("docs/") || lower.ends_with(".md") || lower.ends_with(".rst")
+}
+
+fn is_test_path(path: &str) -> bool {
+    path.starts_with("tests/")
+        || path.contains("/tests/")
+        || path.contains("_test.")
+        || path.contains(".test.")
+        || path.contains("_tests.")
+}
+
+fn is_build_path(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    matches!(
+        name,
+        "Cargo.toml"
+            | "Cargo.lock"
+            | "build.rs"
+            | "package.json"
+            | "package-lock.json"
+            | "Makefile"
+            | "Dockerfile"
+    )
+}
+
+/// 收集暂存区差异，总长度不超过 `max_chars`
+///
+/// 返回差异文本和是否截断，长度按字符计。二进制文件只记录文件名。
+fn collect_staged_diff(cwd: &Path, max_chars: usize) -> Result<(String, bool), String> {
+    let options = DiffOptions {
+        staged: true,
+        ..Default::default()
+    };
+
+    let mut diff = String::new();
+    let mut used = 0;
+    for file in diff_stream(cwd, &options)? {
+        let file = file?;
+        let chunk = match file.patch {
+            Some(patch) => patch,
+            None => format!("Binary file {} changed\n", file.path),
+        };
+        let len = chunk.chars().count();
+        if used + len > max_chars {
+            // 丢弃迭代器会终止 git 进程，不再读取剩余差异
+            diff.extend(chunk.chars().take(max_chars - used));
+            return Ok((diff, true));
+        }
+        diff.push_str(&chunk);
+        used += len;
+    }
+    Ok((diff, false))
+}
+
+/// 构造发给模型的提示
+fn build_prompt(files: &[DiffStat], inferred: CommitType, diff: &str, truncated: bool) -> String {
+    let mut prompt = String::from("Staged files:\n");
+    for file in files {
+        if file.binary {
+            prompt.push_str(&format!("  {} (binary)\n", file.path));
+        } else {
+            prompt.push_str(&format!(
+                "  {} (+{} -{})\n",
+                file.path, file.additions, file.deletions
+            ));
+        }
+    }
+    prompt.push_str(&format!(
+        "\nSuggested type based on the files changed: {}\n\nDiff:\n{}",
+        inferred, diff
+    ));
+    if truncated {
+        prompt.push_str(
+            "\n[Diff truncated to fit the size limit; base the message on the file list \
+             and the visible part of the diff.]\n",
+        );
+    }
+    prompt
+}
+
+/// 解析模型返回的提交信息
+///
+/// 标题不符合 Conventional Commits 格式或类型无效时使用推断的类型。
+fn parse_response(
+    response: &str,
+    inferred: CommitType,
+) -> Option<(CommitType, Option<String>, bool, String, Option<String>)> {
+    // 去掉模型可能添加的代码块标记
+    let text: Vec<&str> = response
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("```"))
+        .collect();
+    let start = text.iter().position(|l| !l.trim().is_empty())?;
+    let header = text[start].trim();
+    let body = text[start + 1..].join("\n").trim().to_string();
+    let body = (!body.is_empty()).then_some(body);
+
+    let parsed = HEADER_RE.captures(header).and_then(|caps| {
+        let commit_type = CommitType::parse(&caps[1])?;
+        Some((
+            commit_type,
+            caps.get(2).map(|m| m.as_str().trim().to_string()),
+            caps.get(3).is_some(),
+            caps[4].trim().to_string(),
+        ))
+    });
+    let (commit_type, scope, breaking, subject) =
+        parsed.unwrap_or_else(|| (inferred, None, false, header.to_string()));
+    Some((commit_type, scope, breaking, subject, body))
+}
+
+/// 根据暂存区的变更生成提交信息建议
+///
+/// `max_diff_chars` 限制传给模型的差异长度，超出部分被截断
+/// （`diff_truncated` 为 true）。没有暂存变更时返回错误。
+pub async fn suggest_commit_message(
+    cwd: &Path,
+    provider: &dyn Provider,
+    max_diff_chars: usize,
+) -> Result<CommitMessageSuggestion, String> {
+    // 读取差异会阻塞等待 git 进程，放到阻塞线程池中执行
+    let repo = cwd.to_path_buf();
+    let (files, diff, diff_truncated) = tokio::task::spawn_blocking(move || {
+        let files = diff_stat(
+            &repo,
+            &DiffOptions {
+                staged: true,
+                ..Default::default()
+            },
+        )?;
+        if files.is_empty() {
+            return Err("没有暂存的变更".to_string());
+        }
+        let (diff, truncated) = collect_staged_diff(&repo, max_diff_chars)?;
+        Ok((files, diff, truncated))
+    })
+    .await
+    .map_err(|e| format!("读取暂存差异失败: {}", e))??;
+
+    let inferred = infer_commit_type(&files);
+    let prompt = build_prompt(&files, inferred, &diff, diff_truncated);
+
+    let (response, _usage) = provider
+        .complete_fast(
+            COMMIT_MESSAGE_SYSTEM_PROMPT,
+            &[Message::user().with_text(&prompt)],
+            &[],
+        )
+        .await
+        .map_err(|e| format!("生成提交信息失败: {}", e))?;
+
+    let (commit_type, scope, breaking, subject, body) =
+        parse_response(&response.as_concat_text(), inferred)
+            .ok_or_else(|| "模型未返回提交信息".to_string())?;
+
+    Ok(CommitMessageSuggestion {
+        commit_type,
+        scope,
+        breaking,
+        subject,
+        body,
+        diff_truncated,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use crate::providers::errors::ProviderError;
+    use async_trait::async_trait;
+    use rmcp::model::Tool;
+    use std::fs;
+    use std::process::Command;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// 返回固定回复并记录收到的提示
+    struct MockProvider {
+        reply: String,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl MockProvider {
+        fn new(reply: &str) -> Self {
+            Self {
+                reply: reply.to_string(),
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::new("mock", "", "", "", vec![""], "", vec![])
+        }
+
+        fn get_name(&self) -> &str {
+            "mock"
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.prompts
+                .lock()
+                .unwrap()
+                .push(messages[0].as_concat_text());
+            Ok((
+                Message::assistant().with_text(&self.reply),
+                ProviderUsage::new("mock-model".to_string(), Usage::default()),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("mock-model")
+        }
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn repo_with_staged_feature() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod a;\n").unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+
+        fs::write(root.join("src/lib.rs"), "pub mod a;\npub mod stream;\n").unwrap();
+        fs::write(
+            root.join("src/stream.rs"),
+            "pub fn stream() -> usize {\n    42\n}\n".repeat(20),
+        )
+        .unwrap();
+        git(root, &["add", "-A"]);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_suggest_commit_message_format() {
+        let dir = repo_with_staged_feature();
+        let provider = MockProvider::new(
+            "```\nfeat(git): add diff streaming\n\nStream diffs file by file.\n```",
+        );
+
+        let suggestion = suggest_commit_message(dir.path(), &provider, 100_000)
+            .await
+            .unwrap();
+        assert_eq!(suggestion.commit_type, CommitType::Feat);
+        assert_eq!(suggestion.scope.as_deref(), Some("git"));
+        assert_eq!(
+            suggestion.message(),
+            "feat(git): add diff streaming\n\nStream diffs file by file."
+        );
+        assert!(!suggestion.diff_truncated);
+        assert_eq!(suggestion.files.len(), 2);
+
+        let prompt = provider.prompts.lock().unwrap()[0].clone();
+        assert!(prompt.contains("src/stream.rs (+60 -0)"));
+        assert!(prompt.contains("Suggested type based on the files changed: feat"));
+        assert!(prompt.contains("+pub mod stream;"));
+        assert!(!prompt.contains("truncated"));
+    }
+
+    #[tokio::test]
+    async fn test_suggest_commit_message_truncates_and_falls_back() {
+        let dir = repo_with_staged_feature();
+        let provider = MockProvider::new("Add streaming module");
+
+        let suggestion = suggest_commit_message(dir.path(), &provider, 200)
+            .await
+            .unwrap();
+        assert!(suggestion.diff_truncated);
+        assert_eq!(suggestion.header(), "feat: Add streaming module");
+        assert!(suggestion.body.is_none());
+
+        let prompt = provider.prompts.lock().unwrap()[0].clone();
+        assert!(prompt.contains("[Diff truncated"));
+        assert!(prompt.len() < 1000);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_commit_message_requires_staged_changes() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let provider = MockProvider::new("chore: nothing");
+        assert!(suggest_commit_message(dir.path(), &provider, 1000)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_infer_commit_type() {
+        let stat = |path: &str, additions, deletions| DiffStat {
+            path: path.to_string(),
+            old_path: None,
+            binary: false,
+            additions,
+            deletions,
+        };
+        assert_eq!(
+            infer_commit_type(&[stat("README.md", 3, 1), stat("docs/guide.md", 2, 0)]),
+            CommitType::Docs
+        );
+        assert_eq!(
+            infer_commit_type(&[stat("tests/agent.rs", 10, 2)]),
+            CommitType::Test
+        );
+        assert_eq!(
+            infer_commit_type(&[stat(".github/workflows/ci.yml", 1, 1)]),
+            CommitType::Ci
+        );
+        assert_eq!(
+            infer_commit_type(&[stat("Cargo.toml", 1, 1)]),
+            CommitType::Build
+        );
+        assert_eq!(
+            infer_commit_type(&[stat("src/lib.rs", 4, 4)]),
+            CommitType::Fix
+        );
+        assert_eq!(
+            infer_commit_type(&[stat("requirements.txt", 1, 1)]),
+            CommitType::Fix
+        );
+        assert_eq!(
+            infer_commit_type(&[stat("tests/fixtures/input.txt", 2, 0)]),
+            CommitType::Test
+        );
+    }
+}
@@ -0,0 +1,407 @@
+//! Git 冲突标记解析与解决
+//!
+//! 解析合并后文件中的 `<<<<<<<` / `=======` / `>>>>>>>` 冲突块，
+//! 支持 diff3 格式（`|||||||` 基线部分）和嵌套冲突（内层使用更长的标记，
+//! 或出现在某一侧内容中的完整冲突块），并按选择的一侧解决冲突。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 冲突标记的最小长度（Git 默认 conflict-marker-size）
+pub const DEFAULT_MARKER_SIZE: usize = 7;
+
+/// 单个冲突块
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictBlock {
+    /// 冲突编号（从 1 开始，按出现顺序）
+    pub id: usize,
+    /// `<<<<<<<` 所在行号（从 1 开始）
+    pub start_line: usize,
+    /// `>>>>>>>` 所在行号（从 1 开始）
+    pub end_line: usize,
+    /// 标记长度
+    pub marker_size: usize,
+    /// 我方标签（如 `HEAD`）
+    pub ours_label: Option<String>,
+    /// 我方内容
+    pub ours: String,
+    /// 基线标签（仅 diff3 格式）
+    pub base_label: Option<String>,
+    /// 基线内容（仅 diff3 格式）
+    pub base: Option<String>,
+    /// 对方标签（如分支名）
+    pub theirs_label: Option<String>,
+    /// 对方内容
+    pub theirs: String,
+}
+
+/// 冲突解决方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// 保留我方
+    Ours,
+    /// 保留对方
+    Theirs,
+    /// 先我方后对方，两者都保留
+    Both,
+}
+
+impl ConflictResolution {
+    /// 从字符串解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ours" => Some(Self::Ours),
+            "theirs" => Some(Self::Theirs),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    /// 字符串表示
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ours => "ours",
+            Self::Theirs => "theirs",
+            Self::Both => "both",
+        }
+    }
+}
+
+/// 冲突标记类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    Start,
+    Base,
+    Separator,
+    End,
+}
+
+/// 识别冲突标记行，返回标记类型、长度和标签
+fn parse_marker(line: &str) -> Option<(Marker, usize, Option<String>)> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let first = line.chars().next()?;
+    let marker = match first {
+        '<' => Marker::Start,
+        '|' => Marker::Base,
+        '=' => Marker::Separator,
+        '>' => Marker::End,
+        _ => return None,
+    };
+    let size = line.chars().take_while(|c| *c == first).count();
+    if size < DEFAULT_MARKER_SIZE {
+        return None;
+    }
+
+    let rest = &line[size..];
+    let label = match marker {
+        // 分隔符后面不能有内容
+        Marker::Separator if !rest.trim().is_empty() => return None,
+        Marker::Separator => None,
+        _ if rest.is_empty() => None,
+        _ if rest.starts_with(' ') => Some(rest.trim().to_string()).filter(|l| !l.is_empty()),
+        _ => return None,
+    };
+    Some((marker, size, label))
+}
+
+/// 冲突块内当前所在的部分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Ours,
+    Base,
+    Theirs,
+}
+
+/// 解析中的冲突块
+struct PendingBlock {
+    start_line: usize,
+    marker_size: usize,
+    ours_label: Option<String>,
+    base_label: Option<String>,
+    ours: String,
+    base: Option<String>,
+    theirs: String,
+    section: Section,
+    /// 同长度标记的嵌套深度，嵌套部分按普通内容处理
+    depth: usize,
+}
+
+impl PendingBlock {
+    fn push(&mut self, line: &str) {
+        match self.section {
+            Section::Ours => self.ours.push_str(line),
+            Section::Base => self.base.get_or_insert_with(String::new).push_str(line),
+            Section::Theirs => self.theirs.push_str(line),
+        }
+    }
+}
+
+/// 解析内容中的所有冲突块
+///
+/// 块内长度不同的标记、以及完整出现在某一侧中的同长度冲突块都视为该侧的内容。
+/// 未闭合或结构错误的冲突块返回错误。
+pub fn parse_conflicts(content: &str) -> Result<Vec<ConflictBlock>, String> {
+    let mut blocks = Vec::new();
+    let mut pending: Option<PendingBlock> = None;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        let marker = parse_marker(line);
+
+        let Some(block) = pending.as_mut() else {
+            if let Some((Marker::Start, marker_size, label)) = marker {
+                pending = Some(PendingBlock {
+                    start_line: line_number,
+                    marker_size,
+                    ours_label: label,
+                    base_label: None,
+                    ours: String::new(),
+                    base: None,
+                    theirs: String::new(),
+                    section: Section::Ours,
+                    depth: 0,
+                });
+            }
+            continue;
+        };
+
+        let marker = marker.filter(|(_, size, _)| *size == block.marker_size);
+        match (marker, block.depth) {
+            (Some((Marker::Start, ..)), _) => {
+                block.depth += 1;
+                block.push(line);
+            }
+            (Some((Marker::End, ..)), depth) if depth > 0 => {
+                block.depth -= 1;
+                block.push(line);
+            }
+            (Some((Marker::Base, _, label)), 0) => {
+                if block.section != Section::Ours {
+                    return Err(format!("第 {} 行: 意外的基线标记", line_number));
+                }
+                block.section = Section::Base;
+                block.base_label = label;
+                block.base = Some(String::new());
+            }
+            (Some((Marker::Separator, ..)), 0) => {
+                if block.section == Section::Theirs {
+                    return Err(format!("第 {} 行: 重复的分隔标记", line_number));
+                }
+                block.section = Section::Theirs;
+            }
+            (Some((Marker::End, _, label)), 0) => {
+                if block.section != Section::Theirs {
+                    return Err(format!(
+                        "第 {} 行: 冲突块缺少分隔标记 (起始于第 {} 行)",
+                        line_number, block.start_line
+                    ));
+                }
+                let block = pending.take().expect("pending block");
+                blocks.push(ConflictBlock {
+                    id: blocks.len() + 1,
+                    start_line: block.start_line,
+                    end_line: line_number,
+                    marker_size: block.marker_size,
+                    ours_label: block.ours_label,
+                    ours: block.ours,
+                    base_label: block.base_label,
+                    base: block.base,
+                    theirs_label: label,
+                    theirs: block.theirs,
+                });
+            }
+            _ => block.push(line),
+        }
+    }
+
+    match pending {
+        Some(block) => Err(format!("第 {} 行起始的冲突块未闭合", block.start_line)),
+        None => Ok(blocks),
+    }
+}
+
+/// 内容中是否包含冲突标记
+pub fn has_conflicts(content: &str) -> bool {
+    content
+        .split_inclusive('\n')
+        .any(|line| matches!(parse_marker(line), Some((Marker::Start, ..))))
+}
+
+/// 按编号解决冲突块，未指定的冲突块保持原样
+///
+/// `choices` 的键为 `ConflictBlock::id`。
+pub fn resolve_conflicts(
+    content: &str,
+    choices: &HashMap<usize, ConflictResolution>,
+) -> Result<String, String> {
+    let blocks = parse_conflicts(content)?;
+    if let Some(id) = choices
+        .keys()
+        .find(|id| !blocks.iter().any(|b| b.id == **id))
+    {
+        return Err(format!("冲突 {} 不存在", id));
+    }
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut output = String::with_capacity(content.len());
+    let mut next_line = 1;
+    for block in &blocks {
+        let Some(resolution) = choices.get(&block.id) else {
+            continue;
+        };
+        for line in &lines[next_line - 1..block.start_line - 1] {
+            output.push_str(line);
+        }
+        match resolution {
+            ConflictResolution::Ours => output.push_str(&block.ours),
+            ConflictResolution::Theirs => output.push_str(&block.theirs),
+            ConflictResolution::Both => {
+                output.push_str(&block.ours);
+                output.push_str(&block.theirs);
+            }
+        }
+        next_line = block.end_line + 1;
+    }
+    for line in &lines[next_line - 1..] {
+        output.push_str(line);
+    }
+    Ok(output)
+}
+
+/// 用同一种方式解决所有冲突块
+pub fn resolve_all_conflicts(
+    content: &str,
+    resolution: ConflictResolution,
+) -> Result<String, String> {
+    let choices = parse_conflicts(content)?
+        .iter()
+        .map(|block| (block.id, resolution))
+        .collect();
+    resolve_conflicts(content, &choices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_CONFLICTS: &str = "\
+fn main() {
+<<<<<<< HEAD
+    println!(\"ours\");
+=======
+    println!(\"theirs\");
+>>>>>>> feature
+    let shared = 1;
+<<<<<<< HEAD
+    let x = 1;
+||||||| merged common ancestors
+    let x = 0;
+=======
+    let x = 2;
+    let y = 3;
+>>>>>>> feature
+}
+";
+
+    #[test]
+    fn test_parse_two_conflict_blocks() {
+        let blocks = parse_conflicts(TWO_CONFLICTS).unwrap();
+        assert_eq!(blocks.len(), 2);
+
+        assert_eq!(blocks[0].id, 1);
+        assert_eq!((blocks[0].start_line, blocks[0].end_line), (2, 6));
+        assert_eq!(blocks[0].ours_label.as_deref(), Some("HEAD"));
+        assert_eq!(blocks[0].ours, "    println!(\"ours\");\n");
+        assert_eq!(blocks[0].theirs, "    println!(\"theirs\");\n");
+        assert_eq!(blocks[0].theirs_label.as_deref(), Some("feature"));
+        assert!(blocks[0].base.is_none());
+
+        assert_eq!((blocks[1].start_line, blocks[1].end_line), (8, 15));
+        assert_eq!(blocks[1].ours, "    let x = 1;\n");
+        assert_eq!(blocks[1].base.as_deref(), Some("    let x = 0;\n"));
+        assert_eq!(
+            blocks[1].base_label.as_deref(),
+            Some("merged common ancestors")
+        );
+        assert_eq!(blocks[1].theirs, "    let x = 2;\n    let y = 3;\n");
+    }
+
+    #[test]
+    fn test_resolve_chosen_sides() {
+        let choices = HashMap::from([
+            (1, ConflictResolution::Theirs),
+            (2, ConflictResolution::Ours),
+        ]);
+        assert_eq!(
+            resolve_conflicts(TWO_CONFLICTS, &choices).unwrap(),
+            "fn main() {\n    println!(\"theirs\");\n    let shared = 1;\n    let x = 1;\n}\n"
+        );
+
+        // 只解决第二个冲突，第一个保持原样
+        let partial = resolve_conflicts(
+            TWO_CONFLICTS,
+            &HashMap::from([(2, ConflictResolution::Both)]),
+        )
+        .unwrap();
+        assert!(partial.starts_with("fn main() {\n<<<<<<< HEAD\n"));
+        assert!(partial.ends_with("    let x = 1;\n    let x = 2;\n    let y = 3;\n}\n"));
+        assert_eq!(parse_conflicts(&partial).unwrap().len(), 1);
+
+        assert!(resolve_conflicts(
+            TWO_CONFLICTS,
+            &HashMap::from([(3, ConflictResolution::Ours)])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_nested_conflicts_are_side_content() {
+        let content = "\
+<<<<<<<< HEAD
+<<<<<<< inner
+a
+=======
+b
+>>>>>>> other
+========
+c
+>>>>>>>> branch
+";
+        let blocks = parse_conflicts(content).unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].marker_size, 8);
+        assert_eq!(
+            blocks[0].ours,
+            "<<<<<<< inner\na\n=======\nb\n>>>>>>> other\n"
+        );
+        assert_eq!(blocks[0].theirs, "c\n");
+
+        let same_size = "\
+<<<<<<< HEAD
+<<<<<<< HEAD
+a
+=======
+b
+>>>>>>> x
+=======
+c
+>>>>>>> y
+";
+        let blocks = parse_conflicts(same_size).unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].ours, "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> x\n");
+        assert_eq!(blocks[0].theirs_label.as_deref(), Some("y"));
+    }
+
+    #[test]
+    fn test_malformed_conflicts() {
+        assert!(parse_conflicts("<<<<<<< HEAD\na\n=======\nb\n").is_err());
+        assert!(parse_conflicts("<<<<<<< HEAD\na\n>>>>>>> x\n").is_err());
+        assert!(parse_conflicts("a\n======= not a marker\nb\n")
+            .unwrap()
+            .is_empty());
+        assert!(!has_conflicts("x\n=======\n"));
+        assert!(has_conflicts(TWO_CONFLICTS));
+    }
+}
@@ -3,7 +3,7 @@
 //! 提供 Git 状态检测、分支信息等基础功能
 
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Git 状态
@@ -153,6 +153,21 @@ pub fn get_git_status(cwd: &Path) -> Result<GitStatus, String> {
     })
 }
 
+/// 获取仓库根目录
+pub fn get_repo_root(cwd: &Path) -> Result<PathBuf, String> {
+    GitUtils::exec_git(&["rev-parse", "--show-toplevel"], cwd).map(PathBuf::from)
+}
+
+/// 获取存在未解决合并冲突的文件（相对仓库根目录）
+pub fn get_conflicted_files(cwd: &Path) -> Result<Vec<String>, String> {
+    let output = GitUtils::exec_git(&["diff", "--name-only", "--diff-filter=U"], cwd)?;
+    Ok(output
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect())
+}
+
 /// 检查是否有上游分支
 #[allow(dead_code)]
 pub fn has_upstream(cwd: &Path) -> bool {
@@ -0,0 +1,505 @@
+//! Git 增量差异
+//!
+//! 以流的方式读取 `git diff` 输出，逐个文件产出差异块，避免一次性加载
+//! 大型变更集；另提供只返回文件路径和增删行数的摘要模式（类似 `--stat`）。
+//! 二进制文件只标记为二进制，不包含内容。
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::thread::JoinHandle;
+
+/// 差异选项
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+    /// 比较对象（提交、分支或范围，如 `main...HEAD`），为空时比较工作区
+    pub revision: Option<String>,
+    /// 比较暂存区（`--cached`）
+    pub staged: bool,
+    /// 限定路径
+    pub paths: Vec<String>,
+    /// 上下文行数（`-U`）
+    pub context_lines: Option<u32>,
+}
+
+impl DiffOptions {
+    /// 拒绝以 `-` 开头的比较对象，避免被 git 当作选项解析
+    fn validate(&self) -> Result<(), String> {
+        match &self.revision {
+            Some(revision) if revision.starts_with('-') => {
+                Err(format!("无效的比较对象: {}", revision))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn args(&self, extra: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = ["diff", "--no-color", "--no-ext-diff"]
+            .iter()
+            .chain(extra)
+            .map(|s| s.to_string())
+            .collect();
+        if self.staged {
+            args.push("--cached".to_string());
+        }
+        if let Some(lines) = self.context_lines {
+            args.push(format!("-U{}", lines));
+        }
+        if let Some(revision) = &self.revision {
+            args.push(revision.clone());
+        }
+        args.push("--".to_string());
+        args.extend(self.paths.iter().cloned());
+        args
+    }
+}
+
+/// 文件变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+}
+
+/// 单个文件的差异
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileDiff {
+    /// 文件路径（删除时为原路径）
+    pub path: String,
+    /// 重命名前的路径
+    pub old_path: Option<String>,
+    /// 变更类型
+    pub kind: FileChangeKind,
+    /// 是否为二进制文件
+    pub binary: bool,
+    /// 新增行数
+    pub additions: usize,
+    /// 删除行数
+    pub deletions: usize,
+    /// 该文件的完整 diff 文本（二进制文件为 None）
+    pub patch: Option<String>,
+}
+
+/// 单个文件的差异统计
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStat {
+    /// 文件路径
+    pub path: String,
+    /// 重命名前的路径
+    pub old_path: Option<String>,
+    /// 是否为二进制文件（此时行数为 0）
+    pub binary: bool,
+    /// 新增行数
+    pub additions: usize,
+    /// 删除行数
+    pub deletions: usize,
+}
+
+/// 逐文件读取 `git diff` 输出的迭代器
+///
+/// 每次只在内存中保留一个文件的差异。迭代器被丢弃时终止 git 进程。
+pub struct DiffStream {
+    child: Child,
+    reader: BufReader<ChildStdout>,
+    /// 后台读取 stderr 的线程，避免 stderr 写满管道后 git 阻塞
+    stderr: Option<JoinHandle<String>>,
+    /// 已读取的下一个文件的 `diff --git` 行
+    pending: Option<String>,
+    finished: bool,
+}
+
+/// 按文件流式读取差异
+pub fn diff_stream(cwd: &Path, options: &DiffOptions) -> Result<DiffStream, String> {
+    options.validate()?;
+    let mut child = Command::new("git")
+        .args(options.args(&[]))
+        .current_dir(cwd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("执行 git 命令失败: {}", e))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "无法读取 git diff 输出".to_string())?;
+    let stderr = child.stderr.take().map(|mut err| {
+        std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = err.read_to_string(&mut output);
+            output
+        })
+    });
+
+    Ok(DiffStream {
+        child,
+        reader: BufReader::new(stdout),
+        stderr,
+        pending: None,
+        finished: false,
+    })
+}
+
+/// 差异摘要：每个文件的路径和增删行数
+pub fn diff_stat(cwd: &Path, options: &DiffOptions) -> Result<Vec<DiffStat>, String> {
+    options.validate()?;
+    let output = Command::new("git")
+        .args(options.args(&["--numstat", "-z"]))
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| format!("执行 git 命令失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git diff 失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+impl DiffStream {
+    /// 读取一行（不含换行符），到达末尾时返回 None
+    fn read_line(&mut self) -> std::io::Result<Option<String>> {
+        let mut buf = Vec::new();
+        if self.reader.read_until(b'\n', &mut buf)? == 0 {
+            return Ok(None);
+        }
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+        }
+        Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+    }
+
+    /// 等待 git 退出并检查状态
+    fn finish(&mut self) -> Result<(), String> {
+        self.finished = true;
+        let status = self
+            .child
+            .wait()
+            .map_err(|e| format!("等待 git 进程失败: {}", e))?;
+        let stderr = self
+            .stderr
+            .take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+        if status.success() {
+            return Ok(());
+        }
+        Err(format!("git diff 失败: {}", stderr.trim()))
+    }
+}
+
+impl Iterator for DiffStream {
+    type Item = Result<FileDiff, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut chunk: Vec<String> = self.pending.take().into_iter().collect();
+        loop {
+            match self.read_line() {
+                Ok(Some(line)) => {
+                    if line.starts_with("diff --git ") && !chunk.is_empty() {
+                        self.pending = Some(line);
+                        return Some(Ok(parse_file_diff(&chunk)));
+                    }
+                    chunk.push(line);
+                }
+                Ok(None) => {
+                    if let Err(e) = self.finish() {
+                        return Some(Err(e));
+                    }
+                    return (!chunk.is_empty()).then(|| Ok(parse_file_diff(&chunk)));
+                }
+                Err(e) => {
+                    self.finished = true;
+                    let _ = self.child.kill();
+                    let _ = self.child.wait();
+                    return Some(Err(format!("读取 git diff 输出失败: {}", e)));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for DiffStream {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// 去掉 git 的引号和 `a/` / `b/` 前缀
+fn strip_diff_path(path: &str) -> String {
+    let path = path.trim();
+    let path = path
+        .strip_prefix('"')
+        .and_then(|p| p.strip_suffix('"'))
+        .unwrap_or(path);
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// 解析单个文件的差异块
+fn parse_file_diff(lines: &[String]) -> FileDiff {
+    let mut old_path = None;
+    let mut new_path = None;
+    let mut kind = FileChangeKind::Modified;
+    let mut binary = false;
+    let mut in_hunk = false;
+    let mut additions = 0;
+    let mut deletions = 0;
+
+    for line in lines.iter().skip(1) {
+        if line.starts_with("@@") {
+            in_hunk = true;
+            continue;
+        }
+        if in_hunk {
+            if line.starts_with('+') {
+                additions += 1;
+            } else if line.starts_with('-') {
+                deletions += 1;
+            }
+            continue;
+        }
+
+        if let Some(path) = line.strip_prefix("--- ") {
+            if path != "/dev/null" {
+                old_path = Some(strip_diff_path(path));
+            }
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if path != "/dev/null" {
+                new_path = Some(strip_diff_path(path));
+            }
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            kind = FileChangeKind::Renamed;
+            old_path = Some(path.to_string());
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            new_path = Some(path.to_string());
+        } else if line.starts_with("new file mode") {
+            kind = FileChangeKind::Added;
+        } else if line.starts_with("deleted file mode") {
+            kind = FileChangeKind::Deleted;
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            binary = true;
+        }
+    }
+
+    // 二进制文件和仅修改权限的文件没有 ---/+++ 行，从 `diff --git a/x b/y` 中取路径
+    let header_path = || {
+        lines[0]
+            .rsplit_once(" b/")
+            .map(|(_, path)| path.trim_matches('"').to_string())
+            .unwrap_or_default()
+    };
+    let path = match kind {
+        FileChangeKind::Deleted => old_path.clone(),
+        _ => new_path,
+    }
+    .unwrap_or_else(header_path);
+
+    FileDiff {
+        path,
+        old_path: old_path.filter(|_| kind == FileChangeKind::Renamed),
+        kind,
+        binary,
+        additions,
+        deletions,
+        patch: (!binary).then(|| {
+            let mut patch = lines.join("\n");
+            patch.push('\n');
+            patch
+        }),
+    }
+}
+
+/// 解析 `git diff --numstat -z` 输出
+///
+/// 普通记录为 `add\tdel\tpath\0`，重命名为 `add\tdel\t\0old\0new\0`，
+/// 二进制文件的行数为 `-`。
+fn parse_numstat(output: &str) -> Vec<DiffStat> {
+    let mut stats = Vec::new();
+    let mut fields = output.split('\0');
+    while let Some(record) = fields.next() {
+        let mut parts = record.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+
+        let (path, old_path) = if path.is_empty() {
+            let old = fields.next().unwrap_or_default().to_string();
+            let new = fields.next().unwrap_or_default().to_string();
+            (new, Some(old))
+        } else {
+            (path.to_string(), None)
+        };
+        let binary = added == "-" && deleted == "-";
+        stats.push(DiffStat {
+            path,
+            old_path,
+            binary,
+            additions: added.parse().unwrap_or(0),
+            deletions: deleted.parse().unwrap_or(0),
+        });
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// 创建含多个文件变更（修改、删除、二进制、新增、重命名）的仓库
+    fn repo_with_changes() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::write(root.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        fs::write(root.join("b.txt"), "gone\n").unwrap();
+        fs::write(root.join("c.bin"), [0u8, 1, 2, 3]).unwrap();
+        fs::write(root.join("old.txt"), "stable\ncontent\nhere\n").unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+
+        fs::write(root.join("a.txt"), "one\n2\nthree\nfour\n").unwrap();
+        fs::remove_file(root.join("b.txt")).unwrap();
+        fs::write(root.join("c.bin"), [0u8, 9, 9, 9, 9]).unwrap();
+        fs::write(root.join("e.txt"), "new\n").unwrap();
+        fs::rename(root.join("old.txt"), root.join("renamed.txt")).unwrap();
+        git(root, &["add", "-A"]);
+        dir
+    }
+
+    #[test]
+    fn test_diff_stream_yields_one_item_per_file() {
+        let dir = repo_with_changes();
+        let options = DiffOptions {
+            staged: true,
+            ..Default::default()
+        };
+
+        let files: Vec<FileDiff> = diff_stream(dir.path(), &options)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let summary: Vec<(&str, FileChangeKind, bool, usize, usize)> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.kind, f.binary, f.additions, f.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a.txt", FileChangeKind::Modified, false, 2, 1),
+                ("b.txt", FileChangeKind::Deleted, false, 0, 1),
+                ("c.bin", FileChangeKind::Modified, true, 0, 0),
+                ("e.txt", FileChangeKind::Added, false, 1, 0),
+                ("renamed.txt", FileChangeKind::Renamed, false, 0, 0),
+            ]
+        );
+
+        let a = &files[0];
+        let patch = a.patch.as_deref().unwrap();
+        assert!(patch.starts_with("diff --git a/a.txt b/a.txt\n"));
+        assert!(patch.contains("-two\n+2\n"));
+        assert!(!patch.contains("e.txt"));
+        assert!(files[2].patch.is_none());
+        assert_eq!(files[4].old_path.as_deref(), Some("old.txt"));
+    }
+
+    #[test]
+    fn test_diff_stat_summary() {
+        let dir = repo_with_changes();
+        let options = DiffOptions {
+            staged: true,
+            ..Default::default()
+        };
+
+        let stats = diff_stat(dir.path(), &options).unwrap();
+        let summary: Vec<(&str, bool, usize, usize)> = stats
+            .iter()
+            .map(|s| (s.path.as_str(), s.binary, s.additions, s.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a.txt", false, 2, 1),
+                ("b.txt", false, 0, 1),
+                ("c.bin", true, 0, 0),
+                ("e.txt", false, 1, 0),
+                ("renamed.txt", false, 0, 0),
+            ]
+        );
+        assert_eq!(stats[4].old_path.as_deref(), Some("old.txt"));
+    }
+
+    #[test]
+    fn test_diff_stream_stops_early_and_reports_errors() {
+        let dir = repo_with_changes();
+        let options = DiffOptions {
+            staged: true,
+            paths: vec!["a.txt".to_string()],
+            ..Default::default()
+        };
+        let mut stream = diff_stream(dir.path(), &options).unwrap();
+        assert_eq!(stream.next().unwrap().unwrap().path, "a.txt");
+        assert!(stream.next().is_none());
+
+        // 输出超过管道缓冲区时提前丢弃迭代器不会阻塞
+        let large: String = (0..200_000).map(|i| format!("line {}\n", i)).collect();
+        fs::write(dir.path().join("large.txt"), large).unwrap();
+        git(dir.path(), &["add", "large.txt"]);
+        let staged = DiffOptions {
+            staged: true,
+            ..Default::default()
+        };
+        let mut stream = diff_stream(dir.path(), &staged).unwrap();
+        assert_eq!(stream.next().unwrap().unwrap().path, "a.txt");
+        drop(stream);
+
+        let bad = DiffOptions {
+            revision: Some("no-such-revision".to_string()),
+            ..Default::default()
+        };
+        let results: Vec<_> = diff_stream(dir.path(), &bad).unwrap().collect();
+        assert!(matches!(results.as_slice(), [Err(_)]));
+        assert!(diff_stat(dir.path(), &bad).is_err());
+    }
+
+    #[test]
+    fn test_revision_starting_with_dash_is_rejected() {
+        let dir = repo_with_changes();
+        let injected = DiffOptions {
+            revision: Some("--output=pwned.txt".to_string()),
+            ..Default::default()
+        };
+
+        assert!(diff_stream(dir.path(), &injected).is_err());
+        assert!(diff_stat(dir.path(), &injected).is_err());
+        assert!(!dir.path().join("pwned.txt").exists());
+    }
+}
@@ -1,12 +1,26 @@
 //! Git 工具模块
 //!
-//! 提供 Git 状态检测、分支信息、安全检查等功能
+//! 提供 Git 状态检测、分支信息、安全检查、合并冲突解析、增量差异、提交信息生成等功能
 
+mod commit_message;
+mod conflict;
 mod core;
+mod diff;
 mod safety;
 
+pub use commit_message::{
+    infer_commit_type, suggest_commit_message, CommitMessageSuggestion, CommitType,
+    COMMIT_MESSAGE_SYSTEM_PROMPT, DEFAULT_MAX_COMMIT_DIFF_CHARS,
+};
+pub use conflict::{
+    has_conflicts, parse_conflicts, resolve_all_conflicts, resolve_conflicts, ConflictBlock,
+    ConflictResolution, DEFAULT_MARKER_SIZE,
+};
 pub use core::{
-    get_current_branch, get_default_branch, get_git_info, get_git_status, is_git_repository,
-    GitInfo, GitStatus, GitUtils, PushStatus,
+    get_conflicted_files, get_current_branch, get_default_branch, get_git_info, get_git_status,
+    get_repo_root, is_git_repository, GitInfo, GitStatus, GitUtils, PushStatus,
+};
+pub use diff::{
+    diff_stat, diff_stream, DiffOptions, DiffStat, DiffStream, FileChangeKind, FileDiff,
 };
 pub use safety::{is_dangerous_command, GitSafety, SafetyCheckResult, SensitiveFilesCheck};
@@ -0,0 +1,632 @@
+//! GitHub Actions 运行状态
+//!
+//! 通过 GitHub REST API 获取分支或 PR 最近的工作流运行，
+//! 包括每个 job 的结论、失败步骤和日志地址
+
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use serde::{Deserialize, Serialize};
+
+/// 默认的 GitHub API 地址
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
+/// GitHub API 单页最大条数
+const MAX_PER_PAGE: usize = 100;
+
+/// 工作流运行 / job / 步骤的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Queued,
+    InProgress,
+    Completed,
+    Waiting,
+    Requested,
+    Pending,
+    #[serde(other)]
+    Unknown,
+}
+
+impl RunStatus {
+    /// 是否尚未结束
+    pub fn is_in_progress(&self) -> bool {
+        !matches!(self, RunStatus::Completed)
+    }
+}
+
+/// 工作流运行 / job / 步骤的结论（仅在完成后存在）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunConclusion {
+    Success,
+    Failure,
+    Neutral,
+    Cancelled,
+    Skipped,
+    TimedOut,
+    ActionRequired,
+    Stale,
+    StartupFailure,
+    #[serde(other)]
+    Unknown,
+}
+
+impl RunConclusion {
+    /// 是否为失败类结论
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            RunConclusion::Failure | RunConclusion::TimedOut | RunConclusion::StartupFailure
+        )
+    }
+}
+
+/// job 中的一个步骤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    /// 步骤名称
+    pub name: String,
+    /// 序号
+    pub number: u64,
+    /// 状态
+    pub status: RunStatus,
+    /// 结论
+    pub conclusion: Option<RunConclusion>,
+}
+
+/// 工作流运行中的 job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowJob {
+    /// job ID
+    pub id: u64,
+    /// 名称
+    pub name: String,
+    /// 状态
+    pub status: RunStatus,
+    /// 结论
+    pub conclusion: Option<RunConclusion>,
+    /// 网页地址
+    pub html_url: Option<String>,
+    /// 日志下载地址（API 会重定向到日志文件）
+    pub logs_url: String,
+    /// 开始时间
+    pub started_at: Option<String>,
+    /// 完成时间
+    pub completed_at: Option<String>,
+    /// 第一个失败的步骤名称
+    pub failed_step: Option<String>,
+    /// 所有步骤
+    pub steps: Vec<WorkflowStep>,
+}
+
+impl WorkflowJob {
+    /// job 是否失败
+    pub fn is_failing(&self) -> bool {
+        self.conclusion.is_some_and(|c| c.is_failure())
+    }
+}
+
+/// 一次工作流运行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    /// 运行 ID
+    pub id: u64,
+    /// 工作流名称
+    pub name: Option<String>,
+    /// 运行编号
+    pub run_number: u64,
+    /// 触发事件
+    pub event: String,
+    /// 状态
+    pub status: RunStatus,
+    /// 结论
+    pub conclusion: Option<RunConclusion>,
+    /// 分支
+    pub head_branch: Option<String>,
+    /// 提交 SHA
+    pub head_sha: String,
+    /// 网页地址
+    pub html_url: String,
+    /// 整个运行的日志下载地址
+    pub logs_url: String,
+    /// 创建时间
+    pub created_at: String,
+    /// 更新时间
+    pub updated_at: String,
+    /// 所有 job
+    pub jobs: Vec<WorkflowJob>,
+}
+
+impl WorkflowRun {
+    /// 运行是否尚未结束
+    pub fn is_in_progress(&self) -> bool {
+        self.status.is_in_progress()
+    }
+
+    /// 失败的 job（运行中的运行也可能已有失败的 job）
+    pub fn failing_jobs(&self) -> impl Iterator<Item = &WorkflowJob> {
+        self.jobs.iter().filter(|job| job.is_failing())
+    }
+}
+
+/// 工作流运行查询条件
+#[derive(Debug, Clone)]
+pub struct WorkflowRunQuery {
+    /// 分支名
+    pub branch: Option<String>,
+    /// PR 编号（按 PR 头提交过滤，优先于 branch）
+    pub pr_number: Option<u64>,
+    /// 最多返回的运行数
+    pub max_runs: usize,
+    /// 每页条数（最大 100）
+    pub per_page: usize,
+    /// 是否获取 job 详情
+    pub include_jobs: bool,
+}
+
+impl Default for WorkflowRunQuery {
+    fn default() -> Self {
+        Self {
+            branch: None,
+            pr_number: None,
+            max_runs: 10,
+            per_page: 30,
+            include_jobs: true,
+        }
+    }
+}
+
+/// GitHub Actions API 客户端
+#[derive(Debug, Clone)]
+pub struct GitHubActionsClient {
+    client: reqwest::Client,
+    api_url: String,
+    owner: String,
+    repo: String,
+}
+
+impl GitHubActionsClient {
+    /// 创建客户端
+    ///
+    /// 令牌从 `GITHUB_TOKEN` 或 `GH_TOKEN` 环境变量读取，
+    /// 未设置时以匿名方式访问（仅公开仓库可用）。
+    pub fn new(owner: impl Into<String>, repo: impl Into<String>) -> Result<Self, String> {
+        let token = std::env::var("GITHUB_TOKEN")
+            .or_else(|_| std::env::var("GH_TOKEN"))
+            .ok();
+        Self::with_api_url(DEFAULT_GITHUB_API_URL, owner, repo, token)
+    }
+
+    /// 使用自定义 API 地址创建客户端（GitHub Enterprise 或测试）
+    pub fn with_api_url(
+        api_url: impl Into<String>,
+        owner: impl Into<String>,
+        repo: impl Into<String>,
+        token: Option<String>,
+    ) -> Result<Self, String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+        headers.insert(USER_AGENT, HeaderValue::from_static("aster"));
+        headers.insert(
+            "X-GitHub-Api-Version",
+            HeaderValue::from_static("2022-11-28"),
+        );
+        if let Some(token) = token.filter(|t| !t.is_empty()) {
+            let value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|e| format!("无效的 GitHub 令牌: {}", e))?;
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+        Ok(Self {
+            client,
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            owner: owner.into(),
+            repo: repo.into(),
+        })
+    }
+
+    fn repo_url(&self, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{}/{}",
+            self.api_url, self.owner, self.repo, path
+        )
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, String> {
+        let response = self
+            .client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| format!("请求 GitHub API 失败: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("GitHub API 返回 {}: {}", status, body.trim()));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| format!("解析 GitHub API 响应失败: {}", e))
+    }
+
+    /// 获取 PR 的头提交 SHA
+    async fn pr_head_sha(&self, pr_number: u64) -> Result<String, String> {
+        #[derive(Deserialize)]
+        struct GhPull {
+            head: GhHead,
+        }
+
+        #[derive(Deserialize)]
+        struct GhHead {
+            sha: String,
+        }
+
+        let pull: GhPull = self
+            .get_json(&self.repo_url(&format!("pulls/{}", pr_number)), &[])
+            .await?;
+        Ok(pull.head.sha)
+    }
+
+    /// 获取最近的工作流运行
+    ///
+    /// 按创建时间倒序分页获取，直到达到 `max_runs` 或没有更多结果。
+    pub async fn list_workflow_runs(
+        &self,
+        query: &WorkflowRunQuery,
+    ) -> Result<Vec<WorkflowRun>, String> {
+        #[derive(Deserialize)]
+        struct GhRuns {
+            total_count: usize,
+            workflow_runs: Vec<GhRun>,
+        }
+
+        let mut filters = Vec::new();
+        if let Some(pr_number) = query.pr_number {
+            filters.push(("head_sha", self.pr_head_sha(pr_number).await?));
+        } else if let Some(branch) = &query.branch {
+            filters.push(("branch", branch.clone()));
+        }
+
+        let per_page = query.per_page.clamp(1, MAX_PER_PAGE);
+        let url = self.repo_url("actions/runs");
+        let mut runs = Vec::new();
+        let mut page = 1;
+        while runs.len() < query.max_runs {
+            let mut params = filters.clone();
+            params.push(("per_page", per_page.to_string()));
+            params.push(("page", page.to_string()));
+
+            let data: GhRuns = self.get_json(&url, &params).await?;
+            let fetched = data.workflow_runs.len();
+            runs.extend(data.workflow_runs);
+            if fetched < per_page || runs.len() >= data.total_count {
+                break;
+            }
+            page += 1;
+        }
+        runs.truncate(query.max_runs);
+
+        let mut result = Vec::with_capacity(runs.len());
+        for run in runs {
+            let jobs = if query.include_jobs {
+                self.list_jobs(run.id).await?
+            } else {
+                Vec::new()
+            };
+            result.push(run.into_run(jobs));
+        }
+        Ok(result)
+    }
+
+    /// 获取某次运行的所有 job
+    pub async fn list_jobs(&self, run_id: u64) -> Result<Vec<WorkflowJob>, String> {
+        #[derive(Deserialize)]
+        struct GhJobs {
+            total_count: usize,
+            jobs: Vec<GhJob>,
+        }
+
+        let url = self.repo_url(&format!("actions/runs/{}/jobs", run_id));
+        let mut jobs = Vec::new();
+        let mut page = 1;
+        loop {
+            let params = [
+                ("per_page", MAX_PER_PAGE.to_string()),
+                ("page", page.to_string()),
+            ];
+            let data: GhJobs = self.get_json(&url, &params).await?;
+            let fetched = data.jobs.len();
+            jobs.extend(data.jobs.into_iter().map(|job| self.convert_job(job)));
+            if fetched < MAX_PER_PAGE || jobs.len() >= data.total_count {
+                break;
+            }
+            page += 1;
+        }
+        Ok(jobs)
+    }
+
+    fn convert_job(&self, job: GhJob) -> WorkflowJob {
+        let failed_step = job
+            .steps
+            .iter()
+            .find(|step| step.conclusion.is_some_and(|c| c.is_failure()))
+            .map(|step| step.name.clone());
+
+        WorkflowJob {
+            logs_url: self.repo_url(&format!("actions/jobs/{}/logs", job.id)),
+            id: job.id,
+            name: job.name,
+            status: job.status,
+            conclusion: job.conclusion,
+            html_url: job.html_url,
+            started_at: job.started_at,
+            completed_at: job.completed_at,
+            failed_step,
+            steps: job.steps,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GhRun {
+    id: u64,
+    name: Option<String>,
+    run_number: u64,
+    event: String,
+    status: RunStatus,
+    conclusion: Option<RunConclusion>,
+    head_branch: Option<String>,
+    head_sha: String,
+    html_url: String,
+    logs_url: String,
+    created_at: String,
+    updated_at: String,
+}
+
+impl GhRun {
+    fn into_run(self, jobs: Vec<WorkflowJob>) -> WorkflowRun {
+        WorkflowRun {
+            id: self.id,
+            name: self.name,
+            run_number: self.run_number,
+            event: self.event,
+            status: self.status,
+            conclusion: self.conclusion,
+            head_branch: self.head_branch,
+            head_sha: self.head_sha,
+            html_url: self.html_url,
+            logs_url: self.logs_url,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            jobs,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GhJob {
+    id: u64,
+    name: String,
+    status: RunStatus,
+    conclusion: Option<RunConclusion>,
+    html_url: Option<String>,
+    started_at: Option<String>,
+    completed_at: Option<String>,
+    #[serde(default)]
+    steps: Vec<WorkflowStep>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn run_json(id: u64, status: &str, conclusion: Option<&str>) -> serde_json::Value {
+        json!({
+            "id": id,
+            "name": "CI",
+            "run_number": id,
+            "event": "pull_request",
+            "status": status,
+            "conclusion": conclusion,
+            "head_branch": "feature",
+            "head_sha": "abc123",
+            "html_url": format!("https://github.com/o/r/actions/runs/{}", id),
+            "logs_url": format!("https://api.github.com/repos/o/r/actions/runs/{}/logs", id),
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:05:00Z"
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_runs_for_pr_with_failing_step() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/pulls/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "head": { "sha": "abc123" }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/actions/runs"))
+            .and(query_param("head_sha", "abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 2,
+                "workflow_runs": [
+                    run_json(2, "completed", Some("failure")),
+                    run_json(3, "in_progress", None)
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/actions/runs/2/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 2,
+                "jobs": [
+                    {
+                        "id": 20,
+                        "name": "build",
+                        "status": "completed",
+                        "conclusion": "success",
+                        "html_url": "https://github.com/o/r/actions/runs/2/job/20",
+                        "started_at": "2026-01-01T00:00:00Z",
+                        "completed_at": "2026-01-01T00:02:00Z",
+                        "steps": []
+                    },
+                    {
+                        "id": 21,
+                        "name": "test",
+                        "status": "completed",
+                        "conclusion": "failure",
+                        "html_url": "https://github.com/o/r/actions/runs/2/job/21",
+                        "started_at": "2026-01-01T00:00:00Z",
+                        "completed_at": "2026-01-01T00:04:00Z",
+                        "steps": [
+                            { "name": "Checkout", "number": 1, "status": "completed", "conclusion": "success" },
+                            { "name": "cargo test", "number": 2, "status": "completed", "conclusion": "failure" },
+                            { "name": "Upload", "number": 3, "status": "completed", "conclusion": "skipped" }
+                        ]
+                    }
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/actions/runs/3/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "jobs": [{
+                    "id": 30,
+                    "name": "test",
+                    "status": "in_progress",
+                    "conclusion": null,
+                    "html_url": null,
+                    "started_at": "2026-01-01T00:00:00Z",
+                    "completed_at": null,
+                    "steps": [
+                        { "name": "cargo test", "number": 1, "status": "in_progress", "conclusion": null }
+                    ]
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client =
+            GitHubActionsClient::with_api_url(server.uri(), "o", "r", Some("token".into()))
+                .unwrap();
+        let runs = client
+            .list_workflow_runs(&WorkflowRunQuery {
+                pr_number: Some(7),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(runs.len(), 2);
+        let failed = &runs[0];
+        assert_eq!(failed.conclusion, Some(RunConclusion::Failure));
+        let failing: Vec<_> = failed.failing_jobs().collect();
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].name, "test");
+        assert_eq!(failing[0].failed_step.as_deref(), Some("cargo test"));
+        assert_eq!(
+            failing[0].logs_url,
+            format!("{}/repos/o/r/actions/jobs/21/logs", server.uri())
+        );
+
+        let running = &runs[1];
+        assert!(running.is_in_progress());
+        assert_eq!(running.conclusion, None);
+        assert_eq!(running.failing_jobs().count(), 0);
+        assert_eq!(running.jobs[0].failed_step, None);
+    }
+
+    #[tokio::test]
+    async fn test_list_runs_paginates_by_branch() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/actions/runs"))
+            .and(query_param("branch", "main"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 3,
+                "workflow_runs": [
+                    run_json(3, "queued", None),
+                    run_json(2, "completed", Some("success"))
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/actions/runs"))
+            .and(query_param("branch", "main"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 3,
+                "workflow_runs": [run_json(1, "completed", Some("timed_out"))]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = GitHubActionsClient::with_api_url(server.uri(), "o", "r", None).unwrap();
+        let query = WorkflowRunQuery {
+            branch: Some("main".to_string()),
+            max_runs: 5,
+            per_page: 2,
+            include_jobs: false,
+            ..Default::default()
+        };
+        let runs = client.list_workflow_runs(&query).await.unwrap();
+        assert_eq!(runs.iter().map(|r| r.id).collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(runs[0].status, RunStatus::Queued);
+        assert!(runs[0].is_in_progress());
+        assert!(runs[0].jobs.is_empty());
+        assert_eq!(runs[2].conclusion, Some(RunConclusion::TimedOut));
+
+        let runs = client
+            .list_workflow_runs(&WorkflowRunQuery {
+                max_runs: 1,
+                ..query
+            })
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_api_error_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/actions/runs"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+            .mount(&server)
+            .await;
+
+        let client = GitHubActionsClient::with_api_url(server.uri(), "o", "r", None).unwrap();
+        let err = client
+            .list_workflow_runs(&WorkflowRunQuery::default())
+            .await
+            .unwrap_err();
+        assert!(err.contains("404"));
+    }
+}
@@ -1,13 +1,20 @@
 //! GitHub 集成模块
 //!
-//! 提供 GitHub Actions 工作流设置、PR 管理等功能
+//! 提供 GitHub Actions 工作流设置、运行状态查询、PR 管理和审查评论等功能
 
+mod actions;
 mod pr;
+mod review;
 mod workflow;
 
+pub use actions::{
+    GitHubActionsClient, RunConclusion, RunStatus, WorkflowJob, WorkflowRun, WorkflowRunQuery,
+    WorkflowStep, DEFAULT_GITHUB_API_URL,
+};
 pub use pr::{
     add_pr_comment, create_pr, get_pr_comments, get_pr_info, CreatePROptions, PRComment, PRInfo,
 };
+pub use review::{diff_position, post_review_comment, submit_review, ReviewComment, ReviewEvent};
 pub use workflow::{
     check_github_cli, setup_github_workflow, GitHubCLIStatus, CLAUDE_CODE_WORKFLOW,
 };
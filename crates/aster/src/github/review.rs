@@ -0,0 +1,288 @@
+//! GitHub PR 审查评论
+//!
+//! 将文件行号换算为 PR 差异中的位置，并提交行级审查评论
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// 行级审查评论
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    /// 文件路径（相对仓库根目录）
+    pub path: String,
+    /// 新文件中的行号
+    pub line: u32,
+    /// 评论内容
+    pub body: String,
+}
+
+/// 审查结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReviewEvent {
+    /// 仅评论
+    #[default]
+    Comment,
+    /// 批准
+    Approve,
+    /// 要求修改
+    RequestChanges,
+}
+
+impl ReviewEvent {
+    fn as_api_str(&self) -> &'static str {
+        match self {
+            ReviewEvent::Comment => "COMMENT",
+            ReviewEvent::Approve => "APPROVE",
+            ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+        }
+    }
+}
+
+/// 计算新文件行号在 patch 中的位置
+///
+/// GitHub 的 position 从第一个 hunk 头之后的行开始计为 1，
+/// 之后的 hunk 头也占一个位置。只有上下文行和新增行可以评论。
+pub fn diff_position(patch: &str, line: u32) -> Result<u32, String> {
+    let mut position = 0u32;
+    let mut new_line: Option<u32> = None;
+
+    for text in patch.lines() {
+        if text.starts_with("@@") {
+            if new_line.is_some() {
+                position += 1;
+            }
+            new_line = Some(parse_hunk_new_start(text)?);
+            continue;
+        }
+        let Some(current) = new_line.as_mut() else {
+            continue;
+        };
+        position += 1;
+        match text.chars().next() {
+            Some('-') | Some('\\') => {}
+            _ => {
+                if *current == line {
+                    return Ok(position);
+                }
+                *current += 1;
+            }
+        }
+    }
+
+    Err(format!(
+        "第 {} 行不在 PR 差异中（只能评论新增行或上下文行）",
+        line
+    ))
+}
+
+/// 解析 hunk 头 `@@ -a,b +c,d @@` 中新文件的起始行
+fn parse_hunk_new_start(header: &str) -> Result<u32, String> {
+    header
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))
+        .and_then(|range| range.split(',').next())
+        .and_then(|start| start.parse().ok())
+        .ok_or_else(|| format!("无法解析 hunk 头: {}", header))
+}
+
+/// 把评论的行号换算为 diff position，生成 API 请求中的 comments 数组
+fn build_review_comments(
+    patches: &HashMap<String, Option<String>>,
+    comments: &[ReviewComment],
+) -> Result<Vec<serde_json::Value>, String> {
+    comments
+        .iter()
+        .map(|comment| {
+            let patch = match patches.get(&comment.path) {
+                Some(Some(patch)) => patch,
+                Some(None) => {
+                    return Err(format!("{} 没有文本差异，无法添加行级评论", comment.path))
+                }
+                None => return Err(format!("{} 不在 PR 的变更文件中", comment.path)),
+            };
+            let position = diff_position(patch, comment.line)
+                .map_err(|e| format!("{}: {}", comment.path, e))?;
+            Ok(serde_json::json!({
+                "path": comment.path,
+                "position": position,
+                "body": comment.body,
+            }))
+        })
+        .collect()
+}
+
+/// 获取 PR 每个变更文件的 patch（二进制文件或过大的差异没有 patch）
+async fn get_pr_patches(pr_number: u32) -> Result<HashMap<String, Option<String>>, String> {
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "--paginate",
+            &format!("repos/{{owner}}/{{repo}}/pulls/{}/files", pr_number),
+            "--jq",
+            ".[] | {filename, patch}",
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("执行 gh 命令失败: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    #[derive(Deserialize)]
+    struct GhFile {
+        filename: String,
+        patch: Option<String>,
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str::<GhFile>(line)
+                .map(|f| (f.filename, f.patch))
+                .map_err(|e| format!("解析 PR 文件列表失败: {}", e))
+        })
+        .collect()
+}
+
+/// 提交包含多条行级评论的审查
+///
+/// 所有评论的位置都先校验，任一行不在差异中时不会提交。
+/// 成功时返回审查的网页地址。
+pub async fn submit_review(
+    pr_number: u32,
+    body: &str,
+    event: ReviewEvent,
+    comments: &[ReviewComment],
+) -> Result<String, String> {
+    let patches = get_pr_patches(pr_number).await?;
+    let payload = serde_json::json!({
+        "body": body,
+        "event": event.as_api_str(),
+        "comments": build_review_comments(&patches, comments)?,
+    });
+
+    let mut child = Command::new("gh")
+        .args([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{{owner}}/{{repo}}/pulls/{}/reviews", pr_number),
+            "--input",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("执行 gh 命令失败: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(payload.to_string().as_bytes())
+            .await
+            .map_err(|e| format!("写入审查内容失败: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("执行 gh 命令失败: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let response: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("解析审查响应失败: {}", e))?;
+    Ok(response
+        .get("html_url")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// 在 PR 差异的指定行上发表评论
+pub async fn post_review_comment(
+    pr_number: u32,
+    path: &str,
+    line: u32,
+    body: &str,
+) -> Result<String, String> {
+    let comment = ReviewComment {
+        path: path.to_string(),
+        line,
+        body: body.to_string(),
+    };
+    submit_review(pr_number, "", ReviewEvent::Comment, &[comment]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "\
+@@ -1,4 +1,5 @@
+ use std::io;
+-use std::fs;
++use std::fs::File;
++use std::path::Path;
+
+ fn main() {
+@@ -20,3 +21,4 @@ fn helper() {
+     let a = 1;
++    let b = 2;
+     a
+ }";
+
+    #[test]
+    fn test_diff_position_maps_new_lines() {
+        // 第一个 hunk：上下文、删除行跳过、新增行
+        assert_eq!(diff_position(PATCH, 1), Ok(1));
+        assert_eq!(diff_position(PATCH, 2), Ok(3));
+        assert_eq!(diff_position(PATCH, 3), Ok(4));
+        assert_eq!(diff_position(PATCH, 5), Ok(6));
+        // 第二个 hunk 头占 position 7
+        assert_eq!(diff_position(PATCH, 21), Ok(8));
+        assert_eq!(diff_position(PATCH, 22), Ok(9));
+        assert_eq!(diff_position(PATCH, 24), Ok(11));
+    }
+
+    #[test]
+    fn test_diff_position_rejects_lines_outside_diff() {
+        let err = diff_position(PATCH, 10).unwrap_err();
+        assert!(err.contains("第 10 行不在 PR 差异中"));
+        assert!(diff_position(PATCH, 25).is_err());
+    }
+
+    #[test]
+    fn test_build_review_comments() {
+        let patches = HashMap::from([
+            ("src/main.rs".to_string(), Some(PATCH.to_string())),
+            ("logo.png".to_string(), None),
+        ]);
+        let comment = |path: &str, line| ReviewComment {
+            path: path.to_string(),
+            line,
+            body: "nit".to_string(),
+        };
+
+        let built = build_review_comments(&patches, &[comment("src/main.rs", 22)]).unwrap();
+        assert_eq!(
+            built[0],
+            serde_json::json!({"path": "src/main.rs", "position": 9, "body": "nit"})
+        );
+
+        let err = build_review_comments(
+            &patches,
+            &[comment("src/main.rs", 1), comment("src/main.rs", 10)],
+        )
+        .unwrap_err();
+        assert!(err.starts_with("src/main.rs: "));
+        assert!(build_review_comments(&patches, &[comment("logo.png", 1)]).is_err());
+        assert!(build_review_comments(&patches, &[comment("missing.rs", 1)]).is_err());
+    }
+}
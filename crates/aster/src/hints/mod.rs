@@ -1,4 +1,8 @@
 mod import_files;
 pub mod load_hints;
+pub mod ranking;
+pub mod triggers;
 
 pub use load_hints::{load_hint_files, AGENTS_MD_FILENAME, ASTER_HINTS_FILENAME};
+pub use ranking::{Hint, HintCategory, HintRanker};
+pub use triggers::{HintTrigger, HintTriggerRule, ToolActivity};
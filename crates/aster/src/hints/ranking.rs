@@ -0,0 +1,211 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::paths::Paths;
+
+pub const DISMISSED_HINTS_FILENAME: &str = "dismissed_hints.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HintCategory {
+    #[default]
+    General,
+    Workflow,
+    ToolUsage,
+    Error,
+    Performance,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hint {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub category: HintCategory,
+    /// Higher is more relevant; hints are returned in descending order.
+    #[serde(default)]
+    pub relevance: f32,
+    /// Hints sharing a dedupe key collapse into the most relevant one.
+    /// Falls back to the hint id when unset.
+    #[serde(default)]
+    pub dedupe_key: Option<String>,
+}
+
+impl Hint {
+    pub fn new(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            text: text.into(),
+            category: HintCategory::default(),
+            relevance: 0.0,
+            dedupe_key: None,
+        }
+    }
+
+    pub fn with_category(mut self, category: HintCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn with_relevance(mut self, relevance: f32) -> Self {
+        self.relevance = relevance;
+        self
+    }
+
+    pub fn with_dedupe_key(mut self, key: impl Into<String>) -> Self {
+        self.dedupe_key = Some(key.into());
+        self
+    }
+
+    pub fn dedupe_key(&self) -> &str {
+        self.dedupe_key.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// Ranks candidate hints, dropping duplicates and hints the user dismissed.
+#[derive(Debug, Default)]
+pub struct HintRanker {
+    dismissed: HashSet<String>,
+    store_path: Option<PathBuf>,
+}
+
+impl HintRanker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads dismissed hint ids from `path`; dismissals are written back there.
+    pub fn with_store(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let dismissed = Self::read_dismissed(&path).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load dismissed hints from {:?}: {}", path, e);
+            HashSet::new()
+        });
+        Self {
+            dismissed,
+            store_path: Some(path),
+        }
+    }
+
+    pub fn load_default() -> Self {
+        Self::with_store(Paths::in_state_dir(DISMISSED_HINTS_FILENAME))
+    }
+
+    fn read_dismissed(path: &Path) -> Result<HashSet<String>> {
+        if !path.exists() {
+            return Ok(HashSet::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn persist(&self) -> Result<()> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut ids: Vec<_> = self.dismissed.iter().collect();
+        ids.sort();
+        std::fs::write(path, serde_json::to_string_pretty(&ids)?)?;
+        Ok(())
+    }
+
+    pub fn dismiss(&mut self, hint_id: &str) -> Result<()> {
+        if self.dismissed.insert(hint_id.to_string()) {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    pub fn is_dismissed(&self, hint_id: &str) -> bool {
+        self.dismissed.contains(hint_id)
+    }
+
+    pub fn clear_dismissed(&mut self) -> Result<()> {
+        self.dismissed.clear();
+        self.persist()
+    }
+
+    /// Returns at most `limit` hints, most relevant first. Dismissed hints are
+    /// removed and each dedupe key keeps only its most relevant hint.
+    pub fn rank(&self, hints: Vec<Hint>, limit: usize) -> Vec<Hint> {
+        let mut best: HashMap<String, usize> = HashMap::new();
+        let mut kept: Vec<Hint> = Vec::new();
+
+        for hint in hints {
+            if self.is_dismissed(&hint.id) {
+                continue;
+            }
+            match best.get(hint.dedupe_key()) {
+                Some(&idx) => {
+                    if hint.relevance > kept[idx].relevance {
+                        kept[idx] = hint;
+                    }
+                }
+                None => {
+                    best.insert(hint.dedupe_key().to_string(), kept.len());
+                    kept.push(hint);
+                }
+            }
+        }
+
+        kept.sort_by(|a, b| b.relevance.total_cmp(&a.relevance));
+        kept.truncate(limit);
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_same_dedupe_key_collapses() {
+        let ranker = HintRanker::new();
+        let hints = vec![
+            Hint::new("read-first-a", "Read the file before editing it")
+                .with_dedupe_key("read-before-edit")
+                .with_relevance(0.4),
+            Hint::new("read-first-b", "Use the read tool before editing")
+                .with_dedupe_key("read-before-edit")
+                .with_relevance(0.9),
+        ];
+
+        let ranked = ranker.rank(hints, 5);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].id, "read-first-b");
+    }
+
+    #[test]
+    fn test_rank_orders_and_limits() {
+        let ranker = HintRanker::new();
+        let hints = vec![
+            Hint::new("a", "a").with_relevance(0.1),
+            Hint::new("b", "b").with_relevance(0.8),
+            Hint::new("c", "c").with_relevance(0.5),
+        ];
+
+        let ids: Vec<_> = ranker.rank(hints, 2).into_iter().map(|h| h.id).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_dismissed_hint_persists() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(DISMISSED_HINTS_FILENAME);
+
+        let mut ranker = HintRanker::with_store(&path);
+        ranker.dismiss("noisy").unwrap();
+        assert!(ranker
+            .rank(vec![Hint::new("noisy", "noisy hint")], 5)
+            .is_empty());
+
+        let reloaded = HintRanker::with_store(&path);
+        assert!(reloaded.is_dismissed("noisy"));
+    }
+}
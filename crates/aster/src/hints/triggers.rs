@@ -0,0 +1,303 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::ranking::{Hint, HintCategory};
+use crate::config::paths::Paths;
+
+pub const HINT_TRIGGERS_FILENAME: &str = "hint_triggers.yaml";
+
+const MAX_HISTORY: usize = 100;
+
+fn default_min_occurrences() -> u32 {
+    1
+}
+
+fn default_window_secs() -> u64 {
+    120
+}
+
+fn default_cooldown_secs() -> u64 {
+    600
+}
+
+/// A data-driven rule describing which recent tool activity should surface a hint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HintTriggerRule {
+    pub id: String,
+    pub hint: Hint,
+    /// Regex matched against the tool name.
+    pub tool_pattern: String,
+    /// Regex matched against the error text; when unset any failure matches.
+    #[serde(default)]
+    pub error_pattern: Option<String>,
+    #[serde(default = "default_min_occurrences")]
+    pub min_occurrences: u32,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// Only count failures that hit the same target (e.g. the same file).
+    #[serde(default)]
+    pub same_target: bool,
+}
+
+/// A single tool result as seen by the trigger.
+#[derive(Debug, Clone)]
+pub struct ToolActivity {
+    pub tool_name: String,
+    pub target: Option<String>,
+    pub error: Option<String>,
+    pub at: Instant,
+}
+
+impl ToolActivity {
+    pub fn success(tool_name: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            target: None,
+            error: None,
+            at: Instant::now(),
+        }
+    }
+
+    pub fn failure(tool_name: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Self::success(tool_name)
+        }
+    }
+
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn at(mut self, at: Instant) -> Self {
+        self.at = at;
+        self
+    }
+}
+
+struct CompiledRule {
+    rule: HintTriggerRule,
+    tool_re: Regex,
+    error_re: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn matches(&self, activity: &ToolActivity) -> bool {
+        let Some(error) = &activity.error else {
+            return false;
+        };
+        self.tool_re.is_match(&activity.tool_name)
+            && self.error_re.as_ref().is_none_or(|re| re.is_match(error))
+    }
+}
+
+/// Watches tool results and emits a hint once a rule's pattern repeats,
+/// then stays quiet for that rule until its cooldown elapses.
+pub struct HintTrigger {
+    rules: Vec<CompiledRule>,
+    history: VecDeque<ToolActivity>,
+    last_fired: HashMap<String, Instant>,
+}
+
+impl HintTrigger {
+    pub fn new(rules: Vec<HintTriggerRule>) -> Result<Self> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let tool_re = Regex::new(&rule.tool_pattern)
+                    .with_context(|| format!("Invalid tool_pattern in rule {}", rule.id))?;
+                let error_re = rule
+                    .error_pattern
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .with_context(|| format!("Invalid error_pattern in rule {}", rule.id))?;
+                Ok(CompiledRule {
+                    rule,
+                    tool_re,
+                    error_re,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            rules,
+            history: VecDeque::new(),
+            last_fired: HashMap::new(),
+        })
+    }
+
+    /// Loads rules from a YAML (or JSON) file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read hint triggers from {:?}", path))?;
+        let rules: Vec<HintTriggerRule> = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse hint triggers from {:?}", path))?;
+        Self::new(rules)
+    }
+
+    pub fn with_default_rules() -> Self {
+        Self::new(default_rules()).expect("default hint trigger rules must compile")
+    }
+
+    /// Uses the rules in the config dir's `hint_triggers.yaml` when present,
+    /// falling back to the built-in rules.
+    pub fn load_default() -> Self {
+        let path = Paths::in_config_dir(HINT_TRIGGERS_FILENAME);
+        if !path.exists() {
+            return Self::with_default_rules();
+        }
+        Self::from_file(&path).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load hint triggers from {:?}: {}", path, e);
+            Self::with_default_rules()
+        })
+    }
+
+    /// Records a tool result and returns any hints that should be shown now.
+    pub fn observe(&mut self, activity: ToolActivity) -> Vec<Hint> {
+        let now = activity.at;
+        self.history.push_back(activity);
+        while self.history.len() > MAX_HISTORY {
+            self.history.pop_front();
+        }
+
+        let latest = self.history.back().expect("just pushed");
+        let mut fired = Vec::new();
+
+        for compiled in &self.rules {
+            let rule = &compiled.rule;
+            if !compiled.matches(latest) {
+                continue;
+            }
+            if let Some(last) = self.last_fired.get(&rule.id) {
+                if now.duration_since(*last) < Duration::from_secs(rule.cooldown_secs) {
+                    continue;
+                }
+            }
+
+            let window = Duration::from_secs(rule.window_secs);
+            let occurrences = self
+                .history
+                .iter()
+                .filter(|a| now.duration_since(a.at) <= window)
+                .filter(|a| !rule.same_target || a.target == latest.target)
+                .filter(|a| compiled.matches(a))
+                .count();
+
+            if occurrences >= rule.min_occurrences as usize {
+                self.last_fired.insert(rule.id.clone(), now);
+                fired.push(rule.hint.clone());
+            }
+        }
+
+        fired
+    }
+
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.last_fired.clear();
+    }
+}
+
+pub fn default_rules() -> Vec<HintTriggerRule> {
+    vec![HintTriggerRule {
+        id: "read-before-edit".to_string(),
+        hint: Hint::new(
+            "read-before-edit",
+            "Edits to this file keep failing. Read the file first so the edit matches its current contents.",
+        )
+        .with_category(HintCategory::ToolUsage)
+        .with_relevance(0.9),
+        tool_pattern: r"^(edit|write)$".to_string(),
+        error_pattern: Some(r"(has not been read|String not found)".to_string()),
+        min_occurrences: 2,
+        window_secs: default_window_secs(),
+        cooldown_secs: default_cooldown_secs(),
+        same_target: true,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_edit_failures_fire_once() {
+        let mut trigger = HintTrigger::with_default_rules();
+        let start = Instant::now();
+
+        let mut fired = Vec::new();
+        for i in 0..5 {
+            let activity = ToolActivity::failure("edit", "String not found in file: 'fn main'")
+                .with_target("src/main.rs")
+                .at(start + Duration::from_secs(i));
+            fired.extend(trigger.observe(activity));
+        }
+
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].id, "read-before-edit");
+    }
+
+    #[test]
+    fn test_failures_on_different_targets_do_not_fire() {
+        let mut trigger = HintTrigger::with_default_rules();
+        let a = trigger
+            .observe(ToolActivity::failure("edit", "String not found in file").with_target("a.rs"));
+        let b = trigger
+            .observe(ToolActivity::failure("edit", "String not found in file").with_target("b.rs"));
+        assert!(a.is_empty());
+        assert!(b.is_empty());
+    }
+
+    #[test]
+    fn test_unrelated_failures_do_not_fire() {
+        let mut trigger = HintTrigger::with_default_rules();
+        let mut fired = Vec::new();
+        for (tool, error) in [
+            ("edit", "Directory not found: src"),
+            ("edit", "Directory not found: src"),
+            ("edit_notes", "String not found in file"),
+            ("edit_notes", "String not found in file"),
+            ("bash", "cat: file has not been read"),
+            ("bash", "cat: file has not been read"),
+        ] {
+            fired.extend(trigger.observe(ToolActivity::failure(tool, error).with_target("src")));
+        }
+        assert!(fired.is_empty());
+    }
+
+    #[test]
+    fn test_rules_load_from_yaml() {
+        let yaml = r#"
+- id: slow-tests
+  hint:
+    id: slow-tests
+    text: Run a single test instead of the whole suite.
+  tool_pattern: "^shell$"
+  error_pattern: "timed out"
+  cooldown_secs: 0
+"#;
+        let rules: Vec<HintTriggerRule> = serde_yaml::from_str(yaml).unwrap();
+        let mut trigger = HintTrigger::new(rules).unwrap();
+
+        assert!(trigger.observe(ToolActivity::success("shell")).is_empty());
+        let fired = trigger.observe(ToolActivity::failure("shell", "command timed out"));
+        assert_eq!(fired.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let rule = HintTriggerRule {
+            tool_pattern: "(".to_string(),
+            ..default_rules().remove(0)
+        };
+        assert!(HintTrigger::new(vec![rule]).is_err());
+    }
+}
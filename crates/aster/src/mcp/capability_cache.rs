@@ -0,0 +1,314 @@
+//! MCP Capability Cache
+//!
+//! Caches the result of the initialize handshake (server version, protocol
+//! version and capabilities) together with the discovered tool list, keyed
+//! by server name and a fingerprint of the server configuration.
+//!
+//! When a server reconnects with an unchanged configuration and advertises
+//! the same version, the cached tool list is reused instead of re-querying
+//! `tools/list`. A `list_changed` notification from the server, a changed
+//! version or an explicit refresh invalidates the entry.
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::mcp::tool_manager::McpTool;
+use crate::mcp::transport::McpNotification;
+use crate::mcp::types::{McpConnection, McpServerInfo, ServerCapabilities};
+
+/// Negotiated server information from the initialize handshake
+#[derive(Debug, Clone)]
+pub struct NegotiatedServer {
+    /// Server name
+    pub server_name: String,
+    /// Fingerprint of the server configuration
+    pub config_fingerprint: String,
+    /// Server version advertised in `serverInfo`
+    pub server_version: Option<String>,
+    /// Negotiated protocol version
+    pub protocol_version: Option<String>,
+    /// Server capabilities
+    pub capabilities: Option<ServerCapabilities>,
+    /// Cached tool list, `None` until discovered or after invalidation
+    pub tools: Option<Vec<McpTool>>,
+    /// Time the cached tool list was fetched from the server
+    pub tools_fetched_at: Option<DateTime<Utc>>,
+    /// Time of the handshake that produced this entry
+    pub negotiated_at: DateTime<Utc>,
+}
+
+/// Cache of negotiated server capabilities and tool lists
+#[derive(Debug, Default)]
+pub struct CapabilityCache {
+    entries: RwLock<HashMap<String, NegotiatedServer>>,
+}
+
+impl CapabilityCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute a stable fingerprint of a server configuration
+    pub fn fingerprint(server: &McpServerInfo) -> String {
+        // HashMap fields (env, headers) serialize in iteration order, and
+        // serde_json keeps insertion order with `preserve_order`, so sort keys
+        let value = canonicalize(serde_json::to_value(server).unwrap_or_default());
+        let digest = Sha256::digest(value.to_string().as_bytes());
+        hex::encode(&digest[..8])
+    }
+
+    /// Record the result of a handshake
+    ///
+    /// The cached tool list is kept only when the configuration fingerprint
+    /// and the advertised server version are unchanged. Returns `true` when
+    /// the cached tool list was retained.
+    pub fn record_handshake(&self, server: &McpServerInfo, connection: &McpConnection) -> bool {
+        let fingerprint = Self::fingerprint(server);
+        let Ok(mut entries) = self.entries.write() else {
+            return false;
+        };
+
+        let retained_tools = entries.get(&server.name).and_then(|previous| {
+            let unchanged = previous.config_fingerprint == fingerprint
+                && previous.server_version.is_some()
+                && previous.server_version == connection.server_version;
+            if unchanged {
+                previous.tools.clone().zip(previous.tools_fetched_at)
+            } else {
+                None
+            }
+        });
+        let retained = retained_tools.is_some();
+        let (tools, tools_fetched_at) = retained_tools.unzip();
+
+        entries.insert(
+            server.name.clone(),
+            NegotiatedServer {
+                server_name: server.name.clone(),
+                config_fingerprint: fingerprint,
+                server_version: connection.server_version.clone(),
+                protocol_version: connection.protocol_version.clone(),
+                capabilities: connection.capabilities.clone(),
+                tools,
+                tools_fetched_at,
+                negotiated_at: Utc::now(),
+            },
+        );
+
+        retained
+    }
+
+    /// Get the negotiated information for a server
+    pub fn get(&self, server_name: &str) -> Option<NegotiatedServer> {
+        self.entries.read().ok()?.get(server_name).cloned()
+    }
+
+    /// Get the cached tool list for a server
+    pub fn cached_tools(&self, server_name: &str) -> Option<Vec<McpTool>> {
+        self.entries.read().ok()?.get(server_name)?.tools.clone()
+    }
+
+    /// Get the cached tool list for a server if it was fetched within `ttl`
+    pub fn fresh_tools(&self, server_name: &str, ttl: Duration) -> Option<Vec<McpTool>> {
+        let entries = self.entries.read().ok()?;
+        let entry = entries.get(server_name)?;
+        let age = Utc::now().signed_duration_since(entry.tools_fetched_at?);
+        if age.to_std().unwrap_or_default() >= ttl {
+            return None;
+        }
+        entry.tools.clone()
+    }
+
+    /// Store the discovered tool list for a server
+    ///
+    /// Ignored if the server has not completed a handshake.
+    pub fn store_tools(&self, server_name: &str, tools: Vec<McpTool>) {
+        if let Ok(mut entries) = self.entries.write() {
+            if let Some(entry) = entries.get_mut(server_name) {
+                entry.tools = Some(tools);
+                entry.tools_fetched_at = Some(Utc::now());
+            }
+        }
+    }
+
+    /// Drop the cached tool list for a server, keeping the handshake info
+    pub fn invalidate(&self, server_name: &str) {
+        if let Ok(mut entries) = self.entries.write() {
+            if let Some(entry) = entries.get_mut(server_name) {
+                entry.tools = None;
+                entry.tools_fetched_at = None;
+            }
+        }
+    }
+
+    /// Remove the entry for a server entirely
+    pub fn remove(&self, server_name: &str) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.remove(server_name);
+        }
+    }
+
+    /// Clear all entries
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.clear();
+        }
+    }
+
+    /// Handle a server notification, invalidating on `list_changed`
+    ///
+    /// Returns `true` if the notification invalidated the cache.
+    pub fn handle_notification(&self, server_name: &str, notification: &McpNotification) -> bool {
+        if !notification.method.ends_with("/list_changed") {
+            return false;
+        }
+        tracing::debug!(
+            "Invalidating capability cache for {} after {}",
+            server_name,
+            notification.method
+        );
+        self.invalidate(server_name);
+        true
+    }
+}
+
+/// Recursively sort object keys so equal values serialize identically
+fn canonicalize(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonicalize(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(canonicalize).collect())
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::types::TransportType;
+
+    fn server_info() -> McpServerInfo {
+        McpServerInfo {
+            name: "fs".to_string(),
+            transport_type: TransportType::Stdio,
+            command: Some("mcp-fs".to_string()),
+            args: None,
+            env: None,
+            cwd: None,
+            url: None,
+            headers: None,
+            options: Default::default(),
+        }
+    }
+
+    fn connection(version: &str) -> McpConnection {
+        let mut connection =
+            McpConnection::new("conn-1".to_string(), "fs".to_string(), TransportType::Stdio);
+        connection.server_version = Some(version.to_string());
+        connection
+    }
+
+    fn tools() -> Vec<McpTool> {
+        vec![McpTool::new("read_file", "fs", serde_json::json!({}))]
+    }
+
+    #[test]
+    fn test_unchanged_server_keeps_tools() {
+        let cache = CapabilityCache::new();
+        cache.record_handshake(&server_info(), &connection("1.0.0"));
+        cache.store_tools("fs", tools());
+
+        assert!(cache.record_handshake(&server_info(), &connection("1.0.0")));
+        assert_eq!(cache.cached_tools("fs").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_version_change_drops_tools() {
+        let cache = CapabilityCache::new();
+        cache.record_handshake(&server_info(), &connection("1.0.0"));
+        cache.store_tools("fs", tools());
+
+        assert!(!cache.record_handshake(&server_info(), &connection("1.1.0")));
+        assert!(cache.cached_tools("fs").is_none());
+    }
+
+    #[test]
+    fn test_config_change_drops_tools() {
+        let cache = CapabilityCache::new();
+        cache.record_handshake(&server_info(), &connection("1.0.0"));
+        cache.store_tools("fs", tools());
+
+        let mut changed = server_info();
+        changed.args = Some(vec!["--root".to_string(), "/tmp".to_string()]);
+        assert!(!cache.record_handshake(&changed, &connection("1.0.0")));
+        assert!(cache.cached_tools("fs").is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_key_order() {
+        let mut first = server_info();
+        first.env = Some(HashMap::from([
+            ("A".to_string(), "1".to_string()),
+            ("B".to_string(), "2".to_string()),
+        ]));
+        let mut second = server_info();
+        second.env = Some(HashMap::from([
+            ("B".to_string(), "2".to_string()),
+            ("A".to_string(), "1".to_string()),
+        ]));
+        assert_eq!(
+            canonicalize(serde_json::json!({"b": 1, "a": {"d": 2, "c": 3}})).to_string(),
+            r#"{"a":{"c":3,"d":2},"b":1}"#
+        );
+        assert_eq!(
+            CapabilityCache::fingerprint(&first),
+            CapabilityCache::fingerprint(&second)
+        );
+    }
+
+    #[test]
+    fn test_fresh_tools_respects_ttl() {
+        let cache = CapabilityCache::new();
+        cache.record_handshake(&server_info(), &connection("1.0.0"));
+        cache.store_tools("fs", tools());
+        assert!(cache.fresh_tools("fs", Duration::from_secs(60)).is_some());
+
+        // Retained across a reconnect, but the fetch time is kept
+        cache.record_handshake(&server_info(), &connection("1.0.0"));
+        assert!(cache.fresh_tools("fs", Duration::from_secs(60)).is_some());
+        assert!(cache.fresh_tools("fs", Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_list_changed_invalidates_cache() {
+        let cache = CapabilityCache::new();
+        cache.record_handshake(&server_info(), &connection("1.0.0"));
+        cache.store_tools("fs", tools());
+
+        let progress = McpNotification::new("notifications/progress");
+        assert!(!cache.handle_notification("fs", &progress));
+        assert!(cache.cached_tools("fs").is_some());
+
+        let changed = McpNotification::new("notifications/tools/list_changed");
+        assert!(cache.handle_notification("fs", &changed));
+        assert!(cache.cached_tools("fs").is_none());
+        assert_eq!(
+            cache.get("fs").unwrap().server_version.as_deref(),
+            Some("1.0.0")
+        );
+    }
+}
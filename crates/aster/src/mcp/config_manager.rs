@@ -566,6 +566,16 @@ impl ConfigManager for McpConfigManager {
             result.add_warning("Timeout is set to 0, which may cause issues");
         }
 
+        // Check working directory
+        if let Some(ref cwd) = config.cwd {
+            if !cwd.is_dir() {
+                result.add_warning(format!(
+                    "Working directory does not exist: {}",
+                    cwd.display()
+                ));
+            }
+        }
+
         // Check for empty environment variables
         if let Some(ref env) = config.env {
             for (key, value) in env {
@@ -764,6 +774,7 @@ fn merge_server_config(global: &McpServerConfig, project: &McpServerConfig) -> M
         command: project.command.clone().or_else(|| global.command.clone()),
         args: project.args.clone().or_else(|| global.args.clone()),
         env: merge_optional_maps(&global.env, &project.env),
+        cwd: project.cwd.clone().or_else(|| global.cwd.clone()),
         url: project.url.clone().or_else(|| global.url.clone()),
         headers: merge_optional_maps(&global.headers, &project.headers),
         enabled: project.enabled,
@@ -775,6 +786,7 @@ fn merge_server_config(global: &McpServerConfig, project: &McpServerConfig) -> M
             project.auto_approve.clone()
         },
         log_level: project.log_level,
+        pool: project.pool.clone().or_else(|| global.pool.clone()),
     }
 }
 
@@ -809,6 +821,7 @@ mod tests {
                 ("API_KEY".to_string(), "secret123".to_string()),
                 ("DEBUG".to_string(), "true".to_string()),
             ])),
+            cwd: None,
             url: None,
             headers: None,
             enabled: true,
@@ -816,6 +829,7 @@ mod tests {
             retries: 3,
             auto_approve: vec![],
             log_level: Default::default(),
+            pool: None,
         }
     }
 
@@ -865,6 +879,25 @@ mod tests {
         assert!(result.errors.iter().any(|e| e.contains("command")));
     }
 
+    #[test]
+    fn test_validate_missing_cwd_warns() {
+        let manager = McpConfigManager::with_options(ConfigManagerOptions {
+            validate_commands: false,
+            ..Default::default()
+        });
+        let config = McpServerConfig {
+            cwd: Some(std::path::PathBuf::from("/nonexistent/aster-mcp-cwd")),
+            ..create_test_config()
+        };
+
+        let result = manager.validate(&config);
+        assert!(result.valid);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("Working directory does not exist")));
+    }
+
     #[test]
     fn test_validate_http_missing_url() {
         let manager = McpConfigManager::new();
@@ -72,6 +72,7 @@ fn server_config_strategy() -> impl Strategy<Value = McpServerConfig> {
                 command,
                 args: Some(vec!["arg1".to_string()]),
                 env,
+                cwd: None,
                 url,
                 headers: None,
                 enabled,
@@ -79,6 +80,7 @@ fn server_config_strategy() -> impl Strategy<Value = McpServerConfig> {
                 retries: 3,
                 auto_approve: vec![],
                 log_level: Default::default(),
+                pool: None,
             }
         })
 }
@@ -11,6 +11,7 @@
 //! - Heartbeat monitoring for connection health
 //! - Request/response matching by ID
 //! - Connection pooling and lifecycle management
+//! - Negotiated capability caching across reconnects
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -18,12 +19,15 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use uuid::Uuid;
 
-use crate::mcp::error::{McpError, McpResult};
+use crate::mcp::capability_cache::CapabilityCache;
+use crate::mcp::error::{McpError, McpErrorCode, McpResult};
+use crate::mcp::roots::{list_roots, SharedRoots, ROOTS_LIST};
 use crate::mcp::transport::{
-    BoxedTransport, McpRequest, McpResponse, TransportConfig, TransportFactory, TransportState,
+    BoxedTransport, McpErrorData, McpMessage, McpNotification, McpRequest, McpResponse,
+    TransportConfig, TransportEvent, TransportFactory, TransportState,
 };
 use crate::mcp::types::{
     ConnectionOptions, ConnectionStatus, McpConnection, McpServerInfo, TransportType,
@@ -62,6 +66,8 @@ struct ConnectionState {
     last_heartbeat: Option<chrono::DateTime<Utc>>,
     /// Heartbeat task handle
     heartbeat_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Fan-out of server notifications received on this connection
+    notification_tx: broadcast::Sender<McpNotification>,
 }
 
 /// Pending request info for tracking and cancellation
@@ -112,6 +118,25 @@ pub trait ConnectionManager: Send + Sync {
     /// Cancel a pending request by sending a cancellation notification
     async fn cancel_request(&self, connection_id: &str, request_id: &str) -> McpResult<()>;
 
+    /// Send a notification to the server on a connection
+    async fn send_notification(
+        &self,
+        connection_id: &str,
+        notification: McpNotification,
+    ) -> McpResult<()>;
+
+    /// Answer `roots/list` requests from servers with these roots
+    fn serve_roots(&self, roots: SharedRoots);
+
+    /// Subscribe to notifications sent by the server on a connection
+    ///
+    /// Used to observe server-initiated messages such as progress updates
+    /// while a request is in flight.
+    async fn subscribe_notifications(
+        &self,
+        connection_id: &str,
+    ) -> McpResult<mpsc::Receiver<McpNotification>>;
+
     /// Get a connection by ID
     fn get_connection(&self, id: &str) -> Option<McpConnection>;
 
@@ -141,6 +166,10 @@ pub struct McpConnectionManager {
     enable_heartbeat: bool,
     /// Enable auto-reconnect
     enable_auto_reconnect: bool,
+    /// Negotiated capabilities and tool lists by server
+    capability_cache: Arc<CapabilityCache>,
+    /// Roots returned to servers that send `roots/list`
+    roots: Arc<std::sync::RwLock<Option<SharedRoots>>>,
 }
 
 impl McpConnectionManager {
@@ -159,9 +188,92 @@ impl McpConnectionManager {
             request_counter: AtomicU64::new(1),
             enable_heartbeat: true,
             enable_auto_reconnect: true,
+            capability_cache: Arc::new(CapabilityCache::new()),
+            roots: Arc::new(std::sync::RwLock::new(None)),
         }
     }
 
+    /// Get the capability cache shared with this connection manager
+    pub fn capability_cache(&self) -> Arc<CapabilityCache> {
+        self.capability_cache.clone()
+    }
+
+    /// Forward server notifications from a transport to subscribers,
+    /// invalidating the capability cache on `list_changed`
+    ///
+    /// Called before the handshake so no server message is missed. Requests
+    /// initiated by the server are queued on the returned receiver until
+    /// [`Self::answer_server_requests`] starts once the connection is stored.
+    fn watch_notifications(
+        &self,
+        server_name: &str,
+        transport: &BoxedTransport,
+        notification_tx: broadcast::Sender<McpNotification>,
+    ) -> mpsc::UnboundedReceiver<McpRequest> {
+        let mut events = transport.subscribe();
+        let cache = self.capability_cache.clone();
+        let server_name = server_name.to_string();
+        let (request_tx, request_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                if let TransportEvent::MessageReceived(message) = event {
+                    match *message {
+                        McpMessage::Notification(notification) => {
+                            cache.handle_notification(&server_name, &notification);
+                            let _ = notification_tx.send(notification);
+                        }
+                        McpMessage::Request(request) => {
+                            let _ = request_tx.send(request);
+                        }
+                        McpMessage::Response(_) => {}
+                    }
+                }
+            }
+        });
+        request_rx
+    }
+
+    /// Reply to requests the server sends on a connection
+    fn answer_server_requests(
+        &self,
+        connection_id: &str,
+        mut requests: mpsc::UnboundedReceiver<McpRequest>,
+    ) {
+        let connections = self.connections.clone();
+        let roots = self.roots.clone();
+        let connection_id = connection_id.to_string();
+        tokio::spawn(async move {
+            while let Some(request) = requests.recv().await {
+                let response = match request.method.as_str() {
+                    ROOTS_LIST => {
+                        let shared = roots.read().unwrap().clone();
+                        let roots = match shared {
+                            Some(shared) => list_roots(&shared).await,
+                            None => Vec::new(),
+                        };
+                        McpResponse::success(request.id, serde_json::json!({ "roots": roots }))
+                    }
+                    "ping" => McpResponse::success(request.id, serde_json::json!({})),
+                    method => McpResponse::error(
+                        request.id,
+                        McpErrorData::new(
+                            McpErrorCode::MethodNotFound.code(),
+                            format!("Method not found: {}", method),
+                        ),
+                    ),
+                };
+
+                let mut conns = connections.write().await;
+                let Some(state) = conns.get_mut(&connection_id) else {
+                    break;
+                };
+                if let Err(e) = state.transport.send(McpMessage::Response(response)).await {
+                    tracing::warn!("Failed to answer server request: {}", e);
+                }
+            }
+        });
+    }
+
     /// Enable or disable heartbeat monitoring
     pub fn set_heartbeat_enabled(&mut self, enabled: bool) {
         self.enable_heartbeat = enabled;
@@ -202,7 +314,7 @@ impl McpConnectionManager {
                     command,
                     args: server.args.clone().unwrap_or_default(),
                     env: server.env.clone().unwrap_or_default(),
-                    cwd: None,
+                    cwd: server.cwd.as_ref().map(|p| p.display().to_string()),
                 })
             }
             TransportType::Http => {
@@ -213,6 +325,7 @@ impl McpConnectionManager {
                 Ok(TransportConfig::Http {
                     url,
                     headers: server.headers.clone().unwrap_or_default(),
+                    pool: server.options.pool.clone(),
                 })
             }
             TransportType::Sse => {
@@ -223,6 +336,7 @@ impl McpConnectionManager {
                 Ok(TransportConfig::Sse {
                     url,
                     headers: server.headers.clone().unwrap_or_default(),
+                    pool: server.options.pool.clone(),
                 })
             }
             TransportType::WebSocket => {
@@ -268,6 +382,14 @@ impl McpConnectionManager {
                 connection.protocol_version = Some(protocol_version.to_string());
             }
 
+            if let Some(version) = result
+                .get("serverInfo")
+                .and_then(|info| info.get("version"))
+                .and_then(|v| v.as_str())
+            {
+                connection.server_version = Some(version.to_string());
+            }
+
             // Parse capabilities if available
             if let Some(capabilities) = result.get("capabilities") {
                 if let Ok(caps) = serde_json::from_value(capabilities.clone()) {
@@ -468,8 +590,20 @@ impl McpConnectionManager {
             server_info.transport_type,
         );
 
+        let notification_tx = self
+            .connections
+            .read()
+            .await
+            .get(connection_id)
+            .map(|state| state.notification_tx.clone())
+            .unwrap_or_else(|| broadcast::channel(100).0);
+        let server_requests =
+            self.watch_notifications(&server_info.name, &transport, notification_tx);
+
         // Perform handshake
         Self::perform_handshake(&mut transport, &mut connection).await?;
+        self.capability_cache
+            .record_handshake(server_info, &connection);
 
         // Update connection status
         connection.status = ConnectionStatus::Connected;
@@ -484,6 +618,7 @@ impl McpConnectionManager {
                 state.last_heartbeat = Some(Utc::now());
             }
         }
+        self.answer_server_requests(connection_id, server_requests);
 
         // Emit established event
         self.emit_event(ConnectionEvent::Established(connection.clone()))
@@ -536,8 +671,13 @@ impl ConnectionManager for McpConnectionManager {
 
         transport.connect().await?;
 
+        let (notification_tx, _) = broadcast::channel(100);
+        let server_requests =
+            self.watch_notifications(&server.name, &transport, notification_tx.clone());
+
         // Perform MCP handshake
         Self::perform_handshake(&mut transport, &mut connection).await?;
+        self.capability_cache.record_handshake(&server, &connection);
 
         // Update connection status
         connection.status = ConnectionStatus::Connected;
@@ -555,9 +695,11 @@ impl ConnectionManager for McpConnectionManager {
                     reconnect_attempts: 0,
                     last_heartbeat: Some(Utc::now()),
                     heartbeat_handle: None,
+                    notification_tx,
                 },
             );
         }
+        self.answer_server_requests(&connection_id, server_requests);
 
         // Update server mapping
         {
@@ -698,6 +840,24 @@ impl ConnectionManager for McpConnectionManager {
     }
 
     async fn cancel_request(&self, connection_id: &str, request_id: &str) -> McpResult<()> {
+        // Send cancellation notification per MCP protocol
+        let cancel_notification = McpNotification::with_params(
+            "notifications/cancelled",
+            serde_json::json!({
+                "requestId": request_id,
+                "reason": "Cancelled by client"
+            }),
+        );
+
+        self.send_notification(connection_id, cancel_notification)
+            .await
+    }
+
+    async fn send_notification(
+        &self,
+        connection_id: &str,
+        notification: McpNotification,
+    ) -> McpResult<()> {
         let mut conns = self.connections.write().await;
 
         if let Some(state) = conns.get_mut(connection_id) {
@@ -705,20 +865,9 @@ impl ConnectionManager for McpConnectionManager {
                 return Err(McpError::connection("Connection is not active"));
             }
 
-            // Send cancellation notification per MCP protocol
-            let cancel_notification = crate::mcp::transport::McpNotification::with_params(
-                "notifications/cancelled",
-                serde_json::json!({
-                    "requestId": request_id,
-                    "reason": "Cancelled by client"
-                }),
-            );
-
             state
                 .transport
-                .send(crate::mcp::transport::McpMessage::Notification(
-                    cancel_notification,
-                ))
+                .send(McpMessage::Notification(notification))
                 .await?;
 
             Ok(())
@@ -730,6 +879,42 @@ impl ConnectionManager for McpConnectionManager {
         }
     }
 
+    fn serve_roots(&self, roots: SharedRoots) {
+        *self.roots.write().unwrap() = Some(roots);
+    }
+
+    async fn subscribe_notifications(
+        &self,
+        connection_id: &str,
+    ) -> McpResult<mpsc::Receiver<McpNotification>> {
+        let mut notifications = {
+            let conns = self.connections.read().await;
+            let state = conns.get(connection_id).ok_or_else(|| {
+                McpError::connection(format!("Connection not found: {}", connection_id))
+            })?;
+            state.notification_tx.subscribe()
+        };
+
+        let (tx, rx) = mpsc::channel(100);
+        tokio::spawn(async move {
+            loop {
+                match notifications.recv().await {
+                    Ok(notification) => {
+                        if tx.send(notification).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Notification subscriber lagged by {}", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
     fn get_connection(&self, id: &str) -> Option<McpConnection> {
         // Use try_read to avoid blocking
         self.connections
@@ -826,6 +1011,7 @@ mod tests {
             command: Some("node".to_string()),
             args: Some(vec!["server.js".to_string()]),
             env: None,
+            cwd: None,
             url: None,
             headers: None,
             options: ConnectionOptions::default(),
@@ -836,6 +1022,108 @@ mod tests {
         assert_eq!(config.unwrap().transport_type(), TransportType::Stdio);
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_server_receives_env_and_cwd() {
+        use crate::mcp::transport::{StdioTransport, Transport};
+        use crate::mcp::types::McpServerConfig;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+
+        // Reads one request and replies with the env var and working directory
+        let script = r#"read line; printf '{"jsonrpc":"2.0","id":"echo-1","result":{"value":"%s","cwd":"%s"}}\n' "$ASTER_TEST_VAR" "$(pwd -P)""#;
+        let config = McpServerConfig {
+            command: Some("sh".to_string()),
+            args: Some(vec!["-c".to_string(), script.to_string()]),
+            env: Some(HashMap::from([(
+                "ASTER_TEST_VAR".to_string(),
+                "passed-through".to_string(),
+            )])),
+            cwd: Some(cwd.clone()),
+            ..Default::default()
+        };
+
+        let server = McpServerInfo::from_config("echo", &config);
+        let transport_config = McpConnectionManager::create_transport_config(&server).unwrap();
+        let mut transport =
+            StdioTransport::from_config(transport_config, ConnectionOptions::default()).unwrap();
+        transport.connect().await.unwrap();
+
+        let response = transport
+            .send_request(McpRequest::new(serde_json::json!("echo-1"), "echo"))
+            .await
+            .unwrap();
+        let result = response.into_result().unwrap();
+        assert_eq!(result["value"], "passed-through");
+        assert_eq!(result["cwd"], cwd.display().to_string());
+
+        let _ = transport.disconnect().await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_server_roots_list_request_is_answered() {
+        use crate::mcp::roots::{McpRootsManager, Root, RootsConfig};
+        use crate::mcp::types::McpServerConfig;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let answer_path = dir.path().join("answer.json");
+
+        // Completes the handshake, asks for roots and saves the client's answer
+        let script = r#"read -r init
+printf '{"jsonrpc":"2.0","id":"init-1","result":{"protocolVersion":"2024-11-05","capabilities":{}}}\n'
+read -r initialized
+printf '{"jsonrpc":"2.0","id":"srv-1","method":"roots/list"}\n'
+read -r answer
+printf '%s' "$answer" > "$ANSWER_FILE.tmp" && mv "$ANSWER_FILE.tmp" "$ANSWER_FILE"
+sleep 5"#;
+        let config = McpServerConfig {
+            command: Some("sh".to_string()),
+            args: Some(vec!["-c".to_string(), script.to_string()]),
+            env: Some(HashMap::from([(
+                "ANSWER_FILE".to_string(),
+                answer_path.display().to_string(),
+            )])),
+            ..Default::default()
+        };
+
+        let mut manager = McpConnectionManager::new();
+        manager.set_heartbeat_enabled(false);
+        let manager = Arc::new(manager);
+        let roots = McpRootsManager::new(RootsConfig {
+            validate_paths: false,
+            ..Default::default()
+        })
+        .with_connection_manager(manager.clone());
+        roots
+            .add_root(Root {
+                uri: "file:///project".to_string(),
+                name: Some("Project".to_string()),
+            })
+            .await;
+
+        let connection = manager
+            .connect(McpServerInfo::from_config("roots", &config))
+            .await
+            .unwrap();
+
+        let mut answer = None;
+        for _ in 0..50 {
+            if let Ok(content) = std::fs::read_to_string(&answer_path) {
+                answer = Some(content);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let answer: serde_json::Value = serde_json::from_str(&answer.unwrap()).unwrap();
+        assert_eq!(answer["id"], "srv-1");
+        assert_eq!(answer["result"]["roots"][0]["uri"], "file:///project");
+        assert_eq!(answer["result"]["roots"][0]["name"], "Project");
+
+        let _ = manager.disconnect(&connection.id).await;
+    }
+
     #[test]
     fn test_create_transport_config_http() {
         let server = McpServerInfo {
@@ -844,6 +1132,7 @@ mod tests {
             command: None,
             args: None,
             env: None,
+            cwd: None,
             url: Some("http://localhost:8080".to_string()),
             headers: None,
             options: ConnectionOptions::default(),
@@ -862,6 +1151,7 @@ mod tests {
             command: None, // Missing required command
             args: None,
             env: None,
+            cwd: None,
             url: None,
             headers: None,
             options: ConnectionOptions::default(),
@@ -871,6 +1161,34 @@ mod tests {
         assert!(config.is_err());
     }
 
+    #[test]
+    fn test_pool_settings_from_server_config() {
+        use crate::mcp::transport::HttpPoolConfig;
+        use crate::mcp::types::McpServerConfig;
+
+        let config: McpServerConfig = serde_json::from_value(serde_json::json!({
+            "transport_type": "http",
+            "url": "http://localhost:8080/mcp",
+            "pool": { "idle_timeout": 30000, "max_idle_per_host": 4 }
+        }))
+        .unwrap();
+
+        let server = McpServerInfo::from_config("remote", &config);
+        let TransportConfig::Http { pool, .. } =
+            McpConnectionManager::create_transport_config(&server).unwrap()
+        else {
+            panic!("expected HTTP transport config");
+        };
+        assert_eq!(
+            pool,
+            HttpPoolConfig {
+                idle_timeout: Some(Duration::from_secs(30)),
+                max_idle_per_host: 4,
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn test_create_transport_config_missing_url() {
         let server = McpServerInfo {
@@ -879,6 +1197,7 @@ mod tests {
             command: None,
             args: None,
             env: None,
+            cwd: None,
             url: None, // Missing required URL
             headers: None,
             options: ConnectionOptions::default(),
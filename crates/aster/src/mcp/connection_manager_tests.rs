@@ -68,6 +68,7 @@ mod property_tests {
                     command,
                     args: Some(vec!["--version".to_string()]),
                     env: Some(HashMap::new()),
+                    cwd: None,
                     url,
                     headers: Some(HashMap::new()),
                     options: ConnectionOptions::default(),
@@ -92,6 +93,7 @@ mod property_tests {
                     reconnect_delay_base: Duration::from_millis(delay_base),
                     reconnect_delay_max: Duration::from_millis(delay_max.max(delay_base + 1)),
                     queue_max_size: 100,
+                    pool: Default::default(),
                 }
             })
     }
@@ -307,6 +309,7 @@ mod property_tests {
                 command: None, // Missing for stdio
                 args: None,
                 env: None,
+                cwd: None,
                 url: None, // Missing for HTTP/WS
                 headers: None,
                 options: ConnectionOptions::default(),
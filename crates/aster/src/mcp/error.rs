@@ -273,6 +273,20 @@ impl McpError {
         }
     }
 
+    /// Returns true if the failure is transient and the request may be retried
+    ///
+    /// Connection, transport and timeout failures as well as internal server
+    /// errors are retryable. Errors describing an invalid request, a rejected
+    /// configuration or a failed tool are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Connection { .. } | Self::Transport { .. } | Self::Timeout { .. } => true,
+            Self::Io { .. } => true,
+            Self::Server { code, .. } => *code == McpErrorCode::InternalError.code(),
+            _ => false,
+        }
+    }
+
     // Constructor helpers
 
     /// Create a connection error
@@ -586,6 +600,16 @@ mod tests {
         assert!(mcp_err.message().contains("file not found"));
     }
 
+    #[test]
+    fn test_is_retryable() {
+        assert!(McpError::transport("broken pipe").is_retryable());
+        assert!(McpError::timeout("slow", Duration::from_secs(1)).is_retryable());
+        assert!(McpError::server(-32603, "internal", None).is_retryable());
+        assert!(!McpError::server(-32602, "bad params", None).is_retryable());
+        assert!(!McpError::protocol("unsupported version").is_retryable());
+        assert!(!McpError::tool("failed", None).is_retryable());
+    }
+
     #[test]
     fn test_error_display() {
         let err = McpError::connection("failed to connect");
@@ -60,7 +60,10 @@ impl McpIntegration<McpConnectionManager> {
         let connection_manager = Arc::new(McpConnectionManager::new());
         let lifecycle_manager = Arc::new(McpLifecycleManager::new());
         let config_manager = Arc::new(McpConfigManager::new());
-        let tool_manager = Arc::new(McpToolManager::new(connection_manager.clone()));
+        let tool_manager = Arc::new(
+            McpToolManager::new(connection_manager.clone())
+                .with_capability_cache(connection_manager.capability_cache()),
+        );
 
         Self {
             connection_manager,
@@ -78,7 +81,10 @@ impl McpIntegration<McpConnectionManager> {
         lifecycle_manager: Arc<McpLifecycleManager>,
         config_manager: Arc<McpConfigManager>,
     ) -> Self {
-        let tool_manager = Arc::new(McpToolManager::new(connection_manager.clone()));
+        let tool_manager = Arc::new(
+            McpToolManager::new(connection_manager.clone())
+                .with_capability_cache(connection_manager.capability_cache()),
+        );
 
         Self {
             connection_manager,
@@ -533,9 +539,13 @@ impl McpServerInfo {
             command: config.command.clone(),
             args: config.args.clone(),
             env: config.env.clone(),
+            cwd: config.cwd.clone(),
             url: config.url.clone(),
             headers: config.headers.clone(),
-            options: ConnectionOptions::default(),
+            options: ConnectionOptions {
+                pool: config.pool.clone().unwrap_or_default(),
+                ..Default::default()
+            },
         }
     }
 }
@@ -8,6 +8,7 @@
 //!
 //! - Start/stop server processes with configurable timeouts
 //! - Automatic restart with exponential backoff on unexpected exits
+//! - Crash-loop detection that quarantines servers which keep crashing
 //! - Health check monitoring
 //! - Dependency-based startup ordering
 //! - stdout/stderr capture and event emission
@@ -25,10 +26,10 @@
 
 use async_trait::async_trait;
 use chrono::Utc;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::process::Stdio;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::{mpsc, Mutex, RwLock};
@@ -64,6 +65,14 @@ pub enum LifecycleEvent {
     },
     /// Server is restarting
     Restarting { server_name: String },
+    /// Server crashed too often and auto-restart was stopped
+    ///
+    /// The server stays quarantined until it is restarted manually.
+    CrashLooping {
+        server_name: String,
+        crashes: u32,
+        window: Duration,
+    },
     /// Health check passed
     HealthOk {
         server_name: String,
@@ -80,6 +89,27 @@ pub enum LifecycleEvent {
     Stderr { server_name: String, data: String },
 }
 
+/// Crash-loop detection settings
+///
+/// A server that crashes more than `threshold` times within `window` is
+/// quarantined: auto-restart stops until the server is restarted manually.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrashLoopPolicy {
+    /// Number of crashes tolerated within the window
+    pub threshold: u32,
+    /// Sliding window in which crashes are counted
+    pub window: Duration,
+}
+
+impl Default for CrashLoopPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: 5,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
 /// Start options for server startup
 #[derive(Debug, Clone, Default)]
 pub struct StartOptions {
@@ -89,6 +119,8 @@ pub struct StartOptions {
     pub wait_for_ready: bool,
     /// Dependencies to start first
     pub dependencies: Vec<String>,
+    /// Crash-loop detection used while auto-restarting
+    pub crash_loop: CrashLoopPolicy,
 }
 
 /// Stop options for server shutdown
@@ -116,6 +148,12 @@ pub(crate) struct ManagedServer {
     health_check_handle: Option<tokio::task::JoinHandle<()>>,
     /// Auto-restart task handle
     restart_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Options of the last start, reused by auto-restart
+    start_options: StartOptions,
+    /// Recent crash times used for crash-loop detection
+    crash_times: VecDeque<Instant>,
+    /// Set when a crash loop was detected; blocks auto-restart
+    quarantined: bool,
 }
 
 impl ManagedServer {
@@ -128,6 +166,30 @@ impl ManagedServer {
             output_handles: Vec::new(),
             health_check_handle: None,
             restart_handle: None,
+            start_options: StartOptions::default(),
+            crash_times: VecDeque::new(),
+            quarantined: false,
+        }
+    }
+
+    /// Record a crash and return the crash count if the server is now crash looping
+    fn record_crash(&mut self, now: Instant) -> Option<u32> {
+        let policy = self.start_options.crash_loop;
+        self.crash_times.push_back(now);
+        while self
+            .crash_times
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > policy.window)
+        {
+            self.crash_times.pop_front();
+        }
+
+        let crashes = self.crash_times.len() as u32;
+        if crashes > policy.threshold {
+            self.quarantined = true;
+            Some(crashes)
+        } else {
+            None
         }
     }
 }
@@ -196,6 +258,9 @@ pub trait LifecycleManager: Send + Sync {
 }
 
 /// Default implementation of the lifecycle manager
+///
+/// Clones share the same managed servers and event channel.
+#[derive(Clone)]
 pub struct McpLifecycleManager {
     /// Managed servers
     pub(crate) servers: Arc<RwLock<HashMap<String, ManagedServer>>>,
@@ -307,21 +372,18 @@ impl McpLifecycleManager {
     }
 
     /// Start monitoring a process for unexpected exit
-    fn start_exit_monitor(&self, server_name: String) {
+    fn start_exit_monitor(&self, server_name: String) -> Option<tokio::task::JoinHandle<()>> {
         if !self.enable_auto_restart {
-            return;
+            return None;
         }
 
-        let servers = self.servers.clone();
-        let event_tx = self.event_tx.clone();
-        let options = self.options.clone();
-        let enable_auto_restart = self.enable_auto_restart;
+        let manager = self.clone();
 
-        tokio::spawn(async move {
+        Some(tokio::spawn(async move {
             loop {
                 // Check if process is still running
                 let should_restart = {
-                    let mut servers_guard = servers.write().await;
+                    let mut servers_guard = manager.servers.write().await;
                     if let Some(server) = servers_guard.get_mut(&server_name) {
                         if let Some(ref mut child) = server.child {
                             match child.try_wait() {
@@ -334,22 +396,39 @@ impl McpLifecycleManager {
                                     server.child = None;
 
                                     // Emit crashed event
-                                    if let Some(tx) = event_tx.lock().await.as_ref() {
-                                        let _ = tx
-                                            .send(LifecycleEvent::Crashed {
+                                    manager
+                                        .emit_event(LifecycleEvent::Crashed {
+                                            server_name: server_name.clone(),
+                                            exit_code,
+                                        })
+                                        .await;
+
+                                    // Check if we should restart
+                                    if let Some(crashes) = server.record_crash(Instant::now()) {
+                                        let window = server.start_options.crash_loop.window;
+                                        server.process.last_error = Some(format!(
+                                            "Crashed {} times within {:?}, auto-restart stopped",
+                                            crashes, window
+                                        ));
+                                        tracing::warn!(
+                                            "MCP server {} is crash looping ({} crashes within {:?})",
+                                            server_name,
+                                            crashes,
+                                            window
+                                        );
+                                        manager
+                                            .emit_event(LifecycleEvent::CrashLooping {
                                                 server_name: server_name.clone(),
-                                                exit_code,
+                                                crashes,
+                                                window,
                                             })
                                             .await;
-                                    }
-
-                                    // Check if we should restart
-                                    if enable_auto_restart
-                                        && server.process.restart_count < options.max_restarts
+                                        false
+                                    } else if server.process.restart_count
+                                        < manager.options.max_restarts
                                     {
                                         true
                                     } else {
-                                        server.process.state = ServerState::Crashed;
                                         server.process.last_error = Some(format!(
                                             "Process exited with code {:?}, max restarts exceeded",
                                             exit_code
@@ -379,43 +458,46 @@ impl McpLifecycleManager {
 
                 if should_restart {
                     // Calculate delay based on restart count
-                    let restart_count = {
-                        let servers_guard = servers.read().await;
-                        servers_guard
-                            .get(&server_name)
-                            .map(|s| s.process.restart_count)
-                            .unwrap_or(0)
+                    let (restart_count, start_options) = {
+                        let servers_guard = manager.servers.read().await;
+                        match servers_guard.get(&server_name) {
+                            Some(s) => (s.process.restart_count, s.start_options.clone()),
+                            None => break,
+                        }
                     };
-
-                    let base = options.restart_delay.as_millis() as u64;
-                    let delay_ms = base.saturating_mul(1u64 << restart_count.min(10));
-                    let delay = Duration::from_millis(delay_ms.min(60_000));
+                    let delay = manager.calculate_restart_delay(restart_count);
 
                     // Emit restarting event
-                    if let Some(tx) = event_tx.lock().await.as_ref() {
-                        let _ = tx
-                            .send(LifecycleEvent::Restarting {
-                                server_name: server_name.clone(),
-                            })
-                            .await;
-                    }
+                    manager
+                        .emit_event(LifecycleEvent::Restarting {
+                            server_name: server_name.clone(),
+                        })
+                        .await;
 
                     tokio::time::sleep(delay).await;
 
-                    // Attempt restart
-                    let mut servers_guard = servers.write().await;
-                    if let Some(server) = servers_guard.get_mut(&server_name) {
-                        server.process.restart_count += 1;
-                        // The actual restart will be handled by the start method
-                        // For now, just update state
-                        server.process.state = ServerState::Starting;
+                    {
+                        let mut servers_guard = manager.servers.write().await;
+                        if let Some(server) = servers_guard.get_mut(&server_name) {
+                            server.process.restart_count += 1;
+                        }
+                    }
+
+                    // The restarted process gets its own exit monitor
+                    let options = StartOptions {
+                        force: true,
+                        ..start_options
+                    };
+                    if let Err(e) = manager.start(&server_name, Some(options)).await {
+                        tracing::warn!("Failed to restart MCP server {}: {}", server_name, e);
                     }
+                    break;
                 }
 
                 // Sleep before next check
                 tokio::time::sleep(Duration::from_millis(500)).await;
             }
-        });
+        }))
     }
 
     /// Start health check monitoring for a server
@@ -456,6 +538,7 @@ impl McpLifecycleManager {
                                         latency: Some(start.elapsed()),
                                         last_check: Utc::now(),
                                         error: None,
+                                        quarantined: false,
                                     }
                                 }
                                 Ok(Some(_)) => {
@@ -465,6 +548,7 @@ impl McpLifecycleManager {
                                         latency: Some(start.elapsed()),
                                         last_check: Utc::now(),
                                         error: Some("Process has exited".to_string()),
+                                        quarantined: false,
                                     }
                                 }
                                 Err(e) => HealthCheckResult {
@@ -472,6 +556,7 @@ impl McpLifecycleManager {
                                     latency: Some(start.elapsed()),
                                     last_check: Utc::now(),
                                     error: Some(e.to_string()),
+                                    quarantined: false,
                                 },
                             }
                         } else {
@@ -480,6 +565,7 @@ impl McpLifecycleManager {
                                 latency: None,
                                 last_check: Utc::now(),
                                 error: Some("No child process".to_string()),
+                                quarantined: false,
                             }
                         }
                     } else {
@@ -612,6 +698,16 @@ impl LifecycleManager for McpLifecycleManager {
                 return Ok(());
             }
 
+            if server.quarantined && !options.force {
+                return Err(McpError::lifecycle(
+                    format!(
+                        "Server {} is crash looping; restart it manually",
+                        server_name
+                    ),
+                    Some(server_name.to_string()),
+                ));
+            }
+
             // Only stdio servers can be started as processes
             if server.config.transport_type != TransportType::Stdio {
                 return Err(McpError::lifecycle(
@@ -637,6 +733,8 @@ impl LifecycleManager for McpLifecycleManager {
             let mut servers = self.servers.write().await;
             if let Some(server) = servers.get_mut(server_name) {
                 server.process.state = ServerState::Starting;
+                server.quarantined = false;
+                server.start_options = options.clone();
             }
         }
 
@@ -659,6 +757,9 @@ impl LifecycleManager for McpLifecycleManager {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .kill_on_drop(true);
+        if let Some(cwd) = &config.cwd {
+            cmd.current_dir(cwd);
+        }
 
         // Spawn process with timeout
         let startup_timeout = self.options.startup_timeout;
@@ -733,7 +834,12 @@ impl LifecycleManager for McpLifecycleManager {
         }
 
         // Start exit monitor for auto-restart
-        self.start_exit_monitor(server_name.to_string());
+        if let Some(handle) = self.start_exit_monitor(server_name.to_string()) {
+            let mut servers = self.servers.write().await;
+            if let Some(server) = servers.get_mut(server_name) {
+                server.restart_handle = Some(handle);
+            }
+        }
 
         // Start health check monitor if enabled
         if self.enable_health_checks {
@@ -919,7 +1025,18 @@ impl LifecycleManager for McpLifecycleManager {
 
     async fn restart(&self, server_name: &str) -> McpResult<()> {
         self.stop(server_name, None).await?;
-        self.start(server_name, None).await
+
+        // A manual restart lifts crash-loop quarantine
+        let options = {
+            let mut servers = self.servers.write().await;
+            servers.get_mut(server_name).map(|server| {
+                server.quarantined = false;
+                server.crash_times.clear();
+                server.process.restart_count = 0;
+                server.start_options.clone()
+            })
+        };
+        self.start(server_name, options).await
     }
 
     async fn restart_all(&self) -> McpResult<()> {
@@ -932,6 +1049,15 @@ impl LifecycleManager for McpLifecycleManager {
 
         let mut servers = self.servers.write().await;
         if let Some(server) = servers.get_mut(server_name) {
+            if server.quarantined {
+                return HealthCheckResult {
+                    healthy: false,
+                    latency: None,
+                    last_check: Utc::now(),
+                    error: server.process.last_error.clone(),
+                    quarantined: true,
+                };
+            }
             if let Some(ref mut child) = server.child {
                 match child.try_wait() {
                     Ok(None) => {
@@ -941,6 +1067,7 @@ impl LifecycleManager for McpLifecycleManager {
                             latency: Some(start.elapsed()),
                             last_check: Utc::now(),
                             error: None,
+                            quarantined: false,
                         }
                     }
                     Ok(Some(status)) => {
@@ -950,6 +1077,7 @@ impl LifecycleManager for McpLifecycleManager {
                             latency: Some(start.elapsed()),
                             last_check: Utc::now(),
                             error: Some(format!("Process exited with status: {:?}", status)),
+                            quarantined: false,
                         }
                     }
                     Err(e) => HealthCheckResult {
@@ -957,6 +1085,7 @@ impl LifecycleManager for McpLifecycleManager {
                         latency: Some(start.elapsed()),
                         last_check: Utc::now(),
                         error: Some(e.to_string()),
+                        quarantined: false,
                     },
                 }
             } else {
@@ -965,6 +1094,7 @@ impl LifecycleManager for McpLifecycleManager {
                     latency: None,
                     last_check: Utc::now(),
                     error: Some("Server not running".to_string()),
+                    quarantined: false,
                 }
             }
         } else {
@@ -973,6 +1103,7 @@ impl LifecycleManager for McpLifecycleManager {
                 latency: None,
                 last_check: Utc::now(),
                 error: Some("Server not found".to_string()),
+                quarantined: false,
             }
         }
     }
@@ -1062,6 +1193,7 @@ mod tests {
             command: Some("echo".to_string()),
             args: Some(vec!["hello".to_string()]),
             env: None,
+            cwd: None,
             url: None,
             headers: None,
             enabled: true,
@@ -1069,6 +1201,7 @@ mod tests {
             retries: 3,
             auto_approve: vec![],
             log_level: Default::default(),
+            pool: None,
         }
     }
 
@@ -1285,4 +1418,63 @@ mod tests {
         // Just verify subscription works without panic
         tokio::time::sleep(Duration::from_millis(50)).await;
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_crash_loop_stops_auto_restart() {
+        let mut manager = McpLifecycleManager::with_options(LifecycleOptions {
+            max_restarts: 100,
+            restart_delay: Duration::from_millis(10),
+            ..Default::default()
+        });
+        manager.set_health_checks_enabled(false);
+
+        let mut config = create_test_config();
+        config.command = Some("sh".to_string());
+        config.args = Some(vec!["-c".to_string(), "exit 1".to_string()]);
+        manager.register_server("flaky", config);
+
+        let mut rx = manager.subscribe();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let options = StartOptions {
+            crash_loop: CrashLoopPolicy {
+                threshold: 2,
+                window: Duration::from_secs(60),
+            },
+            ..Default::default()
+        };
+        manager.start("flaky", Some(options)).await.unwrap();
+
+        let mut started = 0;
+        let mut crashed = 0;
+        let crash_looping = tokio::time::timeout(Duration::from_secs(20), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    LifecycleEvent::Started { .. } => started += 1,
+                    LifecycleEvent::Crashed { .. } => crashed += 1,
+                    LifecycleEvent::CrashLooping { crashes, .. } => return crashes,
+                    _ => {}
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .expect("crash loop was not detected");
+
+        assert_eq!(crash_looping, 3);
+        assert_eq!(started, 3);
+        assert_eq!(crashed, 3);
+
+        // No further restarts once quarantined
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        while let Ok(event) = rx.try_recv() {
+            assert!(!matches!(event, LifecycleEvent::Started { .. }));
+        }
+
+        let health = manager.health_check("flaky").await;
+        assert!(!health.healthy);
+        assert!(health.quarantined);
+        assert!(manager.start("flaky", None).await.is_err());
+    }
 }
@@ -63,6 +63,7 @@ mod property_tests {
                 command: Some(command),
                 args: Some(args),
                 env: Some(HashMap::new()),
+                cwd: None,
                 url: None,
                 headers: None,
                 enabled: true,
@@ -70,6 +71,7 @@ mod property_tests {
                 retries: 3,
                 auto_approve: vec![],
                 log_level: Default::default(),
+                pool: None,
             })
     }
 
@@ -232,6 +234,7 @@ mod property_tests {
                         command: Some("echo".to_string()),
                         args: Some(vec!["test".to_string()]),
                         env: None,
+                        cwd: None,
                         url: None,
                         headers: None,
                         enabled: true,
@@ -239,6 +242,7 @@ mod property_tests {
                         retries: 3,
                         auto_approve: vec![],
                         log_level: Default::default(),
+                        pool: None,
                     };
                     manager.register_server(name, config);
                 }
@@ -427,6 +431,7 @@ mod property_tests {
                         command: Some("echo".to_string()),
                         args: Some(vec!["test".to_string()]),
                         env: None,
+                        cwd: None,
                         url: None,
                         headers: None,
                         enabled: true,
@@ -434,6 +439,7 @@ mod property_tests {
                         retries: 3,
                         auto_approve: vec![],
                         log_level: Default::default(),
+                        pool: None,
                     };
                     manager.register_server(&format!("server-{}", i), config);
                 }
@@ -48,6 +48,7 @@
 //! ```
 
 pub mod cancellation;
+pub mod capability_cache;
 pub mod config_manager;
 pub mod connection_manager;
 pub mod error;
@@ -86,6 +87,7 @@ mod integration_tests;
 mod error_tests;
 
 // Re-export commonly used types
+pub use capability_cache::{CapabilityCache, NegotiatedServer};
 pub use config_manager::{
     ConfigChangeCallback, ConfigEvent, ConfigManager, McpConfigFile, McpConfigManager,
 };
@@ -95,7 +97,8 @@ pub use connection_manager::{
 pub use error::{McpError, McpErrorCode, McpResult, StructuredError};
 pub use integration::McpIntegration;
 pub use lifecycle_manager::{
-    LifecycleEvent, LifecycleManager, McpLifecycleManager, StartOptions, StopOptions,
+    CrashLoopPolicy, LifecycleEvent, LifecycleManager, McpLifecycleManager, StartOptions,
+    StopOptions,
 };
 pub use logging::{LogCallback, McpLogEntry, McpLogger};
 pub use resource_manager::{
@@ -103,13 +106,13 @@ pub use resource_manager::{
     ResourceEvent, ResourceManager,
 };
 pub use tool_manager::{
-    ArgValidationResult, CallInfo, McpTool, McpToolManager, ToolCall, ToolCallResult, ToolManager,
-    ToolResultContent,
+    ArgValidationResult, CallInfo, McpTool, McpToolManager, ToolCall, ToolCallEvent,
+    ToolCallResult, ToolCallStream, ToolManager, ToolResultContent,
 };
 pub use transport::{
-    BoxedTransport, HttpTransport, McpErrorData, McpMessage, McpNotification, McpRequest,
-    McpResponse, SharedTransport, StdioTransport, Transport, TransportConfig, TransportEvent,
-    TransportFactory, TransportState, WebSocketTransport,
+    BoxedTransport, HttpPoolConfig, HttpTransport, McpErrorData, McpMessage, McpNotification,
+    McpRequest, McpResponse, SharedTransport, StdioTransport, Transport, TransportConfig,
+    TransportEvent, TransportFactory, TransportState, WebSocketTransport,
 };
 pub use types::{
     ConfigManagerOptions, ConfigScope, ConnectionOptions, ConnectionStatus, HealthCheckResult,
@@ -135,6 +138,7 @@ pub use notifications::{
 
 // Re-export roots types
 pub use roots::{
-    create_root_from_path, get_default_roots_config, McpRootsManager, Root, RootEvent, RootInfo,
-    RootPermissions, RootsConfig, RootsStats,
+    create_root_from_path, get_default_roots_config, list_roots, McpRootsManager, Root, RootEvent,
+    RootInfo, RootPermissions, RootsChange, RootsConfig, RootsStats, SharedRoots, ROOTS_LIST,
+    ROOTS_LIST_CHANGED,
 };
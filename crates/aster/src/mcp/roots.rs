@@ -2,6 +2,12 @@
 //!
 //! Manages root directories for MCP servers. Roots define the base directories
 //! that servers can access, providing a sandboxing mechanism for file operations.
+//!
+//! Roots are a client capability: aster advertises `roots.listChanged` in its
+//! `initialize` request. When a connection manager is attached it answers
+//! `roots/list` from servers with the current roots, and replacing the roots
+//! with [`McpRootsManager::set_roots`] sends `notifications/roots/list_changed`
+//! to every connected server.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -9,6 +15,32 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 
+use crate::mcp::connection_manager::ConnectionManager;
+use crate::mcp::transport::McpNotification;
+use crate::mcp::types::ConnectionStatus;
+
+/// Notification sent to servers when the roots change
+pub const ROOTS_LIST_CHANGED: &str = "notifications/roots/list_changed";
+
+/// Request servers send to read the client's roots
+pub const ROOTS_LIST: &str = "roots/list";
+
+/// Roots store shared with the connection manager that answers `roots/list`
+pub type SharedRoots = Arc<RwLock<HashMap<String, RootInfo>>>;
+
+/// Current roots as plain Root objects (for MCP protocol)
+pub async fn list_roots(roots: &SharedRoots) -> Vec<Root> {
+    roots
+        .read()
+        .await
+        .values()
+        .map(|r| Root {
+            uri: r.uri.clone(),
+            name: r.name.clone(),
+        })
+        .collect()
+}
+
 /// Root directory for MCP protocol
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Root {
@@ -35,7 +67,7 @@ pub struct RootInfo {
 }
 
 /// Root permissions
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RootPermissions {
     /// Read permission
     pub read: bool,
@@ -77,14 +109,37 @@ pub enum RootEvent {
     RootsCleared { count: usize },
     /// Roots refreshed
     RootsRefreshed { count: usize },
+    /// Roots replaced via `set_roots`
+    RootsChanged { change: RootsChange },
+}
+
+/// Difference applied by [`McpRootsManager::set_roots`]
+#[derive(Debug, Clone, Default)]
+pub struct RootsChange {
+    /// Roots that were not present before
+    pub added: Vec<RootInfo>,
+    /// Roots that are no longer present
+    pub removed: Vec<RootInfo>,
+    /// Roots whose name or permissions changed
+    pub updated: Vec<RootInfo>,
+    /// Servers that were sent `notifications/roots/list_changed`
+    pub notified_servers: Vec<String>,
+}
+
+impl RootsChange {
+    /// Whether the roots changed at all
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
 }
 
 /// Manages root directories for MCP servers
 pub struct McpRootsManager {
-    roots: Arc<RwLock<HashMap<String, RootInfo>>>,
+    roots: SharedRoots,
     allow_dynamic_roots: bool,
     validate_paths: bool,
     event_sender: broadcast::Sender<RootEvent>,
+    connection_manager: Option<Arc<dyn ConnectionManager>>,
 }
 
 impl McpRootsManager {
@@ -96,6 +151,7 @@ impl McpRootsManager {
             allow_dynamic_roots: config.allow_dynamic_roots,
             validate_paths: config.validate_paths,
             event_sender,
+            connection_manager: None,
         };
 
         // Initialize with provided roots (blocking for simplicity)
@@ -110,6 +166,17 @@ impl McpRootsManager {
         manager
     }
 
+    /// Serve these roots to servers on this connection manager and notify
+    /// them when the roots change
+    pub fn with_connection_manager(
+        mut self,
+        connection_manager: Arc<dyn ConnectionManager>,
+    ) -> Self {
+        connection_manager.serve_roots(self.roots.clone());
+        self.connection_manager = Some(connection_manager);
+        self
+    }
+
     /// Subscribe to root events
     pub fn subscribe(&self) -> broadcast::Receiver<RootEvent> {
         self.event_sender.subscribe()
@@ -164,15 +231,7 @@ impl McpRootsManager {
 
     /// Get all roots as plain Root objects (for MCP protocol)
     pub async fn get_roots_for_protocol(&self) -> Vec<Root> {
-        self.roots
-            .read()
-            .await
-            .values()
-            .map(|r| Root {
-                uri: r.uri.clone(),
-                name: r.name.clone(),
-            })
-            .collect()
+        list_roots(&self.roots).await
     }
 
     /// Clear all roots
@@ -183,6 +242,79 @@ impl McpRootsManager {
         let _ = self.event_sender.send(RootEvent::RootsCleared { count });
     }
 
+    /// Replace all roots
+    ///
+    /// Diffs `roots` against the current set, re-reads permissions for every
+    /// root and, if anything changed, notifies every connected server.
+    pub async fn set_roots(&self, roots: Vec<Root>) -> RootsChange {
+        let mut change = RootsChange::default();
+        {
+            let mut current = self.roots.write().await;
+            let mut next = HashMap::new();
+
+            for root in roots {
+                let info = self.parse_root(&root);
+                match current.get(&root.uri) {
+                    None => change.added.push(info.clone()),
+                    Some(previous) => {
+                        if previous.name != info.name
+                            || previous.exists != info.exists
+                            || previous.permissions != info.permissions
+                        {
+                            change.updated.push(info.clone());
+                        }
+                    }
+                }
+                next.insert(root.uri, info);
+            }
+
+            change.removed = current
+                .iter()
+                .filter(|(uri, _)| !next.contains_key(*uri))
+                .map(|(_, info)| info.clone())
+                .collect();
+
+            *current = next;
+        }
+
+        if change.is_empty() {
+            return change;
+        }
+
+        change.notified_servers = self.notify_roots_changed().await;
+        let _ = self.event_sender.send(RootEvent::RootsChanged {
+            change: change.clone(),
+        });
+        change
+    }
+
+    /// Send `notifications/roots/list_changed` to connected servers
+    async fn notify_roots_changed(&self) -> Vec<String> {
+        let Some(connection_manager) = &self.connection_manager else {
+            return Vec::new();
+        };
+
+        let mut notified = Vec::new();
+        for connection in connection_manager.get_all_connections() {
+            if connection.status != ConnectionStatus::Connected {
+                continue;
+            }
+
+            match connection_manager
+                .send_notification(&connection.id, McpNotification::new(ROOTS_LIST_CHANGED))
+                .await
+            {
+                Ok(()) => notified.push(connection.server_name),
+                Err(e) => tracing::warn!(
+                    "Failed to send roots change to {}: {}",
+                    connection.server_name,
+                    e
+                ),
+            }
+        }
+        notified
+    }
+
     /// Check if a URI is registered as a root
     pub async fn has_root(&self, uri: &str) -> bool {
         self.roots.read().await.contains_key(uri)
@@ -429,6 +561,10 @@ pub fn get_default_roots_config() -> RootsConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mcp::connection_manager::ConnectionEvent;
+    use crate::mcp::error::{McpError, McpResult};
+    use crate::mcp::transport::{McpRequest, McpResponse};
+    use crate::mcp::types::{McpConnection, McpServerInfo, TransportType};
 
     #[test]
     fn test_root_creation() {
@@ -565,4 +701,164 @@ mod tests {
         assert!(config.allow_dynamic_roots);
         assert!(config.validate_paths);
     }
+
+    /// Connection manager with one connected and one disconnected server
+    /// that records the notifications it is asked to send
+    #[derive(Default)]
+    struct RecordingConnectionManager {
+        sent: std::sync::Mutex<Vec<(String, String)>>,
+        served: std::sync::Mutex<Option<SharedRoots>>,
+    }
+
+    impl RecordingConnectionManager {
+        fn connection(id: &str, server: &str, status: ConnectionStatus) -> McpConnection {
+            let mut connection =
+                McpConnection::new(id.to_string(), server.to_string(), TransportType::Stdio);
+            connection.status = status;
+            connection
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ConnectionManager for RecordingConnectionManager {
+        async fn connect(&self, _server: McpServerInfo) -> McpResult<McpConnection> {
+            Err(McpError::connection("not supported"))
+        }
+
+        async fn disconnect(&self, _connection_id: &str) -> McpResult<()> {
+            Ok(())
+        }
+
+        async fn disconnect_all(&self) -> McpResult<()> {
+            Ok(())
+        }
+
+        async fn send(&self, _connection_id: &str, _request: McpRequest) -> McpResult<McpResponse> {
+            Err(McpError::connection("not supported"))
+        }
+
+        async fn send_with_timeout(
+            &self,
+            connection_id: &str,
+            request: McpRequest,
+            _timeout: std::time::Duration,
+        ) -> McpResult<McpResponse> {
+            self.send(connection_id, request).await
+        }
+
+        async fn send_with_retry(
+            &self,
+            connection_id: &str,
+            request: McpRequest,
+        ) -> McpResult<McpResponse> {
+            self.send(connection_id, request).await
+        }
+
+        async fn cancel_request(&self, _connection_id: &str, _request_id: &str) -> McpResult<()> {
+            Ok(())
+        }
+
+        async fn send_notification(
+            &self,
+            connection_id: &str,
+            notification: McpNotification,
+        ) -> McpResult<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((connection_id.to_string(), notification.method));
+            Ok(())
+        }
+
+        fn serve_roots(&self, roots: SharedRoots) {
+            *self.served.lock().unwrap() = Some(roots);
+        }
+
+        async fn subscribe_notifications(
+            &self,
+            _connection_id: &str,
+        ) -> McpResult<tokio::sync::mpsc::Receiver<McpNotification>> {
+            Ok(tokio::sync::mpsc::channel(1).1)
+        }
+
+        fn get_connection(&self, id: &str) -> Option<McpConnection> {
+            self.get_all_connections().into_iter().find(|c| c.id == id)
+        }
+
+        fn get_connection_by_server(&self, server_name: &str) -> Option<McpConnection> {
+            self.get_all_connections()
+                .into_iter()
+                .find(|c| c.server_name == server_name)
+        }
+
+        fn get_all_connections(&self) -> Vec<McpConnection> {
+            vec![
+                Self::connection("conn-fs", "fs", ConnectionStatus::Connected),
+                Self::connection("conn-web", "web", ConnectionStatus::Connected),
+                Self::connection("conn-old", "old", ConnectionStatus::Disconnected),
+            ]
+        }
+
+        fn subscribe(&self) -> tokio::sync::mpsc::Receiver<ConnectionEvent> {
+            tokio::sync::mpsc::channel(1).1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_roots_notifies_connected_servers() {
+        let connections = Arc::new(RecordingConnectionManager::default());
+        let manager = McpRootsManager::new(RootsConfig {
+            validate_paths: false,
+            ..Default::default()
+        })
+        .with_connection_manager(connections.clone());
+        manager
+            .add_root(Root {
+                uri: "file:///old".to_string(),
+                name: None,
+            })
+            .await;
+        let mut events = manager.subscribe();
+
+        let change = manager
+            .set_roots(vec![Root {
+                uri: "file:///project".to_string(),
+                name: Some("Project".to_string()),
+            }])
+            .await;
+
+        assert_eq!(change.added.len(), 1);
+        assert_eq!(change.removed[0].uri, "file:///old");
+        assert_eq!(
+            change.notified_servers,
+            vec!["fs".to_string(), "web".to_string()]
+        );
+        assert_eq!(
+            *connections.sent.lock().unwrap(),
+            vec![
+                ("conn-fs".to_string(), ROOTS_LIST_CHANGED.to_string()),
+                ("conn-web".to_string(), ROOTS_LIST_CHANGED.to_string()),
+            ]
+        );
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            RootEvent::RootsChanged { .. }
+        ));
+
+        // Setting the same roots again is a no-op
+        let unchanged = manager
+            .set_roots(vec![Root {
+                uri: "file:///project".to_string(),
+                name: Some("Project".to_string()),
+            }])
+            .await;
+        assert!(unchanged.is_empty());
+        assert_eq!(connections.sent.lock().unwrap().len(), 2);
+
+        // The connection manager answers roots/list from the same store
+        let served = connections.served.lock().unwrap().clone().unwrap();
+        let listed = list_roots(&served).await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].uri, "file:///project");
+    }
 }
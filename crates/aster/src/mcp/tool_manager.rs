@@ -10,6 +10,7 @@
 //! - Tool invocation with timeout support
 //! - Call tracking and cancellation
 //! - Batch tool calls with parallel execution
+//! - Streaming tool calls with progress notifications
 //! - Result format conversion
 //!
 //! # Requirements Coverage
@@ -25,18 +26,24 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, Stream};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::wrappers::ReceiverStream;
 use uuid::Uuid;
 
+use crate::mcp::capability_cache::CapabilityCache;
 use crate::mcp::connection_manager::ConnectionManager;
 use crate::mcp::error::{McpError, McpResult};
-use crate::mcp::transport::McpRequest;
-use crate::mcp::types::JsonObject;
+use crate::mcp::notifications::{Notification, NotificationType};
+use crate::mcp::transport::{McpNotification, McpRequest};
+use crate::mcp::types::{JsonObject, McpConnection};
 
 /// MCP tool definition
 ///
@@ -147,7 +154,9 @@ impl ToolResultContent {
 /// Tool call result
 ///
 /// Represents the result of a tool invocation, containing the content
-/// and an error flag.
+/// and an error flag. `is_error` mirrors the MCP `isError` field: it is set
+/// when the tool ran and reported a failure, which is distinct from a
+/// transport or protocol failure returned as `McpError`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCallResult {
     /// Result content (can be multiple items)
@@ -251,6 +260,9 @@ pub struct CallInfo {
     pub completed: bool,
     /// Whether the call was cancelled
     pub cancelled: bool,
+    /// Progress token sent with the request, used to correlate
+    /// progress notifications back to this call
+    pub progress_token: Option<String>,
 }
 
 impl CallInfo {
@@ -269,9 +281,37 @@ impl CallInfo {
             start_time: Utc::now(),
             completed: false,
             cancelled: false,
+            progress_token: None,
         }
     }
 
+    /// Set the progress token for this call
+    pub fn with_progress_token(mut self, token: impl Into<String>) -> Self {
+        self.progress_token = Some(token.into());
+        self
+    }
+
+    /// Check whether a server notification is a progress update for this call
+    pub fn matches_notification(&self, notification: &McpNotification) -> bool {
+        let Some(token) = &self.progress_token else {
+            return false;
+        };
+        notification.method == "notifications/progress"
+            && notification
+                .params
+                .as_ref()
+                .and_then(|p| p.get("progressToken"))
+                .is_some_and(|t| match t {
+                    serde_json::Value::String(s) => s == token,
+                    other => {
+                        serde_json::from_str::<serde_json::Value>(token)
+                            .ok()
+                            .as_ref()
+                            == Some(other)
+                    }
+                })
+    }
+
     /// Mark the call as completed
     pub fn mark_completed(&mut self) {
         self.completed = true;
@@ -316,6 +356,40 @@ impl ToolCall {
     }
 }
 
+/// Event emitted by a streaming tool call
+#[derive(Debug)]
+pub enum ToolCallEvent {
+    /// Progress notification from the server for this call
+    Progress(Notification),
+    /// Final result of the call; always the last event
+    Result(McpResult<ToolCallResult>),
+}
+
+/// Stream of events for a single tool call
+///
+/// Yields zero or more [`ToolCallEvent::Progress`] events followed by
+/// exactly one [`ToolCallEvent::Result`].
+pub struct ToolCallStream {
+    /// Information about the originating call
+    pub call_info: CallInfo,
+    inner: BoxStream<'static, ToolCallEvent>,
+}
+
+impl ToolCallStream {
+    /// Call ID (also the JSON-RPC request ID)
+    pub fn call_id(&self) -> &str {
+        &self.call_info.call_id
+    }
+}
+
+impl Stream for ToolCallStream {
+    type Item = ToolCallEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
 /// Tool manager trait
 ///
 /// Defines the interface for managing MCP tools, including discovery,
@@ -400,6 +474,9 @@ pub struct McpToolManager<C: ConnectionManager> {
     default_timeout: Duration,
     /// Cache TTL (time-to-live)
     cache_ttl: Duration,
+    /// Negotiated capability cache; when set it replaces `tool_cache` for
+    /// lookups, still subject to `cache_ttl`
+    capability_cache: Option<Arc<CapabilityCache>>,
 }
 
 impl<C: ConnectionManager> McpToolManager<C> {
@@ -412,6 +489,7 @@ impl<C: ConnectionManager> McpToolManager<C> {
             call_counter: AtomicU64::new(1),
             default_timeout: Duration::from_secs(30),
             cache_ttl: Duration::from_secs(300), // 5 minutes
+            capability_cache: None,
         }
     }
 
@@ -428,9 +506,16 @@ impl<C: ConnectionManager> McpToolManager<C> {
             call_counter: AtomicU64::new(1),
             default_timeout,
             cache_ttl,
+            capability_cache: None,
         }
     }
 
+    /// Use a capability cache so tool lists survive reconnects to unchanged servers
+    pub fn with_capability_cache(mut self, cache: Arc<CapabilityCache>) -> Self {
+        self.capability_cache = Some(cache);
+        self
+    }
+
     /// Generate a unique call ID
     pub fn generate_call_id(&self) -> String {
         let counter = self.call_counter.fetch_add(1, Ordering::SeqCst);
@@ -515,36 +600,204 @@ impl<C: ConnectionManager> McpToolManager<C> {
         }
         calls.remove(call_id);
     }
+}
 
-    /// Convert MCP tool result to standardized format
-    ///
-    /// This handles the conversion from raw MCP response to ToolCallResult.
-    fn convert_result(&self, result: serde_json::Value) -> McpResult<ToolCallResult> {
-        // Check if result has content array
-        if let Some(content) = result.get("content") {
-            let content_items: Vec<ToolResultContent> = serde_json::from_value(content.clone())
-                .map_err(|e| {
-                    McpError::protocol(format!("Failed to parse tool result content: {}", e))
-                })?;
+/// Convert an MCP tool result to the standardized format
+///
+/// This handles the conversion from raw MCP response to ToolCallResult.
+pub(super) fn convert_result(result: serde_json::Value) -> McpResult<ToolCallResult> {
+    // `isError` distinguishes a failed tool run from a successful one.
+    // Some servers send it as a string or number, so accept those too
+    let is_error = match result.get("isError") {
+        None | Some(serde_json::Value::Null) => false,
+        Some(serde_json::Value::Bool(flag)) => *flag,
+        Some(serde_json::Value::String(flag)) => {
+            matches!(flag.trim().to_ascii_lowercase().as_str(), "true" | "1")
+        }
+        Some(serde_json::Value::Number(flag)) => flag.as_f64().is_some_and(|n| n != 0.0),
+        Some(other) => {
+            tracing::warn!(
+                "Ignoring non-boolean isError value in tool result: {}",
+                other
+            );
+            false
+        }
+    };
 
-            let is_error = result
-                .get("isError")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
+    // Check if result has content array
+    if let Some(content) = result.get("content") {
+        let content_items: Vec<ToolResultContent> = serde_json::from_value(content.clone())
+            .map_err(|e| {
+                McpError::protocol(format!("Failed to parse tool result content: {}", e))
+            })?;
 
-            return Ok(ToolCallResult {
-                content: content_items,
-                is_error,
-            });
+        return Ok(ToolCallResult {
+            content: content_items,
+            is_error,
+        });
+    }
+
+    if is_error {
+        return Ok(ToolCallResult {
+            content: Vec::new(),
+            is_error,
+        });
+    }
+
+    // Handle legacy format or simple text response
+    if let Some(text) = result.as_str() {
+        return Ok(ToolCallResult::success_text(text));
+    }
+
+    // Return the raw result as JSON text
+    Ok(ToolCallResult::success_text(result.to_string()))
+}
+
+impl<C: ConnectionManager + 'static> McpToolManager<C> {
+    /// Look up the tool, validate arguments and resolve the server connection
+    async fn prepare_call(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: &JsonObject,
+    ) -> McpResult<McpConnection> {
+        // Get the tool definition for validation
+        let tool = self
+            .get_tool(server_name, tool_name)
+            .await?
+            .ok_or_else(|| {
+                McpError::tool(
+                    format!("Tool not found: {}/{}", server_name, tool_name),
+                    Some(tool_name.to_string()),
+                )
+            })?;
+
+        // Validate arguments
+        let validation = self.validate_args(&tool, args);
+        if !validation.valid {
+            return Err(McpError::validation(
+                format!(
+                    "Invalid arguments for tool {}: {}",
+                    tool_name,
+                    validation.errors.join(", ")
+                ),
+                validation.errors,
+            ));
         }
 
-        // Handle legacy format or simple text response
-        if let Some(text) = result.as_str() {
-            return Ok(ToolCallResult::success_text(text));
+        // Get connection
+        self.connection_manager
+            .get_connection_by_server(server_name)
+            .ok_or_else(|| {
+                McpError::connection(format!("No connection found for server: {}", server_name))
+            })
+    }
+
+    /// Re-query the tool list from a server, bypassing all caches
+    pub async fn force_refresh(&self, server_name: &str) -> McpResult<Vec<McpTool>> {
+        if let Some(capability_cache) = &self.capability_cache {
+            capability_cache.invalidate(server_name);
         }
+        self.tool_cache.write().await.remove(server_name);
+        self.list_tools(Some(server_name)).await
+    }
 
-        // Return the raw result as JSON text
-        Ok(ToolCallResult::success_text(result.to_string()))
+    /// Call a tool and stream progress notifications along with the result
+    ///
+    /// The request carries a progress token equal to the call ID, so the
+    /// server's `notifications/progress` messages can be matched back to
+    /// this call. Unrelated notifications are ignored.
+    pub async fn call_streaming(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: JsonObject,
+    ) -> McpResult<ToolCallStream> {
+        self.call_streaming_with_timeout(server_name, tool_name, args, self.default_timeout)
+            .await
+    }
+
+    /// Streaming variant of [`ToolManager::call_tool_with_timeout`]
+    pub async fn call_streaming_with_timeout(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: JsonObject,
+        timeout: Duration,
+    ) -> McpResult<ToolCallStream> {
+        let connection = self.prepare_call(server_name, tool_name, &args).await?;
+
+        // Subscribe before sending so no early progress update is missed
+        let mut notifications = self
+            .connection_manager
+            .subscribe_notifications(&connection.id)
+            .await?;
+
+        let call_id = self.generate_call_id();
+        let call_info = CallInfo::new(&call_id, server_name, tool_name, args.clone())
+            .with_progress_token(&call_id);
+        self.register_call(call_info.clone()).await;
+
+        let request = McpRequest::with_params(
+            serde_json::json!(call_id.clone()),
+            "tools/call",
+            serde_json::json!({
+                "name": tool_name,
+                "arguments": args,
+                "_meta": { "progressToken": call_id }
+            }),
+        );
+
+        let (tx, rx) = mpsc::channel(32);
+        let connection_manager = self.connection_manager.clone();
+        let pending_calls = self.pending_calls.clone();
+        let info = call_info.clone();
+
+        tokio::spawn(async move {
+            let to_event = |notification: McpNotification| {
+                ToolCallEvent::Progress(Notification {
+                    notification_type: NotificationType::Progress,
+                    server_name: info.server_name.clone(),
+                    timestamp: Utc::now(),
+                    method: notification.method,
+                    params: notification.params,
+                })
+            };
+
+            let send = connection_manager.send_with_timeout(&connection.id, request, timeout);
+            tokio::pin!(send);
+
+            let result = loop {
+                tokio::select! {
+                    biased;
+                    Some(notification) = notifications.recv() => {
+                        if info.matches_notification(&notification) {
+                            let _ = tx.send(to_event(notification)).await;
+                        }
+                    }
+                    result = &mut send => break result,
+                }
+            };
+
+            // Deliver progress that arrived alongside the response
+            while let Ok(notification) = notifications.try_recv() {
+                if info.matches_notification(&notification) {
+                    let _ = tx.send(to_event(notification)).await;
+                }
+            }
+
+            pending_calls.write().await.remove(&info.call_id);
+
+            let result = result
+                .and_then(|response| response.into_result())
+                .and_then(convert_result);
+            let _ = tx.send(ToolCallEvent::Result(result)).await;
+        });
+
+        Ok(ToolCallStream {
+            call_info,
+            inner: Box::pin(ReceiverStream::new(rx)),
+        })
     }
 }
 
@@ -554,7 +807,11 @@ impl<C: ConnectionManager + 'static> ToolManager for McpToolManager<C> {
         match server_name {
             Some(name) => {
                 // Check cache first
-                {
+                if let Some(capability_cache) = &self.capability_cache {
+                    if let Some(tools) = capability_cache.fresh_tools(name, self.cache_ttl) {
+                        return Ok(tools);
+                    }
+                } else {
                     let cache = self.tool_cache.read().await;
                     if let Some(entry) = cache.get(name) {
                         if self.is_cache_valid(entry) {
@@ -565,6 +822,9 @@ impl<C: ConnectionManager + 'static> ToolManager for McpToolManager<C> {
 
                 // Fetch from server
                 let tools = self.fetch_tools_from_server(name).await?;
+                if let Some(capability_cache) = &self.capability_cache {
+                    capability_cache.store_tools(name, tools.clone());
+                }
 
                 // Update cache
                 {
@@ -609,6 +869,13 @@ impl<C: ConnectionManager + 'static> ToolManager for McpToolManager<C> {
     }
 
     fn clear_cache(&self, server_name: Option<&str>) {
+        if let Some(capability_cache) = &self.capability_cache {
+            match server_name {
+                Some(name) => capability_cache.invalidate(name),
+                None => capability_cache.clear(),
+            }
+        }
+
         // Convert to owned string for async move
         let server_name_owned = server_name.map(|s| s.to_string());
         let cache = self.tool_cache.clone();
@@ -642,37 +909,7 @@ impl<C: ConnectionManager + 'static> ToolManager for McpToolManager<C> {
         args: JsonObject,
         timeout: Duration,
     ) -> McpResult<ToolCallResult> {
-        // Get the tool definition for validation
-        let tool = self
-            .get_tool(server_name, tool_name)
-            .await?
-            .ok_or_else(|| {
-                McpError::tool(
-                    format!("Tool not found: {}/{}", server_name, tool_name),
-                    Some(tool_name.to_string()),
-                )
-            })?;
-
-        // Validate arguments
-        let validation = self.validate_args(&tool, &args);
-        if !validation.valid {
-            return Err(McpError::validation(
-                format!(
-                    "Invalid arguments for tool {}: {}",
-                    tool_name,
-                    validation.errors.join(", ")
-                ),
-                validation.errors,
-            ));
-        }
-
-        // Get connection
-        let connection = self
-            .connection_manager
-            .get_connection_by_server(server_name)
-            .ok_or_else(|| {
-                McpError::connection(format!("No connection found for server: {}", server_name))
-            })?;
+        let connection = self.prepare_call(server_name, tool_name, &args).await?;
 
         // Generate call ID and register
         let call_id = self.generate_call_id();
@@ -702,7 +939,7 @@ impl<C: ConnectionManager + 'static> ToolManager for McpToolManager<C> {
         match result {
             Ok(response) => {
                 let result_value = response.into_result()?;
-                self.convert_result(result_value)
+                convert_result(result_value)
             }
             Err(e) => Err(e),
         }
@@ -896,6 +1133,34 @@ mod tests {
         assert_eq!(result.first_text(), Some("Something went wrong"));
     }
 
+    #[test]
+    fn test_convert_result_tool_error() {
+        let result = convert_result(serde_json::json!({
+            "content": [{ "type": "text", "text": "file not found" }],
+            "isError": true
+        }))
+        .unwrap();
+        assert!(result.is_error);
+        assert_eq!(result.first_text(), Some("file not found"));
+    }
+
+    #[test]
+    fn test_convert_result_accepts_non_boolean_is_error() {
+        let convert = |is_error: serde_json::Value| {
+            convert_result(serde_json::json!({
+                "content": [{ "type": "text", "text": "out" }],
+                "isError": is_error
+            }))
+            .unwrap()
+            .is_error
+        };
+        assert!(convert(serde_json::json!("true")));
+        assert!(convert(serde_json::json!(1)));
+        assert!(!convert(serde_json::json!("false")));
+        assert!(!convert(serde_json::json!(0)));
+        assert!(!convert(serde_json::json!({ "unexpected": true })));
+    }
+
     #[test]
     fn test_arg_validation_result_valid() {
         let result = ArgValidationResult::valid();
@@ -966,4 +1231,185 @@ mod tests {
         assert!(!types_compatible("string", "number"));
         assert!(!types_compatible("integer", "number"));
     }
+
+    /// Connection manager backed by a scripted transport: every `tools/call`
+    /// emits progress notifications on the subscribed channel, then responds.
+    struct MockConnectionManager {
+        notifications: std::sync::Mutex<Option<mpsc::Sender<McpNotification>>>,
+    }
+
+    impl MockConnectionManager {
+        fn new() -> Self {
+            Self {
+                notifications: std::sync::Mutex::new(None),
+            }
+        }
+
+        fn connection() -> McpConnection {
+            McpConnection::new(
+                "conn-1".to_string(),
+                "server".to_string(),
+                crate::mcp::types::TransportType::Stdio,
+            )
+        }
+
+        fn progress(token: &serde_json::Value, progress: u64) -> McpNotification {
+            McpNotification::with_params(
+                "notifications/progress",
+                serde_json::json!({ "progressToken": token, "progress": progress, "total": 2 }),
+            )
+        }
+    }
+
+    #[async_trait]
+    impl ConnectionManager for MockConnectionManager {
+        async fn connect(
+            &self,
+            _server: crate::mcp::types::McpServerInfo,
+        ) -> McpResult<McpConnection> {
+            Ok(Self::connection())
+        }
+
+        async fn disconnect(&self, _connection_id: &str) -> McpResult<()> {
+            Ok(())
+        }
+
+        async fn disconnect_all(&self) -> McpResult<()> {
+            Ok(())
+        }
+
+        async fn send(
+            &self,
+            connection_id: &str,
+            request: McpRequest,
+        ) -> McpResult<crate::mcp::transport::McpResponse> {
+            self.send_with_timeout(connection_id, request, Duration::from_secs(1))
+                .await
+        }
+
+        async fn send_with_timeout(
+            &self,
+            _connection_id: &str,
+            request: McpRequest,
+            _timeout: Duration,
+        ) -> McpResult<crate::mcp::transport::McpResponse> {
+            use crate::mcp::transport::McpResponse;
+
+            if request.method == "tools/list" {
+                return Ok(McpResponse::success(
+                    request.id,
+                    serde_json::json!({ "tools": [{ "name": "slow_tool" }] }),
+                ));
+            }
+
+            let token = request.params.as_ref().unwrap()["_meta"]["progressToken"].clone();
+            let tx = self.notifications.lock().unwrap().clone().unwrap();
+            tx.send(Self::progress(&serde_json::json!("other-call"), 1))
+                .await
+                .unwrap();
+            tx.send(Self::progress(&token, 1)).await.unwrap();
+            tx.send(Self::progress(&token, 2)).await.unwrap();
+
+            Ok(McpResponse::success(
+                request.id,
+                serde_json::json!({ "content": [{ "type": "text", "text": "done" }] }),
+            ))
+        }
+
+        async fn send_with_retry(
+            &self,
+            connection_id: &str,
+            request: McpRequest,
+        ) -> McpResult<crate::mcp::transport::McpResponse> {
+            self.send(connection_id, request).await
+        }
+
+        async fn cancel_request(&self, _connection_id: &str, _request_id: &str) -> McpResult<()> {
+            Ok(())
+        }
+
+        async fn send_notification(
+            &self,
+            _connection_id: &str,
+            _notification: McpNotification,
+        ) -> McpResult<()> {
+            Ok(())
+        }
+
+        fn serve_roots(&self, _roots: crate::mcp::roots::SharedRoots) {}
+
+        async fn subscribe_notifications(
+            &self,
+            _connection_id: &str,
+        ) -> McpResult<mpsc::Receiver<McpNotification>> {
+            let (tx, rx) = mpsc::channel(16);
+            *self.notifications.lock().unwrap() = Some(tx);
+            Ok(rx)
+        }
+
+        fn get_connection(&self, _id: &str) -> Option<McpConnection> {
+            Some(Self::connection())
+        }
+
+        fn get_connection_by_server(&self, _server_name: &str) -> Option<McpConnection> {
+            Some(Self::connection())
+        }
+
+        fn get_all_connections(&self) -> Vec<McpConnection> {
+            vec![Self::connection()]
+        }
+
+        fn subscribe(&self) -> mpsc::Receiver<crate::mcp::connection_manager::ConnectionEvent> {
+            mpsc::channel(1).1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_streaming_yields_progress_then_result() {
+        use futures::StreamExt;
+
+        let manager = McpToolManager::new(Arc::new(MockConnectionManager::new()));
+        let stream = manager
+            .call_streaming("server", "slow_tool", serde_json::Map::new())
+            .await
+            .unwrap();
+        let call_id = stream.call_id().to_string();
+        assert_eq!(stream.call_info.progress_token.as_deref(), Some(&*call_id));
+
+        let events: Vec<ToolCallEvent> = stream.collect().await;
+        assert_eq!(events.len(), 3);
+
+        for (event, expected) in events[..2].iter().zip([1, 2]) {
+            match event {
+                ToolCallEvent::Progress(notification) => {
+                    let params = notification.params.as_ref().unwrap();
+                    assert_eq!(params["progressToken"], serde_json::json!(call_id));
+                    assert_eq!(params["progress"], serde_json::json!(expected));
+                    assert_eq!(notification.server_name, "server");
+                }
+                other => panic!("expected progress, got {:?}", other),
+            }
+        }
+        match &events[2] {
+            ToolCallEvent::Result(Ok(result)) => assert_eq!(result.first_text(), Some("done")),
+            other => panic!("expected result, got {:?}", other),
+        }
+        assert!(manager.get_pending_calls().is_empty());
+    }
+
+    #[test]
+    fn test_call_info_matches_progress_notification() {
+        let info = CallInfo::new("call-1", "server", "tool", serde_json::Map::new())
+            .with_progress_token("call-1");
+        let matching = McpNotification::with_params(
+            "notifications/progress",
+            serde_json::json!({ "progressToken": "call-1", "progress": 1 }),
+        );
+        let other = McpNotification::with_params(
+            "notifications/progress",
+            serde_json::json!({ "progressToken": "call-2", "progress": 1 }),
+        );
+        assert!(info.matches_notification(&matching));
+        assert!(!info.matches_notification(&other));
+    }
 }
@@ -187,7 +187,7 @@ proptest! {
             "isError": false
         });
 
-        let result = convert_result_standalone(mcp_result);
+        let result = convert_result(mcp_result);
         prop_assert!(result.is_ok(), "Conversion should succeed");
 
         let tool_result = result.unwrap();
@@ -216,7 +216,7 @@ proptest! {
             "isError": is_error
         });
 
-        let result = convert_result_standalone(mcp_result);
+        let result = convert_result(mcp_result);
         prop_assert!(result.is_ok(), "Conversion should succeed");
 
         let tool_result = result.unwrap();
@@ -378,33 +378,6 @@ fn types_compatible_standalone(expected: &str, actual: &str) -> bool {
     false
 }
 
-/// Standalone result conversion (mirrors McpToolManager::convert_result)
-fn convert_result_standalone(result: serde_json::Value) -> Result<ToolCallResult, String> {
-    // Check if result has content array
-    if let Some(content) = result.get("content") {
-        let content_items: Vec<ToolResultContent> = serde_json::from_value(content.clone())
-            .map_err(|e| format!("Failed to parse tool result content: {}", e))?;
-
-        let is_error = result
-            .get("isError")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-
-        return Ok(ToolCallResult {
-            content: content_items,
-            is_error,
-        });
-    }
-
-    // Handle legacy format or simple text response
-    if let Some(text) = result.as_str() {
-        return Ok(ToolCallResult::success_text(text));
-    }
-
-    // Return the raw result as JSON text
-    Ok(ToolCallResult::success_text(result.to_string()))
-}
-
 // ============================================================================
 // Additional Unit Tests
 // ============================================================================
@@ -493,7 +466,7 @@ mod unit_tests {
 
     #[test]
     fn test_convert_result_legacy_string() {
-        let result = convert_result_standalone(serde_json::json!("simple text"));
+        let result = convert_result(serde_json::json!("simple text"));
         assert!(result.is_ok());
 
         let tool_result = result.unwrap();
@@ -502,7 +475,7 @@ mod unit_tests {
 
     #[test]
     fn test_convert_result_json_object() {
-        let result = convert_result_standalone(serde_json::json!({"key": "value"}));
+        let result = convert_result(serde_json::json!({"key": "value"}));
         assert!(result.is_ok());
 
         let tool_result = result.unwrap();
@@ -216,6 +216,34 @@ impl McpMessage {
     }
 }
 
+/// Connection pool settings for HTTP-based transports
+///
+/// The HTTP client is built once per transport and reused for every request,
+/// so idle connections stay open and avoid repeated TCP/TLS handshakes.
+/// Set per server through the `pool` field of `McpServerConfig`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpPoolConfig {
+    /// How long an idle pooled connection is kept alive, in milliseconds (`None` keeps it indefinitely)
+    #[serde(with = "crate::mcp::types::option_duration_millis")]
+    pub idle_timeout: Option<Duration>,
+    /// Maximum idle connections kept per host
+    pub max_idle_per_host: usize,
+    /// TCP keep-alive interval, in milliseconds (`None` disables TCP keep-alive)
+    #[serde(with = "crate::mcp::types::option_duration_millis")]
+    pub tcp_keepalive: Option<Duration>,
+}
+
+impl Default for HttpPoolConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Some(Duration::from_secs(90)),
+            max_idle_per_host: 16,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
 /// Transport configuration for different transport types
 #[derive(Debug, Clone)]
 pub enum TransportConfig {
@@ -236,6 +264,8 @@ pub enum TransportConfig {
         url: String,
         /// HTTP headers
         headers: HashMap<String, String>,
+        /// Connection pool settings
+        pool: HttpPoolConfig,
     },
     /// SSE transport configuration
     Sse {
@@ -243,6 +273,8 @@ pub enum TransportConfig {
         url: String,
         /// HTTP headers
         headers: HashMap<String, String>,
+        /// Connection pool settings
+        pool: HttpPoolConfig,
     },
     /// WebSocket transport configuration
     WebSocket {
@@ -348,10 +380,43 @@ pub trait Transport: Send + Sync {
     }
 }
 
+/// Fan-out of transport events to every subscriber
+///
+/// `subscribe` adds a receiver instead of replacing the previous one, so a
+/// new subscriber never takes events away from an existing consumer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EventSubscribers {
+    senders: Arc<std::sync::Mutex<Vec<mpsc::Sender<TransportEvent>>>>,
+}
+
+impl EventSubscribers {
+    /// Add a subscriber
+    pub(crate) fn subscribe(&self) -> mpsc::Receiver<TransportEvent> {
+        let (tx, rx) = mpsc::channel(100);
+        self.senders.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Send an event to every live subscriber, dropping closed ones
+    pub(crate) async fn emit(&self, event: TransportEvent) {
+        let senders = {
+            let mut senders = self.senders.lock().unwrap();
+            senders.retain(|tx| !tx.is_closed());
+            senders.clone()
+        };
+        for tx in senders {
+            let _ = tx.send(event.clone()).await;
+        }
+    }
+}
+
 /// Boxed transport type for dynamic dispatch
 pub type BoxedTransport = Box<dyn Transport>;
 
 /// Arc-wrapped transport for shared ownership
+///
+/// Clones share a single transport, so HTTP transports also share one
+/// connection pool across all holders.
 pub type SharedTransport = Arc<tokio::sync::Mutex<BoxedTransport>>;
 
 /// Transport factory for creating transports from configuration
@@ -383,18 +448,18 @@ impl TransportFactory {
                     options,
                 )))
             }
-            TransportConfig::Http { url, headers } => {
+            TransportConfig::Http { url, headers, pool } => {
                 use super::http::{HttpConfig, HttpTransport};
                 Ok(Box::new(HttpTransport::new(
-                    HttpConfig { url, headers },
+                    HttpConfig { url, headers, pool },
                     options,
                 )))
             }
-            TransportConfig::Sse { url, headers } => {
+            TransportConfig::Sse { url, headers, pool } => {
                 // SSE uses HTTP transport with streaming
                 use super::http::{HttpConfig, HttpTransport};
                 Ok(Box::new(HttpTransport::new(
-                    HttpConfig { url, headers },
+                    HttpConfig { url, headers, pool },
                     options,
                 )))
             }
@@ -491,6 +556,7 @@ mod tests {
         let http = TransportConfig::Http {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         assert_eq!(http.transport_type(), TransportType::Http);
 
@@ -543,4 +609,28 @@ mod tests {
         );
         assert!(error_with_data.data.is_some());
     }
+
+    #[tokio::test]
+    async fn test_event_subscribers_do_not_steal_events() {
+        let subscribers = EventSubscribers::default();
+        let mut first = subscribers.subscribe();
+        let mut second = subscribers.subscribe();
+
+        subscribers.emit(TransportEvent::Connected).await;
+        assert!(matches!(
+            first.recv().await,
+            Some(TransportEvent::Connected)
+        ));
+        assert!(matches!(
+            second.recv().await,
+            Some(TransportEvent::Connected)
+        ));
+
+        drop(first);
+        subscribers.emit(TransportEvent::Connecting).await;
+        assert!(matches!(
+            second.recv().await,
+            Some(TransportEvent::Connecting)
+        ));
+    }
 }
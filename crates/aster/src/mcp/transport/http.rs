@@ -7,17 +7,25 @@
 //!
 //! Messages are sent as JSON-RPC 2.0 format in HTTP POST request bodies.
 //! Responses are received as JSON-RPC 2.0 format in HTTP response bodies.
+//!
+//! # Connection Pooling
+//!
+//! The underlying `reqwest::Client` is created on the first `connect` and
+//! kept for the lifetime of the transport, so consecutive requests reuse
+//! pooled keep-alive connections. Pool behaviour is tuned via
+//! [`HttpPoolConfig`].
 
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{mpsc, RwLock};
 
 use crate::mcp::error::{McpError, McpResult};
 use crate::mcp::transport::{
-    McpMessage, McpRequest, McpResponse, Transport, TransportConfig, TransportEvent, TransportState,
+    EventSubscribers, HttpPoolConfig, McpMessage, McpRequest, McpResponse, Transport,
+    TransportConfig, TransportEvent, TransportState,
 };
 use crate::mcp::types::{ConnectionOptions, TransportType};
 
@@ -28,6 +36,8 @@ pub struct HttpConfig {
     pub url: String,
     /// HTTP headers
     pub headers: HashMap<String, String>,
+    /// Connection pool settings
+    pub pool: HttpPoolConfig,
 }
 
 /// HTTP transport for MCP communication
@@ -45,7 +55,7 @@ pub struct HttpTransport {
     /// HTTP client
     client: Option<reqwest::Client>,
     /// Event channel sender
-    event_tx: Arc<Mutex<Option<mpsc::Sender<TransportEvent>>>>,
+    event_tx: EventSubscribers,
     /// Request ID counter
     request_counter: AtomicU64,
 }
@@ -58,7 +68,7 @@ impl HttpTransport {
             options,
             state: Arc::new(RwLock::new(TransportState::Disconnected)),
             client: None,
-            event_tx: Arc::new(Mutex::new(None)),
+            event_tx: EventSubscribers::default(),
             request_counter: AtomicU64::new(1),
         }
     }
@@ -66,8 +76,9 @@ impl HttpTransport {
     /// Create from transport config
     pub fn from_config(config: TransportConfig, options: ConnectionOptions) -> McpResult<Self> {
         match config {
-            TransportConfig::Http { url, headers } | TransportConfig::Sse { url, headers } => {
-                Ok(Self::new(HttpConfig { url, headers }, options))
+            TransportConfig::Http { url, headers, pool }
+            | TransportConfig::Sse { url, headers, pool } => {
+                Ok(Self::new(HttpConfig { url, headers, pool }, options))
             }
             _ => Err(McpError::config("Expected HTTP transport configuration")),
         }
@@ -87,9 +98,7 @@ impl HttpTransport {
 
     /// Emit a transport event
     async fn emit_event(&self, event: TransportEvent) {
-        if let Some(tx) = self.event_tx.lock().await.as_ref() {
-            let _ = tx.send(event).await;
-        }
+        self.event_tx.emit(event).await;
     }
 }
 
@@ -110,6 +119,13 @@ impl Transport for HttpTransport {
         self.set_state(TransportState::Connecting).await;
         self.emit_event(TransportEvent::Connecting).await;
 
+        // Reuse an existing client so its pooled connections are kept
+        if self.client.is_some() {
+            self.set_state(TransportState::Connected).await;
+            self.emit_event(TransportEvent::Connected).await;
+            return Ok(());
+        }
+
         // Build HTTP client with headers
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
@@ -129,6 +145,9 @@ impl Transport for HttpTransport {
         let client = reqwest::Client::builder()
             .default_headers(headers)
             .timeout(self.options.timeout)
+            .pool_idle_timeout(self.config.pool.idle_timeout)
+            .pool_max_idle_per_host(self.config.pool.max_idle_per_host)
+            .tcp_keepalive(self.config.pool.tcp_keepalive)
             .build()
             .map_err(|e| McpError::transport_with_source("Failed to create HTTP client", e))?;
 
@@ -221,24 +240,21 @@ impl Transport for HttpTransport {
     }
 
     fn subscribe(&self) -> mpsc::Receiver<TransportEvent> {
-        let (tx, rx) = mpsc::channel(100);
-        let event_tx = self.event_tx.clone();
-        tokio::spawn(async move {
-            *event_tx.lock().await = Some(tx);
-        });
-        rx
+        self.event_tx.subscribe()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::sync::Mutex;
 
     #[test]
     fn test_http_config() {
         let config = HttpConfig {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         assert_eq!(config.url, "http://localhost:8080");
     }
@@ -248,6 +264,7 @@ mod tests {
         let config = HttpConfig {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         let transport = HttpTransport::new(config, ConnectionOptions::default());
         assert_eq!(transport.transport_type(), TransportType::Http);
@@ -259,6 +276,7 @@ mod tests {
         let config = TransportConfig::Http {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         let transport = HttpTransport::from_config(config, ConnectionOptions::default());
         assert!(transport.is_ok());
@@ -269,6 +287,7 @@ mod tests {
         let config = TransportConfig::Sse {
             url: "http://localhost:8080/sse".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         let transport = HttpTransport::from_config(config, ConnectionOptions::default());
         assert!(transport.is_ok());
@@ -291,6 +310,7 @@ mod tests {
         let config = HttpConfig {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         let transport = HttpTransport::new(config, ConnectionOptions::default());
 
@@ -307,6 +327,7 @@ mod tests {
         let config = HttpConfig {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         let mut transport = HttpTransport::new(config, ConnectionOptions::default());
 
@@ -321,6 +342,7 @@ mod tests {
         let config = HttpConfig {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         let mut transport = HttpTransport::new(config, ConnectionOptions::default());
 
@@ -337,6 +359,7 @@ mod tests {
         let config = HttpConfig {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
         };
         let mut transport = HttpTransport::new(config, ConnectionOptions::default());
 
@@ -344,4 +367,87 @@ mod tests {
         let result = transport.send(McpMessage::Request(request)).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_sequential_requests_reuse_pooled_connection() {
+        use crate::mcp::transport::SharedTransport;
+        use std::sync::atomic::AtomicUsize;
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = Arc::new(AtomicUsize::new(0));
+
+        // Minimal HTTP/1.1 keep-alive server answering every request with a
+        // JSON-RPC response
+        let server_accepted = accepted.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                server_accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let (read, mut write) = stream.into_split();
+                    let mut reader = BufReader::new(read);
+                    loop {
+                        let mut content_length = 0;
+                        loop {
+                            let mut line = String::new();
+                            if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                                return;
+                            }
+                            let line = line.trim_end();
+                            if line.is_empty() {
+                                break;
+                            }
+                            if let Some((name, value)) = line.split_once(':') {
+                                if name.eq_ignore_ascii_case("content-length") {
+                                    content_length = value.trim().parse().unwrap_or(0);
+                                }
+                            }
+                        }
+                        let mut body = vec![0u8; content_length];
+                        if reader.read_exact(&mut body).await.is_err() {
+                            return;
+                        }
+                        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        let reply = serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": request["id"],
+                            "result": {}
+                        })
+                        .to_string();
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                            reply.len(),
+                            reply
+                        );
+                        if write.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        let config = TransportConfig::Http {
+            url: format!("http://{}", addr),
+            headers: HashMap::new(),
+            pool: HttpPoolConfig::default(),
+        };
+        let transport = HttpTransport::from_config(config, ConnectionOptions::default()).unwrap();
+        let shared: SharedTransport = Arc::new(Mutex::new(Box::new(transport)));
+        shared.lock().await.connect().await.unwrap();
+
+        for i in 0..3 {
+            let handle = shared.clone();
+            let request = McpRequest::new(serde_json::json!(i), "ping");
+            let response = handle.lock().await.send_request(request).await.unwrap();
+            assert_eq!(response.id, serde_json::json!(i));
+        }
+
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
 }
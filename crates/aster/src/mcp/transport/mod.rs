@@ -20,10 +20,13 @@ pub mod http;
 pub mod stdio;
 pub mod websocket;
 
+pub(crate) use base::EventSubscribers;
+
 // Re-export base types
 pub use base::{
-    BoxedTransport, McpErrorData, McpMessage, McpNotification, McpRequest, McpResponse, RequestId,
-    SharedTransport, Transport, TransportConfig, TransportEvent, TransportFactory, TransportState,
+    BoxedTransport, HttpPoolConfig, McpErrorData, McpMessage, McpNotification, McpRequest,
+    McpResponse, RequestId, SharedTransport, Transport, TransportConfig, TransportEvent,
+    TransportFactory, TransportState,
 };
 
 // Re-export transport implementations
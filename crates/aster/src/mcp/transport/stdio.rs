@@ -20,8 +20,8 @@ use tokio::sync::{mpsc, oneshot, Mutex, RwLock};
 
 use crate::mcp::error::{McpError, McpResult};
 use crate::mcp::transport::{
-    McpMessage, McpNotification, McpRequest, McpResponse, Transport, TransportConfig,
-    TransportEvent, TransportState,
+    EventSubscribers, McpMessage, McpNotification, McpRequest, McpResponse, Transport,
+    TransportConfig, TransportEvent, TransportState,
 };
 use crate::mcp::types::{ConnectionOptions, TransportType};
 
@@ -49,7 +49,7 @@ pub struct StdioTransport {
     /// Pending requests waiting for responses
     pending_requests: Arc<Mutex<HashMap<String, PendingRequest>>>,
     /// Event subscribers
-    event_tx: Arc<Mutex<Option<mpsc::Sender<TransportEvent>>>>,
+    event_tx: EventSubscribers,
     /// Request ID counter
     request_counter: AtomicU64,
     /// Shutdown signal
@@ -79,7 +79,7 @@ impl StdioTransport {
             child: Arc::new(Mutex::new(None)),
             stdin_tx: Arc::new(Mutex::new(None)),
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
-            event_tx: Arc::new(Mutex::new(None)),
+            event_tx: EventSubscribers::default(),
             request_counter: AtomicU64::new(1),
             shutdown_tx: Arc::new(Mutex::new(None)),
         }
@@ -120,18 +120,27 @@ impl StdioTransport {
 
     /// Emit a transport event
     async fn emit_event(&self, event: TransportEvent) {
-        if let Some(tx) = self.event_tx.lock().await.as_ref() {
-            let _ = tx.send(event).await;
-        }
+        self.event_tx.emit(event).await;
     }
 
     /// Handle incoming message from stdout
     async fn handle_message(
         message: &str,
         pending_requests: &Arc<Mutex<HashMap<String, PendingRequest>>>,
-        event_tx: &Arc<Mutex<Option<mpsc::Sender<TransportEvent>>>>,
+        event_tx: &EventSubscribers,
     ) {
-        // Try to parse as a response first
+        // Server-initiated requests carry both an id and a method; check them
+        // first since they would also parse as a response
+        if let Ok(request) = serde_json::from_str::<McpRequest>(message) {
+            event_tx
+                .emit(TransportEvent::MessageReceived(Box::new(
+                    McpMessage::Request(request),
+                )))
+                .await;
+            return;
+        }
+
+        // Try to parse as a response
         if let Ok(response) = serde_json::from_str::<McpResponse>(message) {
             let id_str = match &response.id {
                 serde_json::Value::String(s) => s.clone(),
@@ -148,25 +157,11 @@ impl StdioTransport {
 
         // Try to parse as a notification
         if let Ok(notification) = serde_json::from_str::<McpNotification>(message) {
-            if let Some(tx) = event_tx.lock().await.as_ref() {
-                let _ = tx
-                    .send(TransportEvent::MessageReceived(Box::new(
-                        McpMessage::Notification(notification),
-                    )))
-                    .await;
-            }
-            return;
-        }
-
-        // Try to parse as a request (server-initiated)
-        if let Ok(request) = serde_json::from_str::<McpRequest>(message) {
-            if let Some(tx) = event_tx.lock().await.as_ref() {
-                let _ = tx
-                    .send(TransportEvent::MessageReceived(Box::new(
-                        McpMessage::Request(request),
-                    )))
-                    .await;
-            }
+            event_tx
+                .emit(TransportEvent::MessageReceived(Box::new(
+                    McpMessage::Notification(notification),
+                )))
+                .await;
         }
     }
 
@@ -193,11 +188,9 @@ impl StdioTransport {
                                 // EOF - process exited
                                 let mut s = state.write().await;
                                 *s = TransportState::Disconnected;
-                                if let Some(tx) = event_tx.lock().await.as_ref() {
-                                    let _ = tx.send(TransportEvent::Disconnected {
+                                event_tx.emit(TransportEvent::Disconnected {
                                         reason: Some("Process exited".to_string()),
                                     }).await;
-                                }
                                 break;
                             }
                             Ok(_) => {
@@ -209,11 +202,9 @@ impl StdioTransport {
                             Err(e) => {
                                 let mut s = state.write().await;
                                 *s = TransportState::Error;
-                                if let Some(tx) = event_tx.lock().await.as_ref() {
-                                    let _ = tx.send(TransportEvent::Error {
+                                event_tx.emit(TransportEvent::Error {
                                         error: e.to_string(),
                                     }).await;
-                                }
                                 break;
                             }
                         }
@@ -241,25 +232,21 @@ impl StdioTransport {
                 if let Err(e) = stdin.write_all(data.as_bytes()).await {
                     let mut s = state.write().await;
                     *s = TransportState::Error;
-                    if let Some(tx) = event_tx.lock().await.as_ref() {
-                        let _ = tx
-                            .send(TransportEvent::Error {
-                                error: e.to_string(),
-                            })
-                            .await;
-                    }
+                    event_tx
+                        .emit(TransportEvent::Error {
+                            error: e.to_string(),
+                        })
+                        .await;
                     break;
                 }
                 if let Err(e) = stdin.flush().await {
                     let mut s = state.write().await;
                     *s = TransportState::Error;
-                    if let Some(tx) = event_tx.lock().await.as_ref() {
-                        let _ = tx
-                            .send(TransportEvent::Error {
-                                error: e.to_string(),
-                            })
-                            .await;
-                    }
+                    event_tx
+                        .emit(TransportEvent::Error {
+                            error: e.to_string(),
+                        })
+                        .await;
                     break;
                 }
             }
@@ -318,13 +305,11 @@ impl Transport for StdioTransport {
         // Create channels
         let (message_tx, message_rx) = mpsc::channel::<String>(self.options.queue_max_size);
         let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
-        let (event_tx, _event_rx) = mpsc::channel::<TransportEvent>(100);
 
         // Store handles
         *self.child.lock().await = Some(child);
         *self.stdin_tx.lock().await = Some(message_tx);
         *self.shutdown_tx.lock().await = Some(shutdown_tx);
-        *self.event_tx.lock().await = Some(event_tx);
 
         // Start reader and writer tasks
         self.start_reader_task(stdout, shutdown_rx);
@@ -450,14 +435,7 @@ impl Transport for StdioTransport {
     }
 
     fn subscribe(&self) -> mpsc::Receiver<TransportEvent> {
-        let (tx, rx) = mpsc::channel(100);
-        // Note: In a real implementation, we'd need to handle multiple subscribers
-        // For now, we just create a new channel
-        let event_tx = self.event_tx.clone();
-        tokio::spawn(async move {
-            *event_tx.lock().await = Some(tx);
-        });
-        rx
+        self.event_tx.subscribe()
     }
 }
 
@@ -538,6 +516,7 @@ mod tests {
         let config = TransportConfig::Http {
             url: "http://localhost:8080".to_string(),
             headers: HashMap::new(),
+            pool: Default::default(),
         };
         let transport = StdioTransport::from_config(config, ConnectionOptions::default());
         assert!(transport.is_err());
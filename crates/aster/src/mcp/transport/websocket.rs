@@ -23,8 +23,8 @@ use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
 use crate::mcp::error::{McpError, McpResult};
 use crate::mcp::transport::{
-    McpMessage, McpNotification, McpRequest, McpResponse, Transport, TransportConfig,
-    TransportEvent, TransportState,
+    EventSubscribers, McpMessage, McpNotification, McpRequest, McpResponse, Transport,
+    TransportConfig, TransportEvent, TransportState,
 };
 use crate::mcp::types::{ConnectionOptions, TransportType};
 
@@ -64,7 +64,7 @@ pub struct WebSocketTransport {
     /// Pending requests waiting for responses
     pending_requests: Arc<Mutex<HashMap<String, PendingRequest>>>,
     /// Event channel sender
-    event_tx: Arc<Mutex<Option<mpsc::Sender<TransportEvent>>>>,
+    event_tx: EventSubscribers,
     /// Request ID counter
     request_counter: AtomicU64,
     /// Shutdown signal
@@ -81,7 +81,7 @@ impl WebSocketTransport {
             writer: Arc::new(Mutex::new(None)),
             message_tx: Arc::new(Mutex::new(None)),
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
-            event_tx: Arc::new(Mutex::new(None)),
+            event_tx: EventSubscribers::default(),
             request_counter: AtomicU64::new(1),
             shutdown_tx: Arc::new(Mutex::new(None)),
         }
@@ -113,18 +113,27 @@ impl WebSocketTransport {
 
     /// Emit a transport event
     async fn emit_event(&self, event: TransportEvent) {
-        if let Some(tx) = self.event_tx.lock().await.as_ref() {
-            let _ = tx.send(event).await;
-        }
+        self.event_tx.emit(event).await;
     }
 
     /// Handle incoming message from WebSocket
     async fn handle_message(
         message: &str,
         pending_requests: &Arc<Mutex<HashMap<String, PendingRequest>>>,
-        event_tx: &Arc<Mutex<Option<mpsc::Sender<TransportEvent>>>>,
+        event_tx: &EventSubscribers,
     ) {
-        // Try to parse as a response first
+        // Server-initiated requests carry both an id and a method; check them
+        // first since they would also parse as a response
+        if let Ok(request) = serde_json::from_str::<McpRequest>(message) {
+            event_tx
+                .emit(TransportEvent::MessageReceived(Box::new(
+                    McpMessage::Request(request),
+                )))
+                .await;
+            return;
+        }
+
+        // Try to parse as a response
         if let Ok(response) = serde_json::from_str::<McpResponse>(message) {
             let id_str = match &response.id {
                 serde_json::Value::String(s) => s.clone(),
@@ -141,25 +150,11 @@ impl WebSocketTransport {
 
         // Try to parse as a notification
         if let Ok(notification) = serde_json::from_str::<McpNotification>(message) {
-            if let Some(tx) = event_tx.lock().await.as_ref() {
-                let _ = tx
-                    .send(TransportEvent::MessageReceived(Box::new(
-                        McpMessage::Notification(notification),
-                    )))
-                    .await;
-            }
-            return;
-        }
-
-        // Try to parse as a request (server-initiated)
-        if let Ok(request) = serde_json::from_str::<McpRequest>(message) {
-            if let Some(tx) = event_tx.lock().await.as_ref() {
-                let _ = tx
-                    .send(TransportEvent::MessageReceived(Box::new(
-                        McpMessage::Request(request),
-                    )))
-                    .await;
-            }
+            event_tx
+                .emit(TransportEvent::MessageReceived(Box::new(
+                    McpMessage::Notification(notification),
+                )))
+                .await;
         }
     }
 
@@ -180,11 +175,9 @@ impl WebSocketTransport {
                             Some(Ok(Message::Close(_))) => {
                                 let mut s = state.write().await;
                                 *s = TransportState::Disconnected;
-                                if let Some(tx) = event_tx.lock().await.as_ref() {
-                                    let _ = tx.send(TransportEvent::Disconnected {
+                                event_tx.emit(TransportEvent::Disconnected {
                                         reason: Some("WebSocket closed by server".to_string()),
                                     }).await;
-                                }
                                 break;
                             }
                             Some(Ok(Message::Ping(_))) | Some(Ok(Message::Pong(_))) => {
@@ -199,21 +192,17 @@ impl WebSocketTransport {
                             Some(Err(e)) => {
                                 let mut s = state.write().await;
                                 *s = TransportState::Error;
-                                if let Some(tx) = event_tx.lock().await.as_ref() {
-                                    let _ = tx.send(TransportEvent::Error {
+                                event_tx.emit(TransportEvent::Error {
                                         error: e.to_string(),
                                     }).await;
-                                }
                                 break;
                             }
                             None => {
                                 let mut s = state.write().await;
                                 *s = TransportState::Disconnected;
-                                if let Some(tx) = event_tx.lock().await.as_ref() {
-                                    let _ = tx.send(TransportEvent::Disconnected {
+                                event_tx.emit(TransportEvent::Disconnected {
                                         reason: Some("WebSocket stream ended".to_string()),
                                     }).await;
-                                }
                                 break;
                             }
                         }
@@ -236,13 +225,11 @@ impl WebSocketTransport {
                 if let Err(e) = writer.send(Message::Text(message.into())).await {
                     let mut s = state.write().await;
                     *s = TransportState::Error;
-                    if let Some(tx) = event_tx.lock().await.as_ref() {
-                        let _ = tx
-                            .send(TransportEvent::Error {
-                                error: e.to_string(),
-                            })
-                            .await;
-                    }
+                    event_tx
+                        .emit(TransportEvent::Error {
+                            error: e.to_string(),
+                        })
+                        .await;
                     break;
                 }
             }
@@ -292,13 +279,11 @@ impl Transport for WebSocketTransport {
         // Create channels
         let (message_tx, message_rx) = mpsc::channel::<String>(self.options.queue_max_size);
         let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
-        let (event_tx, _event_rx) = mpsc::channel::<TransportEvent>(100);
 
         // Store handles
         *self.writer.lock().await = Some(writer);
         *self.message_tx.lock().await = Some(message_tx);
         *self.shutdown_tx.lock().await = Some(shutdown_tx);
-        *self.event_tx.lock().await = Some(event_tx);
 
         // Start reader and writer tasks
         self.start_reader_task(reader, shutdown_rx);
@@ -419,12 +404,7 @@ impl Transport for WebSocketTransport {
     }
 
     fn subscribe(&self) -> mpsc::Receiver<TransportEvent> {
-        let (tx, rx) = mpsc::channel(100);
-        let event_tx = self.event_tx.clone();
-        tokio::spawn(async move {
-            *event_tx.lock().await = Some(tx);
-        });
-        rx
+        self.event_tx.subscribe()
     }
 }
 
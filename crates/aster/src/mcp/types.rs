@@ -9,6 +9,8 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::mcp::transport::HttpPoolConfig;
+
 // Re-export commonly used types from rmcp
 pub use rmcp::model::{JsonObject, ServerCapabilities};
 
@@ -141,6 +143,9 @@ pub struct ConnectionOptions {
     /// Message queue maximum size
     #[serde(default = "default_queue_max_size")]
     pub queue_max_size: usize,
+    /// Connection pool settings for HTTP-based transports
+    #[serde(default)]
+    pub pool: HttpPoolConfig,
 }
 
 fn default_timeout() -> Duration {
@@ -176,6 +181,7 @@ impl Default for ConnectionOptions {
             reconnect_delay_base: default_reconnect_delay_base(),
             reconnect_delay_max: default_reconnect_delay_max(),
             queue_max_size: default_queue_max_size(),
+            pool: HttpPoolConfig::default(),
         }
     }
 }
@@ -191,8 +197,11 @@ pub struct McpServerInfo {
     pub command: Option<String>,
     /// Command arguments (for stdio transport)
     pub args: Option<Vec<String>>,
-    /// Environment variables
+    /// Environment variables, merged over the inherited environment
     pub env: Option<HashMap<String, String>>,
+    /// Working directory (for stdio transport)
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
     /// URL for HTTP/SSE/WebSocket transports
     pub url: Option<String>,
     /// HTTP headers
@@ -221,6 +230,9 @@ pub struct McpConnection {
     pub capabilities: Option<ServerCapabilities>,
     /// Protocol version
     pub protocol_version: Option<String>,
+    /// Server version advertised in `serverInfo` during the handshake
+    #[serde(default)]
+    pub server_version: Option<String>,
 }
 
 impl McpConnection {
@@ -236,6 +248,7 @@ impl McpConnection {
             last_activity: now,
             capabilities: None,
             protocol_version: None,
+            server_version: None,
         }
     }
 
@@ -372,6 +385,9 @@ pub struct HealthCheckResult {
     pub last_check: DateTime<Utc>,
     /// Error message if unhealthy
     pub error: Option<String>,
+    /// Whether auto-restart was stopped after a crash loop
+    #[serde(default)]
+    pub quarantined: bool,
 }
 
 /// MCP server configuration
@@ -384,8 +400,11 @@ pub struct McpServerConfig {
     pub command: Option<String>,
     /// Command arguments
     pub args: Option<Vec<String>>,
-    /// Environment variables
+    /// Environment variables, merged over the inherited environment
     pub env: Option<HashMap<String, String>>,
+    /// Working directory for stdio servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
     /// URL for HTTP/SSE/WebSocket transports
     pub url: Option<String>,
     /// HTTP headers
@@ -405,6 +424,9 @@ pub struct McpServerConfig {
     /// Log level for this server (Requirements 8.5)
     #[serde(default)]
     pub log_level: McpLogLevel,
+    /// Connection pool settings for HTTP/SSE transports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<HttpPoolConfig>,
 }
 
 fn default_enabled() -> bool {
@@ -418,6 +440,7 @@ impl Default for McpServerConfig {
             command: None,
             args: None,
             env: None,
+            cwd: None,
             url: None,
             headers: None,
             enabled: default_enabled(),
@@ -425,6 +448,7 @@ impl Default for McpServerConfig {
             retries: default_max_retries(),
             auto_approve: Vec::new(),
             log_level: McpLogLevel::default(),
+            pool: None,
         }
     }
 }
@@ -540,6 +564,30 @@ mod humantime_serde {
     }
 }
 
+/// Serde helper module for optional Duration serialization (milliseconds, `null` for none)
+pub(crate) mod option_duration_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match duration {
+            Some(duration) => serializer.serialize_some(&(duration.as_millis() as u64)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = Option::<u64>::deserialize(deserializer)?;
+        Ok(millis.map(Duration::from_millis))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,3 +179,67 @@ pub fn estimate_image_dimensions(_buffer: &[u8], file_size: u64) -> ImageDimensi
         display_height: Some(size),
     }
 }
+
+/// 缩放结果
+#[derive(Debug, Clone)]
+pub struct ResizedImage {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+    /// 是否实际进行了缩放
+    pub resized: bool,
+}
+
+/// 将图片等比缩放到不超过 `max_dimension` 的边长
+///
+/// 尺寸已在限制内时原样返回；JPEG 重新编码为 JPEG，其余格式输出 PNG
+pub fn resize_image_to_fit(buffer: &[u8], max_dimension: u32) -> Result<ResizedImage, String> {
+    use ::image::GenericImageView;
+
+    let format =
+        ::image::guess_format(buffer).map_err(|e| format!("Unrecognized image format: {}", e))?;
+    let img = ::image::load_from_memory_with_format(buffer, format)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let (original_width, original_height) = img.dimensions();
+
+    let mime_type = get_mime_type_sync(buffer).unwrap_or("image/png");
+    if original_width <= max_dimension && original_height <= max_dimension {
+        return Ok(ResizedImage {
+            data: buffer.to_vec(),
+            mime_type: mime_type.to_string(),
+            width: original_width,
+            height: original_height,
+            original_width,
+            original_height,
+            resized: false,
+        });
+    }
+
+    let resized = img.resize(
+        max_dimension,
+        max_dimension,
+        ::image::imageops::FilterType::Lanczos3,
+    );
+    let (output_format, output_mime) = match format {
+        ::image::ImageFormat::Jpeg => (::image::ImageFormat::Jpeg, "image/jpeg"),
+        _ => (::image::ImageFormat::Png, "image/png"),
+    };
+
+    let mut data = Vec::new();
+    resized
+        .write_to(&mut std::io::Cursor::new(&mut data), output_format)
+        .map_err(|e| format!("Failed to encode resized image: {}", e))?;
+
+    Ok(ResizedImage {
+        data,
+        mime_type: output_mime.to_string(),
+        width: resized.width(),
+        height: resized.height(),
+        original_width,
+        original_height,
+        resized: true,
+    })
+}
@@ -91,4 +91,4 @@ pub fn is_blacklisted_file(file_path: &Path) -> bool {
 }
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
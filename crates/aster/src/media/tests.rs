@@ -236,3 +236,44 @@ fn test_is_blacklisted_file() {
     assert!(!is_blacklisted_file(Path::new("code.rs")));
     assert!(!is_blacklisted_file(Path::new("image.png")));
 }
+
+// ============ Resize Tests ============
+
+/// 生成纯色 PNG 测试图片
+pub(crate) fn encode_test_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ::image::RgbImage::from_pixel(width, height, ::image::Rgb([10, 20, 30]));
+    let mut data = Vec::new();
+    ::image::DynamicImage::ImageRgb8(img)
+        .write_to(
+            &mut std::io::Cursor::new(&mut data),
+            ::image::ImageFormat::Png,
+        )
+        .unwrap();
+    data
+}
+
+#[test]
+fn test_resize_image_to_fit_downscales() {
+    let png = encode_test_png(800, 400);
+    let resized = resize_image_to_fit(&png, 200).unwrap();
+    assert!(resized.resized);
+    assert_eq!((resized.width, resized.height), (200, 100));
+    assert_eq!(
+        (resized.original_width, resized.original_height),
+        (800, 400)
+    );
+    assert_eq!(resized.mime_type, "image/png");
+}
+
+#[test]
+fn test_resize_image_to_fit_keeps_small_image() {
+    let png = encode_test_png(50, 40);
+    let resized = resize_image_to_fit(&png, 200).unwrap();
+    assert!(!resized.resized);
+    assert_eq!(resized.data, png);
+}
+
+#[test]
+fn test_resize_image_to_fit_rejects_garbage() {
+    assert!(resize_image_to_fit(b"not an image", 200).is_err());
+}
@@ -10,6 +10,7 @@ use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 
+use super::decay::{apply_decay, record_access};
 use super::types::{
     ChatMemoryStats, ChatMemoryStore, ConversationSummary, MemoryHierarchyConfig, MemoryImportance,
     Timestamp,
@@ -151,6 +152,48 @@ impl ChatMemory {
         results.into_iter().take(limit).map(|(s, _)| s).collect()
     }
 
+    /// 召回对话
+    ///
+    /// 与 `search` 相同，但会记录召回时间和次数，用于重要性衰减与强化。
+    pub fn recall(&mut self, query: &str, limit: Option<usize>) -> Vec<ConversationSummary> {
+        let ids: Vec<String> = self
+            .search(query, limit)
+            .into_iter()
+            .map(|s| s.id.clone())
+            .collect();
+        if ids.is_empty() {
+            return Vec::new();
+        }
+
+        let current_time = Utc::now();
+        let decay_config = self.config.importance_decay.as_ref();
+        for summary in self.store.summaries.iter_mut() {
+            if ids.contains(&summary.id) {
+                record_access(summary, decay_config, current_time);
+            }
+        }
+        self.save();
+
+        ids.iter()
+            .filter_map(|id| self.get_by_id(id).cloned())
+            .collect()
+    }
+
+    /// 执行一次重要性衰减，返回被降级的摘要数量
+    ///
+    /// 未配置 `importance_decay` 时不做任何处理，应用可定期调用。
+    pub fn decay_tick(&mut self) -> usize {
+        let Some(config) = self.config.importance_decay.as_ref() else {
+            return 0;
+        };
+
+        let decayed = apply_decay(&mut self.store.summaries, config, Utc::now());
+        if decayed > 0 {
+            self.save();
+        }
+        decayed
+    }
+
     /// 按话题搜索
     pub fn search_by_topic(&self, topic: &str, limit: Option<usize>) -> Vec<&ConversationSummary> {
         let limit = limit.unwrap_or(10);
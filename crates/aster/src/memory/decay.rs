@@ -0,0 +1,82 @@
+//! 记忆重要性衰减
+//!
+//! 长时间未被召回的记忆逐级降低重要性，使压缩时优先淘汰它们；
+//! 经常被召回的记忆则会被强化。
+
+use chrono::{DateTime, Utc};
+
+use super::types::{ConversationSummary, ImportanceDecayConfig, MemoryImportance};
+
+/// 解析时间戳
+fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// 衰减计时的起点：最后召回、最后衰减和对话结束时间中最晚的一个
+fn decay_reference(summary: &ConversationSummary) -> Option<DateTime<Utc>> {
+    [
+        summary.access.last_accessed.as_deref(),
+        summary.access.last_decayed.as_deref(),
+        Some(summary.end_time.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(parse_timestamp)
+    .max()
+}
+
+/// 对所有摘要执行一次衰减，返回被降级的数量
+///
+/// 核心记忆不会衰减；`decay_after_days` 为 0 时不做任何处理。
+pub fn apply_decay(
+    summaries: &mut [ConversationSummary],
+    config: &ImportanceDecayConfig,
+    now: DateTime<Utc>,
+) -> usize {
+    if config.decay_after_days == 0 {
+        return 0;
+    }
+
+    let mut decayed = 0;
+    for summary in summaries.iter_mut() {
+        if summary.importance == MemoryImportance::Core
+            || summary.importance <= config.min_importance
+        {
+            continue;
+        }
+        let Some(reference) = decay_reference(summary) else {
+            continue;
+        };
+        if (now - reference).num_days() < config.decay_after_days as i64 {
+            continue;
+        }
+
+        summary.importance = summary.importance.decayed().max(config.min_importance);
+        summary.access.last_decayed = Some(now.to_rfc3339());
+        decayed += 1;
+    }
+    decayed
+}
+
+/// 记录一次召回，达到强化次数时提升重要性
+pub fn record_access(
+    summary: &mut ConversationSummary,
+    config: Option<&ImportanceDecayConfig>,
+    now: DateTime<Utc>,
+) {
+    summary.access.last_accessed = Some(now.to_rfc3339());
+    summary.access.access_count += 1;
+
+    if let Some(config) = config {
+        if config.reinforce_every > 0
+            && summary
+                .access
+                .access_count
+                .is_multiple_of(config.reinforce_every)
+        {
+            summary.importance = summary.importance.reinforced();
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! 简单记忆管理器
 //!
-//! 持久化存储用户偏好和项目上下文
+//! 持久化存储用户偏好和项目上下文，并根据对话信号逐步更新用户画像
 
 use std::collections::HashMap;
 use std::fs;
@@ -8,7 +8,11 @@ use std::path::{Path, PathBuf};
 
 use chrono::Utc;
 
-use super::types::{MemoryEntry, MemoryScope, SimpleMemoryStore, Timestamp};
+use super::profile_inference::{ProfileChange, ProfileInference};
+use super::types::{
+    ForgetQuery, ForgetResult, MemoryEntry, MemoryLink, MemoryScope, SimpleMemoryStore, Timestamp,
+    UserProfile,
+};
 
 const MEMORY_VERSION: &str = "1.0.0";
 
@@ -23,6 +27,8 @@ pub struct MemoryManager {
     project_store_path: PathBuf,
     global_store: SimpleMemoryStore,
     project_store: SimpleMemoryStore,
+    profile_path: PathBuf,
+    profile: ProfileInference,
 }
 
 impl MemoryManager {
@@ -32,7 +38,12 @@ impl MemoryManager {
             .unwrap_or_default()
             .join(".aster")
             .join("memory");
+        Self::with_global_dir(global_dir, project_dir)
+    }
 
+    /// 使用指定的全局记忆目录创建记忆管理器
+    pub fn with_global_dir(global_dir: impl Into<PathBuf>, project_dir: Option<&Path>) -> Self {
+        let global_dir = global_dir.into();
         let project_dir_path = project_dir
             .map(|p| p.join(".aster").join("memory"))
             .unwrap_or_else(|| {
@@ -48,14 +59,50 @@ impl MemoryManager {
         let global_store = Self::load_store(&global_store_path);
         let project_store = Self::load_store(&project_store_path);
 
+        // 用户画像跟随用户，保存在全局目录
+        let profile_path = global_dir.join("profile.json");
+        let profile = Self::load_profile(&profile_path);
+
         Self {
             global_store_path,
             project_store_path,
             global_store,
             project_store,
+            profile_path,
+            profile,
         }
     }
 
+    /// 当前用户画像
+    pub fn user_profile(&self) -> &UserProfile {
+        &self.profile.profile
+    }
+
+    /// 画像推断器，可通过 `inspect` 查看每个字段的推断依据
+    pub fn profile_inference(&self) -> &ProfileInference {
+        &self.profile
+    }
+
+    /// 观察一条用户消息，更新并保存画像，返回本次引起的变更
+    pub fn observe_user_message(&mut self, text: &str) -> Vec<ProfileChange> {
+        let changes = self.profile.observe_message(text);
+        self.save_profile();
+        changes
+    }
+
+    /// 观察对话话题，更新并保存画像
+    pub fn observe_topics(&mut self, topics: &[String]) -> Vec<ProfileChange> {
+        let changes = self.profile.observe_topics(topics);
+        self.save_profile();
+        changes
+    }
+
+    /// 手动编辑画像（如 `set_communication_style`、`unlock`），编辑后保存
+    pub fn edit_profile(&mut self, edit: impl FnOnce(&mut ProfileInference)) {
+        edit(&mut self.profile);
+        self.save_profile();
+    }
+
     /// 设置记忆值
     pub fn set(&mut self, key: &str, value: &str, scope: MemoryScope) {
         let (store, store_path) = match scope {
@@ -173,8 +220,137 @@ impl MemoryManager {
             .collect()
     }
 
+    /// 添加关联链接
+    pub fn add_link(&mut self, link: MemoryLink, scope: MemoryScope) {
+        let (store, store_path) = match scope {
+            MemoryScope::Global => (&mut self.global_store, &self.global_store_path),
+            MemoryScope::Project => (&mut self.project_store, &self.project_store_path),
+        };
+
+        store.links.retain(|l| l.id != link.id);
+        store.links.push(link);
+        Self::save_store(store_path, store);
+    }
+
+    /// 列出关联链接
+    pub fn links(&self, scope: Option<MemoryScope>) -> Vec<&MemoryLink> {
+        let mut links: Vec<&MemoryLink> = Vec::new();
+
+        if scope != Some(MemoryScope::Project) {
+            links.extend(self.global_store.links.iter());
+        }
+        if scope != Some(MemoryScope::Global) {
+            links.extend(self.project_store.links.iter());
+        }
+
+        links
+    }
+
+    /// 遗忘匹配的记忆
+    ///
+    /// 删除键或值匹配查询的条目，并清理引用它们的链接：
+    /// 链接失去所有关联条目时一并删除，否则只移除悬空的键；
+    /// 被删除链接的 ID 也会从其他链接的 `related_links` 中移除。
+    /// `dry_run` 时只返回将被删除的内容。
+    pub fn forget(&mut self, query: &ForgetQuery) -> ForgetResult {
+        let mut result = ForgetResult {
+            dry_run: query.dry_run,
+            ..Default::default()
+        };
+
+        let lower_query = query.query.trim().to_lowercase();
+        if lower_query.is_empty() {
+            return result;
+        }
+
+        for scope in [MemoryScope::Global, MemoryScope::Project] {
+            if query.scope.is_some_and(|s| s != scope) {
+                continue;
+            }
+
+            let (store, store_path) = match scope {
+                MemoryScope::Global => (&mut self.global_store, &self.global_store_path),
+                MemoryScope::Project => (&mut self.project_store, &self.project_store_path),
+            };
+
+            let mut preview;
+            let target = if query.dry_run {
+                preview = store.clone();
+                &mut preview
+            } else {
+                &mut *store
+            };
+
+            if Self::forget_in_store(target, &lower_query, &mut result) && !query.dry_run {
+                Self::save_store(store_path, store);
+            }
+        }
+
+        result
+    }
+
     // === 私有方法 ===
 
+    /// 在单个存储中执行遗忘，返回是否有变更
+    fn forget_in_store(
+        store: &mut SimpleMemoryStore,
+        lower_query: &str,
+        result: &mut ForgetResult,
+    ) -> bool {
+        let keys: Vec<String> = store
+            .entries
+            .values()
+            .filter(|e| {
+                e.key.to_lowercase().contains(lower_query)
+                    || e.value.to_lowercase().contains(lower_query)
+            })
+            .map(|e| e.key.clone())
+            .collect();
+        if keys.is_empty() {
+            return false;
+        }
+
+        let mut removed_entries: Vec<MemoryEntry> = keys
+            .iter()
+            .filter_map(|key| store.entries.remove(key))
+            .collect();
+        removed_entries.sort_by(|a, b| a.key.cmp(&b.key));
+        result.removed_entries.extend(removed_entries);
+
+        // 移除悬空的条目引用，失去所有条目的链接整体删除
+        let mut removed_link_ids = Vec::new();
+        let mut updated_link_ids = Vec::new();
+        store.links.retain_mut(|link| {
+            let before = link.memory_keys.len();
+            link.memory_keys.retain(|k| !keys.contains(k));
+            if link.memory_keys.len() == before {
+                true
+            } else if link.memory_keys.is_empty() {
+                removed_link_ids.push(link.id.clone());
+                result.removed_links.push(link.clone());
+                false
+            } else {
+                updated_link_ids.push(link.id.clone());
+                true
+            }
+        });
+
+        // 级联清理指向已删除链接的 related_links
+        if !removed_link_ids.is_empty() {
+            for link in store.links.iter_mut() {
+                let before = link.related_links.len();
+                link.related_links
+                    .retain(|id| !removed_link_ids.contains(id));
+                if link.related_links.len() != before && !updated_link_ids.contains(&link.id) {
+                    updated_link_ids.push(link.id.clone());
+                }
+            }
+        }
+        result.updated_links.extend(updated_link_ids);
+
+        true
+    }
+
     fn load_store(path: &Path) -> SimpleMemoryStore {
         if path.exists() {
             if let Ok(content) = fs::read_to_string(path) {
@@ -186,6 +362,23 @@ impl MemoryManager {
         SimpleMemoryStore {
             entries: HashMap::new(),
             version: MEMORY_VERSION.to_string(),
+            links: Vec::new(),
+        }
+    }
+
+    fn load_profile(path: &Path) -> ProfileInference {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_profile(&self) {
+        if let Some(parent) = self.profile_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(&self.profile) {
+            let _ = fs::write(&self.profile_path, content);
         }
     }
 
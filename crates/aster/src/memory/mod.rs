@@ -3,11 +3,15 @@
 //! - 类型定义 (types)
 //! - 对话记忆 (chat_memory)
 //! - 记忆压缩 (compressor)
+//! - 重要性衰减 (decay)
 //! - 简单记忆管理 (memory_manager)
+//! - 用户画像推断 (profile_inference)
 
 pub mod chat_memory;
 pub mod compressor;
+pub mod decay;
 pub mod memory_manager;
+pub mod profile_inference;
 pub mod types;
 
 #[cfg(test)]
@@ -16,11 +20,16 @@ mod tests;
 // Re-exports
 pub use chat_memory::ChatMemory;
 pub use compressor::{CompressionResult, CompressorConfig, MemoryCompressor, Period};
+pub use decay::{apply_decay, record_access};
 pub use memory_manager::MemoryManager;
+pub use profile_inference::{
+    ProfileChange, ProfileField, ProfileInference, ProfileInferenceConfig, ProfileInsight,
+};
 pub use types::{
     ChatMemoryStats, ChatMemoryStore, ChunkMessage, CommunicationStyle, ConversationChunk,
-    ConversationSummary, IdentityMemoryStore, LinkMemoryStore, MemoryEmotion, MemoryEntry,
-    MemoryEvent, MemoryEventType, MemoryHierarchyConfig, MemoryImportance, MemoryLink,
-    MemoryRecallResult, MemoryScope, MemoryStats, MessageRole, SelfAwareness, SimpleMemoryStore,
-    SymbolInfo, SymbolType, Timestamp, UserProfile,
+    ConversationSummary, ForgetQuery, ForgetResult, IdentityMemoryStore, ImportanceDecayConfig,
+    LinkMemoryStore, MemoryAccess, MemoryEmotion, MemoryEntry, MemoryEvent, MemoryEventType,
+    MemoryHierarchyConfig, MemoryImportance, MemoryLink, MemoryRecallResult, MemoryScope,
+    MemoryStats, MessageRole, SelfAwareness, SimpleMemoryStore, SymbolInfo, SymbolType, Timestamp,
+    UserProfile,
 };
@@ -0,0 +1,381 @@
+//! 用户画像推断
+//!
+//! 从对话信号（语言、消息长度、话题）中逐步更新用户画像。
+//! 更新是保守的：同一信号需要重复出现并占多数才会修改设置；
+//! 用户手动设置的字段会被锁定，不再被推断覆盖。
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{CommunicationStyle, UserProfile};
+
+/// 要求简短回答的提示词
+const CONCISE_CUES: &[&str] = &["be brief", "be concise", "tl;dr", "tldr", "简短", "简洁"];
+
+/// 要求详细回答的提示词
+const DETAILED_CUES: &[&str] = &["in detail", "explain more", "elaborate", "详细"];
+
+/// 推断配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileInferenceConfig {
+    /// 修改设置前需要的最少观察次数
+    pub min_observations: u32,
+    /// 修改设置需要的最低置信度（该值在所有观察中的占比）
+    pub min_confidence: f32,
+    /// 简洁消息的最大词数（CJK 按字计）
+    pub terse_max_words: usize,
+    /// 详细消息的最少词数（CJK 按字计）
+    pub detailed_min_words: usize,
+    /// 保留的重要话题数量
+    pub max_topics: usize,
+}
+
+impl Default for ProfileInferenceConfig {
+    fn default() -> Self {
+        Self {
+            min_observations: 5,
+            min_confidence: 0.7,
+            terse_max_words: 12,
+            detailed_min_words: 80,
+            max_topics: 10,
+        }
+    }
+}
+
+/// 画像字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileField {
+    PreferredLanguage,
+    CommunicationStyle,
+    SignificantTopics,
+}
+
+/// 推断产生的画像变更
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileChange {
+    pub field: ProfileField,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<String>,
+    pub new_value: String,
+    pub confidence: f32,
+}
+
+/// 某个字段的推断依据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileInsight {
+    pub field: ProfileField,
+    /// 得票最多的值
+    pub value: String,
+    /// 该值的观察次数
+    pub observations: u32,
+    /// 置信度（0-1）
+    pub confidence: f32,
+    /// 是否被用户锁定
+    pub locked: bool,
+}
+
+/// 用户画像推断器
+///
+/// 画像和累计的证据都可以序列化保存，用户可以通过 `inspect` 查看依据，
+/// 通过 `set_*` 手动修改（同时锁定该字段）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileInference {
+    /// 当前画像
+    pub profile: UserProfile,
+    #[serde(default)]
+    config: ProfileInferenceConfig,
+    #[serde(default)]
+    languages: HashMap<String, u32>,
+    #[serde(default)]
+    styles: HashMap<CommunicationStyle, u32>,
+    #[serde(default)]
+    topics: HashMap<String, u32>,
+    #[serde(default)]
+    locked: HashSet<ProfileField>,
+}
+
+impl ProfileInference {
+    /// 创建推断器
+    pub fn new(profile: UserProfile, config: ProfileInferenceConfig) -> Self {
+        Self {
+            profile,
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// 观察一条用户消息，返回本次引起的画像变更
+    pub fn observe_message(&mut self, text: &str) -> Vec<ProfileChange> {
+        let mut changes = Vec::new();
+
+        if let Some(language) = detect_language(text) {
+            *self.languages.entry(language.to_string()).or_insert(0) += 1;
+            changes.extend(self.update_language());
+        }
+
+        if let Some(style) = self.detect_style(text) {
+            *self.styles.entry(style).or_insert(0) += 1;
+            changes.extend(self.update_style());
+        }
+
+        changes
+    }
+
+    /// 观察对话中的话题
+    pub fn observe_topics(&mut self, topics: &[String]) -> Vec<ProfileChange> {
+        for topic in topics {
+            let topic = topic.trim().to_lowercase();
+            if !topic.is_empty() {
+                *self.topics.entry(topic).or_insert(0) += 1;
+            }
+        }
+        self.update_topics().into_iter().collect()
+    }
+
+    /// 查看每个字段的推断依据
+    pub fn inspect(&self) -> Vec<ProfileInsight> {
+        let mut insights = Vec::new();
+        if let Some((value, count, confidence)) = leading(&self.languages) {
+            insights.push(self.insight(
+                ProfileField::PreferredLanguage,
+                value.clone(),
+                count,
+                confidence,
+            ));
+        }
+        if let Some((style, count, confidence)) = leading(&self.styles) {
+            insights.push(self.insight(
+                ProfileField::CommunicationStyle,
+                style_name(*style).to_string(),
+                count,
+                confidence,
+            ));
+        }
+        if let Some((topic, count, confidence)) = leading(&self.topics) {
+            insights.push(self.insight(
+                ProfileField::SignificantTopics,
+                topic.clone(),
+                count,
+                confidence,
+            ));
+        }
+        insights
+    }
+
+    /// 手动设置偏好语言并锁定
+    pub fn set_preferred_language(&mut self, language: impl Into<String>) {
+        self.profile.preferred_language = language.into();
+        self.locked.insert(ProfileField::PreferredLanguage);
+    }
+
+    /// 手动设置交流风格并锁定，None 表示清除
+    pub fn set_communication_style(&mut self, style: Option<CommunicationStyle>) {
+        self.profile.communication_style = style;
+        self.locked.insert(ProfileField::CommunicationStyle);
+    }
+
+    /// 手动设置重要话题并锁定
+    pub fn set_significant_topics(&mut self, topics: Vec<String>) {
+        self.profile.significant_topics = topics;
+        self.locked.insert(ProfileField::SignificantTopics);
+    }
+
+    /// 解除锁定，允许继续推断
+    pub fn unlock(&mut self, field: ProfileField) {
+        self.locked.remove(&field);
+    }
+
+    /// 字段是否被锁定
+    pub fn is_locked(&self, field: ProfileField) -> bool {
+        self.locked.contains(&field)
+    }
+
+    /// 清除某个字段累计的证据
+    pub fn reset_evidence(&mut self, field: ProfileField) {
+        match field {
+            ProfileField::PreferredLanguage => self.languages.clear(),
+            ProfileField::CommunicationStyle => self.styles.clear(),
+            ProfileField::SignificantTopics => self.topics.clear(),
+        }
+    }
+
+    // === 私有方法 ===
+
+    fn insight(
+        &self,
+        field: ProfileField,
+        value: String,
+        observations: u32,
+        confidence: f32,
+    ) -> ProfileInsight {
+        ProfileInsight {
+            field,
+            value,
+            observations,
+            confidence,
+            locked: self.is_locked(field),
+        }
+    }
+
+    /// 领先值是否满足修改设置的条件
+    fn qualifies(&self, count: u32, confidence: f32) -> bool {
+        count >= self.config.min_observations && confidence >= self.config.min_confidence
+    }
+
+    fn update_language(&mut self) -> Option<ProfileChange> {
+        if self.is_locked(ProfileField::PreferredLanguage) {
+            return None;
+        }
+        let (language, count, confidence) = leading(&self.languages)?;
+        if !self.qualifies(count, confidence) || self.profile.preferred_language == *language {
+            return None;
+        }
+
+        let language = language.clone();
+        let old = std::mem::replace(&mut self.profile.preferred_language, language.clone());
+        Some(ProfileChange {
+            field: ProfileField::PreferredLanguage,
+            old_value: (!old.is_empty()).then_some(old),
+            new_value: language,
+            confidence,
+        })
+    }
+
+    fn update_style(&mut self) -> Option<ProfileChange> {
+        if self.is_locked(ProfileField::CommunicationStyle) {
+            return None;
+        }
+        let (style, count, confidence) = leading(&self.styles)?;
+        let style = *style;
+        if !self.qualifies(count, confidence) || self.profile.communication_style == Some(style) {
+            return None;
+        }
+
+        let old = self.profile.communication_style.replace(style);
+        Some(ProfileChange {
+            field: ProfileField::CommunicationStyle,
+            old_value: old.map(|s| style_name(s).to_string()),
+            new_value: style_name(style).to_string(),
+            confidence,
+        })
+    }
+
+    fn update_topics(&mut self) -> Option<ProfileChange> {
+        if self.is_locked(ProfileField::SignificantTopics) {
+            return None;
+        }
+
+        // 话题不互斥，只要求重复出现
+        let mut frequent: Vec<(&String, u32)> = self
+            .topics
+            .iter()
+            .filter(|(_, count)| **count >= self.config.min_observations)
+            .map(|(topic, count)| (topic, *count))
+            .collect();
+        frequent.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let topics: Vec<String> = frequent
+            .into_iter()
+            .take(self.config.max_topics)
+            .map(|(topic, _)| topic.clone())
+            .collect();
+
+        if topics.is_empty() || topics == self.profile.significant_topics {
+            return None;
+        }
+
+        let old = std::mem::replace(&mut self.profile.significant_topics, topics.clone());
+        let total: u32 = self.topics.values().sum();
+        let covered: u32 = topics.iter().filter_map(|t| self.topics.get(t)).sum();
+        Some(ProfileChange {
+            field: ProfileField::SignificantTopics,
+            old_value: (!old.is_empty()).then(|| old.join(", ")),
+            new_value: topics.join(", "),
+            confidence: covered as f32 / total.max(1) as f32,
+        })
+    }
+
+    fn detect_style(&self, text: &str) -> Option<CommunicationStyle> {
+        let lower = text.to_lowercase();
+        if CONCISE_CUES.iter().any(|cue| lower.contains(cue)) {
+            return Some(CommunicationStyle::Concise);
+        }
+        if DETAILED_CUES.iter().any(|cue| lower.contains(cue)) {
+            return Some(CommunicationStyle::Detailed);
+        }
+
+        let words = word_count(text);
+        if words == 0 {
+            None
+        } else if words <= self.config.terse_max_words {
+            Some(CommunicationStyle::Concise)
+        } else if words >= self.config.detailed_min_words {
+            Some(CommunicationStyle::Detailed)
+        } else {
+            None
+        }
+    }
+}
+
+/// 得票最多的值、票数和占比
+fn leading<K: Ord>(counts: &HashMap<K, u32>) -> Option<(&K, u32, f32)> {
+    let total: u32 = counts.values().sum();
+    counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(value, count)| (value, *count, *count as f32 / total as f32))
+}
+
+fn style_name(style: CommunicationStyle) -> &'static str {
+    match style {
+        CommunicationStyle::Concise => "concise",
+        CommunicationStyle::Detailed => "detailed",
+        CommunicationStyle::Casual => "casual",
+        CommunicationStyle::Formal => "formal",
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4e00}'..='\u{9fff}' | '\u{3040}'..='\u{30ff}' | '\u{ac00}'..='\u{d7af}')
+}
+
+/// 词数：CJK 字符逐字计数，其余按空白分词
+fn word_count(text: &str) -> usize {
+    let cjk = text.chars().filter(|c| is_cjk(*c)).count();
+    let words = text
+        .split_whitespace()
+        .filter(|w| w.chars().any(|c| c.is_alphanumeric() && !is_cjk(c)))
+        .count();
+    cjk + words
+}
+
+/// 根据文字系统粗略判断消息语言
+fn detect_language(text: &str) -> Option<&'static str> {
+    let mut han = 0;
+    let mut kana = 0;
+    let mut hangul = 0;
+    let mut latin = 0;
+    for c in text.chars() {
+        match c {
+            '\u{3040}'..='\u{30ff}' => kana += 1,
+            '\u{4e00}'..='\u{9fff}' => han += 1,
+            '\u{ac00}'..='\u{d7af}' => hangul += 1,
+            c if c.is_ascii_alphabetic() => latin += 1,
+            _ => {}
+        }
+    }
+
+    // 代码和路径大多是 ASCII，CJK 字符只要占一定比例就以其为准
+    if kana > 0 && kana + han >= 2 {
+        Some("ja")
+    } else if hangul >= 2 {
+        Some("ko")
+    } else if han >= 2 && han * 4 >= latin {
+        Some("zh")
+    } else if latin >= 3 && han == 0 && hangul == 0 {
+        Some("en")
+    } else {
+        None
+    }
+}
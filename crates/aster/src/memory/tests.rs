@@ -40,6 +40,7 @@ fn test_conversation_summary_serialize() {
         end_time: "2024-01-15T11:00:00Z".to_string(),
         message_count: 10,
         embedding: None,
+        access: MemoryAccess::default(),
     };
 
     let json = serde_json::to_string(&summary).unwrap();
@@ -65,6 +66,7 @@ fn test_memory_link_serialize() {
         description: "Test link".to_string(),
         importance: MemoryImportance::Medium,
         related_links: vec![],
+        memory_keys: vec![],
     };
 
     let json = serde_json::to_string(&link).unwrap();
@@ -118,6 +120,7 @@ fn test_compressor_single_summary() {
         end_time: "2024-01-15T11:00:00Z".to_string(),
         message_count: 5,
         embedding: None,
+        access: MemoryAccess::default(),
     };
 
     let result = compressor.compress(&[summary]).unwrap();
@@ -143,6 +146,7 @@ fn test_compressor_multiple_summaries() {
             end_time: "2024-01-15T11:00:00Z".to_string(),
             message_count: 10,
             embedding: None,
+            access: MemoryAccess::default(),
         },
         ConversationSummary {
             id: "2".to_string(),
@@ -157,6 +161,7 @@ fn test_compressor_multiple_summaries() {
             end_time: "2024-01-16T11:00:00Z".to_string(),
             message_count: 8,
             embedding: None,
+            access: MemoryAccess::default(),
         },
     ];
 
@@ -197,6 +202,7 @@ fn test_compressor_evaluate_importance() {
         end_time: "2024-01-15T11:00:00Z".to_string(),
         message_count: 25,
         embedding: None,
+        access: MemoryAccess::default(),
     };
 
     let importance = compressor.evaluate_importance(&high_summary);
@@ -216,6 +222,7 @@ fn test_compressor_evaluate_importance() {
         end_time: "2024-01-15T10:05:00Z".to_string(),
         message_count: 2,
         embedding: None,
+        access: MemoryAccess::default(),
     };
 
     let importance = compressor.evaluate_importance(&low_summary);
@@ -240,6 +247,7 @@ fn test_compressor_should_compress() {
             end_time: "2024-01-15T11:00:00Z".to_string(),
             message_count: 5,
             embedding: None,
+            access: MemoryAccess::default(),
         })
         .collect();
 
@@ -265,6 +273,7 @@ fn test_compressor_group_by_period() {
             end_time: "2024-01-15T11:00:00Z".to_string(),
             message_count: 5,
             embedding: None,
+            access: MemoryAccess::default(),
         },
         ConversationSummary {
             id: "2".to_string(),
@@ -279,6 +288,7 @@ fn test_compressor_group_by_period() {
             end_time: "2024-01-16T11:00:00Z".to_string(),
             message_count: 5,
             embedding: None,
+            access: MemoryAccess::default(),
         },
     ];
 
@@ -288,3 +298,291 @@ fn test_compressor_group_by_period() {
     let groups = compressor.group_by_period(&summaries, Period::Month);
     assert_eq!(groups.len(), 1);
 }
+
+fn link(id: &str, memory_keys: &[&str], related_links: &[&str]) -> MemoryLink {
+    MemoryLink {
+        id: id.to_string(),
+        timestamp: "2024-01-15T10:00:00Z".to_string(),
+        conversation_id: None,
+        session_id: None,
+        files: vec![],
+        symbols: vec![],
+        commits: vec![],
+        topics: vec![],
+        description: id.to_string(),
+        importance: MemoryImportance::Medium,
+        related_links: related_links.iter().map(|s| s.to_string()).collect(),
+        memory_keys: memory_keys.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_forget_cleans_up_links() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut manager = MemoryManager::new(Some(temp_dir.path()));
+    manager.set("email", "alice@example.com", MemoryScope::Project);
+    manager.set("editor", "vim", MemoryScope::Project);
+    manager.set("language", "rust", MemoryScope::Project);
+    manager.add_link(link("contact", &["email"], &[]), MemoryScope::Project);
+    manager.add_link(
+        link("profile", &["email", "editor"], &["contact"]),
+        MemoryScope::Project,
+    );
+    manager.add_link(
+        link("stack", &["language"], &["contact", "profile"]),
+        MemoryScope::Project,
+    );
+
+    // 预览不删除任何内容
+    let preview = manager.forget(
+        &ForgetQuery::new("alice@")
+            .with_scope(MemoryScope::Project)
+            .dry_run(),
+    );
+    assert!(preview.dry_run);
+    assert_eq!(preview.removed_entries.len(), 1);
+    assert_eq!(preview.removed_links.len(), 1);
+    assert!(manager.get("email", Some(MemoryScope::Project)).is_some());
+    assert_eq!(manager.links(Some(MemoryScope::Project)).len(), 3);
+
+    let result = manager.forget(&ForgetQuery::new("alice@").with_scope(MemoryScope::Project));
+    assert!(!result.dry_run);
+    assert_eq!(result.removed_entries[0].key, "email");
+    assert_eq!(result.removed_links[0].id, "contact");
+    assert_eq!(result.updated_links, vec!["profile", "stack"]);
+    assert!(manager.get("email", Some(MemoryScope::Project)).is_none());
+
+    let links = manager.links(Some(MemoryScope::Project));
+    assert_eq!(links.len(), 2);
+    let profile = links.iter().find(|l| l.id == "profile").unwrap();
+    assert_eq!(profile.memory_keys, vec!["editor"]);
+    assert!(profile.related_links.is_empty());
+    let stack = links.iter().find(|l| l.id == "stack").unwrap();
+    assert_eq!(stack.related_links, vec!["profile"]);
+
+    // 持久化后重新加载仍然一致
+    let reloaded = MemoryManager::new(Some(temp_dir.path()));
+    assert_eq!(reloaded.links(Some(MemoryScope::Project)).len(), 2);
+    assert!(reloaded.get("email", Some(MemoryScope::Project)).is_none());
+
+    // 空查询不会删除任何内容
+    let result = manager.forget(&ForgetQuery::new("  "));
+    assert!(result.removed_entries.is_empty());
+}
+
+#[test]
+fn test_importance_decays_unless_recalled() {
+    use chrono::{DateTime, Duration, Utc};
+
+    let start: DateTime<Utc> = "2024-01-15T11:00:00Z".parse().unwrap();
+    let summary = |id: &str| ConversationSummary {
+        id: id.to_string(),
+        session_id: "s1".to_string(),
+        summary: id.to_string(),
+        topics: vec![],
+        files_discussed: vec![],
+        symbols_discussed: vec![],
+        emotion: MemoryEmotion::Neutral,
+        importance: MemoryImportance::High,
+        start_time: "2024-01-15T10:00:00Z".to_string(),
+        end_time: start.to_rfc3339(),
+        message_count: 1,
+        embedding: None,
+        access: MemoryAccess::default(),
+    };
+    let config = ImportanceDecayConfig {
+        decay_after_days: 7,
+        min_importance: MemoryImportance::Low,
+        reinforce_every: 0,
+    };
+    let mut summaries = vec![summary("untouched"), summary("recalled")];
+
+    // 未到衰减期限
+    assert_eq!(
+        apply_decay(&mut summaries, &config, start + Duration::days(6)),
+        0
+    );
+
+    // 第 6 天召回第二条，第 8 天只有第一条衰减
+    record_access(&mut summaries[1], Some(&config), start + Duration::days(6));
+    assert_eq!(
+        apply_decay(&mut summaries, &config, start + Duration::days(8)),
+        1
+    );
+    assert_eq!(summaries[0].importance, MemoryImportance::Medium);
+    assert_eq!(summaries[1].importance, MemoryImportance::High);
+    assert_eq!(summaries[1].access.access_count, 1);
+
+    // 衰减后重新计时，且不会低于下限
+    assert_eq!(
+        apply_decay(&mut summaries, &config, start + Duration::days(10)),
+        0
+    );
+    apply_decay(&mut summaries, &config, start + Duration::days(30));
+    apply_decay(&mut summaries, &config, start + Duration::days(60));
+    assert_eq!(summaries[0].importance, MemoryImportance::Low);
+}
+
+#[test]
+fn test_recall_reinforces_importance() {
+    let config = ImportanceDecayConfig {
+        reinforce_every: 2,
+        ..Default::default()
+    };
+    let mut summary = ConversationSummary {
+        id: "1".to_string(),
+        session_id: "s1".to_string(),
+        summary: "Test".to_string(),
+        topics: vec![],
+        files_discussed: vec![],
+        symbols_discussed: vec![],
+        emotion: MemoryEmotion::Neutral,
+        importance: MemoryImportance::Low,
+        start_time: "2024-01-15T10:00:00Z".to_string(),
+        end_time: "2024-01-15T11:00:00Z".to_string(),
+        message_count: 1,
+        embedding: None,
+        access: MemoryAccess::default(),
+    };
+
+    let now = chrono::Utc::now();
+    record_access(&mut summary, Some(&config), now);
+    assert_eq!(summary.importance, MemoryImportance::Low);
+    record_access(&mut summary, Some(&config), now);
+    assert_eq!(summary.importance, MemoryImportance::Medium);
+    assert_eq!(summary.access.last_accessed, Some(now.to_rfc3339()));
+}
+
+#[test]
+fn test_profile_inference_requires_repeated_signals() {
+    let mut inference =
+        ProfileInference::new(UserProfile::default(), ProfileInferenceConfig::default());
+    let terse = [
+        "fix the build",
+        "run tests again",
+        "ok ship it",
+        "rename that fn",
+    ];
+
+    for message in terse {
+        assert!(inference
+            .observe_message(message)
+            .iter()
+            .all(|c| c.field != ProfileField::CommunicationStyle));
+    }
+    assert_eq!(inference.profile.communication_style, None);
+
+    // 第 5 条简短消息达到阈值
+    let changes = inference.observe_message("now commit");
+    let change = changes
+        .iter()
+        .find(|c| c.field == ProfileField::CommunicationStyle)
+        .unwrap();
+    assert_eq!(change.new_value, "concise");
+    assert_eq!(change.confidence, 1.0);
+    assert_eq!(
+        inference.profile.communication_style,
+        Some(CommunicationStyle::Concise)
+    );
+    assert_eq!(inference.profile.preferred_language, "en");
+
+    let insight = inference
+        .inspect()
+        .into_iter()
+        .find(|i| i.field == ProfileField::CommunicationStyle)
+        .unwrap();
+    assert_eq!(insight.observations, 5);
+    assert!(!insight.locked);
+
+    // 用户手动修改后锁定，推断不再覆盖
+    inference.set_communication_style(Some(CommunicationStyle::Detailed));
+    for _ in 0..10 {
+        inference.observe_message("ok");
+    }
+    assert_eq!(
+        inference.profile.communication_style,
+        Some(CommunicationStyle::Detailed)
+    );
+    assert!(inference.is_locked(ProfileField::CommunicationStyle));
+}
+
+#[test]
+fn test_profile_inference_mixed_signals_and_topics() {
+    let mut inference = ProfileInference::new(
+        UserProfile::default(),
+        ProfileInferenceConfig {
+            min_observations: 3,
+            ..Default::default()
+        },
+    );
+
+    // 简短和详细交替出现，置信度不足
+    let detailed = "please walk me through ".repeat(20);
+    for _ in 0..3 {
+        inference.observe_message("fix it");
+        inference.observe_message(&detailed);
+    }
+    assert_eq!(inference.profile.communication_style, None);
+
+    for _ in 0..2 {
+        inference.observe_topics(&["Rust".to_string(), "async".to_string()]);
+    }
+    assert!(inference.profile.significant_topics.is_empty());
+    let changes = inference.observe_topics(&["rust".to_string()]);
+    assert_eq!(changes[0].field, ProfileField::SignificantTopics);
+    assert_eq!(inference.profile.significant_topics, vec!["rust"]);
+
+    let json = serde_json::to_string(&inference).unwrap();
+    let restored: ProfileInference = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.profile.significant_topics, vec!["rust"]);
+}
+
+#[test]
+fn test_memory_manager_updates_profile_from_messages() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let global_dir = temp_dir.path().join("global");
+    let mut manager = MemoryManager::with_global_dir(&global_dir, Some(temp_dir.path()));
+
+    for message in [
+        "fix the build",
+        "run tests again",
+        "ok ship it",
+        "rename that fn",
+    ] {
+        manager.observe_user_message(message);
+    }
+    assert_eq!(manager.user_profile().communication_style, None);
+    let changes = manager.observe_user_message("now commit");
+    assert!(changes
+        .iter()
+        .any(|c| c.field == ProfileField::CommunicationStyle));
+    assert_eq!(
+        manager.user_profile().communication_style,
+        Some(CommunicationStyle::Concise)
+    );
+
+    // 画像和证据都已持久化
+    let mut reloaded = MemoryManager::with_global_dir(&global_dir, Some(temp_dir.path()));
+    assert_eq!(
+        reloaded.user_profile().communication_style,
+        Some(CommunicationStyle::Concise)
+    );
+    let insight = reloaded
+        .profile_inference()
+        .inspect()
+        .into_iter()
+        .find(|i| i.field == ProfileField::CommunicationStyle)
+        .unwrap();
+    assert_eq!(insight.observations, 5);
+
+    // 用户的手动修改同样持久化，并锁定该字段
+    reloaded.edit_profile(|p| p.set_communication_style(Some(CommunicationStyle::Detailed)));
+    let reloaded = MemoryManager::with_global_dir(&global_dir, Some(temp_dir.path()));
+    assert_eq!(
+        reloaded.user_profile().communication_style,
+        Some(CommunicationStyle::Detailed)
+    );
+    assert!(reloaded
+        .profile_inference()
+        .is_locked(ProfileField::CommunicationStyle));
+}
@@ -24,6 +24,28 @@ pub enum MemoryImportance {
     Core = 5,
 }
 
+impl MemoryImportance {
+    /// 降低一级（核心记忆和临时记忆保持不变）
+    pub fn decayed(self) -> Self {
+        match self {
+            MemoryImportance::Core => MemoryImportance::Core,
+            MemoryImportance::High => MemoryImportance::Medium,
+            MemoryImportance::Medium => MemoryImportance::Low,
+            MemoryImportance::Low | MemoryImportance::Ephemeral => MemoryImportance::Ephemeral,
+        }
+    }
+
+    /// 提升一级（最高到 High，核心记忆只能显式设置）
+    pub fn reinforced(self) -> Self {
+        match self {
+            MemoryImportance::Ephemeral => MemoryImportance::Low,
+            MemoryImportance::Low => MemoryImportance::Medium,
+            MemoryImportance::Medium | MemoryImportance::High => MemoryImportance::High,
+            MemoryImportance::Core => MemoryImportance::Core,
+        }
+    }
+}
+
 /// 记忆情感色彩
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -67,6 +89,22 @@ pub struct ConversationSummary {
     /// 嵌入向量（用于语义搜索）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embedding: Option<Vec<f32>>,
+    /// 访问记录（用于重要性衰减）
+    #[serde(default)]
+    pub access: MemoryAccess,
+}
+
+/// 记忆访问记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryAccess {
+    /// 最后一次被召回的时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_accessed: Option<Timestamp>,
+    /// 被召回的次数
+    pub access_count: u32,
+    /// 最后一次衰减的时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_decayed: Option<Timestamp>,
 }
 
 /// 对话片段（用于层级压缩）
@@ -155,6 +193,9 @@ pub struct MemoryLink {
     pub importance: MemoryImportance,
     /// 相关的其他链接
     pub related_links: Vec<String>,
+    /// 关联的记忆条目键
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_keys: Vec<String>,
 }
 
 /// 关联记忆存储
@@ -196,7 +237,7 @@ pub struct UserProfile {
 }
 
 /// 交流风格
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CommunicationStyle {
     Concise,
@@ -326,6 +367,30 @@ pub struct MemoryHierarchyConfig {
     /// 嵌入模型（用于语义搜索）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embedding_model: Option<String>,
+    /// 重要性衰减（None 表示不衰减）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importance_decay: Option<ImportanceDecayConfig>,
+}
+
+/// 重要性衰减配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportanceDecayConfig {
+    /// 超过 N 天未被召回时降低一级
+    pub decay_after_days: u32,
+    /// 衰减的下限
+    pub min_importance: MemoryImportance,
+    /// 每被召回 N 次提升一级（0 表示不提升）
+    pub reinforce_every: u32,
+}
+
+impl Default for ImportanceDecayConfig {
+    fn default() -> Self {
+        Self {
+            decay_after_days: 14,
+            min_importance: MemoryImportance::Low,
+            reinforce_every: 3,
+        }
+    }
 }
 
 impl Default for MemoryHierarchyConfig {
@@ -336,6 +401,7 @@ impl Default for MemoryHierarchyConfig {
             compression_threshold: 50,
             max_core_memories: 20,
             embedding_model: None,
+            importance_decay: None,
         }
     }
 }
@@ -363,6 +429,53 @@ pub enum MemoryScope {
 pub struct SimpleMemoryStore {
     pub entries: HashMap<String, MemoryEntry>,
     pub version: String,
+    /// 关联链接
+    #[serde(default)]
+    pub links: Vec<MemoryLink>,
+}
+
+/// 遗忘查询
+#[derive(Debug, Clone)]
+pub struct ForgetQuery {
+    /// 匹配键或值（不区分大小写）
+    pub query: String,
+    /// 作用域，None 表示全部
+    pub scope: Option<MemoryScope>,
+    /// 仅预览，不实际删除
+    pub dry_run: bool,
+}
+
+impl ForgetQuery {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            scope: None,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_scope(mut self, scope: MemoryScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+}
+
+/// 遗忘结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForgetResult {
+    /// 删除的记忆条目
+    pub removed_entries: Vec<MemoryEntry>,
+    /// 因失去所有关联条目而删除的链接
+    pub removed_links: Vec<MemoryLink>,
+    /// 被更新（移除了悬空引用）的链接 ID
+    pub updated_links: Vec<String>,
+    /// 是否为预览
+    pub dry_run: bool,
 }
 
 /// 记忆统计信息
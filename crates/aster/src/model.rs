@@ -1,3 +1,4 @@
+use crate::providers::capabilities::{capabilities, ModelCapabilities};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -80,6 +81,9 @@ pub struct ModelConfig {
     pub toolshim: bool,
     pub toolshim_model: Option<String>,
     pub fast_model: Option<String>,
+    /// Ask the provider for a JSON object response, if it supports it
+    #[serde(default)]
+    pub json_mode: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +115,7 @@ impl ModelConfig {
             toolshim,
             toolshim_model,
             fast_model: None,
+            json_mode: false,
         })
     }
 
@@ -236,7 +241,11 @@ impl ModelConfig {
         }
     }
 
-    fn get_model_specific_limit(model_name: &str) -> Option<usize> {
+    pub(crate) fn default_context_limit() -> usize {
+        DEFAULT_CONTEXT_LIMIT
+    }
+
+    pub(crate) fn get_model_specific_limit(model_name: &str) -> Option<usize> {
         MODEL_SPECIFIC_LIMITS
             .iter()
             .find(|(pattern, _)| model_name.contains(pattern))
@@ -285,6 +294,11 @@ impl ModelConfig {
         self
     }
 
+    pub fn with_json_mode(mut self, json_mode: bool) -> Self {
+        self.json_mode = json_mode;
+        self
+    }
+
     pub fn use_fast_model(&self) -> Self {
         if let Some(fast_model) = &self.fast_model {
             let mut config = self.clone();
@@ -315,6 +329,17 @@ impl ModelConfig {
         }
     }
 
+    /// Capabilities of the configured model, with any explicit context or
+    /// output token limits applied
+    pub fn capabilities(&self) -> ModelCapabilities {
+        let mut capabilities = capabilities(&self.model_name);
+        capabilities.context_window = self.context_limit();
+        if let Some(max_tokens) = self.max_tokens {
+            capabilities.max_output_tokens = max_tokens as usize;
+        }
+        capabilities
+    }
+
     pub fn new_or_fail(model_name: &str) -> ModelConfig {
         ModelConfig::new(model_name)
             .unwrap_or_else(|_| panic!("Failed to create model config for {}", model_name))
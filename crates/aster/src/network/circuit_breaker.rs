@@ -0,0 +1,275 @@
+//! 端点熔断器
+//!
+//! 连续失败达到阈值后熔断，冷却期内快速失败，冷却结束后放行单个探测请求
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use url::Url;
+
+/// 熔断器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// 触发熔断的连续失败次数
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// 熔断冷却时间（毫秒）
+    #[serde(default = "default_cooldown")]
+    pub cooldown: u64,
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+fn default_cooldown() -> u64 {
+    30000
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        DEFAULT_CIRCUIT_BREAKER_CONFIG
+    }
+}
+
+/// 默认熔断器配置
+pub const DEFAULT_CIRCUIT_BREAKER_CONFIG: CircuitBreakerConfig = CircuitBreakerConfig {
+    failure_threshold: 5,
+    cooldown: 30000, // 30秒
+};
+
+/// 熔断状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// 正常放行
+    Closed,
+    /// 熔断中，请求快速失败
+    Open,
+    /// 冷却结束，放行探测请求
+    HalfOpen,
+}
+
+/// 端点熔断状态快照（用于诊断）
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitStatus {
+    /// 当前状态
+    pub state: CircuitState,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 距离冷却结束的剩余时间（毫秒），仅 Open 状态有值
+    pub retry_after: Option<u64>,
+}
+
+/// 熔断期间的快速失败错误
+#[derive(Debug, Clone, Error)]
+#[error("Circuit open for {endpoint}: failing fast, retry after {retry_after}ms")]
+pub struct CircuitOpenError {
+    /// 端点
+    pub endpoint: String,
+    /// 距离冷却结束的剩余时间（毫秒）
+    pub retry_after: u64,
+}
+
+#[derive(Debug)]
+struct EndpointCircuit {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_started_at: Option<Instant>,
+}
+
+impl EndpointCircuit {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            probe_started_at: None,
+        }
+    }
+}
+
+/// 按端点维护状态的熔断器
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    endpoints: Mutex<HashMap<String, EndpointCircuit>>,
+}
+
+impl CircuitBreaker {
+    /// 使用指定配置创建
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            endpoints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_millis(self.config.cooldown)
+    }
+
+    /// 请求前检查端点是否可用
+    ///
+    /// 熔断冷却结束后转为 HalfOpen 并放行一个探测请求；探测未完成时其他请求仍快速失败，
+    /// 探测超过一个冷却周期未上报结果则允许再次探测
+    pub fn check(&self, endpoint: &str) -> Result<(), CircuitOpenError> {
+        let cooldown = self.cooldown();
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let Some(circuit) = endpoints.get_mut(endpoint) else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        match circuit.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => {
+                let elapsed = circuit.opened_at.map_or(cooldown, |t| now - t);
+                if elapsed >= cooldown {
+                    circuit.state = CircuitState::HalfOpen;
+                    circuit.probe_started_at = Some(now);
+                    Ok(())
+                } else {
+                    Err(CircuitOpenError {
+                        endpoint: endpoint.to_string(),
+                        retry_after: (cooldown - elapsed).as_millis() as u64,
+                    })
+                }
+            }
+            CircuitState::HalfOpen => match circuit.probe_started_at {
+                Some(started) if now - started < cooldown => Err(CircuitOpenError {
+                    endpoint: endpoint.to_string(),
+                    retry_after: (cooldown - (now - started)).as_millis() as u64,
+                }),
+                _ => {
+                    circuit.probe_started_at = Some(now);
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    /// 记录成功，关闭熔断
+    pub fn record_success(&self, endpoint: &str) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        if let Some(circuit) = endpoints.get_mut(endpoint) {
+            if circuit.state != CircuitState::Closed {
+                tracing::info!("Circuit closed for {}", endpoint);
+            }
+            *circuit = EndpointCircuit::new();
+        }
+    }
+
+    /// 记录失败，达到阈值或探测失败时熔断
+    pub fn record_failure(&self, endpoint: &str) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let circuit = endpoints
+            .entry(endpoint.to_string())
+            .or_insert_with(EndpointCircuit::new);
+
+        circuit.consecutive_failures += 1;
+        let trip = circuit.state == CircuitState::HalfOpen
+            || circuit.consecutive_failures >= self.config.failure_threshold.max(1);
+        if trip {
+            if circuit.state != CircuitState::Open {
+                tracing::warn!(
+                    "Circuit opened for {} after {} consecutive failures",
+                    endpoint,
+                    circuit.consecutive_failures
+                );
+            }
+            circuit.state = CircuitState::Open;
+            circuit.opened_at = Some(Instant::now());
+            circuit.probe_started_at = None;
+        }
+    }
+
+    /// 端点当前状态（冷却已结束的 Open 视为 HalfOpen）
+    pub fn state(&self, endpoint: &str) -> CircuitState {
+        self.status(endpoint)
+            .map_or(CircuitState::Closed, |status| status.state)
+    }
+
+    /// 端点状态快照，未记录过的端点返回 None
+    pub fn status(&self, endpoint: &str) -> Option<CircuitStatus> {
+        let endpoints = self.endpoints.lock().unwrap();
+        endpoints
+            .get(endpoint)
+            .map(|circuit| self.snapshot(circuit))
+    }
+
+    /// 所有端点的状态快照
+    pub fn statuses(&self) -> HashMap<String, CircuitStatus> {
+        let endpoints = self.endpoints.lock().unwrap();
+        endpoints
+            .iter()
+            .map(|(endpoint, circuit)| (endpoint.clone(), self.snapshot(circuit)))
+            .collect()
+    }
+
+    /// 重置端点状态
+    pub fn reset(&self, endpoint: &str) {
+        self.endpoints.lock().unwrap().remove(endpoint);
+    }
+
+    fn snapshot(&self, circuit: &EndpointCircuit) -> CircuitStatus {
+        let cooldown = self.cooldown();
+        let remaining = circuit
+            .opened_at
+            .map(|t| cooldown.saturating_sub(t.elapsed()));
+
+        let (state, retry_after) = match (circuit.state, remaining) {
+            (CircuitState::Open, Some(remaining)) if remaining.is_zero() => {
+                (CircuitState::HalfOpen, None)
+            }
+            (CircuitState::Open, remaining) => {
+                (CircuitState::Open, remaining.map(|r| r.as_millis() as u64))
+            }
+            (state, _) => (state, None),
+        };
+
+        CircuitStatus {
+            state,
+            consecutive_failures: circuit.consecutive_failures,
+            retry_after,
+        }
+    }
+}
+
+static CIRCUIT_BREAKERS: Lazy<Mutex<HashMap<String, Arc<CircuitBreaker>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 获取端点（见 `endpoint_key`）对应的熔断器，同一端点共用一个实例，不同端点互不影响
+pub fn circuit_breaker_for(endpoint: &str) -> Arc<CircuitBreaker> {
+    CIRCUIT_BREAKERS
+        .lock()
+        .unwrap()
+        .entry(endpoint.to_string())
+        .or_default()
+        .clone()
+}
+
+/// 应计入熔断失败的 HTTP 状态码：限流（429）及网关类错误（502/503/504）
+pub fn is_circuit_failure_status(status: u16) -> bool {
+    matches!(status, 429 | 502 | 503 | 504)
+}
+
+/// 从 URL 提取端点标识（scheme://host:port），无法解析时原样返回
+pub fn endpoint_key(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(parsed) => match (parsed.host_str(), parsed.port_or_known_default()) {
+            (Some(host), Some(port)) => format!("{}://{}:{}", parsed.scheme(), host, port),
+            (Some(host), None) => format!("{}://{}", parsed.scheme(), host),
+            _ => url.to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
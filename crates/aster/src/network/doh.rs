@@ -0,0 +1,210 @@
+//! DNS-over-HTTPS 解析
+//!
+//! 通过 DoH JSON API 解析主机名，可作为 reqwest 的 DNS 解析器使用
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Cloudflare DoH 地址（使用 IP，避免解析 DoH 服务器本身依赖系统 DNS）
+pub const CLOUDFLARE_DOH_SERVER: &str = "https://1.1.1.1/dns-query";
+/// Google DoH 地址
+pub const GOOGLE_DOH_SERVER: &str = "https://8.8.8.8/resolve";
+
+/// DoH 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DohConfig {
+    /// 是否启用 DoH（默认关闭，使用系统解析器）
+    #[serde(default)]
+    pub enabled: bool,
+    /// DoH 服务器地址（需支持 `application/dns-json`）
+    #[serde(default = "default_doh_server")]
+    pub server: String,
+    /// 查询超时（毫秒）
+    #[serde(default = "default_doh_timeout")]
+    pub timeout: u64,
+}
+
+fn default_doh_server() -> String {
+    CLOUDFLARE_DOH_SERVER.to_string()
+}
+fn default_doh_timeout() -> u64 {
+    5000
+}
+
+impl Default for DohConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: default_doh_server(),
+            timeout: default_doh_timeout(),
+        }
+    }
+}
+
+/// DoH 错误
+#[derive(Debug, Error)]
+pub enum DohError {
+    /// 请求 DoH 服务器失败
+    #[error("DoH request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    /// DNS 响应码非 NOERROR
+    #[error("DoH query for {host} returned status {status}")]
+    Status { host: String, status: u32 },
+    /// 没有可用的地址记录
+    #[error("DoH query for {0} returned no addresses")]
+    NoRecords(String),
+}
+
+/// DNS JSON 响应
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    #[serde(rename = "TTL", default)]
+    ttl: u64,
+    data: String,
+}
+
+const RECORD_A: u16 = 1;
+const RECORD_AAAA: u16 = 28;
+
+#[derive(Debug)]
+struct CacheEntry {
+    addrs: Vec<IpAddr>,
+    expires_at: Instant,
+}
+
+/// DoH 解析器，按响应 TTL 缓存解析结果
+#[derive(Debug, Clone)]
+pub struct DohResolver {
+    inner: Arc<DohResolverInner>,
+}
+
+#[derive(Debug)]
+struct DohResolverInner {
+    server: String,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl DohResolver {
+    /// 使用指定配置创建
+    pub fn new(config: &DohConfig) -> Result<Self, DohError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout))
+            .build()?;
+        Ok(Self {
+            inner: Arc::new(DohResolverInner {
+                server: config.server.clone(),
+                client,
+                cache: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    /// 解析主机名，优先返回未过期的缓存
+    pub async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, DohError> {
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return Ok(vec![ip]);
+        }
+
+        let key = host.to_ascii_lowercase();
+        if let Some(addrs) = self.cached(&key) {
+            return Ok(addrs);
+        }
+
+        let mut answers = self.query(&key, RECORD_A).await?;
+        if answers.is_empty() {
+            answers = self.query(&key, RECORD_AAAA).await?;
+        }
+        if answers.is_empty() {
+            return Err(DohError::NoRecords(key));
+        }
+
+        let ttl = answers.iter().map(|(_, ttl)| *ttl).min().unwrap_or(0);
+        let addrs: Vec<IpAddr> = answers.into_iter().map(|(ip, _)| ip).collect();
+        if ttl > 0 {
+            self.inner.cache.lock().unwrap().insert(
+                key,
+                CacheEntry {
+                    addrs: addrs.clone(),
+                    expires_at: Instant::now() + Duration::from_secs(ttl),
+                },
+            );
+        }
+        Ok(addrs)
+    }
+
+    /// 清空解析缓存
+    pub fn clear_cache(&self) {
+        self.inner.cache.lock().unwrap().clear();
+    }
+
+    fn cached(&self, host: &str) -> Option<Vec<IpAddr>> {
+        let mut cache = self.inner.cache.lock().unwrap();
+        match cache.get(host) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.addrs.clone()),
+            Some(_) => {
+                cache.remove(host);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// 查询单一记录类型，返回地址及其 TTL（秒）
+    async fn query(&self, host: &str, record_type: u16) -> Result<Vec<(IpAddr, u64)>, DohError> {
+        let record_type_param = record_type.to_string();
+        let response: DohResponse = self
+            .inner
+            .client
+            .get(&self.inner.server)
+            .query(&[("name", host), ("type", record_type_param.as_str())])
+            .header("accept", "application/dns-json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // NXDOMAIN 视为无记录，由调用方统一报错
+        if response.status != 0 && response.status != 3 {
+            return Err(DohError::Status {
+                host: host.to_string(),
+                status: response.status,
+            });
+        }
+
+        // CNAME 等其他记录类型直接跳过，服务器会一并返回最终地址记录
+        Ok(response
+            .answer
+            .into_iter()
+            .filter(|answer| answer.record_type == record_type)
+            .filter_map(|answer| answer.data.parse().ok().map(|ip| (ip, answer.ttl)))
+            .collect())
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = resolver.lookup(name.as_str()).await?;
+            let addrs: Addrs = Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(addrs)
+        })
+    }
+}
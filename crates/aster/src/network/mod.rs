@@ -1,11 +1,17 @@
 //! 网络模块
 //!
-//! 提供代理、超时、重试等网络功能
+//! 提供代理、DNS-over-HTTPS、超时、重试、熔断等网络功能
 
+mod circuit_breaker;
+mod doh;
+mod pac;
 mod proxy;
 mod retry;
 mod timeout;
 
+pub use circuit_breaker::*;
+pub use doh::*;
+pub use pac::*;
 pub use proxy::*;
 pub use retry::*;
 pub use timeout::*;
@@ -0,0 +1,267 @@
+//! PAC (Proxy Auto-Config) 脚本支持
+//!
+//! 使用内置 JS 引擎执行 `FindProxyForURL`，按目标 URL 选择代理
+
+use boa_engine::{js_string, Context, JsValue, NativeFunction, Source};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+use thiserror::Error;
+use url::Url;
+
+/// 下载 PAC 脚本的超时时间
+const PAC_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// PAC 标准辅助函数（`dnsResolve` 和 `myIpAddress` 由 Rust 提供）
+///
+/// 不支持依赖当前时间的 `weekdayRange`/`dateRange`/`timeRange`
+const PAC_PRELUDE: &str = r#"
+function isPlainHostName(host) {
+    return host.indexOf('.') < 0;
+}
+function dnsDomainIs(host, domain) {
+    return host.length >= domain.length
+        && host.substring(host.length - domain.length) === domain;
+}
+function localHostOrDomainIs(host, hostdom) {
+    return host === hostdom || hostdom.lastIndexOf(host + '.', 0) === 0;
+}
+function isResolvable(host) {
+    return dnsResolve(host) !== null;
+}
+function dnsDomainLevels(host) {
+    return host.split('.').length - 1;
+}
+function shExpMatch(str, shexp) {
+    var pattern = shexp
+        .replace(/[.+^${}()|[\]\\]/g, '\\$&')
+        .replace(/\*/g, '.*')
+        .replace(/\?/g, '.');
+    return new RegExp('^' + pattern + '$').test(str);
+}
+function __convertAddr(ip) {
+    var parts = ip.split('.');
+    return ((parts[0] << 24) | (parts[1] << 16) | (parts[2] << 8) | parts[3]) >>> 0;
+}
+function isInNet(host, pattern, mask) {
+    var ip = /^\d+\.\d+\.\d+\.\d+$/.test(host) ? host : dnsResolve(host);
+    if (!ip) {
+        return false;
+    }
+    var m = __convertAddr(mask);
+    return (__convertAddr(ip) & m) === (__convertAddr(pattern) & m);
+}
+"#;
+
+/// PAC 错误
+#[derive(Debug, Error)]
+pub enum PacError {
+    /// 无法读取或下载 PAC 脚本
+    #[error("Failed to load PAC script from {location}: {message}")]
+    Load { location: String, message: String },
+    /// 脚本执行出错或未定义 FindProxyForURL
+    #[error("PAC script error: {0}")]
+    Script(String),
+    /// 目标 URL 无效
+    #[error("Invalid URL for PAC lookup: {0}")]
+    InvalidUrl(String),
+}
+
+/// PAC 返回的单个代理选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacProxy {
+    /// 直连
+    Direct,
+    /// 代理 URL，如 `http://proxy:8080`、`socks5://proxy:1080`
+    Proxy(String),
+}
+
+/// PAC 脚本
+#[derive(Debug, Clone)]
+pub struct PacScript {
+    source: String,
+}
+
+impl PacScript {
+    /// 从脚本内容创建，会先执行一次以校验语法和 `FindProxyForURL` 是否存在
+    pub fn new(source: impl Into<String>) -> Result<Self, PacError> {
+        let script = Self {
+            source: source.into(),
+        };
+        script.context()?;
+        Ok(script)
+    }
+
+    /// 从 http(s) URL、`file://` URL 或本地路径加载
+    pub async fn load(location: &str) -> Result<Self, PacError> {
+        let load_error = |message: String| PacError::Load {
+            location: location.to_string(),
+            message,
+        };
+
+        let source = if location.starts_with("http://") || location.starts_with("https://") {
+            reqwest::Client::builder()
+                .timeout(PAC_FETCH_TIMEOUT)
+                .build()
+                .map_err(|e| load_error(e.to_string()))?
+                .get(location)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| load_error(e.to_string()))?
+                .text()
+                .await
+                .map_err(|e| load_error(e.to_string()))?
+        } else {
+            let path = match Url::parse(location) {
+                Ok(url) if url.scheme() == "file" => url
+                    .to_file_path()
+                    .map_err(|_| load_error("invalid file URL".to_string()))?,
+                _ => location.into(),
+            };
+            tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|e| load_error(e.to_string()))?
+        };
+
+        Self::new(source)
+    }
+
+    /// 执行 `FindProxyForURL(url, host)`，返回原始结果，如 `PROXY a:8080; DIRECT`
+    pub fn find_proxy(&self, target_url: &str) -> Result<String, PacError> {
+        let host = Url::parse(target_url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .ok_or_else(|| PacError::InvalidUrl(target_url.to_string()))?;
+
+        let mut ctx = self.context()?;
+        let function = ctx
+            .global_object()
+            .get(js_string!("FindProxyForURL"), &mut ctx)
+            .map_err(|e| PacError::Script(e.to_string()))?;
+        let callable = function
+            .as_callable()
+            .ok_or_else(|| PacError::Script("FindProxyForURL is not a function".to_string()))?;
+
+        let result = callable
+            .call(
+                &JsValue::undefined(),
+                &[
+                    JsValue::from(js_string!(target_url)),
+                    JsValue::from(js_string!(host.as_str())),
+                ],
+                &mut ctx,
+            )
+            .map_err(|e| PacError::Script(e.to_string()))?;
+
+        result
+            .to_string(&mut ctx)
+            .map(|s| s.to_std_string_escaped())
+            .map_err(|e| PacError::Script(e.to_string()))
+    }
+
+    /// 按 PAC 结果选择代理，返回第一个选项；直连时返回 None
+    ///
+    /// 脚本中的 `dnsResolve` 会同步解析域名，异步代码中应使用 `proxy_for_url_async`
+    pub fn proxy_for_url(&self, target_url: &str) -> Result<Option<String>, PacError> {
+        let result = self.find_proxy(target_url)?;
+        Ok(match parse_pac_result(&result).into_iter().next() {
+            Some(PacProxy::Proxy(url)) => Some(url),
+            Some(PacProxy::Direct) | None => None,
+        })
+    }
+
+    /// `proxy_for_url` 的异步版本，在阻塞线程池中执行脚本
+    pub async fn proxy_for_url_async(&self, target_url: &str) -> Result<Option<String>, PacError> {
+        let script = self.clone();
+        let target_url = target_url.to_string();
+        tokio::task::spawn_blocking(move || script.proxy_for_url(&target_url))
+            .await
+            .map_err(|e| PacError::Script(e.to_string()))?
+    }
+
+    fn context(&self) -> Result<Context, PacError> {
+        let mut ctx = Context::default();
+        ctx.register_global_callable(
+            js_string!("dnsResolve"),
+            1,
+            NativeFunction::from_fn_ptr(|_this, args, ctx| {
+                let host = args
+                    .first()
+                    .cloned()
+                    .unwrap_or_default()
+                    .to_string(ctx)?
+                    .to_std_string_escaped();
+                Ok(resolve_ipv4(&host)
+                    .map(|ip| JsValue::from(js_string!(ip.as_str())))
+                    .unwrap_or(JsValue::null()))
+            }),
+        )
+        .map_err(|e| PacError::Script(e.to_string()))?;
+        ctx.register_global_callable(
+            js_string!("myIpAddress"),
+            0,
+            NativeFunction::from_fn_ptr(|_this, _args, _ctx| {
+                Ok(JsValue::from(js_string!(local_ip_address().as_str())))
+            }),
+        )
+        .map_err(|e| PacError::Script(e.to_string()))?;
+
+        ctx.eval(Source::from_bytes(PAC_PRELUDE))
+            .map_err(|e| PacError::Script(e.to_string()))?;
+        ctx.eval(Source::from_bytes(&self.source))
+            .map_err(|e| PacError::Script(e.to_string()))?;
+
+        let defined = ctx
+            .global_object()
+            .get(js_string!("FindProxyForURL"), &mut ctx)
+            .map(|f| f.is_callable())
+            .unwrap_or(false);
+        if !defined {
+            return Err(PacError::Script(
+                "FindProxyForURL is not defined".to_string(),
+            ));
+        }
+        Ok(ctx)
+    }
+}
+
+/// 解析 PAC 返回值，如 `PROXY a:8080; SOCKS5 b:1080; DIRECT`
+pub fn parse_pac_result(result: &str) -> Vec<PacProxy> {
+    result
+        .split(';')
+        .filter_map(|entry| {
+            let mut parts = entry.split_whitespace();
+            let kind = parts.next()?.to_uppercase();
+            let address = parts.next();
+            match (kind.as_str(), address) {
+                ("DIRECT", _) => Some(PacProxy::Direct),
+                ("PROXY" | "HTTP", Some(addr)) => Some(PacProxy::Proxy(format!("http://{}", addr))),
+                ("HTTPS", Some(addr)) => Some(PacProxy::Proxy(format!("https://{}", addr))),
+                ("SOCKS" | "SOCKS5", Some(addr)) => {
+                    Some(PacProxy::Proxy(format!("socks5://{}", addr)))
+                }
+                ("SOCKS4", Some(addr)) => Some(PacProxy::Proxy(format!("socks4://{}", addr))),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn resolve_ipv4(host: &str) -> Option<String> {
+    (host, 0)
+        .to_socket_addrs()
+        .ok()?
+        .find(|addr| addr.is_ipv4())
+        .map(|addr| addr.ip().to_string())
+}
+
+/// 本机出口 IP（UDP connect 不会实际发送数据）
+fn local_ip_address() -> String {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("8.8.8.8:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|_| "127.0.0.1".to_string())
+}
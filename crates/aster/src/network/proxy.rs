@@ -1,11 +1,23 @@
 //! 代理配置和支持
 //!
-//! 支持 HTTP/HTTPS/SOCKS 代理
+//! 支持 HTTP/HTTPS/SOCKS 代理、系统代理检测和 PAC 脚本
 
+use super::pac::PacScript;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 use url::Url;
 
+/// 系统代理检测结果及 PAC 脚本的缓存时间
+const SYSTEM_PROXY_CACHE_TTL: Duration = Duration::from_secs(300);
+
+static SYSTEM_RESOLVER: Lazy<Mutex<Option<(Instant, Arc<ProxyResolver>)>>> =
+    Lazy::new(|| Mutex::new(None));
+
 /// 代理配置
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProxyConfig {
@@ -30,6 +42,9 @@ pub struct ProxyConfig {
     /// 是否使用系统代理设置
     #[serde(default = "default_use_system_proxy")]
     pub use_system_proxy: bool,
+    /// PAC 脚本地址（http(s) URL、file URL 或本地路径）
+    #[serde(default)]
+    pub pac: Option<String>,
 }
 
 fn default_use_system_proxy() -> bool {
@@ -109,7 +124,145 @@ pub fn get_proxy_from_env() -> ProxyConfig {
         username: None,
         password: None,
         use_system_proxy: true,
+        pac: None,
+    }
+}
+
+/// 检测系统代理设置
+///
+/// macOS 读取 `scutil --proxy`，Windows 读取注册表中的 Internet Settings；
+/// 未检测到系统代理时回退到 `HTTP(S)_PROXY`/`NO_PROXY` 环境变量
+pub fn detect_system_proxy() -> ProxyConfig {
+    system_proxy_settings()
+        .filter(|config| {
+            config.http.is_some()
+                || config.https.is_some()
+                || config.socks.is_some()
+                || config.pac.is_some()
+        })
+        .unwrap_or_else(get_proxy_from_env)
+}
+
+fn system_proxy_settings() -> Option<ProxyConfig> {
+    if cfg!(target_os = "macos") {
+        let output = Command::new("scutil").arg("--proxy").output().ok()?;
+        Some(parse_scutil_proxy(&String::from_utf8_lossy(&output.stdout)))
+    } else if cfg!(windows) {
+        let output = Command::new("reg")
+            .args([
+                "query",
+                r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings",
+            ])
+            .output()
+            .ok()?;
+        Some(parse_windows_proxy(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    } else {
+        None
+    }
+}
+
+/// 解析 macOS `scutil --proxy` 输出
+pub fn parse_scutil_proxy(output: &str) -> ProxyConfig {
+    let mut values = std::collections::HashMap::new();
+    let mut no_proxy = Vec::new();
+    let mut in_exceptions = false;
+
+    for line in output.lines().map(str::trim) {
+        if line.starts_with("ExceptionsList") {
+            in_exceptions = true;
+            continue;
+        }
+        if in_exceptions {
+            if line == "}" {
+                in_exceptions = false;
+            } else if let Some((_, host)) = line.split_once(" : ") {
+                no_proxy.push(host.trim().to_string());
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(" : ") {
+            values.insert(key.trim(), value.trim());
+        }
+    }
+
+    let enabled = |key: &str| values.get(key) == Some(&"1");
+    let proxy = |prefix: &str, scheme: &str| {
+        if !enabled(&format!("{}Enable", prefix)) {
+            return None;
+        }
+        let host = values.get(format!("{}Proxy", prefix).as_str())?;
+        let port = values.get(format!("{}Port", prefix).as_str());
+        Some(match port {
+            Some(port) => format!("{}://{}:{}", scheme, host, port),
+            None => format!("{}://{}", scheme, host),
+        })
+    };
+
+    ProxyConfig {
+        http: proxy("HTTP", "http"),
+        https: proxy("HTTPS", "http"),
+        socks: proxy("SOCKS", "socks5"),
+        no_proxy,
+        pac: if enabled("ProxyAutoConfigEnable") {
+            values
+                .get("ProxyAutoConfigURLString")
+                .map(|s| s.to_string())
+        } else {
+            None
+        },
+        ..Default::default()
+    }
+}
+
+/// 解析 Windows `reg query` Internet Settings 输出
+pub fn parse_windows_proxy(output: &str) -> ProxyConfig {
+    let mut config = ProxyConfig::default();
+    let mut enabled = false;
+    let mut server = None;
+
+    for line in output.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(_kind)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let value = parts.collect::<Vec<_>>().join(" ");
+        match name {
+            "ProxyEnable" => enabled = value == "0x1",
+            "ProxyServer" => server = Some(value),
+            "ProxyOverride" => {
+                config.no_proxy = value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty() && *s != "<local>")
+                    .map(str::to_string)
+                    .collect();
+            }
+            "AutoConfigURL" => config.pac = Some(value),
+            _ => {}
+        }
+    }
+
+    if let Some(server) = server.filter(|_| enabled) {
+        if server.contains('=') {
+            // 按协议分别配置，如 http=a:80;https=b:443;socks=c:1080
+            for entry in server.split(';') {
+                match entry.split_once('=') {
+                    Some(("http", addr)) => config.http = Some(format!("http://{}", addr)),
+                    Some(("https", addr)) => config.https = Some(format!("http://{}", addr)),
+                    Some(("socks", addr)) => config.socks = Some(format!("socks5://{}", addr)),
+                    _ => {}
+                }
+            }
+        } else {
+            let url = format!("http://{}", server);
+            config.http = Some(url.clone());
+            config.https = Some(url);
+        }
     }
+
+    config
 }
 
 /// 解析代理 URL，提取认证信息
@@ -286,3 +439,84 @@ pub fn get_reqwest_proxy(config: Option<&ProxyConfig>) -> Option<reqwest::Proxy>
 
     reqwest::Proxy::all(&final_url).ok()
 }
+
+/// 按目标 URL 解析代理
+///
+/// 配置了 PAC 脚本时优先使用脚本结果，脚本执行失败时回退到静态代理配置
+#[derive(Debug, Clone, Default)]
+pub struct ProxyResolver {
+    config: ProxyConfig,
+    pac: Option<PacScript>,
+}
+
+impl ProxyResolver {
+    /// 使用指定配置和 PAC 脚本创建
+    pub fn new(config: ProxyConfig, pac: Option<PacScript>) -> Self {
+        Self { config, pac }
+    }
+
+    /// 自动检测系统代理，并加载其中配置的 PAC 脚本
+    pub async fn detect() -> Self {
+        let config = tokio::task::spawn_blocking(detect_system_proxy)
+            .await
+            .unwrap_or_else(|_| get_proxy_from_env());
+        Self::from_config(config).await
+    }
+
+    /// 缓存的系统代理解析器，超过 `SYSTEM_PROXY_CACHE_TTL` 后重新检测并加载 PAC 脚本
+    pub async fn system() -> Arc<Self> {
+        let mut cached = SYSTEM_RESOLVER.lock().await;
+        if let Some((detected_at, resolver)) = cached.as_ref() {
+            if detected_at.elapsed() < SYSTEM_PROXY_CACHE_TTL {
+                return resolver.clone();
+            }
+        }
+        let resolver = Arc::new(Self::detect().await);
+        *cached = Some((Instant::now(), resolver.clone()));
+        resolver
+    }
+
+    /// 从配置创建，配置了 PAC 地址时加载脚本；加载失败只记录警告
+    pub async fn from_config(config: ProxyConfig) -> Self {
+        let pac = match config.pac.as_deref() {
+            Some(location) => match PacScript::load(location).await {
+                Ok(script) => Some(script),
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        Self { config, pac }
+    }
+
+    /// 目标 URL 应使用的代理，直连时返回 None
+    ///
+    /// PAC 脚本可能同步解析域名，异步代码中应使用 `resolve`
+    pub fn resolve_proxy(&self, target_url: &str) -> Option<String> {
+        if let Some(ref pac) = self.pac {
+            match pac.proxy_for_url(target_url) {
+                Ok(proxy) => return proxy,
+                Err(e) => tracing::warn!("PAC lookup failed for {}: {}", target_url, e),
+            }
+        }
+        get_proxy_for_url(target_url, &self.config)
+    }
+
+    /// `resolve_proxy` 的异步版本，PAC 脚本在阻塞线程池中执行
+    pub async fn resolve(&self, target_url: &str) -> Option<String> {
+        if let Some(ref pac) = self.pac {
+            match pac.proxy_for_url_async(target_url).await {
+                Ok(proxy) => return proxy,
+                Err(e) => tracing::warn!("PAC lookup failed for {}: {}", target_url, e),
+            }
+        }
+        get_proxy_for_url(target_url, &self.config)
+    }
+}
+
+/// 使用缓存的系统代理设置解析目标 URL 的代理
+pub async fn resolve_proxy(target_url: &str) -> Option<String> {
+    ProxyResolver::system().await.resolve(target_url).await
+}
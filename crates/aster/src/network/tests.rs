@@ -209,3 +209,256 @@ async fn test_cancelable_delay_cancelled() {
     let result = cancelable_delay(1000, Some(&token)).await;
     assert!(result.is_err());
 }
+
+const TEST_PAC: &str = r#"
+function FindProxyForURL(url, host) {
+    if (isPlainHostName(host) || dnsDomainIs(host, ".intranet.corp")) {
+        return "DIRECT";
+    }
+    if (shExpMatch(host, "*.github.com")) {
+        return "PROXY proxy.corp:3128; DIRECT";
+    }
+    return "SOCKS5 socks.corp:1080";
+}
+"#;
+
+#[test]
+fn test_pac_routes_per_host() {
+    let resolver = ProxyResolver::new(
+        ProxyConfig::default(),
+        Some(PacScript::new(TEST_PAC).unwrap()),
+    );
+
+    assert_eq!(
+        resolver.resolve_proxy("https://wiki.intranet.corp/page"),
+        None
+    );
+    assert_eq!(resolver.resolve_proxy("http://build/status"), None);
+    assert_eq!(
+        resolver.resolve_proxy("https://api.github.com/repos"),
+        Some("http://proxy.corp:3128".to_string())
+    );
+    assert_eq!(
+        resolver.resolve_proxy("https://example.com"),
+        Some("socks5://socks.corp:1080".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_pac_resolves_off_the_runtime() {
+    let resolver = ProxyResolver::new(
+        ProxyConfig::default(),
+        Some(PacScript::new(TEST_PAC).unwrap()),
+    );
+
+    assert_eq!(
+        resolver.resolve("https://api.github.com/repos").await,
+        Some("http://proxy.corp:3128".to_string())
+    );
+    assert_eq!(resolver.resolve("http://build/status").await, None);
+}
+
+#[test]
+fn test_pac_script_without_find_proxy_is_rejected() {
+    assert!(PacScript::new("var x = 1;").is_err());
+    assert!(PacScript::new("function FindProxyForURL(url, host) {").is_err());
+}
+
+#[test]
+fn test_resolver_falls_back_to_static_config() {
+    let config = ProxyConfig {
+        https: Some("http://proxy:8080".to_string()),
+        no_proxy: vec!["localhost".to_string()],
+        ..Default::default()
+    };
+    let resolver = ProxyResolver::new(config, None);
+
+    assert_eq!(
+        resolver.resolve_proxy("https://example.com"),
+        Some("http://proxy:8080".to_string())
+    );
+    assert_eq!(resolver.resolve_proxy("https://localhost:3000"), None);
+}
+
+#[test]
+fn test_parse_pac_result() {
+    assert_eq!(
+        parse_pac_result("PROXY a:8080; HTTPS b:443; SOCKS c:1080; DIRECT"),
+        vec![
+            PacProxy::Proxy("http://a:8080".to_string()),
+            PacProxy::Proxy("https://b:443".to_string()),
+            PacProxy::Proxy("socks5://c:1080".to_string()),
+            PacProxy::Direct,
+        ]
+    );
+}
+
+#[test]
+fn test_parse_scutil_proxy() {
+    let output = "<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+  }
+  HTTPEnable : 1
+  HTTPPort : 3128
+  HTTPProxy : proxy.corp
+  HTTPSEnable : 0
+  ProxyAutoConfigEnable : 1
+  ProxyAutoConfigURLString : http://wpad.corp/proxy.pac
+}";
+    let config = parse_scutil_proxy(output);
+
+    assert_eq!(config.http.as_deref(), Some("http://proxy.corp:3128"));
+    assert!(config.https.is_none());
+    assert_eq!(config.no_proxy, vec!["*.local", "169.254/16"]);
+    assert_eq!(config.pac.as_deref(), Some("http://wpad.corp/proxy.pac"));
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_trips_and_recovers() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+        failure_threshold: 3,
+        cooldown: 100,
+    });
+    let endpoint = "https://api.example.com:443";
+
+    for _ in 0..2 {
+        assert!(breaker.check(endpoint).is_ok());
+        breaker.record_failure(endpoint);
+    }
+    assert_eq!(breaker.state(endpoint), CircuitState::Closed);
+
+    breaker.record_failure(endpoint);
+    assert_eq!(breaker.state(endpoint), CircuitState::Open);
+
+    // 冷却期内快速失败
+    let start = std::time::Instant::now();
+    for _ in 0..10 {
+        let err = breaker.check(endpoint).unwrap_err();
+        assert_eq!(err.endpoint, endpoint);
+        assert!(err.retry_after <= 100);
+    }
+    assert!(start.elapsed() < std::time::Duration::from_millis(50));
+    assert!(breaker.check("https://other.example.com:443").is_ok());
+
+    tokio::time::sleep(std::time::Duration::from_millis(120)).await;
+    assert_eq!(breaker.state(endpoint), CircuitState::HalfOpen);
+
+    // 只放行一个探测请求
+    assert!(breaker.check(endpoint).is_ok());
+    assert!(breaker.check(endpoint).is_err());
+
+    breaker.record_success(endpoint);
+    assert_eq!(breaker.state(endpoint), CircuitState::Closed);
+    assert!(breaker.check(endpoint).is_ok());
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_failed_probe_reopens() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+        failure_threshold: 1,
+        cooldown: 50,
+    });
+    let endpoint = "http://localhost:8080";
+
+    breaker.record_failure(endpoint);
+    assert!(breaker.check(endpoint).is_err());
+
+    tokio::time::sleep(std::time::Duration::from_millis(70)).await;
+    assert!(breaker.check(endpoint).is_ok());
+    breaker.record_failure(endpoint);
+
+    let status = breaker.status(endpoint).unwrap();
+    assert_eq!(status.state, CircuitState::Open);
+    assert_eq!(status.consecutive_failures, 2);
+    assert!(status.retry_after.is_some());
+    assert!(breaker.check(endpoint).is_err());
+}
+
+#[test]
+fn test_endpoint_key() {
+    assert_eq!(
+        endpoint_key("https://api.openai.com/v1/chat"),
+        "https://api.openai.com:443"
+    );
+    assert_eq!(
+        endpoint_key("http://localhost:11434"),
+        "http://localhost:11434"
+    );
+    assert_eq!(endpoint_key("not a url"), "not a url");
+}
+
+async fn mock_doh_server(host: &str, ip: &str, ttl: u64) -> wiremock::MockServer {
+    use wiremock::matchers::{header, method, query_param};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let server = wiremock::MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("name", host))
+        .and(query_param("type", "1"))
+        .and(header("accept", "application/dns-json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "Status": 0,
+            "Answer": [
+                { "name": format!("{}.", host), "type": 5, "TTL": ttl, "data": "alias.test." },
+                { "name": "alias.test.", "type": 1, "TTL": ttl, "data": ip }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_doh_lookup_caches_by_ttl() {
+    let doh = mock_doh_server("provider.test", "10.1.2.3", 300).await;
+    let resolver = DohResolver::new(&DohConfig {
+        enabled: true,
+        server: format!("{}/dns-query", doh.uri()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let expected: Vec<std::net::IpAddr> = vec!["10.1.2.3".parse().unwrap()];
+    assert_eq!(resolver.lookup("provider.test").await.unwrap(), expected);
+    // 第二次命中缓存，mock 只允许被调用一次
+    assert_eq!(resolver.lookup("Provider.Test").await.unwrap(), expected);
+    assert_eq!(
+        resolver.lookup("192.168.0.1").await.unwrap(),
+        vec!["192.168.0.1".parse::<std::net::IpAddr>().unwrap()]
+    );
+}
+
+#[tokio::test]
+async fn test_doh_resolver_used_by_http_client() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let provider = wiremock::MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .mount(&provider)
+        .await;
+
+    let doh = mock_doh_server("api.provider.test", "127.0.0.1", 60).await;
+    let resolver = DohResolver::new(&DohConfig {
+        enabled: true,
+        server: format!("{}/dns-query", doh.uri()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let client = reqwest::Client::builder()
+        .dns_resolver(std::sync::Arc::new(resolver))
+        .build()
+        .unwrap();
+    let url = format!(
+        "http://api.provider.test:{}/v1/models",
+        provider.address().port()
+    );
+    let body = client.get(url).send().await.unwrap().text().await.unwrap();
+    assert_eq!(body, "ok");
+}
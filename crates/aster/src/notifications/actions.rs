@@ -0,0 +1,162 @@
+//! 通知动作分发
+//!
+//! 将桌面通知上的按钮点击路由回注册的回调
+
+use super::types::NotificationAction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::warn;
+
+/// 通知动作回调，参数为通知 ID 和动作标识
+pub type NotificationActionCallback = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// 通知动作事件（桌面端点击按钮后回传）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationActionEvent {
+    /// 通知 ID
+    pub notification_id: String,
+    /// 动作标识
+    pub action: String,
+}
+
+/// 待处理的通知动作
+struct PendingActions {
+    actions: Vec<String>,
+    callback: NotificationActionCallback,
+}
+
+/// 通知动作分发器
+#[derive(Default)]
+pub struct NotificationActionDispatcher {
+    pending: RwLock<HashMap<String, PendingActions>>,
+}
+
+impl NotificationActionDispatcher {
+    /// 创建新的分发器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 为通知注册动作回调
+    pub fn register(
+        &self,
+        notification_id: &str,
+        actions: &[NotificationAction],
+        callback: NotificationActionCallback,
+    ) {
+        if let Ok(mut pending) = self.pending.write() {
+            pending.insert(
+                notification_id.to_string(),
+                PendingActions {
+                    actions: actions.iter().map(|a| a.action.clone()).collect(),
+                    callback,
+                },
+            );
+        }
+    }
+
+    /// 分发动作，成功时移除该通知的待处理动作
+    ///
+    /// 未知的通知或动作只记录日志并返回 `false`
+    pub fn dispatch(&self, notification_id: &str, action: &str) -> bool {
+        let callback = {
+            let Ok(mut pending) = self.pending.write() else {
+                return false;
+            };
+            let Some(entry) = pending.get(notification_id) else {
+                warn!(
+                    "No pending actions for notification {} (action: {})",
+                    notification_id, action
+                );
+                return false;
+            };
+            if !entry.actions.iter().any(|a| a == action) {
+                warn!(
+                    "Unhandled action '{}' for notification {}",
+                    action, notification_id
+                );
+                return false;
+            }
+            pending.remove(notification_id).map(|entry| entry.callback)
+        };
+
+        match callback {
+            Some(callback) => {
+                callback(notification_id, action);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 取消通知的待处理动作
+    pub fn cancel(&self, notification_id: &str) -> bool {
+        self.pending
+            .write()
+            .map(|mut p| p.remove(notification_id).is_some())
+            .unwrap_or(false)
+    }
+
+    /// 是否有待处理动作
+    pub fn has_pending(&self, notification_id: &str) -> bool {
+        self.pending
+            .read()
+            .map(|p| p.contains_key(notification_id))
+            .unwrap_or(false)
+    }
+
+    /// 待处理通知数量
+    pub fn pending_count(&self) -> usize {
+        self.pending.read().map(|p| p.len()).unwrap_or(0)
+    }
+
+    /// 清空所有待处理动作
+    pub fn clear(&self) {
+        if let Ok(mut pending) = self.pending.write() {
+            pending.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn actions() -> Vec<NotificationAction> {
+        vec![
+            NotificationAction::new("retry", "Retry").primary(),
+            NotificationAction::new("view_diff", "View diff"),
+        ]
+    }
+
+    #[test]
+    fn test_dispatch_invokes_callback_once() {
+        let dispatcher = NotificationActionDispatcher::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        dispatcher.register(
+            "n1",
+            &actions(),
+            Arc::new(move |id, action| {
+                sink.lock().unwrap().push(format!("{}:{}", id, action));
+            }),
+        );
+
+        assert!(dispatcher.dispatch("n1", "retry"));
+        assert!(!dispatcher.dispatch("n1", "retry"));
+        assert_eq!(*received.lock().unwrap(), vec!["n1:retry".to_string()]);
+        assert_eq!(dispatcher.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_unknown_action_is_ignored() {
+        let dispatcher = NotificationActionDispatcher::new();
+        dispatcher.register("n1", &actions(), Arc::new(|_, _| panic!("unexpected")));
+
+        assert!(!dispatcher.dispatch("n1", "approve"));
+        assert!(!dispatcher.dispatch("missing", "retry"));
+        assert!(dispatcher.has_pending("n1"));
+    }
+}
@@ -20,7 +20,7 @@ pub fn send_desktop_notification(notification: &Notification) -> anyhow::Result<
     #[cfg(target_os = "linux")]
     {
         let urgency = match notification.notification_type {
-            NotificationType::Error => "critical",
+            NotificationType::Error | NotificationType::Critical => "critical",
             NotificationType::Warning => "normal",
             _ => "low",
         };
@@ -51,7 +51,7 @@ pub fn play_sound(notification_type: NotificationType) -> anyhow::Result<()> {
     #[cfg(target_os = "macos")]
     {
         let sound = match notification_type {
-            NotificationType::Error => "Basso",
+            NotificationType::Error | NotificationType::Critical => "Basso",
             NotificationType::Warning => "Sosumi",
             _ => "Pop",
         };
@@ -92,6 +92,7 @@ pub fn terminal_notify(message: &str, notification_type: NotificationType) {
         NotificationType::Success => "✓ ",
         NotificationType::Warning => "⚠ ",
         NotificationType::Error => "✗ ",
+        NotificationType::Critical => "‼ ",
     };
     println!("{}{}", prefix, message);
 }
@@ -2,10 +2,15 @@
 //!
 //! 管理通知的发送、存储和状态
 
+use super::actions::{
+    NotificationActionCallback, NotificationActionDispatcher, NotificationActionEvent,
+};
 use super::desktop::{play_sound, send_desktop_notification};
+use super::throttle::{NotificationThrottle, ThrottleDecision};
 use super::types::*;
-use std::sync::{Arc, RwLock};
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::action_required_manager::ActionRequiredManager;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// 通知管理器
 pub struct NotificationManager {
@@ -15,15 +20,22 @@ pub struct NotificationManager {
     notifications: Arc<RwLock<Vec<Notification>>>,
     /// 最大通知数
     max_notifications: usize,
+    /// 去重与限流
+    throttle: NotificationThrottle,
+    /// 通知动作分发
+    dispatcher: NotificationActionDispatcher,
 }
 
 impl NotificationManager {
     /// 创建新的通知管理器
     pub fn new(config: NotificationConfig) -> Self {
+        let throttle = NotificationThrottle::new(config.throttle.clone());
         Self {
             config,
             notifications: Arc::new(RwLock::new(Vec::new())),
             max_notifications: 100,
+            throttle,
+            dispatcher: NotificationActionDispatcher::new(),
         }
     }
 
@@ -65,6 +77,7 @@ impl NotificationManager {
             NotificationType::Success,
             NotificationType::Warning,
             NotificationType::Error,
+            NotificationType::Critical,
         ];
 
         let type_index = priority_order
@@ -80,17 +93,147 @@ impl NotificationManager {
     }
 
     /// 发送通知
+    ///
+    /// 窗口内的相同通知会合并到已有通知并增加计数，此时返回 `None`；
+    /// 超出种类限流的通知同样返回 `None`
     pub fn notify(
         &self,
         title: &str,
         message: &str,
         notification_type: NotificationType,
         kind: NotificationKind,
+    ) -> Option<Notification> {
+        self.notify_inner(title, message, notification_type, kind, Vec::new())
+    }
+
+    /// 发送带动作按钮的通知
+    ///
+    /// 用户点击按钮后通过 [`Self::handle_action`] 回调 `callback`；
+    /// 通知被合并或限流时不会注册回调
+    pub fn notify_with_actions(
+        &self,
+        title: &str,
+        message: &str,
+        notification_type: NotificationType,
+        kind: NotificationKind,
+        actions: Vec<NotificationAction>,
+        callback: NotificationActionCallback,
+    ) -> Option<Notification> {
+        let notification = self.notify_inner(title, message, notification_type, kind, actions)?;
+        self.dispatcher
+            .register(&notification.id, &notification.actions, callback);
+        Some(notification)
+    }
+
+    /// 发送带动作按钮的通知并等待用户选择，返回动作标识
+    pub async fn notify_and_wait(
+        &self,
+        title: &str,
+        message: &str,
+        notification_type: NotificationType,
+        kind: NotificationKind,
+        actions: Vec<NotificationAction>,
+        timeout_duration: Duration,
+    ) -> anyhow::Result<String> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Mutex::new(Some(tx));
+        let notification = self
+            .notify_with_actions(
+                title,
+                message,
+                notification_type,
+                kind,
+                actions,
+                Arc::new(move |_, action| {
+                    if let Some(tx) = tx.lock().ok().and_then(|mut tx| tx.take()) {
+                        let _ = tx.send(action.to_string());
+                    }
+                }),
+            )
+            .ok_or_else(|| anyhow::anyhow!("Notification was suppressed"))?;
+
+        match tokio::time::timeout(timeout_duration, rx).await {
+            Ok(Ok(action)) => Ok(action),
+            Ok(Err(_)) => {
+                self.dispatcher.cancel(&notification.id);
+                Err(anyhow::anyhow!("Notification action channel closed"))
+            }
+            Err(_) => {
+                self.dispatcher.cancel(&notification.id);
+                Err(anyhow::anyhow!("Timeout waiting for notification action"))
+            }
+        }
+    }
+
+    /// 处理通知动作点击，返回是否有回调被触发
+    pub fn handle_action(&self, notification_id: &str, action: &str) -> bool {
+        let handled = self.dispatcher.dispatch(notification_id, action);
+        if handled {
+            self.mark_as_read(notification_id);
+        }
+        handled
+    }
+
+    /// 处理桌面端回传的通知动作事件
+    pub fn handle_action_event(&self, event: &NotificationActionEvent) -> bool {
+        self.handle_action(&event.notification_id, &event.action)
+    }
+
+    /// 全局通知管理器，桌面端（Tauri）的点击事件经由它回到 Agent
+    pub fn global() -> &'static Self {
+        static INSTANCE: once_cell::sync::Lazy<NotificationManager> =
+            once_cell::sync::Lazy::new(|| NotificationManager::new(NotificationConfig::default()));
+        &INSTANCE
+    }
+
+    /// 为 `ActionRequiredManager` 中等待的请求发送带动作按钮的通知
+    ///
+    /// 点击按钮后以 `{"action": <动作标识>}` 解决该请求
+    pub fn notify_action_required(
+        &self,
+        title: &str,
+        message: &str,
+        request_id: &str,
+        actions: Vec<NotificationAction>,
+    ) -> Option<Notification> {
+        let request_id = request_id.to_string();
+        self.notify_with_actions(
+            title,
+            message,
+            NotificationType::Warning,
+            NotificationKind::PermissionRequired,
+            actions,
+            Arc::new(move |_, action| resolve_action_required(&request_id, action)),
+        )
+    }
+
+    fn notify_inner(
+        &self,
+        title: &str,
+        message: &str,
+        notification_type: NotificationType,
+        kind: NotificationKind,
+        actions: Vec<NotificationAction>,
     ) -> Option<Notification> {
         if !self.is_enabled() || !self.meets_priority(notification_type) {
             return None;
         }
 
+        let key = NotificationThrottle::dedupe_key(title, message, notification_type, kind);
+        match self.throttle.check(key, notification_type, kind) {
+            ThrottleDecision::Emit => {}
+            ThrottleDecision::Coalesce { id, count } => {
+                if let Ok(mut notifications) = self.notifications.write() {
+                    if let Some(n) = notifications.iter_mut().find(|n| n.id == id) {
+                        n.count = count;
+                        n.read = false;
+                    }
+                }
+                return None;
+            }
+            ThrottleDecision::RateLimited => return None,
+        }
+
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_millis() as u64)
@@ -104,8 +247,10 @@ impl NotificationManager {
             message: message.to_string(),
             timestamp,
             read: false,
-            actions: Vec::new(),
+            actions,
+            count: 1,
         };
+        self.throttle.record(key, kind, &notification.id);
 
         // 添加到列表
         if let Ok(mut notifications) = self.notifications.write() {
@@ -177,6 +322,8 @@ impl NotificationManager {
         if let Ok(mut notifications) = self.notifications.write() {
             notifications.clear();
         }
+        self.throttle.reset();
+        self.dispatcher.clear();
     }
 
     /// 便捷方法：发送信息通知
@@ -225,3 +372,166 @@ impl Default for NotificationManager {
         Self::new(NotificationConfig::default())
     }
 }
+
+/// 以点击的动作解决等待中的请求
+fn resolve_action_required(request_id: &str, action: &str) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        tracing::warn!(
+            "No async runtime to resolve request {} with action '{}'",
+            request_id,
+            action
+        );
+        return;
+    };
+    let request_id = request_id.to_string();
+    let response = serde_json::json!({ "action": action });
+    handle.spawn(async move {
+        if let Err(e) = ActionRequiredManager::global()
+            .resolve(&request_id, response)
+            .await
+        {
+            tracing::warn!("Failed to resolve request {}: {}", request_id, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet_config() -> NotificationConfig {
+        NotificationConfig {
+            desktop_notifications: false,
+            sound_enabled: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_identical_notifications_are_coalesced() {
+        let manager = NotificationManager::new(quiet_config());
+
+        let emitted: Vec<_> = (0..10)
+            .filter_map(|_| manager.error("Edit failed", "old_string not found"))
+            .collect();
+
+        assert_eq!(emitted.len(), 1);
+        let all = manager.get_all();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].count, 10);
+        assert_eq!(
+            all[0].display_message(),
+            "old_string not found (10 similar)"
+        );
+    }
+
+    #[test]
+    fn test_category_rate_limit() {
+        let mut config = quiet_config();
+        config.throttle.max_per_category = 3;
+        let manager = NotificationManager::new(config);
+
+        for i in 0..5 {
+            manager.error("Edit failed", &format!("error {}", i));
+        }
+        assert_eq!(manager.get_all().len(), 3);
+    }
+
+    #[test]
+    fn test_notification_action_routed_to_callback() {
+        let manager = NotificationManager::new(quiet_config());
+        let clicked = Arc::new(Mutex::new(None));
+        let sink = clicked.clone();
+
+        let notification = manager
+            .notify_with_actions(
+                "Edit failed",
+                "Retry the edit?",
+                NotificationType::Warning,
+                NotificationKind::Error,
+                vec![NotificationAction::new("retry", "Retry").primary()],
+                Arc::new(move |_, action| {
+                    *sink.lock().unwrap() = Some(action.to_string());
+                }),
+            )
+            .unwrap();
+
+        assert!(!manager.handle_action(&notification.id, "approve"));
+        assert!(manager.handle_action_event(&NotificationActionEvent {
+            notification_id: notification.id.clone(),
+            action: "retry".to_string(),
+        }));
+        assert_eq!(clicked.lock().unwrap().as_deref(), Some("retry"));
+        assert_eq!(manager.get_unread_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_action_required_resolved_by_notification() {
+        let manager = NotificationManager::new(quiet_config());
+        let message = format!("Allow write to {}?", uuid::Uuid::new_v4());
+        let waiting = tokio::spawn({
+            let message = message.clone();
+            async move {
+                ActionRequiredManager::global()
+                    .request_and_wait(message, serde_json::json!({}), Duration::from_secs(5))
+                    .await
+            }
+        });
+        let request_id = loop {
+            let pending = ActionRequiredManager::global().pending().await;
+            if let Some(request) = pending.into_iter().find(|p| p.message == message) {
+                break request.id;
+            }
+            tokio::task::yield_now().await;
+        };
+
+        let notification = manager
+            .notify_action_required(
+                "Permission required",
+                &message,
+                &request_id,
+                vec![
+                    NotificationAction::new("approve", "Approve").primary(),
+                    NotificationAction::new("deny", "Deny"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(notification.kind, NotificationKind::PermissionRequired);
+        assert!(manager.handle_action(&notification.id, "approve"));
+
+        let response = waiting.await.unwrap().unwrap();
+        assert_eq!(response, serde_json::json!({ "action": "approve" }));
+    }
+
+    #[tokio::test]
+    async fn test_notify_and_wait_times_out() {
+        let manager = NotificationManager::new(quiet_config());
+        let result = manager
+            .notify_and_wait(
+                "Approve",
+                "Apply changes?",
+                NotificationType::Info,
+                NotificationKind::PermissionRequired,
+                vec![NotificationAction::new("approve", "Approve")],
+                Duration::from_millis(10),
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_critical_bypasses_throttle() {
+        let manager = NotificationManager::new(quiet_config());
+
+        for _ in 0..3 {
+            let n = manager.notify(
+                "Disk full",
+                "No space left",
+                NotificationType::Critical,
+                NotificationKind::Error,
+            );
+            assert!(n.is_some());
+        }
+        assert_eq!(manager.get_all().len(), 3);
+    }
+}
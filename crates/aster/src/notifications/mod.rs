@@ -2,12 +2,18 @@
 //!
 //! 提供桌面通知和终端通知功能
 
+mod actions;
 mod desktop;
 mod manager;
+mod throttle;
 mod types;
 
+pub use actions::{
+    NotificationActionCallback, NotificationActionDispatcher, NotificationActionEvent,
+};
 pub use desktop::{bell, play_sound, send_desktop_notification};
 pub use manager::NotificationManager;
+pub use throttle::{NotificationThrottle, ThrottleConfig, ThrottleDecision};
 pub use types::{
     Notification, NotificationAction, NotificationConfig, NotificationKind, NotificationType,
 };
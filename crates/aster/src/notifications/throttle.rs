@@ -0,0 +1,299 @@
+//! 通知去重与限流
+//!
+//! 相同内容的通知在时间窗口内合并为一条并计数，
+//! 同一种类的通知按窗口限制数量，严重通知不受限制
+
+use super::types::{NotificationKind, NotificationType};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 去重与限流配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 去重窗口（毫秒），窗口内相同通知会被合并
+    pub dedupe_window_ms: u64,
+    /// 限流窗口（毫秒）
+    pub rate_limit_window_ms: u64,
+    /// 每个种类在限流窗口内允许的最大通知数
+    pub max_per_category: u32,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dedupe_window_ms: 10_000,
+            rate_limit_window_ms: 60_000,
+            max_per_category: 10,
+        }
+    }
+}
+
+/// 限流判定结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThrottleDecision {
+    /// 正常发送
+    Emit,
+    /// 合并到已有通知
+    Coalesce {
+        /// 被合并的通知 ID
+        id: String,
+        /// 合并后的计数
+        count: u32,
+    },
+    /// 超出种类限流，丢弃
+    RateLimited,
+}
+
+/// 去重条目
+#[derive(Debug)]
+struct DedupeEntry {
+    notification_id: String,
+    first_seen: Instant,
+    count: u32,
+}
+
+#[derive(Debug, Default)]
+struct ThrottleState {
+    dedupe: HashMap<u64, DedupeEntry>,
+    per_category: HashMap<NotificationKind, VecDeque<Instant>>,
+}
+
+/// 通知去重与限流器
+#[derive(Debug)]
+pub struct NotificationThrottle {
+    config: ThrottleConfig,
+    state: Mutex<ThrottleState>,
+}
+
+impl NotificationThrottle {
+    /// 创建新的限流器
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(ThrottleState::default()),
+        }
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &ThrottleConfig {
+        &self.config
+    }
+
+    /// 计算去重键
+    pub fn dedupe_key(
+        title: &str,
+        message: &str,
+        notification_type: NotificationType,
+        kind: NotificationKind,
+    ) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        title.hash(&mut hasher);
+        message.hash(&mut hasher);
+        notification_type.hash(&mut hasher);
+        kind.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 判定一条通知是否应发送
+    pub fn check(
+        &self,
+        key: u64,
+        notification_type: NotificationType,
+        kind: NotificationKind,
+    ) -> ThrottleDecision {
+        self.check_at(key, notification_type, kind, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        key: u64,
+        notification_type: NotificationType,
+        kind: NotificationKind,
+        now: Instant,
+    ) -> ThrottleDecision {
+        if !self.config.enabled || notification_type == NotificationType::Critical {
+            return ThrottleDecision::Emit;
+        }
+
+        let Ok(mut state) = self.state.lock() else {
+            return ThrottleDecision::Emit;
+        };
+
+        let dedupe_window = Duration::from_millis(self.config.dedupe_window_ms);
+        state
+            .dedupe
+            .retain(|_, entry| now.duration_since(entry.first_seen) < dedupe_window);
+
+        if let Some(entry) = state.dedupe.get_mut(&key) {
+            entry.count += 1;
+            return ThrottleDecision::Coalesce {
+                id: entry.notification_id.clone(),
+                count: entry.count,
+            };
+        }
+
+        let rate_window = Duration::from_millis(self.config.rate_limit_window_ms);
+        let sent = state.per_category.entry(kind).or_default();
+        while sent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= rate_window)
+        {
+            sent.pop_front();
+        }
+        if sent.len() >= self.config.max_per_category as usize {
+            tracing::debug!("Notification of kind {:?} rate limited", kind);
+            return ThrottleDecision::RateLimited;
+        }
+
+        ThrottleDecision::Emit
+    }
+
+    /// 记录已发送的通知，供后续去重与限流使用
+    pub fn record(&self, key: u64, kind: NotificationKind, notification_id: &str) {
+        self.record_at(key, kind, notification_id, Instant::now());
+    }
+
+    fn record_at(&self, key: u64, kind: NotificationKind, notification_id: &str, now: Instant) {
+        if !self.config.enabled {
+            return;
+        }
+        if let Ok(mut state) = self.state.lock() {
+            state.dedupe.insert(
+                key,
+                DedupeEntry {
+                    notification_id: notification_id.to_string(),
+                    first_seen: now,
+                    count: 1,
+                },
+            );
+            state.per_category.entry(kind).or_default().push_back(now);
+        }
+    }
+
+    /// 重置所有状态
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.dedupe.clear();
+            state.per_category.clear();
+        }
+    }
+}
+
+impl Default for NotificationThrottle {
+    fn default() -> Self {
+        Self::new(ThrottleConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(message: &str) -> u64 {
+        NotificationThrottle::dedupe_key(
+            "Tool failed",
+            message,
+            NotificationType::Error,
+            NotificationKind::Error,
+        )
+    }
+
+    #[test]
+    fn test_identical_notifications_coalesce() {
+        let throttle = NotificationThrottle::default();
+        let now = Instant::now();
+        let k = key("boom");
+
+        assert_eq!(
+            throttle.check_at(k, NotificationType::Error, NotificationKind::Error, now),
+            ThrottleDecision::Emit
+        );
+        throttle.record_at(k, NotificationKind::Error, "n1", now);
+
+        assert_eq!(
+            throttle.check_at(k, NotificationType::Error, NotificationKind::Error, now),
+            ThrottleDecision::Coalesce {
+                id: "n1".to_string(),
+                count: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_dedupe_window_expires() {
+        let throttle = NotificationThrottle::new(ThrottleConfig {
+            dedupe_window_ms: 100,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let k = key("boom");
+        throttle.record_at(k, NotificationKind::Error, "n1", now);
+
+        let later = now + Duration::from_millis(150);
+        assert_eq!(
+            throttle.check_at(k, NotificationType::Error, NotificationKind::Error, later),
+            ThrottleDecision::Emit
+        );
+    }
+
+    #[test]
+    fn test_per_category_rate_limit() {
+        let throttle = NotificationThrottle::new(ThrottleConfig {
+            max_per_category: 2,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        for (i, msg) in ["a", "b"].iter().enumerate() {
+            let k = key(msg);
+            assert_eq!(
+                throttle.check_at(k, NotificationType::Error, NotificationKind::Error, now),
+                ThrottleDecision::Emit
+            );
+            throttle.record_at(k, NotificationKind::Error, &format!("n{}", i), now);
+        }
+
+        assert_eq!(
+            throttle.check_at(
+                key("c"),
+                NotificationType::Error,
+                NotificationKind::Error,
+                now
+            ),
+            ThrottleDecision::RateLimited
+        );
+        // 其他种类不受影响
+        assert_eq!(
+            throttle.check_at(
+                key("c"),
+                NotificationType::Info,
+                NotificationKind::Message,
+                now
+            ),
+            ThrottleDecision::Emit
+        );
+    }
+
+    #[test]
+    fn test_critical_bypasses_throttle() {
+        let throttle = NotificationThrottle::new(ThrottleConfig {
+            max_per_category: 0,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let k = key("boom");
+        throttle.record_at(k, NotificationKind::Error, "n1", now);
+
+        assert_eq!(
+            throttle.check_at(k, NotificationType::Critical, NotificationKind::Error, now),
+            ThrottleDecision::Emit
+        );
+    }
+}
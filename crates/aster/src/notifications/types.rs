@@ -2,10 +2,11 @@
 //!
 //! 定义通知相关的数据结构
 
+use super::throttle::ThrottleConfig;
 use serde::{Deserialize, Serialize};
 
 /// 通知类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NotificationType {
     #[default]
@@ -13,10 +14,12 @@ pub enum NotificationType {
     Success,
     Warning,
     Error,
+    /// 严重通知，不受去重与限流影响
+    Critical,
 }
 
 /// 通知种类
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum NotificationKind {
     TaskComplete,
@@ -39,6 +42,23 @@ pub struct NotificationAction {
     pub primary: bool,
 }
 
+impl NotificationAction {
+    /// 创建新的通知动作
+    pub fn new(action: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            action: action.into(),
+            primary: false,
+        }
+    }
+
+    /// 标记为主要动作
+    pub fn primary(mut self) -> Self {
+        self.primary = true;
+        self
+    }
+}
+
 /// 通知
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
@@ -58,6 +78,24 @@ pub struct Notification {
     pub read: bool,
     /// 可用动作
     pub actions: Vec<NotificationAction>,
+    /// 合并的相似通知数量
+    #[serde(default = "default_count")]
+    pub count: u32,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+impl Notification {
+    /// 展示用消息，合并多条时附带数量
+    pub fn display_message(&self) -> String {
+        if self.count > 1 {
+            format!("{} ({} similar)", self.message, self.count)
+        } else {
+            self.message.clone()
+        }
+    }
 }
 
 /// 通知配置
@@ -75,6 +113,9 @@ pub struct NotificationConfig {
     pub quiet_hours_end: Option<u8>,
     /// 最低优先级
     pub min_priority: Option<NotificationType>,
+    /// 去重与限流配置
+    #[serde(default)]
+    pub throttle: ThrottleConfig,
 }
 
 impl Default for NotificationConfig {
@@ -86,6 +127,7 @@ impl Default for NotificationConfig {
             quiet_hours_start: None,
             quiet_hours_end: None,
             min_priority: None,
+            throttle: ThrottleConfig::default(),
         }
     }
 }
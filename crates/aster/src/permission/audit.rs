@@ -13,8 +13,10 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use super::types::{PermissionContext, PermissionResult};
+use crate::security::redaction::ArgumentRedactor;
 
 /// Audit log level
 ///
@@ -164,6 +166,8 @@ pub struct AuditLogger {
     level: AuditLogLevel,
     /// Whether audit logging is enabled
     enabled: bool,
+    /// Redacts sensitive parameters before they are logged
+    redactor: Arc<ArgumentRedactor>,
 }
 
 impl Default for AuditLogger {
@@ -171,6 +175,7 @@ impl Default for AuditLogger {
         Self {
             level: AuditLogLevel::Info,
             enabled: true,
+            redactor: Arc::new(ArgumentRedactor::default()),
         }
     }
 }
@@ -185,10 +190,27 @@ impl AuditLogger {
     pub fn new(level: AuditLogLevel) -> Self {
         Self {
             level,
-            enabled: true,
+            ..Default::default()
         }
     }
 
+    /// Set the redactor applied to entry parameters
+    pub fn with_redactor(mut self, redactor: ArgumentRedactor) -> Self {
+        self.redactor = Arc::new(redactor);
+        self
+    }
+
+    /// Get the redactor applied to entry parameters
+    pub fn redactor(&self) -> &ArgumentRedactor {
+        &self.redactor
+    }
+
+    /// Redact sensitive parameters of an entry as it would be logged
+    pub fn redact_entry(&self, mut entry: AuditLogEntry) -> AuditLogEntry {
+        entry.parameters = self.redactor.redact(&entry.tool_name, &entry.parameters);
+        entry
+    }
+
     /// Get the current log level
     pub fn level(&self) -> AuditLogLevel {
         self.level
@@ -252,6 +274,8 @@ impl AuditLogger {
             return Ok(());
         }
 
+        let entry = self.redact_entry(entry);
+
         // Serialize entry to JSON for structured logging
         let entry_json = serde_json::to_string(&entry).map_err(|_| ())?;
 
@@ -330,6 +354,8 @@ impl AuditLogger {
             return Ok(());
         }
 
+        let entry = self.redact_entry(entry);
+
         // Serialize entry to JSON for structured logging
         let entry_json = serde_json::to_string(&entry).map_err(|_| ())?;
 
@@ -402,6 +428,8 @@ impl AuditLogger {
             return Ok(());
         }
 
+        let entry = self.redact_entry(entry);
+
         // Serialize entry to JSON for structured logging
         let entry_json = serde_json::to_string(&entry).map_err(|_| ())?;
 
@@ -666,4 +694,28 @@ mod tests {
         logger.log_tool_execution(entry.clone());
         logger.log(entry);
     }
+
+    #[test]
+    fn test_write_content_is_redacted() {
+        let logger = AuditLogger::default();
+        let content = "aws_secret_access_key = q8Wk2Lr9Tz4Xv7Nb3Hd6Fj1Mp5Sy0Gc8Ve2Ua4Oi";
+        let mut params = HashMap::new();
+        params.insert("path".to_string(), serde_json::json!("/tmp/credentials"));
+        params.insert("content".to_string(), serde_json::json!(content));
+
+        let entry = logger.redact_entry(
+            AuditLogEntry::new("tool_execution", "write")
+                .with_parameters(params)
+                .with_context(create_test_context()),
+        );
+        let logged = serde_json::to_string(&entry).unwrap();
+
+        assert!(!logged.contains(content));
+        assert!(logged.contains("/tmp/credentials"));
+        assert_eq!(
+            entry.parameters["content"]["length"],
+            serde_json::json!(content.len())
+        );
+        assert!(entry.parameters["content"]["sha256"].is_string());
+    }
 }
@@ -0,0 +1,393 @@
+//! 计划执行器
+//!
+//! 按步骤依赖关系把计划作为 DAG 执行：互不依赖的步骤并行运行，
+//! 失败的步骤只阻塞依赖它的步骤，最终汇总完成、失败和跳过的步骤。
+//! 通过 `execute_plan` 执行时每个步骤的结果都会持久化，中断后可用 `resume_plan` 继续。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+
+use super::persistence::PlanPersistenceManager;
+use super::run_state::{PlanRunState, PlanRunStore};
+use super::types::PlanStep;
+
+/// 步骤执行器
+#[async_trait]
+pub trait StepRunner: Send + Sync {
+    /// 执行一个步骤，返回输出或错误信息
+    async fn run_step(&self, step: &PlanStep) -> Result<String, String>;
+}
+
+/// 步骤执行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Skipped,
+}
+
+impl StepStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StepStatus::Pending => "pending",
+            StepStatus::Running => "running",
+            StepStatus::Completed => "completed",
+            StepStatus::Failed => "failed",
+            StepStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// 单个步骤的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepResult {
+    pub step: u32,
+    pub status: StepStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 导致跳过的失败或跳过的依赖
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+impl StepResult {
+    fn new(step: u32, status: StepStatus) -> Self {
+        Self {
+            step,
+            status,
+            output: None,
+            error: None,
+            blocked_by: Vec::new(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+/// 步骤状态变化事件
+#[derive(Debug, Clone)]
+pub struct StepEvent {
+    pub step: u32,
+    pub status: StepStatus,
+}
+
+/// 执行报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// 按步骤编号排序的结果
+    pub results: Vec<StepResult>,
+    pub completed: Vec<u32>,
+    pub failed: Vec<u32>,
+    pub skipped: Vec<u32>,
+}
+
+impl ExecutionReport {
+    /// 所有步骤是否都已完成
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty()
+    }
+
+    /// 获取某个步骤的结果
+    pub fn get(&self, step: u32) -> Option<&StepResult> {
+        self.results.iter().find(|r| r.step == step)
+    }
+
+    /// 把执行状态写回计划步骤
+    pub fn apply_to(&self, steps: &mut [PlanStep]) {
+        for step in steps.iter_mut() {
+            if let Some(result) = self.get(step.step) {
+                step.status = Some(result.status.as_str().to_string());
+                if result.status == StepStatus::Completed {
+                    step.completed_at = result.finished_at;
+                    if let (Some(start), Some(end)) = (result.started_at, result.finished_at) {
+                        step.actual_minutes = Some(((end - start) / 60_000) as u32);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 检查步骤依赖：编号唯一、依赖存在且没有环
+pub fn validate_dependencies(steps: &[PlanStep]) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    for step in steps {
+        if !ids.insert(step.step) {
+            return Err(format!("步骤编号重复: {}", step.step));
+        }
+    }
+    for step in steps {
+        if let Some(dep) = step.dependencies.iter().find(|d| !ids.contains(d)) {
+            return Err(format!("步骤 {} 依赖不存在的步骤 {}", step.step, dep));
+        }
+    }
+
+    // Kahn 拓扑排序检测环，重复声明的依赖只算一条边
+    let dependencies: HashMap<u32, HashSet<u32>> = steps
+        .iter()
+        .map(|s| (s.step, s.dependencies.iter().copied().collect()))
+        .collect();
+    let mut in_degree: HashMap<u32, usize> = dependencies
+        .iter()
+        .map(|(id, deps)| (*id, deps.len()))
+        .collect();
+    let mut ready: Vec<u32> = in_degree
+        .iter()
+        .filter(|(_, d)| **d == 0)
+        .map(|(id, _)| *id)
+        .collect();
+    let mut visited = 0;
+    while let Some(id) = ready.pop() {
+        visited += 1;
+        for (step, _) in dependencies.iter().filter(|(_, deps)| deps.contains(&id)) {
+            let degree = in_degree.get_mut(step).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                ready.push(*step);
+            }
+        }
+    }
+
+    if visited != steps.len() {
+        let mut cyclic: Vec<u32> = in_degree
+            .into_iter()
+            .filter(|(_, d)| *d > 0)
+            .map(|(id, _)| id)
+            .collect();
+        cyclic.sort_unstable();
+        return Err(format!("步骤依赖存在循环: {:?}", cyclic));
+    }
+    Ok(())
+}
+
+/// 计划执行器
+pub struct PlanExecutor {
+    runner: Arc<dyn StepRunner>,
+    max_parallel: usize,
+    run_store: Option<PlanRunStore>,
+    event_sender: broadcast::Sender<StepEvent>,
+}
+
+impl PlanExecutor {
+    /// 创建执行器，默认最多并行 4 个步骤
+    pub fn new(runner: Arc<dyn StepRunner>) -> Self {
+        let (event_sender, _) = broadcast::channel(64);
+        Self {
+            runner,
+            max_parallel: 4,
+            run_store: None,
+            event_sender,
+        }
+    }
+
+    /// 设置最大并行数
+    pub fn with_max_parallel(mut self, max_parallel: usize) -> Self {
+        self.max_parallel = max_parallel.max(1);
+        self
+    }
+
+    /// 设置执行状态存储，默认为 `~/.aster/plan-runs`
+    pub fn with_run_store(mut self, store: PlanRunStore) -> Self {
+        self.run_store = Some(store);
+        self
+    }
+
+    /// 订阅步骤状态事件
+    pub fn subscribe(&self) -> broadcast::Receiver<StepEvent> {
+        self.event_sender.subscribe()
+    }
+
+    fn emit(&self, step: u32, status: StepStatus) {
+        let _ = self.event_sender.send(StepEvent { step, status });
+    }
+
+    /// 执行计划步骤
+    pub async fn execute(&self, steps: &[PlanStep]) -> Result<ExecutionReport, String> {
+        self.run(steps, BTreeMap::new(), None).await
+    }
+
+    /// 执行计划并持久化每个步骤的结果
+    pub async fn execute_plan(
+        &self,
+        plan_id: &str,
+        steps: &[PlanStep],
+    ) -> Result<ExecutionReport, String> {
+        validate_dependencies(steps)?;
+
+        let store = self.run_store.clone().unwrap_or_default();
+        let mut state = PlanRunState::new(plan_id, steps.to_vec());
+        store.save(&state)?;
+        self.run(steps, BTreeMap::new(), Some((&store, &mut state)))
+            .await
+    }
+
+    /// 从上次中断处继续执行计划
+    ///
+    /// 已完成的步骤保留原有结果不再执行，失败和被跳过的步骤重新执行。
+    /// 没有执行记录时从已保存的计划开始新的执行。
+    pub async fn resume_plan(&self, plan_id: &str) -> Result<ExecutionReport, String> {
+        let store = self.run_store.clone().unwrap_or_default();
+        let mut state = match store.load(plan_id)? {
+            Some(state) => state,
+            None => {
+                let plan = PlanPersistenceManager::load_plan(plan_id)?;
+                PlanRunState::new(plan_id, plan.steps)
+            }
+        };
+        validate_dependencies(&state.steps)?;
+
+        state.results = state.completed();
+        store.save(&state)?;
+
+        let steps = state.steps.clone();
+        let completed = state.results.clone();
+        self.run(&steps, completed, Some((&store, &mut state)))
+            .await
+    }
+
+    async fn run(
+        &self,
+        steps: &[PlanStep],
+        completed: BTreeMap<u32, StepResult>,
+        mut checkpoint: Option<(&PlanRunStore, &mut PlanRunState)>,
+    ) -> Result<ExecutionReport, String> {
+        validate_dependencies(steps)?;
+
+        let mut pending: BTreeMap<u32, &PlanStep> = steps
+            .iter()
+            .filter(|s| !completed.contains_key(&s.step))
+            .map(|s| (s.step, s))
+            .collect();
+        let mut results = completed;
+        let mut running: HashMap<tokio::task::Id, (u32, u64)> = HashMap::new();
+        let mut tasks = JoinSet::new();
+
+        loop {
+            self.skip_blocked(&mut pending, &mut results);
+
+            // 启动依赖全部完成的步骤
+            let ready: Vec<u32> = pending
+                .values()
+                .filter(|step| {
+                    step.dependencies.iter().all(|dep| {
+                        results
+                            .get(dep)
+                            .is_some_and(|r| r.status == StepStatus::Completed)
+                    })
+                })
+                .map(|step| step.step)
+                .take(self.max_parallel.saturating_sub(running.len()))
+                .collect();
+            for id in ready {
+                let step = pending.remove(&id).unwrap().clone();
+                let runner = Arc::clone(&self.runner);
+                let handle = tasks.spawn(async move { runner.run_step(&step).await });
+                running.insert(handle.id(), (id, current_timestamp()));
+                self.emit(id, StepStatus::Running);
+            }
+
+            if running.is_empty() {
+                break;
+            }
+
+            let Some(joined) = tasks.join_next_with_id().await else {
+                break;
+            };
+            let (task_id, outcome) = match joined {
+                Ok((task_id, outcome)) => (task_id, outcome),
+                Err(e) => (e.id(), Err(format!("步骤执行异常: {}", e))),
+            };
+            let Some((id, started_at)) = running.remove(&task_id) else {
+                continue;
+            };
+
+            let mut result = StepResult::new(id, StepStatus::Completed);
+            result.started_at = Some(started_at);
+            result.finished_at = Some(current_timestamp());
+            match outcome {
+                Ok(output) => result.output = Some(output),
+                Err(error) => {
+                    result.status = StepStatus::Failed;
+                    result.error = Some(error);
+                }
+            }
+            if let Some((store, state)) = checkpoint.as_mut() {
+                if let Err(e) = store.record_step(state, &result) {
+                    tracing::warn!("保存步骤 {} 的执行状态失败: {}", id, e);
+                }
+            }
+            self.emit(id, result.status);
+            results.insert(id, result);
+        }
+
+        let mut report = ExecutionReport::default();
+        for result in results.into_values() {
+            match result.status {
+                StepStatus::Completed => report.completed.push(result.step),
+                StepStatus::Failed => report.failed.push(result.step),
+                StepStatus::Skipped => report.skipped.push(result.step),
+                StepStatus::Pending | StepStatus::Running => {}
+            }
+            report.results.push(result);
+        }
+        Ok(report)
+    }
+
+    /// 跳过依赖失败或被跳过的步骤（级联）
+    fn skip_blocked(
+        &self,
+        pending: &mut BTreeMap<u32, &PlanStep>,
+        results: &mut BTreeMap<u32, StepResult>,
+    ) {
+        loop {
+            let blocked: Vec<(u32, Vec<u32>)> = pending
+                .values()
+                .filter_map(|step| {
+                    let blocked_by: Vec<u32> = step
+                        .dependencies
+                        .iter()
+                        .copied()
+                        .filter(|dep| {
+                            results.get(dep).is_some_and(|r| {
+                                matches!(r.status, StepStatus::Failed | StepStatus::Skipped)
+                            })
+                        })
+                        .collect();
+                    (!blocked_by.is_empty()).then_some((step.step, blocked_by))
+                })
+                .collect();
+            if blocked.is_empty() {
+                return;
+            }
+
+            for (id, blocked_by) in blocked {
+                pending.remove(&id);
+                let mut result = StepResult::new(id, StepStatus::Skipped);
+                result.blocked_by = blocked_by;
+                results.insert(id, result);
+                self.emit(id, StepStatus::Skipped);
+            }
+        }
+    }
+}
+
+fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -1,13 +1,17 @@
 //! Plan 模块
 //!
-//! 提供计划持久化、版本控制和多方案对比功能
+//! 提供计划持久化、版本控制、多方案对比、按依赖执行和断点续跑功能
 
 mod comparison;
+mod executor;
 mod persistence;
+mod run_state;
 mod types;
 
 pub use comparison::*;
+pub use executor::*;
 pub use persistence::*;
+pub use run_state::*;
 pub use types::*;
 
 #[cfg(test)]
@@ -0,0 +1,166 @@
+//! 计划执行状态持久化
+//!
+//! 记录每个步骤的执行结果，使长时间运行的计划在中断后可以从断点继续。
+//! 每完成一个步骤就写一次状态文件，先写临时文件并刷盘再重命名，
+//! 进程在步骤中途崩溃或机器断电都不会损坏已有记录。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::executor::{StepResult, StepStatus};
+use super::types::PlanStep;
+
+/// 一次计划执行的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanRunState {
+    pub plan_id: String,
+    /// 开始执行时的步骤快照，恢复时按此执行
+    pub steps: Vec<PlanStep>,
+    /// 已结束步骤的结果，按步骤编号索引
+    #[serde(default)]
+    pub results: BTreeMap<u32, StepResult>,
+    pub started_at: u64,
+    pub updated_at: u64,
+}
+
+impl PlanRunState {
+    /// 创建新的执行状态
+    pub fn new(plan_id: impl Into<String>, steps: Vec<PlanStep>) -> Self {
+        let now = current_timestamp();
+        Self {
+            plan_id: plan_id.into(),
+            steps,
+            results: BTreeMap::new(),
+            started_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// 已完成步骤的结果，恢复执行时这些步骤不会重跑
+    pub fn completed(&self) -> BTreeMap<u32, StepResult> {
+        self.results
+            .iter()
+            .filter(|(_, r)| r.status == StepStatus::Completed)
+            .map(|(id, r)| (*id, r.clone()))
+            .collect()
+    }
+
+    /// 是否所有步骤都已完成
+    pub fn is_finished(&self) -> bool {
+        self.steps.iter().all(|s| {
+            self.results
+                .get(&s.step)
+                .is_some_and(|r| r.status == StepStatus::Completed)
+        })
+    }
+}
+
+/// 执行状态存储
+#[derive(Debug, Clone)]
+pub struct PlanRunStore {
+    dir: PathBuf,
+}
+
+impl Default for PlanRunStore {
+    fn default() -> Self {
+        Self::new(
+            dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".aster")
+                .join("plan-runs"),
+        )
+    }
+}
+
+impl PlanRunStore {
+    /// 使用指定目录创建存储
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// 存储目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn state_path(&self, plan_id: &str) -> Result<PathBuf, String> {
+        validate_plan_id(plan_id)?;
+        Ok(self.dir.join(format!("{}.json", plan_id)))
+    }
+
+    /// 加载执行状态，不存在时返回 None
+    pub fn load(&self, plan_id: &str) -> Result<Option<PlanRunState>, String> {
+        let path = self.state_path(plan_id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read_to_string(&path).map_err(|e| format!("读取执行状态失败: {}", e))?;
+        serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| format!("解析执行状态失败: {}", e))
+    }
+
+    /// 原子地保存执行状态
+    pub fn save(&self, state: &PlanRunState) -> Result<(), String> {
+        let path = self.state_path(&state.plan_id)?;
+        fs::create_dir_all(&self.dir).map_err(|e| format!("创建执行状态目录失败: {}", e))?;
+
+        let data = serde_json::to_string_pretty(state)
+            .map_err(|e| format!("序列化执行状态失败: {}", e))?;
+        let tmp_path = self.dir.join(format!(".{}.json.tmp", state.plan_id));
+        let write = || -> std::io::Result<()> {
+            let mut file = File::create(&tmp_path)?;
+            file.write_all(data.as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&tmp_path, &path)?;
+            // 刷新目录项，确保重命名本身也已落盘
+            #[cfg(unix)]
+            File::open(&self.dir)?.sync_all()?;
+            Ok(())
+        };
+        write().map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            format!("写入执行状态失败: {}", e)
+        })
+    }
+
+    /// 记录一个步骤的结果
+    pub fn record_step(&self, state: &mut PlanRunState, result: &StepResult) -> Result<(), String> {
+        state.results.insert(result.step, result.clone());
+        state.updated_at = current_timestamp();
+        self.save(state)
+    }
+
+    /// 删除执行状态
+    pub fn remove(&self, plan_id: &str) -> Result<(), String> {
+        let path = self.state_path(plan_id)?;
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("删除执行状态失败: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// 校验计划 ID，只允许字母、数字、`-` 和 `_`，防止路径穿越
+fn validate_plan_id(plan_id: &str) -> Result<(), String> {
+    let valid = !plan_id.is_empty()
+        && plan_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的计划 ID: {}", plan_id))
+    }
+}
+
+fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -241,3 +241,218 @@ fn test_generate_comparison_report() {
     let _ = PlanPersistenceManager::delete_plan(&id1, true);
     let _ = PlanPersistenceManager::delete_plan(&id2, true);
 }
+
+// ============ Executor Tests ============
+
+fn dag_step(step: u32, dependencies: Vec<u32>) -> PlanStep {
+    PlanStep {
+        step,
+        description: format!("Step {}", step),
+        files: vec![],
+        complexity: StepComplexity::Low,
+        dependencies,
+        estimated_minutes: None,
+        risks: None,
+        status: None,
+        actual_minutes: None,
+        completed_at: None,
+    }
+}
+
+/// 记录最大并发数，指定步骤失败
+struct TrackingRunner {
+    fail: u32,
+    running: std::sync::atomic::AtomicUsize,
+    max_running: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl StepRunner for TrackingRunner {
+    async fn run_step(&self, step: &PlanStep) -> Result<String, String> {
+        use std::sync::atomic::Ordering;
+
+        let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+
+        if step.step == self.fail {
+            Err("boom".to_string())
+        } else {
+            Ok(format!("done {}", step.step))
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_executor_runs_branches_in_parallel_and_skips_dependents() {
+    //      1
+    //    /   \
+    //   2     3
+    //   |     |
+    //   4     5
+    //    \   /
+    //      6
+    let mut steps = vec![
+        dag_step(1, vec![]),
+        dag_step(2, vec![1]),
+        dag_step(3, vec![1]),
+        dag_step(4, vec![2]),
+        dag_step(5, vec![3]),
+        dag_step(6, vec![4, 5]),
+    ];
+    let runner = std::sync::Arc::new(TrackingRunner {
+        fail: 2,
+        running: Default::default(),
+        max_running: Default::default(),
+    });
+    let executor = PlanExecutor::new(runner.clone());
+    let mut events = executor.subscribe();
+
+    let report = executor.execute(&steps).await.unwrap();
+
+    assert_eq!(report.completed, vec![1, 3, 5]);
+    assert_eq!(report.failed, vec![2]);
+    assert_eq!(report.skipped, vec![4, 6]);
+    assert!(!report.is_success());
+    assert_eq!(report.get(2).unwrap().error.as_deref(), Some("boom"));
+    assert_eq!(report.get(4).unwrap().blocked_by, vec![2]);
+    assert_eq!(report.get(6).unwrap().blocked_by, vec![4]);
+    assert_eq!(report.get(5).unwrap().output.as_deref(), Some("done 5"));
+    // 2 和 3 同时运行
+    assert_eq!(
+        runner.max_running.load(std::sync::atomic::Ordering::SeqCst),
+        2
+    );
+
+    let mut running = 0;
+    while let Ok(event) = events.try_recv() {
+        if event.status == StepStatus::Running {
+            running += 1;
+        }
+    }
+    assert_eq!(running, 4);
+
+    report.apply_to(&mut steps);
+    assert_eq!(steps[0].status.as_deref(), Some("completed"));
+    assert!(steps[0].completed_at.is_some());
+    assert_eq!(steps[3].status.as_deref(), Some("skipped"));
+}
+
+#[tokio::test]
+async fn test_executor_rejects_invalid_dependencies() {
+    let runner = std::sync::Arc::new(TrackingRunner {
+        fail: 0,
+        running: Default::default(),
+        max_running: Default::default(),
+    });
+    let executor = PlanExecutor::new(runner).with_max_parallel(1);
+
+    let cyclic = vec![
+        dag_step(1, vec![2]),
+        dag_step(2, vec![1]),
+        dag_step(3, vec![]),
+    ];
+    let err = executor.execute(&cyclic).await.unwrap_err();
+    assert!(err.contains("[1, 2]"));
+
+    let missing = vec![dag_step(1, vec![9])];
+    assert!(executor.execute(&missing).await.is_err());
+
+    let chain = vec![dag_step(1, vec![]), dag_step(2, vec![1])];
+    let report = executor.execute(&chain).await.unwrap();
+    assert!(report.is_success());
+
+    // 重复声明的依赖不是环
+    let duplicated = vec![dag_step(1, vec![]), dag_step(2, vec![1, 1])];
+    assert!(validate_dependencies(&duplicated).is_ok());
+    let report = executor.execute(&duplicated).await.unwrap();
+    assert_eq!(report.completed, vec![1, 2]);
+}
+
+/// 记录执行过的步骤，指定步骤永远不结束（模拟进程在该步骤中途崩溃）
+struct CrashingRunner {
+    hang_on: u32,
+    calls: std::sync::Mutex<Vec<u32>>,
+}
+
+#[async_trait::async_trait]
+impl StepRunner for CrashingRunner {
+    async fn run_step(&self, step: &PlanStep) -> Result<String, String> {
+        self.calls.lock().unwrap().push(step.step);
+        if step.step == self.hang_on {
+            std::future::pending::<()>().await;
+        }
+        Ok(format!("done {}", step.step))
+    }
+}
+
+#[tokio::test]
+async fn test_resume_plan_skips_completed_steps() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = PlanRunStore::new(temp_dir.path());
+    let steps = vec![
+        dag_step(1, vec![]),
+        dag_step(2, vec![1]),
+        dag_step(3, vec![2]),
+        dag_step(4, vec![3]),
+    ];
+
+    // 第一次执行在步骤 3 中途“崩溃”
+    let crashing = std::sync::Arc::new(CrashingRunner {
+        hang_on: 3,
+        calls: Default::default(),
+    });
+    let executor = PlanExecutor::new(crashing.clone()).with_run_store(store.clone());
+    let outcome = tokio::time::timeout(
+        std::time::Duration::from_millis(200),
+        executor.execute_plan("plan-resume", &steps),
+    )
+    .await;
+    assert!(outcome.is_err());
+    assert_eq!(*crashing.calls.lock().unwrap(), vec![1, 2, 3]);
+
+    let state = store.load("plan-resume").unwrap().unwrap();
+    assert_eq!(
+        state.completed().keys().copied().collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert!(!state.is_finished());
+    // 没有残留的临时文件
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+    // 恢复后只执行剩余步骤
+    let runner = std::sync::Arc::new(CrashingRunner {
+        hang_on: 0,
+        calls: Default::default(),
+    });
+    let executor = PlanExecutor::new(runner.clone()).with_run_store(store.clone());
+    let report = executor.resume_plan("plan-resume").await.unwrap();
+
+    assert_eq!(*runner.calls.lock().unwrap(), vec![3, 4]);
+    assert_eq!(report.completed, vec![1, 2, 3, 4]);
+    assert!(report.is_success());
+    assert_eq!(report.get(1).unwrap().output.as_deref(), Some("done 1"));
+    assert!(store.load("plan-resume").unwrap().unwrap().is_finished());
+}
+
+#[tokio::test]
+async fn test_run_store_rejects_path_traversal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = PlanRunStore::new(temp_dir.path().join("runs"));
+
+    for plan_id in ["../escaped", "a/b", "", ".."] {
+        let state = PlanRunState::new(plan_id, vec![dag_step(1, vec![])]);
+        assert!(store.save(&state).is_err());
+        assert!(store.load(plan_id).is_err());
+        assert!(store.remove(plan_id).is_err());
+    }
+    assert!(!temp_dir.path().join("escaped.json").exists());
+
+    let executor = PlanExecutor::new(std::sync::Arc::new(CrashingRunner {
+        hang_on: 0,
+        calls: Default::default(),
+    }))
+    .with_run_store(store);
+    assert!(executor.resume_plan("../escaped").await.is_err());
+}
@@ -85,6 +85,11 @@ fn load_or_create_installation() -> InstallationData {
     data
 }
 
+/// Stable identifier for this installation, created on first use
+pub fn installation_id() -> String {
+    load_or_create_installation().installation_id
+}
+
 fn save_installation(data: &InstallationData) {
     let path = installation_file_path();
 
@@ -4,22 +4,185 @@
 
 use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
 use std::time::Instant;
 
 use super::templates::{
     get_diagnostics_info, get_git_status_info, get_ide_info, get_memory_info, get_todo_list_info,
 };
-use super::types::{Attachment, AttachmentType, GitStatusInfo, PromptContext};
+use super::types::{
+    image_data_url, Attachment, AttachmentLimits, AttachmentTooLargeError, AttachmentType,
+    GitStatusInfo, NonVisionImagePolicy, PromptBuildError, PromptContext,
+    IMAGE_OMITTED_PLACEHOLDER,
+};
+use crate::media::resize_image_to_fit;
+use crate::providers::capabilities::known_capabilities;
+
+/// OCR 处理函数，参数为 MIME 类型和图片数据，返回识别出的文字
+pub type ImageOcr = Arc<dyn Fn(&str, &[u8]) -> Result<String, String> + Send + Sync>;
 
 /// 附件管理器
 pub struct AttachmentManager {
     telemetry_enabled: bool,
+    limits: AttachmentLimits,
+    image_policy: NonVisionImagePolicy,
+    ocr: Option<ImageOcr>,
 }
 
 impl AttachmentManager {
     /// 创建新的附件管理器
     pub fn new(telemetry_enabled: bool) -> Self {
-        Self { telemetry_enabled }
+        Self {
+            telemetry_enabled,
+            limits: AttachmentLimits::default(),
+            image_policy: NonVisionImagePolicy::default(),
+            ocr: None,
+        }
+    }
+
+    /// 设置附件大小限制
+    pub fn with_limits(mut self, limits: AttachmentLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 获取附件大小限制
+    pub fn limits(&self) -> &AttachmentLimits {
+        &self.limits
+    }
+
+    /// 设置模型不支持视觉时的图片处理方式
+    pub fn with_image_policy(mut self, policy: NonVisionImagePolicy) -> Self {
+        self.image_policy = policy;
+        self
+    }
+
+    /// 设置 OCR 处理函数，供 `NonVisionImagePolicy::Ocr` 使用
+    pub fn with_ocr(mut self, ocr: ImageOcr) -> Self {
+        self.ocr = Some(ocr);
+        self
+    }
+
+    /// 按模型能力调整附件
+    ///
+    /// 已知模型不支持视觉时，按 `image_policy` 将图片替换为占位符或 OCR 文本，或直接返回错误；
+    /// 未指定模型或模型未知时原样返回，由 provider 决定如何处理图片
+    pub fn adapt_for_model(
+        &self,
+        attachments: Vec<Attachment>,
+        model: Option<&str>,
+    ) -> Result<Vec<Attachment>, PromptBuildError> {
+        let Some(model) = model else {
+            return Ok(attachments);
+        };
+        if known_capabilities(model).is_none_or(|caps| caps.supports_vision) {
+            return Ok(attachments);
+        }
+
+        attachments
+            .into_iter()
+            .map(|attachment| {
+                if attachment.attachment_type == AttachmentType::Image {
+                    self.replace_image(attachment, model)
+                } else {
+                    Ok(attachment)
+                }
+            })
+            .collect()
+    }
+
+    fn replace_image(
+        &self,
+        attachment: Attachment,
+        model: &str,
+    ) -> Result<Attachment, PromptBuildError> {
+        let label = attachment.label.as_deref().unwrap_or("image");
+        let content = match self.image_policy {
+            NonVisionImagePolicy::Error => {
+                return Err(PromptBuildError::UnsupportedAttachment(format!(
+                    "{}: model {} has no vision support",
+                    label, model
+                )));
+            }
+            NonVisionImagePolicy::Drop => IMAGE_OMITTED_PLACEHOLDER.to_string(),
+            NonVisionImagePolicy::Ocr => self
+                .ocr_text(&attachment)
+                .unwrap_or_else(|| IMAGE_OMITTED_PLACEHOLDER.to_string()),
+        };
+
+        Ok(Attachment {
+            attachment_type: AttachmentType::Custom,
+            content,
+            ..attachment
+        })
+    }
+
+    /// 未配置 OCR 或识别失败时返回 None
+    fn ocr_text(&self, attachment: &Attachment) -> Option<String> {
+        let ocr = self.ocr.as_ref()?;
+        let (mime_type, data) = attachment.image_data()?;
+        let label = attachment.label.as_deref().unwrap_or("image");
+        match ocr(&mime_type, &data) {
+            Ok(text) => Some(format!(
+                "[text extracted from image {} by OCR]\n{}",
+                label, text
+            )),
+            Err(e) => {
+                tracing::warn!("OCR failed for {}: {}", label, e);
+                None
+            }
+        }
+    }
+
+    /// 对附件应用大小限制
+    ///
+    /// 超出尺寸的图片会先等比缩放；缩放后仍超限或非图片附件超限时返回错误
+    pub fn apply_limits(
+        &self,
+        attachments: Vec<Attachment>,
+    ) -> Result<Vec<Attachment>, PromptBuildError> {
+        attachments
+            .into_iter()
+            .map(|attachment| self.apply_limit(attachment))
+            .collect()
+    }
+
+    fn apply_limit(&self, attachment: Attachment) -> Result<Attachment, PromptBuildError> {
+        let attachment = match attachment.image_data() {
+            Some((_, data)) => {
+                let resized =
+                    resize_image_to_fit(&data, self.limits.max_image_dimension).map_err(|e| {
+                        PromptBuildError::InvalidAttachment(format!(
+                            "{}: {}",
+                            attachment.label.as_deref().unwrap_or("image"),
+                            e
+                        ))
+                    })?;
+                if resized.resized {
+                    Attachment {
+                        content: image_data_url(&resized.mime_type, &resized.data),
+                        ..attachment
+                    }
+                } else {
+                    attachment
+                }
+            }
+            None => attachment,
+        };
+
+        let size_bytes = attachment.size_bytes();
+        let max_bytes = self.limits.limit_for(attachment.attachment_type);
+        if size_bytes > max_bytes {
+            return Err(AttachmentTooLargeError {
+                attachment_type: attachment.attachment_type,
+                label: attachment.label.clone(),
+                size_bytes,
+                max_bytes,
+            }
+            .into());
+        }
+
+        Ok(attachment)
     }
 
     /// 生成所有附件
@@ -11,7 +11,8 @@ use super::templates::{
     CORE_IDENTITY, GIT_GUIDELINES, OUTPUT_STYLE, SUBAGENT_SYSTEM, TASK_MANAGEMENT, TOOL_GUIDELINES,
 };
 use super::types::{
-    Attachment, BuildResult, PermissionMode, PromptContext, PromptTooLongError, SystemPromptOptions,
+    AttachmentType, BuildResult, DroppedSection, PermissionMode, PromptBuildError, PromptContext,
+    PromptSection, PromptTooLongError, SectionPriority, SystemPromptOptions,
 };
 
 /// 系统提示词构建器
@@ -49,7 +50,7 @@ impl SystemPromptBuilder {
         &mut self,
         context: &PromptContext,
         options: Option<SystemPromptOptions>,
-    ) -> Result<BuildResult, PromptTooLongError> {
+    ) -> Result<BuildResult, PromptBuildError> {
         let start_time = Instant::now();
         let opts = options.unwrap_or_default();
 
@@ -74,24 +75,50 @@ impl SystemPromptBuilder {
                     hash_info,
                     attachments: vec![],
                     truncated: false,
+                    dropped_sections: vec![],
+                    attachment_bytes: 0,
+                    attachment_tokens: 0,
                     build_time_ms: start_time.elapsed().as_millis() as u64,
                 });
             }
         }
 
-        // 生成附件
-        let attachments = self.attachment_manager.generate_attachments(context);
+        // 生成附件，按模型能力调整后应用大小限制
+        let attachments = self.attachment_manager.adapt_for_model(
+            self.attachment_manager.generate_attachments(context),
+            context.model.as_deref(),
+        )?;
+        let attachments = self.attachment_manager.apply_limits(attachments)?;
+        let attachment_bytes = attachments.iter().map(|a| a.size_bytes()).sum();
+        let attachment_tokens = attachments
+            .iter()
+            .map(|a| match a.attachment_type {
+                AttachmentType::Image => {
+                    let base64 = a.content.split_once(',').map_or("", |(_, data)| data);
+                    crate::media::estimate_image_tokens(base64) as usize
+                }
+                _ => estimate_tokens(&a.content),
+            })
+            .sum();
 
         // 构建各个部分
-        let mut parts: Vec<String> = Vec::new();
+        let mut sections: Vec<BudgetSection> = Vec::new();
+        let mut push = |section: PromptSection, content: String| {
+            sections.push(BudgetSection {
+                name: section.name().to_string(),
+                priority: opts.priority_of(section),
+                content,
+            });
+        };
 
         // 1. 核心身份
         if opts.include_identity {
-            parts.push(CORE_IDENTITY.to_string());
+            push(PromptSection::CoreIdentity, CORE_IDENTITY.to_string());
         }
 
         // 2. 帮助信息
-        parts.push(
+        push(
+            PromptSection::Help,
             "If the user asks for help or wants to give feedback inform them of the following:\n\
              - /help: Get help with using the agent\n\
              - To give feedback, users should report the issue at the project repository"
@@ -99,24 +126,27 @@ impl SystemPromptBuilder {
         );
 
         // 3. 输出风格
-        parts.push(OUTPUT_STYLE.to_string());
+        push(PromptSection::OutputStyle, OUTPUT_STYLE.to_string());
 
         // 4. 任务管理
-        parts.push(TASK_MANAGEMENT.to_string());
+        push(PromptSection::TaskManagement, TASK_MANAGEMENT.to_string());
 
         // 5. 代码编写指南
-        parts.push(CODING_GUIDELINES.to_string());
+        push(
+            PromptSection::CodingGuidelines,
+            CODING_GUIDELINES.to_string(),
+        );
 
         // 6. 工具使用指南
         if opts.include_tool_guidelines {
-            parts.push(TOOL_GUIDELINES.to_string());
+            push(PromptSection::ToolGuidelines, TOOL_GUIDELINES.to_string());
         }
 
         // 7. Git 操作指南
-        parts.push(GIT_GUIDELINES.to_string());
+        push(PromptSection::GitGuidelines, GIT_GUIDELINES.to_string());
 
         // 8. 子代理系统
-        parts.push(SUBAGENT_SYSTEM.to_string());
+        push(PromptSection::SubagentSystem, SUBAGENT_SYSTEM.to_string());
 
         // 9. 权限模式
         if opts.include_permission_mode {
@@ -129,7 +159,10 @@ impl SystemPromptBuilder {
                     PermissionMode::Delegate => "delegate",
                     PermissionMode::DontAsk => "dont_ask",
                 };
-                parts.push(get_permission_mode_description(mode_str).to_string());
+                push(
+                    PromptSection::PermissionMode,
+                    get_permission_mode_description(mode_str).to_string(),
+                );
             }
         }
 
@@ -141,33 +174,27 @@ impl SystemPromptBuilder {
             today_date: context.today_date.as_deref().unwrap_or("unknown"),
             model: context.model.as_deref(),
         };
-        parts.push(get_environment_info(&env_info));
+        push(PromptSection::Environment, get_environment_info(&env_info));
 
         // 11. 附件内容
+        // 图片附件以独立内容块发送，不计入文本段落
         for attachment in &attachments {
-            if !attachment.content.is_empty() {
-                parts.push(attachment.content.clone());
+            if !attachment.content.is_empty() && attachment.attachment_type != AttachmentType::Image
+            {
+                sections.push(BudgetSection {
+                    name: attachment
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| format!("{:?}", attachment.attachment_type)),
+                    priority: attachment.section_priority(),
+                    content: attachment.content.clone(),
+                });
             }
         }
 
-        // 组装完整提示词
-        let mut content = parts.join("\n\n");
-
-        // 检查长度限制
-        let mut truncated = false;
-        let estimated_tokens = estimate_tokens(&content);
-
-        if estimated_tokens > opts.max_tokens {
-            // 尝试截断附件
-            content = self.truncate_to_limit(&parts, &attachments, opts.max_tokens);
-            truncated = true;
-
-            // 再次检查
-            let final_tokens = estimate_tokens(&content);
-            if final_tokens > opts.max_tokens {
-                return Err(PromptTooLongError::new(final_tokens, opts.max_tokens));
-            }
-        }
+        // 组装完整提示词，超出预算时按优先级丢弃或截断段落
+        let (content, dropped_sections) = self.fit_to_budget(sections, opts.max_tokens)?;
+        let truncated = !dropped_sections.is_empty();
 
         // 计算哈希
         let hash_info = self.cache.compute_hash(&content);
@@ -196,51 +223,125 @@ impl SystemPromptBuilder {
             );
         }
 
+        if self.debug && truncated {
+            for dropped in &dropped_sections {
+                eprintln!(
+                    "[SystemPromptBuilder] {} section '{}' ({} tokens)",
+                    if dropped.truncated {
+                        "Truncated"
+                    } else {
+                        "Dropped"
+                    },
+                    dropped.name,
+                    dropped.original_tokens
+                );
+            }
+        }
+
         Ok(BuildResult {
             content,
             hash_info,
             attachments,
             truncated,
+            dropped_sections,
+            attachment_bytes,
+            attachment_tokens,
             build_time_ms,
         })
     }
 
-    /// 截断到限制
-    fn truncate_to_limit(
+    /// 按优先级将段落装入 token 预算
+    ///
+    /// 从最低优先级（同优先级从后往前）开始处理：若丢弃该段落后仍有足够空间，
+    /// 则截断它以尽量保留内容，否则整段丢弃。`Required` 段落本身超出预算时返回错误。
+    fn fit_to_budget(
         &self,
-        parts: &[String],
-        _attachments: &[Attachment],
+        sections: Vec<BudgetSection>,
         max_tokens: usize,
-    ) -> String {
-        // 优先保留核心部分
-        let core_parts: Vec<&String> = parts.iter().take(7).collect();
-        let remaining_parts: Vec<&String> = parts.iter().skip(7).collect();
+    ) -> Result<(String, Vec<DroppedSection>), PromptTooLongError> {
+        let content = join_sections(sections.iter().map(|s| s.content.as_str()));
+        if estimate_tokens(&content) <= max_tokens {
+            return Ok((content, Vec::new()));
+        }
 
-        // 计算核心部分的 tokens
-        let mut content = core_parts
-            .iter()
-            .map(|s| s.as_str())
-            .collect::<Vec<_>>()
-            .join("\n\n");
-        let mut current_tokens = estimate_tokens(&content);
-
-        // 添加剩余部分直到接近限制
-        let reserve_tokens = max_tokens / 10; // 保留 10% 空间
-        let target_tokens = max_tokens - reserve_tokens;
-
-        for part in remaining_parts {
-            let part_tokens = estimate_tokens(part);
-            if current_tokens + part_tokens < target_tokens {
-                content.push_str("\n\n");
-                content.push_str(part);
-                current_tokens += part_tokens;
-            }
+        let required = join_sections(
+            sections
+                .iter()
+                .filter(|s| s.priority == SectionPriority::Required)
+                .map(|s| s.content.as_str())
+                .chain(std::iter::once(TRUNCATION_REMINDER)),
+        );
+        let required_tokens = estimate_tokens(&required);
+        if required_tokens > max_tokens {
+            return Err(PromptTooLongError::new(required_tokens, max_tokens));
         }
 
-        // 添加截断提示
-        content.push_str("\n\n<system-reminder>\nSome context was truncated due to length limits. Use tools to gather additional information as needed.\n</system-reminder>");
+        let mut order: Vec<usize> = (0..sections.len())
+            .filter(|&i| sections[i].priority != SectionPriority::Required)
+            .collect();
+        order.sort_by(|&a, &b| {
+            sections[a]
+                .priority
+                .cmp(&sections[b].priority)
+                .then_with(|| b.cmp(&a))
+        });
+
+        let mut kept: Vec<Option<String>> =
+            sections.iter().map(|s| Some(s.content.clone())).collect();
+        let assemble = |kept: &[Option<String>]| {
+            join_sections(
+                kept.iter()
+                    .flatten()
+                    .map(|s| s.as_str())
+                    .chain(std::iter::once(TRUNCATION_REMINDER)),
+            )
+        };
 
-        content
+        let mut dropped = Vec::new();
+        for idx in order {
+            if estimate_tokens(&assemble(&kept)) <= max_tokens {
+                break;
+            }
+
+            let section = &sections[idx];
+            let original_tokens = estimate_tokens(&section.content);
+            kept[idx] = None;
+            let remaining = estimate_tokens(&assemble(&kept));
+
+            let mut truncated = false;
+            if remaining + MIN_TRUNCATED_SECTION_TOKENS <= max_tokens {
+                let room = max_tokens - remaining;
+                let ratio = room as f64 / original_tokens.max(1) as f64;
+                let mut keep_chars = (section.content.chars().count() as f64 * ratio) as usize;
+                while keep_chars > 0 {
+                    let candidate = truncate_chars(&section.content, keep_chars);
+                    kept[idx] = Some(candidate);
+                    if estimate_tokens(&assemble(&kept)) <= max_tokens {
+                        truncated = true;
+                        break;
+                    }
+                    kept[idx] = None;
+                    keep_chars = keep_chars * 9 / 10;
+                }
+            }
+
+            dropped.push(DroppedSection {
+                name: section.name.clone(),
+                priority: section.priority,
+                original_tokens,
+                truncated,
+            });
+            if truncated {
+                break;
+            }
+        }
+
+        let content = assemble(&kept);
+        let final_tokens = estimate_tokens(&content);
+        if final_tokens > max_tokens {
+            return Err(PromptTooLongError::new(final_tokens, max_tokens));
+        }
+        Ok((content, dropped))
     }
 
     /// 获取提示词预览
@@ -289,6 +390,32 @@ impl SystemPromptBuilder {
     }
 }
 
+/// 参与预算分配的段落
+struct BudgetSection {
+    name: String,
+    priority: SectionPriority,
+    content: String,
+}
+
+/// 截断后追加的提醒
+const TRUNCATION_REMINDER: &str = "<system-reminder>\nSome context was truncated due to length limits. Use tools to gather additional information as needed.\n</system-reminder>";
+
+/// 截断后段落的最小空间，不足时直接丢弃
+const MIN_TRUNCATED_SECTION_TOKENS: usize = 64;
+
+fn join_sections<'a>(parts: impl Iterator<Item = &'a str>) -> String {
+    parts.collect::<Vec<_>>().join("\n\n")
+}
+
+fn truncate_chars(content: &str, max_chars: usize) -> String {
+    let end = content
+        .char_indices()
+        .nth(max_chars)
+        .map(|(i, _)| i)
+        .unwrap_or(content.len());
+    format!("{}\n[... truncated]", content.get(..end).unwrap_or(content))
+}
+
 impl Default for SystemPromptBuilder {
     fn default() -> Self {
         Self::new(false)
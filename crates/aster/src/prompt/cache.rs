@@ -4,6 +4,7 @@
 
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use super::types::PromptHashInfo;
@@ -62,11 +63,30 @@ pub fn estimate_tokens(text: &str) -> usize {
     tokens.ceil() as usize
 }
 
+/// 缓存条目来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheEntryOrigin {
+    /// 通过 warmup 预热写入
+    Warmup,
+    /// 运行时按需写入
+    Runtime,
+}
+
 /// 缓存条目
 struct CacheEntry {
     content: String,
     hash_info: PromptHashInfo,
     expires_at: Instant,
+    origin: CacheEntryOrigin,
+}
+
+/// 预热结果
+#[derive(Debug, Clone, Default)]
+pub struct WarmupReport {
+    /// 成功预热的键
+    pub warmed: Vec<String>,
+    /// 预热失败的键及原因
+    pub failed: Vec<(String, String)>,
 }
 
 /// 提示词缓存
@@ -74,6 +94,7 @@ pub struct PromptCache {
     cache: HashMap<String, CacheEntry>,
     ttl: Duration,
     max_entries: usize,
+    token_budget: Option<usize>,
 }
 
 impl PromptCache {
@@ -83,7 +104,57 @@ impl PromptCache {
             cache: HashMap::new(),
             ttl: Duration::from_millis(ttl_ms.unwrap_or(5 * 60 * 1000)), // 5 分钟
             max_entries: max_entries.unwrap_or(100),
+            token_budget: None,
+        }
+    }
+
+    /// 设置预热条目的 token 预算，超出预算的条目不会被预热
+    pub fn with_token_budget(mut self, max_tokens: usize) -> Self {
+        self.token_budget = Some(max_tokens);
+        self
+    }
+
+    /// 预热缓存
+    ///
+    /// 依次调用每个构建函数并写入缓存。单个条目构建失败或超出 token 预算
+    /// 只记录到报告中，不影响其余条目。
+    pub fn warmup<I, F, E>(&mut self, keys_and_builders: I) -> WarmupReport
+    where
+        I: IntoIterator<Item = (String, F)>,
+        F: FnOnce() -> Result<String, E>,
+        E: Display,
+    {
+        let mut report = WarmupReport::default();
+
+        for (key, build) in keys_and_builders {
+            let content = match build() {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::warn!("Prompt cache warmup failed for {}: {}", key, e);
+                    report.failed.push((key, e.to_string()));
+                    continue;
+                }
+            };
+
+            let hash_info = self.compute_hash(&content);
+            if let Some(budget) = self.token_budget {
+                if hash_info.estimated_tokens > budget {
+                    report.failed.push((
+                        key,
+                        format!(
+                            "Prompt is {} tokens, exceeding the {} token budget",
+                            hash_info.estimated_tokens, budget
+                        ),
+                    ));
+                    continue;
+                }
+            }
+
+            self.insert(key.clone(), content, hash_info, CacheEntryOrigin::Warmup);
+            report.warmed.push(key);
         }
+
+        report
     }
 
     /// 计算提示词哈希
@@ -126,11 +197,28 @@ impl PromptCache {
         content: String,
         hash_info: Option<PromptHashInfo>,
     ) -> PromptHashInfo {
+        let computed_hash_info = hash_info.unwrap_or_else(|| self.compute_hash(&content));
+        self.insert(
+            key,
+            content,
+            computed_hash_info.clone(),
+            CacheEntryOrigin::Runtime,
+        );
+        computed_hash_info
+    }
+
+    fn insert(
+        &mut self,
+        key: String,
+        content: String,
+        hash_info: PromptHashInfo,
+        origin: CacheEntryOrigin,
+    ) {
         // 清理过期条目
         self.cleanup();
 
         // 检查容量
-        if self.cache.len() >= self.max_entries {
+        if !self.cache.contains_key(&key) && self.cache.len() >= self.max_entries {
             // 删除最旧的条目
             if let Some(oldest_key) = self
                 .cache
@@ -142,18 +230,15 @@ impl PromptCache {
             }
         }
 
-        let computed_hash_info = hash_info.unwrap_or_else(|| self.compute_hash(&content));
-
         self.cache.insert(
             key,
             CacheEntry {
                 content,
-                hash_info: computed_hash_info.clone(),
+                hash_info,
                 expires_at: Instant::now() + self.ttl,
+                origin,
             },
         );
-
-        computed_hash_info
     }
 
     /// 检查缓存是否有效
@@ -190,9 +275,13 @@ impl PromptCache {
         let mut total_bytes = 0;
         let mut oldest_entry: Option<u64> = None;
         let mut newest_entry: Option<u64> = None;
+        let mut warmed_entries = 0;
 
         for entry in self.cache.values() {
             total_bytes += entry.content.len();
+            if entry.origin == CacheEntryOrigin::Warmup {
+                warmed_entries += 1;
+            }
             let computed_at = entry.hash_info.computed_at;
 
             match oldest_entry {
@@ -213,6 +302,8 @@ impl PromptCache {
             total_bytes,
             oldest_entry,
             newest_entry,
+            warmed_entries,
+            runtime_entries: self.cache.len() - warmed_entries,
         }
     }
 }
@@ -230,6 +321,10 @@ pub struct CacheStats {
     pub total_bytes: usize,
     pub oldest_entry: Option<u64>,
     pub newest_entry: Option<u64>,
+    /// 预热写入的条目数
+    pub warmed_entries: usize,
+    /// 运行时写入的条目数
+    pub runtime_entries: usize,
 }
 
 /// 生成缓存键
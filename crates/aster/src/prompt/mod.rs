@@ -16,16 +16,20 @@ pub mod types;
 mod tests;
 
 // Re-exports
-pub use attachments::AttachmentManager;
+pub use attachments::{AttachmentManager, ImageOcr};
 pub use builder::SystemPromptBuilder;
-pub use cache::{estimate_tokens, generate_cache_key, CacheStats, PromptCache};
+pub use cache::{
+    estimate_tokens, generate_cache_key, CacheEntryOrigin, CacheStats, PromptCache, WarmupReport,
+};
 pub use templates::{
     get_diagnostics_info, get_environment_info, get_git_status_info, get_ide_info, get_memory_info,
     get_permission_mode_description, get_todo_list_info, EnvironmentInfo, CODING_GUIDELINES,
     CORE_IDENTITY, GIT_GUIDELINES, OUTPUT_STYLE, SUBAGENT_SYSTEM, TASK_MANAGEMENT, TOOL_GUIDELINES,
 };
 pub use types::{
-    Attachment, AttachmentType, BuildResult, DiagnosticInfo, DiagnosticSeverity, GitStatusInfo,
-    IdeType, PermissionMode, PromptContext, PromptHashInfo, PromptTooLongError,
-    SystemPromptOptions, TodoItem, TodoStatus,
+    Attachment, AttachmentLimits, AttachmentTooLargeError, AttachmentType, BuildResult,
+    DiagnosticInfo, DiagnosticSeverity, DroppedSection, GitStatusInfo, IdeType,
+    NonVisionImagePolicy, PermissionMode, PromptBuildError, PromptContext, PromptHashInfo,
+    PromptSection, PromptTooLongError, SectionPriority, SystemPromptOptions, TodoItem, TodoStatus,
+    IMAGE_OMITTED_PLACEHOLDER,
 };
@@ -1,6 +1,7 @@
 //! prompt 模块测试
 
 use super::*;
+use crate::media::tests::encode_test_png;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -67,6 +68,41 @@ fn test_prompt_cache_is_valid() {
     assert!(!cache.is_valid("nonexistent", &hash_info.hash));
 }
 
+#[test]
+fn test_prompt_cache_warmup() {
+    type Builder = Box<dyn FnOnce() -> Result<String, String>>;
+
+    let mut cache = PromptCache::new(None, None).with_token_budget(20);
+    let builders: Vec<(String, Builder)> = vec![
+        ("a".to_string(), Box::new(|| Ok("short prompt".to_string()))),
+        (
+            "b".to_string(),
+            Box::new(|| Err("build failed".to_string())),
+        ),
+        ("c".to_string(), Box::new(|| Ok("long prompt ".repeat(50)))),
+        (
+            "d".to_string(),
+            Box::new(|| Ok("another prompt".to_string())),
+        ),
+    ];
+
+    let report = cache.warmup(builders);
+    assert_eq!(report.warmed, vec!["a".to_string(), "d".to_string()]);
+    assert_eq!(report.failed.len(), 2);
+    assert_eq!(
+        report.failed[0],
+        ("b".to_string(), "build failed".to_string())
+    );
+    assert_eq!(report.failed[1].0, "c");
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("c").is_none());
+
+    cache.set("e".to_string(), "runtime prompt".to_string(), None);
+    let stats = cache.get_stats();
+    assert_eq!(stats.warmed_entries, 2);
+    assert_eq!(stats.runtime_entries, 1);
+}
+
 #[test]
 fn test_generate_cache_key() {
     let key = generate_cache_key(
@@ -344,3 +380,242 @@ fn test_system_prompt_builder_preview() {
     assert!(preview.contains("truncated"));
     assert!(preview.contains("200 chars"));
 }
+
+fn budget_context() -> PromptContext {
+    PromptContext {
+        working_dir: PathBuf::from("/tmp/test"),
+        platform: Some("linux".to_string()),
+        today_date: Some("2024-01-15".to_string()),
+        ..Default::default()
+    }
+}
+
+fn build_with_budget(max_tokens: usize) -> Result<BuildResult, PromptBuildError> {
+    let mut builder = SystemPromptBuilder::new(false);
+    let options = SystemPromptOptions {
+        max_tokens,
+        enable_cache: false,
+        ..Default::default()
+    };
+    builder.build(&budget_context(), Some(options))
+}
+
+#[test]
+fn test_system_prompt_builder_within_budget_drops_nothing() {
+    let result = build_with_budget(180000).unwrap();
+    assert!(!result.truncated);
+    assert!(result.dropped_sections.is_empty());
+}
+
+#[test]
+fn test_system_prompt_builder_drops_low_priority_sections() {
+    let full = build_with_budget(180000).unwrap();
+    let git_tokens = estimate_tokens(GIT_GUIDELINES);
+    let budget = full.hash_info.estimated_tokens - git_tokens;
+
+    let result = build_with_budget(budget).unwrap();
+    assert!(result.truncated);
+    assert!(result.hash_info.estimated_tokens <= budget);
+    assert!(result.content.contains(CORE_IDENTITY));
+    assert!(result
+        .dropped_sections
+        .iter()
+        .all(|d| d.priority == SectionPriority::Low));
+    assert!(result
+        .dropped_sections
+        .iter()
+        .any(|d| d.name == PromptSection::GitGuidelines.name()));
+}
+
+#[test]
+fn test_system_prompt_builder_truncates_large_low_priority_attachment() {
+    let mut context = budget_context();
+    context.custom_attachments = Some(vec![Attachment {
+        attachment_type: AttachmentType::Custom,
+        content: "Background notes about the project. ".repeat(100),
+        label: Some("Project Notes".to_string()),
+        priority: Some(50),
+        compute_time_ms: None,
+    }]);
+
+    let mut builder = SystemPromptBuilder::new(false);
+    let options = |max_tokens| SystemPromptOptions {
+        max_tokens,
+        enable_cache: false,
+        ..Default::default()
+    };
+    let full = builder.build(&context, Some(options(180000))).unwrap();
+    let budget = full.hash_info.estimated_tokens - 500;
+
+    let result = builder.build(&context, Some(options(budget))).unwrap();
+    assert!(result.hash_info.estimated_tokens <= budget);
+    assert_eq!(result.dropped_sections.len(), 1);
+    assert_eq!(result.dropped_sections[0].name, "Project Notes");
+    assert!(result.dropped_sections[0].truncated);
+    assert!(result.content.contains("Background notes"));
+    assert!(result.content.contains("[... truncated]"));
+    assert!(result.content.contains(GIT_GUIDELINES));
+}
+
+#[test]
+fn test_system_prompt_builder_required_sections_over_budget() {
+    let result = build_with_budget(50);
+    let PromptBuildError::TooLong(err) = result.unwrap_err() else {
+        panic!("expected PromptBuildError::TooLong");
+    };
+    assert_eq!(err.max_tokens, 50);
+    assert!(err.estimated_tokens > 50);
+}
+
+#[test]
+fn test_attachment_limits_reject_oversized_text() {
+    let manager = AttachmentManager::default().with_limits(AttachmentLimits {
+        default_max_bytes: 100,
+        ..Default::default()
+    });
+    let attachment = Attachment {
+        attachment_type: AttachmentType::Custom,
+        content: "x".repeat(101),
+        label: Some("Huge Log".to_string()),
+        priority: None,
+        compute_time_ms: None,
+    };
+
+    let err = manager.apply_limits(vec![attachment]).unwrap_err();
+    match &err {
+        PromptBuildError::AttachmentTooLarge(e) => {
+            assert_eq!(e.size_bytes, 101);
+            assert_eq!(e.max_bytes, 100);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.to_string().contains("Huge Log"));
+}
+
+#[test]
+fn test_attachment_limits_downscale_large_image() {
+    let manager = AttachmentManager::default().with_limits(AttachmentLimits {
+        max_image_dimension: 64,
+        ..Default::default()
+    });
+    let attachment = Attachment::image("screenshot.png", "image/png", &encode_test_png(256, 128));
+
+    let limited = manager.apply_limits(vec![attachment]).unwrap();
+    let (mime_type, data) = limited[0].image_data().unwrap();
+    assert_eq!(mime_type, "image/png");
+    let resized = ::image::load_from_memory(&data).unwrap();
+    assert_eq!((resized.width(), resized.height()), (64, 32));
+    assert_eq!(limited[0].label.as_deref(), Some("screenshot.png"));
+}
+
+#[test]
+fn test_build_result_reports_attachment_usage() {
+    let mut context = budget_context();
+    context.custom_attachments = Some(vec![
+        Attachment::image("diagram.png", "image/png", &encode_test_png(8, 8)),
+        Attachment {
+            attachment_type: AttachmentType::Custom,
+            content: "Project notes".to_string(),
+            label: Some("Notes".to_string()),
+            priority: Some(50),
+            compute_time_ms: None,
+        },
+    ]);
+
+    let mut builder = SystemPromptBuilder::new(false);
+    let options = SystemPromptOptions {
+        enable_cache: false,
+        ..Default::default()
+    };
+    let result = builder.build(&context, Some(options)).unwrap();
+
+    assert_eq!(result.attachments.len(), 2);
+    assert!(result.attachment_bytes > "Project notes".len());
+    assert!(result.attachment_tokens > 0);
+    assert!(result.content.contains("Project notes"));
+    assert!(!result.content.contains("data:image/png"));
+}
+
+fn text_only_context(image: Attachment) -> PromptContext {
+    PromptContext {
+        model: Some("gpt-3.5-turbo".to_string()),
+        custom_attachments: Some(vec![image]),
+        ..budget_context()
+    }
+}
+
+#[test]
+fn test_image_replaced_for_text_only_model() {
+    let context = text_only_context(Attachment::image(
+        "chart.png",
+        "image/png",
+        &encode_test_png(8, 8),
+    ));
+    let options = SystemPromptOptions {
+        enable_cache: false,
+        ..Default::default()
+    };
+
+    let mut builder = SystemPromptBuilder::new(false);
+    let result = builder.build(&context, Some(options.clone())).unwrap();
+    assert_eq!(result.attachments.len(), 1);
+    assert_eq!(
+        result.attachments[0].attachment_type,
+        AttachmentType::Custom
+    );
+    assert_eq!(result.attachments[0].label.as_deref(), Some("chart.png"));
+    assert!(result.content.contains(IMAGE_OMITTED_PLACEHOLDER));
+
+    let ocr: ImageOcr =
+        std::sync::Arc::new(|mime_type, _| Ok(format!("Q3 revenue ({})", mime_type)));
+    let manager = AttachmentManager::default()
+        .with_image_policy(NonVisionImagePolicy::Ocr)
+        .with_ocr(ocr);
+    let mut builder = SystemPromptBuilder::with_components(manager, PromptCache::default(), false);
+    let result = builder.build(&context, Some(options.clone())).unwrap();
+    assert!(result.content.contains("Q3 revenue (image/png)"));
+    assert!(!result.content.contains(IMAGE_OMITTED_PLACEHOLDER));
+
+    let manager = AttachmentManager::default().with_image_policy(NonVisionImagePolicy::Error);
+    let mut builder = SystemPromptBuilder::with_components(manager, PromptCache::default(), false);
+    let err = builder.build(&context, Some(options)).unwrap_err();
+    assert!(matches!(err, PromptBuildError::UnsupportedAttachment(_)));
+}
+
+#[test]
+fn test_image_kept_for_vision_model() {
+    let mut context = text_only_context(Attachment::image(
+        "chart.png",
+        "image/png",
+        &encode_test_png(8, 8),
+    ));
+    context.model = Some("gpt-4o".to_string());
+
+    let manager = AttachmentManager::default().with_image_policy(NonVisionImagePolicy::Error);
+    let attachments = manager
+        .adapt_for_model(
+            context.custom_attachments.clone().unwrap(),
+            context.model.as_deref(),
+        )
+        .unwrap();
+    assert_eq!(attachments[0].attachment_type, AttachmentType::Image);
+}
+
+#[test]
+fn test_image_kept_for_unknown_model() {
+    let mut context = text_only_context(Attachment::image(
+        "chart.png",
+        "image/png",
+        &encode_test_png(8, 8),
+    ));
+    context.model = Some("my-local-finetune".to_string());
+
+    let manager = AttachmentManager::default().with_image_policy(NonVisionImagePolicy::Error);
+    let attachments = manager
+        .adapt_for_model(
+            context.custom_attachments.clone().unwrap(),
+            context.model.as_deref(),
+        )
+        .unwrap();
+    assert_eq!(attachments[0].attachment_type, AttachmentType::Image);
+}
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// 附件类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AttachmentType {
     AgentsMd,
@@ -20,6 +20,8 @@ pub enum AttachmentType {
     DelegateMode,
     GitStatus,
     TodoList,
+    /// 图片附件，内容为 `data:<mime>;base64,<data>` 形式的 data URL
+    Image,
     Custom,
 }
 
@@ -36,6 +38,95 @@ pub struct Attachment {
     pub compute_time_ms: Option<u64>,
 }
 
+impl Attachment {
+    /// 创建图片附件
+    pub fn image(label: impl Into<String>, mime_type: &str, data: &[u8]) -> Self {
+        Self {
+            attachment_type: AttachmentType::Image,
+            content: image_data_url(mime_type, data),
+            label: Some(label.into()),
+            priority: None,
+            compute_time_ms: None,
+        }
+    }
+
+    /// 解析图片附件的 MIME 类型和原始数据
+    pub fn image_data(&self) -> Option<(String, Vec<u8>)> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        if self.attachment_type != AttachmentType::Image {
+            return None;
+        }
+        let rest = self.content.strip_prefix("data:")?;
+        let (mime_type, data) = rest.split_once(";base64,")?;
+        let bytes = STANDARD.decode(data).ok()?;
+        Some((mime_type.to_string(), bytes))
+    }
+
+    /// 附件字节数（图片为解码后的大小）
+    pub fn size_bytes(&self) -> usize {
+        match self.image_data() {
+            Some((_, data)) => data.len(),
+            None => self.content.len(),
+        }
+    }
+}
+
+/// 将图片编码为 data URL
+pub(crate) fn image_data_url(mime_type: &str, data: &[u8]) -> String {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    format!("data:{};base64,{}", mime_type, STANDARD.encode(data))
+}
+
+/// 附件大小限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentLimits {
+    /// 默认单个附件最大字节数
+    pub default_max_bytes: usize,
+    /// 按附件类型覆盖的最大字节数
+    #[serde(default)]
+    pub max_bytes: HashMap<AttachmentType, usize>,
+    /// 图片最大边长（像素），超出时自动等比缩放
+    pub max_image_dimension: u32,
+}
+
+impl AttachmentLimits {
+    /// 获取指定类型的大小上限
+    pub fn limit_for(&self, attachment_type: AttachmentType) -> usize {
+        self.max_bytes
+            .get(&attachment_type)
+            .copied()
+            .unwrap_or(self.default_max_bytes)
+    }
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        let mut max_bytes = HashMap::new();
+        max_bytes.insert(AttachmentType::Image, 5 * 1024 * 1024);
+        Self {
+            default_max_bytes: 200 * 1024,
+            max_bytes,
+            max_image_dimension: 1568,
+        }
+    }
+}
+
+/// 当前模型不支持视觉时图片附件的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NonVisionImagePolicy {
+    /// 替换为文本占位符
+    #[default]
+    Drop,
+    /// 通过 OCR 提取图片中的文字，失败时退回占位符
+    Ocr,
+    /// 返回错误
+    Error,
+}
+
+/// 图片被丢弃时替换的占位文本
+pub const IMAGE_OMITTED_PLACEHOLDER: &str = "[image omitted: model has no vision]";
+
 /// 权限模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -169,6 +260,97 @@ pub struct PromptContext {
     pub is_git_repo: bool,
 }
 
+/// 提示词段落优先级
+///
+/// 超出 token 预算时按优先级从低到高丢弃或截断段落，`Required` 段落不会被丢弃
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionPriority {
+    Low,
+    Normal,
+    High,
+    Required,
+}
+
+/// 系统提示词固定段落
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSection {
+    CoreIdentity,
+    Help,
+    OutputStyle,
+    TaskManagement,
+    CodingGuidelines,
+    ToolGuidelines,
+    GitGuidelines,
+    SubagentSystem,
+    PermissionMode,
+    Environment,
+}
+
+impl PromptSection {
+    /// 段落名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            PromptSection::CoreIdentity => "core_identity",
+            PromptSection::Help => "help",
+            PromptSection::OutputStyle => "output_style",
+            PromptSection::TaskManagement => "task_management",
+            PromptSection::CodingGuidelines => "coding_guidelines",
+            PromptSection::ToolGuidelines => "tool_guidelines",
+            PromptSection::GitGuidelines => "git_guidelines",
+            PromptSection::SubagentSystem => "subagent_system",
+            PromptSection::PermissionMode => "permission_mode",
+            PromptSection::Environment => "environment",
+        }
+    }
+
+    /// 默认优先级
+    pub fn default_priority(&self) -> SectionPriority {
+        match self {
+            PromptSection::CoreIdentity
+            | PromptSection::PermissionMode
+            | PromptSection::Environment => SectionPriority::Required,
+            PromptSection::OutputStyle
+            | PromptSection::CodingGuidelines
+            | PromptSection::ToolGuidelines => SectionPriority::High,
+            PromptSection::TaskManagement | PromptSection::SubagentSystem => {
+                SectionPriority::Normal
+            }
+            PromptSection::Help | PromptSection::GitGuidelines => SectionPriority::Low,
+        }
+    }
+}
+
+impl Attachment {
+    /// 附件在 token 预算中的优先级
+    ///
+    /// `priority` 数值越小越重要；critical reminder 始终保留
+    pub fn section_priority(&self) -> SectionPriority {
+        if self.attachment_type == AttachmentType::CriticalSystemReminder {
+            return SectionPriority::Required;
+        }
+        match self.priority.unwrap_or(0) {
+            p if p <= 10 => SectionPriority::High,
+            p if p <= 25 => SectionPriority::Normal,
+            _ => SectionPriority::Low,
+        }
+    }
+}
+
+/// 因预算被丢弃或截断的段落
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DroppedSection {
+    /// 段落名称
+    pub name: String,
+    /// 段落优先级
+    pub priority: SectionPriority,
+    /// 原始 tokens
+    pub original_tokens: usize,
+    /// 是否仅被截断（否则整段丢弃）
+    pub truncated: bool,
+}
+
 /// 系统提示词构建选项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemPromptOptions {
@@ -190,9 +372,12 @@ pub struct SystemPromptOptions {
     /// 包含诊断信息
     #[serde(default = "default_true")]
     pub include_diagnostics: bool,
-    /// 最大长度限制 (tokens)
+    /// 最大长度限制 (tokens)，超出时按段落优先级丢弃或截断
     #[serde(default = "default_max_tokens")]
     pub max_tokens: usize,
+    /// 覆盖固定段落的默认优先级
+    #[serde(default)]
+    pub section_priorities: HashMap<PromptSection, SectionPriority>,
     /// 是否启用缓存
     #[serde(default = "default_true")]
     pub enable_cache: bool,
@@ -206,6 +391,16 @@ fn default_max_tokens() -> usize {
     180000
 }
 
+impl SystemPromptOptions {
+    /// 获取段落优先级（考虑覆盖配置）
+    pub fn priority_of(&self, section: PromptSection) -> SectionPriority {
+        self.section_priorities
+            .get(&section)
+            .copied()
+            .unwrap_or_else(|| section.default_priority())
+    }
+}
+
 impl Default for SystemPromptOptions {
     fn default() -> Self {
         Self {
@@ -216,6 +411,7 @@ impl Default for SystemPromptOptions {
             include_ide_info: true,
             include_diagnostics: true,
             max_tokens: 180000,
+            section_priorities: HashMap::new(),
             enable_cache: true,
         }
     }
@@ -245,6 +441,15 @@ pub struct BuildResult {
     pub attachments: Vec<Attachment>,
     /// 是否被截断
     pub truncated: bool,
+    /// 因预算被丢弃或截断的段落
+    #[serde(default)]
+    pub dropped_sections: Vec<DroppedSection>,
+    /// 附件总字节数
+    #[serde(default)]
+    pub attachment_bytes: usize,
+    /// 附件估算 tokens
+    #[serde(default)]
+    pub attachment_tokens: usize,
     /// 构建耗时 (ms)
     pub build_time_ms: u64,
 }
@@ -279,3 +484,67 @@ impl std::fmt::Display for PromptTooLongError {
 }
 
 impl std::error::Error for PromptTooLongError {}
+
+/// 附件超出大小限制
+#[derive(Debug, Clone)]
+pub struct AttachmentTooLargeError {
+    pub attachment_type: AttachmentType,
+    pub label: Option<String>,
+    pub size_bytes: usize,
+    pub max_bytes: usize,
+}
+
+impl std::fmt::Display for AttachmentTooLargeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Attachment {} ({:?}) is {} bytes, exceeding the {} byte limit",
+            self.label.as_deref().unwrap_or("<unnamed>"),
+            self.attachment_type,
+            self.size_bytes,
+            self.max_bytes
+        )
+    }
+}
+
+impl std::error::Error for AttachmentTooLargeError {}
+
+/// 提示词构建错误
+#[derive(Debug, Clone)]
+pub enum PromptBuildError {
+    /// 提示词超出长度限制
+    TooLong(PromptTooLongError),
+    /// 附件超出大小限制
+    AttachmentTooLarge(AttachmentTooLargeError),
+    /// 附件处理失败（如图片无法解码）
+    InvalidAttachment(String),
+    /// 当前模型不支持该附件（如向纯文本模型发送图片）
+    UnsupportedAttachment(String),
+}
+
+impl std::fmt::Display for PromptBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptBuildError::TooLong(e) => write!(f, "{}", e),
+            PromptBuildError::AttachmentTooLarge(e) => write!(f, "{}", e),
+            PromptBuildError::InvalidAttachment(msg) => write!(f, "Invalid attachment: {}", msg),
+            PromptBuildError::UnsupportedAttachment(msg) => {
+                write!(f, "Unsupported attachment: {}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PromptBuildError {}
+
+impl From<PromptTooLongError> for PromptBuildError {
+    fn from(e: PromptTooLongError) -> Self {
+        PromptBuildError::TooLong(e)
+    }
+}
+
+impl From<AttachmentTooLargeError> for PromptBuildError {
+    fn from(e: AttachmentTooLargeError) -> Self {
+        PromptBuildError::AttachmentTooLarge(e)
+    }
+}
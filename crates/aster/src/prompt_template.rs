@@ -1,8 +1,12 @@
 use include_dir::{include_dir, Dir};
-use minijinja::{Environment, Error as MiniJinjaError, Value as MJValue};
+use minijinja::{
+    Environment, Error as MiniJinjaError, ErrorKind, UndefinedBehavior, Value as MJValue,
+};
 use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
 /// This directory will be embedded into the final binary.
@@ -80,6 +84,32 @@ pub fn render_global_from_file<T: Serialize>(
     render_global_file(template_file, context_data)
 }
 
+/// How variables missing from the render context are treated.
+///
+/// Templates support `{% if %}`/`{% elif %}`/`{% else %}` sections and
+/// `{% for %}` loops (with `{% else %}` for empty lists). Expressions are
+/// evaluated by MiniJinja's sandboxed engine, so templates cannot run
+/// arbitrary code or touch the host; unbalanced tags fail at parse time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UndefinedMode {
+    /// Undefined variables print as empty strings, iterate as empty lists
+    /// and are falsy in conditions.
+    #[default]
+    Lenient,
+    /// Printing or iterating an undefined variable is an error. Conditions may
+    /// still test optional flags such as `{% if tools_available %}`.
+    Strict,
+}
+
+impl From<UndefinedMode> for UndefinedBehavior {
+    fn from(mode: UndefinedMode) -> Self {
+        match mode {
+            UndefinedMode::Lenient => UndefinedBehavior::Lenient,
+            UndefinedMode::Strict => UndefinedBehavior::SemiStrict,
+        }
+    }
+}
+
 /// Renders a **one-off ephemeral** template (inline string).
 ///
 /// This does *not* store anything in the global environment and is best for
@@ -91,8 +121,23 @@ pub fn render_global_from_file<T: Serialize>(
 pub fn render_inline_once<T: Serialize>(
     template_str: &str,
     context_data: &T,
+) -> Result<String, MiniJinjaError> {
+    render_inline_with_mode(template_str, context_data, UndefinedMode::Lenient)
+}
+
+/// Like [`render_inline_once`], with control over how undefined variables are handled.
+///
+/// # Arguments
+/// * `template_str`  - The raw template string.
+/// * `context_data`  - Data to be inserted into the template (must be `Serialize`).
+/// * `mode`          - Whether undefined variables are an error.
+pub fn render_inline_with_mode<T: Serialize>(
+    template_str: &str,
+    context_data: &T,
+    mode: UndefinedMode,
 ) -> Result<String, MiniJinjaError> {
     let mut env = Environment::new();
+    env.set_undefined_behavior(mode.into());
     env.add_template("inline_ephemeral", template_str)?;
     let tmpl = env.get_template("inline_ephemeral")?;
     let ctx = MJValue::from_serialize(context_data);
@@ -100,6 +145,154 @@ pub fn render_inline_once<T: Serialize>(
     Ok(rendered.trim().to_string())
 }
 
+/// Matches statically named `include`, `extends`, `import` and `from` references.
+static TEMPLATE_REF_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\{%-?\s*(?:include|extends|import|from)\s+(?:"([^"]+)"|'([^']+)')"#)
+        .expect("valid template reference regex")
+});
+
+fn template_references(source: &str) -> Vec<String> {
+    TEMPLATE_REF_RE
+        .captures_iter(source)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// A named collection of templates that can `{% include %}` or `{% extends %}`
+/// one another, so shared sections live in a single template.
+///
+/// Core prompts from `CORE_PROMPTS_DIR` are also available by name, and a
+/// template added to the set shadows a core prompt with the same name.
+///
+/// Scoping follows MiniJinja: every template in a chain sees the same render
+/// context, and a `{% block %}` defined by a child replaces the parent's block
+/// of the same name (`{{ super() }}` renders the parent's version).
+#[derive(Debug, Clone, Default)]
+pub struct PromptTemplateSet {
+    templates: BTreeMap<String, String>,
+    undefined_mode: UndefinedMode,
+}
+
+impl PromptTemplateSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds (or replaces) a template in the set.
+    pub fn add_template(&mut self, name: impl Into<String>, source: impl Into<String>) {
+        self.templates.insert(name.into(), source.into());
+    }
+
+    /// Builder-style variant of [`Self::add_template`].
+    pub fn with_template(mut self, name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.add_template(name, source);
+        self
+    }
+
+    /// Sets how undefined variables are handled when rendering.
+    pub fn with_undefined_mode(mut self, mode: UndefinedMode) -> Self {
+        self.undefined_mode = mode;
+        self
+    }
+
+    /// Loads every file in `dir` as a template named after its file name.
+    pub fn from_dir(dir: &Path) -> std::io::Result<Self> {
+        let mut set = Self::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                set.add_template(name, std::fs::read_to_string(&path)?);
+            }
+        }
+        Ok(set)
+    }
+
+    fn source(&self, name: &str) -> Option<String> {
+        if let Some(source) = self.templates.get(name) {
+            return Some(source.clone());
+        }
+        CORE_PROMPTS_DIR
+            .get_file(name)
+            .map(|f| String::from_utf8_lossy(f.contents()).to_string())
+    }
+
+    /// Checks that every statically referenced template exists and that no
+    /// template includes or extends itself, directly or transitively.
+    ///
+    /// Errors name the full chain, e.g. `a.md -> b.md -> a.md`.
+    pub fn validate(&self, entry: &str) -> Result<(), MiniJinjaError> {
+        let mut chain = Vec::new();
+        self.validate_from(entry, &mut chain)
+    }
+
+    fn validate_from(&self, name: &str, chain: &mut Vec<String>) -> Result<(), MiniJinjaError> {
+        if chain.iter().any(|n| n == name) {
+            chain.push(name.to_string());
+            return Err(MiniJinjaError::new(
+                ErrorKind::BadInclude,
+                format!("template include cycle: {}", chain.join(" -> ")),
+            ));
+        }
+
+        let Some(source) = self.source(name) else {
+            chain.push(name.to_string());
+            return Err(MiniJinjaError::new(
+                ErrorKind::TemplateNotFound,
+                format!("template not found: {}", chain.join(" -> ")),
+            ));
+        };
+
+        chain.push(name.to_string());
+        for reference in template_references(&source) {
+            self.validate_from(&reference, chain)?;
+        }
+        chain.pop();
+        Ok(())
+    }
+
+    fn environment(&self) -> Result<Environment<'static>, MiniJinjaError> {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_undefined_behavior(self.undefined_mode.into());
+
+        for file in CORE_PROMPTS_DIR.files() {
+            let name = file.path().to_string_lossy().to_string();
+            if self.templates.contains_key(&name) {
+                continue;
+            }
+            let source = String::from_utf8_lossy(file.contents()).to_string();
+            env.add_template_owned(name, source)?;
+        }
+        for (name, source) in &self.templates {
+            env.add_template_owned(name.clone(), source.clone())?;
+        }
+        Ok(env)
+    }
+
+    /// Validates the include/extends chain starting at `template_name`, then renders it.
+    ///
+    /// # Arguments
+    /// * `template_name` - The entry template in the set (or a core prompt).
+    /// * `context_data`  - Data to be inserted into the template (must be `Serialize`).
+    pub fn render<T: Serialize>(
+        &self,
+        template_name: &str,
+        context_data: &T,
+    ) -> Result<String, MiniJinjaError> {
+        self.validate(template_name)?;
+        let env = self.environment()?;
+        let tmpl = env.get_template(template_name)?;
+        let ctx = MJValue::from_serialize(context_data);
+        let rendered = tmpl.render(ctx)?;
+        Ok(rendered.trim().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +430,137 @@ mod tests {
         let expected = "### Tool Descriptions";
         assert_eq!(rendered, expected);
     }
+
+    #[test]
+    fn test_template_set_two_level_inheritance() {
+        let set = PromptTemplateSet::new()
+            .with_template(
+                "base.md",
+                "# {% block title %}Base{% endblock %}\n\n{% block body %}{% endblock %}\n\n{% include \"footer.md\" %}",
+            )
+            .with_template(
+                "agent.md",
+                "{% extends \"base.md\" %}{% block title %}Agent {{ name }}{% endblock %}{% block body %}You are helpful.{% endblock %}",
+            )
+            .with_template(
+                "coder.md",
+                "{% extends \"agent.md\" %}{% block body %}{{ super() }} You write code.{% endblock %}",
+            )
+            .with_template("footer.md", "-- {{ name }}");
+
+        let context = build_context(Some("Aster"), None);
+        let rendered = set.render("coder.md", &context).unwrap();
+        assert_eq!(
+            rendered,
+            "# Agent Aster\nYou are helpful. You write code.\n-- Aster"
+        );
+    }
+
+    #[test]
+    fn test_template_set_detects_cycle() {
+        let set = PromptTemplateSet::new()
+            .with_template("a.md", "{% include \"b.md\" %}")
+            .with_template("b.md", "{% include 'a.md' %}");
+
+        let err = set.render("a.md", &json!({})).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadInclude);
+        assert!(err.to_string().contains("a.md -> b.md -> a.md"));
+    }
+
+    #[test]
+    fn test_template_set_missing_include() {
+        let set = PromptTemplateSet::new()
+            .with_template("a.md", "{% extends \"b.md\" %}")
+            .with_template("b.md", "{% include \"missing.md\" %}");
+
+        let err = set.render("a.md", &json!({})).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TemplateNotFound);
+        assert!(err.to_string().contains("a.md -> b.md -> missing.md"));
+    }
+
+    #[test]
+    fn test_template_set_can_include_core_prompt() {
+        let set = PromptTemplateSet::new().with_template("wrapper.md", "{% include \"mock.md\" %}");
+        let context = TestContext {
+            name: "Alice".to_string(),
+            age: 30,
+        };
+        let rendered = set.render("wrapper.md", &context).unwrap();
+        assert!(rendered.ends_with("Hello, Alice! You are 30 years old."));
+    }
+
+    const TOOLS_SECTION: &str = "\
+{% if tools_available %}
+Tools:
+{% for tool in tools %}
+{% if tool.dangerous %}
+- {{ tool.name }} (requires approval)
+{% else %}
+- {{ tool.name }}
+{% endif %}
+{% else %}
+(no tools registered)
+{% endfor %}
+{% else %}
+Tools are disabled.
+{% endif %}";
+
+    fn render_tools_section(context: &serde_json::Value) -> String {
+        PromptTemplateSet::new()
+            .with_template("tools.md", TOOLS_SECTION)
+            .render("tools.md", context)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_nested_conditionals_in_loop() {
+        let rendered = render_tools_section(&json!({
+            "tools_available": true,
+            "tools": [
+                {"name": "read", "dangerous": false},
+                {"name": "shell", "dangerous": true},
+            ],
+        }));
+        assert_eq!(rendered, "Tools:\n- read\n- shell (requires approval)");
+
+        let rendered = render_tools_section(&json!({"tools_available": false}));
+        assert_eq!(rendered, "Tools are disabled.");
+    }
+
+    #[test]
+    fn test_loop_over_empty_and_non_empty_list() {
+        let empty = render_tools_section(&json!({"tools_available": true, "tools": []}));
+        assert_eq!(empty, "Tools:\n(no tools registered)");
+
+        let one = render_tools_section(&json!({
+            "tools_available": true,
+            "tools": [{"name": "read"}],
+        }));
+        assert_eq!(one, "Tools:\n- read");
+    }
+
+    #[test]
+    fn test_unbalanced_tags_are_rejected() {
+        let err = render_inline_once("{% if a %}open", &json!({"a": true})).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::SyntaxError);
+
+        let err = render_inline_once("{% for x in xs %}{{ x }}{% endif %}", &json!({"xs": [1]}))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::SyntaxError);
+    }
+
+    #[test]
+    fn test_strict_undefined_mode() {
+        let template = "{% if verbose %}Verbose. {% endif %}Hello, {{ name }}!";
+
+        let lenient = render_inline_with_mode(template, &json!({}), UndefinedMode::Lenient);
+        assert_eq!(lenient.unwrap(), "Hello, !");
+
+        let strict = render_inline_with_mode(template, &json!({}), UndefinedMode::Strict);
+        assert_eq!(strict.unwrap_err().kind(), ErrorKind::UndefinedError);
+
+        let ok =
+            render_inline_with_mode(template, &json!({"name": "Aster"}), UndefinedMode::Strict);
+        assert_eq!(ok.unwrap(), "Hello, Aster!");
+    }
 }
@@ -14,6 +14,7 @@ use super::errors::ProviderError;
 use super::formats::anthropic::{
     create_request, get_usage, response_to_message, response_to_streaming_message,
 };
+use super::prompt_cache::{PromptCacheMiddleware, PromptCacheStyle};
 use super::utils::{get_model, handle_status_openai_compat, map_http_error_to_provider_error};
 use crate::config::declarative_providers::DeclarativeProviderConfig;
 use crate::conversation::message::Message;
@@ -198,7 +199,8 @@ impl Provider for AnthropicProvider {
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let payload = create_request(model_config, system, messages, tools)?;
+        let mut payload = create_request(model_config, system, messages, tools)?;
+        PromptCacheMiddleware::default().apply(self.prompt_cache_style(), messages, &mut payload);
 
         let response = self
             .with_retry(|| async { self.post(&payload).await })
@@ -253,24 +255,27 @@ impl Provider for AnthropicProvider {
         tools: &[Tool],
     ) -> Result<MessageStream, ProviderError> {
         let mut payload = create_request(&self.model, system, messages, tools)?;
+        PromptCacheMiddleware::default().apply(self.prompt_cache_style(), messages, &mut payload);
         payload
             .as_object_mut()
             .unwrap()
             .insert("stream".to_string(), Value::Bool(true));
 
-        let mut request = self.api_client.request("v1/messages");
         let mut log = RequestLog::start(&self.model, &payload)?;
 
-        for (key, value) in self.get_conditional_headers() {
-            request = request.header(key, value)?;
-        }
-
-        let resp = request.response_post(&payload).await.inspect_err(|e| {
-            let _ = log.error(e);
-        })?;
-        let response = handle_status_openai_compat(resp).await.inspect_err(|e| {
-            let _ = log.error(e);
-        })?;
+        let response = self
+            .with_retry(|| async {
+                let mut request = self.api_client.request("v1/messages");
+                for (key, value) in self.get_conditional_headers() {
+                    request = request.header(key, value)?;
+                }
+                let resp = request.response_post(&payload).await?;
+                handle_status_openai_compat(resp).await
+            })
+            .await
+            .inspect_err(|e| {
+                let _ = log.error(e);
+            })?;
 
         let stream = response.bytes_stream().map_err(io::Error::other);
 
@@ -291,4 +296,15 @@ impl Provider for AnthropicProvider {
     fn supports_streaming(&self) -> bool {
         self.supports_streaming
     }
+
+    fn supports_stream_continuation(&self) -> bool {
+        // A trailing assistant message is continued as a prefill, which the
+        // API does not allow together with extended thinking
+        std::env::var("CLAUDE_THINKING_ENABLED").is_err()
+    }
+
+    fn prompt_cache_style(&self) -> PromptCacheStyle {
+        // Custom providers built on this one keep the Anthropic request format
+        PromptCacheStyle::Anthropic
+    }
 }
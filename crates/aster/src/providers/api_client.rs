@@ -1,3 +1,7 @@
+use crate::network::{
+    circuit_breaker_for, endpoint_key, is_circuit_failure_status, CircuitBreaker, DohConfig,
+    DohResolver,
+};
 use crate::session_context::SESSION_ID_HEADER;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -9,6 +13,7 @@ use serde_json::Value;
 use std::fmt;
 use std::fs::read_to_string;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct ApiClient {
@@ -18,6 +23,9 @@ pub struct ApiClient {
     default_headers: HeaderMap,
     timeout: Duration,
     tls_config: Option<TlsConfig>,
+    dns_resolver: Option<DohResolver>,
+    endpoint: String,
+    circuit_breaker: Arc<CircuitBreaker>,
 }
 
 pub enum AuthMethod {
@@ -152,6 +160,29 @@ impl Default for TlsConfig {
     }
 }
 
+/// Build a DNS-over-HTTPS resolver when `ASTER_DOH_ENABLED` is set; off by default
+fn doh_resolver_from_config() -> Result<Option<DohResolver>> {
+    let config = crate::config::Config::global();
+    let enabled = config
+        .get_param::<bool>("ASTER_DOH_ENABLED")
+        .unwrap_or(false);
+    if !enabled {
+        return Ok(None);
+    }
+
+    let mut doh_config = DohConfig {
+        enabled: true,
+        ..Default::default()
+    };
+    if let Ok(server) = config.get_param::<String>("ASTER_DOH_SERVER") {
+        doh_config.server = server;
+    }
+
+    let resolver = DohResolver::new(&doh_config)
+        .map_err(|e| anyhow::anyhow!("Failed to create DoH resolver: {}", e))?;
+    Ok(Some(resolver))
+}
+
 pub struct OAuthConfig {
     pub host: String,
     pub client_id: String,
@@ -212,7 +243,15 @@ impl ApiClient {
             client_builder = Self::configure_tls(client_builder, config)?;
         }
 
+        // Resolve hostnames via DNS-over-HTTPS if enabled
+        let dns_resolver = doh_resolver_from_config()?;
+        if let Some(ref resolver) = dns_resolver {
+            client_builder = client_builder.dns_resolver(Arc::new(resolver.clone()));
+        }
+
         let client = client_builder.build()?;
+        let endpoint = endpoint_key(&host);
+        let circuit_breaker = circuit_breaker_for(&endpoint);
 
         Ok(Self {
             client,
@@ -221,6 +260,9 @@ impl ApiClient {
             default_headers: HeaderMap::new(),
             timeout,
             tls_config,
+            dns_resolver,
+            endpoint,
+            circuit_breaker,
         })
     }
 
@@ -234,6 +276,10 @@ impl ApiClient {
             client_builder = Self::configure_tls(client_builder, tls_config)?;
         }
 
+        if let Some(ref resolver) = self.dns_resolver {
+            client_builder = client_builder.dns_resolver(Arc::new(resolver.clone()));
+        }
+
         self.client = client_builder.build()?;
         Ok(())
     }
@@ -311,6 +357,33 @@ impl ApiClient {
             .map_err(|e| anyhow::anyhow!("Failed to construct URL: {}", e))
     }
 
+    /// Fail fast when the circuit for this host is open
+    fn check_circuit(&self) -> Result<()> {
+        self.circuit_breaker.check(&self.endpoint)?;
+        Ok(())
+    }
+
+    /// Feed the outcome into this host's circuit breaker. Timeouts, connection
+    /// errors, rate limiting and gateway errors count as failures.
+    fn record_outcome(&self, result: reqwest::Result<Response>) -> Result<Response> {
+        match result {
+            Ok(response) => {
+                if is_circuit_failure_status(response.status().as_u16()) {
+                    self.circuit_breaker.record_failure(&self.endpoint);
+                } else {
+                    self.circuit_breaker.record_success(&self.endpoint);
+                }
+                Ok(response)
+            }
+            Err(e) => {
+                if e.is_timeout() || e.is_connect() || e.is_request() {
+                    self.circuit_breaker.record_failure(&self.endpoint);
+                }
+                Err(e.into())
+            }
+        }
+    }
+
     async fn get_oauth_token(&self, config: &OAuthConfig) -> Result<String> {
         super::oauth::get_oauth_token_async(
             &config.host,
@@ -348,8 +421,10 @@ impl<'a> ApiRequestBuilder<'a> {
             serde_json::to_string(payload).unwrap_or_else(|_| "{}".to_string())
         );
 
+        self.client.check_circuit()?;
         let request = self.send_request(|url, client| client.post(url)).await?;
-        Ok(request.json(payload).send().await?)
+        self.client
+            .record_outcome(request.json(payload).send().await)
     }
 
     pub async fn api_get(self) -> Result<ApiResponse> {
@@ -358,8 +433,9 @@ impl<'a> ApiRequestBuilder<'a> {
     }
 
     pub async fn response_get(self) -> Result<Response> {
+        self.client.check_circuit()?;
         let request = self.send_request(|url, client| client.get(url)).await?;
-        Ok(request.send().await?)
+        self.client.record_outcome(request.send().await)
     }
 
     async fn send_request<F>(&self, request_builder: F) -> Result<reqwest::RequestBuilder>
@@ -454,4 +530,71 @@ mod tests {
 
         assert!(!headers.contains_key(SESSION_ID_HEADER));
     }
+
+    #[tokio::test]
+    async fn test_rate_limiting_trips_only_this_hosts_circuit() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let limited = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&limited)
+            .await;
+        let healthy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&healthy)
+            .await;
+
+        let client = ApiClient::new(
+            limited.uri(),
+            AuthMethod::BearerToken("test-token".to_string()),
+        )
+        .unwrap();
+        let threshold = client.circuit_breaker.config().failure_threshold;
+        for _ in 0..threshold {
+            let response = client.request("v1/models").response_get().await.unwrap();
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        }
+
+        let err = client
+            .request("v1/models")
+            .response_get()
+            .await
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<crate::network::CircuitOpenError>()
+            .is_some());
+        assert_eq!(
+            limited.received_requests().await.unwrap().len(),
+            threshold as usize
+        );
+
+        let other = ApiClient::new(
+            healthy.uri(),
+            AuthMethod::BearerToken("test-token".to_string()),
+        )
+        .unwrap();
+        assert!(other.request("v1/models").response_get().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_count_as_failures() {
+        let client = ApiClient::new(
+            "http://127.0.0.1:9".to_string(),
+            AuthMethod::BearerToken("test-token".to_string()),
+        )
+        .unwrap();
+
+        assert!(client.request("v1/models").response_get().await.is_err());
+        assert_eq!(
+            client
+                .circuit_breaker
+                .status(&client.endpoint)
+                .unwrap()
+                .consecutive_failures,
+            1
+        );
+    }
 }
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::canonical::{map_to_canonical_model, CanonicalModelRegistry};
 use super::errors::ProviderError;
+use super::prompt_cache::PromptCacheStyle;
 use super::retry::RetryConfig;
 use crate::config::base::ConfigValue;
 use crate::conversation::message::Message;
@@ -473,6 +474,11 @@ pub trait Provider: Send + Sync {
         false
     }
 
+    /// How prompt cache markers are added to this provider's requests
+    fn prompt_cache_style(&self) -> PromptCacheStyle {
+        PromptCacheStyle::for_provider(self.get_name(), &self.get_model_config().model_name)
+    }
+
     /// Create embeddings if supported. Default implementation returns an error.
     async fn create_embeddings(&self, _texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
         Err(ProviderError::ExecutionError(
@@ -501,6 +507,12 @@ pub trait Provider: Send + Sync {
         false
     }
 
+    /// Whether `stream` continues a partial response when `messages` ends with
+    /// an assistant message, instead of starting over
+    fn supports_stream_continuation(&self) -> bool {
+        false
+    }
+
     /// Get the currently active model name
     /// For regular providers, this returns the configured model
     /// For LeadWorkerProvider, this returns the currently active model (lead or worker)
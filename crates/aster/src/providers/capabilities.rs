@@ -0,0 +1,165 @@
+use crate::model::ModelConfig;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// Output limit assumed for models we know nothing about
+const DEFAULT_MAX_OUTPUT_TOKENS: usize = 4_096;
+
+/// What a model can accept and produce.
+///
+/// Used to avoid sending requests a model cannot handle, e.g. images to a
+/// text-only model or tool definitions to a model without function calling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    pub supports_vision: bool,
+    pub supports_function_calling: bool,
+    /// Native JSON output mode (`response_format` or equivalent)
+    pub supports_json_mode: bool,
+    pub max_output_tokens: usize,
+    pub context_window: usize,
+}
+
+impl ModelCapabilities {
+    /// The capabilities assumed for an unknown model: text only, no tools,
+    /// no JSON mode and a small output limit.
+    pub fn conservative() -> Self {
+        Self {
+            supports_vision: false,
+            supports_function_calling: false,
+            supports_json_mode: false,
+            max_output_tokens: DEFAULT_MAX_OUTPUT_TOKENS,
+            context_window: ModelConfig::default_context_limit(),
+        }
+    }
+}
+
+/// (pattern, vision, function calling, json mode, max output tokens)
+///
+/// Matched in order against the lowercased model name, so more specific
+/// patterns must come before the families they belong to.
+type CapabilityEntry = (&'static str, bool, bool, bool, usize);
+
+static MODEL_CAPABILITIES: Lazy<Vec<CapabilityEntry>> = Lazy::new(|| {
+    vec![
+        // openai
+        ("gpt-5", true, true, true, 128_000),
+        ("gpt-4.1", true, true, true, 32_768),
+        ("gpt-4-1", true, true, true, 32_768),
+        ("gpt-4o", true, true, true, 16_384),
+        ("gpt-4-turbo", true, true, true, 4_096),
+        ("gpt-4", false, true, false, 8_192),
+        ("gpt-3.5-turbo", false, true, true, 4_096),
+        ("o1-mini", false, false, false, 65_536),
+        ("o3-mini", false, true, true, 100_000),
+        ("o4-mini", true, true, true, 100_000),
+        ("o3", true, true, true, 100_000),
+        ("o1", true, true, true, 100_000),
+        // anthropic
+        ("claude-opus-4", true, true, false, 32_000),
+        ("claude-sonnet-4", true, true, false, 64_000),
+        ("claude-haiku-4", true, true, false, 64_000),
+        ("claude-3-7-sonnet", true, true, false, 64_000),
+        ("claude-3-5-haiku", true, true, false, 8_192),
+        ("claude-3-5-sonnet", true, true, false, 8_192),
+        ("claude", true, true, false, 4_096),
+        // google
+        ("gemini-2.5", true, true, true, 65_536),
+        ("gemini", true, true, true, 8_192),
+        ("gemma-3-1b", false, false, false, 8_192),
+        ("gemma-3", true, false, false, 8_192),
+        ("gemma3", true, false, false, 8_192),
+        ("gemma", false, false, false, 8_192),
+        // vision variants of open models
+        ("llava", true, false, false, 4_096),
+        ("-vl", true, true, true, 8_192),
+        ("vision", true, true, false, 4_096),
+        // facebook
+        ("llama-4", true, true, true, 8_192),
+        ("llama", false, true, false, 4_096),
+        // qwen
+        ("qwen3-coder", false, true, true, 65_536),
+        ("qwen", false, true, true, 8_192),
+        // xai
+        ("grok-code-fast", false, true, true, 10_000),
+        ("grok-4", true, true, true, 64_000),
+        ("grok", false, true, true, 8_192),
+        // other
+        ("deepseek", false, true, true, 8_192),
+        ("kimi-k2", false, true, true, 16_384),
+        ("mistral", false, true, true, 8_192),
+    ]
+});
+
+/// Looks up the capabilities of `model`.
+///
+/// Provider prefixes such as `openai/gpt-4o` are fine since patterns are
+/// matched anywhere in the name. Models that match no pattern get
+/// [`ModelCapabilities::conservative`].
+pub fn capabilities(model: &str) -> ModelCapabilities {
+    known_capabilities(model).unwrap_or_else(|| {
+        let defaults = ModelCapabilities::conservative();
+        ModelCapabilities {
+            context_window: ModelConfig::get_model_specific_limit(&model.to_lowercase())
+                .unwrap_or(defaults.context_window),
+            ..defaults
+        }
+    })
+}
+
+/// Like [`capabilities`], but `None` for models that match no pattern.
+///
+/// Use this where guessing wrong for an unknown model would lose data, e.g.
+/// stripping images from a model that may well support them.
+pub fn known_capabilities(model: &str) -> Option<ModelCapabilities> {
+    let name = model.to_lowercase();
+    MODEL_CAPABILITIES
+        .iter()
+        .find(|(pattern, ..)| name.contains(pattern))
+        .map(|&(_, vision, tools, json, max_output)| ModelCapabilities {
+            supports_vision: vision,
+            supports_function_calling: tools,
+            supports_json_mode: json,
+            max_output_tokens: max_output,
+            context_window: ModelConfig::get_model_specific_limit(&name)
+                .unwrap_or_else(ModelConfig::default_context_limit),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_models() {
+        let gpt = capabilities("gpt-4o-2024-08-06");
+        assert!(gpt.supports_vision);
+        assert!(gpt.supports_function_calling);
+        assert!(gpt.supports_json_mode);
+        assert_eq!(gpt.max_output_tokens, 16_384);
+        assert_eq!(gpt.context_window, 128_000);
+
+        let claude = capabilities("anthropic/claude-sonnet-4-20250514");
+        assert!(claude.supports_vision);
+        assert!(claude.supports_function_calling);
+        assert!(!claude.supports_json_mode);
+        assert_eq!(claude.context_window, 200_000);
+
+        let o3_mini = capabilities("o3-mini");
+        assert!(!o3_mini.supports_vision);
+        assert!(o3_mini.supports_function_calling);
+
+        let gemma = capabilities("gemma-2-9b-it");
+        assert!(!gemma.supports_vision);
+        assert!(!gemma.supports_function_calling);
+        assert_eq!(gemma.context_window, 8_192);
+    }
+
+    #[test]
+    fn test_unknown_model_is_conservative() {
+        assert_eq!(
+            capabilities("my-local-finetune"),
+            ModelCapabilities::conservative()
+        );
+        assert_eq!(known_capabilities("my-local-finetune"), None);
+    }
+}
@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use rmcp::model::Tool;
+use serde::{Deserialize, Serialize};
+
+use super::base::{
+    LeadWorkerProviderTrait, MessageStream, Provider, ProviderMetadata, ProviderUsage,
+};
+use super::canonical::{map_to_canonical_model, CanonicalModelRegistry};
+use super::errors::ProviderError;
+use super::prompt_cache::PromptCacheStyle;
+use crate::config::Config;
+use crate::conversation::message::Message;
+use crate::model::ModelConfig;
+use crate::token_counter::create_token_counter;
+
+/// Output tokens assumed for the estimate when the model config has no max_tokens
+pub const DEFAULT_ESTIMATED_OUTPUT_TOKENS: usize = 4096;
+
+/// Prices in USD per token
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+}
+
+impl ModelPricing {
+    pub fn new(input: f64, output: f64) -> Self {
+        Self { input, output }
+    }
+
+    pub fn cost(&self, input_tokens: usize, output_tokens: usize) -> f64 {
+        input_tokens as f64 * self.input + output_tokens as f64 * self.output
+    }
+}
+
+/// Per-model pricing table
+///
+/// Models missing from the table fall back to the pricing in the bundled
+/// canonical model registry.
+#[derive(Debug, Clone, Default)]
+pub struct CostModel {
+    prices: HashMap<String, ModelPricing>,
+}
+
+impl CostModel {
+    pub fn new(prices: HashMap<String, ModelPricing>) -> Self {
+        Self { prices }
+    }
+
+    /// Load the table from `ASTER_MODEL_PRICING`, e.g.
+    /// `{"gpt-4o": {"input": 0.0000025, "output": 0.00001}}`
+    pub fn from_config() -> Self {
+        let prices = Config::global()
+            .get_param::<HashMap<String, ModelPricing>>("ASTER_MODEL_PRICING")
+            .unwrap_or_default();
+        Self::new(prices)
+    }
+
+    pub fn with_price(mut self, model: impl Into<String>, pricing: ModelPricing) -> Self {
+        self.prices.insert(model.into(), pricing);
+        self
+    }
+
+    /// Pricing for a model, or None if it is unknown
+    pub fn pricing(&self, provider: &str, model: &str) -> Option<ModelPricing> {
+        if let Some(pricing) = self.prices.get(model) {
+            return Some(*pricing);
+        }
+        let registry = CanonicalModelRegistry::bundled().ok()?;
+        let canonical = map_to_canonical_model(provider, model, registry)?;
+        let pricing = &registry.get(&canonical)?.pricing;
+        Some(ModelPricing::new(pricing.prompt?, pricing.completion?))
+    }
+
+    /// Actual cost of a completed request
+    pub fn usage_cost(&self, provider: &str, usage: &ProviderUsage) -> Option<f64> {
+        let pricing = self.pricing(provider, &usage.model)?;
+        let input = usage.usage.input_tokens.unwrap_or(0).max(0) as usize;
+        let output = usage.usage.output_tokens.unwrap_or(0).max(0) as usize;
+        Some(pricing.cost(input, output))
+    }
+}
+
+/// Config key for the per-session budget in USD
+pub const SESSION_BUDGET_CONFIG_KEY: &str = "ASTER_SESSION_BUDGET";
+
+/// Money spent and held for requests in flight, in USD
+#[derive(Debug, Default)]
+struct Ledger {
+    spent: f64,
+    reserved: f64,
+}
+
+/// The estimate held for one request until its actual cost is known
+///
+/// Dropping the reservation releases the estimate and charges the actual
+/// cost: the reported usage if there is one, otherwise the full estimate once
+/// the response has started, e.g. for a stream dropped before its usage
+/// arrived.
+struct Reservation {
+    ledger: Arc<Mutex<Ledger>>,
+    cost_model: Arc<CostModel>,
+    provider: String,
+    amount: f64,
+    usage: Option<ProviderUsage>,
+    started: bool,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let cost = match &self.usage {
+            Some(usage) => {
+                let cost = self.cost_model.usage_cost(&self.provider, usage);
+                if cost.is_none() {
+                    tracing::debug!("No pricing for model {}, cost not tracked", usage.model);
+                }
+                cost
+            }
+            None if self.started => Some(self.amount),
+            None => None,
+        };
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.reserved = (ledger.reserved - self.amount).max(0.0);
+        if let Some(cost) = cost {
+            ledger.spent += cost;
+        }
+    }
+}
+
+/// A provider that enforces a spending budget for one session
+///
+/// Before each request the worst-case cost (prompt tokens plus the maximum
+/// output tokens) is estimated and reserved; requests that could push the
+/// session over its budget, counting the reservations of requests still in
+/// flight, are refused with [`ProviderError::BudgetExceeded`] without being
+/// sent. The actual cost is computed from the returned usage. Requests to
+/// models without pricing are not limited.
+pub struct BudgetedProvider {
+    inner: Arc<dyn Provider>,
+    cost_model: Arc<CostModel>,
+    budget: Option<f64>,
+    ledger: Arc<Mutex<Ledger>>,
+}
+
+impl BudgetedProvider {
+    pub fn new(inner: Arc<dyn Provider>, cost_model: CostModel, budget: Option<f64>) -> Self {
+        Self {
+            inner,
+            cost_model: Arc::new(cost_model),
+            budget,
+            ledger: Arc::new(Mutex::new(Ledger::default())),
+        }
+    }
+
+    /// Wrap `inner` when `ASTER_SESSION_BUDGET` is configured, otherwise return it unchanged
+    pub fn from_config(inner: Arc<dyn Provider>) -> Arc<dyn Provider> {
+        match Config::global().get_param::<f64>(SESSION_BUDGET_CONFIG_KEY) {
+            Ok(budget) => Arc::new(Self::new(inner, CostModel::from_config(), Some(budget))),
+            Err(_) => inner,
+        }
+    }
+
+    /// Total cost of the requests made through this provider, in USD
+    pub fn session_cost(&self) -> f64 {
+        self.ledger.lock().unwrap().spent
+    }
+
+    pub fn budget(&self) -> Option<f64> {
+        self.budget
+    }
+
+    /// Estimate the worst-case cost of a request, or None if the model has no pricing
+    pub async fn estimate_cost(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<Option<f64>, ProviderError> {
+        let Some(pricing) = self
+            .cost_model
+            .pricing(self.inner.get_name(), &model_config.model_name)
+        else {
+            return Ok(None);
+        };
+        let counter = create_token_counter()
+            .await
+            .map_err(|e| ProviderError::ExecutionError(format!("Failed to count tokens: {}", e)))?;
+        let input_tokens = counter.count_chat_tokens(system, messages, tools);
+        let output_tokens = model_config
+            .max_tokens
+            .map(|t| t.max(0) as usize)
+            .unwrap_or(DEFAULT_ESTIMATED_OUTPUT_TOKENS);
+        Ok(Some(pricing.cost(input_tokens, output_tokens)))
+    }
+
+    /// Check the budget and reserve the estimated cost of the request
+    ///
+    /// The check and the reservation happen under one lock, so concurrent
+    /// requests cannot all pass the check against the same remaining budget.
+    async fn reserve(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<Reservation, ProviderError> {
+        let Some(budget) = self.budget else {
+            return Ok(self.reservation(0.0));
+        };
+        let Some(estimate) = self
+            .estimate_cost(model_config, system, messages, tools)
+            .await?
+        else {
+            return Ok(self.reservation(0.0));
+        };
+
+        {
+            let mut ledger = self.ledger.lock().unwrap();
+            let committed = ledger.spent + ledger.reserved;
+            if committed + estimate > budget {
+                return Err(ProviderError::BudgetExceeded(format!(
+                    "request to {} could cost up to ${:.4}, but only ${:.4} of the ${:.4} session budget is left",
+                    model_config.model_name,
+                    estimate,
+                    (budget - committed).max(0.0),
+                    budget
+                )));
+            }
+            ledger.reserved += estimate;
+        }
+        Ok(self.reservation(estimate))
+    }
+
+    /// A reservation for `amount`, which must already be added to the ledger
+    fn reservation(&self, amount: f64) -> Reservation {
+        Reservation {
+            ledger: Arc::clone(&self.ledger),
+            cost_model: Arc::clone(&self.cost_model),
+            provider: self.get_name().to_string(),
+            amount,
+            usage: None,
+            started: false,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for BudgetedProvider {
+    fn metadata() -> ProviderMetadata {
+        // This is a wrapper provider, so we return minimal metadata
+        ProviderMetadata::empty()
+    }
+
+    fn get_name(&self) -> &str {
+        self.inner.get_name()
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.inner.get_model_config()
+    }
+
+    async fn complete_with_model(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let mut reservation = self.reserve(model_config, system, messages, tools).await?;
+        let (message, usage) = self
+            .inner
+            .complete_with_model(model_config, system, messages, tools)
+            .await?;
+        reservation.usage = Some(usage.clone());
+        Ok((message, usage))
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        // Let the inner provider pick the model, e.g. lead or worker
+        let mut reservation = self
+            .reserve(&self.get_model_config(), system, messages, tools)
+            .await?;
+        let (message, usage) = self.inner.complete(system, messages, tools).await?;
+        reservation.usage = Some(usage.clone());
+        Ok((message, usage))
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let mut reservation = self
+            .reserve(&self.get_model_config(), system, messages, tools)
+            .await?;
+        let mut stream = self.inner.stream(system, messages, tools).await?;
+
+        Ok(Box::pin(async_stream::stream! {
+            // Usage is reported as a running total, so only the last one is
+            // charged, when the stream ends or is dropped
+            while let Some(item) = stream.next().await {
+                if let Ok((message, usage)) = &item {
+                    reservation.started |= message.is_some();
+                    if let Some(usage) = usage {
+                        reservation.usage = Some(usage.clone());
+                    }
+                }
+                yield item;
+            }
+        }))
+    }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.inner.fetch_supported_models().await
+    }
+
+    async fn fetch_recommended_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.inner.fetch_recommended_models().await
+    }
+
+    async fn map_to_canonical_model(
+        &self,
+        provider_model: &str,
+    ) -> Result<Option<String>, ProviderError> {
+        self.inner.map_to_canonical_model(provider_model).await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        self.inner.create_embeddings(texts).await
+    }
+
+    async fn supports_cache_control(&self) -> bool {
+        self.inner.supports_cache_control().await
+    }
+
+    fn prompt_cache_style(&self) -> PromptCacheStyle {
+        self.inner.prompt_cache_style()
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        self.inner.as_lead_worker()
+    }
+
+    fn get_active_model_name(&self) -> String {
+        self.inner.get_active_model_name()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_stream_continuation(&self) -> bool {
+        self.inner.supports_stream_continuation()
+    }
+
+    async fn configure_oauth(&self) -> Result<(), ProviderError> {
+        self.inner.configure_oauth().await
+    }
+
+    fn retry_config(&self) -> super::retry::RetryConfig {
+        self.inner.retry_config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct PricedProvider {
+        calls: AtomicUsize,
+        model_config: ModelConfig,
+    }
+
+    fn priced_usage() -> ProviderUsage {
+        ProviderUsage::new(
+            "test-priced-model".to_string(),
+            Usage::new(Some(1000), Some(500), None),
+        )
+    }
+
+    /// $1 per 1k input tokens, $2 per 1k output tokens; a request costs $2
+    fn budgeted(budget: f64) -> (Arc<PricedProvider>, BudgetedProvider) {
+        let mut model_config = ModelConfig::new_or_fail("test-priced-model");
+        model_config.max_tokens = Some(500);
+        let inner = Arc::new(PricedProvider {
+            calls: AtomicUsize::new(0),
+            model_config,
+        });
+        let cost_model =
+            CostModel::default().with_price("test-priced-model", ModelPricing::new(0.001, 0.002));
+        let provider = BudgetedProvider::new(inner.clone(), cost_model, Some(budget));
+        (inner, provider)
+    }
+
+    #[async_trait]
+    impl Provider for PricedProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "priced"
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            Ok((Message::assistant().with_text("ok"), priced_usage()))
+        }
+
+        /// Reports usage with the first chunk, then never finishes
+        async fn stream(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<MessageStream, ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let first = Ok((
+                Some(Message::assistant().with_text("partial")),
+                Some(priced_usage()),
+            ));
+            Ok(Box::pin(
+                futures::stream::iter(vec![first]).chain(futures::stream::pending()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_budget_blocks_over_limit_request() {
+        let (inner, provider) = budgeted(3.0);
+        let messages = vec![Message::user().with_text("hello")];
+
+        provider.complete("", &messages, &[]).await.unwrap();
+        assert!((provider.session_cost() - 2.0).abs() < 1e-9);
+
+        // Another request would need at least $1 for its 500 output tokens
+        let err = provider.complete("", &messages, &[]).await.unwrap_err();
+        assert!(matches!(err, ProviderError::BudgetExceeded(_)));
+        assert!(err.to_string().contains("session budget"));
+        assert_eq!(
+            inner.calls.load(Ordering::SeqCst),
+            1,
+            "request must not be sent"
+        );
+        assert!((provider.session_cost() - 2.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_the_budget() {
+        // Each request reserves about $1, so only one fits in $1.50
+        let (inner, provider) = budgeted(1.5);
+        let messages = vec![Message::user().with_text("hello")];
+
+        let (a, b) = tokio::join!(
+            provider.complete("", &messages, &[]),
+            provider.complete("", &messages, &[])
+        );
+
+        assert_eq!(usize::from(a.is_ok()) + usize::from(b.is_ok()), 1);
+        assert!([a, b]
+            .into_iter()
+            .any(|r| matches!(r, Err(ProviderError::BudgetExceeded(_)))));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_dropped_early_is_charged() {
+        let (_, provider) = budgeted(10.0);
+        let messages = vec![Message::user().with_text("hello")];
+
+        let mut stream = provider.stream("", &messages, &[]).await.unwrap();
+        stream.next().await.unwrap().unwrap();
+        drop(stream);
+
+        assert!((provider.session_cost() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unpriced_model_has_no_cost() {
+        let usage = ProviderUsage::new(
+            "some-unknown-local-model".to_string(),
+            Usage::new(Some(10), Some(10), None),
+        );
+        assert_eq!(CostModel::default().usage_cost("custom", &usage), None);
+    }
+}
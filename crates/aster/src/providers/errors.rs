@@ -30,6 +30,9 @@ pub enum ProviderError {
 
     #[error("Unsupported operation: {0}")]
     NotImplemented(String),
+
+    #[error("Budget exceeded: {0}")]
+    BudgetExceeded(String),
 }
 
 impl ProviderError {
@@ -43,6 +46,7 @@ impl ProviderError {
             ProviderError::ExecutionError(_) => "execution",
             ProviderError::UsageError(_) => "usage",
             ProviderError::NotImplemented(_) => "not_implemented",
+            ProviderError::BudgetExceeded(_) => "budget",
         }
     }
 }
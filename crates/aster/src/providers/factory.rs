@@ -8,6 +8,7 @@ use super::{
     claude_code::ClaudeCodeProvider,
     codex::CodexProvider,
     codex_stateful::CodexStatefulProvider,
+    cost::BudgetedProvider,
     cursor_agent::CursorAgentProvider,
     databricks::DatabricksProvider,
     gcpvertexai::GcpVertexAIProvider,
@@ -20,6 +21,7 @@ use super::{
     openai::OpenAiProvider,
     openrouter::OpenRouterProvider,
     provider_registry::ProviderRegistry,
+    response_cache::CachingProvider,
     snowflake::SnowflakeProvider,
     tetrate::TetrateProvider,
     venice::VeniceProvider,
@@ -273,20 +275,27 @@ fn map_provider_alias(name: &str) -> String {
 pub async fn create(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
     let config = crate::config::Config::global();
 
-    if let Ok(lead_model_name) = config.get_param::<String>("ASTER_LEAD_MODEL") {
+    let provider = if let Ok(lead_model_name) = config.get_param::<String>("ASTER_LEAD_MODEL") {
         tracing::info!("Creating lead/worker provider from environment variables");
-        return create_lead_worker_from_env(name, &model, &lead_model_name).await;
-    }
+        create_lead_worker_from_env(name, &model, &lead_model_name).await?
+    } else {
+        let constructor = get_from_registry(name).await?.constructor.clone();
+        constructor(model).await?
+    };
+    Ok(wrap_from_config(provider))
+}
 
-    let constructor = get_from_registry(name).await?.constructor.clone();
-    constructor(model).await
+/// Apply the optional response cache and session budget; cache hits are free
+fn wrap_from_config(provider: Arc<dyn Provider>) -> Arc<dyn Provider> {
+    BudgetedProvider::from_config(CachingProvider::from_config(provider))
 }
 
 pub async fn create_with_default_model(name: impl AsRef<str>) -> Result<Arc<dyn Provider>> {
-    get_from_registry(name.as_ref())
+    let provider = get_from_registry(name.as_ref())
         .await?
         .create_with_default_model()
-        .await
+        .await?;
+    Ok(wrap_from_config(provider))
 }
 
 pub async fn create_with_named_model(
@@ -21,7 +21,6 @@ const TOOL_USE_TYPE: &str = "tool_use";
 const TOOL_RESULT_TYPE: &str = "tool_result";
 const THINKING_TYPE: &str = "thinking";
 const REDACTED_THINKING_TYPE: &str = "redacted_thinking";
-const CACHE_CONTROL_FIELD: &str = "cache_control";
 const ID_FIELD: &str = "id";
 const NAME_FIELD: &str = "name";
 const INPUT_FIELD: &str = "input";
@@ -147,30 +146,6 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
         }));
     }
 
-    // Add "cache_control" to the last and second-to-last "user" messages.
-    // During each turn, we mark the final message with cache_control so the conversation can be
-    // incrementally cached. The second-to-last user message is also marked for caching with the
-    // cache_control parameter, so that this checkpoint can read from the previous cache.
-    let mut user_count = 0;
-    for message in anthropic_messages.iter_mut().rev() {
-        if message.get(ROLE_FIELD) == Some(&json!(USER_ROLE)) {
-            if let Some(content) = message.get_mut(CONTENT_FIELD) {
-                if let Some(content_array) = content.as_array_mut() {
-                    if let Some(last_content) = content_array.last_mut() {
-                        last_content.as_object_mut().unwrap().insert(
-                            CACHE_CONTROL_FIELD.to_string(),
-                            json!({ TYPE_FIELD: "ephemeral" }),
-                        );
-                    }
-                }
-            }
-            user_count += 1;
-            if user_count >= 2 {
-                break;
-            }
-        }
-    }
-
     anthropic_messages
 }
 
@@ -198,15 +173,6 @@ pub fn format_tools(tools: &[Tool]) -> Vec<Value> {
         }
     }
 
-    // Add "cache_control" to the last tool spec, if any. This means that all tool definitions,
-    // will be cached as a single prefix.
-    if let Some(last_tool) = tool_specs.last_mut() {
-        last_tool.as_object_mut().unwrap().insert(
-            CACHE_CONTROL_FIELD.to_string(),
-            json!({ TYPE_FIELD: "ephemeral" }),
-        );
-    }
-
     tool_specs
 }
 
@@ -215,7 +181,6 @@ pub fn format_system(system: &str) -> Value {
     json!([{
         TYPE_FIELD: TEXT_TYPE,
         TEXT_TYPE: system,
-        CACHE_CONTROL_FIELD: { TYPE_FIELD: "ephemeral" }
     }])
 }
 
@@ -385,7 +350,8 @@ pub fn create_request(
     messages: &[Message],
     tools: &[Tool],
 ) -> Result<Value> {
-    let anthropic_messages = format_messages(messages);
+    let mut anthropic_messages = format_messages(messages);
+    trim_assistant_prefill(&mut anthropic_messages);
     let tool_specs = format_tools(tools);
     let system_spec = format_system(system);
 
@@ -461,6 +427,35 @@ pub fn create_request(
     Ok(payload)
 }
 
+/// Trim trailing whitespace from a final assistant message
+///
+/// A trailing assistant message is a prefill the model continues from (used to
+/// resume a dropped stream); the API rejects prefills ending in whitespace.
+fn trim_assistant_prefill(messages: &mut Vec<Value>) {
+    let Some(last) = messages.last_mut() else {
+        return;
+    };
+    if last.get(ROLE_FIELD).and_then(|r| r.as_str()) != Some(ASSISTANT_ROLE) {
+        return;
+    }
+    let Some(content) = last.get_mut(CONTENT_FIELD).and_then(|c| c.as_array_mut()) else {
+        return;
+    };
+    if let Some(block) = content.last_mut() {
+        if block.get(TYPE_FIELD).and_then(|t| t.as_str()) == Some(TEXT_TYPE) {
+            let trimmed = block[TEXT_TYPE].as_str().unwrap_or_default().trim_end();
+            if trimmed.is_empty() {
+                content.pop();
+            } else {
+                block[TEXT_TYPE] = json!(trimmed);
+            }
+        }
+    }
+    if content.is_empty() {
+        messages.pop();
+    }
+}
+
 /// Process streaming response from Anthropic's API
 pub fn response_to_streaming_message<S>(
     mut stream: S,
@@ -880,6 +875,24 @@ mod tests {
         assert_eq!(spec[2]["content"][0]["text"], "How are you?");
     }
 
+    #[test]
+    fn test_assistant_prefill_is_trimmed() {
+        let messages = vec![
+            Message::user().with_text("Say hello"),
+            Message::assistant().with_text("Hello, "),
+        ];
+        let payload = create_request(
+            &ModelConfig::new_or_fail("claude-sonnet-4-20250514"),
+            "",
+            &messages,
+            &[],
+        )
+        .unwrap();
+        let last = payload["messages"].as_array().unwrap().last().unwrap();
+        assert_eq!(last["role"], "assistant");
+        assert_eq!(last["content"][0]["text"], "Hello,");
+    }
+
     #[test]
     fn test_tools_to_anthropic_spec() {
         let tools = vec![
@@ -919,8 +932,8 @@ mod tests {
         assert_eq!(spec[1]["name"], "weather");
         assert_eq!(spec[1]["description"], "Get weather information");
 
-        // Verify cache control is added to last tool
-        assert!(spec[1].get("cache_control").is_some());
+        // Cache markers are added by the prompt cache middleware, not the format
+        assert!(spec[1].get("cache_control").is_none());
     }
 
     #[test]
@@ -933,7 +946,7 @@ mod tests {
         assert_eq!(spec_array.len(), 1);
         assert_eq!(spec_array[0]["type"], "text");
         assert_eq!(spec_array[0]["text"], system);
-        assert!(spec_array[0].get("cache_control").is_some());
+        assert!(spec_array[0].get("cache_control").is_none());
     }
 
     #[test]
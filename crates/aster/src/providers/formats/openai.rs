@@ -727,6 +727,10 @@ pub fn create_request(
             .insert(key.to_string(), json!(tokens));
     }
 
+    if model_config.json_mode {
+        payload["response_format"] = json!({"type": "json_object"});
+    }
+
     if for_streaming {
         payload["stream"] = json!(true);
         payload["stream_options"] = json!({"include_usage": true});
@@ -1304,6 +1308,7 @@ mod tests {
             toolshim: false,
             toolshim_model: None,
             fast_model: None,
+            json_mode: false,
         };
         let request = create_request(
             &model_config,
@@ -1343,6 +1348,7 @@ mod tests {
             toolshim: false,
             toolshim_model: None,
             fast_model: None,
+            json_mode: false,
         };
         let request = create_request(
             &model_config,
@@ -1383,6 +1389,7 @@ mod tests {
             toolshim: false,
             toolshim_model: None,
             fast_model: None,
+            json_mode: false,
         };
         let request = create_request(
             &model_config,
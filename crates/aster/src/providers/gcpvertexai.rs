@@ -20,6 +20,7 @@ use crate::providers::formats::gcpvertexai::{
 
 use crate::providers::formats::gcpvertexai::GcpLocation::Iowa;
 use crate::providers::gcpauth::GcpAuth;
+use crate::providers::prompt_cache::{PromptCacheMiddleware, PromptCacheStyle};
 use crate::providers::retry::RetryConfig;
 use crate::providers::utils::RequestLog;
 use rmcp::model::Tool;
@@ -518,7 +519,9 @@ impl Provider for GcpVertexAIProvider {
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
         // Create request and context
-        let (request, context) = create_request(model_config, system, messages, tools)?;
+        let (mut request, context) = create_request(model_config, system, messages, tools)?;
+        let style = PromptCacheStyle::for_provider(self.get_name(), &model_config.model_name);
+        PromptCacheMiddleware::default().apply(style, messages, &mut request);
 
         // Send request and process response
         let response = self.post(&request, &context).await?;
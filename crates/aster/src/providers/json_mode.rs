@@ -0,0 +1,243 @@
+use crate::conversation::message::Message;
+use crate::providers::base::{Provider, ProviderUsage};
+use crate::providers::capabilities::capabilities;
+use crate::providers::errors::ProviderError;
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StructuredOutputError {
+    #[error("Invalid JSON schema: {0}")]
+    InvalidSchema(String),
+
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    #[error("Model did not return valid JSON after retry: {}", .errors.join("; "))]
+    InvalidResponse {
+        response: String,
+        errors: Vec<String>,
+    },
+}
+
+/// Requests a JSON response matching `schema` and validates it.
+///
+/// When the model supports native JSON mode it is switched on through
+/// `ModelConfig::json_mode`. The schema is always appended to the system
+/// prompt, since native JSON mode only guarantees syntax. Responses are
+/// repaired where possible (code fences, surrounding prose) and, if still
+/// invalid, the model is asked once more with the validation errors.
+pub async fn complete_json(
+    provider: &dyn Provider,
+    system: &str,
+    messages: &[Message],
+    schema: &Value,
+) -> Result<(Value, ProviderUsage), StructuredOutputError> {
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| StructuredOutputError::InvalidSchema(e.to_string()))?;
+
+    let model_config = provider.get_model_config();
+    let native = capabilities(&model_config.model_name).supports_json_mode;
+    let model_config = model_config.with_json_mode(native);
+    let system = format!("{}\n\n{}", system, schema_instruction(schema));
+
+    let mut messages = messages.to_vec();
+    let mut total_usage: Option<ProviderUsage> = None;
+
+    loop {
+        let (response, usage) = provider
+            .complete_with_model(&model_config, &system, &messages, &[])
+            .await?;
+        let total = match &total_usage {
+            Some(total) => total.combine_with(&usage),
+            None => usage,
+        };
+
+        let text = response.as_concat_text();
+        let errors = match parse_json_response(&text) {
+            Ok(value) => {
+                let errors: Vec<String> = validator
+                    .iter_errors(&value)
+                    .map(|e| format!("{} at '{}'", e, e.instance_path))
+                    .collect();
+                if errors.is_empty() {
+                    return Ok((value, total));
+                }
+                errors
+            }
+            Err(e) => vec![format!("not valid JSON: {}", e)],
+        };
+
+        if total_usage.is_some() {
+            return Err(StructuredOutputError::InvalidResponse {
+                response: text,
+                errors,
+            });
+        }
+
+        tracing::debug!(
+            "Retrying JSON response after validation errors: {:?}",
+            errors
+        );
+        messages.push(response);
+        messages.push(Message::user().with_text(format!(
+            "Your response did not match the required JSON schema:\n- {}\n\n\
+             Reply again with only the corrected JSON.",
+            errors.join("\n- ")
+        )));
+        total_usage = Some(total);
+    }
+}
+
+fn schema_instruction(schema: &Value) -> String {
+    format!(
+        "Respond with a single JSON value that conforms to this JSON Schema. \
+         Do not include any other text or code fences.\n\n{}",
+        serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+    )
+}
+
+/// Parses `text` as JSON, tolerating code fences and prose around the value.
+pub fn parse_json_response(text: &str) -> Result<Value, serde_json::Error> {
+    let trimmed = text.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+
+    serde_json::from_str(unfenced).or_else(|err| {
+        let start = unfenced.find(['{', '[']);
+        let end = unfenced.rfind(['}', ']']);
+        match (start, end) {
+            (Some(start), Some(end)) if start < end => unfenced
+                .get(start..=end)
+                .map_or(Err(err), serde_json::from_str),
+            _ => Err(err),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{ProviderMetadata, Usage};
+    use async_trait::async_trait;
+    use rmcp::model::Tool;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    struct ScriptedProvider {
+        model_config: ModelConfig,
+        responses: Mutex<Vec<String>>,
+        requests: Mutex<Vec<(ModelConfig, String, Vec<Message>)>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(model: &str, responses: &[&str]) -> Self {
+            Self {
+                model_config: ModelConfig::new_or_fail(model),
+                responses: Mutex::new(responses.iter().rev().map(|r| r.to_string()).collect()),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "scripted"
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete_with_model(
+            &self,
+            model_config: &ModelConfig,
+            system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.requests.lock().unwrap().push((
+                model_config.clone(),
+                system.to_string(),
+                messages.to_vec(),
+            ));
+            let text = self.responses.lock().unwrap().pop().unwrap();
+            Ok((
+                Message::assistant().with_text(text),
+                ProviderUsage::new(
+                    model_config.model_name.clone(),
+                    Usage::new(Some(10), Some(5), Some(15)),
+                ),
+            ))
+        }
+    }
+
+    fn person_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+            "required": ["name", "age"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retries_once_without_native_json_mode() {
+        let provider = ScriptedProvider::new(
+            "local-text-model",
+            &[
+                "Sure! ```json\n{\"name\": \"Ada\"}\n```",
+                "```json\n{\"name\": \"Ada\", \"age\": 36}\n```",
+            ],
+        );
+        let messages = vec![Message::user().with_text("Who wrote the first program?")];
+
+        let (value, usage) =
+            complete_json(&provider, "You are helpful.", &messages, &person_schema())
+                .await
+                .unwrap();
+
+        assert_eq!(value, json!({"name": "Ada", "age": 36}));
+        assert_eq!(usage.usage.total_tokens, Some(30));
+
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let (config, system, retry_messages) = &requests[1];
+        assert!(!config.json_mode);
+        assert!(system.contains("\"required\""));
+        assert_eq!(retry_messages.len(), 3);
+        assert!(retry_messages[2].as_concat_text().contains("age"));
+    }
+
+    #[tokio::test]
+    async fn test_fails_after_second_invalid_response() {
+        let provider = ScriptedProvider::new("local-text-model", &["not json", "still not json"]);
+
+        let err = complete_json(&provider, "", &[], &person_schema())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StructuredOutputError::InvalidResponse { ref response, .. } if response == "still not json"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_native_json_mode_is_requested() {
+        let provider = ScriptedProvider::new("gpt-4o", &["{\"name\": \"Ada\", \"age\": 36}"]);
+
+        complete_json(&provider, "", &[], &person_schema())
+            .await
+            .unwrap();
+        assert!(provider.requests.lock().unwrap()[0].0.json_mode);
+    }
+}
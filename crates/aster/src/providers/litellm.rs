@@ -7,6 +7,7 @@ use super::api_client::{ApiClient, AuthMethod};
 use super::base::{ConfigKey, ModelInfo, Provider, ProviderMetadata, ProviderUsage};
 use super::embedding::EmbeddingCapable;
 use super::errors::ProviderError;
+use super::prompt_cache::{PromptCacheMiddleware, PromptCacheStyle};
 use super::retry::ProviderRetry;
 use super::utils::{get_model, handle_response_openai_compat, ImageFormat, RequestLog};
 use crate::conversation::message::Message;
@@ -183,7 +184,11 @@ impl Provider for LiteLLMProvider {
         )?;
 
         if self.supports_cache_control().await {
-            payload = update_request_for_cache_control(&payload);
+            PromptCacheMiddleware::default().apply(
+                PromptCacheStyle::OpenAiCompatible,
+                messages,
+                &mut payload,
+            );
         }
 
         let response = self
@@ -267,71 +272,6 @@ impl EmbeddingCapable for LiteLLMProvider {
     }
 }
 
-/// Updates the request payload to include cache control headers for automatic prompt caching
-/// Adds ephemeral cache control to the last 2 user messages, system message, and last tool
-pub fn update_request_for_cache_control(original_payload: &Value) -> Value {
-    let mut payload = original_payload.clone();
-
-    if let Some(messages_spec) = payload
-        .as_object_mut()
-        .and_then(|obj| obj.get_mut("messages"))
-        .and_then(|messages| messages.as_array_mut())
-    {
-        let mut user_count = 0;
-        for message in messages_spec.iter_mut().rev() {
-            if message.get("role") == Some(&json!("user")) {
-                if let Some(content) = message.get_mut("content") {
-                    if let Some(content_str) = content.as_str() {
-                        *content = json!([{
-                            "type": "text",
-                            "text": content_str,
-                            "cache_control": { "type": "ephemeral" }
-                        }]);
-                    }
-                }
-                user_count += 1;
-                if user_count >= 2 {
-                    break;
-                }
-            }
-        }
-
-        if let Some(system_message) = messages_spec
-            .iter_mut()
-            .find(|msg| msg.get("role") == Some(&json!("system")))
-        {
-            if let Some(content) = system_message.get_mut("content") {
-                if let Some(content_str) = content.as_str() {
-                    *system_message = json!({
-                        "role": "system",
-                        "content": [{
-                            "type": "text",
-                            "text": content_str,
-                            "cache_control": { "type": "ephemeral" }
-                        }]
-                    });
-                }
-            }
-        }
-    }
-
-    if let Some(tools_spec) = payload
-        .as_object_mut()
-        .and_then(|obj| obj.get_mut("tools"))
-        .and_then(|tools| tools.as_array_mut())
-    {
-        if let Some(last_tool) = tools_spec.last_mut() {
-            if let Some(function) = last_tool.get_mut("function") {
-                function
-                    .as_object_mut()
-                    .unwrap()
-                    .insert("cache_control".to_string(), json!({ "type": "ephemeral" }));
-            }
-        }
-    }
-    payload
-}
-
 fn parse_custom_headers(headers_str: String) -> HashMap<String, String> {
     let mut headers = HashMap::new();
     for line in headers_str.lines() {
@@ -7,10 +7,12 @@ pub mod base;
 #[cfg(feature = "provider-aws")]
 pub mod bedrock;
 pub mod canonical;
+pub mod capabilities;
 pub mod claude_code;
 pub mod codex;
 pub mod codex_app_server;
 pub mod codex_stateful;
+pub mod cost;
 pub mod cursor_agent;
 pub mod databricks;
 pub mod embedding;
@@ -22,18 +24,22 @@ pub mod gcpvertexai;
 pub mod gemini_cli;
 pub mod githubcopilot;
 pub mod google;
+pub mod json_mode;
 pub mod lead_worker;
 pub mod litellm;
 pub mod oauth;
 pub mod ollama;
 pub mod openai;
 pub mod openrouter;
+pub mod prompt_cache;
 pub mod provider_registry;
 pub mod provider_test;
+pub mod response_cache;
 mod retry;
 #[cfg(feature = "provider-aws")]
 pub mod sagemaker_tgi;
 pub mod snowflake;
+pub mod stream_retry;
 pub mod testprovider;
 pub mod tetrate;
 pub mod toolshim;
@@ -5,6 +5,7 @@ use serde_json::{json, Value};
 use super::api_client::{ApiClient, AuthMethod};
 use super::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::errors::ProviderError;
+use super::prompt_cache::{PromptCacheMiddleware, PromptCacheStyle};
 use super::retry::ProviderRetry;
 use super::utils::{
     get_model, handle_response_google_compat, handle_response_openai_compat,
@@ -128,79 +129,6 @@ impl OpenRouterProvider {
     }
 }
 
-/// Update the request when using anthropic model.
-/// For anthropic model, we can enable prompt caching to save cost. Since openrouter is the OpenAI compatible
-/// endpoint, we need to modify the open ai request to have anthropic cache control field.
-fn update_request_for_anthropic(original_payload: &Value) -> Value {
-    let mut payload = original_payload.clone();
-
-    if let Some(messages_spec) = payload
-        .as_object_mut()
-        .and_then(|obj| obj.get_mut("messages"))
-        .and_then(|messages| messages.as_array_mut())
-    {
-        // Add "cache_control" to the last and second-to-last "user" messages.
-        // During each turn, we mark the final message with cache_control so the conversation can be
-        // incrementally cached. The second-to-last user message is also marked for caching with the
-        // cache_control parameter, so that this checkpoint can read from the previous cache.
-        let mut user_count = 0;
-        for message in messages_spec.iter_mut().rev() {
-            if message.get("role") == Some(&json!("user")) {
-                if let Some(content) = message.get_mut("content") {
-                    if let Some(content_str) = content.as_str() {
-                        *content = json!([{
-                            "type": "text",
-                            "text": content_str,
-                            "cache_control": { "type": "ephemeral" }
-                        }]);
-                    }
-                }
-                user_count += 1;
-                if user_count >= 2 {
-                    break;
-                }
-            }
-        }
-
-        // Update the system message to have cache_control field.
-        if let Some(system_message) = messages_spec
-            .iter_mut()
-            .find(|msg| msg.get("role") == Some(&json!("system")))
-        {
-            if let Some(content) = system_message.get_mut("content") {
-                if let Some(content_str) = content.as_str() {
-                    *system_message = json!({
-                        "role": "system",
-                        "content": [{
-                            "type": "text",
-                            "text": content_str,
-                            "cache_control": { "type": "ephemeral" }
-                        }]
-                    });
-                }
-            }
-        }
-    }
-
-    if let Some(tools_spec) = payload
-        .as_object_mut()
-        .and_then(|obj| obj.get_mut("tools"))
-        .and_then(|tools| tools.as_array_mut())
-    {
-        // Add "cache_control" to the last tool spec, if any. This means that all tool definitions,
-        // will be cached as a single prefix.
-        if let Some(last_tool) = tools_spec.last_mut() {
-            if let Some(function) = last_tool.get_mut("function") {
-                function
-                    .as_object_mut()
-                    .unwrap()
-                    .insert("cache_control".to_string(), json!({ "type": "ephemeral" }));
-            }
-        }
-    }
-    payload
-}
-
 async fn create_request_based_on_model(
     provider: &OpenRouterProvider,
     system: &str,
@@ -216,9 +144,7 @@ async fn create_request_based_on_model(
         false,
     )?;
 
-    if provider.supports_cache_control().await {
-        payload = update_request_for_anthropic(&payload);
-    }
+    PromptCacheMiddleware::default().apply(provider.prompt_cache_style(), messages, &mut payload);
 
     payload
         .as_object_mut()
@@ -374,6 +300,20 @@ impl Provider for OpenRouterProvider {
             .starts_with(OPENROUTER_MODEL_PREFIX_ANTHROPIC)
     }
 
+    /// Anthropic models behind OpenRouter accept `cache_control` blocks in the
+    /// OpenAI-compatible request
+    fn prompt_cache_style(&self) -> PromptCacheStyle {
+        if self
+            .model
+            .model_name
+            .starts_with(OPENROUTER_MODEL_PREFIX_ANTHROPIC)
+        {
+            PromptCacheStyle::OpenAiCompatible
+        } else {
+            PromptCacheStyle::None
+        }
+    }
+
     fn supports_streaming(&self) -> bool {
         self.supports_streaming
     }
@@ -393,9 +333,7 @@ impl Provider for OpenRouterProvider {
             true,
         )?;
 
-        if self.supports_cache_control().await {
-            payload = update_request_for_anthropic(&payload);
-        }
+        PromptCacheMiddleware::default().apply(self.prompt_cache_style(), messages, &mut payload);
 
         payload
             .as_object_mut()
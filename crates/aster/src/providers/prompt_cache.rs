@@ -0,0 +1,428 @@
+use rmcp::model::Role;
+use serde_json::{json, Map, Value};
+
+use crate::context::cache_controller::CacheController;
+use crate::context::token_estimator::TokenEstimator;
+use crate::context::types::CacheConfig;
+use crate::conversation::message::Message;
+
+const CACHE_CONTROL_FIELD: &str = "cache_control";
+
+/// Anthropic accepts at most this many cache breakpoints per request
+pub const MAX_CACHE_BREAKPOINTS: usize = 4;
+
+/// How a provider expects prompt cache markers in its request payload
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PromptCacheStyle {
+    /// The provider does not support explicit cache markers
+    #[default]
+    None,
+    /// Anthropic messages API: `system` is a list of blocks and every message
+    /// has a list of content blocks
+    Anthropic,
+    /// OpenAI chat completions format forwarded to a model that understands
+    /// Anthropic-style `cache_control` blocks (e.g. Claude behind a router)
+    OpenAiCompatible,
+}
+
+impl PromptCacheStyle {
+    /// Pick the marker style for a provider and model
+    pub fn for_provider(provider_name: &str, model_name: &str) -> Self {
+        let is_claude = model_name.to_lowercase().contains("claude");
+        match provider_name {
+            "anthropic" => PromptCacheStyle::Anthropic,
+            "gcp_vertex_ai" if is_claude => PromptCacheStyle::Anthropic,
+            "openrouter" | "litellm" if is_claude => PromptCacheStyle::OpenAiCompatible,
+            _ => PromptCacheStyle::None,
+        }
+    }
+}
+
+/// Number of trailing user messages marked for incremental caching
+const CACHED_USER_MESSAGES: usize = 2;
+
+/// Adds cache control markers to the stable prefix of a request payload
+///
+/// The tool definitions and the system prompt are marked first when their
+/// prefix reaches `min_tokens_for_cache`, followed by the last two user
+/// messages that [`CacheController::get_cache_eligibility`] reports as
+/// cacheable: the final one lets the next turn read the whole conversation
+/// from the cache, and the one before it reads the cache written by the
+/// previous turn. Markers already present in the payload are kept and counted
+/// against [`MAX_CACHE_BREAKPOINTS`], so applying the middleware twice is a
+/// no-op.
+#[derive(Debug, Clone, Default)]
+pub struct PromptCacheMiddleware {
+    config: CacheConfig,
+}
+
+impl PromptCacheMiddleware {
+    pub fn new(config: CacheConfig) -> Self {
+        Self { config }
+    }
+
+    /// Mark the request payload built from `messages`, returning the number
+    /// of markers added
+    pub fn apply(
+        &self,
+        style: PromptCacheStyle,
+        messages: &[Message],
+        payload: &mut Value,
+    ) -> usize {
+        if style == PromptCacheStyle::None {
+            return 0;
+        }
+        let min_tokens = self.config.min_tokens_for_cache;
+        let tools_tokens = payload.get("tools").map(estimate_value_tokens).unwrap_or(0);
+        let system_tokens = system_content(style, payload)
+            .map(estimate_value_tokens)
+            .unwrap_or(0);
+        let user_texts = eligible_user_texts(messages, &self.config);
+
+        let mut budget = MAX_CACHE_BREAKPOINTS.saturating_sub(count_markers(payload));
+        let mut added = 0;
+        let mut mark = |block: Option<&mut Map<String, Value>>| {
+            let Some(block) = block else {
+                return;
+            };
+            if budget == 0 || block.contains_key(CACHE_CONTROL_FIELD) {
+                return;
+            }
+            block.insert(
+                CACHE_CONTROL_FIELD.to_string(),
+                json!({ "type": "ephemeral" }),
+            );
+            budget -= 1;
+            added += 1;
+        };
+
+        // Cached prefixes are read in the order tools, system, messages
+        if self.config.cache_tool_definitions && tools_tokens >= min_tokens {
+            mark(last_tool(style, payload));
+        }
+        if self.config.cache_system_prompt && tools_tokens + system_tokens >= min_tokens {
+            mark(system_block(style, payload));
+        }
+        for text in &user_texts {
+            mark(user_block_with_text(payload, text));
+        }
+        added
+    }
+}
+
+fn estimate_value_tokens(value: &Value) -> usize {
+    TokenEstimator::estimate_tokens(&value.to_string())
+}
+
+/// Text of the last text block of the last cacheable user messages, newest first
+fn eligible_user_texts(messages: &[Message], config: &CacheConfig) -> Vec<String> {
+    let eligibility = CacheController::get_cache_eligibility(messages, config);
+    eligibility
+        .cacheable_indices
+        .iter()
+        .rev()
+        .map(|&index| &messages[index])
+        .filter(|message| message.role == Role::User && message.is_agent_visible())
+        .filter_map(|message| {
+            message
+                .content
+                .iter()
+                .rev()
+                .find_map(|content| content.as_text())
+                .filter(|text| !text.is_empty())
+                .map(str::to_string)
+        })
+        .take(CACHED_USER_MESSAGES)
+        .collect()
+}
+
+fn count_markers(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => {
+            usize::from(map.contains_key(CACHE_CONTROL_FIELD))
+                + map.values().map(count_markers).sum::<usize>()
+        }
+        Value::Array(items) => items.iter().map(count_markers).sum(),
+        _ => 0,
+    }
+}
+
+/// The last tool definition; OpenAI-style tools carry the marker on `function`
+fn last_tool(style: PromptCacheStyle, payload: &mut Value) -> Option<&mut Map<String, Value>> {
+    let tool = payload.get_mut("tools")?.as_array_mut()?.last_mut()?;
+    match style {
+        PromptCacheStyle::OpenAiCompatible if tool.get("function").is_some() => {
+            tool.get_mut("function")?.as_object_mut()
+        }
+        _ => tool.as_object_mut(),
+    }
+}
+
+/// Turn plain string content into a single text block so it can carry a marker
+fn last_content_block(content: &mut Value) -> Option<&mut Map<String, Value>> {
+    if let Some(text) = content.as_str() {
+        if text.is_empty() {
+            return None;
+        }
+        *content = json!([{ "type": "text", "text": text }]);
+    }
+    content.as_array_mut()?.last_mut()?.as_object_mut()
+}
+
+fn system_content(style: PromptCacheStyle, payload: &Value) -> Option<&Value> {
+    match style {
+        PromptCacheStyle::Anthropic => payload.get("system"),
+        PromptCacheStyle::OpenAiCompatible => {
+            let first = payload.get("messages")?.as_array()?.first()?;
+            if first.get("role").and_then(Value::as_str) != Some("system") {
+                return None;
+            }
+            first.get("content")
+        }
+        PromptCacheStyle::None => None,
+    }
+}
+
+fn system_block(style: PromptCacheStyle, payload: &mut Value) -> Option<&mut Map<String, Value>> {
+    match style {
+        PromptCacheStyle::Anthropic => last_content_block(payload.get_mut("system")?),
+        PromptCacheStyle::OpenAiCompatible => {
+            let first = payload.get_mut("messages")?.as_array_mut()?.first_mut()?;
+            if first.get("role").and_then(Value::as_str) != Some("system") {
+                return None;
+            }
+            last_content_block(first.get_mut("content")?)
+        }
+        PromptCacheStyle::None => None,
+    }
+}
+
+/// The last content block of the latest user entry whose final text is `text`
+fn user_block_with_text<'a>(
+    payload: &'a mut Value,
+    text: &str,
+) -> Option<&'a mut Map<String, Value>> {
+    let entry = payload
+        .get_mut("messages")?
+        .as_array_mut()?
+        .iter_mut()
+        .rev()
+        .filter(|entry| entry.get("role").and_then(Value::as_str) == Some("user"))
+        .find(|entry| last_text(entry.get("content")) == Some(text))?;
+    last_content_block(entry.get_mut("content")?)
+}
+
+fn last_text(content: Option<&Value>) -> Option<&str> {
+    let content = content?;
+    if let Some(text) = content.as_str() {
+        return Some(text);
+    }
+    content
+        .as_array()?
+        .iter()
+        .rev()
+        .find_map(|block| block.get("text").and_then(Value::as_str))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_marker(value: &Value) -> bool {
+        value.get(CACHE_CONTROL_FIELD).is_some()
+    }
+
+    /// Middleware that treats every non-empty message as large enough to cache
+    fn middleware() -> PromptCacheMiddleware {
+        PromptCacheMiddleware::new(CacheConfig {
+            min_tokens_for_cache: 1,
+            cache_recent_messages: 10,
+            ..CacheConfig::default()
+        })
+    }
+
+    fn conversation(turns: &[(Role, &str)]) -> Vec<Message> {
+        turns
+            .iter()
+            .map(|(role, text)| match role {
+                Role::User => Message::user().with_text(*text),
+                Role::Assistant => Message::assistant().with_text(*text),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markers_openai_compatible() {
+        let messages = conversation(&[
+            (Role::User, "Here is the design document."),
+            (Role::Assistant, "Understood."),
+            (Role::User, "What changed?"),
+        ]);
+        let mut payload = json!({
+            "messages": [
+                { "role": "system", "content": "You are a helpful assistant." },
+                { "role": "user", "content": "Here is the design document." },
+                { "role": "assistant", "content": "Understood." },
+                { "role": "user", "content": "What changed?" },
+            ],
+            "tools": [
+                { "type": "function", "function": { "name": "read" } },
+                { "type": "function", "function": { "name": "write" } },
+            ],
+        });
+
+        let middleware = middleware();
+        let added = middleware.apply(PromptCacheStyle::OpenAiCompatible, &messages, &mut payload);
+
+        assert_eq!(added, 4);
+        assert!(has_marker(&payload["tools"][1]["function"]));
+        assert!(!has_marker(&payload["tools"][0]["function"]));
+        assert!(has_marker(&payload["messages"][0]["content"][0]));
+        assert_eq!(
+            payload["messages"][0]["content"][0]["text"],
+            "You are a helpful assistant."
+        );
+        assert!(has_marker(&payload["messages"][1]["content"][0]));
+        assert!(has_marker(&payload["messages"][3]["content"][0]));
+        assert_eq!(payload["messages"][2]["content"], "Understood.");
+
+        // Applying again adds nothing
+        assert_eq!(
+            middleware.apply(PromptCacheStyle::OpenAiCompatible, &messages, &mut payload),
+            0
+        );
+    }
+
+    #[test]
+    fn test_markers_last_two_user_messages_anthropic() {
+        let messages = conversation(&[
+            (Role::User, "First"),
+            (Role::Assistant, "Understood."),
+            (Role::User, "Second"),
+            (Role::Assistant, "Done."),
+            (Role::User, "Third"),
+        ]);
+        let block = |text: &str| json!([{ "type": "text", "text": text }]);
+        let mut payload = json!({
+            "system": block("You are a helpful assistant."),
+            "messages": [
+                { "role": "user", "content": block("First") },
+                { "role": "assistant", "content": block("Understood.") },
+                { "role": "user", "content": block("Second") },
+                { "role": "assistant", "content": block("Done.") },
+                { "role": "user", "content": block("Third") },
+            ],
+            "tools": [{ "name": "read" }, { "name": "write" }],
+        });
+
+        let added = middleware().apply(PromptCacheStyle::Anthropic, &messages, &mut payload);
+
+        assert_eq!(added, 4);
+        assert!(has_marker(&payload["tools"][1]));
+        assert!(!has_marker(&payload["tools"][0]));
+        assert!(has_marker(&payload["system"][0]));
+        assert!(!has_marker(&payload["messages"][0]["content"][0]));
+        assert!(has_marker(&payload["messages"][2]["content"][0]));
+        assert!(has_marker(&payload["messages"][4]["content"][0]));
+    }
+
+    #[test]
+    fn test_markers_respect_breakpoint_limit_anthropic() {
+        let messages = conversation(&[
+            (Role::User, "Here is the design document."),
+            (Role::Assistant, "Understood."),
+            (Role::User, "What changed?"),
+        ]);
+        let block = |text: &str| json!([{ "type": "text", "text": text }]);
+        let mut payload = json!({
+            "system": block("You are a helpful assistant."),
+            "messages": [
+                { "role": "user", "content": block("Here is the design document.") },
+                { "role": "assistant", "content": block("Understood.") },
+                { "role": "user", "content": block("What changed?") },
+            ],
+            "tools": [{ "name": "read" }],
+        });
+        payload["messages"][1]["content"][0][CACHE_CONTROL_FIELD] = json!({ "type": "ephemeral" });
+        payload["messages"][2]["content"][0][CACHE_CONTROL_FIELD] = json!({ "type": "ephemeral" });
+
+        let added = middleware().apply(PromptCacheStyle::Anthropic, &messages, &mut payload);
+
+        assert_eq!(added, 2);
+        assert!(has_marker(&payload["tools"][0]));
+        assert!(has_marker(&payload["system"][0]));
+        assert!(!has_marker(&payload["messages"][0]["content"][0]));
+        assert_eq!(count_markers(&payload), MAX_CACHE_BREAKPOINTS);
+    }
+
+    #[test]
+    fn test_markers_follow_cache_eligibility() {
+        let document = "x".repeat(4000);
+        let messages = conversation(&[
+            (Role::User, document.as_str()),
+            (Role::Assistant, "Understood."),
+            (Role::User, "What changed?"),
+        ]);
+        let block = |text: &str| json!([{ "type": "text", "text": text }]);
+        let mut payload = json!({
+            "system": block("You are a helpful assistant."),
+            "messages": [
+                { "role": "user", "content": block(&document) },
+                { "role": "assistant", "content": block("Understood.") },
+                { "role": "user", "content": block("What changed?") },
+            ],
+            "tools": [{ "name": "read" }],
+        });
+
+        // Default threshold: only the large user message is cacheable and the
+        // short tools/system prefix is left unmarked
+        let added = PromptCacheMiddleware::default().apply(
+            PromptCacheStyle::Anthropic,
+            &messages,
+            &mut payload,
+        );
+
+        assert_eq!(added, 1);
+        assert!(has_marker(&payload["messages"][0]["content"][0]));
+        assert!(!has_marker(&payload["messages"][2]["content"][0]));
+        assert!(!has_marker(&payload["system"][0]));
+        assert!(!has_marker(&payload["tools"][0]));
+    }
+
+    #[test]
+    fn test_short_prompt_is_untouched() {
+        let messages = conversation(&[(Role::User, "Hi")]);
+        let mut payload = json!({
+            "messages": [
+                { "role": "system", "content": "You are a helpful assistant." },
+                { "role": "user", "content": "Hi" },
+            ],
+            "tools": [{ "type": "function", "function": { "name": "read" } }],
+        });
+        let before = payload.clone();
+
+        let added = PromptCacheMiddleware::default().apply(
+            PromptCacheStyle::OpenAiCompatible,
+            &messages,
+            &mut payload,
+        );
+
+        assert_eq!(added, 0);
+        assert_eq!(payload, before);
+    }
+
+    #[test]
+    fn test_unsupported_provider_is_untouched() {
+        let messages = conversation(&[(Role::User, "Hi")]);
+        let mut payload = json!({
+            "messages": [{ "role": "system", "content": "You are a helpful assistant." }],
+            "tools": [{ "name": "read" }],
+        });
+        let before = payload.clone();
+
+        let style = PromptCacheStyle::for_provider("openai", "gpt-4o");
+        assert_eq!(style, PromptCacheStyle::None);
+        assert_eq!(middleware().apply(style, &messages, &mut payload), 0);
+        assert_eq!(payload, before);
+    }
+}
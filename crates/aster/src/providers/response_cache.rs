@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use rmcp::model::{Role, Tool};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::base::{
+    LeadWorkerProviderTrait, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage,
+};
+use super::errors::ProviderError;
+use super::prompt_cache::PromptCacheStyle;
+use super::retry::RetryConfig;
+use crate::config::Config;
+use crate::conversation::message::{Message, MessageContent};
+use crate::model::ModelConfig;
+
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 256;
+
+/// Config key that enables the response cache for created providers
+pub const RESPONSE_CACHE_CONFIG_KEY: &str = "ASTER_RESPONSE_CACHE";
+/// Config key overriding the cache TTL, in seconds
+pub const RESPONSE_CACHE_TTL_CONFIG_KEY: &str = "ASTER_RESPONSE_CACHE_TTL_SECS";
+
+#[derive(Debug, Clone)]
+pub struct ResponseCacheConfig {
+    /// How long a cached response stays valid
+    pub ttl: Duration,
+    /// Oldest entries are evicted beyond this size
+    pub max_entries: usize,
+    /// Also cache requests without `temperature: 0`, whose responses vary
+    pub cache_nondeterministic: bool,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: DEFAULT_CACHE_TTL,
+            max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            cache_nondeterministic: false,
+        }
+    }
+}
+
+/// Hit and miss counters for telemetry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ResponseCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Requests that were not eligible for caching
+    pub bypassed: u64,
+    pub entries: usize,
+}
+
+struct CacheEntry {
+    stored_at: Instant,
+    message: Message,
+    usage: ProviderUsage,
+}
+
+/// A provider that returns cached responses for identical non-streaming requests
+///
+/// Requests are keyed by a hash of the model settings, system prompt, message
+/// roles and content, and tools; message ids and timestamps are ignored. By
+/// default only requests with `temperature: 0` are cached. A cache hit gets a
+/// fresh message id and reports zero token usage, since nothing was sent.
+pub struct CachingProvider {
+    inner: Arc<dyn Provider>,
+    config: ResponseCacheConfig,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bypassed: AtomicU64,
+}
+
+#[derive(Serialize)]
+struct RequestKey<'a> {
+    model: &'a str,
+    temperature: Option<f32>,
+    max_tokens: Option<i32>,
+    system: &'a str,
+    messages: Vec<(&'a Role, &'a [MessageContent])>,
+    tools: &'a [Tool],
+}
+
+impl CachingProvider {
+    pub fn new(inner: Arc<dyn Provider>, config: ResponseCacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            bypassed: AtomicU64::new(0),
+        }
+    }
+
+    /// Wrap `inner` when `ASTER_RESPONSE_CACHE` is enabled, otherwise return it unchanged
+    pub fn from_config(inner: Arc<dyn Provider>) -> Arc<dyn Provider> {
+        let config = Config::global();
+        if !config
+            .get_param::<bool>(RESPONSE_CACHE_CONFIG_KEY)
+            .unwrap_or(false)
+        {
+            return inner;
+        }
+        let ttl = config
+            .get_param::<u64>(RESPONSE_CACHE_TTL_CONFIG_KEY)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_CACHE_TTL);
+        Arc::new(Self::new(
+            inner,
+            ResponseCacheConfig {
+                ttl,
+                ..Default::default()
+            },
+        ))
+    }
+
+    pub fn stats(&self) -> ResponseCacheStats {
+        ResponseCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            bypassed: self.bypassed.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().len(),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn is_cacheable(&self, model_config: &ModelConfig) -> bool {
+        self.config.cache_nondeterministic || model_config.temperature.is_some_and(|t| t <= 0.0)
+    }
+
+    fn request_key(
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Option<String> {
+        let key = RequestKey {
+            model: &model_config.model_name,
+            temperature: model_config.temperature,
+            max_tokens: model_config.max_tokens,
+            system,
+            messages: messages
+                .iter()
+                .map(|m| (&m.role, m.content.as_slice()))
+                .collect(),
+            tools,
+        };
+        let bytes = serde_json::to_vec(&key).ok()?;
+        Some(hex::encode(Sha256::digest(bytes)))
+    }
+
+    /// A cached response, as a new message that cost nothing
+    fn lookup(&self, key: &str) -> Option<(Message, ProviderUsage)> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(key)?;
+        if entry.stored_at.elapsed() > self.config.ttl {
+            entries.remove(key);
+            return None;
+        }
+
+        let mut message = entry.message.clone();
+        if message.id.is_some() {
+            message.id = Some(format!("msg_{}", Uuid::new_v4()));
+        }
+        message.created = chrono::Utc::now().timestamp();
+        let usage = ProviderUsage::new(
+            entry.usage.model.clone(),
+            Usage::new(Some(0), Some(0), Some(0)),
+        );
+        Some((message, usage))
+    }
+
+    /// Answer from the cache when the request is eligible, otherwise call `fetch`
+    async fn complete_cached<F, Fut>(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+        fetch: F,
+    ) -> Result<(Message, ProviderUsage), ProviderError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(Message, ProviderUsage), ProviderError>>,
+    {
+        let key = if self.is_cacheable(model_config) {
+            Self::request_key(model_config, system, messages, tools)
+        } else {
+            None
+        };
+        let Some(key) = key else {
+            self.bypassed.fetch_add(1, Ordering::Relaxed);
+            return fetch().await;
+        };
+
+        if let Some(cached) = self.lookup(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!("Response cache hit for {}", model_config.model_name);
+            return Ok(cached);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let (message, usage) = fetch().await?;
+        self.store(key, &message, &usage);
+        Ok((message, usage))
+    }
+
+    fn store(&self, key: String, message: &Message, usage: &ProviderUsage) {
+        let mut entries = self.entries.lock().unwrap();
+        let ttl = self.config.ttl;
+        entries.retain(|_, e| e.stored_at.elapsed() <= ttl);
+        while entries.len() >= self.config.max_entries.max(1) {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, e)| e.stored_at)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+        entries.insert(
+            key,
+            CacheEntry {
+                stored_at: Instant::now(),
+                message: message.clone(),
+                usage: usage.clone(),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl Provider for CachingProvider {
+    fn metadata() -> ProviderMetadata {
+        // This is a wrapper provider, so we return minimal metadata
+        ProviderMetadata::empty()
+    }
+
+    fn get_name(&self) -> &str {
+        self.inner.get_name()
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.inner.get_model_config()
+    }
+
+    async fn complete_with_model(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_cached(model_config, system, messages, tools, || {
+            self.inner
+                .complete_with_model(model_config, system, messages, tools)
+        })
+        .await
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        // Let the inner provider pick the model, e.g. lead or worker
+        let model_config = self.get_model_config();
+        self.complete_cached(&model_config, system, messages, tools, || {
+            self.inner.complete(system, messages, tools)
+        })
+        .await
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        self.bypassed.fetch_add(1, Ordering::Relaxed);
+        self.inner.stream(system, messages, tools).await
+    }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.inner.fetch_supported_models().await
+    }
+
+    async fn fetch_recommended_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.inner.fetch_recommended_models().await
+    }
+
+    async fn map_to_canonical_model(
+        &self,
+        provider_model: &str,
+    ) -> Result<Option<String>, ProviderError> {
+        self.inner.map_to_canonical_model(provider_model).await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        self.inner.create_embeddings(texts).await
+    }
+
+    async fn supports_cache_control(&self) -> bool {
+        self.inner.supports_cache_control().await
+    }
+
+    fn prompt_cache_style(&self) -> PromptCacheStyle {
+        self.inner.prompt_cache_style()
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        self.inner.as_lead_worker()
+    }
+
+    fn get_active_model_name(&self) -> String {
+        self.inner.get_active_model_name()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_stream_continuation(&self) -> bool {
+        self.inner.supports_stream_continuation()
+    }
+
+    async fn configure_oauth(&self) -> Result<(), ProviderError> {
+        self.inner.configure_oauth().await
+    }
+
+    fn retry_config(&self) -> RetryConfig {
+        self.inner.retry_config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+
+    struct CountingProvider {
+        calls: AtomicU64,
+        model_config: ModelConfig,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "counting"
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok((
+                Message::assistant()
+                    .with_id(format!("msg_response_{}", call))
+                    .with_text(format!("response {}", call)),
+                ProviderUsage::new(
+                    "test-model".to_string(),
+                    Usage::new(Some(10), Some(5), None),
+                ),
+            ))
+        }
+    }
+
+    fn provider(temperature: f32) -> (Arc<CountingProvider>, CachingProvider) {
+        let inner = Arc::new(CountingProvider {
+            calls: AtomicU64::new(0),
+            model_config: ModelConfig::new_or_fail("test-model")
+                .with_temperature(Some(temperature)),
+        });
+        let cache = CachingProvider::new(inner.clone(), ResponseCacheConfig::default());
+        (inner, cache)
+    }
+
+    #[tokio::test]
+    async fn test_identical_request_is_cache_hit() {
+        let (inner, cache) = provider(0.0);
+
+        let (first, first_usage) = cache
+            .complete("system", &[Message::user().with_text("hi")], &[])
+            .await
+            .unwrap();
+        // A fresh but identical message has a different timestamp and id
+        let (second, second_usage) = cache
+            .complete("system", &[Message::user().with_text("hi")], &[])
+            .await
+            .unwrap();
+
+        assert_eq!(first.as_concat_text(), "response 1");
+        assert_eq!(second.as_concat_text(), "response 1");
+        assert_eq!(first_usage.usage.input_tokens, Some(10));
+        // A hit is a new message and costs nothing
+        assert_ne!(second.id, first.id);
+        assert!(second.id.is_some());
+        assert_eq!(second_usage.usage.input_tokens, Some(0));
+        assert_eq!(second_usage.usage.output_tokens, Some(0));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            cache.stats(),
+            ResponseCacheStats {
+                hits: 1,
+                misses: 1,
+                bypassed: 0,
+                entries: 1,
+            }
+        );
+
+        cache
+            .complete("system", &[Message::user().with_text("bye")], &[])
+            .await
+            .unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_nondeterministic_request_is_not_cached() {
+        let (inner, cache) = provider(0.7);
+        for _ in 0..2 {
+            cache
+                .complete("system", &[Message::user().with_text("hi")], &[])
+                .await
+                .unwrap();
+        }
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.stats().bypassed, 2);
+    }
+}
@@ -0,0 +1,427 @@
+use super::base::{MessageStream, Provider};
+use super::errors::ProviderError;
+use super::retry::{should_retry, RetryConfig};
+use crate::config::Config;
+use crate::conversation::message::{Message, MessageContent};
+use futures::StreamExt;
+use rmcp::model::Tool;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+/// What to do when a stream fails after some output was already delivered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamRetryPolicy {
+    /// Retry only while nothing has been delivered; otherwise surface the error.
+    /// Never duplicates output.
+    #[default]
+    RetryBeforeFirstToken,
+    /// Also retry after output was delivered, asking the provider to continue
+    /// from the delivered text. Falls back to `RetryBeforeFirstToken` for
+    /// providers that cannot continue a partial response.
+    Resume,
+}
+
+impl StreamRetryPolicy {
+    /// Read the policy from `ASTER_STREAM_RETRY_POLICY` (`retry_before_first_token` or `resume`)
+    pub fn from_config() -> Self {
+        match Config::global()
+            .get_param::<String>("ASTER_STREAM_RETRY_POLICY")
+            .ok()
+            .as_deref()
+        {
+            Some("resume") => StreamRetryPolicy::Resume,
+            _ => StreamRetryPolicy::RetryBeforeFirstToken,
+        }
+    }
+}
+
+/// What happened to a failed stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamRetryEvent {
+    /// Nothing had been delivered yet, so the request is being sent again
+    Retrying { attempt: usize, error: String },
+    /// Output had been delivered and the provider is continuing from it
+    Resumed {
+        attempt: usize,
+        delivered_chars: usize,
+        error: String,
+    },
+    /// The stream ended with an error; `delivered_chars` of text were kept
+    Abandoned {
+        delivered_chars: usize,
+        error: String,
+    },
+}
+
+/// Retries streams that fail mid-way without re-emitting delivered output
+pub struct StreamRetry {
+    config: RetryConfig,
+    policy: StreamRetryPolicy,
+    event_sender: broadcast::Sender<StreamRetryEvent>,
+}
+
+impl StreamRetry {
+    pub fn new(config: RetryConfig, policy: StreamRetryPolicy) -> Self {
+        let (event_sender, _) = broadcast::channel(16);
+        Self {
+            config,
+            policy,
+            event_sender,
+        }
+    }
+
+    /// Publish events on an existing channel, so a long-lived subscriber
+    /// (e.g. the agent's) sees events from every stream
+    pub fn with_event_sender(mut self, event_sender: broadcast::Sender<StreamRetryEvent>) -> Self {
+        self.event_sender = event_sender;
+        self
+    }
+
+    pub fn policy(&self) -> StreamRetryPolicy {
+        self.policy
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StreamRetryEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// Stream from a provider, continuing partial responses when the policy
+    /// and the provider allow it
+    pub fn stream(
+        &self,
+        provider: Arc<dyn Provider>,
+        system: String,
+        messages: Vec<Message>,
+        tools: Vec<Tool>,
+    ) -> MessageStream {
+        let policy = if provider.supports_stream_continuation() {
+            self.policy
+        } else {
+            StreamRetryPolicy::RetryBeforeFirstToken
+        };
+        let retry = StreamRetry {
+            config: self.config.clone(),
+            policy,
+            event_sender: self.event_sender.clone(),
+        };
+
+        retry.run(move |partial| {
+            let provider = Arc::clone(&provider);
+            let system = system.clone();
+            let mut messages = messages.clone();
+            messages.extend(partial);
+            let tools = tools.clone();
+            async move { provider.stream(&system, &messages, &tools).await }
+        })
+    }
+
+    /// Run a stream with retries
+    ///
+    /// `open` is called with `None` for a fresh request and with the delivered
+    /// assistant text when resuming; the resumed stream must only yield the
+    /// continuation. Failures to open the stream are returned as-is, since
+    /// providers already retry the request itself (`ProviderRetry`); only
+    /// failures after the stream opened are retried here.
+    ///
+    /// Delivered thinking counts as output: it can't be used as a prefill, so
+    /// a stream that drops after thinking is abandoned rather than replayed.
+    pub fn run<F, Fut>(&self, open: F) -> MessageStream
+    where
+        F: Fn(Option<Message>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<MessageStream, ProviderError>> + Send + 'static,
+    {
+        let config = self.config.clone();
+        let policy = self.policy;
+        let events = self.event_sender.clone();
+
+        Box::pin(async_stream::stream! {
+            let mut attempts = 0;
+            let mut delivered = String::new();
+            let mut delivered_tool_call = false;
+            let mut delivered_thinking = false;
+            let mut resume_from: Option<Message> = None;
+
+            loop {
+                let error = match open(resume_from.clone()).await {
+                    Ok(mut stream) => {
+                        let mut failure = None;
+                        while let Some(item) = stream.next().await {
+                            match item {
+                                Ok((message, usage)) => {
+                                    if let Some(message) = &message {
+                                        delivered.push_str(&message.as_concat_text());
+                                        delivered_tool_call |= message.is_tool_call();
+                                        delivered_thinking |= message.content.iter().any(|c| {
+                                            matches!(
+                                                c,
+                                                MessageContent::Thinking(_)
+                                                    | MessageContent::RedactedThinking(_)
+                                            )
+                                        });
+                                    }
+                                    yield Ok((message, usage));
+                                }
+                                Err(e) => {
+                                    failure = Some(e);
+                                    break;
+                                }
+                            }
+                        }
+                        match failure {
+                            Some(e) => e,
+                            None => break,
+                        }
+                    }
+                    Err(e) => {
+                        let _ = events.send(StreamRetryEvent::Abandoned {
+                            delivered_chars: delivered.len(),
+                            error: e.to_string(),
+                        });
+                        yield Err(e);
+                        break;
+                    }
+                };
+
+                let has_output = !delivered.is_empty() || delivered_tool_call || delivered_thinking;
+                // A partial response can only be continued from text
+                let can_continue = policy == StreamRetryPolicy::Resume
+                    && !delivered_tool_call
+                    && !delivered_thinking;
+                if !should_retry(&error)
+                    || attempts >= config.max_retries()
+                    || (has_output && !can_continue)
+                {
+                    tracing::warn!(
+                        "Abandoning stream after {} delivered chars: {}",
+                        delivered.len(),
+                        error
+                    );
+                    let _ = events.send(StreamRetryEvent::Abandoned {
+                        delivered_chars: delivered.len(),
+                        error: error.to_string(),
+                    });
+                    yield Err(error);
+                    break;
+                }
+
+                attempts += 1;
+                if has_output {
+                    tracing::warn!(
+                        "Stream dropped after {} chars, resuming ({}/{}): {}",
+                        delivered.len(),
+                        attempts,
+                        config.max_retries(),
+                        error
+                    );
+                    let _ = events.send(StreamRetryEvent::Resumed {
+                        attempt: attempts,
+                        delivered_chars: delivered.len(),
+                        error: error.to_string(),
+                    });
+                    resume_from = Some(Message::assistant().with_text(delivered.clone()));
+                } else {
+                    tracing::warn!(
+                        "Stream failed, retrying ({}/{}): {}",
+                        attempts,
+                        config.max_retries(),
+                        error
+                    );
+                    let _ = events.send(StreamRetryEvent::Retrying {
+                        attempt: attempts,
+                        error: error.to_string(),
+                    });
+                }
+                sleep(config.delay_for_attempt(attempts)).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::ProviderUsage;
+    use futures::stream;
+    use std::sync::Mutex;
+
+    type StreamItem = Result<(Option<Message>, Option<ProviderUsage>), ProviderError>;
+
+    fn chunk(text: &str) -> StreamItem {
+        Ok((Some(Message::assistant().with_text(text)), None))
+    }
+
+    fn dropped() -> StreamItem {
+        Err(ProviderError::RequestFailed("connection reset".to_string()))
+    }
+
+    /// First request yields "Hello, " then drops; later requests answer with
+    /// the continuation when given the partial text, or the full reply otherwise
+    fn flaky_provider(
+        requests: Arc<Mutex<Vec<Option<String>>>>,
+    ) -> impl Fn(Option<Message>) -> std::future::Ready<Result<MessageStream, ProviderError>> {
+        move |partial| {
+            let mut requests = requests.lock().unwrap();
+            let first = requests.is_empty();
+            let partial = partial.map(|m| m.as_concat_text());
+            requests.push(partial.clone());
+
+            let items = match (first, partial) {
+                (true, _) => vec![chunk("Hello, "), dropped()],
+                (false, Some(_)) => vec![chunk("world!")],
+                (false, None) => vec![chunk("Hello, "), chunk("world!")],
+            };
+            std::future::ready(Ok(Box::pin(stream::iter(items)) as MessageStream))
+        }
+    }
+
+    async fn collect(mut stream: MessageStream) -> (String, Option<ProviderError>) {
+        let mut text = String::new();
+        let mut error = None;
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok((Some(message), _)) => text.push_str(&message.as_concat_text()),
+                Ok((None, _)) => {}
+                Err(e) => error = Some(e),
+            }
+        }
+        (text, error)
+    }
+
+    fn no_backoff() -> RetryConfig {
+        RetryConfig::new(3, 0, 1.0, 0)
+    }
+
+    #[tokio::test]
+    async fn test_drop_after_output_is_abandoned_by_default() {
+        let retry = StreamRetry::new(no_backoff(), StreamRetryPolicy::RetryBeforeFirstToken);
+        let mut events = retry.subscribe();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let (text, error) = collect(retry.run(flaky_provider(requests.clone()))).await;
+
+        assert_eq!(text, "Hello, ");
+        assert!(matches!(error, Some(ProviderError::RequestFailed(_))));
+        assert_eq!(
+            requests.lock().unwrap().len(),
+            1,
+            "must not retry after output"
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            StreamRetryEvent::Abandoned {
+                delivered_chars: 7,
+                error: "Request failed: connection reset".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drop_after_output_is_resumed_without_duplicates() {
+        let retry = StreamRetry::new(no_backoff(), StreamRetryPolicy::Resume);
+        let mut events = retry.subscribe();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let (text, error) = collect(retry.run(flaky_provider(requests.clone()))).await;
+
+        assert_eq!(text, "Hello, world!");
+        assert!(error.is_none());
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![None, Some("Hello, ".to_string())]
+        );
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            StreamRetryEvent::Resumed {
+                attempt: 1,
+                delivered_chars: 7,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_events_reach_shared_subscriber() {
+        let (sender, mut events) = broadcast::channel(16);
+        for _ in 0..2 {
+            let retry = StreamRetry::new(no_backoff(), StreamRetryPolicy::RetryBeforeFirstToken)
+                .with_event_sender(sender.clone());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            collect(retry.run(flaky_provider(requests))).await;
+        }
+
+        for _ in 0..2 {
+            assert!(matches!(
+                events.try_recv().unwrap(),
+                StreamRetryEvent::Abandoned { .. }
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_after_thinking_is_not_replayed() {
+        let retry = StreamRetry::new(no_backoff(), StreamRetryPolicy::Resume);
+        let calls = Arc::new(Mutex::new(0));
+
+        let counter = calls.clone();
+        let stream = retry.run(move |_| {
+            *counter.lock().unwrap() += 1;
+            let thinking = Message::assistant().with_thinking("Let me think", "sig");
+            let items = vec![Ok((Some(thinking), None)), dropped()];
+            std::future::ready(Ok(Box::pin(stream::iter(items)) as MessageStream))
+        });
+        let (_, error) = collect(stream).await;
+
+        assert!(matches!(error, Some(ProviderError::RequestFailed(_))));
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_open_failure_is_not_retried_again() {
+        let retry = StreamRetry::new(no_backoff(), StreamRetryPolicy::RetryBeforeFirstToken);
+        let calls = Arc::new(Mutex::new(0));
+
+        let counter = calls.clone();
+        let stream = retry.run(move |_| {
+            *counter.lock().unwrap() += 1;
+            std::future::ready(Err::<MessageStream, _>(ProviderError::RequestFailed(
+                "refused".to_string(),
+            )))
+        });
+        let (_, error) = collect(stream).await;
+
+        assert!(matches!(error, Some(ProviderError::RequestFailed(_))));
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_drop_before_output_is_retried() {
+        let retry = StreamRetry::new(no_backoff(), StreamRetryPolicy::RetryBeforeFirstToken);
+        let mut events = retry.subscribe();
+        let calls = Arc::new(Mutex::new(0));
+
+        let counter = calls.clone();
+        let stream = retry.run(move |_| {
+            let mut calls = counter.lock().unwrap();
+            *calls += 1;
+            let items = if *calls == 1 {
+                vec![dropped()]
+            } else {
+                vec![chunk("Hello, world!")]
+            };
+            std::future::ready(Ok(Box::pin(stream::iter(items)) as MessageStream))
+        });
+        let (text, error) = collect(stream).await;
+
+        assert_eq!(text, "Hello, world!");
+        assert!(error.is_none());
+        assert_eq!(*calls.lock().unwrap(), 2);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            StreamRetryEvent::Retrying { attempt: 1, .. }
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+//! Dry-run preview of what a recipe would do, built from its declaration without executing anything.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::agents::final_output_tool::FINAL_OUTPUT_TOOL_NAME;
+use crate::agents::subagent_tool::SUBAGENT_TOOL_NAME;
+use crate::agents::types::SuccessCheck;
+use crate::recipe::{Recipe, RecipeParameter};
+
+/// Tool name reported for shell commands run by retry checks and `on_failure`
+pub const PLAN_SHELL_TOOL: &str = "shell";
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepKind {
+    LoadExtension,
+    Instructions,
+    Prompt,
+    SubRecipe,
+    FinalOutput,
+    SuccessCheck,
+    OnFailure,
+    Retry,
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct PlanStep {
+    pub kind: PlanStepKind,
+    pub description: String,
+    /// Tools the step would invoke; `<extension>__*` stands for any tool of that extension
+    pub tools: Vec<String>,
+    pub parameters: BTreeMap<String, String>,
+    /// Whether the step only runs depending on runtime conditions
+    pub conditional: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+impl PlanStep {
+    fn new(kind: PlanStepKind, description: impl Into<String>) -> Self {
+        Self {
+            kind,
+            description: description.into(),
+            tools: Vec::new(),
+            parameters: BTreeMap::new(),
+            conditional: false,
+            condition: None,
+        }
+    }
+
+    fn tools(mut self, tools: Vec<String>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    fn parameter(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parameters.insert(key.into(), value.into());
+        self
+    }
+
+    fn when(mut self, condition: impl Into<String>) -> Self {
+        self.conditional = true;
+        self.condition = Some(condition.into());
+        self
+    }
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct RecipePlan {
+    pub title: String,
+    pub parameters: Vec<RecipeParameter>,
+    pub steps: Vec<PlanStep>,
+}
+
+impl Recipe {
+    /// Returns the ordered steps this recipe would run, without executing anything
+    pub fn plan(&self) -> RecipePlan {
+        let mut steps = Vec::new();
+        let extensions = self.extensions.as_deref().unwrap_or_default();
+
+        for extension in extensions {
+            steps.push(PlanStep::new(
+                PlanStepKind::LoadExtension,
+                format!("Load extension '{}'", extension.name()),
+            ));
+        }
+
+        let mut agent_tools: Vec<String> = extensions
+            .iter()
+            .map(|extension| format!("{}__*", extension.key()))
+            .collect();
+        let sub_recipes = self.sub_recipes.as_deref().unwrap_or_default();
+        if !sub_recipes.is_empty() {
+            agent_tools.push(SUBAGENT_TOOL_NAME.to_string());
+        }
+        if self.response.is_some() {
+            agent_tools.push(FINAL_OUTPUT_TOOL_NAME.to_string());
+        }
+
+        if let Some(instructions) = &self.instructions {
+            steps.push(
+                PlanStep::new(
+                    PlanStepKind::Instructions,
+                    "Run the agent with the recipe instructions",
+                )
+                .tools(agent_tools.clone())
+                .parameter("instructions", instructions),
+            );
+        }
+        if let Some(prompt) = &self.prompt {
+            steps.push(
+                PlanStep::new(
+                    PlanStepKind::Prompt,
+                    "Start the session with the recipe prompt",
+                )
+                .tools(agent_tools.clone())
+                .parameter("prompt", prompt),
+            );
+        }
+
+        for sub_recipe in sub_recipes {
+            let mut step = PlanStep::new(
+                PlanStepKind::SubRecipe,
+                format!("Run sub-recipe '{}'", sub_recipe.name),
+            )
+            .tools(vec![SUBAGENT_TOOL_NAME.to_string()])
+            .parameter("path", &sub_recipe.path)
+            .when("the agent delegates to this sub-recipe");
+            for (key, value) in sub_recipe.values.iter().flatten() {
+                step = step.parameter(key, value);
+            }
+            steps.push(step);
+        }
+
+        if let Some(schema) = self.response.as_ref().and_then(|r| r.json_schema.as_ref()) {
+            steps.push(
+                PlanStep::new(
+                    PlanStepKind::FinalOutput,
+                    "Return output matching the response schema",
+                )
+                .tools(vec![FINAL_OUTPUT_TOOL_NAME.to_string()])
+                .parameter("json_schema", schema.to_string()),
+            );
+        }
+
+        if let Some(retry) = &self.retry {
+            for check in &retry.checks {
+                let SuccessCheck::Shell { command } = check;
+                steps.push(
+                    PlanStep::new(PlanStepKind::SuccessCheck, "Run success check")
+                        .tools(vec![PLAN_SHELL_TOOL.to_string()])
+                        .parameter("command", command),
+                );
+            }
+            if let Some(command) = &retry.on_failure {
+                steps.push(
+                    PlanStep::new(PlanStepKind::OnFailure, "Run failure cleanup command")
+                        .tools(vec![PLAN_SHELL_TOOL.to_string()])
+                        .parameter("command", command)
+                        .when("a success check fails"),
+                );
+            }
+            steps.push(
+                PlanStep::new(PlanStepKind::Retry, "Retry the recipe from the start")
+                    .parameter("max_retries", retry.max_retries.to_string())
+                    .when("a success check fails and retries remain"),
+            );
+        }
+
+        RecipePlan {
+            title: self.title.clone(),
+            parameters: self.parameters.clone().unwrap_or_default(),
+            steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_matches_declared_steps() {
+        let content = r#"
+title: Cleanup
+description: Delete stale build outputs
+instructions: Remove the target directory for {{ project }}
+prompt: Start cleaning
+parameters:
+  - key: project
+    input_type: string
+    requirement: required
+    description: Project to clean
+extensions:
+  - type: builtin
+    name: developer
+sub_recipes:
+  - name: verify
+    path: verify.yaml
+    values:
+      strict: true
+response:
+  json_schema:
+    type: object
+retry:
+  max_retries: 2
+  checks:
+    - type: shell
+      command: test ! -d target
+  on_failure: git checkout target
+"#;
+        let recipe = Recipe::from_content(content).unwrap();
+        let plan = recipe.plan();
+
+        assert_eq!(plan.title, "Cleanup");
+        assert_eq!(plan.parameters.len(), 1);
+        let kinds: Vec<_> = plan.steps.iter().map(|s| s.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PlanStepKind::LoadExtension,
+                PlanStepKind::Instructions,
+                PlanStepKind::Prompt,
+                PlanStepKind::SubRecipe,
+                PlanStepKind::FinalOutput,
+                PlanStepKind::SuccessCheck,
+                PlanStepKind::OnFailure,
+                PlanStepKind::Retry,
+            ]
+        );
+
+        let instructions = &plan.steps[1];
+        assert_eq!(
+            instructions.tools,
+            vec!["developer__*", SUBAGENT_TOOL_NAME, FINAL_OUTPUT_TOOL_NAME]
+        );
+        assert!(!instructions.conditional);
+
+        let sub_recipe = &plan.steps[3];
+        assert!(sub_recipe.conditional);
+        assert_eq!(sub_recipe.parameters["path"], "verify.yaml");
+        assert_eq!(sub_recipe.parameters["strict"], "true");
+
+        assert_eq!(plan.steps[5].parameters["command"], "test ! -d target");
+        assert!(!plan.steps[5].conditional);
+        assert!(plan.steps[6].conditional);
+        assert_eq!(plan.steps[7].parameters["max_retries"], "2");
+    }
+
+    #[test]
+    fn test_plan_for_prompt_only_recipe() {
+        let recipe = Recipe::builder()
+            .title("Hello")
+            .description("Say hi")
+            .prompt("hi")
+            .build()
+            .unwrap();
+        let plan = recipe.plan();
+
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].kind, PlanStepKind::Prompt);
+        assert!(plan.steps[0].tools.is_empty());
+        assert!(plan.parameters.is_empty());
+    }
+}
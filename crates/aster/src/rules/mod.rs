@@ -3,10 +3,14 @@
 //! - 类型定义 (types)
 //! - AGENTS.md 解析 (parser)
 //! - 规则应用 (applier)
+//! - 目录规则配置 (profile)
+//! - AGENTS.md 检查 (validator)
 
 pub mod applier;
 pub mod parser;
+pub mod profile;
 pub mod types;
+pub mod validator;
 
 #[cfg(test)]
 mod tests;
@@ -18,4 +22,8 @@ pub use applier::{
 pub use parser::{
     extract_rules, find_agents_md, find_settings_files, load_project_rules, parse_agents_md,
 };
-pub use types::{AgentsMdSection, CustomRule, ProjectRules, RuleAction, RuleApplyResult};
+pub use profile::{apply_profile, match_profile, RulesManager};
+pub use types::{
+    AgentsMdSection, CustomRule, ProjectRules, RuleAction, RuleApplyResult, RuleProfile, RulesEvent,
+};
+pub use validator::{validate_agents_md, validate_agents_md_content, MAX_AGENTS_MD_TOKENS};
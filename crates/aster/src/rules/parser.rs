@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 
 use regex::Regex;
 
+use super::profile::apply_profile;
 use super::types::{AgentsMdSection, CustomRule, ProjectRules, RuleAction};
 
 /// 要查找的 AGENTS.md 文件名
@@ -133,35 +134,85 @@ pub fn parse_agents_md(file_path: &Path) -> Vec<AgentsMdSection> {
     sections
 }
 
+/// AGENTS.md 章节类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum SectionKind {
+    Instructions,
+    AllowedTools,
+    DisallowedTools,
+    PermissionMode,
+    Model,
+    SystemPrompt,
+    Rules,
+    Memory,
+}
+
+/// 有效的权限模式
+pub(super) const PERMISSION_MODES: &[&str] =
+    &["default", "acceptEdits", "bypassPermissions", "plan"];
+
+/// 根据标题判断章节类型，未知章节返回 None
+pub(super) fn classify_section(title: &str, level: usize) -> Option<SectionKind> {
+    let title_lower = title.to_lowercase();
+
+    // "disallowed tool" 包含 "allowed tool"，需要先判断
+    if title_lower.contains("instruction") || level == 0 {
+        Some(SectionKind::Instructions)
+    } else if title_lower.contains("disallowed tool") || title_lower.contains("forbidden tool") {
+        Some(SectionKind::DisallowedTools)
+    } else if title_lower.contains("allowed tool") {
+        Some(SectionKind::AllowedTools)
+    } else if title_lower.contains("permission") {
+        Some(SectionKind::PermissionMode)
+    } else if title_lower.contains("model") {
+        Some(SectionKind::Model)
+    } else if title_lower.contains("system prompt") {
+        Some(SectionKind::SystemPrompt)
+    } else if title_lower.contains("rule") {
+        Some(SectionKind::Rules)
+    } else if title_lower.contains("memory") || title_lower.contains("context") {
+        Some(SectionKind::Memory)
+    } else {
+        None
+    }
+}
+
 /// 从章节中提取规则
 pub fn extract_rules(sections: &[AgentsMdSection]) -> ProjectRules {
     let mut rules = ProjectRules::default();
 
     for section in sections {
-        let title_lower = section.title.to_lowercase();
-
-        if title_lower.contains("instruction") || section.level == 0 {
-            let instructions = rules.instructions.get_or_insert_with(String::new);
-            instructions.push_str(&section.content);
-            instructions.push('\n');
-        } else if title_lower.contains("allowed tool") {
-            rules.allowed_tools = Some(parse_list_from_content(&section.content));
-        } else if title_lower.contains("disallowed tool") || title_lower.contains("forbidden tool")
-        {
-            rules.disallowed_tools = Some(parse_list_from_content(&section.content));
-        } else if title_lower.contains("permission") {
-            let mode = section.content.lines().next().unwrap_or("").trim();
-            if ["default", "acceptEdits", "bypassPermissions", "plan"].contains(&mode) {
-                rules.permission_mode = Some(mode.to_string());
+        match classify_section(&section.title, section.level) {
+            Some(SectionKind::Instructions) => {
+                let instructions = rules.instructions.get_or_insert_with(String::new);
+                instructions.push_str(&section.content);
+                instructions.push('\n');
+            }
+            Some(SectionKind::AllowedTools) => {
+                rules.allowed_tools = Some(parse_list_from_content(&section.content));
+            }
+            Some(SectionKind::DisallowedTools) => {
+                rules.disallowed_tools = Some(parse_list_from_content(&section.content));
+            }
+            Some(SectionKind::PermissionMode) => {
+                let mode = section.content.lines().next().unwrap_or("").trim();
+                if PERMISSION_MODES.contains(&mode) {
+                    rules.permission_mode = Some(mode.to_string());
+                }
+            }
+            Some(SectionKind::Model) => {
+                rules.model = section.content.lines().next().map(|s| s.trim().to_string());
+            }
+            Some(SectionKind::SystemPrompt) => {
+                rules.system_prompt = Some(section.content.clone());
             }
-        } else if title_lower.contains("model") {
-            rules.model = section.content.lines().next().map(|s| s.trim().to_string());
-        } else if title_lower.contains("system prompt") {
-            rules.system_prompt = Some(section.content.clone());
-        } else if title_lower.contains("rule") {
-            rules.custom_rules = Some(parse_custom_rules(&section.content));
-        } else if title_lower.contains("memory") || title_lower.contains("context") {
-            rules.memory = Some(parse_memory_from_content(&section.content));
+            Some(SectionKind::Rules) => {
+                rules.custom_rules = Some(parse_custom_rules(&section.content));
+            }
+            Some(SectionKind::Memory) => {
+                rules.memory = Some(parse_memory_from_content(&section.content));
+            }
+            None => {}
         }
     }
 
@@ -169,7 +220,7 @@ pub fn extract_rules(sections: &[AgentsMdSection]) -> ProjectRules {
 }
 
 /// 从内容中解析列表项
-fn parse_list_from_content(content: &str) -> Vec<String> {
+pub(super) fn parse_list_from_content(content: &str) -> Vec<String> {
     let list_re = Regex::new(r"^\s*[-*+]\s+(.+)$").unwrap();
     let mut items = Vec::new();
 
@@ -251,16 +302,22 @@ pub fn load_project_rules(project_dir: Option<&Path>) -> ProjectRules {
         .map(|p| p.to_path_buf())
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
 
+    // 应用匹配工作目录的配置
+    apply_profile(load_base_rules(&dir), &dir)
+}
+
+/// 加载 AGENTS.md 和设置文件中的规则（不应用目录配置）
+pub(super) fn load_base_rules(dir: &Path) -> ProjectRules {
     let mut rules = ProjectRules::default();
 
     // 加载 AGENTS.md
-    if let Some(agents_md_path) = find_agents_md(Some(&dir)) {
+    if let Some(agents_md_path) = find_agents_md(Some(dir)) {
         let sections = parse_agents_md(&agents_md_path);
         rules = merge_rules(rules, extract_rules(&sections));
     }
 
     // 加载设置文件
-    for settings_path in find_settings_files(Some(&dir)) {
+    for settings_path in find_settings_files(Some(dir)) {
         if let Ok(content) = fs::read_to_string(&settings_path) {
             if let Ok(settings) = serde_json::from_str::<ProjectRules>(&content) {
                 rules = merge_rules(rules, settings);
@@ -272,7 +329,7 @@ pub fn load_project_rules(project_dir: Option<&Path>) -> ProjectRules {
 }
 
 /// 合并规则（后者优先）
-fn merge_rules(base: ProjectRules, override_rules: ProjectRules) -> ProjectRules {
+pub(super) fn merge_rules(base: ProjectRules, override_rules: ProjectRules) -> ProjectRules {
     ProjectRules {
         instructions: override_rules.instructions.or(base.instructions),
         allowed_tools: override_rules.allowed_tools.or(base.allowed_tools),
@@ -294,5 +351,13 @@ fn merge_rules(base: ProjectRules, override_rules: ProjectRules) -> ProjectRules
             }
             (b, o) => o.or(b),
         },
+        profiles: match (base.profiles, override_rules.profiles) {
+            (Some(mut b), Some(o)) => {
+                b.extend(o);
+                Some(b)
+            }
+            (b, o) => o.or(b),
+        },
+        active_profile: override_rules.active_profile.or(base.active_profile),
     }
 }
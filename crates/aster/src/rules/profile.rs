@@ -0,0 +1,136 @@
+//! 目录规则配置
+//!
+//! 根据工作目录自动激活规则配置，并在切换目录时通知规则变化
+
+use std::path::{Path, PathBuf};
+
+use glob::Pattern;
+use tokio::sync::broadcast;
+
+use super::parser::{load_base_rules, merge_rules};
+use super::types::{ProjectRules, RuleProfile, RulesEvent};
+
+/// 展开 `~` 开头的 glob 模式
+fn expand_pattern(pattern: &str) -> String {
+    let pattern = pattern.trim_end_matches('/');
+    match (pattern.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ if pattern == "~" => dirs::home_dir()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_else(|| pattern.to_string()),
+        _ => pattern.to_string(),
+    }
+}
+
+/// 查找匹配目录的配置
+///
+/// 目录本身或任一上级目录匹配配置的 glob 即视为匹配，按声明顺序取第一个。
+pub fn match_profile<'a>(profiles: &'a [RuleProfile], dir: &Path) -> Option<&'a RuleProfile> {
+    profiles.iter().find(|profile| {
+        profile.paths.iter().any(|path| {
+            Pattern::new(&expand_pattern(path))
+                .map(|pattern| dir.ancestors().any(|a| pattern.matches_path(a)))
+                .unwrap_or(false)
+        })
+    })
+}
+
+/// 将匹配目录的配置合并到规则之上，并记录激活的配置名称
+pub fn apply_profile(mut rules: ProjectRules, dir: &Path) -> ProjectRules {
+    let profile = rules
+        .profiles
+        .as_deref()
+        .and_then(|profiles| match_profile(profiles, dir))
+        .cloned();
+
+    match profile {
+        Some(profile) => {
+            let mut profile_rules = profile.rules;
+            profile_rules.profiles = None;
+            let mut merged = merge_rules(rules, profile_rules);
+            merged.active_profile = Some(profile.name);
+            merged
+        }
+        None => {
+            rules.active_profile = None;
+            rules
+        }
+    }
+}
+
+/// 规则管理器
+///
+/// 跟踪当前工作目录的规则，切换目录时重新计算并发送 [`RulesEvent::Changed`]。
+pub struct RulesManager {
+    working_dir: PathBuf,
+    extra_profiles: Vec<RuleProfile>,
+    rules: ProjectRules,
+    event_sender: broadcast::Sender<RulesEvent>,
+}
+
+impl RulesManager {
+    /// 创建规则管理器
+    pub fn new(working_dir: impl Into<PathBuf>) -> Self {
+        Self::with_profiles(working_dir, Vec::new())
+    }
+
+    /// 创建规则管理器，并附加设置文件之外的配置
+    pub fn with_profiles(working_dir: impl Into<PathBuf>, profiles: Vec<RuleProfile>) -> Self {
+        let working_dir = working_dir.into();
+        let (event_sender, _) = broadcast::channel(16);
+        let rules = Self::load(&working_dir, &profiles);
+        Self {
+            working_dir,
+            extra_profiles: profiles,
+            rules,
+            event_sender,
+        }
+    }
+
+    fn load(dir: &Path, extra_profiles: &[RuleProfile]) -> ProjectRules {
+        let mut rules = load_base_rules(dir);
+        if !extra_profiles.is_empty() {
+            rules
+                .profiles
+                .get_or_insert_with(Vec::new)
+                .extend(extra_profiles.iter().cloned());
+        }
+        apply_profile(rules, dir)
+    }
+
+    /// 订阅事件
+    pub fn subscribe(&self) -> broadcast::Receiver<RulesEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// 当前规则
+    pub fn rules(&self) -> &ProjectRules {
+        &self.rules
+    }
+
+    /// 当前工作目录
+    pub fn working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+
+    /// 切换工作目录并重新计算规则
+    ///
+    /// 规则发生变化时发送事件并返回 true。
+    pub fn set_working_dir(&mut self, dir: impl Into<PathBuf>) -> bool {
+        let dir = dir.into();
+        let rules = Self::load(&dir, &self.extra_profiles);
+        let changed = serde_json::to_value(&rules).ok() != serde_json::to_value(&self.rules).ok();
+
+        let previous_profile = std::mem::replace(&mut self.rules, rules).active_profile;
+        self.working_dir = dir;
+
+        if changed {
+            let _ = self.event_sender.send(RulesEvent::Changed {
+                working_dir: self.working_dir.clone(),
+                previous_profile,
+                active_profile: self.rules.active_profile.clone(),
+            });
+        }
+        changed
+    }
+}
@@ -99,6 +99,21 @@ fn test_extract_rules_from_sections() {
     assert_eq!(memory.get("Language"), Some(&"Rust".to_string()));
 }
 
+#[test]
+fn test_extract_rules_disallowed_tools_section() {
+    // "Disallowed Tools" 也包含 "allowed tool"，不能被当作允许列表
+    let sections = vec![AgentsMdSection {
+        title: "Disallowed Tools".to_string(),
+        content: "- Bash".to_string(),
+        level: 2,
+    }];
+
+    let rules = extract_rules(&sections);
+
+    assert!(rules.allowed_tools.is_none());
+    assert_eq!(rules.disallowed_tools, Some(vec!["Bash".to_string()]));
+}
+
 #[test]
 fn test_apply_rules_deny() {
     let rules = vec![CustomRule {
@@ -260,3 +275,174 @@ fn test_apply_rules_no_pattern() {
     // 没有 pattern 应该跳过
     assert!(!result.blocked);
 }
+
+fn profile(name: &str, path: &std::path::Path, model: &str) -> RuleProfile {
+    RuleProfile {
+        name: name.to_string(),
+        paths: vec![path.join("*").to_string_lossy().to_string()],
+        rules: ProjectRules {
+            model: Some(model.to_string()),
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn test_profiles_activate_per_directory() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let work = temp_dir.path().join("work");
+    let oss = temp_dir.path().join("oss");
+    let work_repo = work.join("service").join("src");
+    let oss_repo = oss.join("lib");
+    std::fs::create_dir_all(&work_repo).unwrap();
+    std::fs::create_dir_all(&oss_repo).unwrap();
+    std::fs::create_dir_all(oss_repo.join(".aster")).unwrap();
+    std::fs::write(
+        oss_repo.join(".aster").join("settings.json"),
+        r#"{"instructions": "Project instructions"}"#,
+    )
+    .unwrap();
+
+    let profiles = vec![
+        profile("work", &work, "work-model"),
+        profile("oss", &oss, "oss-model"),
+    ];
+    let mut manager = RulesManager::with_profiles(&work_repo, profiles);
+    let mut events = manager.subscribe();
+
+    // 上级目录 work/service 匹配 work/*
+    assert_eq!(manager.rules().active_profile.as_deref(), Some("work"));
+    assert_eq!(manager.rules().model.as_deref(), Some("work-model"));
+
+    assert!(manager.set_working_dir(&oss_repo));
+    assert_eq!(manager.rules().active_profile.as_deref(), Some("oss"));
+    assert_eq!(manager.rules().model.as_deref(), Some("oss-model"));
+    assert_eq!(
+        manager.rules().instructions.as_deref(),
+        Some("Project instructions")
+    );
+    match events.try_recv().unwrap() {
+        RulesEvent::Changed {
+            working_dir,
+            previous_profile,
+            active_profile,
+        } => {
+            assert_eq!(working_dir, oss_repo);
+            assert_eq!(previous_profile.as_deref(), Some("work"));
+            assert_eq!(active_profile.as_deref(), Some("oss"));
+        }
+    }
+
+    // 同一目录重新计算不会产生事件
+    assert!(!manager.set_working_dir(&oss_repo));
+    assert!(events.try_recv().is_err());
+
+    assert!(manager.set_working_dir(temp_dir.path()));
+    assert_eq!(manager.rules().active_profile, None);
+}
+
+#[test]
+fn test_match_profile_first_declared_wins() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path().join("repo");
+    let profiles = vec![
+        profile("first", temp_dir.path(), "a"),
+        profile("second", temp_dir.path(), "b"),
+    ];
+
+    assert_eq!(match_profile(&profiles, &dir).unwrap().name, "first");
+    assert!(match_profile(&profiles, temp_dir.path()).is_none());
+
+    let rules = apply_profile(
+        ProjectRules {
+            model: Some("global".to_string()),
+            instructions: Some("global instructions".to_string()),
+            profiles: Some(profiles),
+            ..Default::default()
+        },
+        &dir,
+    );
+    assert_eq!(rules.model.as_deref(), Some("a"));
+    assert_eq!(rules.instructions.as_deref(), Some("global instructions"));
+    assert_eq!(rules.active_profile.as_deref(), Some("first"));
+}
+
+#[test]
+fn test_validate_agents_md_reports_diagnostics() {
+    let content = r#"# Project Instructions
+
+Follow the style guide.
+
+## Deployment Notes
+
+Deploy on Fridays.
+
+## Allowed Tools
+
+- Read
+- Bash
+
+## Disallowed Tools
+
+- Bash
+
+## Permission Mode
+
+yolo
+
+## Rules
+
+- **No Logs**: Avoid console.log
+  - pattern: console\.log(
+- **No Logs**: Duplicate
+- just some text
+
+## Model
+
+## Model
+
+claude
+"#;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("AGENTS.md");
+    std::fs::write(&path, content).unwrap();
+
+    let result = validate_agents_md(&path);
+    assert!(!result.valid);
+    let expected = [
+        "第 5 行 [警告] 未知的章节 \"Deployment Notes\"",
+        "第 20 行 [错误] 无效的权限模式 \"yolo\"",
+        "第 25 行 [错误] 无效的规则模式",
+        "第 26 行 [警告] 规则 \"No Logs\" 与第 24 行的规则重名",
+        "第 27 行 [警告] 无法解析的规则",
+        "第 29 行 [警告] 章节 \"Model\" 为空",
+        "第 31 行 [警告] 章节 \"Model\" 与第 29 行的章节重复",
+        "第 16 行 [错误] 工具 \"Bash\" 同时出现在允许列表（第 12 行）和禁止列表中",
+    ];
+    assert_eq!(
+        result.warnings.len(),
+        expected.len(),
+        "{:#?}",
+        result.warnings
+    );
+    for (warning, prefix) in result.warnings.iter().zip(expected) {
+        assert!(warning.starts_with(prefix), "{} !~ {}", warning, prefix);
+    }
+}
+
+#[test]
+fn test_validate_agents_md_length_and_missing_file() {
+    let result = validate_agents_md_content(&create_agents_md_template());
+    assert!(result.valid);
+
+    let long = format!(
+        "# Instructions\n\n{}",
+        "word ".repeat(MAX_AGENTS_MD_TOKENS * 2)
+    );
+    let result = validate_agents_md_content(&long);
+    assert!(result.valid);
+    assert!(result.warnings[0].contains("超过建议的"));
+
+    let result = validate_agents_md(std::path::Path::new("/nonexistent/AGENTS.md"));
+    assert!(!result.valid);
+}
@@ -3,6 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// 项目规则
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -31,6 +32,38 @@ pub struct ProjectRules {
     /// 记忆/上下文
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memory: Option<HashMap<String, String>>,
+    /// 按目录自动激活的规则配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<Vec<RuleProfile>>,
+    /// 当前激活的配置名称
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+}
+
+/// 目录规则配置
+///
+/// 工作目录（或其任一上级目录）匹配 `paths` 中的 glob 时激活，
+/// `rules` 合并到全局规则之上。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleProfile {
+    /// 配置名称
+    pub name: String,
+    /// 目录 glob 模式，支持 `~` 开头
+    pub paths: Vec<String>,
+    /// 配置的规则
+    #[serde(default)]
+    pub rules: ProjectRules,
+}
+
+/// 规则事件
+#[derive(Debug, Clone)]
+pub enum RulesEvent {
+    /// 切换工作目录后规则发生变化
+    Changed {
+        working_dir: PathBuf,
+        previous_profile: Option<String>,
+        active_profile: Option<String>,
+    },
 }
 
 /// 自定义规则
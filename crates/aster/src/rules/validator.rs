@@ -0,0 +1,276 @@
+//! AGENTS.md 检查
+//!
+//! 检查未知章节、空规则、相互冲突的指令和过长的内容
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+
+use super::parser::{classify_section, parse_list_from_content, SectionKind, PERMISSION_MODES};
+use crate::config::ValidationResult;
+use crate::prompt::estimate_tokens;
+
+/// AGENTS.md 建议的最大 token 数，超出后可能挤占提示词预算
+pub const MAX_AGENTS_MD_TOKENS: usize = 8000;
+
+/// 带行号的章节
+struct LintSection<'a> {
+    title: &'a str,
+    line: usize,
+    kind: Option<SectionKind>,
+    /// 章节内容行（行号, 文本）
+    lines: Vec<(usize, &'a str)>,
+}
+
+impl LintSection<'_> {
+    fn is_empty(&self) -> bool {
+        self.lines.iter().all(|(_, l)| l.trim().is_empty())
+    }
+
+    fn content(&self) -> String {
+        self.lines
+            .iter()
+            .map(|(_, l)| *l)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 第一个非空内容行
+    fn first_line(&self) -> Option<(usize, &str)> {
+        self.lines
+            .iter()
+            .find(|(_, l)| !l.trim().is_empty())
+            .map(|(n, l)| (*n, l.trim()))
+    }
+}
+
+/// 检查结果收集器
+#[derive(Default)]
+struct Diagnostics {
+    messages: Vec<String>,
+    has_error: bool,
+}
+
+impl Diagnostics {
+    fn error(&mut self, line: usize, message: impl AsRef<str>) {
+        self.has_error = true;
+        self.messages
+            .push(format!("第 {} 行 [错误] {}", line, message.as_ref()));
+    }
+
+    fn warn(&mut self, line: usize, message: impl AsRef<str>) {
+        self.messages
+            .push(format!("第 {} 行 [警告] {}", line, message.as_ref()));
+    }
+}
+
+/// 按标题切分内容，记录行号
+fn split_sections(content: &str) -> Vec<LintSection<'_>> {
+    let heading_re = Regex::new(r"^(#{1,6})\s+(.+)$").unwrap();
+    let mut sections: Vec<LintSection> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        if let Some(caps) = heading_re.captures(line) {
+            let title = caps.get(2).unwrap().as_str().trim();
+            let level = caps.get(1).unwrap().as_str().len();
+            sections.push(LintSection {
+                title,
+                line: line_no,
+                kind: classify_section(title, level),
+                lines: Vec::new(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.lines.push((line_no, line));
+        } else if !line.trim().is_empty() {
+            // 第一个标题之前的内容视为指令
+            sections.push(LintSection {
+                title: "Instructions",
+                line: line_no,
+                kind: Some(SectionKind::Instructions),
+                lines: vec![(line_no, line)],
+            });
+        }
+    }
+
+    sections
+}
+
+/// 检查自定义规则章节
+fn lint_rules(section: &LintSection, diagnostics: &mut Diagnostics) {
+    let item_re = Regex::new(r"^\s*[-*+]\s+").unwrap();
+    let rule_re = Regex::new(r"^\s*[-*+]\s+\*\*(.+?)\*\*:\s*(.+)$").unwrap();
+    let action_re = Regex::new(r"(?i)action:\s*(\S+)").unwrap();
+    let pattern_re = Regex::new(r"(?i)pattern:\s*(.+)").unwrap();
+
+    let mut names: HashMap<String, usize> = HashMap::new();
+    let mut has_rule = false;
+
+    for (line_no, line) in &section.lines {
+        if let Some(caps) = rule_re.captures(line) {
+            has_rule = true;
+            let name = caps.get(1).unwrap().as_str().trim().to_string();
+            if let Some(first) = names.get(&name) {
+                diagnostics.warn(
+                    *line_no,
+                    format!("规则 \"{}\" 与第 {} 行的规则重名", name, first),
+                );
+            } else {
+                names.insert(name, *line_no);
+            }
+        } else if let Some(caps) = pattern_re.captures(line) {
+            let pattern = caps.get(1).unwrap().as_str().trim();
+            if let Err(e) = Regex::new(pattern) {
+                diagnostics.error(*line_no, format!("无效的规则模式 \"{}\": {}", pattern, e));
+            }
+        } else if let Some(caps) = action_re.captures(line) {
+            let action = caps.get(1).unwrap().as_str().to_lowercase();
+            if !["allow", "deny", "warn", "transform"].contains(&action.as_str()) {
+                diagnostics.warn(
+                    *line_no,
+                    format!("未知的规则动作 \"{}\"，将按 warn 处理", action),
+                );
+            }
+        } else if item_re.is_match(line) {
+            diagnostics.warn(*line_no, "无法解析的规则，应使用 \"- **名称**: 说明\" 格式");
+        }
+    }
+
+    if !has_rule && !section.is_empty() {
+        diagnostics.warn(section.line, "规则章节中没有可解析的规则");
+    }
+}
+
+/// 检查 AGENTS.md 内容
+pub fn validate_agents_md_content(content: &str) -> ValidationResult {
+    let mut diagnostics = Diagnostics::default();
+    let sections = split_sections(content);
+
+    if content.trim().is_empty() {
+        diagnostics.warn(1, "AGENTS.md 文件为空");
+    }
+
+    let mut seen: HashMap<SectionKind, usize> = HashMap::new();
+    let mut allowed_tools: Vec<(String, usize)> = Vec::new();
+    let mut disallowed_tools: Vec<(String, usize)> = Vec::new();
+
+    for section in &sections {
+        let Some(kind) = section.kind else {
+            if !section.is_empty() {
+                diagnostics.warn(
+                    section.line,
+                    format!("未知的章节 \"{}\"，其内容不会被加载为规则", section.title),
+                );
+            }
+            continue;
+        };
+
+        // 指令、规则和记忆会累加，其他章节后者覆盖前者
+        if !matches!(
+            kind,
+            SectionKind::Instructions | SectionKind::Rules | SectionKind::Memory
+        ) {
+            if let Some(first) = seen.get(&kind) {
+                diagnostics.warn(
+                    section.line,
+                    format!(
+                        "章节 \"{}\" 与第 {} 行的章节重复，将覆盖之前的设置",
+                        section.title, first
+                    ),
+                );
+            }
+        }
+        seen.entry(kind).or_insert(section.line);
+
+        if section.is_empty() {
+            if kind == SectionKind::Rules {
+                diagnostics.warn(section.line, "规则章节为空");
+            } else if kind != SectionKind::Instructions {
+                diagnostics.warn(section.line, format!("章节 \"{}\" 为空", section.title));
+            }
+            continue;
+        }
+
+        match kind {
+            SectionKind::AllowedTools | SectionKind::DisallowedTools => {
+                let tools = parse_list_from_content(&section.content());
+                if tools.is_empty() {
+                    diagnostics.warn(section.line, "工具列表中没有列表项");
+                }
+                let target = if kind == SectionKind::AllowedTools {
+                    &mut allowed_tools
+                } else {
+                    &mut disallowed_tools
+                };
+                for tool in tools {
+                    let line = section
+                        .lines
+                        .iter()
+                        .find(|(_, l)| l.contains(tool.as_str()))
+                        .map(|(n, _)| *n)
+                        .unwrap_or(section.line);
+                    target.push((tool, line));
+                }
+            }
+            SectionKind::PermissionMode => {
+                if let Some((line, mode)) = section.first_line() {
+                    if !PERMISSION_MODES.contains(&mode) {
+                        diagnostics.error(
+                            line,
+                            format!(
+                                "无效的权限模式 \"{}\"，可选值: {}",
+                                mode,
+                                PERMISSION_MODES.join(", ")
+                            ),
+                        );
+                    }
+                }
+            }
+            SectionKind::Rules => lint_rules(section, &mut diagnostics),
+            _ => {}
+        }
+    }
+
+    for (tool, line) in &disallowed_tools {
+        if let Some((_, allowed_line)) = allowed_tools.iter().find(|(t, _)| t == tool) {
+            diagnostics.error(
+                *line,
+                format!(
+                    "工具 \"{}\" 同时出现在允许列表（第 {} 行）和禁止列表中",
+                    tool, allowed_line
+                ),
+            );
+        }
+    }
+
+    let tokens = estimate_tokens(content);
+    if tokens > MAX_AGENTS_MD_TOKENS {
+        diagnostics.warn(
+            1,
+            format!(
+                "内容约 {} tokens，超过建议的 {}，可能挤占提示词预算",
+                tokens, MAX_AGENTS_MD_TOKENS
+            ),
+        );
+    }
+
+    ValidationResult {
+        valid: !diagnostics.has_error,
+        warnings: diagnostics.messages,
+    }
+}
+
+/// 检查 AGENTS.md 文件
+///
+/// 返回的 `warnings` 中每条信息都带有行号，存在错误时 `valid` 为 false。
+pub fn validate_agents_md(path: &Path) -> ValidationResult {
+    match fs::read_to_string(path) {
+        Ok(content) => validate_agents_md_content(&content),
+        Err(e) => ValidationResult {
+            valid: false,
+            warnings: vec![format!("无法读取 {}: {}", path.display(), e)],
+        },
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! 提供沙箱配置管理、预设、验证功能
 
+use super::filesystem::FilesystemPolicy;
+use super::network::NetworkAllowlist;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -125,6 +127,12 @@ pub struct SandboxConfig {
     pub audit_logging: Option<AuditLogging>,
     /// 资源限制
     pub resource_limits: Option<ResourceLimits>,
+    /// 文件系统策略（Bubblewrap 按规则挂载路径）
+    #[serde(default)]
+    pub filesystem_policy: Option<FilesystemPolicy>,
+    /// 网络白名单（设置后只能经由过滤代理连接白名单内的主机）
+    #[serde(default)]
+    pub network_allowlist: Option<NetworkAllowlist>,
 }
 
 impl Default for SandboxConfig {
@@ -157,6 +165,8 @@ impl Default for SandboxConfig {
             custom_args: Vec::new(),
             audit_logging: None,
             resource_limits: None,
+            filesystem_policy: None,
+            network_allowlist: None,
         }
     }
 }
@@ -439,6 +449,14 @@ impl SandboxConfigManager {
                 .resource_limits
                 .clone()
                 .or_else(|| base.resource_limits.clone()),
+            filesystem_policy: override_config
+                .filesystem_policy
+                .clone()
+                .or_else(|| base.filesystem_policy.clone()),
+            network_allowlist: override_config
+                .network_allowlist
+                .clone()
+                .or_else(|| base.network_allowlist.clone()),
         }
     }
 
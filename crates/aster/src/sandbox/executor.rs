@@ -3,11 +3,25 @@
 //! 提供统一的沙箱执行接口，自动选择最佳沙箱类型
 
 use super::config::{SandboxConfig, SandboxType};
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use super::filesystem::PathPermission;
+use super::network::BlockedConnection;
+#[cfg(target_os = "linux")]
+use super::network::{listen_in_netns, proxy_env};
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use super::network::{NetworkAllowlist, NetworkProxy};
+use super::resource_limits::ExecutionUsage;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::process::Stdio;
-use std::time::Duration;
-use tokio::process::Command;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::sync::OnceLock;
+#[cfg(unix)]
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::process::{Child, Command};
 
 /// 执行结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +38,12 @@ pub struct ExecutorResult {
     pub sandbox_type: SandboxType,
     /// 执行时长（毫秒）
     pub duration: Option<u64>,
+    /// 被网络白名单拒绝的连接
+    #[serde(default)]
+    pub blocked_connections: Vec<BlockedConnection>,
+    /// 实际资源消耗
+    #[serde(default)]
+    pub usage: ExecutionUsage,
 }
 
 /// 执行选项
@@ -55,10 +75,49 @@ pub struct SandboxCapabilities {
 }
 
 /// 在沙箱中执行命令
+///
+/// 配置了 `network_allowlist` 时，启动本次执行专用的过滤代理，沙箱只能经由代理
+/// 访问网络，被拒绝的连接记录在 [`ExecutorResult::blocked_connections`]。
+/// 只有 Bubblewrap 和 Seatbelt 能强制执行白名单；其他沙箱类型、沙箱被禁用或
+/// 不可用时返回错误，而不是放开网络执行。
 pub async fn execute_in_sandbox(
     command: &str,
     args: &[String],
     config: &SandboxConfig,
+) -> anyhow::Result<ExecutorResult> {
+    let Some(allowlist) = config.network_allowlist.clone() else {
+        return execute_with_config(command, args, config).await;
+    };
+    if !config.enabled {
+        anyhow::bail!("网络白名单需要启用沙箱");
+    }
+
+    let start_time = Instant::now();
+    let result = match config.sandbox_type {
+        #[cfg(target_os = "linux")]
+        SandboxType::Bubblewrap => {
+            execute_in_bubblewrap_with_allowlist(command, args, config, allowlist).await
+        }
+        #[cfg(target_os = "macos")]
+        SandboxType::Seatbelt => {
+            execute_in_seatbelt_with_allowlist(command, args, config, allowlist).await
+        }
+        other => {
+            let _ = allowlist;
+            Err(anyhow::anyhow!("{:?} 沙箱无法强制执行网络白名单", other))
+        }
+    };
+
+    result.map(|mut r| {
+        r.duration = Some(start_time.elapsed().as_millis() as u64);
+        r
+    })
+}
+
+async fn execute_with_config(
+    command: &str,
+    args: &[String],
+    config: &SandboxConfig,
 ) -> anyhow::Result<ExecutorResult> {
     let start_time = std::time::Instant::now();
 
@@ -84,7 +143,7 @@ pub async fn execute_in_sandbox(
         SandboxType::Seatbelt => {
             #[cfg(target_os = "macos")]
             {
-                execute_in_seatbelt(command, args, config).await
+                execute_in_seatbelt(command, args, config, None).await
             }
             #[cfg(not(target_os = "macos"))]
             {
@@ -132,11 +191,7 @@ async fn execute_unsandboxed(
         .and_then(|l| l.max_execution_time)
         .map(Duration::from_millis);
 
-    let output = if let Some(timeout) = timeout {
-        tokio::time::timeout(timeout, cmd.output()).await??
-    } else {
-        cmd.output().await?
-    };
+    let (output, usage) = run_with_usage(&mut cmd, timeout).await?;
 
     Ok(ExecutorResult {
         exit_code: output.status.code().unwrap_or(1),
@@ -145,9 +200,181 @@ async fn execute_unsandboxed(
         sandboxed: false,
         sandbox_type: SandboxType::None,
         duration: None,
+        blocked_connections: Vec::new(),
+        usage,
     })
 }
 
+/// 运行命令直到退出，同时收集资源消耗
+///
+/// Unix 上用 `wait4` 回收子进程以获得其 rusage（包含它已回收的后代进程）；
+/// 超时后杀死子进程所在的进程组并返回超时错误。
+#[cfg(unix)]
+async fn run_with_usage(
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+) -> anyhow::Result<(Output, ExecutionUsage)> {
+    let std_cmd = cmd
+        .as_std_mut()
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // 独立的进程组，超时时连同持有输出管道的后代进程一起杀死
+    if timeout.is_some() {
+        std::os::unix::process::CommandExt::process_group(std_cmd, 0);
+    }
+    let child = std_cmd.spawn()?;
+    wait_child_with_usage(child, timeout).await
+}
+
+/// 等待已启动的子进程退出，同时收集资源消耗（见 [`run_with_usage`]）
+#[cfg(unix)]
+async fn wait_child_with_usage(
+    child: std::process::Child,
+    timeout: Option<Duration>,
+) -> anyhow::Result<(Output, ExecutionUsage)> {
+    let start = Instant::now();
+    let pid = child.id() as libc::pid_t;
+    let reaped = Arc::new(Mutex::new(false));
+
+    let waiter = {
+        let reaped = reaped.clone();
+        tokio::task::spawn_blocking(move || wait_with_usage(child, &reaped))
+    };
+    let joined = match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, waiter).await {
+            Ok(joined) => joined,
+            Err(elapsed) => {
+                let reaped = reaped.lock().unwrap();
+                if !*reaped {
+                    // SAFETY: waiter 只在持有该锁时回收子进程，未回收的 pid 及以它为
+                    // 组号的进程组不会被复用
+                    unsafe {
+                        if libc::getpgid(pid) == pid {
+                            libc::killpg(pid, libc::SIGKILL);
+                        } else {
+                            libc::kill(pid, libc::SIGKILL);
+                        }
+                    }
+                }
+                return Err(elapsed.into());
+            }
+        },
+        None => waiter.await,
+    };
+
+    let (output, mut usage) = joined??;
+    usage.wall_time_ms = start.elapsed().as_millis() as u64;
+    Ok((output, usage))
+}
+
+/// 读取输出并用 `wait4` 回收子进程
+///
+/// 先用 `WNOWAIT` 等待子进程退出但不回收，再在持有 `reaped` 锁时回收并置位，
+/// 超时处理据此判断 pid 是否仍可安全地发送信号。
+#[cfg(unix)]
+fn wait_with_usage(
+    mut child: std::process::Child,
+    reaped: &Mutex<bool>,
+) -> std::io::Result<(Output, ExecutionUsage)> {
+    use std::io::Read;
+    use std::os::unix::process::ExitStatusExt;
+
+    let stderr = child.stderr.take();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_end(&mut buf);
+        }
+        buf
+    });
+    let mut stdout = Vec::new();
+    if let Some(mut out) = child.stdout.take() {
+        out.read_to_end(&mut stdout)?;
+    }
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    let pid = child.id() as libc::pid_t;
+    loop {
+        // SAFETY: siginfo_t 只包含整数字段，全零是合法值
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        // SAFETY: 子进程尚未被回收，pid 有效；WNOWAIT 使其保持可回收状态
+        let ret = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOWAIT,
+            )
+        };
+        if ret >= 0 {
+            break;
+        }
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    let mut reaped = reaped.lock().unwrap();
+    *reaped = true;
+    let mut status = 0;
+    // SAFETY: rusage 只包含整数字段，全零是合法值
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: std::process::Child 不会自行回收子进程，pid 仍然有效
+        let ret = unsafe { libc::wait4(pid, &mut status, 0, &mut rusage) };
+        if ret >= 0 {
+            break;
+        }
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    let millis = |tv: libc::timeval| tv.tv_sec as u64 * 1000 + tv.tv_usec as u64 / 1000;
+    // ru_maxrss 在 macOS 上以字节为单位，在 Linux 上以 KB 为单位
+    let peak_memory_bytes = if cfg!(target_os = "macos") {
+        rusage.ru_maxrss as u64
+    } else {
+        rusage.ru_maxrss as u64 * 1024
+    };
+
+    Ok((
+        Output {
+            status: std::process::ExitStatus::from_raw(status),
+            stdout,
+            stderr,
+        },
+        ExecutionUsage {
+            peak_memory_bytes: Some(peak_memory_bytes),
+            cpu_time_ms: Some(millis(rusage.ru_utime) + millis(rusage.ru_stime)),
+            wall_time_ms: 0,
+        },
+    ))
+}
+
+/// 运行命令直到退出（当前平台只能统计墙钟时间）
+#[cfg(not(unix))]
+async fn run_with_usage(
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+) -> anyhow::Result<(Output, ExecutionUsage)> {
+    let start = Instant::now();
+    let output = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, cmd.output()).await??,
+        None => cmd.output().await?,
+    };
+    Ok((
+        output,
+        ExecutionUsage {
+            wall_time_ms: start.elapsed().as_millis() as u64,
+            ..Default::default()
+        },
+    ))
+}
+
 /// Docker 沙箱执行
 async fn execute_in_docker(
     command: &str,
@@ -160,8 +387,20 @@ async fn execute_in_docker(
         .map(|s| s.as_str())
         .unwrap_or("alpine:latest");
 
+    let env_args: Vec<String> = config
+        .environment_variables
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+
     let mut docker_args = vec!["run", "--rm"];
 
+    // 环境变量
+    for env in &env_args {
+        docker_args.push("-e");
+        docker_args.push(env);
+    }
+
     // 资源限制
     if let Some(ref limits) = config.resource_limits {
         if let Some(max_memory) = limits.max_memory {
@@ -171,7 +410,7 @@ async fn execute_in_docker(
         }
     }
 
-    // 网络
+    // 网络隔离
     if !config.network_access {
         docker_args.push("--network=none");
     }
@@ -187,7 +426,7 @@ async fn execute_in_docker(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    let output = cmd.output().await?;
+    let (output, usage) = run_with_usage(&mut cmd, None).await?;
 
     Ok(ExecutorResult {
         exit_code: output.status.code().unwrap_or(1),
@@ -196,30 +435,79 @@ async fn execute_in_docker(
         sandboxed: true,
         sandbox_type: SandboxType::Docker,
         duration: None,
+        blocked_connections: Vec::new(),
+        // 资源消耗发生在容器内，docker 客户端进程的统计没有意义
+        usage: ExecutionUsage {
+            peak_memory_bytes: None,
+            cpu_time_ms: None,
+            ..usage
+        },
     })
 }
 
-/// Bubblewrap 沙箱执行 (Linux)
+/// 构建 Bubblewrap 参数
+///
+/// 挂载顺序决定优先级：后挂载的路径覆盖先挂载的路径。依次为配置中的只读路径、
+/// 可写路径、`filesystem_policy` 的规则（按顺序），最后用空 tmpfs 遮盖
+/// `denied_paths`。未挂载的路径在沙箱内不存在。
 #[cfg(target_os = "linux")]
-async fn execute_in_bubblewrap(
-    command: &str,
-    args: &[String],
-    config: &SandboxConfig,
-) -> anyhow::Result<ExecutorResult> {
-    let mut bwrap_args = vec!["--unshare-all".to_string()];
+fn bubblewrap_args(command: &str, args: &[String], config: &SandboxConfig) -> Vec<String> {
+    let mut bwrap_args = Vec::new();
+
+    // 命名空间隔离
+    if config.unshare_all {
+        bwrap_args.push("--unshare-all".to_string());
+        if config.network_access {
+            bwrap_args.push("--share-net".to_string());
+        }
+    } else {
+        bwrap_args.push("--unshare-pid".to_string());
+        if !config.network_access {
+            bwrap_args.push("--unshare-net".to_string());
+        }
+    }
 
-    // 只读路径
+    let mut bind = |flag: &str, path: &Path| {
+        let path = path.to_string_lossy().to_string();
+        bwrap_args.push(flag.to_string());
+        bwrap_args.push(path.clone());
+        bwrap_args.push(path);
+    };
+
+    // 只读路径（不存在的路径跳过，例如部分发行版没有 /lib64）
     for path in &config.read_only_paths {
-        bwrap_args.push("--ro-bind".to_string());
-        bwrap_args.push(path.to_string_lossy().to_string());
-        bwrap_args.push(path.to_string_lossy().to_string());
+        bind("--ro-bind-try", path);
     }
 
     // 可写路径
     for path in &config.writable_paths {
-        bwrap_args.push("--bind".to_string());
-        bwrap_args.push(path.to_string_lossy().to_string());
-        bwrap_args.push(path.to_string_lossy().to_string());
+        bind("--bind-try", path);
+    }
+
+    // 文件系统策略
+    let mut denied: Vec<&Path> = Vec::new();
+    if let Some(policy) = &config.filesystem_policy {
+        for rule in &policy.rules {
+            let path = Path::new(&rule.pattern);
+            match rule.permission {
+                PathPermission::ReadOnly => bind("--ro-bind-try", path),
+                PathPermission::ReadWrite => bind("--bind-try", path),
+                PathPermission::Denied => denied.push(path),
+            }
+        }
+    }
+    denied.extend(config.denied_paths.iter().map(PathBuf::as_path));
+
+    // 禁止访问的路径：目录用空 tmpfs 遮盖，文件用 /dev/null 遮盖
+    for path in denied {
+        if path.is_dir() {
+            bwrap_args.push("--tmpfs".to_string());
+            bwrap_args.push(path.to_string_lossy().to_string());
+        } else if path.exists() {
+            bwrap_args.push("--ro-bind".to_string());
+            bwrap_args.push("/dev/null".to_string());
+            bwrap_args.push(path.to_string_lossy().to_string());
+        }
     }
 
     // /dev 访问
@@ -247,13 +535,59 @@ async fn execute_in_bubblewrap(
     bwrap_args.push("--".to_string());
     bwrap_args.push(command.to_string());
     bwrap_args.extend(args.iter().cloned());
+    bwrap_args
+}
+
+/// 检查 Bubblewrap 是否可用
+///
+/// 仅安装 `bwrap` 还不够：容器内或禁用了非特权用户命名空间的系统上无法创建
+/// 命名空间。因此实际启动一次隔离的 `true` 进行探测，结果缓存在进程内，
+/// 失败时返回原因。
+#[cfg(target_os = "linux")]
+fn bwrap_usable() -> Result<(), String> {
+    static PROBE: OnceLock<Result<(), String>> = OnceLock::new();
+    PROBE
+        .get_or_init(|| {
+            let output = std::process::Command::new("bwrap")
+                .args(["--unshare-all", "--die-with-parent", "--ro-bind", "/", "/"])
+                .args(["--", "true"])
+                .stdin(Stdio::null())
+                .output()
+                .map_err(|e| format!("无法启动 bwrap: {}", e))?;
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "bwrap 无法创建命名空间: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+        })
+        .clone()
+}
+
+/// Bubblewrap 沙箱执行 (Linux)
+///
+/// 无法建立命名空间时（由 [`bwrap_usable`] 探测）记录原因并回退到无沙箱执行，
+/// 结果中 `sandboxed` 为 false。沙箱建立之后的失败（例如命令不存在）原样返回。
+#[cfg(target_os = "linux")]
+async fn execute_in_bubblewrap(
+    command: &str,
+    args: &[String],
+    config: &SandboxConfig,
+) -> anyhow::Result<ExecutorResult> {
+    if let Err(reason) = tokio::task::spawn_blocking(bwrap_usable).await? {
+        tracing::warn!("Bubblewrap 不可用（{}），回退到无沙箱执行", reason);
+        return execute_unsandboxed(command, args, config).await;
+    }
 
     let mut cmd = Command::new("bwrap");
-    cmd.args(&bwrap_args)
+    cmd.args(bubblewrap_args(command, args, config))
+        .envs(&config.environment_variables)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    let output = cmd.output().await?;
+    let (output, usage) = run_with_usage(&mut cmd, None).await?;
 
     Ok(ExecutorResult {
         exit_code: output.status.code().unwrap_or(1),
@@ -262,50 +596,282 @@ async fn execute_in_bubblewrap(
         sandboxed: true,
         sandbox_type: SandboxType::Bubblewrap,
         duration: None,
+        blocked_connections: Vec::new(),
+        usage,
     })
 }
 
-/// Seatbelt 沙箱执行 (macOS)
-#[cfg(target_os = "macos")]
-async fn execute_in_seatbelt(
+/// 沙箱内过滤代理的端口。沙箱的网络命名空间是新建的，端口不会被占用。
+#[cfg(target_os = "linux")]
+const SANDBOX_PROXY_PORT: u16 = 3128;
+
+/// 带网络白名单的 Bubblewrap 执行 (Linux)
+///
+/// 网络命名空间保持隔离，沙箱内只有回环网卡。bwrap 建立命名空间后通过
+/// `--info-fd` 报告沙箱进程的 pid，并在 `--block-fd` 可读之前暂停；这期间在
+/// 沙箱的网络命名空间内创建代理的监听套接字，之后才放行命令。沙箱内只能连到
+/// 代理，直接连接其他地址都会失败。Bubblewrap 不可用时返回错误。
+#[cfg(target_os = "linux")]
+async fn execute_in_bubblewrap_with_allowlist(
     command: &str,
     args: &[String],
     config: &SandboxConfig,
+    allowlist: NetworkAllowlist,
 ) -> anyhow::Result<ExecutorResult> {
-    // 构建 sandbox profile
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    if let Err(reason) = tokio::task::spawn_blocking(bwrap_usable).await? {
+        anyhow::bail!("Bubblewrap 不可用（{}），无法强制执行网络白名单", reason);
+    }
+
+    let mut config = config.clone();
+    config.network_access = false;
+    config.environment_variables.extend(proxy_env(&format!(
+        "http://127.0.0.1:{}",
+        SANDBOX_PROXY_PORT
+    )));
+
+    let (mut info_reader, info_writer) = std::io::pipe()?;
+    let (block_reader, mut block_writer) = std::io::pipe()?;
+    let inherited = [info_writer.as_raw_fd(), block_reader.as_raw_fd()];
+
+    let mut cmd = std::process::Command::new("bwrap");
+    cmd.arg("--info-fd")
+        .arg(inherited[0].to_string())
+        .arg("--block-fd")
+        .arg(inherited[1].to_string())
+        .args(bubblewrap_args(command, args, &config))
+        .envs(&config.environment_variables)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // SAFETY: fork 之后只调用 fcntl，让 bwrap 继承这两个管道端
+    unsafe {
+        cmd.pre_exec(move || {
+            for fd in inherited {
+                if libc::fcntl(fd, libc::F_SETFD, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+    let mut child = cmd.spawn()?;
+    drop(info_writer);
+    drop(block_reader);
+
+    let listener = tokio::task::spawn_blocking(move || {
+        // 沙箱进程也持有 info fd，不能等到 EOF，读到完整的 JSON 即可
+        let mut info = Vec::new();
+        let mut buf = [0u8; 256];
+        let pid = loop {
+            let read = info_reader.read(&mut buf)?;
+            if read == 0 {
+                return Err(std::io::Error::other("bwrap 没有报告沙箱进程"));
+            }
+            info.extend_from_slice(&buf[..read]);
+            if let Ok(value) = serde_json::from_slice::<serde_json::Value>(&info) {
+                match value.get("child-pid").and_then(|pid| pid.as_u64()) {
+                    Some(pid) => break pid as u32,
+                    None => return Err(std::io::Error::other("bwrap 没有报告沙箱进程")),
+                }
+            }
+        };
+        listen_in_netns(pid, SANDBOX_PROXY_PORT)
+    })
+    .await?;
+
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            let _ = child.kill();
+            let stderr = tokio::task::spawn_blocking(move || child.wait_with_output())
+                .await?
+                .map(|output| String::from_utf8_lossy(&output.stderr).trim().to_string())
+                .unwrap_or_default();
+            anyhow::bail!("无法在沙箱网络内启动过滤代理（{}）: {}", e, stderr);
+        }
+    };
+    let proxy = NetworkProxy::serve(tokio::net::TcpListener::from_std(listener)?, allowlist)?;
+
+    // 放行沙箱内的命令；bwrap 已经退出时写入失败，由下面的等待报告结果
+    let _ = block_writer.write_all(b"1");
+    drop(block_writer);
+
+    let (output, usage) = wait_child_with_usage(child, None).await?;
+
+    Ok(ExecutorResult {
+        exit_code: output.status.code().unwrap_or(1),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        sandboxed: true,
+        sandbox_type: SandboxType::Bubblewrap,
+        duration: None,
+        blocked_connections: proxy.blocked(),
+        usage,
+    })
+}
+
+/// 进程运行所需的系统路径（只读）
+#[cfg(target_os = "macos")]
+const SEATBELT_SYSTEM_PATHS: &[&str] = &[
+    "/System",
+    "/usr/lib",
+    "/usr/share",
+    "/private/var/db/dyld",
+    "/Library/Apple",
+    "/dev",
+];
+
+/// Seatbelt 按真实路径匹配，`/tmp`、`/var` 等符号链接需要先解析
+#[cfg(target_os = "macos")]
+fn seatbelt_path(path: &Path) -> String {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    path.to_string_lossy()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+/// 构建 Seatbelt sandbox profile
+///
+/// 默认拒绝一切访问，只放行系统运行路径、工作目录（读写）以及配置中的路径。
+/// SBPL 中后出现的规则优先，因此禁止访问的路径放在最后。
+/// 配置了网络白名单时只允许连接 `proxy_port` 上的过滤代理，没有代理则禁止联网。
+#[cfg(target_os = "macos")]
+fn seatbelt_profile(
+    config: &SandboxConfig,
+    working_dir: Option<&Path>,
+    proxy_port: Option<u16>,
+) -> String {
     let mut profile = String::from("(version 1)\n(deny default)\n");
 
     // 允许执行
     profile.push_str("(allow process-exec)\n");
+    profile.push_str("(allow process-fork)\n");
+    profile.push_str("(allow signal (target same-sandbox))\n");
+    profile.push_str("(allow sysctl-read)\n");
+    profile.push_str("(allow mach-lookup)\n");
+    // 解析路径需要读取父目录的元数据，但不包括文件内容
+    profile.push_str("(allow file-read-metadata)\n");
+    profile.push_str("(allow file-write-data (literal \"/dev/null\"))\n");
+
+    let read = |path: &Path| format!("(allow file-read* (subpath \"{}\"))\n", seatbelt_path(path));
+    let write = |path: &Path| {
+        format!(
+            "(allow file-read* file-write* (subpath \"{}\"))\n",
+            seatbelt_path(path)
+        )
+    };
+
+    for path in SEATBELT_SYSTEM_PATHS {
+        profile.push_str(&read(Path::new(path)));
+    }
 
     // 只读路径
     for path in &config.read_only_paths {
-        profile.push_str(&format!(
-            "(allow file-read* (subpath \"{}\"))\n",
-            path.display()
-        ));
+        profile.push_str(&read(path));
     }
 
     // 可写路径
-    for path in &config.writable_paths {
+    for path in config
+        .writable_paths
+        .iter()
+        .map(PathBuf::as_path)
+        .chain(working_dir)
+    {
+        profile.push_str(&write(path));
+    }
+
+    // 文件系统策略
+    let mut denied: Vec<&Path> = Vec::new();
+    if let Some(policy) = &config.filesystem_policy {
+        for rule in &policy.rules {
+            let path = Path::new(&rule.pattern);
+            match rule.permission {
+                PathPermission::ReadOnly => profile.push_str(&read(path)),
+                PathPermission::ReadWrite => profile.push_str(&write(path)),
+                PathPermission::Denied => denied.push(path),
+            }
+        }
+    }
+    denied.extend(config.denied_paths.iter().map(PathBuf::as_path));
+
+    // 网络访问：有白名单时只允许连接本机上的过滤代理端口
+    if config.network_allowlist.is_some() {
+        if let Some(port) = proxy_port {
+            profile.push_str(&format!(
+                "(allow network-outbound (remote ip \"localhost:{}\"))\n",
+                port
+            ));
+        }
+    } else if config.network_access {
+        profile.push_str("(allow network*)\n");
+    }
+
+    // 禁止访问的路径
+    for path in denied {
         profile.push_str(&format!(
-            "(allow file-write* (subpath \"{}\"))\n",
-            path.display()
+            "(deny file-read* file-write* (subpath \"{}\"))\n",
+            seatbelt_path(path)
         ));
     }
 
-    // 网络访问
-    if config.network_access {
-        profile.push_str("(allow network*)\n");
+    profile
+}
+
+/// 检查 sandbox-exec 是否可用
+///
+/// 实际应用一个最小 profile 运行 `true` 进行探测，结果缓存在进程内，失败时返回原因。
+#[cfg(target_os = "macos")]
+fn seatbelt_usable() -> Result<(), String> {
+    static PROBE: OnceLock<Result<(), String>> = OnceLock::new();
+    PROBE
+        .get_or_init(|| {
+            let output = std::process::Command::new("sandbox-exec")
+                .args(["-p", "(version 1)\n(allow default)\n", "/usr/bin/true"])
+                .stdin(Stdio::null())
+                .output()
+                .map_err(|e| format!("无法启动 sandbox-exec: {}", e))?;
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "sandbox-exec 无法应用 profile: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+        })
+        .clone()
+}
+
+/// Seatbelt 沙箱执行 (macOS)
+///
+/// 工作目录为当前进程的工作目录。sandbox-exec 不可用时记录原因并回退到无沙箱执行。
+/// `proxy_port` 为网络白名单代理的端口。
+#[cfg(target_os = "macos")]
+async fn execute_in_seatbelt(
+    command: &str,
+    args: &[String],
+    config: &SandboxConfig,
+    proxy_port: Option<u16>,
+) -> anyhow::Result<ExecutorResult> {
+    if let Err(reason) = seatbelt_usable() {
+        tracing::warn!("Seatbelt 不可用（{}），回退到无沙箱执行", reason);
+        return execute_unsandboxed(command, args, config).await;
     }
 
+    let working_dir = std::env::current_dir().ok();
+    let profile = seatbelt_profile(config, working_dir.as_deref(), proxy_port);
     let mut cmd = Command::new("sandbox-exec");
     cmd.args(["-p", &profile, command])
         .args(args)
+        .envs(&config.environment_variables)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    let output = cmd.output().await?;
+    let (output, usage) = run_with_usage(&mut cmd, None).await?;
 
     Ok(ExecutorResult {
         exit_code: output.status.code().unwrap_or(1),
@@ -314,16 +880,38 @@ async fn execute_in_seatbelt(
         sandboxed: true,
         sandbox_type: SandboxType::Seatbelt,
         duration: None,
+        blocked_connections: Vec::new(),
+        usage,
     })
 }
 
-/// Firejail 沙箱执行 (Linux)
-#[cfg(target_os = "linux")]
-async fn execute_in_firejail(
+/// 带网络白名单的 Seatbelt 执行 (macOS)
+///
+/// profile 只允许连接代理监听的本机端口，代理监听在宿主机回环地址上。
+/// sandbox-exec 不可用时返回错误。
+#[cfg(target_os = "macos")]
+async fn execute_in_seatbelt_with_allowlist(
     command: &str,
     args: &[String],
     config: &SandboxConfig,
+    allowlist: NetworkAllowlist,
 ) -> anyhow::Result<ExecutorResult> {
+    if let Err(reason) = tokio::task::spawn_blocking(seatbelt_usable).await? {
+        anyhow::bail!("Seatbelt 不可用（{}），无法强制执行网络白名单", reason);
+    }
+
+    let proxy = NetworkProxy::start(allowlist).await?;
+    let mut config = config.clone();
+    config.environment_variables.extend(proxy.env());
+
+    let mut result = execute_in_seatbelt(command, args, &config, Some(proxy.addr().port())).await?;
+    result.blocked_connections = proxy.blocked();
+    Ok(result)
+}
+
+/// 构建 Firejail 参数
+#[cfg(target_os = "linux")]
+fn firejail_args(command: &str, args: &[String], config: &SandboxConfig) -> Vec<String> {
     let mut firejail_args = vec!["--quiet".to_string()];
 
     // 网络隔离
@@ -342,13 +930,23 @@ async fn execute_in_firejail(
     firejail_args.push("--".to_string());
     firejail_args.push(command.to_string());
     firejail_args.extend(args.iter().cloned());
+    firejail_args
+}
 
+/// Firejail 沙箱执行 (Linux)
+#[cfg(target_os = "linux")]
+async fn execute_in_firejail(
+    command: &str,
+    args: &[String],
+    config: &SandboxConfig,
+) -> anyhow::Result<ExecutorResult> {
     let mut cmd = Command::new("firejail");
-    cmd.args(&firejail_args)
+    cmd.args(firejail_args(command, args, config))
+        .envs(&config.environment_variables)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    let output = cmd.output().await?;
+    let (output, usage) = run_with_usage(&mut cmd, None).await?;
 
     Ok(ExecutorResult {
         exit_code: output.status.code().unwrap_or(1),
@@ -357,34 +955,165 @@ async fn execute_in_firejail(
         sandboxed: true,
         sandbox_type: SandboxType::Firejail,
         duration: None,
+        blocked_connections: Vec::new(),
+        usage,
     })
 }
 
+/// 在沙箱中启动进程，不等待其结束
+///
+/// 用于需要在运行期间监控资源使用的长时间进程，标准输出和标准错误为管道。
+/// 返回子进程和实际使用的沙箱类型；当前平台不支持的沙箱类型回退到无沙箱。
+/// Docker 容器内的进程无法从宿主机监控，因此会挂载 `allowed_paths`（只读）
+/// 和 `writable_paths`（读写）并由 Docker 负责内存限制。
+/// 不支持 `network_allowlist`，配置了白名单时返回错误；网络只按 `network_access` 控制。
+/// 子进程是自己进程组的组长，可以用 `kill(-pid)` 结束它创建的整个进程树。
+pub fn spawn_in_sandbox(
+    command: &str,
+    args: &[String],
+    config: &SandboxConfig,
+) -> anyhow::Result<(Child, SandboxType)> {
+    spawn_with_fallback(command, args, config, true)
+}
+
+/// 与 [`spawn_in_sandbox`] 相同，但沙箱被禁用、类型为 `None` 或不可用时返回错误，
+/// 绝不在沙箱外启动进程
+pub fn spawn_in_sandbox_strict(
+    command: &str,
+    args: &[String],
+    config: &SandboxConfig,
+) -> anyhow::Result<(Child, SandboxType)> {
+    spawn_with_fallback(command, args, config, false)
+}
+
+fn spawn_with_fallback(
+    command: &str,
+    args: &[String],
+    config: &SandboxConfig,
+    fallback: bool,
+) -> anyhow::Result<(Child, SandboxType)> {
+    if config.network_allowlist.is_some() {
+        anyhow::bail!("spawn_in_sandbox 不支持网络白名单");
+    }
+
+    let sandbox_type = if config.enabled {
+        config.sandbox_type
+    } else {
+        SandboxType::None
+    };
+
+    let (mut cmd, sandbox_type) = match sandbox_type {
+        #[cfg(target_os = "linux")]
+        SandboxType::Bubblewrap => match bwrap_usable() {
+            Ok(()) => {
+                let mut cmd = Command::new("bwrap");
+                cmd.args(bubblewrap_args(command, args, config));
+                (cmd, SandboxType::Bubblewrap)
+            }
+            Err(reason) => {
+                if !fallback {
+                    anyhow::bail!("Bubblewrap 不可用: {}", reason);
+                }
+                tracing::warn!("Bubblewrap 不可用（{}），回退到无沙箱执行", reason);
+                let mut cmd = Command::new(command);
+                cmd.args(args);
+                (cmd, SandboxType::None)
+            }
+        },
+        #[cfg(target_os = "linux")]
+        SandboxType::Firejail => {
+            let mut cmd = Command::new("firejail");
+            cmd.args(firejail_args(command, args, config));
+            (cmd, SandboxType::Firejail)
+        }
+        #[cfg(target_os = "macos")]
+        SandboxType::Seatbelt => match seatbelt_usable() {
+            Ok(()) => {
+                let working_dir = std::env::current_dir().ok();
+                let profile = seatbelt_profile(config, working_dir.as_deref(), None);
+                let mut cmd = Command::new("sandbox-exec");
+                cmd.args(["-p", &profile, command]).args(args);
+                (cmd, SandboxType::Seatbelt)
+            }
+            Err(reason) => {
+                if !fallback {
+                    anyhow::bail!("Seatbelt 不可用: {}", reason);
+                }
+                tracing::warn!("Seatbelt 不可用（{}），回退到无沙箱执行", reason);
+                let mut cmd = Command::new(command);
+                cmd.args(args);
+                (cmd, SandboxType::None)
+            }
+        },
+        SandboxType::Docker => {
+            let mut cmd = Command::new("docker");
+            cmd.args(["run", "--rm"]);
+            if let Some(max_memory) = config.resource_limits.as_ref().and_then(|l| l.max_memory) {
+                cmd.arg("-m")
+                    .arg(format!("{}m", (max_memory / 1024 / 1024).max(6)));
+            }
+            if !config.network_access {
+                cmd.arg("--network=none");
+            }
+            for path in &config.allowed_paths {
+                cmd.arg("-v")
+                    .arg(format!("{}:{}:ro", path.display(), path.display()));
+            }
+            for path in &config.writable_paths {
+                cmd.arg("-v")
+                    .arg(format!("{}:{}", path.display(), path.display()));
+            }
+            let image = config
+                .docker
+                .as_ref()
+                .and_then(|d| d.image.as_deref())
+                .unwrap_or("alpine:latest");
+            cmd.arg(image).arg(command).args(args);
+            (cmd, SandboxType::Docker)
+        }
+        other => {
+            if !fallback {
+                anyhow::bail!("{:?} 沙箱在当前平台不可用", other);
+            }
+            if other != SandboxType::None {
+                tracing::warn!("{:?} 沙箱在当前平台不可用，回退到无沙箱执行", other);
+            }
+            let mut cmd = Command::new(command);
+            cmd.args(args);
+            (cmd, SandboxType::None)
+        }
+    };
+
+    for (key, value) in &config.environment_variables {
+        cmd.env(key, value);
+    }
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    Ok((cmd.spawn()?, sandbox_type))
+}
+
 /// 检测最佳沙箱类型
 pub fn detect_best_sandbox() -> SandboxType {
     #[cfg(target_os = "linux")]
     {
-        // 检查 bwrap
-        if std::process::Command::new("which")
-            .arg("bwrap")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return SandboxType::Bubblewrap;
+        // 检查 bwrap 能否建立命名空间，优先于其他方案
+        match bwrap_usable() {
+            Ok(()) => return SandboxType::Bubblewrap,
+            Err(reason) => tracing::debug!("跳过 Bubblewrap: {}", reason),
         }
     }
 
     #[cfg(target_os = "macos")]
     {
         // macOS 默认有 sandbox-exec
-        if std::process::Command::new("which")
-            .arg("sandbox-exec")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return SandboxType::Seatbelt;
+        match seatbelt_usable() {
+            Ok(()) => return SandboxType::Seatbelt,
+            Err(reason) => tracing::debug!("跳过 Seatbelt: {}", reason),
         }
     }
 
@@ -412,21 +1141,13 @@ pub fn get_sandbox_capabilities() -> SandboxCapabilities {
 
     #[cfg(target_os = "linux")]
     {
-        caps.bubblewrap = std::process::Command::new("which")
-            .arg("bwrap")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false);
+        caps.bubblewrap = bwrap_usable().is_ok();
         caps.resource_limits = true;
     }
 
     #[cfg(target_os = "macos")]
     {
-        caps.seatbelt = std::process::Command::new("which")
-            .arg("sandbox-exec")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false);
+        caps.seatbelt = seatbelt_usable().is_ok();
         caps.resource_limits = true;
     }
 
@@ -499,3 +1220,250 @@ impl SandboxExecutor {
         &self.config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_result_reports_resource_usage() {
+        // 纯 CPU 的单线程工作负载
+        let script = "i=0; while [ $i -lt 100000 ]; do i=$((i+1)); done";
+        let result = execute_in_sandbox(
+            "sh",
+            &["-c".to_string(), script.to_string()],
+            &SandboxConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+
+        let usage = result.usage;
+        let peak = usage.peak_memory_bytes.expect("peak memory");
+        assert!(peak > 0 && peak < 1024 * 1024 * 1024, "peak {}", peak);
+        let cpu = usage.cpu_time_ms.expect("cpu time");
+        assert!(cpu > 0, "cpu {}", cpu);
+        // 单线程进程的 CPU 时间不会明显超过墙钟时间
+        assert!(cpu <= usage.wall_time_ms + 50, "{:?}", usage);
+        assert!(usage.wall_time_ms <= result.duration.unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_descendants_holding_output() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let marker = dir.path().join("marker");
+        // 后台进程继承 stdout，子进程被杀后仍会让读取阻塞
+        let script = format!("(sleep 1; touch '{}') & sleep 30", marker.display());
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &script]);
+
+        let start = Instant::now();
+        let result = run_with_usage(&mut cmd, Some(Duration::from_millis(200))).await;
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists(), "background process outlived the timeout");
+    }
+
+    #[cfg(target_os = "linux")]
+    fn write_file(path: &Path) -> Vec<String> {
+        vec![
+            "-c".to_string(),
+            format!("echo data > '{}'", path.display()),
+        ]
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_bubblewrap_blocks_write_outside_policy() {
+        use crate::sandbox::{FilesystemPolicy, PathRule};
+
+        if let Err(reason) = bwrap_usable() {
+            eprintln!("跳过: {}", reason);
+            return;
+        }
+
+        let workspace = tempfile::tempdir().unwrap();
+        let readonly = tempfile::tempdir().unwrap();
+        let unlisted = tempfile::tempdir().unwrap();
+
+        let mut policy = FilesystemPolicy::new();
+        policy.add_rule(PathRule::read_write(workspace.path().to_string_lossy()));
+        policy.add_rule(PathRule::read_only(readonly.path().to_string_lossy()));
+        let config = SandboxConfig {
+            sandbox_type: SandboxType::Bubblewrap,
+            // 临时目录位于 /tmp 下，不能整体挂载为可写
+            writable_paths: Vec::new(),
+            filesystem_policy: Some(policy),
+            ..Default::default()
+        };
+
+        let inside = workspace.path().join("inside.txt");
+        let result = execute_in_sandbox("sh", &write_file(&inside), &config)
+            .await
+            .unwrap();
+        assert!(result.sandboxed);
+        assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+        assert!(inside.exists());
+
+        for dir in [readonly.path(), unlisted.path()] {
+            let outside = dir.join("outside.txt");
+            let result = execute_in_sandbox("sh", &write_file(&outside), &config)
+                .await
+                .unwrap();
+            assert!(result.sandboxed);
+            assert_ne!(result.exit_code, 0);
+            assert!(!outside.exists());
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_bubblewrap_failure_inside_sandbox_is_not_retried_unsandboxed() {
+        if let Err(reason) = bwrap_usable() {
+            eprintln!("跳过: {}", reason);
+            return;
+        }
+
+        let config = SandboxConfig {
+            sandbox_type: SandboxType::Bubblewrap,
+            ..Default::default()
+        };
+        let result = execute_in_sandbox("/nonexistent/command", &[], &config)
+            .await
+            .unwrap();
+        assert!(result.sandboxed);
+        assert_eq!(result.sandbox_type, SandboxType::Bubblewrap);
+        assert_ne!(result.exit_code, 0);
+        assert!(result.stderr.starts_with("bwrap:"), "{}", result.stderr);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_bubblewrap_allowlist_refuses_direct_connection() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        if let Err(reason) = bwrap_usable() {
+            eprintln!("跳过: {}", reason);
+            return;
+        }
+        if !Path::new("/bin/bash").exists() {
+            eprintln!("跳过: 没有 bash");
+            return;
+        }
+
+        // 宿主机上的服务，不在白名单内
+        let server = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        let accepted = Arc::new(AtomicBool::new(false));
+        {
+            let accepted = Arc::clone(&accepted);
+            tokio::spawn(async move {
+                while server.accept().await.is_ok() {
+                    accepted.store(true, Ordering::SeqCst);
+                }
+            });
+        }
+
+        let config = SandboxConfig {
+            sandbox_type: SandboxType::Bubblewrap,
+            network_allowlist: Some(NetworkAllowlist::new(["example.com"])),
+            ..Default::default()
+        };
+        let script = format!(
+            "if (exec 3<>/dev/tcp/127.0.0.1/{port}) 2>/dev/null; then echo direct-connected; \
+             else echo direct-refused; fi; \
+             exec 3<>/dev/tcp/127.0.0.1/{proxy}; \
+             printf 'CONNECT 127.0.0.1:{port} HTTP/1.1\\r\\n\\r\\n' >&3; \
+             read -r status <&3; echo \"$status\"",
+            port = port,
+            proxy = SANDBOX_PROXY_PORT,
+        );
+        let result = execute_in_sandbox("/bin/bash", &["-c".to_string(), script], &config)
+            .await
+            .unwrap();
+        assert!(result.sandboxed);
+        assert!(result.stdout.contains("direct-refused"), "{:?}", result);
+        assert!(result.stdout.contains("403"), "{:?}", result);
+        assert_eq!(
+            result.blocked_connections,
+            vec![BlockedConnection {
+                host: "127.0.0.1".to_string(),
+                port,
+            }]
+        );
+        assert!(!accepted.load(Ordering::SeqCst));
+
+        // 没有沙箱时不会放开网络执行
+        let unsandboxed = SandboxConfig {
+            sandbox_type: SandboxType::None,
+            ..config.clone()
+        };
+        assert!(execute_in_sandbox("true", &[], &unsandboxed).await.is_err());
+        let docker = SandboxConfig {
+            sandbox_type: SandboxType::Docker,
+            ..config
+        };
+        assert!(execute_in_sandbox("true", &[], &docker).await.is_err());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_seatbelt_allowlist_only_allows_proxy_port() {
+        let config = SandboxConfig {
+            sandbox_type: SandboxType::Seatbelt,
+            network_allowlist: Some(NetworkAllowlist::new(["example.com"])),
+            ..Default::default()
+        };
+
+        let profile = seatbelt_profile(&config, None, Some(8123));
+        assert!(profile.contains("(allow network-outbound (remote ip \"localhost:8123\"))"));
+        assert!(!profile.contains("localhost:*"));
+        assert!(!profile.contains("(allow network*)"));
+
+        let profile = seatbelt_profile(&config, None, None);
+        assert!(!profile.contains("network"));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[tokio::test]
+    async fn test_seatbelt_blocks_read_of_denied_path() {
+        if let Err(reason) = seatbelt_usable() {
+            eprintln!("跳过: {}", reason);
+            return;
+        }
+
+        // 临时目录不在任何放行规则内
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("secret.txt");
+        std::fs::write(&secret, "secret").unwrap();
+
+        let config = SandboxConfig {
+            sandbox_type: SandboxType::Seatbelt,
+            writable_paths: Vec::new(),
+            ..Default::default()
+        };
+        let profile = seatbelt_profile(&config, Some(Path::new("/private/tmp/workspace")), None);
+        assert!(profile.contains("(deny default)"));
+        assert!(
+            profile.contains("(allow file-read* file-write* (subpath \"/private/tmp/workspace\"))")
+        );
+
+        let result = execute_in_sandbox("cat", &[secret.to_string_lossy().to_string()], &config)
+            .await
+            .unwrap();
+        assert!(result.sandboxed);
+        assert_ne!(result.exit_code, 0);
+        assert!(!result.stdout.contains("secret"));
+
+        // 工作目录（cargo test 在 crate 根目录运行）可读
+        let result = execute_in_sandbox("cat", &["Cargo.toml".to_string()], &config)
+            .await
+            .unwrap();
+        assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+    }
+}
@@ -5,14 +5,19 @@
 mod config;
 mod executor;
 mod filesystem;
+mod network;
 mod resource_limits;
 
 pub use config::{
-    ResourceLimits, SandboxConfig, SandboxConfigManager, SandboxPreset, SANDBOX_PRESETS,
+    ResourceLimits, SandboxConfig, SandboxConfigManager, SandboxPreset, SandboxType,
+    SANDBOX_PRESETS,
 };
 pub use executor::{
-    detect_best_sandbox, execute_in_sandbox, get_sandbox_capabilities, ExecutorOptions,
-    ExecutorResult, SandboxExecutor,
+    detect_best_sandbox, execute_in_sandbox, get_sandbox_capabilities, spawn_in_sandbox,
+    spawn_in_sandbox_strict, ExecutorOptions, ExecutorResult, SandboxExecutor,
 };
 pub use filesystem::{FilesystemPolicy, FilesystemSandbox, PathRule};
-pub use resource_limits::{ResourceLimiter, ResourceUsage};
+pub use network::{BlockedConnection, NetworkAllowlist, NetworkProxy};
+pub use resource_limits::{
+    process_tree_usage, ExecutionUsage, ResourceLimitError, ResourceLimiter, ResourceUsage,
+};
@@ -0,0 +1,498 @@
+//! 网络沙箱
+//!
+//! 提供按主机名/IP 放行的网络白名单。沙箱内进程的出站连接经由本地过滤代理，
+//! 只有白名单内的主机可以连接，被拒绝的连接会被记录。
+//!
+//! 代理环境变量只是告诉沙箱内的程序去哪里连接，真正的限制来自沙箱本身：
+//! Bubblewrap 保持独立的网络命名空间，只把代理的监听套接字放进去
+//! （见 [`listen_in_netns`]）；Seatbelt 只允许连接本机。
+
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// 请求头的最大长度
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// 网络白名单
+///
+/// 条目为主机名、IP 地址或 `*.example.com` 形式的通配符（只匹配子域名）。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkAllowlist {
+    /// 允许连接的主机
+    pub hosts: Vec<String>,
+}
+
+impl NetworkAllowlist {
+    /// 创建白名单
+    pub fn new(hosts: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            hosts: hosts.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// 白名单是否为空
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    /// 检查主机是否允许连接
+    pub fn allows(&self, host: &str) -> bool {
+        let host = normalize_host(host);
+        self.hosts.iter().any(|entry| {
+            let entry = normalize_host(entry);
+            match entry.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|prefix| prefix.ends_with('.')),
+                None => host == entry,
+            }
+        })
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// 被白名单拒绝的连接
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockedConnection {
+    /// 目标主机
+    pub host: String,
+    /// 目标端口
+    pub port: u16,
+}
+
+/// 按白名单过滤的本地 HTTP 代理
+///
+/// 支持 `CONNECT`（HTTPS 及任意 TCP）和绝对 URI 形式的普通 HTTP 请求。
+/// 代理在被 drop 时停止。
+pub struct NetworkProxy {
+    addr: SocketAddr,
+    blocked: Arc<Mutex<Vec<BlockedConnection>>>,
+    task: JoinHandle<()>,
+}
+
+impl NetworkProxy {
+    /// 在 127.0.0.1 的随机端口上启动代理
+    pub async fn start(allowlist: NetworkAllowlist) -> std::io::Result<Self> {
+        Self::serve(TcpListener::bind("127.0.0.1:0").await?, allowlist)
+    }
+
+    /// 在已有的监听套接字上启动代理
+    ///
+    /// 套接字监听在所有地址上时（例如沙箱网络命名空间内的套接字），
+    /// 以回环地址作为代理地址。
+    pub fn serve(listener: TcpListener, allowlist: NetworkAllowlist) -> std::io::Result<Self> {
+        let mut addr = listener.local_addr()?;
+        if addr.ip().is_unspecified() {
+            addr.set_ip(Ipv4Addr::LOCALHOST.into());
+        }
+        let blocked = Arc::new(Mutex::new(Vec::new()));
+        let allowlist = Arc::new(allowlist);
+
+        let task = {
+            let blocked = Arc::clone(&blocked);
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let allowlist = Arc::clone(&allowlist);
+                    let blocked = Arc::clone(&blocked);
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &allowlist, &blocked).await {
+                            tracing::debug!("网络代理连接出错: {}", e);
+                        }
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            blocked,
+            task,
+        })
+    }
+
+    /// 代理监听地址
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 代理 URL
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// 让子进程使用代理的环境变量
+    pub fn env(&self) -> Vec<(String, String)> {
+        proxy_env(&self.url())
+    }
+
+    /// 目前为止被拒绝的连接
+    pub fn blocked(&self) -> Vec<BlockedConnection> {
+        self.blocked.lock().unwrap().clone()
+    }
+}
+
+impl Drop for NetworkProxy {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 让子进程使用 `url` 处代理的环境变量
+pub fn proxy_env(url: &str) -> Vec<(String, String)> {
+    let mut env: Vec<(String, String)> = [
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+        "http_proxy",
+        "https_proxy",
+        "all_proxy",
+    ]
+    .iter()
+    .map(|key| (key.to_string(), url.to_string()))
+    .collect();
+    // 不允许绕过代理
+    env.push(("NO_PROXY".to_string(), String::new()));
+    env.push(("no_proxy".to_string(), String::new()));
+    env
+}
+
+/// 在进程 `pid` 所在的网络命名空间内监听 `port`（所有地址）
+///
+/// 非特权 bwrap 的网络命名空间归它创建的用户命名空间所有，多线程的当前进程
+/// 无法加入。因此 fork 出单线程的辅助进程，依次加入目标的用户命名空间和网络
+/// 命名空间，创建监听套接字后通过 `SCM_RIGHTS` 传回。套接字始终属于创建它的
+/// 命名空间：沙箱内的进程只能连到这个套接字，而当前进程接受连接后仍从宿主机
+/// 网络连接上游。
+#[cfg(target_os = "linux")]
+pub(crate) fn listen_in_netns(pid: u32, port: u16) -> std::io::Result<std::net::TcpListener> {
+    use std::fs::File;
+    use std::io::Error;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    let user_ns = File::open(format!("/proc/{}/ns/user", pid))?;
+    let net_ns = File::open(format!("/proc/{}/ns/net", pid))?;
+
+    let mut fds = [0; 2];
+    // SAFETY: fds 容纳 socketpair 返回的两个描述符
+    let ret = unsafe {
+        libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
+            0,
+            fds.as_mut_ptr(),
+        )
+    };
+    if ret == -1 {
+        return Err(Error::last_os_error());
+    }
+    // SAFETY: 两个描述符刚由 socketpair 创建，归这里所有
+    let (parent_end, child_end) =
+        unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+
+    // fork 之后只能调用异步信号安全的函数，所需内存提前准备好
+    let addr = libc::sockaddr_in {
+        sin_family: libc::AF_INET as libc::sa_family_t,
+        sin_port: port.to_be(),
+        sin_addr: libc::in_addr {
+            s_addr: libc::INADDR_ANY,
+        },
+        sin_zero: [0; 8],
+    };
+    // SAFETY: CMSG_SPACE 只做长度计算
+    let cmsg_space = unsafe { libc::CMSG_SPACE(std::mem::size_of::<libc::c_int>() as u32) };
+    let mut send_buf = vec![0u8; cmsg_space as usize];
+    let mut recv_buf = vec![0u8; cmsg_space as usize];
+
+    // SAFETY: 子进程只执行系统调用，随后 _exit，不返回到 Rust 运行时
+    let child = unsafe { libc::fork() };
+    if child == -1 {
+        return Err(Error::last_os_error());
+    }
+    if child == 0 {
+        // SAFETY: 见上
+        unsafe {
+            let code = netns_listener_child(
+                user_ns.as_raw_fd(),
+                net_ns.as_raw_fd(),
+                &addr,
+                child_end.as_raw_fd(),
+                &mut send_buf,
+            );
+            libc::_exit(code)
+        }
+    }
+    drop(child_end);
+
+    let mut byte = 0u8;
+    let mut iov = libc::iovec {
+        iov_base: &mut byte as *mut u8 as *mut libc::c_void,
+        iov_len: 1,
+    };
+    // SAFETY: msghdr 只包含整数和指针字段，全零是合法值
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = recv_buf.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = recv_buf.len() as _;
+    // SAFETY: msg 指向的缓冲区在调用期间有效；辅助进程退出时对端关闭，不会一直阻塞
+    let received =
+        unsafe { libc::recvmsg(parent_end.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    let recv_error = Error::last_os_error();
+
+    let mut status = 0;
+    // SAFETY: child 是刚 fork 出的子进程，只在这里回收
+    while unsafe { libc::waitpid(child, &mut status, 0) } == -1 {
+        let err = Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    if received == -1 {
+        return Err(recv_error);
+    }
+    // SAFETY: recvmsg 已填充 msg，CMSG_FIRSTHDR 在没有控制消息时返回空指针
+    let cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    if received == 0
+        || cmsg.is_null()
+        || unsafe {
+            (*cmsg).cmsg_level != libc::SOL_SOCKET || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+        }
+    {
+        let step = if libc::WIFEXITED(status) {
+            libc::WEXITSTATUS(status)
+        } else {
+            -1
+        };
+        return Err(Error::other(format!(
+            "无法在沙箱网络命名空间内监听（辅助进程在第 {} 步失败）",
+            step
+        )));
+    }
+    // SAFETY: SCM_RIGHTS 消息的数据是一个刚传入的描述符，归这里所有
+    let fd = unsafe { std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::c_int) };
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// [`listen_in_netns`] 的辅助进程，返回值为退出码（失败的步骤）
+///
+/// # Safety
+///
+/// 只能在 fork 出的子进程中调用，`cmsg_buf` 至少为 `CMSG_SPACE(sizeof(int))` 字节。
+#[cfg(target_os = "linux")]
+unsafe fn netns_listener_child(
+    user_ns: libc::c_int,
+    net_ns: libc::c_int,
+    addr: &libc::sockaddr_in,
+    channel: libc::c_int,
+    cmsg_buf: &mut [u8],
+) -> libc::c_int {
+    // 目标与当前进程处在同一用户命名空间时（例如 setuid 的 bwrap）返回 EINVAL，
+    // 此时直接尝试加入网络命名空间
+    if libc::setns(user_ns, libc::CLONE_NEWUSER) == -1
+        && std::io::Error::last_os_error().raw_os_error() != Some(libc::EINVAL)
+    {
+        return 1;
+    }
+    if libc::setns(net_ns, libc::CLONE_NEWNET) == -1 {
+        return 2;
+    }
+
+    let sock = libc::socket(libc::AF_INET, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0);
+    if sock == -1 {
+        return 3;
+    }
+    if libc::bind(
+        sock,
+        addr as *const libc::sockaddr_in as *const libc::sockaddr,
+        std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+    ) == -1
+    {
+        return 4;
+    }
+    if libc::listen(sock, 128) == -1 {
+        return 5;
+    }
+
+    let mut byte = 0u8;
+    let mut iov = libc::iovec {
+        iov_base: &mut byte as *mut u8 as *mut libc::c_void,
+        iov_len: 1,
+    };
+    let mut msg: libc::msghdr = std::mem::zeroed();
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = cmsg_buf.len() as _;
+    let cmsg = libc::CMSG_FIRSTHDR(&msg);
+    (*cmsg).cmsg_level = libc::SOL_SOCKET;
+    (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+    (*cmsg).cmsg_len = libc::CMSG_LEN(std::mem::size_of::<libc::c_int>() as u32) as _;
+    std::ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut libc::c_int, sock);
+    if libc::sendmsg(channel, &msg, 0) == -1 {
+        return 6;
+    }
+    0
+}
+
+/// 解析请求行，返回 (目标主机, 端口, 是否为 CONNECT)
+fn parse_request_line(line: &str) -> Option<(String, u16, bool)> {
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = target.rsplit_once(':')?;
+        return Some((normalize_host(host), port.parse().ok()?, true));
+    }
+
+    let url = url::Url::parse(target).ok()?;
+    let host = url.host_str()?;
+    let port = url.port_or_known_default()?;
+    Some((normalize_host(host), port, false))
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    allowlist: &NetworkAllowlist,
+    blocked: &Mutex<Vec<BlockedConnection>>,
+) -> std::io::Result<()> {
+    let mut client = BufReader::new(stream);
+
+    // 读取完整请求头
+    let mut head = String::new();
+    loop {
+        let read = client.read_line(&mut head).await?;
+        if read == 0 || head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            break;
+        }
+        if head.len() > MAX_REQUEST_HEAD {
+            return respond(client.get_mut(), "431 Request Header Fields Too Large").await;
+        }
+    }
+
+    let Some((host, port, is_connect)) = head.lines().next().and_then(parse_request_line) else {
+        return respond(client.get_mut(), "400 Bad Request").await;
+    };
+
+    if !allowlist.allows(&host) {
+        tracing::info!("网络白名单拒绝连接 {}:{}", host, port);
+        blocked
+            .lock()
+            .unwrap()
+            .push(BlockedConnection { host, port });
+        return respond(client.get_mut(), "403 Forbidden").await;
+    }
+
+    let mut upstream = match TcpStream::connect((host.as_str(), port)).await {
+        Ok(upstream) => upstream,
+        Err(_) => return respond(client.get_mut(), "502 Bad Gateway").await,
+    };
+
+    if is_connect {
+        client
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+    } else {
+        upstream.write_all(head.as_bytes()).await?;
+    }
+
+    // 转发已缓冲但尚未读取的数据
+    let buffered = client.buffer().to_vec();
+    if !buffered.is_empty() {
+        upstream.write_all(&buffered).await?;
+    }
+    let mut client = client.into_inner();
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+async fn respond(stream: &mut TcpStream, status: &str) -> std::io::Result<()> {
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    async fn connect_through(proxy: &NetworkProxy, target: &str) -> (String, TcpStream) {
+        let mut stream = TcpStream::connect(proxy.addr()).await.unwrap();
+        stream
+            .write_all(
+                format!("CONNECT {} HTTP/1.1\r\nHost: {}\r\n\r\n", target, target).as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            let mut byte = [0u8; 1];
+            if stream.read(&mut byte).await.unwrap() == 0 {
+                break;
+            }
+            response.push(byte[0]);
+        }
+        (String::from_utf8(response).unwrap(), stream)
+    }
+
+    #[tokio::test]
+    async fn test_proxy_refuses_host_outside_allowlist() {
+        let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = server.accept().await {
+                let _ = stream.write_all(b"hello").await;
+            }
+        });
+
+        let proxy = NetworkProxy::start(NetworkAllowlist::new(["127.0.0.1"]))
+            .await
+            .unwrap();
+
+        let (response, mut stream) = connect_through(&proxy, &format!("127.0.0.1:{}", port)).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let mut greeting = [0u8; 5];
+        stream.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(&greeting, b"hello");
+
+        // 同一服务器，但通过不在白名单内的主机名访问
+        let (response, _) = connect_through(&proxy, &format!("localhost:{}", port)).await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{}", response);
+        assert_eq!(
+            proxy.blocked(),
+            vec![BlockedConnection {
+                host: "localhost".to_string(),
+                port,
+            }]
+        );
+
+        let allowlist = NetworkAllowlist::new(["*.npmjs.org", "PyPI.org"]);
+        assert!(allowlist.allows("registry.npmjs.org"));
+        assert!(!allowlist.allows("npmjs.org"));
+        assert!(!allowlist.allows("evilnpmjs.org"));
+        assert!(allowlist.allows("pypi.org."));
+    }
+}
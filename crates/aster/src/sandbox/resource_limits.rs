@@ -20,6 +20,19 @@ pub struct ResourceUsage {
     pub execution_time_ms: u64,
 }
 
+/// 一次执行的实际资源消耗
+///
+/// 进程退出后由后端统计，不依赖是否设置了资源限制。平台无法提供的字段为 None。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionUsage {
+    /// 峰值内存（字节），进程树中单个进程的最大 RSS
+    pub peak_memory_bytes: Option<u64>,
+    /// 用户态与内核态 CPU 时间之和（毫秒），包含已回收的子进程
+    pub cpu_time_ms: Option<u64>,
+    /// 墙钟时间（毫秒）
+    pub wall_time_ms: u64,
+}
+
 /// 资源限制器
 pub struct ResourceLimiter {
     /// 最大内存
@@ -207,6 +220,65 @@ impl std::fmt::Display for ResourceLimitError {
 
 impl std::error::Error for ResourceLimitError {}
 
+/// 读取进程及其所有子进程的资源使用
+///
+/// 通过 /proc 统计内存（RSS）、进程数和文件描述符，进程不存在时返回 None。
+#[cfg(target_os = "linux")]
+pub fn process_tree_usage(pid: u32) -> Option<ResourceUsage> {
+    use std::collections::HashMap;
+    use std::fs;
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for entry in fs::read_dir("/proc").ok()?.flatten() {
+        let Some(child) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        if let Some(ppid) = read_ppid(child) {
+            children.entry(ppid).or_default().push(child);
+        }
+    }
+
+    let mut usage = ResourceUsage::default();
+    let mut found = false;
+    let mut stack = vec![pid];
+    while let Some(current) = stack.pop() {
+        let Ok(status) = fs::read_to_string(format!("/proc/{}/status", current)) else {
+            continue;
+        };
+        found = true;
+        usage.process_count += 1;
+        usage.memory_bytes += status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))
+            .and_then(|v| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+            .unwrap_or(0)
+            * 1024;
+        usage.file_descriptors += fs::read_dir(format!("/proc/{}/fd", current))
+            .map(|fds| fds.count() as u32)
+            .unwrap_or(0);
+        if let Some(pids) = children.get(&current) {
+            stack.extend(pids);
+        }
+    }
+
+    found.then_some(usage)
+}
+
+/// 读取父进程 ID
+#[cfg(target_os = "linux")]
+fn read_ppid(pid: u32) -> Option<u32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // 进程名可能包含空格和括号，从最后一个 ')' 之后解析
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// 读取进程及其所有子进程的资源使用（当前平台不支持）
+#[cfg(not(target_os = "linux"))]
+pub fn process_tree_usage(_pid: u32) -> Option<ResourceUsage> {
+    None
+}
+
 /// 构建 ulimit 参数
 pub fn build_ulimit_args(limits: &super::config::ResourceLimits) -> Vec<String> {
     let mut args = Vec::new();
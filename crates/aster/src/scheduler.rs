@@ -11,6 +11,11 @@ pub mod executor;
 #[path = "scheduler/delivery.rs"]
 pub mod delivery;
 
+#[path = "scheduler/cron_scheduler.rs"]
+pub mod cron_scheduler;
+
+pub use cron_scheduler::{CronExpression, CronScheduler};
+
 use std::collections::HashMap;
 use std::fs;
 use std::io;
@@ -123,6 +128,10 @@ pub struct ScheduledJob {
     pub currently_running: bool,
     #[serde(default)]
     pub paused: bool,
+    /// Paused by `pause_all` rather than individually, so `resume_all`
+    /// unpauses it, including after a restart
+    #[serde(default)]
+    pub paused_by_pause_all: bool,
     #[serde(default)]
     pub current_session_id: Option<String>,
     #[serde(default)]
@@ -365,6 +374,7 @@ impl Scheduler {
                         last_run: None,
                         currently_running: false,
                         paused: false,
+                        paused_by_pause_all: false,
                         current_session_id: None,
                         process_start_time: None,
                     };
@@ -511,23 +521,48 @@ impl Scheduler {
         sched_id: &str,
         limit: usize,
     ) -> Result<Vec<(String, Session)>, SchedulerError> {
-        let all_sessions = SessionManager::list_sessions()
-            .await
-            .map_err(|e| SchedulerError::StorageError(io::Error::other(e)))?;
-
-        let mut schedule_sessions: Vec<(String, Session)> = all_sessions
-            .into_iter()
-            .filter(|s| s.schedule_id.as_deref() == Some(sched_id))
-            .map(|s| (s.id.clone(), s))
-            .collect();
+        schedule_sessions(sched_id, limit).await
+    }
 
-        schedule_sessions.sort_by(|a, b| b.1.created_at.cmp(&a.1.created_at));
-        schedule_sessions.truncate(limit);
+    pub async fn run_now(&self, sched_id: &str) -> Result<String, SchedulerError> {
+        let (job_to_run, cancel_token) = self.begin_manual_run(sched_id).await?;
+        let result = execute_job(
+            job_to_run,
+            self.jobs.clone(),
+            sched_id.to_string(),
+            cancel_token,
+        )
+        .await;
+        self.finish_manual_run(sched_id, result).await
+    }
 
-        Ok(schedule_sessions)
+    /// Runs a job once in the background without touching its schedule.
+    ///
+    /// The job runs even if it is paused; its recurrence and paused state are
+    /// left unchanged.
+    pub async fn trigger_now(self: &Arc<Self>, sched_id: &str) -> Result<(), SchedulerError> {
+        let (job_to_run, cancel_token) = self.begin_manual_run(sched_id).await?;
+        let scheduler = Arc::clone(self);
+        let sched_id = sched_id.to_string();
+        tokio::spawn(async move {
+            let result = execute_job(
+                job_to_run,
+                scheduler.jobs.clone(),
+                sched_id.clone(),
+                cancel_token,
+            )
+            .await;
+            if let Err(e) = scheduler.finish_manual_run(&sched_id, result).await {
+                tracing::error!("Triggered job '{}' failed: {}", sched_id, e);
+            }
+        });
+        Ok(())
     }
 
-    pub async fn run_now(&self, sched_id: &str) -> Result<String, SchedulerError> {
+    async fn begin_manual_run(
+        &self,
+        sched_id: &str,
+    ) -> Result<(ScheduledJob, CancellationToken), SchedulerError> {
         let job_to_run = {
             let mut jobs_guard = self.jobs.lock().await;
             match jobs_guard.get_mut(sched_id) {
@@ -553,15 +588,14 @@ impl Scheduler {
             let mut tasks = self.running_tasks.lock().await;
             tasks.insert(sched_id.to_string(), cancel_token.clone());
         }
+        Ok((job_to_run, cancel_token))
+    }
 
-        let result = execute_job(
-            job_to_run,
-            self.jobs.clone(),
-            sched_id.to_string(),
-            cancel_token.clone(),
-        )
-        .await;
-
+    async fn finish_manual_run(
+        &self,
+        sched_id: &str,
+        result: Result<String>,
+    ) -> Result<String, SchedulerError> {
         {
             let mut tasks = self.running_tasks.lock().await;
             tasks.remove(sched_id);
@@ -601,6 +635,8 @@ impl Scheduler {
                         )));
                     }
                     job.paused = true;
+                    // An explicit pause outlives `resume_all`
+                    job.paused_by_pause_all = false;
                 }
                 None => return Err(SchedulerError::JobNotFound(sched_id.to_string())),
             }
@@ -613,7 +649,10 @@ impl Scheduler {
         {
             let mut jobs_guard = self.jobs.lock().await;
             match jobs_guard.get_mut(sched_id) {
-                Some((_, job)) => job.paused = false,
+                Some((_, job)) => {
+                    job.paused = false;
+                    job.paused_by_pause_all = false;
+                }
                 None => return Err(SchedulerError::JobNotFound(sched_id.to_string())),
             }
         }
@@ -621,6 +660,49 @@ impl Scheduler {
         persist_jobs(&self.storage_path, &self.jobs).await
     }
 
+    /// Pauses every job, e.g. during a deploy. Returns the ids of jobs that
+    /// were not already paused.
+    ///
+    /// Running jobs finish their current run. Schedules are kept, so
+    /// `resume_all` picks up where they left off.
+    pub async fn pause_all(&self) -> Result<Vec<String>, SchedulerError> {
+        let mut paused = Vec::new();
+        {
+            let mut jobs_guard = self.jobs.lock().await;
+            for (id, (_, job)) in jobs_guard.iter_mut() {
+                if !job.paused {
+                    job.paused = true;
+                    job.paused_by_pause_all = true;
+                    paused.push(id.clone());
+                }
+            }
+        }
+        paused.sort();
+
+        persist_jobs(&self.storage_path, &self.jobs).await?;
+        Ok(paused)
+    }
+
+    /// Resumes the jobs paused by `pause_all`. Jobs that were paused
+    /// individually stay paused. Returns the ids of resumed jobs.
+    pub async fn resume_all(&self) -> Result<Vec<String>, SchedulerError> {
+        let mut resumed = Vec::new();
+        {
+            let mut jobs_guard = self.jobs.lock().await;
+            for (id, (_, job)) in jobs_guard.iter_mut() {
+                if job.paused_by_pause_all {
+                    job.paused = false;
+                    job.paused_by_pause_all = false;
+                    resumed.push(id.clone());
+                }
+            }
+        }
+        resumed.sort();
+
+        persist_jobs(&self.storage_path, &self.jobs).await?;
+        Ok(resumed)
+    }
+
     pub async fn update_schedule(
         &self,
         sched_id: &str,
@@ -711,13 +793,53 @@ impl Scheduler {
     }
 }
 
-#[allow(clippy::too_many_lines)]
+/// Sessions created by a schedule, newest first.
+async fn schedule_sessions(
+    sched_id: &str,
+    limit: usize,
+) -> Result<Vec<(String, Session)>, SchedulerError> {
+    let all_sessions = SessionManager::list_sessions()
+        .await
+        .map_err(|e| SchedulerError::StorageError(io::Error::other(e)))?;
+
+    let mut schedule_sessions: Vec<(String, Session)> = all_sessions
+        .into_iter()
+        .filter(|s| s.schedule_id.as_deref() == Some(sched_id))
+        .map(|s| (s.id.clone(), s))
+        .collect();
+
+    schedule_sessions.sort_by(|a, b| b.1.created_at.cmp(&a.1.created_at));
+    schedule_sessions.truncate(limit);
+
+    Ok(schedule_sessions)
+}
+
 async fn execute_job(
     job: ScheduledJob,
     jobs: Arc<Mutex<JobsMap>>,
     job_id: String,
     cancel_token: CancellationToken,
 ) -> Result<String> {
+    run_job(job, cancel_token, |session_id| async move {
+        let mut jobs_guard = jobs.lock().await;
+        if let Some((_, job_def)) = jobs_guard.get_mut(job_id.as_str()) {
+            job_def.current_session_id = Some(session_id);
+        }
+    })
+    .await
+}
+
+/// Runs a job's recipe, reporting the session id once the session is created.
+#[allow(clippy::too_many_lines)]
+async fn run_job<F, Fut>(
+    job: ScheduledJob,
+    cancel_token: CancellationToken,
+    on_session_created: F,
+) -> Result<String>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
     if job.source.is_empty() {
         return Ok(job.id.to_string());
     }
@@ -762,11 +884,7 @@ async fn execute_job(
 
     agent.update_provider(agent_provider, &session.id).await?;
 
-    let mut jobs_guard = jobs.lock().await;
-    if let Some((_, job_def)) = jobs_guard.get_mut(job_id.as_str()) {
-        job_def.current_session_id = Some(session.id.clone());
-    }
-    drop(jobs_guard);
+    on_session_created(session.id.clone()).await;
 
     let start_time = std::time::Instant::now();
     tokio::spawn(async move {
@@ -951,6 +1069,7 @@ mod tests {
             last_run: None,
             currently_running: false,
             paused: false,
+            paused_by_pause_all: false,
             current_session_id: None,
             process_start_time: None,
         };
@@ -976,6 +1095,7 @@ mod tests {
             last_run: None,
             currently_running: false,
             paused: false,
+            paused_by_pause_all: false,
             current_session_id: None,
             process_start_time: None,
         };
@@ -987,4 +1107,121 @@ mod tests {
         let jobs = scheduler.list_scheduled_jobs().await;
         assert!(jobs[0].last_run.is_none(), "Paused job should not run");
     }
+
+    fn every_second_job(id: &str) -> ScheduledJob {
+        ScheduledJob {
+            id: id.to_string(),
+            source: String::new(),
+            cron: "* * * * * *".to_string(),
+            last_run: None,
+            currently_running: false,
+            paused: false,
+            paused_by_pause_all: false,
+            current_session_id: None,
+            process_start_time: None,
+        }
+    }
+
+    async fn find_job(scheduler: &Scheduler, id: &str) -> ScheduledJob {
+        scheduler
+            .list_scheduled_jobs()
+            .await
+            .into_iter()
+            .find(|j| j.id == id)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pause_all_and_resume_all() {
+        let temp_dir = tempdir().unwrap();
+        let scheduler = Scheduler::new(temp_dir.path().join("schedules.json"))
+            .await
+            .unwrap();
+
+        scheduler
+            .add_scheduled_job(every_second_job("nightly"), false)
+            .await
+            .unwrap();
+        let mut held = every_second_job("manual_hold");
+        held.paused = true;
+        scheduler.add_scheduled_job(held, false).await.unwrap();
+
+        assert_eq!(scheduler.pause_all().await.unwrap(), vec!["nightly"]);
+        let last_run_at_pause = find_job(&scheduler, "nightly").await.last_run;
+        sleep(Duration::from_millis(1500)).await;
+        let nightly = find_job(&scheduler, "nightly").await;
+        assert!(nightly.paused, "Job should be reported as paused");
+        assert_eq!(
+            nightly.last_run, last_run_at_pause,
+            "Paused job should not fire"
+        );
+        assert!(find_job(&scheduler, "manual_hold").await.paused);
+
+        assert_eq!(scheduler.resume_all().await.unwrap(), vec!["nightly"]);
+        sleep(Duration::from_millis(1500)).await;
+        let nightly = find_job(&scheduler, "nightly").await;
+        assert!(!nightly.paused);
+        assert!(
+            nightly.last_run > last_run_at_pause,
+            "Schedule should continue"
+        );
+        let held = find_job(&scheduler, "manual_hold").await;
+        assert!(held.paused, "Individually paused job should stay paused");
+        assert!(held.last_run.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resume_all_after_restart() {
+        let temp_dir = tempdir().unwrap();
+        let storage_path = temp_dir.path().join("schedules.json");
+        let recipe_path = create_test_recipe(temp_dir.path(), "nightly");
+        let yearly_job = |id: &str| {
+            let mut job = every_second_job(id);
+            job.source = recipe_path.to_string_lossy().to_string();
+            job.cron = "0 0 0 1 1 *".to_string();
+            job
+        };
+
+        {
+            let scheduler = Scheduler::new(storage_path.clone()).await.unwrap();
+            scheduler
+                .add_scheduled_job(yearly_job("nightly"), false)
+                .await
+                .unwrap();
+            scheduler
+                .add_scheduled_job(yearly_job("manual_hold"), false)
+                .await
+                .unwrap();
+            scheduler.pause_schedule("manual_hold").await.unwrap();
+            assert_eq!(scheduler.pause_all().await.unwrap(), vec!["nightly"]);
+        }
+
+        let scheduler = Scheduler::new(storage_path).await.unwrap();
+        assert!(find_job(&scheduler, "nightly").await.paused);
+        assert_eq!(scheduler.resume_all().await.unwrap(), vec!["nightly"]);
+        assert!(!find_job(&scheduler, "nightly").await.paused);
+        assert!(find_job(&scheduler, "manual_hold").await.paused);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_now_keeps_schedule() {
+        let temp_dir = tempdir().unwrap();
+        let scheduler = Scheduler::new(temp_dir.path().join("schedules.json"))
+            .await
+            .unwrap();
+
+        let mut job = every_second_job("report");
+        job.cron = "0 0 0 1 1 *".to_string();
+        scheduler.add_scheduled_job(job, false).await.unwrap();
+        scheduler.pause_schedule("report").await.unwrap();
+
+        scheduler.trigger_now("report").await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        let job = find_job(&scheduler, "report").await;
+        assert!(job.last_run.is_some(), "Triggered job should have run");
+        assert!(!job.currently_running);
+        assert!(job.paused);
+        assert_eq!(job.cron, "0 0 0 1 1 *");
+    }
 }
@@ -0,0 +1,806 @@
+//! 基于 Cron 表达式的调度器
+//!
+//! `CronScheduler` 自行解析 cron 表达式并计算下次触发时间，不依赖 tokio-cron-scheduler。
+//! 触发时间按调度器时区的本地时间计算，夏令时切换时每个计划时刻只触发一次：
+//! - 不存在的本地时间（春季拨快）在切换后的第一个有效分钟触发
+//! - 重复的本地时间（秋季拨回）只在第一次出现时触发
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use cron::{Schedule, TimeUnitSpec};
+use tokio::sync::{Mutex, Notify};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    get_default_scheduled_recipes_dir, run_job, schedule_sessions, ScheduledJob, SchedulerError,
+};
+use crate::scheduler_trait::SchedulerTrait;
+use crate::session::Session;
+
+/// 查找下次触发时间的最大天数（覆盖 2 月 29 日这类八年才出现一次的日期）
+const MAX_SEARCH_DAYS: i64 = 366 * 8;
+
+/// 夏令时跳过的时间段最长按一天处理
+const MAX_GAP_MINUTES: i64 = 24 * 60;
+
+/// 解析后的 cron 表达式
+///
+/// 支持 5 字段（分 时 日 月 周）、6 字段（带秒）、7 字段（带年）
+/// 以及 `@daily`、`@hourly` 等宏。
+#[derive(Debug, Clone)]
+pub struct CronExpression {
+    source: String,
+    schedule: Schedule,
+}
+
+impl CronExpression {
+    /// 解析 cron 表达式
+    pub fn parse(expr: &str) -> Result<Self, SchedulerError> {
+        let expr = expr.trim();
+        let normalized = if expr.starts_with('@') {
+            expr.to_string()
+        } else {
+            match expr.split_whitespace().count() {
+                5 => format!("0 {}", expr),
+                6 | 7 => expr.to_string(),
+                n => {
+                    return Err(SchedulerError::CronParseError(format!(
+                        "Invalid cron expression '{}': expected 5, 6 or 7 fields, got {}",
+                        expr, n
+                    )))
+                }
+            }
+        };
+
+        let schedule = Schedule::from_str(&normalized).map_err(|e| {
+            SchedulerError::CronParseError(format!("Invalid cron expression '{}': {}", expr, e))
+        })?;
+        Ok(Self {
+            source: expr.to_string(),
+            schedule,
+        })
+    }
+
+    /// 原始表达式
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// 计算 `after` 之后（不含）的下次触发时间
+    ///
+    /// 结果只取决于 `after` 和时区，不依赖当前时间。
+    pub fn next_after<Z: TimeZone>(&self, after: &DateTime<Z>) -> Option<DateTime<Z>> {
+        let tz = after.timezone();
+        let start = after.naive_local().with_nanosecond(0)? + Duration::seconds(1);
+
+        let mut date = start.date();
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                for hour in self.schedule.hours().iter() {
+                    for minute in self.schedule.minutes().iter() {
+                        for second in self.schedule.seconds().iter() {
+                            let Some(local) = date.and_hms_opt(hour, minute, second) else {
+                                continue;
+                            };
+                            if local < start {
+                                continue;
+                            }
+                            if let Some(instant) = resolve_local(&tz, local, after) {
+                                return Some(instant);
+                            }
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: chrono::NaiveDate) -> bool {
+        u32::try_from(date.year()).is_ok_and(|year| self.schedule.years().includes(year))
+            && self.schedule.months().includes(date.month())
+            && self.schedule.days_of_month().includes(date.day())
+            && self
+                .schedule
+                .days_of_week()
+                .includes(date.weekday().number_from_sunday())
+    }
+}
+
+/// 把本地时间映射到唯一的时刻，已经早于 `after` 的返回 None
+fn resolve_local<Z: TimeZone>(
+    tz: &Z,
+    local: NaiveDateTime,
+    after: &DateTime<Z>,
+) -> Option<DateTime<Z>> {
+    let instant = match tz.from_local_datetime(&local) {
+        LocalResult::Single(instant) => instant,
+        // 秋季拨回：只在第一次出现时触发
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        // 春季拨快：顺延到跳过时间段后的第一个有效分钟
+        LocalResult::None => {
+            let mut probe = local.with_second(0)?;
+            (0..MAX_GAP_MINUTES).find_map(|_| {
+                probe += Duration::minutes(1);
+                tz.from_local_datetime(&probe).earliest()
+            })?
+        }
+    };
+    (instant > *after).then_some(instant)
+}
+
+struct CronEntry {
+    job: ScheduledJob,
+    expression: CronExpression,
+    next_fire: Option<DateTime<Utc>>,
+}
+
+struct CronSchedulerInner {
+    jobs: Mutex<HashMap<String, CronEntry>>,
+    storage_path: PathBuf,
+    running_tasks: Mutex<HashMap<String, CancellationToken>>,
+    timezone: Tz,
+    wake: Notify,
+}
+
+impl CronSchedulerInner {
+    fn next_fire(
+        &self,
+        expression: &CronExpression,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        expression
+            .next_after(&after.with_timezone(&self.timezone))
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    async fn persist(&self) -> Result<(), SchedulerError> {
+        let list: Vec<ScheduledJob> = {
+            let jobs = self.jobs.lock().await;
+            jobs.values().map(|e| e.job.clone()).collect()
+        };
+        if let Some(parent) = self.storage_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_string_pretty(&list)?;
+        fs::write(&self.storage_path, data)?;
+        Ok(())
+    }
+
+    async fn earliest_fire(&self) -> Option<DateTime<Utc>> {
+        let jobs = self.jobs.lock().await;
+        jobs.values().filter_map(|e| e.next_fire).min()
+    }
+
+    /// 触发到期的任务并计算它们的下次触发时间
+    ///
+    /// 下次触发时间从当前时间算起，错过的触发（例如系统休眠）不会补跑。
+    async fn fire_due(self: &Arc<Self>, now: DateTime<Utc>) {
+        let due: Vec<ScheduledJob> = {
+            let mut jobs = self.jobs.lock().await;
+            let mut due = Vec::new();
+            for entry in jobs.values_mut() {
+                if !entry.next_fire.is_some_and(|t| t <= now) {
+                    continue;
+                }
+                entry.next_fire = self.next_fire(&entry.expression, now);
+                if entry.job.paused || entry.job.currently_running {
+                    continue;
+                }
+                entry.job.last_run = Some(now);
+                entry.job.currently_running = true;
+                entry.job.process_start_time = Some(now);
+                due.push(entry.job.clone());
+            }
+            due
+        };
+        if due.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.persist().await {
+            tracing::error!("Failed to persist job status: {}", e);
+        }
+        for job in due {
+            tracing::info!("Cron task triggered for job '{}'", job.id);
+            let inner = Arc::clone(self);
+            tokio::spawn(async move {
+                let job_id = job.id.clone();
+                match inner.execute(job).await {
+                    Ok(_) => tracing::info!("Job '{}' completed", job_id),
+                    Err(e) => {
+                        tracing::error!("Job '{}' failed: {}", job_id, e);
+                        crate::posthog::emit_error("scheduler_job_failed", &e.to_string());
+                    }
+                }
+            });
+        }
+    }
+
+    /// 执行已标记为运行中的任务，结束后清除运行状态
+    async fn execute(self: &Arc<Self>, job: ScheduledJob) -> anyhow::Result<String> {
+        let job_id = job.id.clone();
+        let cancel_token = CancellationToken::new();
+        self.running_tasks
+            .lock()
+            .await
+            .insert(job_id.clone(), cancel_token.clone());
+
+        let inner = Arc::clone(self);
+        let session_job_id = job_id.clone();
+        let result = run_job(job, cancel_token, |session_id| async move {
+            let mut jobs = inner.jobs.lock().await;
+            if let Some(entry) = jobs.get_mut(&session_job_id) {
+                entry.job.current_session_id = Some(session_id);
+            }
+        })
+        .await;
+
+        self.running_tasks.lock().await.remove(&job_id);
+        {
+            let mut jobs = self.jobs.lock().await;
+            if let Some(entry) = jobs.get_mut(&job_id) {
+                entry.job.currently_running = false;
+                entry.job.current_session_id = None;
+                entry.job.process_start_time = None;
+            }
+        }
+        if let Err(e) = self.persist().await {
+            tracing::error!("Failed to persist job completion: {}", e);
+        }
+        result
+    }
+
+    async fn run_loop(self: Arc<Self>, shutdown: CancellationToken) {
+        loop {
+            let next = self.earliest_fire().await;
+            let delay = next
+                .map(|t| (t - Utc::now()).to_std().unwrap_or_default())
+                .unwrap_or_default();
+
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = self.wake.notified() => continue,
+                _ = tokio::time::sleep(delay), if next.is_some() => {}
+            }
+            self.fire_due(Utc::now()).await;
+        }
+    }
+}
+
+/// 基于 cron 表达式的调度器
+///
+/// 与 [`super::Scheduler`] 使用相同的任务存储格式。所有任务共用一个定时循环，
+/// 每次只等待最近的触发时间；任务变更时唤醒循环重新计算。
+pub struct CronScheduler {
+    inner: Arc<CronSchedulerInner>,
+    shutdown: CancellationToken,
+}
+
+impl CronScheduler {
+    /// 创建调度器，加载已保存的任务并启动定时循环
+    pub async fn new(storage_path: PathBuf, timezone: Tz) -> Result<Arc<Self>, SchedulerError> {
+        let inner = Arc::new(CronSchedulerInner {
+            jobs: Mutex::new(HashMap::new()),
+            storage_path,
+            running_tasks: Mutex::new(HashMap::new()),
+            timezone,
+            wake: Notify::new(),
+        });
+        Self::load_jobs_from_storage(&inner).await;
+
+        let shutdown = CancellationToken::new();
+        tokio::spawn(Arc::clone(&inner).run_loop(shutdown.clone()));
+        Ok(Arc::new(Self { inner, shutdown }))
+    }
+
+    /// 调度器使用的时区
+    pub fn timezone(&self) -> Tz {
+        self.inner.timezone
+    }
+
+    /// 任务的下次触发时间
+    pub async fn next_fire_time(
+        &self,
+        sched_id: &str,
+    ) -> Result<Option<DateTime<Utc>>, SchedulerError> {
+        let jobs = self.inner.jobs.lock().await;
+        jobs.get(sched_id)
+            .map(|e| e.next_fire)
+            .ok_or_else(|| SchedulerError::JobNotFound(sched_id.to_string()))
+    }
+
+    async fn load_jobs_from_storage(inner: &Arc<CronSchedulerInner>) {
+        if !inner.storage_path.exists() {
+            return;
+        }
+        let data = match fs::read_to_string(&inner.storage_path) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to read schedules.json: {}. Starting with empty schedule list.",
+                    e
+                );
+                return;
+            }
+        };
+        if data.trim().is_empty() {
+            return;
+        }
+
+        let list: Vec<ScheduledJob> = match serde_json::from_str(&data) {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to parse schedules.json: {}. Starting with empty schedule list.",
+                    e
+                );
+                return;
+            }
+        };
+
+        let now = Utc::now();
+        let mut jobs = inner.jobs.lock().await;
+        for mut job in list {
+            if !Path::new(&job.source).exists() {
+                tracing::warn!(
+                    "Recipe file {} not found, skipping job '{}'",
+                    job.source,
+                    job.id
+                );
+                continue;
+            }
+            let expression = match CronExpression::parse(&job.cron) {
+                Ok(expression) => expression,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to parse cron for job '{}': {}. Skipping.",
+                        job.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            // 上次进程退出时仍在运行的任务已经中断
+            job.currently_running = false;
+            job.current_session_id = None;
+            job.process_start_time = None;
+            let next_fire = inner.next_fire(&expression, now);
+            jobs.insert(
+                job.id.clone(),
+                CronEntry {
+                    job,
+                    expression,
+                    next_fire,
+                },
+            );
+        }
+    }
+
+    async fn generate_unique_job_id(&self, path: &Path) -> String {
+        let base_id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unnamed")
+            .to_string();
+
+        let jobs = self.inner.jobs.lock().await;
+        let mut id = base_id.clone();
+        let mut counter = 1;
+        while jobs.contains_key(&id) {
+            id = format!("{}_{}", base_id, counter);
+            counter += 1;
+        }
+        id
+    }
+}
+
+impl Drop for CronScheduler {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
+}
+
+#[async_trait]
+impl SchedulerTrait for CronScheduler {
+    async fn add_scheduled_job(
+        &self,
+        job: ScheduledJob,
+        copy_recipe: bool,
+    ) -> Result<(), SchedulerError> {
+        if self.inner.jobs.lock().await.contains_key(&job.id) {
+            return Err(SchedulerError::JobIdExists(job.id.clone()));
+        }
+        let expression = CronExpression::parse(&job.cron)?;
+
+        let mut stored_job = job;
+        if copy_recipe {
+            let original_recipe_path = Path::new(&stored_job.source);
+            if !original_recipe_path.is_file() {
+                return Err(SchedulerError::RecipeLoadError(format!(
+                    "Recipe file not found: {}",
+                    stored_job.source
+                )));
+            }
+
+            let extension = original_recipe_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("yaml");
+            let destination = get_default_scheduled_recipes_dir()?
+                .join(format!("{}.{}", stored_job.id, extension));
+            fs::copy(original_recipe_path, &destination)?;
+            stored_job.source = destination.to_string_lossy().into_owned();
+            stored_job.current_session_id = None;
+            stored_job.process_start_time = None;
+        }
+
+        {
+            let mut jobs = self.inner.jobs.lock().await;
+            if jobs.contains_key(&stored_job.id) {
+                return Err(SchedulerError::JobIdExists(stored_job.id.clone()));
+            }
+            let next_fire = self.inner.next_fire(&expression, Utc::now());
+            jobs.insert(
+                stored_job.id.clone(),
+                CronEntry {
+                    job: stored_job,
+                    expression,
+                    next_fire,
+                },
+            );
+        }
+        self.inner.wake.notify_one();
+        self.inner.persist().await
+    }
+
+    async fn schedule_recipe(
+        &self,
+        recipe_path: PathBuf,
+        cron_schedule: Option<String>,
+    ) -> Result<(), SchedulerError> {
+        let recipe_path_str = recipe_path.to_string_lossy().to_string();
+        let existing_job_id = {
+            let jobs = self.inner.jobs.lock().await;
+            jobs.values()
+                .find(|e| e.job.source == recipe_path_str)
+                .map(|e| e.job.id.clone())
+        };
+
+        match (cron_schedule, existing_job_id) {
+            (Some(cron), Some(job_id)) => self.update_schedule(&job_id, cron).await,
+            (Some(cron), None) => {
+                let job = ScheduledJob {
+                    id: self.generate_unique_job_id(&recipe_path).await,
+                    source: recipe_path_str,
+                    cron,
+                    last_run: None,
+                    currently_running: false,
+                    paused: false,
+                    paused_by_pause_all: false,
+                    current_session_id: None,
+                    process_start_time: None,
+                };
+                self.add_scheduled_job(job, false).await
+            }
+            (None, Some(job_id)) => self.remove_scheduled_job(&job_id, false).await,
+            (None, None) => Ok(()),
+        }
+    }
+
+    async fn list_scheduled_jobs(&self) -> Vec<ScheduledJob> {
+        let jobs = self.inner.jobs.lock().await;
+        jobs.values().map(|e| e.job.clone()).collect()
+    }
+
+    async fn remove_scheduled_job(
+        &self,
+        id: &str,
+        remove_recipe: bool,
+    ) -> Result<(), SchedulerError> {
+        let removed = self.inner.jobs.lock().await.remove(id);
+        let Some(entry) = removed else {
+            return Err(SchedulerError::JobNotFound(id.to_string()));
+        };
+        self.inner.wake.notify_one();
+
+        if remove_recipe {
+            let path = Path::new(&entry.job.source);
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        self.inner.persist().await
+    }
+
+    async fn pause_schedule(&self, id: &str) -> Result<(), SchedulerError> {
+        {
+            let mut jobs = self.inner.jobs.lock().await;
+            match jobs.get_mut(id) {
+                Some(entry) if entry.job.currently_running => {
+                    return Err(SchedulerError::AnyhowError(anyhow!(
+                        "Cannot pause running schedule '{}'",
+                        id
+                    )));
+                }
+                Some(entry) => entry.job.paused = true,
+                None => return Err(SchedulerError::JobNotFound(id.to_string())),
+            }
+        }
+        self.inner.persist().await
+    }
+
+    async fn unpause_schedule(&self, id: &str) -> Result<(), SchedulerError> {
+        {
+            let mut jobs = self.inner.jobs.lock().await;
+            match jobs.get_mut(id) {
+                Some(entry) => entry.job.paused = false,
+                None => return Err(SchedulerError::JobNotFound(id.to_string())),
+            }
+        }
+        self.inner.persist().await
+    }
+
+    async fn run_now(&self, id: &str) -> Result<String, SchedulerError> {
+        let job = {
+            let mut jobs = self.inner.jobs.lock().await;
+            match jobs.get_mut(id) {
+                Some(entry) if entry.job.currently_running => {
+                    return Err(SchedulerError::AnyhowError(anyhow!(
+                        "Job '{}' is already running",
+                        id
+                    )));
+                }
+                Some(entry) => {
+                    let now = Utc::now();
+                    entry.job.last_run = Some(now);
+                    entry.job.currently_running = true;
+                    entry.job.process_start_time = Some(now);
+                    entry.job.clone()
+                }
+                None => return Err(SchedulerError::JobNotFound(id.to_string())),
+            }
+        };
+        self.inner.persist().await?;
+
+        self.inner
+            .execute(job)
+            .await
+            .map_err(|e| SchedulerError::AnyhowError(anyhow!("Job '{}' failed: {}", id, e)))
+    }
+
+    async fn sessions(
+        &self,
+        sched_id: &str,
+        limit: usize,
+    ) -> Result<Vec<(String, Session)>, SchedulerError> {
+        schedule_sessions(sched_id, limit).await
+    }
+
+    async fn update_schedule(
+        &self,
+        sched_id: &str,
+        new_cron: String,
+    ) -> Result<(), SchedulerError> {
+        {
+            let mut jobs = self.inner.jobs.lock().await;
+            let Some(entry) = jobs.get_mut(sched_id) else {
+                return Err(SchedulerError::JobNotFound(sched_id.to_string()));
+            };
+            if entry.job.currently_running {
+                return Err(SchedulerError::AnyhowError(anyhow!(
+                    "Cannot update running schedule '{}'",
+                    sched_id
+                )));
+            }
+            if new_cron == entry.job.cron {
+                return Ok(());
+            }
+            let expression = CronExpression::parse(&new_cron)?;
+            entry.next_fire = self.inner.next_fire(&expression, Utc::now());
+            entry.expression = expression;
+            entry.job.cron = new_cron;
+        }
+        self.inner.wake.notify_one();
+        self.inner.persist().await
+    }
+
+    async fn kill_running_job(&self, sched_id: &str) -> Result<(), SchedulerError> {
+        {
+            let jobs = self.inner.jobs.lock().await;
+            match jobs.get(sched_id) {
+                Some(entry) if !entry.job.currently_running => {
+                    return Err(SchedulerError::AnyhowError(anyhow!(
+                        "Schedule '{}' is not running",
+                        sched_id
+                    )));
+                }
+                None => return Err(SchedulerError::JobNotFound(sched_id.to_string())),
+                _ => {}
+            }
+        }
+
+        if let Some(token) = self.inner.running_tasks.lock().await.get(sched_id) {
+            token.cancel();
+        }
+        Ok(())
+    }
+
+    async fn get_running_job_info(
+        &self,
+        sched_id: &str,
+    ) -> Result<Option<(String, DateTime<Utc>)>, SchedulerError> {
+        let jobs = self.inner.jobs.lock().await;
+        match jobs.get(sched_id) {
+            Some(entry) if entry.job.currently_running => {
+                match (&entry.job.current_session_id, &entry.job.process_start_time) {
+                    (Some(sid), Some(start)) => Ok(Some((sid.clone(), *start))),
+                    _ => Ok(None),
+                }
+            }
+            Some(_) => Ok(None),
+            None => Err(SchedulerError::JobNotFound(sched_id.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+    use chrono_tz::Asia::Shanghai;
+    use tempfile::tempdir;
+
+    fn at(tz: Tz, y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Tz> {
+        tz.with_ymd_and_hms(y, mo, d, h, mi, 0).earliest().unwrap()
+    }
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    /// 从 `start` 开始依次计算 `count` 次触发时间
+    fn fires(expr: &str, start: DateTime<Tz>, count: usize) -> Vec<DateTime<Utc>> {
+        let expression = CronExpression::parse(expr).unwrap();
+        let mut current = start;
+        (0..count)
+            .map(|_| {
+                current = expression.next_after(&current).unwrap();
+                current.with_timezone(&Utc)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_expressions() {
+        for expr in [
+            "0 2 * * *",
+            "*/15 * * * * *",
+            "0 0 9 * * Mon-Fri 2030",
+            "@daily",
+        ] {
+            assert!(CronExpression::parse(expr).is_ok(), "{}", expr);
+        }
+        for expr in ["", "* * *", "61 * * * *", "@fortnightly"] {
+            assert!(
+                matches!(
+                    CronExpression::parse(expr),
+                    Err(SchedulerError::CronParseError(_))
+                ),
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_fire_times() {
+        // @daily 在上海时区的午夜触发
+        assert_eq!(
+            fires("@daily", at(Shanghai, 2025, 6, 1, 12, 0), 2),
+            vec![utc(2025, 6, 1, 16, 0), utc(2025, 6, 2, 16, 0)]
+        );
+        assert_eq!(
+            fires("*/15 * * * *", at(Shanghai, 2025, 6, 1, 12, 7), 3),
+            vec![
+                utc(2025, 6, 1, 4, 15),
+                utc(2025, 6, 1, 4, 30),
+                utc(2025, 6, 1, 4, 45)
+            ]
+        );
+        // 2025-06-06 是周五，下一次工作日触发在周一
+        assert_eq!(
+            fires("0 0 9 * * Mon-Fri", at(New_York, 2025, 6, 6, 10, 0), 1),
+            vec![utc(2025, 6, 9, 13, 0)]
+        );
+        // 结果只取决于起始时间
+        let start = at(New_York, 2025, 1, 1, 0, 0);
+        assert_eq!(fires("0 2 * * *", start, 5), fires("0 2 * * *", start, 5));
+    }
+
+    #[test]
+    fn test_daily_across_spring_forward() {
+        // 2025-03-09 02:00 纽约拨快到 03:00，当天 02:00 不存在
+        let fired = fires("0 2 * * *", at(New_York, 2025, 3, 8, 0, 0), 3);
+        assert_eq!(
+            fired,
+            vec![
+                utc(2025, 3, 8, 7, 0),  // 02:00 EST
+                utc(2025, 3, 9, 7, 0),  // 顺延到 03:00 EDT
+                utc(2025, 3, 10, 6, 0), // 02:00 EDT
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fall_back_fires_once() {
+        // 2025-11-02 02:00 纽约拨回到 01:00，01:00-01:59 出现两次
+        let fired = fires("0 2 * * *", at(New_York, 2025, 11, 1, 0, 0), 3);
+        assert_eq!(
+            fired,
+            vec![
+                utc(2025, 11, 1, 6, 0), // 02:00 EDT
+                utc(2025, 11, 2, 7, 0), // 02:00 EST
+                utc(2025, 11, 3, 7, 0),
+            ]
+        );
+
+        let fired = fires("30 1 * * *", at(New_York, 2025, 11, 1, 12, 0), 2);
+        assert_eq!(
+            fired,
+            vec![utc(2025, 11, 2, 5, 30), utc(2025, 11, 3, 6, 30)]
+        );
+
+        // 重复的一小时内不会再次触发
+        let fired = fires("0 */30 * * * *", at(New_York, 2025, 11, 2, 1, 0), 3);
+        assert_eq!(
+            fired,
+            vec![
+                utc(2025, 11, 2, 5, 30), // 01:30 EDT
+                utc(2025, 11, 2, 7, 0),  // 02:00 EST
+                utc(2025, 11, 2, 7, 30),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_job_runs_on_schedule() {
+        let temp_dir = tempdir().unwrap();
+        let scheduler = CronScheduler::new(temp_dir.path().join("schedules.json"), Shanghai)
+            .await
+            .unwrap();
+
+        let job = ScheduledJob {
+            id: "every_second".to_string(),
+            source: String::new(),
+            cron: "* * * * * *".to_string(),
+            last_run: None,
+            currently_running: false,
+            paused: false,
+            paused_by_pause_all: false,
+            current_session_id: None,
+            process_start_time: None,
+        };
+        scheduler.add_scheduled_job(job, false).await.unwrap();
+        assert!(scheduler
+            .next_fire_time("every_second")
+            .await
+            .unwrap()
+            .is_some());
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+
+        let jobs = scheduler.list_scheduled_jobs().await;
+        assert!(jobs[0].last_run.is_some(), "Job should have run");
+        assert!(temp_dir.path().join("schedules.json").exists());
+    }
+}
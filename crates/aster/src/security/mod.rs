@@ -1,8 +1,14 @@
 pub mod classification_client;
+pub mod path_guard;
 pub mod patterns;
+pub mod redaction;
 pub mod scanner;
+pub mod secrets;
 pub mod security_inspector;
 
+pub use path_guard::{sanitize_path, PathTraversalError};
+pub use redaction::ArgumentRedactor;
+
 use crate::config::Config;
 use crate::conversation::message::{Message, ToolRequest};
 use crate::permission::permission_judge::PermissionCheckResult;
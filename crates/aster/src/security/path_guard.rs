@@ -0,0 +1,192 @@
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PathTraversalError {
+    #[error("Path '{}' escapes '{}' via '..'", .requested.display(), .base.display())]
+    ParentTraversal { base: PathBuf, requested: PathBuf },
+
+    #[error("Absolute path '{}' is outside '{}'", .requested.display(), .base.display())]
+    AbsoluteOutsideBase { base: PathBuf, requested: PathBuf },
+
+    #[error(
+        "Path '{}' resolves through a symlink to '{}', outside '{}'",
+        .requested.display(),
+        .resolved.display(),
+        .base.display()
+    )]
+    SymlinkEscape {
+        base: PathBuf,
+        requested: PathBuf,
+        resolved: PathBuf,
+    },
+
+    #[error("Invalid base directory '{}': {source}", .base.display())]
+    InvalidBase {
+        base: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Resolves `requested` against `base`, rejecting anything that escapes `base`.
+///
+/// Relative paths are joined onto `base`; absolute paths are accepted only if
+/// they already point inside it. `..` components are resolved lexically and
+/// symlinks along the existing part of the path are followed, so a link inside
+/// `base` that points elsewhere is rejected too. The path itself does not need
+/// to exist, which allows validating the target of a write.
+///
+/// Returns the canonical path on success.
+pub fn sanitize_path(base: &Path, requested: &Path) -> Result<PathBuf, PathTraversalError> {
+    let canonical_base = base
+        .canonicalize()
+        .map_err(|source| PathTraversalError::InvalidBase {
+            base: base.to_path_buf(),
+            source,
+        })?;
+
+    let joined = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        base.join(requested)
+    };
+    let Some(normalized) = normalize(&joined) else {
+        return Err(PathTraversalError::ParentTraversal {
+            base: base.to_path_buf(),
+            requested: requested.to_path_buf(),
+        });
+    };
+
+    // `base` may itself be reached through a symlink (e.g. /tmp on macOS),
+    // so accept either spelling for the lexical check
+    let lexically_inside = normalize(base).is_some_and(|b| normalized.starts_with(b))
+        || normalized.starts_with(&canonical_base);
+    if !lexically_inside {
+        return Err(if requested.is_absolute() {
+            PathTraversalError::AbsoluteOutsideBase {
+                base: base.to_path_buf(),
+                requested: requested.to_path_buf(),
+            }
+        } else {
+            PathTraversalError::ParentTraversal {
+                base: base.to_path_buf(),
+                requested: requested.to_path_buf(),
+            }
+        });
+    }
+
+    let resolved = resolve_existing_prefix(&normalized);
+    if !resolved.starts_with(&canonical_base) {
+        return Err(PathTraversalError::SymlinkEscape {
+            base: base.to_path_buf(),
+            requested: requested.to_path_buf(),
+            resolved,
+        });
+    }
+
+    Ok(resolved)
+}
+
+/// Removes `.` and `..` components without touching the filesystem.
+///
+/// Returns `None` if `..` would climb above the root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+/// Canonicalizes the longest existing ancestor of `path` and re-appends the
+/// components that do not exist yet.
+fn resolve_existing_prefix(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |acc: PathBuf, name| acc.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_relative_path_inside_base() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+
+        let resolved = sanitize_path(dir.path(), Path::new("src/./new/../main.rs")).unwrap();
+        assert_eq!(
+            resolved,
+            dir.path().canonicalize().unwrap().join("src/main.rs")
+        );
+    }
+
+    #[test]
+    fn test_parent_traversal_rejected() {
+        let dir = TempDir::new().unwrap();
+
+        let err = sanitize_path(dir.path(), Path::new("../outside.txt")).unwrap_err();
+        assert!(matches!(err, PathTraversalError::ParentTraversal { .. }));
+
+        let err = sanitize_path(dir.path(), Path::new("a/../../../etc/passwd")).unwrap_err();
+        assert!(matches!(err, PathTraversalError::ParentTraversal { .. }));
+    }
+
+    #[test]
+    fn test_absolute_path_outside_base_rejected() {
+        let dir = TempDir::new().unwrap();
+        let other = TempDir::new().unwrap();
+
+        let err = sanitize_path(dir.path(), &other.path().join("file.txt")).unwrap_err();
+        assert!(matches!(
+            err,
+            PathTraversalError::AbsoluteOutsideBase { .. }
+        ));
+
+        // Absolute paths inside the base are fine
+        let inside = dir.path().join("file.txt");
+        assert!(sanitize_path(dir.path(), &inside).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_escape_rejected() {
+        let dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+
+        let err = sanitize_path(dir.path(), Path::new("link/secret.txt")).unwrap_err();
+        assert!(matches!(err, PathTraversalError::SymlinkEscape { .. }));
+
+        // Also caught for files that do not exist yet
+        let err = sanitize_path(dir.path(), Path::new("link/new.txt")).unwrap_err();
+        assert!(matches!(err, PathTraversalError::SymlinkEscape { .. }));
+    }
+}
@@ -0,0 +1,197 @@
+use regex::Regex;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Rules applying to every tool
+const ALL_TOOLS: &str = "*";
+
+/// Argument names that are redacted for every tool by default
+const DEFAULT_SENSITIVE_NAMES: &str =
+    r"(?i)^(.*_)?(token|secret|password|passwd|api_?key|authorization|credentials?)$";
+
+#[derive(Debug, Clone)]
+enum FieldMatcher {
+    Name(String),
+    Pattern(Regex),
+}
+
+impl FieldMatcher {
+    fn matches(&self, field: &str) -> bool {
+        match self {
+            FieldMatcher::Name(name) => name == field,
+            FieldMatcher::Pattern(regex) => regex.is_match(field),
+        }
+    }
+}
+
+/// Replaces sensitive tool arguments before they reach audit logs.
+///
+/// Rules are registered per tool name, or for all tools with `"*"`. A
+/// redacted value is replaced by its length and a SHA-256 prefix, which is
+/// enough to correlate entries without exposing the content.
+#[derive(Debug, Clone)]
+pub struct ArgumentRedactor {
+    rules: HashMap<String, Vec<FieldMatcher>>,
+}
+
+impl ArgumentRedactor {
+    /// A redactor with no rules.
+    pub fn empty() -> Self {
+        Self {
+            rules: HashMap::new(),
+        }
+    }
+
+    /// Redacts the argument `field` of `tool` (`"*"` for every tool).
+    pub fn redact_field(mut self, tool: impl Into<String>, field: impl Into<String>) -> Self {
+        self.rules
+            .entry(tool.into())
+            .or_default()
+            .push(FieldMatcher::Name(field.into()));
+        self
+    }
+
+    /// Redacts every argument of `tool` whose name matches `pattern`.
+    pub fn redact_pattern(mut self, tool: impl Into<String>, pattern: Regex) -> Self {
+        self.rules
+            .entry(tool.into())
+            .or_default()
+            .push(FieldMatcher::Pattern(pattern));
+        self
+    }
+
+    pub fn should_redact(&self, tool: &str, field: &str) -> bool {
+        [tool, ALL_TOOLS]
+            .iter()
+            .filter_map(|key| self.rules.get(*key))
+            .flatten()
+            .any(|matcher| matcher.matches(field))
+    }
+
+    /// Returns a copy of `params` with the matching arguments redacted.
+    ///
+    /// Nested objects and arrays are walked too, so a field such as
+    /// `edits[].new_str` is matched by its own name.
+    pub fn redact(&self, tool: &str, params: &HashMap<String, Value>) -> HashMap<String, Value> {
+        params
+            .iter()
+            .map(|(field, value)| (field.clone(), self.redact_field_value(tool, field, value)))
+            .collect()
+    }
+
+    fn redact_field_value(&self, tool: &str, field: &str, value: &Value) -> Value {
+        if self.should_redact(tool, field) {
+            redacted_value(value)
+        } else {
+            self.redact_nested(tool, value)
+        }
+    }
+
+    fn redact_nested(&self, tool: &str, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(field, value)| {
+                        (field.clone(), self.redact_field_value(tool, field, value))
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.redact_nested(tool, item))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+impl Default for ArgumentRedactor {
+    /// Redacts file contents written by the file tools and credential-like
+    /// arguments of any tool.
+    fn default() -> Self {
+        Self::empty()
+            .redact_field("write", "content")
+            .redact_field("edit", "old_str")
+            .redact_field("edit", "new_str")
+            .redact_field("edit", "patch")
+            .redact_pattern(
+                ALL_TOOLS,
+                Regex::new(DEFAULT_SENSITIVE_NAMES).expect("valid sensitive name pattern"),
+            )
+    }
+}
+
+fn redacted_value(value: &Value) -> Value {
+    let raw = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let digest = Sha256::digest(raw.as_bytes());
+    json!({
+        "redacted": true,
+        "length": raw.len(),
+        "sha256": hex::encode(&digest[..8]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_are_scoped_to_tool() {
+        let redactor = ArgumentRedactor::default();
+
+        assert!(redactor.should_redact("write", "content"));
+        assert!(!redactor.should_redact("read", "content"));
+        assert!(redactor.should_redact("web_fetch", "api_key"));
+        assert!(redactor.should_redact("bash", "GITHUB_TOKEN"));
+        assert!(!redactor.should_redact("bash", "command"));
+    }
+
+    #[test]
+    fn test_redacted_value_keeps_length_and_hash() {
+        let redactor = ArgumentRedactor::empty().redact_field("write", "content");
+        let params = HashMap::from([
+            ("path".to_string(), json!("/tmp/a.txt")),
+            ("content".to_string(), json!("hello")),
+        ]);
+
+        let redacted = redactor.redact("write", &params);
+        assert_eq!(redacted["path"], json!("/tmp/a.txt"));
+        assert_eq!(redacted["content"]["length"], json!(5));
+        assert_eq!(redacted["content"]["sha256"], json!("2cf24dba5fb0a30e"));
+    }
+
+    #[test]
+    fn test_nested_edits_and_patch_are_redacted() {
+        let redactor = ArgumentRedactor::default();
+        let params = HashMap::from([
+            ("path".to_string(), json!("src/main.rs")),
+            (
+                "edits".to_string(),
+                json!([{"old_str": "let key = 1;", "new_str": "let key = 2;"}]),
+            ),
+            ("patch".to_string(), json!("@@ -1 +1 @@\n-a\n+b\n")),
+        ]);
+
+        let redacted = redactor.redact("edit", &params);
+        assert_eq!(redacted["path"], json!("src/main.rs"));
+        assert_eq!(redacted["edits"][0]["old_str"]["redacted"], json!(true));
+        assert_eq!(redacted["edits"][0]["new_str"]["redacted"], json!(true));
+        assert_eq!(redacted["patch"]["redacted"], json!(true));
+
+        let nested_secret = HashMap::from([(
+            "options".to_string(),
+            json!({"headers": {"authorization": "Bearer abc"}}),
+        )]);
+        let redacted = redactor.redact("web_fetch", &nested_secret);
+        assert_eq!(
+            redacted["options"]["headers"]["authorization"]["redacted"],
+            json!(true)
+        );
+    }
+}
@@ -2,15 +2,20 @@
 //!
 //! Provides multi-format export functionality for sessions.
 
-use crate::conversation::message::MessageContent;
+use crate::conversation::message::{Message, MessageContent};
+use crate::conversation::Conversation;
 use crate::session::{Session, SessionManager};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rmcp::model::Role;
 
 /// Export format options
 #[derive(Debug, Clone, Copy, Default)]
 pub enum ExportFormat {
     #[default]
     Json,
+    /// Session metadata on the first line, then one message per line
+    Jsonl,
     Markdown,
     Html,
 }
@@ -26,6 +31,12 @@ pub struct ExportOptions {
     pub include_metadata: bool,
     /// Pretty print JSON output
     pub pretty_print: bool,
+    /// Only export messages with these roles (all roles when empty)
+    pub roles: Vec<Role>,
+    /// Only export messages created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only export messages created before this time
+    pub until: Option<DateTime<Utc>>,
 }
 
 impl ExportOptions {
@@ -35,6 +46,9 @@ impl ExportOptions {
             include_messages: true,
             include_metadata: true,
             pretty_print: true,
+            roles: Vec::new(),
+            since: None,
+            until: None,
         }
     }
 
@@ -52,17 +66,78 @@ impl ExportOptions {
         self.include_metadata = include;
         self
     }
+
+    pub fn roles(mut self, roles: Vec<Role>) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    pub fn time_range(
+        mut self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.since = since;
+        self.until = until;
+        self
+    }
+
+    fn has_message_filters(&self) -> bool {
+        !self.roles.is_empty() || self.since.is_some() || self.until.is_some()
+    }
+
+    fn matches(&self, message: &Message) -> bool {
+        (self.roles.is_empty() || self.roles.contains(&message.role))
+            && self
+                .since
+                .is_none_or(|since| message.created >= since.timestamp())
+            && self
+                .until
+                .is_none_or(|until| message.created < until.timestamp())
+    }
 }
 
 /// Export a session to the specified format
 pub async fn export_session(session_id: &str, options: ExportOptions) -> Result<String> {
     let session = SessionManager::get_session(session_id, options.include_messages).await?;
+    export_session_data(&session, &options)
+}
+
+/// Export an already loaded session to the specified format
+///
+/// Role and time range filters are applied before formatting. A filter that
+/// matches nothing produces an export with an empty conversation.
+pub fn export_session_data(session: &Session, options: &ExportOptions) -> Result<String> {
+    let filtered;
+    let session = if options.has_message_filters() {
+        filtered = filter_messages(session, options);
+        &filtered
+    } else {
+        session
+    };
 
     match options.format {
-        ExportFormat::Json => export_to_json(&session, &options),
-        ExportFormat::Markdown => export_to_markdown(&session, &options),
-        ExportFormat::Html => export_to_html(&session, &options),
+        ExportFormat::Json => export_to_json(session, options),
+        ExportFormat::Jsonl => export_to_jsonl(session, options),
+        ExportFormat::Markdown => export_to_markdown(session, options),
+        ExportFormat::Html => export_to_html(session, options),
+    }
+}
+
+/// Copy of the session keeping only messages that match the filters
+fn filter_messages(session: &Session, options: &ExportOptions) -> Session {
+    let mut filtered = session.clone();
+    if let Some(conversation) = &session.conversation {
+        let messages: Vec<Message> = conversation
+            .messages()
+            .iter()
+            .filter(|m| options.matches(m))
+            .cloned()
+            .collect();
+        filtered.message_count = messages.len();
+        filtered.conversation = Some(Conversation::new_unvalidated(messages));
     }
+    filtered
 }
 
 /// Export session to JSON format
@@ -74,6 +149,28 @@ fn export_to_json(session: &Session, options: &ExportOptions) -> Result<String>
     }
 }
 
+/// Export session to JSONL format
+///
+/// The first line holds the session without its conversation so that the
+/// transcript can be imported again; each following line is one message.
+fn export_to_jsonl(session: &Session, options: &ExportOptions) -> Result<String> {
+    let header = Session {
+        conversation: None,
+        ..session.clone()
+    };
+    let mut lines = vec![serde_json::to_string(&header)?];
+
+    if options.include_messages {
+        if let Some(conversation) = &session.conversation {
+            for message in conversation.messages() {
+                lines.push(serde_json::to_string(message)?);
+            }
+        }
+    }
+
+    Ok(lines.join("\n"))
+}
+
 /// Export session to Markdown format
 fn export_to_markdown(session: &Session, options: &ExportOptions) -> Result<String> {
     let mut lines = Vec::new();
@@ -422,4 +519,59 @@ mod tests {
         assert!(!options.include_messages);
         assert!(options.include_metadata);
     }
+
+    fn mixed_session() -> Session {
+        let messages = vec![
+            Message::user().with_text("first question"),
+            Message::assistant().with_text("first answer"),
+            Message::user().with_text("second question"),
+            Message::assistant().with_text("second answer"),
+        ];
+        Session {
+            name: "mixed".to_string(),
+            message_count: messages.len(),
+            conversation: Some(Conversation::new_unvalidated(messages)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_export_only_user_messages() {
+        let session = mixed_session();
+
+        let options = ExportOptions::new()
+            .format(ExportFormat::Markdown)
+            .roles(vec![Role::User]);
+        let markdown = export_session_data(&session, &options).unwrap();
+        assert!(markdown.contains("first question"));
+        assert!(markdown.contains("second question"));
+        assert!(!markdown.contains("answer"));
+        assert!(markdown.contains("- **Messages:** 2"));
+
+        let json =
+            export_session_data(&session, &ExportOptions::new().roles(vec![Role::User])).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let exported = value["conversation"].as_array().unwrap();
+        assert_eq!(exported.len(), 2);
+        assert!(exported.iter().all(|m| m["role"] == "user"));
+    }
+
+    #[test]
+    fn test_export_empty_after_filtering() {
+        let session = mixed_session();
+        let future = Utc::now() + chrono::Duration::days(1);
+
+        for format in [
+            ExportFormat::Json,
+            ExportFormat::Jsonl,
+            ExportFormat::Markdown,
+            ExportFormat::Html,
+        ] {
+            let options = ExportOptions::new()
+                .format(format)
+                .time_range(Some(future), None);
+            let output = export_session_data(&session, &options).unwrap();
+            assert!(!output.contains("question"));
+        }
+    }
 }
@@ -0,0 +1,190 @@
+//! 跨 session 全文搜索
+//!
+//! 基于 SQLite FTS5 索引消息文本，返回带高亮片段和上下文的搜索结果。
+//! 查询支持 `"短语"` 和 `前缀*` 两种语法，其余词按 AND 组合。
+
+use crate::session::store::SessionSearchHit;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+/// 片段中匹配内容的起始标记
+pub const HIGHLIGHT_START: &str = "<mark>";
+/// 片段中匹配内容的结束标记
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+/// 从消息 JSON 中提取文本内容
+const MESSAGE_TEXT_SQL: &str =
+    "COALESCE((SELECT group_concat(json_extract(value, '$.text'), char(10)) \
+     FROM json_each(NEW.content_json) WHERE json_extract(value, '$.type') = 'text'), '')";
+
+/// 创建 FTS5 索引及同步触发器，并为尚未索引的已有消息建立索引
+///
+/// 可重复执行：中断后重跑不会报错，也不会重复索引消息。调用方应在事务中执行
+pub(crate) async fn create_fts_index(conn: &mut SqliteConnection) -> Result<()> {
+    let statements = [
+        "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(text, session_id UNINDEXED, tokenize = 'unicode61')".to_string(),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN \
+             INSERT INTO messages_fts (rowid, text, session_id) VALUES (NEW.id, {MESSAGE_TEXT_SQL}, NEW.session_id); \
+             END"
+        ),
+        "CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN \
+         DELETE FROM messages_fts WHERE rowid = OLD.id; \
+         END"
+            .to_string(),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF content_json ON messages BEGIN \
+             UPDATE messages_fts SET text = {MESSAGE_TEXT_SQL} WHERE rowid = NEW.id; \
+             END"
+        ),
+        format!(
+            "INSERT INTO messages_fts (rowid, text, session_id) SELECT id, {}, session_id FROM messages \
+             WHERE id NOT IN (SELECT rowid FROM messages_fts)",
+            MESSAGE_TEXT_SQL.replace("NEW.", "messages.")
+        ),
+    ];
+
+    for statement in &statements {
+        sqlx::query(statement).execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// 将用户查询转换为 FTS5 查询，无有效词时返回 None
+///
+/// 所有词都作为字符串字面量传给 FTS5，避免用户输入被解析为 FTS5 运算符
+pub fn build_fts_query(query: &str) -> Option<String> {
+    let quote = |term: &str| format!("\"{}\"", term.replace('"', "\"\""));
+    let mut terms = Vec::new();
+
+    for (i, segment) in query.split('"').enumerate() {
+        // 奇数段位于引号内，作为短语匹配
+        if i % 2 == 1 {
+            if !segment.trim().is_empty() {
+                terms.push(quote(segment.trim()));
+            }
+            continue;
+        }
+        for word in segment.split_whitespace() {
+            match word.strip_suffix('*') {
+                Some(prefix) if !prefix.is_empty() => terms.push(format!("{}*", quote(prefix))),
+                Some(_) => {}
+                None => terms.push(quote(word)),
+            }
+        }
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+type SearchRow = (
+    String,
+    String,
+    i64,
+    String,
+    String,
+    f64,
+    DateTime<Utc>,
+    Option<String>,
+    Option<String>,
+);
+
+/// 在所有 session 中搜索，按相关度排序
+pub(crate) async fn search_all(
+    pool: &Pool<Sqlite>,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SessionSearchHit>> {
+    let Some(fts_query) = build_fts_query(query) else {
+        return Ok(Vec::new());
+    };
+
+    let rows = sqlx::query_as::<_, SearchRow>(
+        r#"
+        SELECT
+            m.session_id,
+            COALESCE(NULLIF(s.name, ''), s.description),
+            (SELECT COUNT(*) FROM messages p
+             WHERE p.session_id = m.session_id
+               AND (p.timestamp < m.timestamp OR (p.timestamp = m.timestamp AND p.id < m.id))),
+            m.role,
+            snippet(messages_fts, 0, ?, ?, '…', 16),
+            bm25(messages_fts),
+            m.timestamp,
+            (SELECT f.text FROM messages p JOIN messages_fts f ON f.rowid = p.id
+             WHERE p.session_id = m.session_id AND p.id < m.id
+             ORDER BY p.id DESC LIMIT 1),
+            (SELECT f.text FROM messages p JOIN messages_fts f ON f.rowid = p.id
+             WHERE p.session_id = m.session_id AND p.id > m.id
+             ORDER BY p.id ASC LIMIT 1)
+        FROM messages_fts
+        INNER JOIN messages m ON m.id = messages_fts.rowid
+        INNER JOIN sessions s ON s.id = m.session_id
+        WHERE messages_fts MATCH ?
+        ORDER BY bm25(messages_fts), m.timestamp DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(HIGHLIGHT_START)
+    .bind(HIGHLIGHT_END)
+    .bind(&fts_query)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                session_id,
+                session_name,
+                position,
+                role,
+                snippet,
+                bm25,
+                timestamp,
+                context_before,
+                context_after,
+            )| SessionSearchHit {
+                message_id: format!("msg_{}_{}", session_id, position),
+                session_id,
+                session_name,
+                role,
+                snippet,
+                // bm25 越小越相关，取反使 rank 越大越相关
+                rank: -bm25,
+                timestamp,
+                context_before: context_before.filter(|t| !t.is_empty()),
+                context_after: context_after.filter(|t| !t.is_empty()),
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_fts_query() {
+        assert_eq!(
+            build_fts_query("proxy config").as_deref(),
+            Some("\"proxy\" \"config\"")
+        );
+        assert_eq!(
+            build_fts_query("\"connection refused\" deploy*").as_deref(),
+            Some("\"connection refused\" \"deploy\"*")
+        );
+        // FTS5 运算符按普通词处理
+        assert_eq!(
+            build_fts_query("NOT a OR b-c").as_deref(),
+            Some("\"NOT\" \"a\" \"OR\" \"b-c\"")
+        );
+        assert_eq!(build_fts_query("  * \"\" "), None);
+    }
+}
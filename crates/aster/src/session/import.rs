@@ -0,0 +1,167 @@
+//! Session Import Support
+//!
+//! Reconstructs sessions from JSON or JSONL transcripts, the inverse of
+//! `session::export`.
+
+use crate::conversation::message::Message;
+use crate::session::{Session, SessionManager};
+use anyhow::Result;
+use std::io::{BufRead, BufReader, Read};
+
+/// Transcript format accepted by [`import_session`]
+#[derive(Debug, Clone, Copy, Default)]
+pub enum ImportFormat {
+    /// A session exported as JSON, or a bare array of messages
+    #[default]
+    Json,
+    /// An optional session header line followed by one message per line
+    Jsonl,
+}
+
+/// A transcript parsed from JSON or JSONL
+#[derive(Debug, Clone, Default)]
+pub struct ParsedTranscript {
+    /// Session metadata, if the transcript carried any
+    pub header: Option<Session>,
+    /// Messages in transcript order
+    pub messages: Vec<Message>,
+    /// Malformed lines or messages that were skipped
+    pub skipped: usize,
+}
+
+/// Result of importing a transcript
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    /// The newly created session
+    pub session: Session,
+    /// Number of messages imported
+    pub imported_messages: usize,
+    /// Malformed lines or messages that were skipped
+    pub skipped: usize,
+}
+
+/// Import a transcript as a new session
+///
+/// The session gets a new id; messages keep their order and timestamps.
+/// Malformed messages are skipped and counted instead of failing the import.
+pub async fn import_session(reader: impl Read, format: ImportFormat) -> Result<ImportResult> {
+    let transcript = parse_transcript(BufReader::new(reader), format)?;
+    SessionManager::instance()
+        .await?
+        .import_transcript(transcript)
+        .await
+}
+
+/// Parse a transcript without storing it
+pub fn parse_transcript(reader: impl BufRead, format: ImportFormat) -> Result<ParsedTranscript> {
+    match format {
+        ImportFormat::Json => parse_json(reader),
+        ImportFormat::Jsonl => Ok(parse_jsonl(reader)),
+    }
+}
+
+fn parse_json(mut reader: impl BufRead) -> Result<ParsedTranscript> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid JSON transcript: {}", e))?;
+
+    let (header, messages) = match value {
+        serde_json::Value::Array(messages) => (None, messages),
+        serde_json::Value::Object(mut object) => {
+            let messages = match object.remove("conversation") {
+                Some(serde_json::Value::Array(messages)) => messages,
+                _ => Vec::new(),
+            };
+            let header: Session = serde_json::from_value(serde_json::Value::Object(object))
+                .map_err(|e| anyhow::anyhow!("Invalid session metadata: {}", e))?;
+            (Some(header), messages)
+        }
+        _ => anyhow::bail!("JSON transcript must be a session object or a message array"),
+    };
+
+    let mut transcript = ParsedTranscript {
+        header,
+        ..Default::default()
+    };
+    for message in messages {
+        match serde_json::from_value::<Message>(message) {
+            Ok(message) => transcript.messages.push(message),
+            Err(_) => transcript.skipped += 1,
+        }
+    }
+    Ok(transcript)
+}
+
+fn parse_jsonl(reader: impl BufRead) -> ParsedTranscript {
+    let mut transcript = ParsedTranscript::default();
+
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            transcript.skipped += 1;
+            continue;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+            transcript.skipped += 1;
+            continue;
+        };
+
+        if value.get("role").is_some() {
+            match serde_json::from_value::<Message>(value) {
+                Ok(message) => transcript.messages.push(message),
+                Err(_) => transcript.skipped += 1,
+            }
+            continue;
+        }
+
+        // Only a line before any message can be the session header
+        let is_first = transcript.header.is_none() && transcript.messages.is_empty();
+        match serde_json::from_value::<Session>(value) {
+            Ok(header) if is_first => transcript.header = Some(header),
+            _ => transcript.skipped += 1,
+        }
+    }
+
+    transcript
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Role;
+
+    #[test]
+    fn test_parse_jsonl_skips_malformed_lines() {
+        let user = serde_json::to_string(&Message::user().with_text("hello")).unwrap();
+        let assistant = serde_json::to_string(&Message::assistant().with_text("hi")).unwrap();
+        let input = format!(
+            "{}\nnot json\n\n{{\"role\":\"user\",\"content\":42}}\n{}\n{{\"unexpected\":true}}\n",
+            user, assistant
+        );
+
+        let transcript = parse_transcript(input.as_bytes(), ImportFormat::Jsonl).unwrap();
+        assert!(transcript.header.is_none());
+        assert_eq!(transcript.skipped, 3);
+        assert_eq!(transcript.messages.len(), 2);
+        assert_eq!(transcript.messages[0].role, Role::User);
+        assert_eq!(transcript.messages[1].as_concat_text(), "hi");
+    }
+
+    #[test]
+    fn test_parse_json_message_array() {
+        let input = serde_json::json!([
+            Message::user().with_text("question"),
+            { "role": "assistant" },
+        ])
+        .to_string();
+
+        let transcript = parse_transcript(input.as_bytes(), ImportFormat::Json).unwrap();
+        assert_eq!(transcript.messages.len(), 1);
+        assert_eq!(transcript.skipped, 1);
+        assert!(parse_transcript("42".as_bytes(), ImportFormat::Json).is_err());
+    }
+}
@@ -4,6 +4,7 @@
 //! - `SessionStore` trait: 可插拔的存储抽象
 //! - `SessionManager`: 向后兼容的静态方法（使用全局 store）
 //! - SQLite 默认实现
+//! - PostgreSQL 实现（`session-postgres` feature，多实例共享 session）
 //!
 //! ## 使用方式
 //!
@@ -29,16 +30,25 @@ mod diagnostics;
 mod export;
 pub mod extension_data;
 mod fork;
+mod full_text_search;
+mod import;
 mod legacy;
+#[cfg(feature = "session-postgres")]
+mod postgres_store;
 pub mod resume;
 pub mod session_manager;
 mod statistics;
 mod store;
 
 // 导出存储抽象
+#[cfg(feature = "session-postgres")]
+pub use postgres_store::{
+    PostgresSessionStore, PostgresSessionStoreConfig, POSTGRES_SCHEMA_VERSION,
+};
 pub use store::{
-    get_global_session_store, is_global_session_store_set, set_global_session_store,
-    ChatHistoryMatch, NoopSessionStore, SessionStore, TokenStatsUpdate,
+    get_global_session_store, is_global_session_store_set, normalize_tags,
+    set_global_session_store, ChatHistoryMatch, MessageOrder, MessagePage, NoopSessionStore,
+    SessionSearchHit, SessionStore, TagMatch, TokenStatsUpdate,
 };
 
 // 导出现有功能（向后兼容）
@@ -59,6 +69,8 @@ pub use fork::{
     fork_session, get_session_branch_tree, merge_sessions, ForkMetadata, ForkOptions, MergeOptions,
     MergeStrategy, MetadataStrategy, SessionBranchTree,
 };
+pub use full_text_search::{build_fts_query, HIGHLIGHT_END, HIGHLIGHT_START};
+pub use import::{import_session, parse_transcript, ImportFormat, ImportResult, ParsedTranscript};
 pub use resume::{
     build_resume_message, delete_summary, has_summary, list_summaries, load_summary,
     load_summary_data, save_summary, SummaryCacheData,
@@ -5,12 +5,16 @@ use crate::model::ModelConfig;
 use crate::providers::base::{Provider, MSG_COUNT_FOR_SESSION_NAME_GENERATION};
 use crate::recipe::Recipe;
 use crate::session::extension_data::ExtensionData;
+use crate::session::import::{ImportResult, ParsedTranscript};
+use crate::session::store::{
+    normalize_tags, MessageOrder, MessagePage, SessionSearchHit, TagMatch,
+};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rmcp::model::Role;
 use serde::{Deserialize, Serialize};
 use sqlx::sqlite::SqliteConnectOptions;
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Sqlite, SqliteConnection};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -19,7 +23,7 @@ use tokio::sync::OnceCell;
 use tracing::{info, warn};
 use utoipa::ToSchema;
 
-pub const CURRENT_SCHEMA_VERSION: i32 = 6;
+pub const CURRENT_SCHEMA_VERSION: i32 = 8;
 pub const SESSIONS_FOLDER: &str = "sessions";
 pub const DB_NAME: &str = "sessions.db";
 
@@ -90,6 +94,8 @@ pub struct Session {
     pub message_count: usize,
     pub provider_name: Option<String>,
     pub model_config: Option<ModelConfig>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 pub struct SessionUpdateBuilder {
@@ -323,6 +329,46 @@ impl SessionManager {
             .await
     }
 
+    pub async fn get_messages_paginated(
+        session_id: &str,
+        offset: usize,
+        limit: usize,
+        order: MessageOrder,
+    ) -> Result<MessagePage> {
+        Self::instance()
+            .await?
+            .get_messages_paginated(session_id, offset, limit, order)
+            .await
+    }
+
+    pub async fn search_all(query: &str, limit: usize) -> Result<Vec<SessionSearchHit>> {
+        Self::instance().await?.search_all(query, limit).await
+    }
+
+    pub async fn add_session_tags(session_id: &str, tags: &[String]) -> Result<()> {
+        Self::instance()
+            .await?
+            .add_session_tags(session_id, tags)
+            .await
+    }
+
+    pub async fn remove_session_tags(session_id: &str, tags: &[String]) -> Result<()> {
+        Self::instance()
+            .await?
+            .remove_session_tags(session_id, tags)
+            .await
+    }
+
+    pub async fn list_sessions_by_tags(
+        tags: &[String],
+        tag_match: TagMatch,
+    ) -> Result<Vec<Session>> {
+        Self::instance()
+            .await?
+            .list_sessions_by_tags(tags, tag_match)
+            .await
+    }
+
     pub async fn maybe_update_name(id: &str, provider: Arc<dyn Provider>) -> Result<()> {
         let session = Self::get_session(id, true).await?;
 
@@ -365,6 +411,9 @@ impl SessionManager {
     }
 }
 
+/// 消息行：role, content_json, created_timestamp, metadata_json
+type MessageRow = (String, String, i64, Option<String>);
+
 pub struct SessionStorage {
     pool: Pool<Sqlite>,
 }
@@ -410,6 +459,7 @@ impl Default for Session {
             message_count: 0,
             provider_name: None,
             model_config: None,
+            tags: Vec::new(),
         }
     }
 }
@@ -446,6 +496,11 @@ impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Session {
 
         let user_set_name = row.try_get("user_set_name").unwrap_or(false);
 
+        let tags_json: Option<String> = row.try_get("tags_json").ok().flatten();
+        let tags = tags_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
         let session_type_str: String = row
             .try_get("session_type")
             .unwrap_or_else(|_| "user".to_string());
@@ -474,6 +529,7 @@ impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Session {
             message_count: row.try_get("message_count").unwrap_or(0) as usize,
             provider_name: row.try_get("provider_name").ok().flatten(),
             model_config,
+            tags,
         })
     }
 }
@@ -600,6 +656,11 @@ impl SessionStorage {
             .execute(&pool)
             .await?;
 
+        let mut tx = pool.begin().await?;
+        crate::session::full_text_search::create_fts_index(&mut tx).await?;
+        Self::create_tags_table(&mut tx).await?;
+        tx.commit().await?;
+
         Ok(Self { pool })
     }
 
@@ -706,6 +767,26 @@ impl SessionStorage {
         Ok(())
     }
 
+    /// 创建会话标签表及索引；可重复执行，调用方应在事务中执行
+    async fn create_tags_table(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS session_tags (
+                session_id TEXT NOT NULL REFERENCES sessions(id),
+                tag TEXT NOT NULL,
+                PRIMARY KEY (session_id, tag)
+            )
+        "#,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_session_tags_tag ON session_tags(tag)")
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
     async fn run_migrations(&self) -> Result<()> {
         let current_version = self.get_schema_version().await?;
 
@@ -838,6 +919,16 @@ impl SessionStorage {
                 .execute(&self.pool)
                 .await?;
             }
+            7 => {
+                let mut tx = self.pool.begin().await?;
+                crate::session::full_text_search::create_fts_index(&mut tx).await?;
+                tx.commit().await?;
+            }
+            8 => {
+                let mut tx = self.pool.begin().await?;
+                Self::create_tags_table(&mut tx).await?;
+                tx.commit().await?;
+            }
             _ => {
                 anyhow::bail!("Unknown migration version: {}", version);
             }
@@ -893,8 +984,9 @@ impl SessionStorage {
                total_tokens, input_tokens, output_tokens,
                accumulated_total_tokens, accumulated_input_tokens, accumulated_output_tokens,
                schedule_id, recipe_json, user_recipe_values_json,
-               provider_name, model_config_json
-        FROM sessions
+               provider_name, model_config_json,
+               (SELECT json_group_array(tag) FROM (SELECT tag FROM session_tags WHERE session_id = s.id ORDER BY tag)) AS tags_json
+        FROM sessions s
         WHERE id = ?
     "#,
         )
@@ -1031,35 +1123,95 @@ impl SessionStorage {
     }
 
     async fn get_conversation(&self, session_id: &str) -> Result<Conversation> {
-        let rows = sqlx::query_as::<_, (String, String, i64, Option<String>)>(
-            "SELECT role, content_json, created_timestamp, metadata_json FROM messages WHERE session_id = ? ORDER BY timestamp",
+        let rows = sqlx::query_as::<_, MessageRow>(
+            "SELECT role, content_json, created_timestamp, metadata_json FROM messages WHERE session_id = ? ORDER BY timestamp, id",
         )
             .bind(session_id)
             .fetch_all(&self.pool)
             .await?;
 
         let mut messages = Vec::new();
-        for (idx, (role_str, content_json, created_timestamp, metadata_json)) in
-            rows.into_iter().enumerate()
-        {
-            let role = match role_str.as_str() {
-                "user" => Role::User,
-                "assistant" => Role::Assistant,
-                _ => continue,
-            };
+        for (idx, row) in rows.into_iter().enumerate() {
+            if let Some(message) = Self::message_from_row(session_id, idx, row)? {
+                messages.push(message);
+            }
+        }
 
-            let content = serde_json::from_str(&content_json)?;
-            let metadata = metadata_json
-                .and_then(|json| serde_json::from_str(&json).ok())
-                .unwrap_or_default();
+        Ok(Conversation::new_unvalidated(messages))
+    }
+
+    /// 将消息行转换为消息，`idx` 为消息在对话中的位置；未知角色返回 None
+    fn message_from_row(
+        session_id: &str,
+        idx: usize,
+        (role_str, content_json, created_timestamp, metadata_json): MessageRow,
+    ) -> Result<Option<Message>> {
+        let role = match role_str.as_str() {
+            "user" => Role::User,
+            "assistant" => Role::Assistant,
+            _ => return Ok(None),
+        };
+
+        let content = serde_json::from_str(&content_json)?;
+        let metadata = metadata_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        let mut message = Message::new(role, created_timestamp, content);
+        message.metadata = metadata;
+        Ok(Some(message.with_id(format!("msg_{}_{}", session_id, idx))))
+    }
+
+    async fn search_all(&self, query: &str, limit: usize) -> Result<Vec<SessionSearchHit>> {
+        crate::session::full_text_search::search_all(&self.pool, query, limit).await
+    }
 
-            let mut message = Message::new(role, created_timestamp, content);
-            message.metadata = metadata;
-            message = message.with_id(format!("msg_{}_{}", session_id, idx));
-            messages.push(message);
+    async fn get_messages_paginated(
+        &self,
+        session_id: &str,
+        offset: usize,
+        limit: usize,
+        order: MessageOrder,
+    ) -> Result<MessagePage> {
+        let total =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM messages WHERE session_id = ?")
+                .bind(session_id)
+                .fetch_one(&self.pool)
+                .await? as usize;
+
+        let direction = match order {
+            MessageOrder::Ascending => "ASC",
+            MessageOrder::Descending => "DESC",
+        };
+        let query = format!(
+            "SELECT role, content_json, created_timestamp, metadata_json FROM messages WHERE session_id = ? ORDER BY timestamp {direction}, id {direction} LIMIT ? OFFSET ?"
+        );
+        let rows = sqlx::query_as::<_, MessageRow>(&query)
+            .bind(session_id)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut messages = Vec::new();
+        for (i, row) in rows.into_iter().enumerate() {
+            let position = offset + i;
+            // 消息 ID 使用其在完整对话中的位置，与 get_conversation 保持一致
+            let idx = match order {
+                MessageOrder::Ascending => position,
+                MessageOrder::Descending => total.saturating_sub(position + 1),
+            };
+            if let Some(message) = Self::message_from_row(session_id, idx, row)? {
+                messages.push(message);
+            }
         }
 
-        Ok(Conversation::new_unvalidated(messages))
+        Ok(MessagePage {
+            messages,
+            total,
+            offset,
+            limit,
+        })
     }
 
     async fn add_message(&self, session_id: &str, message: &Message) -> Result<()> {
@@ -1137,6 +1289,7 @@ impl SessionStorage {
                    s.accumulated_total_tokens, s.accumulated_input_tokens, s.accumulated_output_tokens,
                    s.schedule_id, s.recipe_json, s.user_recipe_values_json,
                    s.provider_name, s.model_config_json,
+                   (SELECT json_group_array(tag) FROM (SELECT tag FROM session_tags WHERE session_id = s.id ORDER BY tag)) AS tags_json,
                    COUNT(m.id) as message_count
             FROM sessions s
             INNER JOIN messages m ON s.id = m.session_id
@@ -1178,6 +1331,11 @@ impl SessionStorage {
             .execute(&mut *tx)
             .await?;
 
+        sqlx::query("DELETE FROM session_tags WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+
         sqlx::query("DELETE FROM sessions WHERE id = ?")
             .bind(session_id)
             .execute(&mut *tx)
@@ -1210,7 +1368,35 @@ impl SessionStorage {
     }
 
     async fn import_session(&self, json: &str) -> Result<Session> {
-        let import: Session = serde_json::from_str(json)?;
+        let mut import: Session = serde_json::from_str(json)?;
+        let messages = import
+            .conversation
+            .take()
+            .map(|c| c.messages().clone())
+            .unwrap_or_default();
+
+        let transcript = ParsedTranscript {
+            header: Some(import),
+            messages,
+            skipped: 0,
+        };
+        Ok(self.import_transcript(transcript).await?.session)
+    }
+
+    /// 以新 session 导入解析后的对话记录，保留消息顺序和时间戳
+    pub(crate) async fn import_transcript(
+        &self,
+        transcript: ParsedTranscript,
+    ) -> Result<ImportResult> {
+        let ParsedTranscript {
+            header,
+            messages,
+            skipped,
+        } = transcript;
+        let import = header.unwrap_or_else(|| Session {
+            name: "Imported session".to_string(),
+            ..Default::default()
+        });
 
         let session = self
             .create_session(
@@ -1238,12 +1424,19 @@ impl SessionStorage {
 
         self.apply_update(builder).await?;
 
-        if let Some(conversation) = import.conversation {
-            self.replace_conversation(&session.id, &conversation)
+        let imported_messages = messages.len();
+        if !messages.is_empty() {
+            self.replace_conversation(&session.id, &Conversation::new_unvalidated(messages))
                 .await?;
         }
 
-        self.get_session(&session.id, true).await
+        self.add_session_tags(&session.id, &import.tags).await?;
+
+        Ok(ImportResult {
+            session: self.get_session(&session.id, true).await?,
+            imported_messages,
+            skipped,
+        })
     }
 
     async fn copy_session(&self, session_id: &str, new_name: String) -> Result<Session> {
@@ -1270,9 +1463,104 @@ impl SessionStorage {
                 .await?;
         }
 
+        self.add_session_tags(&new_session.id, &original_session.tags)
+            .await?;
+
         self.get_session(&new_session.id, true).await
     }
 
+    async fn add_session_tags(&self, session_id: &str, tags: &[String]) -> Result<()> {
+        let tags = normalize_tags(tags);
+        if tags.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let exists =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?)")
+                .bind(session_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        if !exists {
+            return Err(anyhow::anyhow!("Session not found"));
+        }
+
+        for tag in &tags {
+            sqlx::query("INSERT OR IGNORE INTO session_tags (session_id, tag) VALUES (?, ?)")
+                .bind(session_id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn remove_session_tags(&self, session_id: &str, tags: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for tag in normalize_tags(tags) {
+            sqlx::query("DELETE FROM session_tags WHERE session_id = ? AND tag = ?")
+                .bind(session_id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn list_sessions_by_tags(
+        &self,
+        tags: &[String],
+        tag_match: TagMatch,
+    ) -> Result<Vec<Session>> {
+        let tags = normalize_tags(tags);
+        if tags.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let required = match tag_match {
+            TagMatch::All => tags.len(),
+            TagMatch::Any => 1,
+        };
+        let placeholders: String = tags.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        // 显式打标签的 session 即使没有消息也返回
+        let query = format!(
+            r#"
+            SELECT s.id, s.working_dir, s.name, s.description, s.user_set_name, s.session_type, s.created_at, s.updated_at, s.extension_data,
+                   s.total_tokens, s.input_tokens, s.output_tokens,
+                   s.accumulated_total_tokens, s.accumulated_input_tokens, s.accumulated_output_tokens,
+                   s.schedule_id, s.recipe_json, s.user_recipe_values_json,
+                   s.provider_name, s.model_config_json,
+                   (SELECT json_group_array(tag) FROM (SELECT tag FROM session_tags WHERE session_id = s.id ORDER BY tag)) AS tags_json,
+                   COUNT(m.id) as message_count
+            FROM sessions s
+            LEFT JOIN messages m ON s.id = m.session_id
+            WHERE s.id IN (
+                SELECT session_id FROM session_tags
+                WHERE tag IN ({placeholders})
+                GROUP BY session_id
+                HAVING COUNT(*) >= ?
+            )
+            GROUP BY s.id
+            ORDER BY s.updated_at DESC
+            "#,
+        );
+
+        let mut q = sqlx::query_as::<_, Session>(&query);
+        for tag in &tags {
+            q = q.bind(tag);
+        }
+        q.bind(required as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn truncate_conversation(&self, session_id: &str, timestamp: i64) -> Result<()> {
         sqlx::query("DELETE FROM messages WHERE session_id = ? AND created_timestamp >= ?")
             .bind(session_id)
@@ -1395,6 +1683,294 @@ mod tests {
         assert_eq!(insights.total_tokens, expected_tokens as i64);
     }
 
+    #[tokio::test]
+    async fn test_get_messages_paginated() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SessionStorage::create(&temp_dir.path().join("test_sessions.db"))
+            .await
+            .unwrap();
+        let session = storage
+            .create_session(
+                PathBuf::from("/tmp"),
+                "paged".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+
+        for i in 0..25 {
+            storage
+                .add_message(
+                    &session.id,
+                    &Message::user().with_text(format!("message {}", i)),
+                )
+                .await
+                .unwrap();
+        }
+
+        let texts = |page: &MessagePage| -> Vec<String> {
+            page.messages.iter().map(|m| m.as_concat_text()).collect()
+        };
+
+        let first = storage
+            .get_messages_paginated(&session.id, 0, 10, MessageOrder::Ascending)
+            .await
+            .unwrap();
+        assert_eq!(first.total, 25);
+        assert_eq!(first.messages.len(), 10);
+        assert_eq!(texts(&first)[0], "message 0");
+        assert_eq!(texts(&first)[9], "message 9");
+        assert!(first.has_more());
+
+        let last = storage
+            .get_messages_paginated(&session.id, 20, 10, MessageOrder::Ascending)
+            .await
+            .unwrap();
+        assert_eq!(last.total, 25);
+        assert_eq!(
+            texts(&last),
+            (20..25)
+                .map(|i| format!("message {}", i))
+                .collect::<Vec<_>>()
+        );
+        assert!(!last.has_more());
+        assert_eq!(
+            last.messages[0].id.as_deref(),
+            Some(format!("msg_{}_20", session.id).as_str())
+        );
+
+        let newest = storage
+            .get_messages_paginated(&session.id, 0, 3, MessageOrder::Descending)
+            .await
+            .unwrap();
+        assert_eq!(
+            texts(&newest),
+            vec!["message 24", "message 23", "message 22"]
+        );
+        assert_eq!(
+            newest.messages[0].id.as_deref(),
+            Some(format!("msg_{}_24", session.id).as_str())
+        );
+
+        let beyond = storage
+            .get_messages_paginated(&session.id, 30, 10, MessageOrder::Ascending)
+            .await
+            .unwrap();
+        assert!(beyond.messages.is_empty());
+        assert_eq!(beyond.total, 25);
+    }
+
+    #[tokio::test]
+    async fn test_search_all_ranks_best_match_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SessionStorage::create(&temp_dir.path().join("test_sessions.db"))
+            .await
+            .unwrap();
+
+        let sessions = [
+            (
+                "proxy setup",
+                vec![
+                    "how do I set up the proxy",
+                    "configure the proxy with the proxy url and proxy auth",
+                    "thanks",
+                ],
+            ),
+            (
+                "deploys",
+                vec![
+                    "the deployment failed with connection refused",
+                    "check whether the proxy is running",
+                ],
+            ),
+            ("unrelated", vec!["write a haiku about autumn"]),
+        ];
+
+        let mut ids = Vec::new();
+        for (name, texts) in &sessions {
+            let session = storage
+                .create_session(PathBuf::from("/tmp"), name.to_string(), SessionType::User)
+                .await
+                .unwrap();
+            for (i, text) in texts.iter().enumerate() {
+                let message = if i % 2 == 0 {
+                    Message::user()
+                } else {
+                    Message::assistant()
+                };
+                storage
+                    .add_message(&session.id, &message.with_text(*text))
+                    .await
+                    .unwrap();
+            }
+            ids.push(session.id);
+        }
+
+        let hits = storage.search_all("proxy", 10).await.unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].session_id, ids[0]);
+        assert_eq!(hits[0].message_id, format!("msg_{}_1", ids[0]));
+        assert_eq!(hits[0].role, "assistant");
+        assert!(hits[0].snippet.contains("<mark>proxy</mark>"));
+        assert!(hits.windows(2).all(|w| w[0].rank >= w[1].rank));
+        assert_eq!(
+            hits[0].context_before.as_deref(),
+            Some("how do I set up the proxy")
+        );
+        assert_eq!(hits[0].context_after.as_deref(), Some("thanks"));
+
+        let phrase = storage
+            .search_all("\"connection refused\"", 10)
+            .await
+            .unwrap();
+        assert_eq!(phrase.len(), 1);
+        assert_eq!(phrase[0].session_id, ids[1]);
+        assert!(phrase[0]
+            .snippet
+            .contains("<mark>connection refused</mark>"));
+        assert!(storage
+            .search_all("\"proxy refused\"", 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let prefix = storage.search_all("deploy*", 10).await.unwrap();
+        assert_eq!(prefix.len(), 1);
+        assert_eq!(prefix[0].session_name, "deploys");
+
+        let both = storage.search_all("haiku autumn", 10).await.unwrap();
+        assert_eq!(both.len(), 1);
+        assert_eq!(both[0].session_id, ids[2]);
+    }
+
+    #[tokio::test]
+    async fn test_fts_migration_can_rerun() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SessionStorage::create(&temp_dir.path().join("test_sessions.db"))
+            .await
+            .unwrap();
+        let session = storage
+            .create_session(
+                PathBuf::from("/tmp"),
+                "rerun".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        storage
+            .add_message(&session.id, &Message::user().with_text("restart the proxy"))
+            .await
+            .unwrap();
+
+        // A run interrupted after the index exists but before the version bump
+        storage.apply_migration(7).await.unwrap();
+        storage.apply_migration(7).await.unwrap();
+
+        let hits = storage.search_all("proxy", 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tags_migration_can_rerun() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SessionStorage::create(&temp_dir.path().join("test_sessions.db"))
+            .await
+            .unwrap();
+        let session = storage
+            .create_session(
+                PathBuf::from("/tmp"),
+                "rerun".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        storage
+            .add_session_tags(&session.id, &["work".to_string()])
+            .await
+            .unwrap();
+
+        storage.apply_migration(8).await.unwrap();
+        storage.apply_migration(8).await.unwrap();
+
+        let tagged = storage
+            .list_sessions_by_tags(&["work".to_string()], TagMatch::Any)
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_tags_filtering() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SessionStorage::create(&temp_dir.path().join("test_sessions.db"))
+            .await
+            .unwrap();
+
+        let tags = |names: &[&str]| names.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let mut ids = HashMap::new();
+        for (name, session_tags) in [
+            ("backend bug", tags(&["work", "bug"])),
+            ("backend feature", tags(&["work", "feature"])),
+            ("hobby bug", tags(&["personal", "bug"])),
+            ("untagged", Vec::new()),
+        ] {
+            let session = storage
+                .create_session(PathBuf::from("/tmp"), name.to_string(), SessionType::User)
+                .await
+                .unwrap();
+            storage
+                .add_session_tags(&session.id, &session_tags)
+                .await
+                .unwrap();
+            ids.insert(name, session.id);
+        }
+
+        let names = |sessions: Vec<Session>| {
+            let mut names: Vec<String> = sessions.into_iter().map(|s| s.name).collect();
+            names.sort();
+            names
+        };
+
+        let work_bugs = storage
+            .list_sessions_by_tags(&tags(&["work", "bug"]), TagMatch::All)
+            .await
+            .unwrap();
+        assert_eq!(work_bugs.len(), 1);
+        assert_eq!(work_bugs[0].tags, vec!["bug", "work"]);
+        assert_eq!(names(work_bugs), vec!["backend bug"]);
+
+        let any = storage
+            .list_sessions_by_tags(&tags(&["feature", "personal"]), TagMatch::Any)
+            .await
+            .unwrap();
+        assert_eq!(names(any), vec!["backend feature", "hobby bug"]);
+
+        storage
+            .remove_session_tags(&ids["backend bug"], &tags(&["bug"]))
+            .await
+            .unwrap();
+        let bugs = storage
+            .list_sessions_by_tags(&tags(&[" bug "]), TagMatch::All)
+            .await
+            .unwrap();
+        assert_eq!(names(bugs), vec!["hobby bug"]);
+
+        let loaded = storage
+            .get_session(&ids["backend bug"], false)
+            .await
+            .unwrap();
+        assert_eq!(loaded.tags, vec!["work"]);
+        assert_eq!(
+            crate::session::SessionSummary::from(&loaded).tags,
+            vec!["work"]
+        );
+
+        assert!(storage
+            .add_session_tags("missing", &tags(&["work"]))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_export_import_roundtrip() {
         const DESCRIPTION: &str = "Original session";
@@ -1475,6 +2051,81 @@ mod tests {
         assert_eq!(conversation.messages()[1].role, Role::Assistant);
     }
 
+    #[tokio::test]
+    async fn test_transcript_export_import_roundtrip() {
+        use crate::session::export::{export_session_data, ExportFormat, ExportOptions};
+        use crate::session::import::{parse_transcript, ImportFormat};
+
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SessionStorage::create(&temp_dir.path().join("test_sessions.db"))
+            .await
+            .unwrap();
+
+        let original = storage
+            .create_session(
+                PathBuf::from("/tmp/transcript"),
+                "transcript".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        for (i, text) in ["first question", "first answer", "follow up"]
+            .iter()
+            .enumerate()
+        {
+            let role = if i % 2 == 0 {
+                Role::User
+            } else {
+                Role::Assistant
+            };
+            let message = Message::new(
+                role,
+                1_700_000_000 + i as i64,
+                vec![MessageContent::text(*text)],
+            );
+            storage.add_message(&original.id, &message).await.unwrap();
+        }
+        let original = storage.get_session(&original.id, true).await.unwrap();
+
+        let without_ids = |session: &Session| -> Vec<Message> {
+            session
+                .conversation
+                .as_ref()
+                .unwrap()
+                .messages()
+                .iter()
+                .map(|m| Message {
+                    id: None,
+                    ..m.clone()
+                })
+                .collect()
+        };
+
+        for (export_format, import_format) in [
+            (ExportFormat::Jsonl, ImportFormat::Jsonl),
+            (ExportFormat::Json, ImportFormat::Json),
+        ] {
+            let mut exported =
+                export_session_data(&original, &ExportOptions::new().format(export_format))
+                    .unwrap();
+            if matches!(import_format, ImportFormat::Jsonl) {
+                exported.push_str("\n{not valid json");
+            }
+
+            let transcript = parse_transcript(exported.as_bytes(), import_format).unwrap();
+            let result = storage.import_transcript(transcript).await.unwrap();
+
+            assert_ne!(result.session.id, original.id);
+            assert_eq!(result.session.name, "transcript");
+            assert_eq!(result.imported_messages, 3);
+            assert_eq!(
+                result.skipped,
+                usize::from(matches!(import_format, ImportFormat::Jsonl))
+            );
+            assert_eq!(without_ids(&result.session), without_ids(&original));
+        }
+    }
+
     #[tokio::test]
     async fn test_import_session_with_description_field() {
         const OLD_FORMAT_JSON: &str = r#"{
@@ -39,6 +39,7 @@ pub struct SessionSummary {
     pub total_tokens: Option<i32>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub tags: Vec<String>,
 }
 
 impl From<&Session> for SessionSummary {
@@ -50,6 +51,7 @@ impl From<&Session> for SessionSummary {
             total_tokens: session.total_tokens,
             created_at: session.created_at,
             updated_at: session.updated_at,
+            tags: session.tags.clone(),
         }
     }
 }
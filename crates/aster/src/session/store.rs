@@ -109,6 +109,141 @@ pub trait SessionStore: Send + Sync {
         before_date: Option<chrono::DateTime<chrono::Utc>>,
         exclude_session_id: Option<String>,
     ) -> Result<Vec<ChatHistoryMatch>>;
+
+    /// 跨所有 session 全文搜索消息，按相关度从高到低排序
+    ///
+    /// 查询支持 `"短语"` 和 `前缀*` 语法。默认实现返回不支持错误。
+    async fn search_all(&self, _query: &str, _limit: usize) -> Result<Vec<SessionSearchHit>> {
+        Err(anyhow::anyhow!(
+            "Full-text search is not supported by this session store"
+        ))
+    }
+
+    /// 为 session 添加标签，已存在的标签忽略
+    ///
+    /// 默认实现返回不支持错误。
+    async fn add_session_tags(&self, _session_id: &str, _tags: &[String]) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "Session tags are not supported by this session store"
+        ))
+    }
+
+    /// 移除 session 的标签，不存在的标签忽略
+    ///
+    /// 默认实现返回不支持错误。
+    async fn remove_session_tags(&self, _session_id: &str, _tags: &[String]) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "Session tags are not supported by this session store"
+        ))
+    }
+
+    /// 按标签列出 session，`tag_match` 决定需要匹配全部还是任一标签
+    ///
+    /// 默认实现在 `list_sessions` 的结果中过滤。
+    async fn list_sessions_by_tags(
+        &self,
+        tags: &[String],
+        tag_match: TagMatch,
+    ) -> Result<Vec<Session>> {
+        let tags = normalize_tags(tags);
+        if tags.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .list_sessions()
+            .await?
+            .into_iter()
+            .filter(|session| tag_match.matches(&session.tags, &tags))
+            .collect())
+    }
+
+    /// 分页获取消息
+    ///
+    /// 默认实现加载完整对话后切片，存储实现应在查询层分页以避免加载整个 session。
+    async fn get_messages_paginated(
+        &self,
+        session_id: &str,
+        offset: usize,
+        limit: usize,
+        order: MessageOrder,
+    ) -> Result<MessagePage> {
+        let session = self.get_session(session_id, true).await?;
+        let mut messages = session
+            .conversation
+            .map(|c| c.messages().clone())
+            .unwrap_or_default();
+        let total = messages.len();
+        if order == MessageOrder::Descending {
+            messages.reverse();
+        }
+
+        Ok(MessagePage {
+            messages: messages.into_iter().skip(offset).take(limit).collect(),
+            total,
+            offset,
+            limit,
+        })
+    }
+}
+
+/// 消息分页顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageOrder {
+    /// 从最早的消息开始
+    #[default]
+    Ascending,
+    /// 从最新的消息开始
+    Descending,
+}
+
+/// 按多个标签过滤时的匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagMatch {
+    /// 同时包含所有标签（AND）
+    #[default]
+    All,
+    /// 包含任一标签（OR）
+    Any,
+}
+
+impl TagMatch {
+    /// 判断 session 的标签是否满足过滤条件
+    pub fn matches(&self, session_tags: &[String], wanted: &[String]) -> bool {
+        match self {
+            TagMatch::All => wanted.iter().all(|tag| session_tags.contains(tag)),
+            TagMatch::Any => wanted.iter().any(|tag| session_tags.contains(tag)),
+        }
+    }
+}
+
+/// 规范化标签：去除首尾空白、丢弃空标签并去重排序
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// 一页消息
+#[derive(Debug, Clone)]
+pub struct MessagePage {
+    pub messages: Vec<Message>,
+    /// session 中的消息总数
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl MessagePage {
+    /// 是否还有下一页
+    pub fn has_more(&self) -> bool {
+        self.offset + self.messages.len() < self.total
+    }
 }
 
 /// 聊天历史搜索结果
@@ -122,6 +257,25 @@ pub struct ChatHistoryMatch {
     pub relevance_score: f32,
 }
 
+/// 全文搜索结果
+#[derive(Debug, Clone)]
+pub struct SessionSearchHit {
+    pub session_id: String,
+    pub session_name: String,
+    /// 消息 ID（与加载对话时的消息 ID 一致）
+    pub message_id: String,
+    pub role: String,
+    /// 匹配片段，匹配词用 `<mark>`/`</mark>` 包裹
+    pub snippet: String,
+    /// 相关度，越大越相关
+    pub rank: f64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// 上一条消息的文本
+    pub context_before: Option<String>,
+    /// 下一条消息的文本
+    pub context_after: Option<String>,
+}
+
 /// Token 统计更新参数
 #[derive(Debug, Clone, Default)]
 pub struct TokenStatsUpdate {
@@ -169,6 +323,7 @@ impl SessionStore for NoopSessionStore {
             message_count: 0,
             provider_name: None,
             model_config: None,
+            tags: Vec::new(),
         })
     }
 
@@ -272,6 +427,14 @@ impl SessionStore for NoopSessionStore {
     ) -> Result<Vec<ChatHistoryMatch>> {
         Ok(vec![])
     }
+
+    async fn add_session_tags(&self, _session_id: &str, _tags: &[String]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn remove_session_tags(&self, _session_id: &str, _tags: &[String]) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// 全局 session store 实例
@@ -33,6 +33,9 @@ pub(crate) type MessageCallback = Box<dyn Fn(&MessageState) + Send + Sync>;
 /// 错误回调类型
 pub(crate) type ErrorCallback = Box<dyn Fn(&StreamError) + Send + Sync>;
 
+/// 取消回调类型
+pub(crate) type CancelledCallback = Box<dyn Fn(&CancelledEvent) + Send + Sync>;
+
 /// Anthropic API standard stream event types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -43,6 +46,8 @@ pub enum StreamEventType {
     ContentBlockStop,
     MessageDelta,
     MessageStop,
+    /// Emitted locally when the stream is aborted before `message_stop`
+    Cancelled,
 }
 
 /// Delta types for content updates
@@ -181,10 +186,22 @@ impl Default for MessageState {
     }
 }
 
+/// Final event of a cancelled stream, carrying what was generated so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelledEvent {
+    pub r#type: StreamEventType,
+    /// The partial message, with any open content block closed
+    pub partial: MessageState,
+}
+
 /// Stream options for timeout and abort control
 #[derive(Debug, Clone)]
 pub struct StreamOptions {
     pub timeout: Option<Duration>,
+    /// Maximum wait from stream start until the first content delta
+    pub first_token_timeout: Option<Duration>,
+    /// Maximum gap between events once content has started arriving
+    pub idle_timeout: Option<Duration>,
     pub heartbeat_interval: Option<Duration>,
     pub heartbeat_timeout: Option<Duration>,
     pub max_queue_size: usize,
@@ -194,6 +211,8 @@ impl Default for StreamOptions {
     fn default() -> Self {
         Self {
             timeout: None,
+            first_token_timeout: None,
+            idle_timeout: None,
             heartbeat_interval: Some(Duration::from_secs(5)),
             heartbeat_timeout: Some(Duration::from_secs(30)),
             max_queue_size: 100,
@@ -213,6 +232,7 @@ pub struct StreamCallbacks {
     pub on_message: Option<MessageCallback>,
     pub on_error: Option<ErrorCallback>,
     pub on_abort: Option<Box<dyn Fn() + Send + Sync>>,
+    pub on_cancelled: Option<CancelledCallback>,
     pub on_complete: Option<Box<dyn Fn() + Send + Sync>>,
 }
 
@@ -220,6 +240,10 @@ pub struct StreamCallbacks {
 #[derive(Debug, Clone)]
 pub enum StreamError {
     Timeout(String),
+    /// No content arrived within `first_token_timeout`
+    FirstTokenTimeout(Duration),
+    /// Content stopped arriving for longer than `idle_timeout`
+    IdleTimeout(Duration),
     HeartbeatTimeout,
     Aborted,
     ParseError(String),
@@ -230,6 +254,12 @@ impl std::fmt::Display for StreamError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             StreamError::Timeout(msg) => write!(f, "Stream timeout: {}", msg),
+            StreamError::FirstTokenTimeout(timeout) => {
+                write!(f, "Stream timeout: no first token within {:?}", timeout)
+            }
+            StreamError::IdleTimeout(timeout) => {
+                write!(f, "Stream timeout: idle for more than {:?}", timeout)
+            }
             StreamError::HeartbeatTimeout => write!(f, "Stream heartbeat timeout"),
             StreamError::Aborted => write!(f, "Stream aborted"),
             StreamError::ParseError(msg) => write!(f, "Parse error: {}", msg),
@@ -288,11 +318,16 @@ pub fn parse_tolerant_json(json_str: &str) -> serde_json::Value {
 /// Enhanced message stream handler
 pub struct EnhancedMessageStream {
     current_message: Option<MessageState>,
+    /// Index of the content block started but not yet stopped
+    open_block: Option<usize>,
+    partial_message: Option<MessageState>,
     messages: Vec<MessageState>,
     aborted: bool,
     ended: bool,
     error: Option<StreamError>,
     event_queue: VecDeque<serde_json::Value>,
+    started_at: Instant,
+    first_token_at: Option<Instant>,
     last_activity: Instant,
     options: StreamOptions,
     callbacks: StreamCallbacks,
@@ -303,11 +338,15 @@ impl EnhancedMessageStream {
     pub fn new(options: StreamOptions, callbacks: StreamCallbacks) -> Self {
         Self {
             current_message: None,
+            open_block: None,
+            partial_message: None,
             messages: Vec::new(),
             aborted: false,
             ended: false,
             error: None,
             event_queue: VecDeque::new(),
+            started_at: Instant::now(),
+            first_token_at: None,
             last_activity: Instant::now(),
             options,
             callbacks,
@@ -334,7 +373,37 @@ impl EnhancedMessageStream {
         Ok(())
     }
 
+    /// Check the first-token and idle timeouts
+    ///
+    /// Before the first content delta only `first_token_timeout` applies;
+    /// afterwards only `idle_timeout` does.
+    pub fn check_timeouts(&self) -> Result<(), StreamError> {
+        if self.aborted || self.ended {
+            return Ok(());
+        }
+        match self.first_token_at {
+            None => {
+                if let Some(timeout) = self.options.first_token_timeout {
+                    if self.started_at.elapsed() > timeout {
+                        return Err(StreamError::FirstTokenTimeout(timeout));
+                    }
+                }
+            }
+            Some(_) => {
+                if let Some(timeout) = self.options.idle_timeout {
+                    if self.last_activity.elapsed() > timeout {
+                        return Err(StreamError::IdleTimeout(timeout));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Abort the stream
+    ///
+    /// If a message was in progress, it is kept as the partial message and
+    /// reported through a final `Cancelled` event.
     pub fn abort(&mut self) {
         if self.aborted || self.ended {
             return;
@@ -342,10 +411,31 @@ impl EnhancedMessageStream {
 
         self.aborted = true;
         self.error = Some(StreamError::Aborted);
+        self.event_queue.clear();
 
         if let Some(ref cb) = self.callbacks.on_abort {
             cb();
         }
+
+        if let Some(mut partial) = self.current_message.take() {
+            if let Some(index) = self.open_block.take() {
+                if let (Some(block), Some(cb)) =
+                    (partial.content.get(index), &self.callbacks.on_content_block)
+                {
+                    cb(block);
+                }
+            }
+            partial.stop_reason = Some("cancelled".to_string());
+
+            let event = CancelledEvent {
+                r#type: StreamEventType::Cancelled,
+                partial,
+            };
+            if let Some(ref cb) = self.callbacks.on_cancelled {
+                cb(&event);
+            }
+            self.partial_message = Some(event.partial);
+        }
     }
 
     /// Handle a stream event
@@ -454,6 +544,7 @@ impl EnhancedMessageStream {
             };
 
             msg.content.push(content_block);
+            self.open_block = Some(msg.content.len() - 1);
         }
         Ok(())
     }
@@ -464,6 +555,8 @@ impl EnhancedMessageStream {
             .as_mut()
             .ok_or_else(|| StreamError::InvalidState("No current message".to_string()))?;
 
+        self.first_token_at.get_or_insert_with(Instant::now);
+
         let index = event.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
         let delta = event.get("delta");
 
@@ -596,6 +689,7 @@ impl EnhancedMessageStream {
     }
 
     fn handle_content_block_stop(&mut self, _event: &serde_json::Value) -> Result<(), StreamError> {
+        self.open_block = None;
         if let Some(ref msg) = self.current_message {
             if let Some(block) = msg.content.last() {
                 if let Some(ref cb) = self.callbacks.on_content_block {
@@ -672,6 +766,11 @@ impl EnhancedMessageStream {
             .unwrap_or_default()
     }
 
+    /// Get the message that was in progress when the stream was aborted
+    pub fn get_partial_message(&self) -> Option<&MessageState> {
+        self.partial_message.as_ref()
+    }
+
     /// Get all messages
     pub fn get_messages(&self) -> &[MessageState] {
         &self.messages
@@ -855,4 +954,127 @@ mod tests {
         assert!(stream.is_ended());
         assert_eq!(stream.get_final_text(), "Test");
     }
+
+    #[test]
+    fn test_abort_emits_cancelled_with_partial_message() {
+        use std::sync::{Arc, Mutex};
+
+        let cancelled: Arc<Mutex<Vec<CancelledEvent>>> = Arc::new(Mutex::new(Vec::new()));
+        let closed_blocks = Arc::new(Mutex::new(0));
+        let callbacks = StreamCallbacks {
+            on_cancelled: Some(Box::new({
+                let cancelled = cancelled.clone();
+                move |event| cancelled.lock().unwrap().push(event.clone())
+            })),
+            on_content_block: Some(Box::new({
+                let closed_blocks = closed_blocks.clone();
+                move |_| *closed_blocks.lock().unwrap() += 1
+            })),
+            ..Default::default()
+        };
+        let mut stream = EnhancedMessageStream::new(StreamOptions::default(), callbacks);
+
+        for event in [
+            serde_json::json!({
+                "type": "message_start",
+                "message": { "id": "msg_1", "role": "assistant", "model": "claude" }
+            }),
+            serde_json::json!({
+                "type": "content_block_start",
+                "index": 0,
+                "content_block": { "type": "text" }
+            }),
+            serde_json::json!({
+                "type": "content_block_delta",
+                "index": 0,
+                "delta": { "type": "text_delta", "text": "Hello " }
+            }),
+            serde_json::json!({
+                "type": "content_block_delta",
+                "index": 0,
+                "delta": { "type": "text_delta", "text": "World" }
+            }),
+        ] {
+            stream.handle_event(event).unwrap();
+        }
+
+        stream.abort();
+        stream
+            .handle_event(serde_json::json!({
+                "type": "content_block_delta",
+                "index": 0,
+                "delta": { "type": "text_delta", "text": "!" }
+            }))
+            .unwrap();
+
+        let events = cancelled.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].r#type, StreamEventType::Cancelled);
+        assert_eq!(*closed_blocks.lock().unwrap(), 1);
+
+        let partial = stream.get_partial_message().unwrap();
+        assert_eq!(partial.content.len(), 1);
+        let ContentBlock::Text(block) = &partial.content[0] else {
+            panic!("expected a text block");
+        };
+        assert_eq!(block.text, "Hello World");
+        assert_eq!(partial.stop_reason.as_deref(), Some("cancelled"));
+        assert!(stream.get_final_message().is_none());
+    }
+
+    fn timeout_stream(first_token: u64, idle: u64) -> EnhancedMessageStream {
+        let options = StreamOptions {
+            first_token_timeout: Some(Duration::from_millis(first_token)),
+            idle_timeout: Some(Duration::from_millis(idle)),
+            ..Default::default()
+        };
+        let mut stream = EnhancedMessageStream::new(options, StreamCallbacks::default());
+        stream
+            .handle_event(serde_json::json!({
+                "type": "message_start",
+                "message": { "id": "msg_1", "role": "assistant", "model": "claude" }
+            }))
+            .unwrap();
+        stream
+            .handle_event(serde_json::json!({
+                "type": "content_block_start",
+                "index": 0,
+                "content_block": { "type": "text" }
+            }))
+            .unwrap();
+        stream
+    }
+
+    #[test]
+    fn test_first_token_timeout() {
+        // Non-content events keep the stream active but do not count as a first token
+        let stream = timeout_stream(30, 1_000);
+        assert!(stream.check_timeouts().is_ok());
+
+        std::thread::sleep(Duration::from_millis(50));
+        let err = stream.check_timeouts().unwrap_err();
+        assert!(matches!(err, StreamError::FirstTokenTimeout(_)));
+        assert!(err.to_string().contains("first token"));
+    }
+
+    #[test]
+    fn test_idle_timeout() {
+        let mut stream = timeout_stream(10, 100);
+        stream
+            .handle_event(serde_json::json!({
+                "type": "content_block_delta",
+                "index": 0,
+                "delta": { "type": "text_delta", "text": "Hello" }
+            }))
+            .unwrap();
+
+        // The first token arrived, so the expired first-token budget no longer matters
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(stream.check_timeouts().is_ok());
+
+        std::thread::sleep(Duration::from_millis(100));
+        let err = stream.check_timeouts().unwrap_err();
+        assert!(matches!(err, StreamError::IdleTimeout(_)));
+        assert!(err.to_string().contains("idle"));
+    }
 }
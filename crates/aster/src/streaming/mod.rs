@@ -13,8 +13,8 @@ pub mod stream_io;
 
 // Re-exports
 pub use message_stream::{
-    ContentBlock, DeltaType, EnhancedMessageStream, MessageState, StreamCallbacks, StreamEventType,
-    StreamOptions,
+    CancelledEvent, ContentBlock, DeltaType, EnhancedMessageStream, MessageState, StreamCallbacks,
+    StreamEventType, StreamOptions,
 };
 pub use sse::{SSEDecoder, SSEEvent, SSEStream};
 pub use stream_io::{
@@ -1,10 +0,0 @@
-use tokio::process::Command;
-
-#[cfg(windows)]
-const CREATE_NO_WINDOW_FLAG: u32 = 0x08000000;
-
-#[allow(unused_variables)]
-pub fn configure_command_no_window(command: &mut Command) {
-    #[cfg(windows)]
-    command.creation_flags(CREATE_NO_WINDOW_FLAG);
-}
@@ -0,0 +1,208 @@
+//! Interactive subprocesses driven through stdin.
+//!
+//! [`InteractiveProcess`] keeps stdin open so input can be written
+//! incrementally, e.g. to drive a REPL or answer prompts. stdout and stderr
+//! are drained by background tasks as soon as output arrives, so a process
+//! blocked on a full pipe can never deadlock a writer waiting on stdin.
+
+use std::io;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::mpsc;
+
+use super::configure_command_no_window;
+use super::output_buffer::{OutputRingBuffer, SharedOutputBuffer};
+
+pub struct InteractiveProcess {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    output: SharedOutputBuffer,
+    chunks: mpsc::UnboundedReceiver<Vec<u8>>,
+    /// Output received but not yet returned by a read
+    unread: Vec<u8>,
+}
+
+impl InteractiveProcess {
+    /// Spawns `command` with piped stdin, stdout and stderr.
+    ///
+    /// stdout and stderr are merged into a single stream.
+    pub fn spawn(mut command: Command) -> io::Result<Self> {
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        configure_command_no_window(&mut command);
+
+        let mut child = command.spawn()?;
+        let stdin = child.stdin.take();
+        let output = OutputRingBuffer::default().shared();
+        let (tx, chunks) = mpsc::unbounded_channel();
+
+        if let Some(stdout) = child.stdout.take() {
+            spawn_reader(stdout, output.clone(), tx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            spawn_reader(stderr, output.clone(), tx);
+        }
+
+        Ok(Self {
+            child,
+            stdin,
+            output,
+            chunks,
+            unread: Vec::new(),
+        })
+    }
+
+    pub fn id(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// Recent output, retained regardless of what has been read.
+    pub fn output(&self) -> SharedOutputBuffer {
+        self.output.clone()
+    }
+
+    pub async fn write_stdin(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "stdin is closed"))?;
+        stdin.write_all(bytes).await?;
+        stdin.flush().await
+    }
+
+    /// Closes stdin, signalling EOF to the process.
+    pub async fn close_stdin(&mut self) -> io::Result<()> {
+        match self.stdin.take() {
+            Some(mut stdin) => stdin.shutdown().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Reads output up to and including `pattern`, e.g. a prompt.
+    ///
+    /// Output after the pattern is kept for the next read. Fails with
+    /// `TimedOut` if the pattern does not appear within `timeout` and with
+    /// `UnexpectedEof` if the process closes its output first.
+    pub async fn read_until(&mut self, pattern: &str, timeout: Duration) -> io::Result<String> {
+        let pattern = pattern.as_bytes();
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            if let Some(pos) = find(&self.unread, pattern) {
+                let rest = self.unread.split_off(pos + pattern.len());
+                let matched = std::mem::replace(&mut self.unread, rest);
+                return Ok(String::from_utf8_lossy(&matched).into_owned());
+            }
+
+            match tokio::time::timeout_at(deadline, self.chunks.recv()).await {
+                Ok(Some(chunk)) => self.unread.extend_from_slice(&chunk),
+                Ok(None) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "process output closed before pattern appeared",
+                    ))
+                }
+                Err(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("timed out after {:?} waiting for output", timeout),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Reads the next line of output without its line terminator.
+    pub async fn read_line(&mut self, timeout: Duration) -> io::Result<String> {
+        let line = self.read_until("\n", timeout).await?;
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    pub async fn kill(&mut self) -> io::Result<()> {
+        self.child.kill().await
+    }
+
+    /// Closes stdin and waits for the process to exit.
+    pub async fn wait(&mut self) -> io::Result<ExitStatus> {
+        self.close_stdin().await?;
+        self.child.wait().await
+    }
+}
+
+fn spawn_reader<R>(mut reader: R, output: SharedOutputBuffer, tx: mpsc::UnboundedSender<Vec<u8>>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut chunk = [0u8; 8192];
+        while let Ok(n) = reader.read(&mut chunk).await {
+            if n == 0 {
+                break;
+            }
+            if let Ok(mut output) = output.lock() {
+                output.push(&chunk[..n]);
+            }
+            // The handle may have been dropped; keep draining the pipe anyway
+            let _ = tx.send(chunk[..n].to_vec());
+        }
+    });
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cat_echoes_lines() {
+        let mut process = InteractiveProcess::spawn(Command::new("cat")).unwrap();
+        let timeout = Duration::from_secs(5);
+
+        for line in ["first", "second", "third"] {
+            process
+                .write_stdin(format!("{}\n", line).as_bytes())
+                .await
+                .unwrap();
+            assert_eq!(process.read_line(timeout).await.unwrap(), line);
+        }
+
+        assert!(process.wait().await.unwrap().success());
+        assert_eq!(
+            process.output().lock().unwrap().tail(3),
+            vec!["first", "second", "third"]
+        );
+        assert!(process.write_stdin(b"late\n").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_until_times_out() {
+        let mut process = InteractiveProcess::spawn(Command::new("cat")).unwrap();
+        process.write_stdin(b"no prompt here\n").await.unwrap();
+
+        let err = process
+            .read_until(">>> ", Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+        // Output read while waiting is still available
+        assert_eq!(
+            process.read_line(Duration::from_secs(5)).await.unwrap(),
+            "no prompt here"
+        );
+        process.kill().await.unwrap();
+    }
+}
@@ -0,0 +1,18 @@
+pub mod interactive;
+pub mod output_buffer;
+pub mod shell;
+
+pub use interactive::InteractiveProcess;
+pub use output_buffer::{spawn_capture, OutputRingBuffer, SharedOutputBuffer};
+pub use shell::{argv_command, quote_windows_arg, run_argv, run_shell, Shell};
+
+use tokio::process::Command;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW_FLAG: u32 = 0x08000000;
+
+#[allow(unused_variables)]
+pub fn configure_command_no_window(command: &mut Command) {
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW_FLAG);
+}
@@ -0,0 +1,222 @@
+//! Bounded capture of subprocess output.
+//!
+//! Long-running processes can produce far more output than is useful to keep.
+//! [`OutputRingBuffer`] retains only the most recent lines, bounded by both a
+//! line count and a byte budget, while counting everything that was produced
+//! so callers can tell how much was discarded.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::task::JoinHandle;
+
+pub const DEFAULT_MAX_BYTES: usize = 64 * 1024;
+pub const DEFAULT_MAX_LINES: usize = 1000;
+
+/// Keeps the tail of a process's output within a fixed memory budget.
+#[derive(Debug, Clone)]
+pub struct OutputRingBuffer {
+    max_bytes: usize,
+    max_lines: usize,
+    lines: VecDeque<String>,
+    /// Bytes of the last line that has not been terminated yet
+    partial: Vec<u8>,
+    retained_bytes: usize,
+    total_bytes: u64,
+}
+
+impl OutputRingBuffer {
+    pub fn new(max_bytes: usize, max_lines: usize) -> Self {
+        Self {
+            max_bytes: max_bytes.max(1),
+            max_lines: max_lines.max(1),
+            lines: VecDeque::new(),
+            partial: Vec::new(),
+            retained_bytes: 0,
+            total_bytes: 0,
+        }
+    }
+
+    pub fn shared(self) -> SharedOutputBuffer {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Appends raw output, splitting it into lines.
+    pub fn push(&mut self, data: &[u8]) {
+        self.total_bytes += data.len() as u64;
+
+        let mut rest = data;
+        while let Some(pos) = rest.iter().position(|b| *b == b'\n') {
+            self.partial.extend_from_slice(&rest[..pos]);
+            let line = String::from_utf8_lossy(&self.partial).into_owned();
+            self.partial.clear();
+            self.push_line(line);
+            rest = &rest[pos + 1..];
+        }
+        self.partial.extend_from_slice(rest);
+
+        // An unterminated line must not grow past the budget either
+        if self.partial.len() > self.max_bytes {
+            let excess = self.partial.len() - self.max_bytes;
+            self.partial.drain(..excess);
+        }
+        self.evict();
+    }
+
+    fn push_line(&mut self, mut line: String) {
+        if line.len() > self.max_bytes {
+            let mut cut = line.len() - self.max_bytes;
+            while !line.is_char_boundary(cut) {
+                cut += 1;
+            }
+            line.drain(..cut);
+        }
+        self.retained_bytes += line.len();
+        self.lines.push_back(line);
+    }
+
+    fn evict(&mut self) {
+        while self.lines.len() > self.max_lines
+            || (!self.lines.is_empty() && self.retained_bytes + self.partial.len() > self.max_bytes)
+        {
+            if let Some(line) = self.lines.pop_front() {
+                self.retained_bytes -= line.len();
+            }
+        }
+    }
+
+    /// Returns the last `n` lines, including an unterminated final line.
+    pub fn tail(&self, n: usize) -> Vec<String> {
+        let partial =
+            (!self.partial.is_empty()).then(|| String::from_utf8_lossy(&self.partial).into_owned());
+        let complete = n.saturating_sub(partial.is_some() as usize);
+
+        let mut lines: Vec<String> = self
+            .lines
+            .iter()
+            .skip(self.lines.len().saturating_sub(complete))
+            .cloned()
+            .collect();
+        lines.extend(partial);
+        lines
+    }
+
+    /// Returns all retained output joined with newlines.
+    pub fn contents(&self) -> String {
+        self.tail(usize::MAX).join("\n")
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len() + (!self.partial.is_empty()) as usize
+    }
+
+    /// Total bytes written since creation, including discarded output.
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Bytes currently held, excluding line separators.
+    pub fn retained_bytes(&self) -> usize {
+        self.retained_bytes + self.partial.len()
+    }
+
+    /// Bytes produced but no longer retained, including line separators.
+    pub fn discarded_bytes(&self) -> u64 {
+        let separators = self.lines.len() as u64;
+        self.total_bytes
+            .saturating_sub(self.retained_bytes() as u64 + separators)
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.partial.clear();
+        self.retained_bytes = 0;
+    }
+}
+
+impl Default for OutputRingBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BYTES, DEFAULT_MAX_LINES)
+    }
+}
+
+pub type SharedOutputBuffer = Arc<Mutex<OutputRingBuffer>>;
+
+/// Reads `reader` to EOF in the background, feeding everything into `buffer`.
+pub fn spawn_capture<R>(
+    mut reader: R,
+    buffer: SharedOutputBuffer,
+) -> JoinHandle<std::io::Result<()>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut chunk = [0u8; 8192];
+        loop {
+            let n = reader.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            if let Ok(mut buffer) = buffer.lock() {
+                buffer.push(&chunk[..n]);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_tail_is_retained() {
+        let mut buffer = OutputRingBuffer::new(1024, 10);
+        let mut written = 0;
+        for i in 0..100 {
+            let line = format!("line {}\n", i);
+            written += line.len();
+            buffer.push(line.as_bytes());
+        }
+
+        assert_eq!(buffer.line_count(), 10);
+        assert_eq!(buffer.tail(2), vec!["line 98", "line 99"]);
+        assert_eq!(buffer.total_bytes(), written as u64);
+        assert!(buffer.discarded_bytes() > 0);
+    }
+
+    #[test]
+    fn test_byte_budget_evicts_old_lines() {
+        let mut buffer = OutputRingBuffer::new(16, 100);
+        buffer.push(b"aaaaaaaa\nbbbbbbbb\ncccccccc\n");
+
+        assert_eq!(buffer.tail(10), vec!["bbbbbbbb", "cccccccc"]);
+        assert_eq!(buffer.retained_bytes(), 16);
+        assert_eq!(buffer.discarded_bytes(), 9);
+    }
+
+    #[test]
+    fn test_partial_line_joins_next_chunk() {
+        let mut buffer = OutputRingBuffer::default();
+        buffer.push(b"hel");
+        assert_eq!(buffer.tail(1), vec!["hel"]);
+        buffer.push(b"lo\nwor");
+        assert_eq!(buffer.tail(5), vec!["hello", "wor"]);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_capture_reads_to_eof() {
+        let buffer = OutputRingBuffer::new(64, 3).shared();
+        let data: Vec<u8> = (0..50)
+            .flat_map(|i| format!("{}\n", i).into_bytes())
+            .collect();
+
+        spawn_capture(std::io::Cursor::new(data.clone()), buffer.clone())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let buffer = buffer.lock().unwrap();
+        assert_eq!(buffer.tail(3), vec!["47", "48", "49"]);
+        assert_eq!(buffer.total_bytes(), data.len() as u64);
+    }
+}
@@ -0,0 +1,221 @@
+//! Running commands through a chosen shell or directly with an argument vector.
+//!
+//! Shell mode hands a command string to a shell and is subject to its
+//! interpretation (globbing, expansion, operators). Argv mode runs a program
+//! directly with an explicit argument vector so untrusted arguments cannot
+//! inject shell syntax.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::io;
+use std::process::{Output, Stdio};
+use tokio::process::Command;
+
+use super::configure_command_no_window;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shell {
+    Sh,
+    Bash,
+    Zsh,
+    Pwsh,
+    Cmd,
+    /// Any other POSIX-compatible shell, invoked as `<program> -c <command>`
+    Custom(String),
+}
+
+impl Shell {
+    /// `cmd` on Windows, `$SHELL` or `sh` elsewhere.
+    pub fn platform_default() -> Self {
+        if cfg!(windows) {
+            return Shell::Cmd;
+        }
+        match std::env::var("SHELL") {
+            Ok(path) => Self::from_program(&path),
+            Err(_) => Shell::Sh,
+        }
+    }
+
+    fn from_program(path: &str) -> Self {
+        let name = std::path::Path::new(path)
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .unwrap_or_default();
+        match name {
+            "sh" => Shell::Sh,
+            "bash" => Shell::Bash,
+            "zsh" => Shell::Zsh,
+            "pwsh" | "powershell" => Shell::Pwsh,
+            "cmd" => Shell::Cmd,
+            _ => Shell::Custom(path.to_string()),
+        }
+    }
+
+    pub fn program(&self) -> &str {
+        match self {
+            Shell::Sh => "sh",
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Pwsh => "pwsh",
+            Shell::Cmd => "cmd",
+            Shell::Custom(program) => program,
+        }
+    }
+
+    /// Builds a command that runs `cmd` through this shell.
+    pub fn command(&self, cmd: &str) -> Command {
+        let mut command = Command::new(self.program());
+        match self {
+            Shell::Pwsh => {
+                command.args(["-NoProfile", "-NonInteractive", "-Command", cmd]);
+            }
+            Shell::Cmd => {
+                // cmd.exe does not parse its command line with the CreateProcess
+                // rules, so the command must be passed through verbatim
+                #[cfg(windows)]
+                command.raw_arg("/S /C").raw_arg(format!("\"{}\"", cmd));
+                #[cfg(not(windows))]
+                command.args(["/S", "/C", cmd]);
+            }
+            _ => {
+                command.args(["-c", cmd]);
+            }
+        }
+        configure_command_no_window(&mut command);
+        command
+    }
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self::platform_default()
+    }
+}
+
+/// Builds a command that runs `program` directly with `args`, bypassing any shell.
+pub fn argv_command<I, S>(program: impl AsRef<OsStr>, args: I) -> Command
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    // On Windows `Command::args` quotes each argument following the
+    // CreateProcess / CommandLineToArgvW rules, see `quote_windows_arg`
+    let mut command = Command::new(program);
+    command.args(args);
+    configure_command_no_window(&mut command);
+    command
+}
+
+/// Runs `cmd` through `shell` and collects its output.
+pub async fn run_shell(shell: &Shell, cmd: &str) -> io::Result<Output> {
+    collect_output(shell.command(cmd)).await
+}
+
+/// Runs `program` with an explicit argument vector and collects its output.
+pub async fn run_argv<I, S>(program: impl AsRef<OsStr>, args: I) -> io::Result<Output>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    collect_output(argv_command(program, args)).await
+}
+
+async fn collect_output(mut command: Command) -> io::Result<Output> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+}
+
+/// Quotes one argument so `CommandLineToArgvW` parses it back unchanged.
+///
+/// Backslashes are literal unless they precede a double quote, in which case
+/// they must be doubled; embedded quotes are escaped with a backslash.
+pub fn quote_windows_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '\u{b}', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Backslashes before the closing quote must not escape it
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_windows_arg() {
+        assert_eq!(quote_windows_arg("plain"), "plain");
+        assert_eq!(quote_windows_arg(""), "\"\"");
+        assert_eq!(quote_windows_arg("with space"), "\"with space\"");
+        assert_eq!(quote_windows_arg(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(
+            quote_windows_arg(r"C:\dir with space\"),
+            r#""C:\dir with space\\""#
+        );
+        assert_eq!(quote_windows_arg(r#"a\"b"#), r#""a\\\"b""#);
+        assert_eq!(quote_windows_arg(r"C:\plain\path"), r"C:\plain\path");
+    }
+
+    #[test]
+    fn test_shell_from_program() {
+        assert_eq!(Shell::from_program("/bin/zsh"), Shell::Zsh);
+        assert_eq!(Shell::from_program("/usr/local/bin/bash"), Shell::Bash);
+        assert_eq!(
+            Shell::from_program("/opt/bin/fish"),
+            Shell::Custom("/opt/bin/fish".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_argv_passes_arguments_verbatim() {
+        let args = [
+            "with space",
+            r#"double "quoted""#,
+            "single 'quoted'",
+            "$HOME; echo pwned",
+        ];
+        let output = run_argv("printf", std::iter::once("%s\n").chain(args))
+            .await
+            .unwrap();
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().collect::<Vec<_>>(), args);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_shell_interprets_command() {
+        let output = run_shell(&Shell::Sh, "printf '%s' \"a b\" | tr ' ' '-'")
+            .await
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "a-b");
+    }
+}
@@ -0,0 +1,201 @@
+//! Teleport 消息压缩
+//!
+//! 超过阈值的消息负载使用 zstd 压缩后以 base64 字符串传输，
+//! 仅在双方协商确认都支持压缩后启用
+
+use super::types::RemoteMessage;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// 压缩算法名称（用于能力协商）
+pub const ZSTD_COMPRESSION: &str = "zstd";
+
+/// 消息压缩配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// 是否启用压缩
+    #[serde(default = "default_compression_enabled")]
+    pub enabled: bool,
+    /// 负载序列化后超过该字节数才压缩
+    #[serde(default = "default_compression_threshold")]
+    pub threshold: usize,
+    /// zstd 压缩级别
+    #[serde(default = "default_compression_level")]
+    pub level: i32,
+}
+
+fn default_compression_enabled() -> bool {
+    true
+}
+fn default_compression_threshold() -> usize {
+    16 * 1024
+}
+fn default_compression_level() -> i32 {
+    3
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_compression_enabled(),
+            threshold: default_compression_threshold(),
+            level: default_compression_level(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// 本端在握手中声明支持的算法
+    pub fn supported_algorithms(&self) -> Vec<String> {
+        if self.enabled {
+            vec![ZSTD_COMPRESSION.to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// 消息编解码器
+///
+/// 协商前只发送未压缩消息；解码始终接受压缩和未压缩消息
+#[derive(Debug, Clone)]
+pub struct MessageCodec {
+    config: CompressionConfig,
+    negotiated: bool,
+}
+
+impl MessageCodec {
+    /// 创建编解码器（尚未协商）
+    pub fn new(config: CompressionConfig) -> Self {
+        Self {
+            config,
+            negotiated: false,
+        }
+    }
+
+    /// 根据对端声明的算法协商，返回是否启用压缩
+    pub fn negotiate(&mut self, peer_algorithms: &[String]) -> bool {
+        self.negotiated =
+            self.config.enabled && peer_algorithms.iter().any(|a| a == ZSTD_COMPRESSION);
+        self.negotiated
+    }
+
+    /// 是否已协商启用压缩
+    pub fn compression_enabled(&self) -> bool {
+        self.negotiated
+    }
+
+    /// 编码待发送的消息，超过阈值且压缩后更小时压缩负载
+    pub fn encode(&self, message: RemoteMessage) -> anyhow::Result<RemoteMessage> {
+        if !self.negotiated || message.compressed {
+            return Ok(message);
+        }
+
+        let raw = serde_json::to_vec(&message.payload)?;
+        if raw.len() <= self.config.threshold {
+            return Ok(message);
+        }
+
+        let compressed = zstd::encode_all(raw.as_slice(), self.config.level)?;
+        let encoded = base64::prelude::BASE64_STANDARD.encode(compressed);
+        if encoded.len() >= raw.len() {
+            return Ok(message);
+        }
+
+        Ok(RemoteMessage {
+            payload: serde_json::Value::String(encoded),
+            compressed: true,
+            ..message
+        })
+    }
+
+    /// 解码收到的消息，还原压缩的负载
+    pub fn decode(&self, message: RemoteMessage) -> anyhow::Result<RemoteMessage> {
+        if !message.compressed {
+            return Ok(message);
+        }
+
+        let encoded = message
+            .payload
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("压缩消息的负载不是字符串"))?;
+        let compressed = base64::prelude::BASE64_STANDARD.decode(encoded)?;
+        let raw = zstd::decode_all(compressed.as_slice())?;
+
+        Ok(RemoteMessage {
+            payload: serde_json::from_slice(&raw)?,
+            compressed: false,
+            ..message
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::teleport::RemoteMessageType;
+
+    fn message(payload: serde_json::Value) -> RemoteMessage {
+        RemoteMessage {
+            message_type: RemoteMessageType::ToolResult,
+            id: Some("msg-1".to_string()),
+            session_id: "session-1".to_string(),
+            payload,
+            timestamp: "2026-01-14T00:00:00Z".to_string(),
+            compressed: false,
+            sequence: None,
+        }
+    }
+
+    fn negotiated_codec() -> MessageCodec {
+        let mut codec = MessageCodec::new(CompressionConfig::default());
+        assert!(codec.negotiate(&[ZSTD_COMPRESSION.to_string()]));
+        codec
+    }
+
+    #[test]
+    fn test_large_message_round_trip() {
+        let output = "line of tool output\n".repeat(5000);
+        let original = message(serde_json::json!({ "output": output, "exit_code": 0 }));
+        let codec = negotiated_codec();
+
+        let encoded = codec.encode(original.clone()).unwrap();
+        assert!(encoded.compressed);
+        assert!(encoded.payload.as_str().unwrap().len() < output.len());
+
+        // 经过序列化传输后解码
+        let wire = serde_json::to_string(&encoded).unwrap();
+        let received: RemoteMessage = serde_json::from_str(&wire).unwrap();
+        let decoded = codec.decode(received).unwrap();
+
+        assert!(!decoded.compressed);
+        assert_eq!(decoded.payload, original.payload);
+        assert_eq!(decoded.id, original.id);
+        assert_eq!(decoded.message_type, original.message_type);
+    }
+
+    #[test]
+    fn test_small_message_not_compressed() {
+        let codec = negotiated_codec();
+        let encoded = codec
+            .encode(message(serde_json::json!({ "text": "hi" })))
+            .unwrap();
+        assert!(!encoded.compressed);
+        assert_eq!(encoded.payload, serde_json::json!({ "text": "hi" }));
+    }
+
+    #[test]
+    fn test_falls_back_without_peer_support() {
+        let mut codec = MessageCodec::new(CompressionConfig::default());
+        assert!(!codec.negotiate(&[]));
+
+        let large = message(serde_json::json!({ "output": "x".repeat(100_000) }));
+        assert!(!codec.encode(large).unwrap().compressed);
+
+        let mut disabled = MessageCodec::new(CompressionConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        assert!(!disabled.negotiate(&[ZSTD_COMPRESSION.to_string()]));
+    }
+}
@@ -166,6 +166,8 @@ impl WebSocketManager {
                                 session_id: session_id.clone(),
                                 timestamp: chrono::Utc::now().to_rfc3339(),
                                 payload: serde_json::json!({}),
+                                compressed: false,
+                                sequence: None,
                             };
                             let _ = event_tx.send(ConnectionEvent::Message(heartbeat));
                         }
@@ -401,6 +403,8 @@ mod tests {
                 session_id: "test".to_string(),
                 payload: serde_json::json!({}),
                 timestamp: "2026-01-14".to_string(),
+                compressed: false,
+                sequence: None,
             }),
             ConnectionEvent::Error("error".to_string()),
         ];
@@ -424,6 +428,8 @@ mod tests {
             session_id: "test".to_string(),
             payload: serde_json::json!({}),
             timestamp: "2026-01-14".to_string(),
+            compressed: false,
+            sequence: None,
         };
         let result = manager.send(msg).await;
         assert!(result.is_err());
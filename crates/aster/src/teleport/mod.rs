@@ -5,24 +5,30 @@
 //! ## 功能
 //! - 远程会话连接（WebSocket）
 //! - 消息同步
+//! - 大消息压缩（zstd，协商后启用）
 //! - 仓库验证
-//! - 心跳和断线重连
+//! - 心跳和断线重连（离线消息排队与补发）
 
+mod compression;
 mod connection;
+mod queue;
 mod session;
 mod types;
 mod validation;
 
+pub use compression::{CompressionConfig, MessageCodec, ZSTD_COMPRESSION};
 pub use connection::{
     can_teleport_to_session, connect_to_remote_session, ConnectionConfig, ConnectionEvent,
     WebSocketManager,
 };
+pub use queue::{InboundSequencer, OfflineQueueConfig, OutboundQueue};
 pub use session::{create_remote_session, RemoteSession};
 pub use types::{
     ConnectionState, RemoteMessage, RemoteMessageType, RemoteSessionState, RepoValidationResult,
     RepoValidationStatus, SyncState, TeleportConfig, TeleportMetadata,
 };
 pub use validation::{
-    compare_repo_urls, get_current_branch, get_current_repo_url, is_working_directory_clean,
-    normalize_repo_url, validate_session_repository,
+    clear_repo_state_cache, compare_repo_urls, get_current_branch, get_current_repo_url,
+    get_repo_state, is_working_directory_clean, normalize_repo_url, validate_session_repository,
+    RepoState,
 };
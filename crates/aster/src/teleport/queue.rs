@@ -0,0 +1,338 @@
+//! Teleport 离线消息队列
+//!
+//! 断线期间缓存待发送消息，已发送的消息保留到对端确认为止，
+//! 重连后按原序号补发；接收端按序号去重
+//!
+//! 离线消息和未确认消息分别计数：在线发送不受离线队列容量限制，
+//! 未确认消息超过容量时丢弃最早的一条（对端不回确认时不会无限增长）
+
+use super::types::{RemoteMessage, RemoteMessageType};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// 离线队列配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineQueueConfig {
+    /// 最多缓存的离线消息数量，同时也是保留的未确认消息上限
+    #[serde(default = "default_queue_capacity")]
+    pub capacity: usize,
+    /// 持久化文件路径（为空时仅保存在内存中）
+    #[serde(default)]
+    pub persist_path: Option<PathBuf>,
+}
+
+fn default_queue_capacity() -> usize {
+    1000
+}
+
+impl Default for OfflineQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_queue_capacity(),
+            persist_path: None,
+        }
+    }
+}
+
+/// 是否为需要编号的业务消息（心跳和同步握手不编号、不缓存）
+pub fn is_sequenced(message_type: RemoteMessageType) -> bool {
+    !matches!(
+        message_type,
+        RemoteMessageType::Heartbeat
+            | RemoteMessageType::SyncRequest
+            | RemoteMessageType::SyncResponse
+    )
+}
+
+/// 持久化内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedQueue {
+    next_sequence: u64,
+    messages: Vec<RemoteMessage>,
+}
+
+/// 发送端队列：分配序号，缓存断线期间的消息和已发送但未确认的消息
+#[derive(Debug)]
+pub struct OutboundQueue {
+    config: OfflineQueueConfig,
+    next_sequence: u64,
+    /// 已发送、等待对端确认的消息
+    in_flight: VecDeque<RemoteMessage>,
+    /// 断线期间尚未发送的消息
+    pending: VecDeque<RemoteMessage>,
+}
+
+impl OutboundQueue {
+    /// 创建队列，配置了持久化路径时恢复上次未确认的消息
+    pub fn new(config: OfflineQueueConfig) -> Self {
+        let persisted = config
+            .persist_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<PersistedQueue>(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            config,
+            next_sequence: persisted.next_sequence.max(1),
+            in_flight: VecDeque::new(),
+            pending: persisted.messages.into(),
+        }
+    }
+
+    /// 为业务消息分配下一个序号
+    pub fn assign_sequence(&mut self, mut message: RemoteMessage) -> RemoteMessage {
+        if is_sequenced(message.message_type) && message.sequence.is_none() {
+            message.sequence = Some(self.next_sequence);
+            self.next_sequence += 1;
+            self.persist();
+        }
+        message
+    }
+
+    /// 缓存断线期间的消息，队列已满时返回错误
+    pub fn enqueue(&mut self, message: RemoteMessage) -> anyhow::Result<()> {
+        self.ensure_capacity()?;
+        let message = self.assign_sequence(message);
+        self.pending.push_back(message);
+        self.persist();
+        Ok(())
+    }
+
+    /// 为在线发送的消息分配序号，业务消息保留到对端确认为止
+    ///
+    /// 未确认消息达到上限时丢弃最早的一条，该消息重连后不再补发
+    pub fn send(&mut self, message: RemoteMessage) -> anyhow::Result<RemoteMessage> {
+        if !is_sequenced(message.message_type) {
+            return Ok(message);
+        }
+        let message = self.assign_sequence(message);
+        while self.in_flight.len() >= self.config.capacity.max(1) {
+            if let Some(dropped) = self.in_flight.pop_front() {
+                tracing::warn!(
+                    "未确认消息超过上限（{} 条），丢弃序号 {:?}",
+                    self.config.capacity,
+                    dropped.sequence
+                );
+            }
+        }
+        self.in_flight.push_back(message.clone());
+        self.persist();
+        Ok(message)
+    }
+
+    /// 对端确认已收到 `last_sequence` 及之前的消息，释放对应缓存
+    pub fn acknowledge(&mut self, last_sequence: u64) {
+        let before = self.in_flight.len() + self.pending.len();
+        let unacked = |m: &RemoteMessage| m.sequence.is_none_or(|seq| seq > last_sequence);
+        self.in_flight.retain(unacked);
+        self.pending.retain(unacked);
+        if self.in_flight.len() + self.pending.len() != before {
+            self.persist();
+        }
+    }
+
+    /// 对端确认已收到 `last_sequence` 及之前的消息，返回所有尚未确认的消息（按序号排列）
+    ///
+    /// 返回的消息仍保留在缓存中，直到对端再次确认
+    pub fn replay_after(&mut self, last_sequence: u64) -> Vec<RemoteMessage> {
+        self.acknowledge(last_sequence);
+        self.in_flight.extend(self.pending.drain(..));
+        self.persist();
+        self.in_flight.iter().cloned().collect()
+    }
+
+    /// 断线期间尚未发送的消息数量
+    pub fn depth(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// 已发送但尚未确认的消息数量
+    pub fn unacknowledged(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    fn ensure_capacity(&self) -> anyhow::Result<()> {
+        if self.pending.len() >= self.config.capacity {
+            anyhow::bail!("离线队列已满（{} 条）", self.config.capacity);
+        }
+        Ok(())
+    }
+
+    /// 下一个将分配的序号
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.config.persist_path else {
+            return;
+        };
+        let persisted = PersistedQueue {
+            next_sequence: self.next_sequence,
+            messages: self
+                .in_flight
+                .iter()
+                .chain(self.pending.iter())
+                .cloned()
+                .collect(),
+        };
+        let result = serde_json::to_string(&persisted)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, content)?;
+                Ok(())
+            });
+        if let Err(e) = result {
+            tracing::warn!("离线队列持久化失败: {}", e);
+        }
+    }
+}
+
+/// 接收端序号跟踪，丢弃已应用过的消息
+#[derive(Debug, Clone, Default)]
+pub struct InboundSequencer {
+    last_applied: u64,
+}
+
+impl InboundSequencer {
+    /// 判断消息是否应被应用；重复或过期的序号返回 false
+    pub fn accept(&mut self, message: &RemoteMessage) -> bool {
+        match message.sequence {
+            Some(seq) if seq <= self.last_applied => false,
+            Some(seq) => {
+                self.last_applied = seq;
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// 已应用的最大序号（重连时告知对端）
+    pub fn last_applied(&self) -> u64 {
+        self.last_applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(text: &str) -> RemoteMessage {
+        RemoteMessage {
+            message_type: RemoteMessageType::Message,
+            id: None,
+            session_id: "session-1".to_string(),
+            payload: serde_json::json!({ "text": text }),
+            timestamp: "2026-01-14T00:00:00Z".to_string(),
+            compressed: false,
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn test_enqueue_is_bounded() {
+        let mut queue = OutboundQueue::new(OfflineQueueConfig {
+            capacity: 2,
+            persist_path: None,
+        });
+        queue.enqueue(message("a")).unwrap();
+        queue.enqueue(message("b")).unwrap();
+        assert!(queue.enqueue(message("c")).is_err());
+        assert_eq!(queue.depth(), 2);
+    }
+
+    #[test]
+    fn test_replay_skips_acknowledged() {
+        let mut queue = OutboundQueue::new(OfflineQueueConfig::default());
+        for text in ["a", "b", "c"] {
+            queue.enqueue(message(text)).unwrap();
+        }
+
+        let replay = queue.replay_after(1);
+        let sequences: Vec<_> = replay.iter().map(|m| m.sequence.unwrap()).collect();
+        assert_eq!(sequences, vec![2, 3]);
+        assert_eq!(queue.depth(), 0);
+        assert_eq!(queue.unacknowledged(), 2);
+        assert_eq!(queue.next_sequence(), 4);
+
+        queue.acknowledge(3);
+        assert_eq!(queue.unacknowledged(), 0);
+    }
+
+    #[test]
+    fn test_sent_messages_kept_until_acknowledged() {
+        let mut queue = OutboundQueue::new(OfflineQueueConfig::default());
+        for text in ["a", "b", "c"] {
+            queue.send(message(text)).unwrap();
+        }
+        queue.acknowledge(1);
+        assert_eq!(queue.unacknowledged(), 2);
+
+        let replay = queue.replay_after(1);
+        let sequences: Vec<_> = replay.iter().map(|m| m.sequence.unwrap()).collect();
+        assert_eq!(sequences, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_unacknowledged_messages_do_not_block_sending() {
+        let mut queue = OutboundQueue::new(OfflineQueueConfig {
+            capacity: 2,
+            persist_path: None,
+        });
+        for text in ["a", "b", "c", "d"] {
+            queue.send(message(text)).unwrap();
+        }
+        assert_eq!(queue.unacknowledged(), 2);
+
+        // 未确认消息不占用离线队列容量
+        queue.enqueue(message("e")).unwrap();
+        queue.enqueue(message("f")).unwrap();
+        assert!(queue.enqueue(message("g")).is_err());
+
+        // 最早的未确认消息已被丢弃
+        let replay = queue.replay_after(0);
+        let sequences: Vec<_> = replay.iter().map(|m| m.sequence.unwrap()).collect();
+        assert_eq!(sequences, vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_persisted_queue_survives_restart() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = OfflineQueueConfig {
+            capacity: 10,
+            persist_path: Some(temp.path().join("queue.json")),
+        };
+
+        let mut queue = OutboundQueue::new(config.clone());
+        queue.enqueue(message("a")).unwrap();
+        queue.enqueue(message("b")).unwrap();
+        drop(queue);
+
+        let mut restored = OutboundQueue::new(config);
+        assert_eq!(restored.depth(), 2);
+        assert_eq!(restored.next_sequence(), 3);
+        let replay = restored.replay_after(0);
+        assert_eq!(replay[0].payload["text"], "a");
+        assert_eq!(replay[1].payload["text"], "b");
+    }
+
+    #[test]
+    fn test_inbound_sequencer_dedupes() {
+        let mut sequencer = InboundSequencer::default();
+        let mut first = message("a");
+        first.sequence = Some(1);
+        let mut second = message("b");
+        second.sequence = Some(2);
+
+        assert!(sequencer.accept(&first));
+        assert!(sequencer.accept(&second));
+        assert!(!sequencer.accept(&first));
+        assert!(!sequencer.accept(&second));
+        assert_eq!(sequencer.last_applied(), 2);
+    }
+}
@@ -2,10 +2,14 @@
 //!
 //! 通过 WebSocket 连接到远程会话
 
+use super::compression::MessageCodec;
+use super::connection::ConnectionEvent;
+use super::queue::{is_sequenced, InboundSequencer, OutboundQueue};
 use super::types::*;
 use super::validation::validate_session_repository;
-use std::sync::{Arc, RwLock};
-use tokio::sync::mpsc;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
 
 /// 远程会话
 pub struct RemoteSession {
@@ -17,19 +21,32 @@ pub struct RemoteSession {
     message_tx: Option<mpsc::Sender<RemoteMessage>>,
     /// 消息接收器
     message_rx: Option<mpsc::Receiver<RemoteMessage>>,
+    /// 消息压缩编解码器
+    codec: MessageCodec,
+    /// 发送序号、未确认消息与离线队列（重连时保留）
+    outbound: Mutex<OutboundQueue>,
+    /// 已应用的远程消息序号
+    inbound: InboundSequencer,
 }
 
 impl RemoteSession {
     /// 创建新的远程会话
     pub fn new(config: TeleportConfig) -> Self {
+        let outbound = OutboundQueue::new(config.offline_queue.clone());
         let state = RemoteSessionState {
             connection_state: ConnectionState::Disconnected,
-            sync_state: SyncState::default(),
+            sync_state: SyncState {
+                queue_depth: outbound.depth(),
+                ..Default::default()
+            },
             config: config.clone(),
             error: None,
         };
 
         Self {
+            codec: MessageCodec::new(config.compression.clone()),
+            outbound: Mutex::new(outbound),
+            inbound: InboundSequencer::default(),
             config,
             state: Arc::new(RwLock::new(state)),
             message_tx: None,
@@ -90,18 +107,160 @@ impl RemoteSession {
     pub async fn disconnect(&mut self) {
         self.message_tx = None;
         self.message_rx = None;
+        // 重连后需要重新协商压缩
+        self.codec = MessageCodec::new(self.config.compression.clone());
         self.set_connection_state(ConnectionState::Disconnected);
     }
 
-    /// 发送消息
+    /// 发送消息（协商启用压缩后，大负载会被压缩）
+    ///
+    /// 断线期间业务消息进入离线队列；已发送的业务消息保留到对端确认，
+    /// 重连同步后连同离线消息一起按原序号补发
     pub async fn send_message(&self, message: RemoteMessage) -> anyhow::Result<()> {
+        let Some(tx) = &self.message_tx else {
+            if !is_sequenced(message.message_type) {
+                anyhow::bail!("未连接到远程会话");
+            }
+            let depth = {
+                let mut outbound = self.outbound.lock().unwrap();
+                outbound.enqueue(message)?;
+                outbound.depth()
+            };
+            self.set_queue_depth(depth);
+            return Ok(());
+        };
+
+        let message = self.outbound.lock().unwrap().send(message)?;
+        tx.send(self.codec.encode(message)?).await?;
+        Ok(())
+    }
+
+    /// 处理收到的消息：解压负载，并根据同步响应协商压缩、补发离线消息
+    ///
+    /// 已应用过的重复消息返回 `None`
+    pub async fn receive_message(
+        &mut self,
+        message: RemoteMessage,
+    ) -> anyhow::Result<Option<RemoteMessage>> {
+        let message = self.codec.decode(message)?;
+        if !self.inbound.accept(&message) {
+            return Ok(None);
+        }
+
+        if message.message_type == RemoteMessageType::Heartbeat {
+            if let Some(last_received) = last_received_sequence(&message) {
+                self.outbound.lock().unwrap().acknowledge(last_received);
+            }
+        }
+
+        if message.message_type == RemoteMessageType::SyncResponse {
+            let peer_algorithms: Vec<String> = message
+                .payload
+                .get("compression")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+            self.codec.negotiate(&peer_algorithms);
+
+            // 对端已收到的最大序号，从其后开始补发
+            let last_received = last_received_sequence(&message).unwrap_or(0);
+            self.replay_queued(last_received).await?;
+        }
+
+        Ok(Some(message))
+    }
+
+    /// 处理连接事件：收到的消息经 `receive_message` 处理心跳确认与同步补发，
+    /// 连接建立后请求同步，断开后转入离线排队
+    ///
+    /// 返回需要交给上层应用的远程消息
+    pub async fn handle_connection_event(
+        &mut self,
+        event: ConnectionEvent,
+    ) -> anyhow::Result<Option<RemoteMessage>> {
+        match event {
+            ConnectionEvent::Message(message) => self.receive_message(message).await,
+            ConnectionEvent::Connected => {
+                if self.message_tx.is_some() {
+                    self.set_connection_state(ConnectionState::Connected);
+                    self.request_sync().await?;
+                }
+                Ok(None)
+            }
+            ConnectionEvent::Disconnected => {
+                self.disconnect().await;
+                Ok(None)
+            }
+            ConnectionEvent::Reconnecting { .. } => {
+                self.set_connection_state(ConnectionState::Connecting);
+                Ok(None)
+            }
+            ConnectionEvent::Error(error) => {
+                self.set_error(&error);
+                Ok(None)
+            }
+        }
+    }
+
+    /// 持续处理连接事件（如 `WebSocketManager::subscribe` 的事件流），
+    /// 远程消息转发到 `applied_tx`；事件流或接收方关闭时结束
+    pub async fn run_connection_events(
+        &mut self,
+        mut events: broadcast::Receiver<ConnectionEvent>,
+        applied_tx: mpsc::Sender<RemoteMessage>,
+    ) {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("连接事件积压，跳过 {} 条", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            match self.handle_connection_event(event).await {
+                Ok(Some(message)) => {
+                    if applied_tx.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("处理连接事件失败: {}", e),
+            }
+        }
+    }
+
+    /// 补发对端尚未确认的消息（断线前已发送的和离线队列中的）
+    async fn replay_queued(&self, last_received: u64) -> anyhow::Result<()> {
         let Some(tx) = &self.message_tx else {
             anyhow::bail!("未连接到远程会话");
         };
-        tx.send(message).await?;
+
+        let replay = self.outbound.lock().unwrap().replay_after(last_received);
+        for message in replay {
+            tx.send(self.codec.encode(message)?).await?;
+        }
+
+        self.set_queue_depth(0);
+        if let Ok(mut s) = self.state.write() {
+            s.connection_state = ConnectionState::Connected;
+            s.sync_state.syncing = false;
+            s.sync_state.last_sync_time = Some(chrono::Utc::now().to_rfc3339());
+        }
         Ok(())
     }
 
+    /// 更新离线队列深度
+    fn set_queue_depth(&self, depth: usize) {
+        if let Ok(mut s) = self.state.write() {
+            s.sync_state.queue_depth = depth;
+        }
+    }
+
+    /// 是否已与对端协商启用压缩
+    pub fn compression_enabled(&self) -> bool {
+        self.codec.compression_enabled()
+    }
+
     /// 获取当前状态
     pub fn get_state(&self) -> RemoteSessionState {
         self.state
@@ -148,13 +307,22 @@ impl RemoteSession {
         }
 
         self.set_connection_state(ConnectionState::Syncing);
+        if let Ok(mut s) = self.state.write() {
+            s.sync_state.syncing = true;
+        }
 
         let sync_request = RemoteMessage {
             message_type: RemoteMessageType::SyncRequest,
             id: None,
             session_id: self.config.session_id.clone(),
             timestamp: chrono::Utc::now().to_rfc3339(),
-            payload: serde_json::json!({}),
+            payload: serde_json::json!({
+                "compression": self.config.compression.supported_algorithms(),
+                "last_received_sequence": self.inbound.last_applied(),
+                "next_sequence": self.outbound.lock().unwrap().next_sequence(),
+            }),
+            compressed: false,
+            sequence: None,
         };
 
         self.send_message(sync_request).await?;
@@ -162,7 +330,197 @@ impl RemoteSession {
     }
 }
 
+/// 对端在同步响应和心跳中携带的已收到最大序号
+fn last_received_sequence(message: &RemoteMessage) -> Option<u64> {
+    message
+        .payload
+        .get("last_received_sequence")
+        .and_then(|v| v.as_u64())
+}
+
 /// 创建远程会话
 pub fn create_remote_session(config: TeleportConfig) -> RemoteSession {
     RemoteSession::new(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TeleportConfig {
+        TeleportConfig {
+            session_id: "session-1".to_string(),
+            ingress_url: Some("wss://example.com".to_string()),
+            auth_token: None,
+            metadata: None,
+            compression: Default::default(),
+            offline_queue: Default::default(),
+        }
+    }
+
+    fn message(text: &str) -> RemoteMessage {
+        RemoteMessage {
+            message_type: RemoteMessageType::Message,
+            id: None,
+            session_id: "session-1".to_string(),
+            payload: serde_json::json!({ "text": text }),
+            timestamp: "2026-01-14T00:00:00Z".to_string(),
+            compressed: false,
+            sequence: None,
+        }
+    }
+
+    fn sync_response(last_received_sequence: u64) -> RemoteMessage {
+        RemoteMessage {
+            message_type: RemoteMessageType::SyncResponse,
+            payload: serde_json::json!({ "last_received_sequence": last_received_sequence }),
+            ..message("")
+        }
+    }
+
+    fn drain_sent(session: &mut RemoteSession) -> Vec<RemoteMessage> {
+        let rx = session.message_rx.as_mut().unwrap();
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_queue_and_replay_in_order() {
+        let mut session = RemoteSession::new(config());
+        session.connect().await.unwrap();
+        session.send_message(message("one")).await.unwrap();
+        let sent = drain_sent(&mut session);
+        assert_eq!(sent[0].sequence, Some(1));
+
+        session.disconnect().await;
+        for text in ["two", "three", "four"] {
+            session.send_message(message(text)).await.unwrap();
+        }
+        assert_eq!(session.get_state().sync_state.queue_depth, 3);
+
+        session.connect().await.unwrap();
+        session.request_sync().await.unwrap();
+        let sync_request = drain_sent(&mut session).remove(0);
+        assert_eq!(sync_request.message_type, RemoteMessageType::SyncRequest);
+        assert_eq!(sync_request.payload["next_sequence"], 5);
+
+        // 对端已收到 "two"，只补发其后的消息
+        session
+            .receive_message(sync_response(2))
+            .await
+            .unwrap()
+            .unwrap();
+        let replayed = drain_sent(&mut session);
+        let texts: Vec<_> = replayed
+            .iter()
+            .map(|m| m.payload["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(texts, vec!["three", "four"]);
+        assert_eq!(replayed[0].sequence, Some(3));
+        assert_eq!(replayed[1].sequence, Some(4));
+
+        let state = session.get_state();
+        assert_eq!(state.sync_state.queue_depth, 0);
+        assert!(session.is_connected());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_mid_stream_replays_unacknowledged() {
+        let mut session = RemoteSession::new(config());
+        session.connect().await.unwrap();
+        for text in ["one", "two", "three"] {
+            session.send_message(message(text)).await.unwrap();
+        }
+        let sent = drain_sent(&mut session);
+        assert_eq!(sent.len(), 3);
+
+        // 对端只确认了 "one"，随后连接中断
+        let heartbeat = RemoteMessage {
+            message_type: RemoteMessageType::Heartbeat,
+            payload: serde_json::json!({ "last_received_sequence": 1 }),
+            ..message("")
+        };
+        session.receive_message(heartbeat).await.unwrap();
+        session.disconnect().await;
+        for text in ["four", "five"] {
+            session.send_message(message(text)).await.unwrap();
+        }
+
+        session.connect().await.unwrap();
+        session.request_sync().await.unwrap();
+        let sync_request = drain_sent(&mut session).remove(0);
+        assert_eq!(sync_request.payload["next_sequence"], 6);
+
+        session
+            .receive_message(sync_response(1))
+            .await
+            .unwrap()
+            .unwrap();
+        let replayed = drain_sent(&mut session);
+        let texts: Vec<_> = replayed
+            .iter()
+            .map(|m| m.payload["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(texts, vec!["two", "three", "four", "five"]);
+        let sequences: Vec<_> = replayed.iter().map(|m| m.sequence.unwrap()).collect();
+        assert_eq!(sequences, vec![2, 3, 4, 5]);
+
+        // 重连后新消息继续沿用序号
+        session.send_message(message("six")).await.unwrap();
+        assert_eq!(drain_sent(&mut session)[0].sequence, Some(6));
+    }
+
+    #[tokio::test]
+    async fn test_connection_events_feed_acks_and_replay() {
+        let mut session = RemoteSession::new(config());
+        session.connect().await.unwrap();
+        for text in ["one", "two", "three"] {
+            session.send_message(message(text)).await.unwrap();
+        }
+        drain_sent(&mut session);
+
+        let (event_tx, event_rx) = broadcast::channel(16);
+        let (applied_tx, mut applied_rx) = mpsc::channel(16);
+        let heartbeat = RemoteMessage {
+            message_type: RemoteMessageType::Heartbeat,
+            payload: serde_json::json!({ "last_received_sequence": 2 }),
+            ..message("")
+        };
+        event_tx.send(ConnectionEvent::Message(heartbeat)).unwrap();
+        event_tx.send(ConnectionEvent::Connected).unwrap();
+        event_tx
+            .send(ConnectionEvent::Message(sync_response(2)))
+            .unwrap();
+        drop(event_tx);
+
+        session.run_connection_events(event_rx, applied_tx).await;
+
+        let sent = drain_sent(&mut session);
+        assert_eq!(sent[0].message_type, RemoteMessageType::SyncRequest);
+        let replayed: Vec<_> = sent[1..]
+            .iter()
+            .map(|m| m.payload["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(replayed, vec!["three"]);
+        assert_eq!(
+            applied_rx.recv().await.unwrap().message_type,
+            RemoteMessageType::Heartbeat
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_remote_messages_are_dropped() {
+        let mut session = RemoteSession::new(config());
+        session.connect().await.unwrap();
+
+        let remote = RemoteMessage {
+            sequence: Some(7),
+            ..message("remote")
+        };
+        assert!(session
+            .receive_message(remote.clone())
+            .await
+            .unwrap()
+            .is_some());
+        assert!(session.receive_message(remote).await.unwrap().is_none());
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! 远程会话连接的数据结构
 
+use super::compression::CompressionConfig;
+use super::queue::OfflineQueueConfig;
 use serde::{Deserialize, Serialize};
 
 /// 远程会话配置
@@ -15,6 +17,12 @@ pub struct TeleportConfig {
     pub auth_token: Option<String>,
     /// 会话元数据
     pub metadata: Option<TeleportMetadata>,
+    /// 消息压缩配置
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// 离线消息队列配置
+    #[serde(default)]
+    pub offline_queue: OfflineQueueConfig,
 }
 
 /// 会话元数据
@@ -55,6 +63,9 @@ pub struct RepoValidationResult {
     pub current_repo: Option<String>,
     /// 错误消息
     pub error_message: Option<String>,
+    /// 仓库信息是否来自缓存
+    #[serde(default)]
+    pub from_cache: bool,
 }
 
 /// 远程消息类型
@@ -90,6 +101,12 @@ pub struct RemoteMessage {
     pub payload: serde_json::Value,
     /// 时间戳
     pub timestamp: String,
+    /// 负载是否经过压缩（zstd + base64 字符串）
+    #[serde(default)]
+    pub compressed: bool,
+    /// 业务消息序号，用于重连补发和去重
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
 }
 
 /// 同步状态
@@ -103,6 +120,9 @@ pub struct SyncState {
     pub synced_messages: u32,
     /// 同步错误
     pub sync_error: Option<String>,
+    /// 离线队列中待补发的消息数量
+    #[serde(default)]
+    pub queue_depth: usize,
 }
 
 /// 连接状态
@@ -151,6 +171,8 @@ mod tests {
                 created_at: Some("2026-01-14".to_string()),
                 updated_at: None,
             }),
+            compression: CompressionConfig::default(),
+            offline_queue: OfflineQueueConfig::default(),
         };
         assert_eq!(config.session_id, "test-session");
         assert!(config.ingress_url.is_some());
@@ -181,6 +203,7 @@ mod tests {
             session_repo: Some("repo1".to_string()),
             current_repo: Some("repo1".to_string()),
             error_message: None,
+            from_cache: false,
         };
         assert_eq!(result.status, RepoValidationStatus::Match);
     }
@@ -207,6 +230,8 @@ mod tests {
             session_id: "session-1".to_string(),
             payload: serde_json::json!({"text": "hello"}),
             timestamp: "2026-01-14T00:00:00Z".to_string(),
+            compressed: false,
+            sequence: None,
         };
         assert_eq!(msg.message_type, RemoteMessageType::Message);
         assert_eq!(msg.session_id, "session-1");
@@ -249,6 +274,8 @@ mod tests {
                 ingress_url: None,
                 auth_token: None,
                 metadata: None,
+                compression: CompressionConfig::default(),
+                offline_queue: OfflineQueueConfig::default(),
             },
             error: None,
         };
@@ -3,12 +3,157 @@
 //! 确保远程会话在正确的 Git 仓库中运行
 
 use super::types::{RepoValidationResult, RepoValidationStatus};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 use tokio::process::Command;
 
+/// 仓库信息缓存，键为 `.git` 目录
+static REPO_STATE_CACHE: Lazy<Mutex<HashMap<PathBuf, CachedRepoState>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 仓库信息（远程 URL 与当前分支）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoState {
+    /// 远程 origin URL
+    pub repo_url: Option<String>,
+    /// 当前分支
+    pub branch: Option<String>,
+    /// 是否来自缓存
+    pub from_cache: bool,
+}
+
+#[derive(Debug)]
+struct CachedRepoState {
+    fingerprint: RepoFingerprint,
+    repo_url: Option<String>,
+    branch: Option<String>,
+}
+
+/// 仓库状态指纹：HEAD、HEAD 指向的引用、index 和 config 任一变化即失效
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RepoFingerprint {
+    head: Option<String>,
+    head_target: Option<String>,
+    head_mtime: Option<SystemTime>,
+    index_mtime: Option<SystemTime>,
+    config_mtime: Option<SystemTime>,
+}
+
+/// 从 `dir` 向上查找 `.git` 目录，支持 worktree 的 `.git` 文件
+fn find_git_dir(dir: &Path) -> Option<PathBuf> {
+    for ancestor in dir.ancestors() {
+        let candidate = ancestor.join(".git");
+        if candidate.is_dir() {
+            return Some(candidate);
+        }
+        if candidate.is_file() {
+            let content = std::fs::read_to_string(&candidate).ok()?;
+            let git_dir = PathBuf::from(content.trim().strip_prefix("gitdir:")?.trim());
+            return Some(if git_dir.is_absolute() {
+                git_dir
+            } else {
+                ancestor.join(git_dir)
+            });
+        }
+    }
+    None
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl RepoFingerprint {
+    fn read(git_dir: &Path) -> Self {
+        // worktree 的 config 位于主仓库（commondir）中
+        let common_dir = std::fs::read_to_string(git_dir.join("commondir"))
+            .map(|dir| git_dir.join(dir.trim()))
+            .unwrap_or_else(|_| git_dir.to_path_buf());
+
+        let head = std::fs::read_to_string(git_dir.join("HEAD")).ok();
+        let head_target = head
+            .as_deref()
+            .and_then(|h| h.trim().strip_prefix("ref:"))
+            .and_then(|r| {
+                let r = r.trim();
+                std::fs::read_to_string(git_dir.join(r))
+                    .or_else(|_| std::fs::read_to_string(common_dir.join(r)))
+                    .ok()
+            });
+
+        Self {
+            head,
+            head_target,
+            head_mtime: modified(&git_dir.join("HEAD")),
+            index_mtime: modified(&git_dir.join("index")),
+            config_mtime: modified(&common_dir.join("config")),
+        }
+    }
+}
+
+/// 获取 `dir` 所在仓库的远程 URL 和当前分支
+///
+/// 结果按 `.git` 目录缓存，HEAD、index 或 config 变化后重新调用 git
+pub async fn get_repo_state(dir: &Path) -> RepoState {
+    let Some(git_dir) = find_git_dir(dir) else {
+        return RepoState {
+            repo_url: repo_url_in(dir).await,
+            branch: branch_in(dir).await,
+            from_cache: false,
+        };
+    };
+
+    let fingerprint = RepoFingerprint::read(&git_dir);
+    if let Some(cached) = REPO_STATE_CACHE.lock().unwrap().get(&git_dir) {
+        if cached.fingerprint == fingerprint {
+            return RepoState {
+                repo_url: cached.repo_url.clone(),
+                branch: cached.branch.clone(),
+                from_cache: true,
+            };
+        }
+    }
+
+    let repo_url = repo_url_in(dir).await;
+    let branch = branch_in(dir).await;
+    REPO_STATE_CACHE.lock().unwrap().insert(
+        git_dir,
+        CachedRepoState {
+            fingerprint,
+            repo_url: repo_url.clone(),
+            branch: branch.clone(),
+        },
+    );
+
+    RepoState {
+        repo_url,
+        branch,
+        from_cache: false,
+    }
+}
+
+/// 清空仓库信息缓存
+pub fn clear_repo_state_cache() {
+    REPO_STATE_CACHE.lock().unwrap().clear();
+}
+
+async fn current_repo_state() -> RepoState {
+    let dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    get_repo_state(&dir).await
+}
+
 /// 获取当前 Git 仓库远程 URL
 pub async fn get_current_repo_url() -> Option<String> {
+    current_repo_state().await.repo_url
+}
+
+async fn repo_url_in(dir: &Path) -> Option<String> {
     let output = Command::new("git")
         .args(["config", "--get", "remote.origin.url"])
+        .current_dir(dir)
         .output()
         .await
         .ok()?;
@@ -66,11 +211,13 @@ pub async fn validate_session_repository(session_repo: Option<&str>) -> RepoVali
             session_repo: None,
             current_repo: None,
             error_message: None,
+            from_cache: false,
         };
     };
 
     // 获取当前仓库
-    let current_repo = match get_current_repo_url().await {
+    let state = current_repo_state().await;
+    let current_repo = match state.repo_url {
         Some(repo) => repo,
         None => {
             return RepoValidationResult {
@@ -78,32 +225,35 @@ pub async fn validate_session_repository(session_repo: Option<&str>) -> RepoVali
                 session_repo: Some(session_repo.to_string()),
                 current_repo: None,
                 error_message: Some("当前目录不是 git 仓库".to_string()),
+                from_cache: state.from_cache,
             };
         }
     };
 
     // 比较仓库
-    if compare_repo_urls(session_repo, &current_repo) {
-        RepoValidationResult {
-            status: RepoValidationStatus::Match,
-            session_repo: Some(session_repo.to_string()),
-            current_repo: Some(current_repo),
-            error_message: None,
-        }
+    let status = if compare_repo_urls(session_repo, &current_repo) {
+        RepoValidationStatus::Match
     } else {
-        RepoValidationResult {
-            status: RepoValidationStatus::Mismatch,
-            session_repo: Some(session_repo.to_string()),
-            current_repo: Some(current_repo),
-            error_message: None,
-        }
+        RepoValidationStatus::Mismatch
+    };
+    RepoValidationResult {
+        status,
+        session_repo: Some(session_repo.to_string()),
+        current_repo: Some(current_repo),
+        error_message: None,
+        from_cache: state.from_cache,
     }
 }
 
 /// 获取当前分支名
 pub async fn get_current_branch() -> Option<String> {
+    current_repo_state().await.branch
+}
+
+async fn branch_in(dir: &Path) -> Option<String> {
     let output = Command::new("git")
         .args(["branch", "--show-current"])
+        .current_dir(dir)
         .output()
         .await
         .ok()?;
@@ -121,6 +271,8 @@ pub async fn get_current_branch() -> Option<String> {
 }
 
 /// 检查工作目录是否干净
+///
+/// 工作区编辑不会改变 HEAD 或 index，因此该检查不缓存，每次都调用 git
 pub async fn is_working_directory_clean() -> bool {
     let output = Command::new("git")
         .args(["status", "--porcelain"])
@@ -205,6 +357,54 @@ mod tests {
         println!("Current branch: {:?}", branch);
     }
 
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[tokio::test]
+    async fn test_commit_invalidates_cache() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(
+            dir,
+            &["remote", "add", "origin", "git@github.com:user/repo.git"],
+        );
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "first"]);
+
+        let first = get_repo_state(dir).await;
+        assert!(!first.from_cache);
+        assert_eq!(first.branch.as_deref(), Some("main"));
+        assert_eq!(
+            first.repo_url.as_deref(),
+            Some("git@github.com:user/repo.git")
+        );
+
+        let cached = get_repo_state(dir).await;
+        assert!(cached.from_cache);
+        assert_eq!(cached.branch.as_deref(), Some("main"));
+
+        // 暂存会修改 index，先刷新缓存，只验证提交本身使缓存失效
+        std::fs::write(dir.join("b.txt"), "b").unwrap();
+        git(dir, &["add", "."]);
+        assert!(!get_repo_state(dir).await.from_cache);
+        assert!(get_repo_state(dir).await.from_cache);
+
+        git(dir, &["commit", "-q", "-m", "second"]);
+
+        let after_commit = get_repo_state(dir).await;
+        assert!(!after_commit.from_cache);
+        assert_eq!(after_commit.branch.as_deref(), Some("main"));
+    }
+
     #[tokio::test]
     async fn test_is_working_directory_clean() {
         let clean = is_working_directory_clean().await;
@@ -11,7 +11,10 @@
 //! Requirements: 1.1, 1.2
 
 use async_trait::async_trait;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use super::context::{ToolContext, ToolDefinition, ToolOptions, ToolResult};
 use super::error::ToolError;
@@ -138,6 +141,15 @@ pub trait Tool: Send + Sync {
     /// - Providing parameter information to the LLM
     fn input_schema(&self) -> serde_json::Value;
 
+    /// Validate input parameters against `input_schema()`
+    ///
+    /// Called by the registry before permission checks and execution.
+    /// Default implementation validates with JSON Schema and reports every
+    /// violation with its location in the input.
+    fn validate_input(&self, params: &serde_json::Value) -> Result<(), ToolError> {
+        validate_against_schema(&self.input_schema(), params)
+    }
+
     /// Execute the tool with the given parameters and context
     ///
     /// This is the main entry point for tool execution.
@@ -205,6 +217,148 @@ pub trait Tool: Send + Sync {
     }
 }
 
+/// Compiled validators keyed by schema text; `None` marks a schema that failed to compile
+static VALIDATORS: Lazy<Mutex<HashMap<String, Option<Arc<jsonschema::Validator>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Compile a schema once and reuse the validator for later calls
+fn cached_validator(schema: &serde_json::Value) -> Option<Arc<jsonschema::Validator>> {
+    let mut validators = VALIDATORS.lock().unwrap();
+    validators
+        .entry(schema.to_string())
+        .or_insert_with(|| match jsonschema::validator_for(schema) {
+            Ok(validator) => Some(Arc::new(validator)),
+            Err(e) => {
+                tracing::warn!("Skipping input validation, invalid schema: {}", e);
+                None
+            }
+        })
+        .clone()
+}
+
+/// Validate parameters against a JSON Schema
+///
+/// Returns `ToolError::InvalidParams` listing each violation. A schema that
+/// fails to compile is logged and skipped, so a malformed schema (e.g. from
+/// an MCP server) does not block the tool. Compiled schemas are cached.
+pub fn validate_against_schema(
+    schema: &serde_json::Value,
+    params: &serde_json::Value,
+) -> Result<(), ToolError> {
+    let Some(validator) = cached_validator(schema) else {
+        return Ok(());
+    };
+
+    let errors: Vec<String> = validator
+        .iter_errors(params)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{} at '{}'", e, path)
+            }
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ToolError::invalid_params(errors.join("; ")))
+    }
+}
+
+/// Coerce obvious JSON type mismatches in parameters to match a schema
+///
+/// Converts string values to integers, numbers or booleans where the schema
+/// expects one and the string is an exact, lossless representation
+/// (`"5"`, `"2.5"`, `"true"`). Fields that also accept strings, and
+/// ambiguous values such as `"5.0"` for an integer or `"yes"` for a boolean,
+/// are left untouched for validation to report.
+///
+/// Returns the JSON pointer of every coerced value.
+pub fn coerce_to_schema(schema: &serde_json::Value, params: &mut serde_json::Value) -> Vec<String> {
+    let mut coerced = Vec::new();
+    coerce_value(schema, params, String::new(), &mut coerced);
+    coerced
+}
+
+fn coerce_value(
+    schema: &serde_json::Value,
+    value: &mut serde_json::Value,
+    path: String,
+    coerced: &mut Vec<String>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+                return;
+            };
+            for (key, field) in map.iter_mut() {
+                if let Some(field_schema) = properties.get(key) {
+                    coerce_value(field_schema, field, format!("{}/{}", path, key), coerced);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            let Some(item_schema) = schema.get("items") else {
+                return;
+            };
+            for (index, item) in items.iter_mut().enumerate() {
+                coerce_value(item_schema, item, format!("{}/{}", path, index), coerced);
+            }
+        }
+        serde_json::Value::String(text) => {
+            if let Some(converted) = coerce_string(schema, text) {
+                *value = converted;
+                coerced.push(path);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn coerce_string(schema: &serde_json::Value, text: &str) -> Option<serde_json::Value> {
+    let types: Vec<&str> = match schema.get("type")? {
+        serde_json::Value::String(t) => vec![t.as_str()],
+        serde_json::Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+        _ => return None,
+    };
+    if types.contains(&"string") {
+        return None;
+    }
+
+    if types.contains(&"integer") {
+        if let Ok(n) = text.parse::<i64>() {
+            return Some(serde_json::Value::from(n));
+        }
+    }
+    if types.contains(&"number") {
+        if let Ok(n) = text.parse::<i64>() {
+            return Some(serde_json::Value::from(n));
+        }
+        // Reject forms like "inf", "NaN" or " 1.5" that f64 parsing would accept or JSON cannot hold
+        let looks_numeric = text
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'));
+        // Integers too large for i64 would lose precision as f64
+        let is_fractional = text.contains(['.', 'e', 'E']);
+        if let Ok(n) = text.parse::<f64>() {
+            if looks_numeric && is_fractional && n.is_finite() {
+                return serde_json::Number::from_f64(n).map(serde_json::Value::Number);
+            }
+        }
+    }
+    if types.contains(&"boolean") {
+        match text {
+            "true" => return Some(serde_json::Value::Bool(true)),
+            "false" => return Some(serde_json::Value::Bool(false)),
+            _ => {}
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -392,4 +546,82 @@ mod tests {
         assert_eq!(result.behavior, deserialized.behavior);
         assert_eq!(result.message, deserialized.message);
     }
+
+    fn coercion_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "count": { "type": "integer" },
+                "ratio": { "type": "number" },
+                "enabled": { "type": "boolean" },
+                "limit": { "type": ["integer", "null"] },
+                "label": { "type": ["string", "integer"] },
+                "ids": { "type": "array", "items": { "type": "integer" } }
+            }
+        })
+    }
+
+    #[test]
+    fn test_coerce_string_to_integer() {
+        let mut params = serde_json::json!({"count": "5", "limit": "-3", "ids": ["1", "2"]});
+        let coerced = coerce_to_schema(&coercion_schema(), &mut params);
+        assert_eq!(
+            params,
+            serde_json::json!({"count": 5, "limit": -3, "ids": [1, 2]})
+        );
+        assert_eq!(coerced.len(), 4);
+        assert!(coerced.contains(&"/ids/1".to_string()));
+    }
+
+    #[test]
+    fn test_coerce_string_to_number() {
+        let mut params = serde_json::json!({"ratio": "2.5"});
+        let coerced = coerce_to_schema(&coercion_schema(), &mut params);
+        assert_eq!(params, serde_json::json!({"ratio": 2.5}));
+        assert_eq!(coerced, vec!["/ratio".to_string()]);
+
+        let mut params = serde_json::json!({"ratio": "7"});
+        coerce_to_schema(&coercion_schema(), &mut params);
+        assert_eq!(params, serde_json::json!({"ratio": 7}));
+    }
+
+    #[test]
+    fn test_coerce_string_to_boolean() {
+        let mut params = serde_json::json!({"enabled": "false"});
+        let coerced = coerce_to_schema(&coercion_schema(), &mut params);
+        assert_eq!(params, serde_json::json!({"enabled": false}));
+        assert_eq!(coerced, vec!["/enabled".to_string()]);
+    }
+
+    #[test]
+    fn test_coerce_refuses_ambiguous_or_lossy_values() {
+        let original = serde_json::json!({
+            "count": "5.0",
+            "ratio": "NaN",
+            "enabled": "yes",
+            "label": "42",
+            "ids": ["1x"]
+        });
+        let mut params = original.clone();
+        let coerced = coerce_to_schema(&coercion_schema(), &mut params);
+        assert!(coerced.is_empty());
+        assert_eq!(params, original);
+
+        let mut params = serde_json::json!({"ratio": "99999999999999999999"});
+        assert!(coerce_to_schema(&coercion_schema(), &mut params).is_empty());
+
+        assert!(validate_against_schema(&coercion_schema(), &original).is_err());
+    }
+
+    #[test]
+    fn test_validator_is_compiled_once() {
+        let schema = coercion_schema();
+        let first = cached_validator(&schema).unwrap();
+        let second = cached_validator(&schema).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let invalid = serde_json::json!({"type": "no-such-type"});
+        assert!(cached_validator(&invalid).is_none());
+        assert!(validate_against_schema(&invalid, &serde_json::json!({})).is_ok());
+    }
 }
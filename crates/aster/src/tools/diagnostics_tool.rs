@@ -0,0 +1,650 @@
+//! Diagnostics Tool
+//!
+//! Lets the agent see compile errors reported by language servers right after
+//! an edit, without running a build. Diagnostics reach the shared
+//! `DiagnosticsStore` in three ways: a host's `textDocument/publishDiagnostics`
+//! handler calls `DiagnosticsStore::publish`, `LspTool` publishes the
+//! diagnostics its callback returns, and this tool pulls them through the same
+//! `LspCallback` for files that changed. The tool reads them for a single file
+//! or the whole workspace.
+//!
+//! When a file changed after its diagnostics were last published, the tool
+//! refreshes them through the LSP callback, or waits briefly for the server to
+//! republish when there is no callback. Changes are
+//! detected from the file's modification time, size and content hash, so an
+//! edit within the filesystem's timestamp resolution is not missed.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Notify;
+
+use super::base::{PermissionCheckResult, Tool};
+use super::context::{ToolContext, ToolResult};
+use super::error::ToolError;
+use super::lsp::{Diagnostic, DiagnosticSeverity, LspArgs, LspCallback, LspOperation, LspResult};
+
+/// Default time to wait for diagnostics to be republished after a change
+pub const DEFAULT_DIAGNOSTICS_SETTLE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Default maximum number of diagnostics returned in workspace mode
+pub const DEFAULT_MAX_WORKSPACE_DIAGNOSTICS: usize = 100;
+
+/// State of a file on disk, used to tell whether it changed since a publish
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileFingerprint {
+    modified: SystemTime,
+    len: u64,
+    hash: blake3::Hash,
+}
+
+impl FileFingerprint {
+    /// Fingerprint of the file, or None if it cannot be read
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let content = std::fs::read(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+            hash: blake3::hash(&content),
+        })
+    }
+}
+
+/// Diagnostics published for one file
+#[derive(Debug, Clone)]
+struct PublishedDiagnostics {
+    diagnostics: Vec<Diagnostic>,
+    /// The file as it was when the diagnostics were published
+    fingerprint: Option<FileFingerprint>,
+}
+
+/// Latest diagnostics published by language servers, keyed by file
+#[derive(Debug, Default)]
+pub struct DiagnosticsStore {
+    files: Mutex<HashMap<PathBuf, PublishedDiagnostics>>,
+    published: Notify,
+}
+
+impl DiagnosticsStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the diagnostics of a file
+    ///
+    /// Call this from the `textDocument/publishDiagnostics` handler. An empty
+    /// list clears the file's diagnostics.
+    pub async fn publish(&self, path: impl Into<PathBuf>, diagnostics: Vec<Diagnostic>) {
+        let path = path.into();
+        let fingerprint = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || FileFingerprint::read(&path))
+                .await
+                .ok()
+                .flatten()
+        };
+        self.files.lock().unwrap().insert(
+            path,
+            PublishedDiagnostics {
+                diagnostics,
+                fingerprint,
+            },
+        );
+        self.published.notify_waiters();
+    }
+
+    /// Current diagnostics for a file
+    pub fn get(&self, path: &Path) -> Vec<Diagnostic> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .map(|published| published.diagnostics.clone())
+            .unwrap_or_default()
+    }
+
+    /// Current diagnostics for every file, sorted by path
+    pub fn all(&self) -> Vec<(PathBuf, Vec<Diagnostic>)> {
+        let mut files: Vec<_> = self
+            .files
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, published)| !published.diagnostics.is_empty())
+            .map(|(path, published)| (path.clone(), published.diagnostics.clone()))
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        files
+    }
+
+    /// Remove all diagnostics
+    pub fn clear(&self) {
+        self.files.lock().unwrap().clear();
+    }
+
+    /// Files in `paths` that changed after their diagnostics were last published
+    ///
+    /// Fingerprinting reads and hashes every file, so it runs on the blocking pool.
+    pub async fn stale_paths(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        let published: Vec<_> = {
+            let files = self.files.lock().unwrap();
+            paths
+                .iter()
+                .map(|path| {
+                    let fingerprint = files.get(path).map(|p| p.fingerprint.clone());
+                    (path.clone(), fingerprint)
+                })
+                .collect()
+        };
+        let all = paths.to_vec();
+        tokio::task::spawn_blocking(move || {
+            published
+                .into_iter()
+                .filter(|(path, published)| {
+                    let Some(current) = FileFingerprint::read(path) else {
+                        return false;
+                    };
+                    match published {
+                        Some(fingerprint) => fingerprint.as_ref() != Some(&current),
+                        None => true,
+                    }
+                })
+                .map(|(path, _)| path)
+                .collect()
+        })
+        .await
+        .unwrap_or(all)
+    }
+
+    /// Wait until none of `paths` is stale or the timeout expires
+    ///
+    /// Returns false if diagnostics may still be outdated.
+    pub async fn wait_for_fresh(&self, paths: &[PathBuf], timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Register before checking so a publish in between is not missed
+            let published = self.published.notified();
+            if self.stale_paths(paths).await.is_empty() {
+                return true;
+            }
+            if tokio::time::timeout_at(deadline, published).await.is_err() {
+                return self.stale_paths(paths).await.is_empty();
+            }
+        }
+    }
+}
+
+/// Diagnostics Tool for reading language server errors and warnings
+pub struct DiagnosticsTool {
+    store: Arc<DiagnosticsStore>,
+    lsp_callback: Option<LspCallback>,
+    settle_timeout: Duration,
+    max_workspace_results: usize,
+}
+
+impl DiagnosticsTool {
+    /// Create a tool reading from the given store
+    pub fn new(store: Arc<DiagnosticsStore>) -> Self {
+        Self {
+            store,
+            lsp_callback: None,
+            settle_timeout: DEFAULT_DIAGNOSTICS_SETTLE_TIMEOUT,
+            max_workspace_results: DEFAULT_MAX_WORKSPACE_DIAGNOSTICS,
+        }
+    }
+
+    /// Refresh diagnostics of changed files through the LSP callback
+    ///
+    /// The callback is asked for `LspOperation::Diagnostics` and should answer
+    /// for the file's current contents.
+    pub fn with_lsp_callback(mut self, callback: LspCallback) -> Self {
+        self.lsp_callback = Some(callback);
+        self
+    }
+
+    /// Set how long to wait for diagnostics to be republished after a change
+    pub fn with_settle_timeout(mut self, timeout: Duration) -> Self {
+        self.settle_timeout = timeout;
+        self
+    }
+
+    /// Set the maximum number of diagnostics returned in workspace mode
+    pub fn with_max_workspace_results(mut self, max: usize) -> Self {
+        self.max_workspace_results = max;
+        self
+    }
+
+    /// Get the shared store
+    pub fn store(&self) -> &Arc<DiagnosticsStore> {
+        &self.store
+    }
+
+    /// Pull fresh diagnostics for the stale files in `paths`
+    ///
+    /// Files the callback cannot answer for are left to `wait_for_fresh`.
+    async fn refresh(&self, paths: &[PathBuf]) {
+        let Some(callback) = &self.lsp_callback else {
+            return;
+        };
+        for path in self.store.stale_paths(paths).await {
+            match callback(
+                LspOperation::Diagnostics,
+                path.clone(),
+                None,
+                LspArgs::default(),
+            )
+            .await
+            {
+                Ok(LspResult::Diagnostics { diagnostics }) => {
+                    self.store.publish(path, diagnostics).await;
+                }
+                Ok(_) => {
+                    tracing::warn!(
+                        "Unexpected LSP result when refreshing diagnostics for {}",
+                        path.display()
+                    );
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        "Failed to refresh diagnostics for {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for DiagnosticsTool {
+    fn name(&self) -> &str {
+        "diagnostics"
+    }
+
+    fn description(&self) -> &str {
+        "Get current compiler errors and warnings reported by language servers, \
+         grouped by severity. Pass a path to check one file (for example right \
+         after editing it), or omit it to list diagnostics across the workspace."
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "File to get diagnostics for. Omit for the whole workspace"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum diagnostics to return in workspace mode (default: 100)"
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        if context.is_cancelled() {
+            return Err(ToolError::Cancelled);
+        }
+
+        if let Some(path_str) = params.get("path").and_then(|v| v.as_str()) {
+            let path = if Path::new(path_str).is_absolute() {
+                PathBuf::from(path_str)
+            } else {
+                context.working_directory.join(path_str)
+            };
+            let paths = std::slice::from_ref(&path);
+            self.refresh(paths).await;
+            let fresh = self.store.wait_for_fresh(paths, self.settle_timeout).await;
+            let diagnostics = self.store.get(&path);
+            let entries: Vec<_> = diagnostics.iter().map(|d| (path.as_path(), d)).collect();
+
+            let mut output = format_grouped(&entries, context);
+            if !fresh {
+                output.push_str("\nNote: diagnostics may not reflect the latest changes yet.\n");
+            }
+            return Ok(ToolResult::success(output)
+                .with_metadata("path", serde_json::json!(path.display().to_string()))
+                .with_metadata("total", serde_json::json!(diagnostics.len()))
+                .with_metadata("fresh", serde_json::json!(fresh))
+                .with_metadata("counts", counts_by_severity(&entries)));
+        }
+
+        let max_results = params
+            .get("max_results")
+            .and_then(|v| v.as_u64())
+            .map(|m| m.max(1) as usize)
+            .unwrap_or(self.max_workspace_results);
+
+        let tracked: Vec<PathBuf> = self.store.all().into_iter().map(|(path, _)| path).collect();
+        self.refresh(&tracked).await;
+        let fresh = self
+            .store
+            .wait_for_fresh(&tracked, self.settle_timeout)
+            .await;
+        let files = self.store.all();
+        let mut entries: Vec<(&Path, &Diagnostic)> = files
+            .iter()
+            .flat_map(|(path, diagnostics)| diagnostics.iter().map(move |d| (path.as_path(), d)))
+            .collect();
+        // Most severe first, so the cap drops hints before errors
+        entries.sort_by_key(|(_, d)| severity_rank(d.severity));
+        let total = entries.len();
+        entries.truncate(max_results);
+
+        let mut output = format_grouped(&entries, context);
+        if total > entries.len() {
+            output.push_str(&format!(
+                "\nShowing {} of {} diagnostics. Check individual files for the rest.\n",
+                entries.len(),
+                total
+            ));
+        }
+        if !fresh {
+            output.push_str("\nNote: diagnostics may not reflect the latest changes yet.\n");
+        }
+
+        Ok(ToolResult::success(output)
+            .with_metadata("total", serde_json::json!(total))
+            .with_metadata("truncated", serde_json::json!(total > entries.len()))
+            .with_metadata("fresh", serde_json::json!(fresh))
+            .with_metadata("counts", counts_by_severity(&entries)))
+    }
+
+    async fn check_permissions(
+        &self,
+        _params: &serde_json::Value,
+        _context: &ToolContext,
+    ) -> PermissionCheckResult {
+        // Reading diagnostics is read-only
+        PermissionCheckResult::allow()
+    }
+}
+
+/// Severity groups in display order; a missing severity is treated as an error
+const SEVERITY_GROUPS: [(DiagnosticSeverity, &str); 4] = [
+    (DiagnosticSeverity::Error, "Errors"),
+    (DiagnosticSeverity::Warning, "Warnings"),
+    (DiagnosticSeverity::Information, "Information"),
+    (DiagnosticSeverity::Hint, "Hints"),
+];
+
+fn severity_rank(severity: Option<DiagnosticSeverity>) -> usize {
+    let severity = severity.unwrap_or(DiagnosticSeverity::Error);
+    SEVERITY_GROUPS
+        .iter()
+        .position(|(group, _)| *group == severity)
+        .unwrap_or(0)
+}
+
+fn counts_by_severity(entries: &[(&Path, &Diagnostic)]) -> serde_json::Value {
+    let mut counts = serde_json::Map::new();
+    for (rank, (_, label)) in SEVERITY_GROUPS.iter().enumerate() {
+        let count = entries
+            .iter()
+            .filter(|(_, d)| severity_rank(d.severity) == rank)
+            .count();
+        counts.insert(label.to_lowercase(), serde_json::json!(count));
+    }
+    serde_json::Value::Object(counts)
+}
+
+/// Format diagnostics grouped by severity with file:line:column locations
+fn format_grouped(entries: &[(&Path, &Diagnostic)], context: &ToolContext) -> String {
+    if entries.is_empty() {
+        return "No diagnostics found".to_string();
+    }
+
+    let mut output = String::new();
+    for (rank, (_, label)) in SEVERITY_GROUPS.iter().enumerate() {
+        let group: Vec<_> = entries
+            .iter()
+            .filter(|(_, d)| severity_rank(d.severity) == rank)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("{} ({}):\n", label, group.len()));
+        for (path, diagnostic) in group {
+            let path = path
+                .strip_prefix(&context.working_directory)
+                .unwrap_or(*path);
+            let code = diagnostic
+                .code
+                .as_ref()
+                .map(|c| format!(" [{}]", c))
+                .unwrap_or_default();
+            let source = diagnostic
+                .source
+                .as_ref()
+                .map(|s| format!(" ({})", s))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "  {}:{}:{}: {}{}{}\n",
+                path.display(),
+                diagnostic.range.start.line + 1,
+                diagnostic.range.start.character + 1,
+                diagnostic.message,
+                code,
+                source
+            ));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::lsp::{Position, Range};
+    use tempfile::TempDir;
+
+    fn diagnostic(line: u32, severity: DiagnosticSeverity, message: &str) -> Diagnostic {
+        Diagnostic {
+            range: Range::new(Position::new(line, 4), Position::new(line, 8)),
+            severity: Some(severity),
+            code: None,
+            source: Some("rustc".to_string()),
+            message: message.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_returns_diagnostics_published_after_edit() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        let store = Arc::new(DiagnosticsStore::new());
+        store.publish(&file, vec![]).await;
+
+        // The agent edits the file; the mock server republishes shortly after
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        std::fs::write(&file, "fn main() {\n    let x: u32 = \"a\";\n}\n").unwrap();
+        let server_store = store.clone();
+        let server_file = file.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            server_store
+                .publish(
+                    server_file,
+                    vec![
+                        diagnostic(1, DiagnosticSeverity::Warning, "unused variable: `x`"),
+                        Diagnostic {
+                            code: Some("E0308".to_string()),
+                            ..diagnostic(1, DiagnosticSeverity::Error, "mismatched types")
+                        },
+                    ],
+                )
+                .await;
+        });
+
+        let tool = DiagnosticsTool::new(store).with_settle_timeout(Duration::from_secs(5));
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+        let result = tool
+            .execute(serde_json::json!({"path": "main.rs"}), &context)
+            .await
+            .unwrap();
+
+        assert_eq!(result.metadata["fresh"], true);
+        assert_eq!(result.metadata["counts"]["errors"], 1);
+        assert_eq!(result.metadata["counts"]["warnings"], 1);
+        let output = result.output.unwrap();
+        assert!(output.contains("Errors (1):\n  main.rs:2:5: mismatched types [E0308] (rustc)"));
+        assert!(output.contains("Warnings (1):\n  main.rs:2:5: unused variable: `x`"));
+        assert!(output.find("Errors").unwrap() < output.find("Warnings").unwrap());
+    }
+
+    /// Mock language server answering diagnostics pulls from the file on disk
+    fn mock_server() -> (LspCallback, Arc<std::sync::atomic::AtomicUsize>) {
+        let pulls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = pulls.clone();
+        let callback: LspCallback = Arc::new(move |operation, path: PathBuf, _position, _args| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async move {
+                assert_eq!(operation, LspOperation::Diagnostics);
+                let content = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+                let diagnostics = if content.contains("\"a\"") {
+                    vec![diagnostic(1, DiagnosticSeverity::Error, "mismatched types")]
+                } else {
+                    vec![]
+                };
+                Ok(LspResult::Diagnostics { diagnostics })
+            })
+        });
+        (callback, pulls)
+    }
+
+    #[tokio::test]
+    async fn test_pulls_diagnostics_from_lsp_callback_after_edit() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        let (callback, pulls) = mock_server();
+        let tool = DiagnosticsTool::new(Arc::new(DiagnosticsStore::new()))
+            .with_lsp_callback(callback)
+            .with_settle_timeout(Duration::from_secs(5));
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+
+        let result = tool
+            .execute(serde_json::json!({"path": "main.rs"}), &context)
+            .await
+            .unwrap();
+        assert_eq!(result.metadata["fresh"], true);
+        assert_eq!(result.output.unwrap(), "No diagnostics found");
+
+        // Unchanged file: the published diagnostics are reused
+        tool.execute(serde_json::json!({"path": "main.rs"}), &context)
+            .await
+            .unwrap();
+        assert_eq!(pulls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        std::fs::write(&file, "fn main() {\n    let x: u32 = \"a\";\n}\n").unwrap();
+        let started = std::time::Instant::now();
+        let result = tool
+            .execute(serde_json::json!({"path": "main.rs"}), &context)
+            .await
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(pulls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(result.metadata["fresh"], true);
+        assert_eq!(result.metadata["counts"]["errors"], 1);
+        assert!(result
+            .output
+            .unwrap()
+            .contains("Errors (1):\n  main.rs:2:5: mismatched types (rustc)"));
+    }
+
+    #[tokio::test]
+    async fn test_same_timestamp_edit_is_stale() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("lib.rs");
+        std::fs::write(&file, "pub fn f() {}\n").unwrap();
+        let modified = std::fs::metadata(&file).unwrap().modified().unwrap();
+
+        let store = DiagnosticsStore::new();
+        store.publish(&file, vec![]).await;
+        let paths = [file.clone()];
+        assert!(store.stale_paths(&paths).await.is_empty());
+
+        // Same size and, as on a coarse filesystem, the same modification time
+        std::fs::write(&file, "pub fn g() {}\n").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert_eq!(store.stale_paths(&paths).await, paths);
+    }
+
+    #[tokio::test]
+    async fn test_stale_diagnostics_time_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("lib.rs");
+        std::fs::write(&file, "pub fn f() {}\n").unwrap();
+
+        let tool = DiagnosticsTool::new(Arc::new(DiagnosticsStore::new()))
+            .with_settle_timeout(Duration::from_millis(20));
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+        let result = tool
+            .execute(serde_json::json!({"path": "lib.rs"}), &context)
+            .await
+            .unwrap();
+
+        assert_eq!(result.metadata["fresh"], false);
+        assert!(result.output.unwrap().contains("may not reflect"));
+    }
+
+    #[tokio::test]
+    async fn test_workspace_mode_caps_results_keeping_errors() {
+        let store = Arc::new(DiagnosticsStore::new());
+        store
+            .publish(
+                "/project/a.rs",
+                vec![
+                    diagnostic(0, DiagnosticSeverity::Hint, "consider borrowing"),
+                    diagnostic(1, DiagnosticSeverity::Warning, "unused import"),
+                ],
+            )
+            .await;
+        store
+            .publish(
+                "/project/b.rs",
+                vec![diagnostic(
+                    2,
+                    DiagnosticSeverity::Error,
+                    "cannot find value",
+                )],
+            )
+            .await;
+
+        let tool = DiagnosticsTool::new(store).with_settle_timeout(Duration::ZERO);
+        let context = ToolContext::new(PathBuf::from("/project"));
+        let result = tool
+            .execute(serde_json::json!({"max_results": 2}), &context)
+            .await
+            .unwrap();
+
+        assert_eq!(result.metadata["total"], 3);
+        assert_eq!(result.metadata["truncated"], true);
+        let output = result.output.unwrap();
+        assert!(output.contains("b.rs:3:5: cannot find value"));
+        assert!(output.contains("a.rs:2:5: unused import"));
+        assert!(!output.contains("consider borrowing"));
+        assert!(output.contains("Showing 2 of 3 diagnostics"));
+    }
+}
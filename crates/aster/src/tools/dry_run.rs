@@ -0,0 +1,203 @@
+//! Dry-Run Module
+//!
+//! Support for previewing tool calls without side effects. While dry-run is
+//! enabled on a `ToolRegistry`, calls known to be read-only run for real and
+//! every other tool call is recorded and answered with a synthetic success
+//! result. The recorded calls can be reviewed before running the plan for real.
+//!
+//! Read-only is decided per call, not per tool: the same shell tool can list a
+//! directory or delete it. Only tools registered as pure run for any
+//! arguments; other calls run only if that exact call was classified read-only.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::context::ToolResult;
+use crate::conversation::message::ToolRequest;
+use crate::permission::detect_read_only_tools;
+use crate::providers::base::Provider;
+
+/// Metadata key marking a result produced in dry-run mode
+pub const DRY_RUN_METADATA_KEY: &str = "dry_run";
+
+/// How a tool call was handled in dry-run mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DryRunDisposition {
+    /// Read-only tool that was actually executed
+    Executed,
+    /// Tool that was only recorded; its result is synthetic
+    Recorded,
+}
+
+impl DryRunDisposition {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Executed => "executed",
+            Self::Recorded => "recorded",
+        }
+    }
+}
+
+/// A tool call seen while dry-run was enabled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DryRunCall {
+    /// Tool name
+    pub tool_name: String,
+    /// Parameters the tool was (or would have been) called with
+    pub params: serde_json::Value,
+    /// Whether the call ran or was only recorded
+    pub disposition: DryRunDisposition,
+    /// When the call was made
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Dry-run state held by a `ToolRegistry`
+#[derive(Debug, Default)]
+pub struct DryRunRecorder {
+    pure_tools: HashSet<String>,
+    read_only_calls: Vec<(String, serde_json::Value)>,
+    calls: Mutex<Vec<DryRunCall>>,
+}
+
+impl DryRunRecorder {
+    /// Create a recorder that lets every call to the given pure tools run for real
+    pub fn new(pure_tools: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            pure_tools: pure_tools.into_iter().collect(),
+            read_only_calls: Vec::new(),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Also let this exact call run for real
+    pub fn with_read_only_call(
+        mut self,
+        tool_name: impl Into<String>,
+        params: serde_json::Value,
+    ) -> Self {
+        self.read_only_calls.push((tool_name.into(), params));
+        self
+    }
+
+    /// Create a recorder whose read-only calls are detected by the provider
+    ///
+    /// Each planned tool request is checked on its own with
+    /// `detect_read_only_tools`, so a tool judged read-only for one set of
+    /// arguments is not trusted with another.
+    pub async fn detect(provider: Arc<dyn Provider>, tool_requests: &[ToolRequest]) -> Self {
+        let checks = tool_requests
+            .iter()
+            .map(|request| detect_read_only_tools(provider.clone(), vec![request]));
+        let verdicts = futures::future::join_all(checks).await;
+
+        let mut recorder = Self::default();
+        for (request, read_only) in tool_requests.iter().zip(verdicts) {
+            let Ok(call) = &request.tool_call else {
+                continue;
+            };
+            if read_only.iter().any(|name| name == call.name.as_ref()) {
+                let params = serde_json::Value::Object(call.arguments.clone().unwrap_or_default());
+                recorder = recorder.with_read_only_call(call.name.to_string(), params);
+            }
+        }
+        recorder
+    }
+
+    /// Whether this call is allowed to run for real
+    pub fn is_read_only(&self, tool_name: &str, params: &serde_json::Value) -> bool {
+        self.pure_tools.contains(tool_name)
+            || self
+                .read_only_calls
+                .iter()
+                .any(|(name, read_only)| name == tool_name && read_only == params)
+    }
+
+    /// Record a call and return its disposition
+    pub fn record(&self, tool_name: &str, params: &serde_json::Value) -> DryRunDisposition {
+        let disposition = if self.is_read_only(tool_name, params) {
+            DryRunDisposition::Executed
+        } else {
+            DryRunDisposition::Recorded
+        };
+        self.calls.lock().unwrap().push(DryRunCall {
+            tool_name: tool_name.to_string(),
+            params: params.clone(),
+            disposition,
+            timestamp: Utc::now(),
+        });
+        disposition
+    }
+
+    /// All calls recorded so far, in call order
+    pub fn calls(&self) -> Vec<DryRunCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Calls that were recorded but not executed
+    pub fn intended_calls(&self) -> Vec<DryRunCall> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| call.disposition == DryRunDisposition::Recorded)
+            .cloned()
+            .collect()
+    }
+
+    /// Consume the recorder, returning all calls
+    pub fn into_calls(self) -> Vec<DryRunCall> {
+        self.calls.into_inner().unwrap()
+    }
+}
+
+/// Synthetic result returned for a recorded (not executed) call
+pub fn synthetic_result(tool_name: &str) -> ToolResult {
+    ToolResult::success(format!(
+        "[dry-run] Tool '{}' was not executed; the call has been recorded",
+        tool_name
+    ))
+    .with_metadata(
+        DRY_RUN_METADATA_KEY,
+        serde_json::json!(DryRunDisposition::Recorded.as_str()),
+    )
+}
+
+/// Mark a real result from a read-only tool executed during dry-run
+pub fn mark_executed(result: ToolResult) -> ToolResult {
+    result.with_metadata(
+        DRY_RUN_METADATA_KEY,
+        serde_json::json!(DryRunDisposition::Executed.as_str()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_is_decided_per_call() {
+        let recorder = DryRunRecorder::new(vec!["read".to_string()])
+            .with_read_only_call("bash", serde_json::json!({"command": "ls"}));
+
+        assert_eq!(
+            recorder.record("read", &serde_json::json!({"path": "a.txt"})),
+            DryRunDisposition::Executed
+        );
+        assert_eq!(
+            recorder.record("bash", &serde_json::json!({"command": "ls"})),
+            DryRunDisposition::Executed
+        );
+        assert_eq!(
+            recorder.record("bash", &serde_json::json!({"command": "rm -rf target"})),
+            DryRunDisposition::Recorded
+        );
+
+        let intended = recorder.intended_calls();
+        assert_eq!(intended.len(), 1);
+        assert_eq!(intended[0].params["command"], "rm -rf target");
+    }
+}
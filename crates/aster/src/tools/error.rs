@@ -6,6 +6,8 @@
 use std::time::Duration;
 use thiserror::Error;
 
+use crate::security::PathTraversalError;
+
 /// Tool execution error types
 ///
 /// Represents all possible errors that can occur during tool operations.
@@ -44,6 +46,12 @@ pub enum ToolError {
     Cancelled,
 }
 
+impl From<PathTraversalError> for ToolError {
+    fn from(err: PathTraversalError) -> Self {
+        Self::PermissionDenied(err.to_string())
+    }
+}
+
 impl ToolError {
     /// Create a NotFound error
     pub fn not_found(name: impl Into<String>) -> Self {
@@ -3,6 +3,7 @@
 //! This module implements the `EditTool` for editing files with:
 //! - Smart string matching with quote normalization
 //! - Batch edits with atomic rollback
+//! - Unified diff application with fuzzy context matching
 //! - External file modification detection
 //! - Match uniqueness validation
 //!
@@ -15,7 +16,8 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use super::{compute_content_hash, FileReadRecord, SharedFileReadHistory};
+use super::patch::{apply_patch, HunkStatus, PatchOptions};
+use super::{compute_content_hash, resolve_tool_path, FileReadRecord, SharedFileReadHistory};
 use crate::tools::base::{PermissionCheckResult, Tool};
 use crate::tools::context::{ToolContext, ToolOptions, ToolResult};
 use crate::tools::error::ToolError;
@@ -65,6 +67,8 @@ pub struct EditTool {
     require_read_before_edit: bool,
     /// Whether to enable smart quote matching
     smart_quote_matching: bool,
+    /// Whether paths must stay inside the working directory
+    restrict_to_working_directory: bool,
 }
 
 impl EditTool {
@@ -74,6 +78,7 @@ impl EditTool {
             read_history,
             require_read_before_edit: true,
             smart_quote_matching: true,
+            restrict_to_working_directory: false,
         }
     }
 
@@ -89,18 +94,20 @@ impl EditTool {
         self
     }
 
+    /// Reject paths that escape the working directory
+    pub fn with_restrict_to_working_directory(mut self, restrict: bool) -> Self {
+        self.restrict_to_working_directory = restrict;
+        self
+    }
+
     /// Get the shared read history
     pub fn read_history(&self) -> &SharedFileReadHistory {
         &self.read_history
     }
 
     /// Resolve a path relative to the working directory
-    fn resolve_path(&self, path: &Path, context: &ToolContext) -> PathBuf {
-        if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            context.working_directory.join(path)
-        }
+    fn resolve_path(&self, path: &Path, context: &ToolContext) -> Result<PathBuf, ToolError> {
+        resolve_tool_path(path, context, self.restrict_to_working_directory)
     }
 }
 
@@ -209,7 +216,7 @@ impl EditTool {
         new_str: &str,
         context: &ToolContext,
     ) -> Result<ToolResult, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check file exists
         if !full_path.exists() {
@@ -331,6 +338,83 @@ impl EditTool {
     }
 }
 
+// =============================================================================
+// Patch Application
+// =============================================================================
+
+impl EditTool {
+    /// Apply a unified diff to a file
+    ///
+    /// The file is only written if every hunk applies, with or without fuzz.
+    pub async fn patch_file(
+        &self,
+        path: &Path,
+        patch: &str,
+        context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let full_path = self.resolve_path(path, context)?;
+
+        if !full_path.exists() {
+            return Err(ToolError::execution_failed(format!(
+                "File not found: {}",
+                full_path.display()
+            )));
+        }
+
+        if self.require_read_before_edit {
+            let history = self.read_history.read().unwrap();
+            if !history.has_read(&full_path) {
+                return Err(ToolError::execution_failed(format!(
+                    "File has not been read: {}. Read the file first before editing.",
+                    full_path.display()
+                )));
+            }
+        }
+
+        self.check_external_modification(&full_path)?;
+
+        let content = fs::read_to_string(&full_path)?;
+        let result = apply_patch(&content, patch, &PatchOptions::default())
+            .map_err(|e| ToolError::invalid_params(e.to_string()))?;
+
+        if !result.all_applied() {
+            return Err(ToolError::execution_failed(format!(
+                "Patch does not apply to {}: hunk(s) {:?} failed. No changes were made.",
+                full_path.display(),
+                result.failed_hunks()
+            )));
+        }
+
+        fs::write(&full_path, &result.content)?;
+        self.update_read_history(&full_path, &result.content)?;
+
+        let fuzzy = result
+            .hunks
+            .iter()
+            .filter(|h| matches!(h.status, HunkStatus::Fuzzy { .. }))
+            .count();
+        debug!(
+            "Patched file: {} ({} hunks, {} with fuzz)",
+            full_path.display(),
+            result.hunks.len(),
+            fuzzy
+        );
+
+        Ok(ToolResult::success(format!(
+            "Successfully applied {} hunk(s) to {}{}",
+            result.hunks.len(),
+            full_path.display(),
+            if fuzzy > 0 {
+                format!(" ({} with fuzzy matching)", fuzzy)
+            } else {
+                String::new()
+            }
+        ))
+        .with_metadata("path", serde_json::json!(full_path.to_string_lossy()))
+        .with_metadata("hunks", serde_json::json!(result.hunks)))
+    }
+}
+
 // =============================================================================
 // Batch Edit Implementation (Requirements: 4.8)
 // =============================================================================
@@ -348,7 +432,7 @@ impl EditTool {
         edits: &[Edit],
         context: &ToolContext,
     ) -> Result<ToolResult, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check file exists
         if !full_path.exists() {
@@ -439,7 +523,7 @@ impl Tool for EditTool {
     fn description(&self) -> &str {
         "Edit a file by replacing a specific string with a new string. \
          The string to replace must be unique in the file. \
-         Supports smart quote matching and batch edits. \
+         Supports smart quote matching, batch edits and unified diff patches. \
          The file must be read first before editing."
     }
 
@@ -459,6 +543,11 @@ impl Tool for EditTool {
                     "type": "string",
                     "description": "The replacement string"
                 },
+                "patch": {
+                    "type": "string",
+                    "description": "A unified diff to apply to the file instead of old_str/new_str. \
+                                    Context is matched fuzzily (whitespace, small line offsets)"
+                },
                 "edits": {
                     "type": "array",
                     "description": "Array of edit operations for batch editing",
@@ -494,6 +583,11 @@ impl Tool for EditTool {
 
         let path = Path::new(path_str);
 
+        // Check for a patch
+        if let Some(patch) = params.get("patch").and_then(|v| v.as_str()) {
+            return self.patch_file(path, patch, context).await;
+        }
+
         // Check for batch edits
         if let Some(edits_value) = params.get("edits") {
             let edits: Vec<Edit> = serde_json::from_value(edits_value.clone())
@@ -532,7 +626,10 @@ impl Tool for EditTool {
         };
 
         let path = Path::new(path_str);
-        let full_path = self.resolve_path(path, context);
+        let full_path = match self.resolve_path(path, context) {
+            Ok(full_path) => full_path,
+            Err(e) => return PermissionCheckResult::deny(e.to_string()),
+        };
 
         // Check if file exists
         if !full_path.exists() {
@@ -973,4 +1070,57 @@ mod tests {
         let result = tool.check_permissions(&params, &context).await;
         assert!(result.is_denied());
     }
+
+    #[tokio::test]
+    async fn test_patch_file_with_offset() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("lib.rs");
+        fs::write(
+            &file_path,
+            "// added later
+
+fn a() {}
+fn b() {}
+fn c() {}
+",
+        )
+        .unwrap();
+
+        let tool = create_edit_tool().with_require_read_before_edit(false);
+        let context = create_test_context(temp_dir.path());
+        let patch = "@@ -1,3 +1,3 @@\n fn a() {}\n-fn b() {}\n+fn b() -> u8 { 1 }\n fn c() {}\n";
+
+        let result = tool
+            .execute(
+                serde_json::json!({"path": "lib.rs", "patch": patch}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(result.metadata["hunks"][0]["status"], "fuzzy");
+        assert_eq!(result.metadata["hunks"][0]["offset"], 2);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "// added later\n\nfn a() {}\nfn b() -> u8 { 1 }\nfn c() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_file_conflict_leaves_file_unchanged() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("lib.rs");
+        let original = "fn a() {}\nfn b() { todo!() }\nfn c() {}\n";
+        fs::write(&file_path, original).unwrap();
+
+        let tool = create_edit_tool().with_require_read_before_edit(false);
+        let context = create_test_context(temp_dir.path());
+        let patch = "@@ -1,3 +1,3 @@\n fn a() {}\n-fn b() {}\n+fn b() -> u8 { 1 }\n fn c() {}\n";
+
+        let result = tool.patch_file(&file_path, patch, &context).await;
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), original);
+    }
 }
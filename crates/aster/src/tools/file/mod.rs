@@ -3,27 +3,51 @@
 //! This module provides file operation tools including:
 //! - ReadTool: Read text files, images, PDFs, and Jupyter notebooks
 //! - WriteTool: Write files with read-before-overwrite validation
-//! - EditTool: Smart string matching and batch edits
+//! - EditTool: Smart string matching, batch edits and fuzzy patch application
 //!
 //! Requirements: 4.1, 4.2, 4.3, 4.4, 4.5, 4.6, 4.7, 4.8, 4.9, 4.10
 
 pub mod edit;
+pub mod patch;
 pub mod read;
 pub mod write;
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 use std::sync::RwLock;
 
+use crate::security::sanitize_path;
+use crate::tools::context::ToolContext;
+use crate::tools::error::ToolError;
+
 // Re-export tools
 pub use edit::EditTool;
 pub use read::ReadTool;
 pub use write::WriteTool;
 
+/// Resolve a tool path against the context's working directory
+///
+/// With `restrict` set, paths escaping the working directory (via `..`,
+/// absolute paths or symlinks) are rejected.
+pub(crate) fn resolve_tool_path(
+    path: &Path,
+    context: &ToolContext,
+    restrict: bool,
+) -> Result<PathBuf, ToolError> {
+    if restrict {
+        return Ok(sanitize_path(&context.working_directory, path)?);
+    }
+    Ok(if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        context.working_directory.join(path)
+    })
+}
+
 /// Record of a file read operation
 ///
 /// Tracks when a file was read and its content hash at that time.
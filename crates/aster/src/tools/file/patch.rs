@@ -0,0 +1,474 @@
+//! Unified Diff Application
+//!
+//! Applies unified diffs to file content with fuzzy context matching, so a
+//! patch still applies when whitespace drifted or lines moved slightly:
+//! - Trailing whitespace is ignored when comparing lines
+//! - Hunks are searched for near their stated position (line offset)
+//! - Outer context lines may be dropped, like `patch`'s fuzz factor
+//!
+//! Each hunk is reported as applied cleanly, applied with fuzz, or failed.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Error parsing a unified diff
+#[derive(Debug, Error, PartialEq)]
+pub enum PatchError {
+    /// A hunk header could not be parsed
+    #[error("Invalid hunk header: {0}")]
+    InvalidHeader(String),
+
+    /// The diff contains no hunks
+    #[error("Patch contains no hunks")]
+    NoHunks,
+}
+
+/// A line within a hunk
+#[derive(Debug, Clone, PartialEq)]
+pub enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// A single hunk of a unified diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// 1-based start line in the original file
+    pub old_start: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    /// Number of leading and trailing context lines
+    fn context_bounds(&self) -> (usize, usize) {
+        let leading = self
+            .lines
+            .iter()
+            .take_while(|l| matches!(l, HunkLine::Context(_)))
+            .count();
+        let trailing = self
+            .lines
+            .iter()
+            .rev()
+            .take_while(|l| matches!(l, HunkLine::Context(_)))
+            .count();
+        (leading, trailing)
+    }
+}
+
+/// How a hunk was applied
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum HunkStatus {
+    /// Matched exactly at the stated position
+    Clean,
+    /// Matched after shifting, dropping context or ignoring whitespace
+    Fuzzy {
+        /// Lines between the stated and the actual position
+        offset: isize,
+        /// Context lines dropped from each end of the hunk
+        fuzz: usize,
+    },
+    /// No matching location was found
+    Failed,
+}
+
+/// Outcome of a single hunk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HunkOutcome {
+    /// 0-based hunk index within the patch
+    pub index: usize,
+    #[serde(flatten)]
+    pub status: HunkStatus,
+}
+
+/// Result of applying a patch
+#[derive(Debug, Clone)]
+pub struct PatchResult {
+    /// Content with all applicable hunks applied
+    pub content: String,
+    pub hunks: Vec<HunkOutcome>,
+}
+
+impl PatchResult {
+    /// Check whether every hunk applied, with or without fuzz
+    pub fn all_applied(&self) -> bool {
+        self.hunks.iter().all(|h| h.status != HunkStatus::Failed)
+    }
+
+    /// Indices of hunks that failed to apply
+    pub fn failed_hunks(&self) -> Vec<usize> {
+        self.hunks
+            .iter()
+            .filter(|h| h.status == HunkStatus::Failed)
+            .map(|h| h.index)
+            .collect()
+    }
+}
+
+/// Options controlling fuzzy matching
+#[derive(Debug, Clone)]
+pub struct PatchOptions {
+    /// Maximum distance in lines from the stated hunk position
+    pub max_offset: usize,
+    /// Maximum context lines dropped from each end of a hunk
+    pub max_fuzz: usize,
+}
+
+impl Default for PatchOptions {
+    fn default() -> Self {
+        Self {
+            max_offset: 100,
+            max_fuzz: 2,
+        }
+    }
+}
+
+/// Parse the hunks of a unified diff, ignoring file headers
+pub fn parse_unified_diff(diff: &str) -> Result<Vec<Hunk>, PatchError> {
+    let mut hunks = Vec::new();
+    let mut lines = diff.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if !line.starts_with("@@") {
+            continue;
+        }
+        let (old_start, old_count, new_count) = parse_hunk_header(line)?;
+
+        let mut hunk = Hunk {
+            old_start,
+            lines: Vec::new(),
+        };
+        let (mut old_seen, mut new_seen) = (0, 0);
+        while old_seen < old_count || new_seen < new_count {
+            let Some(line) = lines.peek() else {
+                break;
+            };
+            if line.starts_with("@@") {
+                break;
+            }
+            let line = lines.next().unwrap_or_default();
+            match line.chars().next() {
+                Some('-') => {
+                    hunk.lines.push(HunkLine::Remove(
+                        line.get(1..).unwrap_or_default().to_string(),
+                    ));
+                    old_seen += 1;
+                }
+                Some('+') => {
+                    hunk.lines
+                        .push(HunkLine::Add(line.get(1..).unwrap_or_default().to_string()));
+                    new_seen += 1;
+                }
+                Some('\\') => {}
+                // Blank context lines often lose their leading space
+                Some(' ') | None => {
+                    hunk.lines.push(HunkLine::Context(
+                        line.get(1..).unwrap_or_default().to_string(),
+                    ));
+                    old_seen += 1;
+                    new_seen += 1;
+                }
+                Some(_) => break,
+            }
+        }
+        hunks.push(hunk);
+    }
+
+    if hunks.is_empty() {
+        return Err(PatchError::NoHunks);
+    }
+    Ok(hunks)
+}
+
+/// Parse `@@ -a,b +c,d @@` into (a, b, d)
+fn parse_hunk_header(line: &str) -> Result<(usize, usize, usize), PatchError> {
+    let invalid = || PatchError::InvalidHeader(line.to_string());
+    let mut parts = line.trim_start_matches('@').split_whitespace();
+
+    let parse_range = |range: Option<&str>, prefix: char| -> Result<(usize, usize), PatchError> {
+        let range = range
+            .and_then(|r| r.strip_prefix(prefix))
+            .ok_or_else(invalid)?;
+        let (start, count) = match range.split_once(',') {
+            Some((start, count)) => (start, count.parse().map_err(|_| invalid())?),
+            None => (range, 1),
+        };
+        Ok((start.parse().map_err(|_| invalid())?, count))
+    };
+
+    let (old_start, old_count) = parse_range(parts.next(), '-')?;
+    let (_, new_count) = parse_range(parts.next(), '+')?;
+    Ok((old_start, old_count, new_count))
+}
+
+/// Apply a unified diff to `content`
+///
+/// Hunks that cannot be placed are reported as failed and skipped; the
+/// remaining hunks are still applied.
+pub fn apply_patch(
+    content: &str,
+    diff: &str,
+    options: &PatchOptions,
+) -> Result<PatchResult, PatchError> {
+    let hunks = parse_unified_diff(diff)?;
+
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut outcomes = Vec::with_capacity(hunks.len());
+    // Shift of later hunk positions caused by earlier hunks
+    let mut delta: isize = 0;
+    // Hunks may not apply before the end of the previous one
+    let mut min_pos = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        // A hunk with no old lines gives the line it is inserted after
+        let start = if old_lines(hunk, 0).is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected = (start as isize + delta).max(0) as usize;
+        match locate(&lines, hunk, expected, min_pos, options) {
+            Some(found) => {
+                let (replacement, consumed) = build_replacement(&lines, hunk, &found);
+                let end = found.pos + consumed;
+                delta += replacement.len() as isize - consumed as isize;
+                min_pos = found.pos + replacement.len();
+                lines.splice(found.pos..end, replacement);
+
+                let status = if found.offset == 0 && found.fuzz == 0 && found.exact {
+                    HunkStatus::Clean
+                } else {
+                    HunkStatus::Fuzzy {
+                        offset: found.offset,
+                        fuzz: found.fuzz,
+                    }
+                };
+                outcomes.push(HunkOutcome { index, status });
+            }
+            None => outcomes.push(HunkOutcome {
+                index,
+                status: HunkStatus::Failed,
+            }),
+        }
+    }
+
+    let mut patched = lines.join(newline);
+    if content.ends_with('\n') && !patched.is_empty() {
+        patched.push_str(newline);
+    }
+
+    Ok(PatchResult {
+        content: patched,
+        hunks: outcomes,
+    })
+}
+
+struct Placement {
+    /// 0-based line where the (trimmed) hunk starts
+    pos: usize,
+    offset: isize,
+    fuzz: usize,
+    /// Whether every line matched byte for byte
+    exact: bool,
+}
+
+fn locate(
+    lines: &[String],
+    hunk: &Hunk,
+    expected: usize,
+    min_pos: usize,
+    options: &PatchOptions,
+) -> Option<Placement> {
+    let (leading, trailing) = hunk.context_bounds();
+    // A hunk of only context changes nothing; trimming it would leave nothing to match
+    let max_fuzz = if leading == hunk.lines.len() {
+        0
+    } else {
+        options.max_fuzz
+    };
+
+    for fuzz in 0..=max_fuzz {
+        let drop_front = fuzz.min(leading);
+        let drop_back = fuzz.min(trailing);
+        // No more context left to drop
+        if fuzz > 0
+            && drop_front == (fuzz - 1).min(leading)
+            && drop_back == (fuzz - 1).min(trailing)
+        {
+            break;
+        }
+        let old: Vec<&str> = old_lines(hunk, fuzz);
+        if old.is_empty() && fuzz > 0 {
+            break;
+        }
+        let expected = expected + drop_front;
+
+        for distance in 0..=options.max_offset {
+            for offset in [distance as isize, -(distance as isize)] {
+                if distance == 0 && offset < 0 {
+                    continue;
+                }
+                let pos = expected as isize + offset;
+                if pos < min_pos as isize || pos as usize + old.len() > lines.len() {
+                    continue;
+                }
+                let pos = pos as usize;
+                let window = &lines[pos..pos + old.len()];
+                if window
+                    .iter()
+                    .zip(&old)
+                    .all(|(line, old)| line.trim_end() == old.trim_end())
+                {
+                    return Some(Placement {
+                        pos,
+                        offset,
+                        fuzz,
+                        exact: window.iter().zip(&old).all(|(line, old)| line == old),
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Hunk lines left after dropping up to `fuzz` context lines from each end
+///
+/// The two ends are clamped so they never cross, even for a hunk whose
+/// context lines count as both leading and trailing.
+fn trimmed_range(hunk: &Hunk, fuzz: usize) -> std::ops::Range<usize> {
+    let (leading, trailing) = hunk.context_bounds();
+    let len = hunk.lines.len();
+    let start = fuzz.min(leading).min(len);
+    let end = (len - fuzz.min(trailing)).max(start);
+    start..end
+}
+
+/// Lines the hunk expects in the original, with context dropped from each end
+fn old_lines(hunk: &Hunk, fuzz: usize) -> Vec<&str> {
+    hunk.lines[trimmed_range(hunk, fuzz)]
+        .iter()
+        .filter_map(|line| match line {
+            HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+            HunkLine::Add(_) => None,
+        })
+        .collect()
+}
+
+/// Build the replacement lines and the number of original lines they replace
+///
+/// Context lines keep the file's own text so whitespace drift in the patch
+/// is not written back.
+fn build_replacement(lines: &[String], hunk: &Hunk, found: &Placement) -> (Vec<String>, usize) {
+    let mut replacement = Vec::new();
+    let mut cursor = found.pos;
+    for line in &hunk.lines[trimmed_range(hunk, found.fuzz)] {
+        match line {
+            HunkLine::Context(_) => {
+                replacement.push(lines[cursor].clone());
+                cursor += 1;
+            }
+            HunkLine::Remove(_) => cursor += 1,
+            HunkLine::Add(text) => replacement.push(text.clone()),
+        }
+    }
+    (replacement, cursor - found.pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str =
+        "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n";
+
+    #[test]
+    fn test_clean_apply() {
+        let diff = "--- a/main.rs\n+++ b/main.rs\n@@ -2,3 +2,3 @@\n     let a = 1;\n-    let b = 2;\n+    let b = 3;\n     println!(\"{}\", a + b);\n";
+
+        let result = apply_patch(ORIGINAL, diff, &PatchOptions::default()).unwrap();
+        assert_eq!(result.hunks[0].status, HunkStatus::Clean);
+        assert!(result.content.contains("let b = 3;"));
+        assert!(result.content.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_hunk_applies_at_offset() {
+        // Five lines were inserted above the hunk since the diff was made,
+        // and the patch has lost the trailing whitespace of one context line
+        let content = format!("// header\n{}", "\n".repeat(4)) + ORIGINAL;
+        let content = content.replace("let a = 1;", "let a = 1;   ");
+        let diff = "@@ -2,3 +2,3 @@\n     let a = 1;\n-    let b = 2;\n+    let b = 3;\n     println!(\"{}\", a + b);\n";
+
+        let result = apply_patch(&content, diff, &PatchOptions::default()).unwrap();
+        assert_eq!(
+            result.hunks[0].status,
+            HunkStatus::Fuzzy { offset: 5, fuzz: 0 }
+        );
+        assert!(result.content.contains("    let b = 3;\n"));
+        // The file's own version of the context line is kept
+        assert!(result.content.contains("let a = 1;   \n"));
+    }
+
+    #[test]
+    fn test_fuzz_drops_stale_context() {
+        let content = ORIGINAL.replace("println!(\"{}\", a + b);", "dbg!(a + b);");
+        let diff = "@@ -2,3 +2,3 @@\n     let a = 1;\n-    let b = 2;\n+    let b = 3;\n     println!(\"{}\", a + b);\n";
+
+        let result = apply_patch(&content, diff, &PatchOptions::default()).unwrap();
+        assert_eq!(
+            result.hunks[0].status,
+            HunkStatus::Fuzzy { offset: 0, fuzz: 1 }
+        );
+        assert!(result.content.contains("let b = 3;\n    dbg!(a + b);"));
+    }
+
+    #[test]
+    fn test_conflicting_hunk_fails() {
+        let content = ORIGINAL.replace("let b = 2;", "let b = compute();");
+        let diff = "@@ -2,3 +2,3 @@\n     let a = 1;\n-    let b = 2;\n+    let b = 3;\n     println!(\"{}\", a + b);\n@@ -5,1 +5,2 @@\n }\n+// end\n";
+
+        let result = apply_patch(&content, diff, &PatchOptions::default()).unwrap();
+        assert_eq!(result.hunks[0].status, HunkStatus::Failed);
+        assert_eq!(result.hunks[1].status, HunkStatus::Clean);
+        assert_eq!(result.failed_hunks(), vec![0]);
+        assert!(result.content.contains("let b = compute();"));
+        assert!(result.content.ends_with("}\n// end\n"));
+    }
+
+    #[test]
+    fn test_context_only_hunk_does_not_panic() {
+        let diff = "@@ -2,2 +2,2 @@\n     let a = 1;\n     let b = 2;\n";
+        let result = apply_patch(ORIGINAL, diff, &PatchOptions::default()).unwrap();
+        assert_eq!(result.hunks[0].status, HunkStatus::Clean);
+        assert_eq!(result.content, ORIGINAL);
+
+        // Stale context: fuzz must not trim the hunk past itself
+        let diff = "@@ -2,2 +2,2 @@\n     let x = 1;\n     let y = 2;\n";
+        let result = apply_patch(ORIGINAL, diff, &PatchOptions::default()).unwrap();
+        assert_eq!(result.hunks[0].status, HunkStatus::Failed);
+        assert_eq!(result.content, ORIGINAL);
+
+        let hunk = &parse_unified_diff(diff).unwrap()[0];
+        assert_eq!(trimmed_range(hunk, 2), 2..2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_unified_diff("no hunks here"),
+            Err(PatchError::NoHunks)
+        );
+        assert!(matches!(
+            parse_unified_diff("@@ -x +1 @@"),
+            Err(PatchError::InvalidHeader(_))
+        ));
+    }
+}
@@ -17,7 +17,7 @@ use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use super::{compute_content_hash, FileReadRecord, SharedFileReadHistory};
+use super::{compute_content_hash, resolve_tool_path, FileReadRecord, SharedFileReadHistory};
 use crate::tools::base::{PermissionCheckResult, Tool};
 use crate::tools::context::{ToolContext, ToolOptions, ToolResult};
 use crate::tools::error::ToolError;
@@ -149,6 +149,8 @@ pub struct ReadTool {
     read_history: SharedFileReadHistory,
     /// Whether PDF reading is enabled
     pdf_enabled: bool,
+    /// Whether paths must stay inside the working directory
+    restrict_to_working_directory: bool,
 }
 
 impl ReadTool {
@@ -157,6 +159,7 @@ impl ReadTool {
         Self {
             read_history,
             pdf_enabled: false,
+            restrict_to_working_directory: false,
         }
     }
 
@@ -166,6 +169,12 @@ impl ReadTool {
         self
     }
 
+    /// Reject paths that escape the working directory
+    pub fn with_restrict_to_working_directory(mut self, restrict: bool) -> Self {
+        self.restrict_to_working_directory = restrict;
+        self
+    }
+
     /// Get the shared read history
     pub fn read_history(&self) -> &SharedFileReadHistory {
         &self.read_history
@@ -189,7 +198,7 @@ impl ReadTool {
         range: Option<LineRange>,
         context: &ToolContext,
     ) -> Result<String, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check file exists
         if !full_path.exists() {
@@ -275,12 +284,8 @@ impl ReadTool {
     }
 
     /// Resolve a path relative to the working directory
-    fn resolve_path(&self, path: &Path, context: &ToolContext) -> PathBuf {
-        if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            context.working_directory.join(path)
-        }
+    fn resolve_path(&self, path: &Path, context: &ToolContext) -> Result<PathBuf, ToolError> {
+        resolve_tool_path(path, context, self.restrict_to_working_directory)
     }
 }
 
@@ -303,7 +308,7 @@ impl ReadTool {
         path: &Path,
         context: &ToolContext,
     ) -> Result<String, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check file exists
         if !full_path.exists() {
@@ -423,7 +428,7 @@ impl ReadTool {
             ));
         }
 
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check file exists
         if !full_path.exists() {
@@ -507,7 +512,7 @@ impl ReadTool {
     ///
     /// Requirements: 4.2 (extended)
     pub async fn read_svg(&self, path: &Path, context: &ToolContext) -> Result<String, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check file exists
         if !full_path.exists() {
@@ -587,7 +592,7 @@ impl ReadTool {
         path: &Path,
         context: &ToolContext,
     ) -> Result<String, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check file exists and read content
         let (content, metadata, notebook) = self.load_notebook_file(&full_path)?;
@@ -942,7 +947,10 @@ impl Tool for ReadTool {
         };
 
         let path = Path::new(path_str);
-        let full_path = self.resolve_path(path, context);
+        let full_path = match self.resolve_path(path, context) {
+            Ok(full_path) => full_path,
+            Err(e) => return PermissionCheckResult::deny(e.to_string()),
+        };
 
         // Check if path is within allowed directories
         // For now, allow all reads (permission manager handles restrictions)
@@ -992,7 +1000,7 @@ impl ReadTool {
         range: Option<LineRange>,
         context: &ToolContext,
     ) -> Result<String, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Load and validate file
         let (content, metadata, text) = self.load_text_file(&full_path)?;
@@ -1595,4 +1603,25 @@ mod tests {
         let result = tool.check_permissions(&params, &context).await;
         assert!(result.is_denied());
     }
+
+    #[tokio::test]
+    async fn test_restricted_read_rejects_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().join("workspace");
+        fs::create_dir(&workspace).unwrap();
+        fs::write(temp_dir.path().join("outside.txt"), "secret").unwrap();
+
+        let tool = create_read_tool().with_restrict_to_working_directory(true);
+        let context = create_test_context(&workspace);
+
+        let result = tool
+            .read_text(Path::new("../outside.txt"), None, &context)
+            .await;
+        assert!(matches!(result, Err(ToolError::PermissionDenied(_))));
+
+        let permission = tool
+            .check_permissions(&serde_json::json!({"path": "../outside.txt"}), &context)
+            .await;
+        assert!(permission.is_denied());
+    }
 }
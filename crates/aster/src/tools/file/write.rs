@@ -13,7 +13,8 @@ use std::path::{Path, PathBuf};
 use async_trait::async_trait;
 use tracing::{debug, warn};
 
-use super::{compute_content_hash, FileReadRecord, SharedFileReadHistory};
+use super::{compute_content_hash, resolve_tool_path, FileReadRecord, SharedFileReadHistory};
+use crate::security::secrets::{SecretFinding, SecretScanAction, SecretScanner};
 use crate::tools::base::{PermissionCheckResult, Tool};
 use crate::tools::context::{ToolContext, ToolOptions, ToolResult};
 use crate::tools::error::ToolError;
@@ -35,6 +36,10 @@ pub struct WriteTool {
     read_history: SharedFileReadHistory,
     /// Whether to require read before overwrite
     require_read_before_overwrite: bool,
+    /// Scans content for secrets before writing; disabled when `None`
+    secret_scanner: Option<SecretScanner>,
+    /// Whether paths must stay inside the working directory
+    restrict_to_working_directory: bool,
 }
 
 impl WriteTool {
@@ -43,6 +48,8 @@ impl WriteTool {
         Self {
             read_history,
             require_read_before_overwrite: true,
+            secret_scanner: None,
+            restrict_to_working_directory: false,
         }
     }
 
@@ -52,18 +59,26 @@ impl WriteTool {
         self
     }
 
+    /// Scan content for secrets before writing it
+    pub fn with_secret_scanner(mut self, scanner: SecretScanner) -> Self {
+        self.secret_scanner = Some(scanner);
+        self
+    }
+
+    /// Reject paths that escape the working directory
+    pub fn with_restrict_to_working_directory(mut self, restrict: bool) -> Self {
+        self.restrict_to_working_directory = restrict;
+        self
+    }
+
     /// Get the shared read history
     pub fn read_history(&self) -> &SharedFileReadHistory {
         &self.read_history
     }
 
     /// Resolve a path relative to the working directory
-    fn resolve_path(&self, path: &Path, context: &ToolContext) -> PathBuf {
-        if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            context.working_directory.join(path)
-        }
+    fn resolve_path(&self, path: &Path, context: &ToolContext) -> Result<PathBuf, ToolError> {
+        resolve_tool_path(path, context, self.restrict_to_working_directory)
     }
 }
 
@@ -84,7 +99,7 @@ impl WriteTool {
         content: &str,
         context: &ToolContext,
     ) -> Result<ToolResult, ToolError> {
-        let full_path = self.resolve_path(path, context);
+        let full_path = self.resolve_path(path, context)?;
 
         // Check content size
         if content.len() > MAX_WRITE_SIZE {
@@ -95,6 +110,8 @@ impl WriteTool {
             )));
         }
 
+        let secret_findings = self.check_secrets(&full_path, content)?;
+
         // Check if file exists and validate read history
         if full_path.exists() && self.require_read_before_overwrite {
             let history = self.read_history.read().unwrap();
@@ -156,18 +173,70 @@ impl WriteTool {
             content.len()
         );
 
-        Ok(ToolResult::success(format!(
+        let mut result = ToolResult::success(format!(
             "Successfully wrote {} bytes to {}",
             content.len(),
             full_path.display()
         ))
         .with_metadata("path", serde_json::json!(full_path.to_string_lossy()))
-        .with_metadata("size", serde_json::json!(content.len())))
+        .with_metadata("size", serde_json::json!(content.len()));
+
+        if !secret_findings.is_empty() {
+            result = result.with_metadata(
+                "secret_warnings",
+                serde_json::json!(secret_findings
+                    .iter()
+                    .map(|f| format!("line {}: {} ({})", f.line, f.kind, f.preview))
+                    .collect::<Vec<_>>()),
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Run the secret scanner, failing in block mode if anything is found
+    fn check_secrets(
+        &self,
+        full_path: &Path,
+        content: &str,
+    ) -> Result<Vec<SecretFinding>, ToolError> {
+        let Some(scanner) = &self.secret_scanner else {
+            return Ok(Vec::new());
+        };
+        let findings = scanner.scan(content);
+        if findings.is_empty() {
+            return Ok(findings);
+        }
+
+        let summary = findings
+            .iter()
+            .map(|f| format!("line {}: {}", f.line, f.kind))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match scanner.action() {
+            SecretScanAction::Block => Err(ToolError::execution_failed(format!(
+                "Refusing to write {}: content appears to contain secrets ({}). \
+                 Use an environment variable or placeholder instead.",
+                full_path.display(),
+                summary
+            ))),
+            SecretScanAction::Warn => {
+                warn!(
+                    "Possible secrets written to {}: {}",
+                    full_path.display(),
+                    summary
+                );
+                Ok(findings)
+            }
+        }
     }
 
     /// Check if a file can be written (exists and has been read, or doesn't exist)
     pub fn can_write(&self, path: &Path, context: &ToolContext) -> bool {
-        let full_path = self.resolve_path(path, context);
+        let Ok(full_path) = self.resolve_path(path, context) else {
+            return false;
+        };
 
         if !full_path.exists() {
             return true;
@@ -252,7 +321,10 @@ impl Tool for WriteTool {
         };
 
         let path = Path::new(path_str);
-        let full_path = self.resolve_path(path, context);
+        let full_path = match self.resolve_path(path, context) {
+            Ok(full_path) => full_path,
+            Err(e) => return PermissionCheckResult::deny(e.to_string()),
+        };
 
         // Check if file exists and hasn't been read
         if full_path.exists() && self.require_read_before_overwrite {
@@ -568,4 +640,51 @@ mod tests {
         // After writing, the file should be in read history
         assert!(tool.read_history.read().unwrap().has_read(&file_path));
     }
+
+    #[tokio::test]
+    async fn test_secret_scanner_blocks_write() {
+        use crate::security::secrets::SecretScannerConfig;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("credentials");
+
+        let tool =
+            create_write_tool().with_secret_scanner(SecretScanner::new(SecretScannerConfig {
+                action: SecretScanAction::Block,
+                ..Default::default()
+            }));
+        let context = create_test_context(temp_dir.path());
+
+        let result = tool
+            .write_file(
+                &file_path,
+                "aws_access_key_id = AKIAZ7Q3L5N2X4V6B8D0\n",
+                &context,
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert!(!file_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_secret_scanner_warns_and_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("credentials");
+
+        let tool = create_write_tool().with_secret_scanner(SecretScanner::default());
+        let context = create_test_context(temp_dir.path());
+
+        let result = tool
+            .write_file(
+                &file_path,
+                "aws_access_key_id = AKIAZ7Q3L5N2X4V6B8D0\n",
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(file_path.exists());
+        assert!(result.metadata.contains_key("secret_warnings"));
+    }
 }
@@ -0,0 +1,494 @@
+//! Git Conflict Tool
+//!
+//! Finds merge conflict blocks in files and resolves them by choosing a side
+//! or keeping both. Parsing (including diff3 and nested markers) lives in
+//! `crate::git`.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::base::{PermissionCheckResult, Tool};
+use super::context::{ToolContext, ToolOptions, ToolResult};
+use super::error::ToolError;
+use crate::git::{
+    get_conflicted_files, get_repo_root, parse_conflicts, resolve_conflicts, ConflictResolution,
+};
+use crate::security::sanitize_path;
+
+/// Maximum characters shown per conflict side in scan output
+const MAX_SIDE_PREVIEW_CHARS: usize = 2000;
+
+/// Git Conflict Tool for scanning and resolving merge conflicts
+#[derive(Debug, Default)]
+pub struct GitConflictTool;
+
+impl GitConflictTool {
+    /// Create a new GitConflictTool
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Scan a file, or every conflicted file in the repository
+    ///
+    /// When scanning the whole repository, files that cannot be read or
+    /// parsed (e.g. binary files) are skipped and listed in the output.
+    fn scan(&self, path: Option<PathBuf>, context: &ToolContext) -> Result<ToolResult, ToolError> {
+        let scan_repository = path.is_none();
+        let files = match path {
+            Some(path) => vec![path],
+            None => {
+                // `git diff --name-only` prints paths relative to the repository root
+                let root = get_repo_root(&context.working_directory)
+                    .map_err(ToolError::execution_failed)?;
+                get_conflicted_files(&context.working_directory)
+                    .map_err(ToolError::execution_failed)?
+                    .into_iter()
+                    .map(|file| root.join(file))
+                    .collect()
+            }
+        };
+
+        let mut output = String::new();
+        let mut report = Vec::new();
+        let mut skipped = Vec::new();
+        for file in &files {
+            let blocks = match std::fs::read_to_string(file)
+                .map_err(|e| e.to_string())
+                .and_then(|content| parse_conflicts(&content))
+            {
+                Ok(blocks) => blocks,
+                Err(e) if scan_repository => {
+                    skipped.push(format!("{}: {}", file.display(), e));
+                    continue;
+                }
+                Err(e) => {
+                    return Err(ToolError::execution_failed(format!(
+                        "{}: {}",
+                        file.display(),
+                        e
+                    )))
+                }
+            };
+            if blocks.is_empty() {
+                continue;
+            }
+
+            let display = file
+                .strip_prefix(&context.working_directory)
+                .unwrap_or(file);
+            output.push_str(&format!(
+                "{}: {} conflict(s)\n",
+                display.display(),
+                blocks.len()
+            ));
+            for block in &blocks {
+                output.push_str(&format!(
+                    "\nConflict {} (lines {}-{}):\n",
+                    block.id, block.start_line, block.end_line
+                ));
+                push_side(
+                    &mut output,
+                    "ours",
+                    block.ours_label.as_deref(),
+                    &block.ours,
+                );
+                if let Some(base) = &block.base {
+                    push_side(&mut output, "base", block.base_label.as_deref(), base);
+                }
+                push_side(
+                    &mut output,
+                    "theirs",
+                    block.theirs_label.as_deref(),
+                    &block.theirs,
+                );
+            }
+            output.push('\n');
+            report.push(serde_json::json!({
+                "path": file.display().to_string(),
+                "conflicts": blocks,
+            }));
+        }
+
+        if report.is_empty() {
+            output = "No merge conflicts found\n".to_string();
+        } else {
+            output.push_str(
+                "Resolve with operation=resolve, a resolution (ours, theirs, both) \
+                 and optionally the conflict ids.\n",
+            );
+        }
+        if !skipped.is_empty() {
+            output.push_str("\nSkipped unreadable files:\n");
+            for entry in &skipped {
+                output.push_str(&format!("  {}\n", entry));
+            }
+        }
+
+        Ok(ToolResult::success(output)
+            .with_metadata("files", serde_json::json!(report))
+            .with_metadata("skipped", serde_json::json!(skipped)))
+    }
+
+    /// Resolve conflicts in a file
+    fn resolve(
+        &self,
+        path: &Path,
+        resolution: ConflictResolution,
+        ids: Option<Vec<usize>>,
+    ) -> Result<ToolResult, ToolError> {
+        let content = std::fs::read_to_string(path)?;
+        let blocks = parse_conflicts(&content).map_err(ToolError::execution_failed)?;
+        if blocks.is_empty() {
+            return Err(ToolError::execution_failed(format!(
+                "No merge conflicts found in {}",
+                path.display()
+            )));
+        }
+
+        let ids = ids.unwrap_or_else(|| blocks.iter().map(|b| b.id).collect());
+        let choices: HashMap<usize, ConflictResolution> =
+            ids.iter().map(|id| (*id, resolution)).collect();
+        let resolved = resolve_conflicts(&content, &choices).map_err(ToolError::invalid_params)?;
+        std::fs::write(path, &resolved)?;
+
+        let remaining = blocks.len() - choices.len();
+        let mut output = format!(
+            "Resolved {} conflict(s) in {} using {}",
+            choices.len(),
+            path.display(),
+            resolution.as_str()
+        );
+        if remaining > 0 {
+            output.push_str(&format!(
+                ". {} conflict(s) remain; scan again to see their new ids",
+                remaining
+            ));
+        }
+
+        Ok(ToolResult::success(output)
+            .with_metadata("path", serde_json::json!(path.display().to_string()))
+            .with_metadata("resolved", serde_json::json!(choices.len()))
+            .with_metadata("remaining", serde_json::json!(remaining)))
+    }
+}
+
+/// Append one side of a conflict to the scan output
+fn push_side(output: &mut String, side: &str, label: Option<&str>, content: &str) {
+    match label {
+        Some(label) => output.push_str(&format!("  {} ({}):\n", side, label)),
+        None => output.push_str(&format!("  {}:\n", side)),
+    }
+    let preview: String = content.chars().take(MAX_SIDE_PREVIEW_CHARS).collect();
+    for line in preview.lines() {
+        output.push_str(&format!("    | {}\n", line));
+    }
+    if preview.len() < content.len() {
+        output.push_str("    | ...\n");
+    }
+}
+
+/// Resolve a path parameter against the working directory
+fn resolve_path(path_str: &str, context: &ToolContext) -> PathBuf {
+    if Path::new(path_str).is_absolute() {
+        PathBuf::from(path_str)
+    } else {
+        context.working_directory.join(path_str)
+    }
+}
+
+/// Resolve a file to write, rejecting paths outside the repository
+///
+/// Outside a git repository the working directory is the boundary.
+fn resolve_write_path(path_str: &str, context: &ToolContext) -> Result<PathBuf, ToolError> {
+    let root = get_repo_root(&context.working_directory)
+        .unwrap_or_else(|_| context.working_directory.clone());
+    Ok(sanitize_path(&root, &resolve_path(path_str, context))?)
+}
+
+#[async_trait]
+impl Tool for GitConflictTool {
+    fn name(&self) -> &str {
+        "git_conflicts"
+    }
+
+    fn description(&self) -> &str {
+        "Find and resolve git merge conflicts. operation=scan lists every conflict \
+         block (ours/base/theirs with line numbers) in a file, or in all conflicted \
+         files when no path is given. operation=resolve replaces conflict blocks in \
+         a file with ours, theirs, or both (ours then theirs), for all conflicts or \
+         only the given ids."
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["scan", "resolve"],
+                    "description": "Scan for conflicts or resolve them"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File to scan or resolve (required for resolve)"
+                },
+                "resolution": {
+                    "type": "string",
+                    "enum": ["ours", "theirs", "both"],
+                    "description": "Side to keep (required for resolve)"
+                },
+                "ids": {
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 1 },
+                    "description": "Conflict ids to resolve (default: all)"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        if context.is_cancelled() {
+            return Err(ToolError::Cancelled);
+        }
+
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::invalid_params("Missing required parameter: operation"))?;
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(|p| resolve_path(p, context));
+
+        match operation {
+            "scan" => self.scan(path, context),
+            "resolve" => {
+                let path = params
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| ToolError::invalid_params("Missing required parameter: path"))?;
+                let path = resolve_write_path(path, context)?;
+                let resolution = params
+                    .get("resolution")
+                    .and_then(|v| v.as_str())
+                    .and_then(ConflictResolution::parse)
+                    .ok_or_else(|| {
+                        ToolError::invalid_params(
+                            "Missing or invalid parameter: resolution (ours, theirs or both)",
+                        )
+                    })?;
+                let ids = params
+                    .get("ids")
+                    .map(|v| serde_json::from_value::<Vec<usize>>(v.clone()))
+                    .transpose()
+                    .map_err(|e| ToolError::invalid_params(format!("Invalid ids: {}", e)))?;
+                self.resolve(&path, resolution, ids)
+            }
+            other => Err(ToolError::invalid_params(format!(
+                "Invalid operation: {}. Must be one of: scan, resolve",
+                other
+            ))),
+        }
+    }
+
+    async fn check_permissions(
+        &self,
+        params: &serde_json::Value,
+        context: &ToolContext,
+    ) -> PermissionCheckResult {
+        if params.get("operation").and_then(|v| v.as_str()) != Some("resolve") {
+            return PermissionCheckResult::allow();
+        }
+        let Some(path) = params.get("path").and_then(|v| v.as_str()) else {
+            return PermissionCheckResult::deny("Missing path parameter");
+        };
+        let full_path = match resolve_write_path(path, context) {
+            Ok(full_path) => full_path,
+            Err(e) => return PermissionCheckResult::deny(e.to_string()),
+        };
+        if !full_path.is_file() {
+            return PermissionCheckResult::deny(format!("File does not exist: {}", path));
+        }
+
+        let resolution = params
+            .get("resolution")
+            .and_then(|v| v.as_str())
+            .unwrap_or("the chosen side");
+        PermissionCheckResult::ask(format!(
+            "Resolve merge conflicts in {} using {}?",
+            full_path.display(),
+            resolution
+        ))
+    }
+
+    fn options(&self) -> ToolOptions {
+        ToolOptions::new().with_max_retries(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MERGED: &str = "\
+use std::io;
+<<<<<<< HEAD
+use std::fs;
+=======
+use std::path::Path;
+>>>>>>> feature
+fn main() {
+<<<<<<< HEAD
+    run(1);
+||||||| base
+    run(0);
+=======
+    run(2);
+>>>>>>> feature
+}
+";
+
+    #[tokio::test]
+    async fn test_scan_and_resolve_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("main.rs");
+        std::fs::write(&file, MERGED).unwrap();
+        let tool = GitConflictTool::new();
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+
+        let result = tool
+            .execute(
+                serde_json::json!({"operation": "scan", "path": "main.rs"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        let output = result.output.unwrap();
+        assert!(output.contains("main.rs: 2 conflict(s)"));
+        assert!(output.contains("Conflict 2 (lines 8-14)"));
+        assert!(output.contains("  base (base):\n    |     run(0);"));
+        let conflicts = &result.metadata["files"][0]["conflicts"];
+        assert_eq!(conflicts[0]["theirs"], "use std::path::Path;\n");
+
+        let params = serde_json::json!({
+            "operation": "resolve",
+            "path": "main.rs",
+            "resolution": "theirs",
+            "ids": [2]
+        });
+        let permission = tool.check_permissions(&params, &context).await;
+        assert!(!permission.is_allowed() && !permission.is_denied());
+        let result = tool.execute(params, &context).await.unwrap();
+        assert_eq!(result.metadata["remaining"], 1);
+
+        let result = tool
+            .execute(
+                serde_json::json!({"operation": "resolve", "path": "main.rs", "resolution": "both"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.metadata["resolved"], 1);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "use std::io;\nuse std::fs;\nuse std::path::Path;\nfn main() {\n    run(2);\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_invalid_input() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("clean.rs"), "fn main() {}\n").unwrap();
+        let tool = GitConflictTool::new();
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+
+        let no_conflicts = serde_json::json!({
+            "operation": "resolve", "path": "clean.rs", "resolution": "ours"
+        });
+        assert!(tool.execute(no_conflicts, &context).await.is_err());
+
+        let bad_resolution = serde_json::json!({
+            "operation": "resolve", "path": "clean.rs", "resolution": "mine"
+        });
+        assert!(matches!(
+            tool.execute(bad_resolution, &context).await,
+            Err(ToolError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_outside_repository_is_denied() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let outside = temp_dir.path().join("outside.rs");
+        std::fs::write(&outside, MERGED).unwrap();
+        let tool = GitConflictTool::new();
+        let context = ToolContext::new(repo);
+
+        for path in ["../outside.rs", outside.to_str().unwrap()] {
+            let params = serde_json::json!({
+                "operation": "resolve", "path": path, "resolution": "ours"
+            });
+            assert!(tool.check_permissions(&params, &context).await.is_denied());
+            assert!(tool.execute(params, &context).await.is_err());
+        }
+        assert_eq!(std::fs::read_to_string(&outside).unwrap(), MERGED);
+    }
+
+    #[tokio::test]
+    async fn test_scan_repository_from_subdirectory_skips_unreadable() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        std::fs::create_dir(repo.join("src")).unwrap();
+        std::fs::write(repo.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(repo.join("data.bin"), "0\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "base"]);
+        git(&["checkout", "-qb", "feature"]);
+        std::fs::write(repo.join("src/lib.rs"), "fn b() {}\n").unwrap();
+        std::fs::write(repo.join("data.bin"), "2\n").unwrap();
+        git(&["commit", "-qam", "feature"]);
+        git(&["checkout", "-q", "main"]);
+        std::fs::write(repo.join("src/lib.rs"), "fn c() {}\n").unwrap();
+        std::fs::write(repo.join("data.bin"), "1\n").unwrap();
+        git(&["commit", "-qam", "main"]);
+        let _ = std::process::Command::new("git")
+            .args(["merge", "-q", "feature"])
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        // A conflicted file that is not valid UTF-8
+        std::fs::write(repo.join("data.bin"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let tool = GitConflictTool::new();
+        let context = ToolContext::new(repo.join("src"));
+        let result = tool
+            .execute(serde_json::json!({"operation": "scan"}), &context)
+            .await
+            .unwrap();
+        let output = result.output.unwrap();
+        assert!(output.contains("1 conflict(s)"), "{}", output);
+        assert_eq!(result.metadata["files"].as_array().unwrap().len(), 1);
+        assert_eq!(result.metadata["skipped"].as_array().unwrap().len(), 1);
+    }
+}
@@ -1,20 +1,33 @@
 //! LSP Tool Implementation
 //!
 //! Provides Language Server Protocol integration for code intelligence features.
-//! Supports go-to-definition, find-references, hover, completion, and diagnostics.
+//! Supports go-to-definition, find-references, hover, completion, diagnostics,
+//! and code actions (quick-fixes and refactorings) applied through `EditTool`.
 //!
 //! Requirements: 7.1, 7.2, 7.3, 7.4, 7.5, 7.6
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 use crate::tools::base::{PermissionCheckResult, Tool};
 use crate::tools::context::{ToolContext, ToolResult};
+use crate::tools::diagnostics_tool::DiagnosticsStore;
 use crate::tools::error::ToolError;
+use crate::tools::file::{create_shared_history, EditTool};
+
+/// Default minimum query length for workspace symbol searches
+///
+/// Many servers return nothing (or the entire index) for an empty query.
+pub const DEFAULT_MIN_WORKSPACE_QUERY_LEN: usize = 1;
+
+/// Default number of workspace symbols returned per page
+pub const DEFAULT_WORKSPACE_SYMBOL_PAGE_SIZE: usize = 50;
 
 /// Position in a text document (0-indexed)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -163,7 +176,9 @@ pub enum LspOperation {
     Diagnostics,
     /// Get document symbols
     DocumentSymbol,
-    /// Search workspace symbols
+    /// Search symbols by name across the workspace (`workspace/symbol`)
+    ///
+    /// The query is passed in `LspArgs::query`.
     WorkspaceSymbol,
     /// Go to implementation
     Implementation,
@@ -173,6 +188,27 @@ pub enum LspOperation {
     IncomingCalls,
     /// Get outgoing calls
     OutgoingCalls,
+    /// Get code actions for a range (`textDocument/codeAction`)
+    CodeActions { range: Range },
+    /// Apply a code action returned by `CodeActions`
+    ///
+    /// Handled by `LspTool::apply_code_action`; never sent to the callback.
+    ApplyCodeAction,
+    /// Run a server command (`workspace/executeCommand`)
+    ///
+    /// The command is passed in `LspArgs::command`.
+    ExecuteCommand,
+}
+
+/// Arguments of operations that need more than a path and a position
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspArgs {
+    /// Symbol name for `WorkspaceSymbol`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Command for `ExecuteCommand`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<LspCommand>,
 }
 
 /// Symbol kind for document/workspace symbols
@@ -307,6 +343,53 @@ pub struct CallHierarchyOutgoingCall {
     pub from_ranges: Vec<Range>,
 }
 
+/// A textual change to a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// The range of text to replace
+    pub range: Range,
+    /// The replacement text (empty to delete)
+    pub new_text: String,
+}
+
+/// Changes to several documents
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceEdit {
+    /// Edits to apply, keyed by file path
+    pub changes: BTreeMap<PathBuf, Vec<TextEdit>>,
+}
+
+/// A command the server knows how to execute
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspCommand {
+    /// Title of the command
+    pub title: String,
+    /// Identifier of the command handler
+    pub command: String,
+    /// Arguments passed to the handler
+    #[serde(default)]
+    pub arguments: Vec<serde_json::Value>,
+}
+
+/// Code action (quick-fix or refactoring) offered by the server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeAction {
+    /// Identifier assigned by the tool, used to apply the action
+    #[serde(default)]
+    pub id: String,
+    /// Human-readable title (e.g., "Import `HashMap`")
+    pub title: String,
+    /// Action kind (e.g., "quickfix", "refactor.extract")
+    pub kind: Option<String>,
+    /// Whether the server marks this as the preferred fix
+    #[serde(default)]
+    pub is_preferred: bool,
+    /// Edit performed by the action
+    pub edit: Option<WorkspaceEdit>,
+    /// Command run after the edit is applied
+    pub command: Option<LspCommand>,
+}
+
 /// Result of an LSP operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -337,6 +420,10 @@ pub enum LspResult {
     OutgoingCalls {
         calls: Vec<CallHierarchyOutgoingCall>,
     },
+    /// Available code actions
+    CodeActions { actions: Vec<CodeAction> },
+    /// Value returned by an executed command
+    ExecuteCommand { result: Option<serde_json::Value> },
 }
 
 /// Callback type for LSP operations
@@ -348,6 +435,7 @@ pub type LspCallback = Arc<
             LspOperation,
             PathBuf,
             Option<Position>,
+            LspArgs,
         ) -> Pin<Box<dyn Future<Output = Result<LspResult, String>> + Send>>
         + Send
         + Sync,
@@ -361,6 +449,7 @@ pub type LspCallback = Arc<
 /// - Hover information
 /// - Code completion
 /// - Diagnostics
+/// - Code actions
 ///
 /// Requirements: 7.1, 7.2, 7.3, 7.4, 7.5, 7.6
 pub struct LspTool {
@@ -368,6 +457,16 @@ pub struct LspTool {
     callback: Option<LspCallback>,
     /// Supported file extensions (empty means all)
     supported_extensions: Vec<String>,
+    /// Minimum query length accepted for workspace symbol searches
+    min_workspace_query_len: usize,
+    /// Editor used to apply code action edits
+    edit_tool: EditTool,
+    /// Code actions from the latest request, with the file they apply to
+    code_actions: Mutex<HashMap<String, (PathBuf, CodeAction)>>,
+    /// Counter used to assign code action ids
+    next_code_action_id: AtomicU64,
+    /// Store that receives the diagnostics returned by the callback
+    diagnostics_store: Option<Arc<DiagnosticsStore>>,
 }
 
 impl Default for LspTool {
@@ -380,11 +479,18 @@ impl LspTool {
     /// Create a new LspTool without a callback
     ///
     /// Note: Without a callback, the tool will return an error when executed.
-    /// Use `with_callback` to set up the LSP handler.
+    /// Use `with_callback` to set up the LSP handler. Code actions are applied
+    /// with an `EditTool` that has its own empty read history; use
+    /// `with_edit_tool` to share the registry's history.
     pub fn new() -> Self {
         Self {
             callback: None,
             supported_extensions: Vec::new(),
+            min_workspace_query_len: DEFAULT_MIN_WORKSPACE_QUERY_LEN,
+            edit_tool: EditTool::new(create_shared_history()),
+            code_actions: Mutex::new(HashMap::new()),
+            next_code_action_id: AtomicU64::new(0),
+            diagnostics_store: None,
         }
     }
 
@@ -400,6 +506,31 @@ impl LspTool {
         self
     }
 
+    /// Set the minimum query length for workspace symbol searches
+    ///
+    /// Use this for servers that reject or ignore short queries.
+    pub fn with_min_workspace_query_length(mut self, min_len: usize) -> Self {
+        self.min_workspace_query_len = min_len;
+        self
+    }
+
+    /// Set the editor used to apply code actions
+    ///
+    /// Pass an `EditTool` sharing the registry's read history so code actions
+    /// get the same read-before-edit and external modification checks.
+    pub fn with_edit_tool(mut self, edit_tool: EditTool) -> Self {
+        self.edit_tool = edit_tool;
+        self
+    }
+
+    /// Publish the diagnostics returned by the callback into a store
+    ///
+    /// Share the store with `DiagnosticsTool` so it sees what this tool fetched.
+    pub fn with_diagnostics_store(mut self, store: Arc<DiagnosticsStore>) -> Self {
+        self.diagnostics_store = Some(store);
+        self
+    }
+
     /// Check if a callback is configured
     pub fn has_callback(&self) -> bool {
         self.callback.is_some()
@@ -427,14 +558,39 @@ impl LspTool {
         path: &Path,
         position: Option<Position>,
     ) -> Result<LspResult, ToolError> {
+        self.execute_operation_with_args(operation, path, position, LspArgs::default())
+            .await
+    }
+
+    /// Execute an LSP operation that takes extra arguments
+    pub async fn execute_operation_with_args(
+        &self,
+        operation: LspOperation,
+        path: &Path,
+        position: Option<Position>,
+        args: LspArgs,
+    ) -> Result<LspResult, ToolError> {
+        if operation == LspOperation::ApplyCodeAction {
+            return Err(ToolError::invalid_params(
+                "Code actions are applied with apply_code_action",
+            ));
+        }
+
         let callback = self
             .callback
             .as_ref()
             .ok_or_else(|| ToolError::execution_failed("LSP server is not available"))?;
 
-        callback(operation, path.to_path_buf(), position)
+        let result = callback(operation, path.to_path_buf(), position, args)
             .await
-            .map_err(ToolError::execution_failed)
+            .map_err(ToolError::execution_failed)?;
+
+        if let (Some(store), LspResult::Diagnostics { diagnostics }) =
+            (&self.diagnostics_store, &result)
+        {
+            store.publish(path, diagnostics.clone()).await;
+        }
+        Ok(result)
     }
 
     /// Go to definition
@@ -531,12 +687,31 @@ impl LspTool {
         }
     }
 
-    /// Search workspace symbols
+    /// Search symbols by name across the workspace
+    ///
+    /// `root` identifies the workspace (and thus the server) to query.
+    /// Queries shorter than the configured minimum length are rejected
+    /// before reaching the server.
     ///
     /// Requirements: 7.7
-    pub async fn workspace_symbols(&self, path: &Path) -> Result<Vec<WorkspaceSymbol>, ToolError> {
+    pub async fn workspace_symbols(
+        &self,
+        root: &Path,
+        query: &str,
+    ) -> Result<Vec<WorkspaceSymbol>, ToolError> {
+        if query.trim().chars().count() < self.min_workspace_query_len {
+            return Err(ToolError::invalid_params(format!(
+                "Workspace symbol query must be at least {} character(s)",
+                self.min_workspace_query_len
+            )));
+        }
+
+        let args = LspArgs {
+            query: Some(query.trim().to_string()),
+            ..Default::default()
+        };
         match self
-            .execute_operation(LspOperation::WorkspaceSymbol, path, None)
+            .execute_operation_with_args(LspOperation::WorkspaceSymbol, root, None, args)
             .await?
         {
             LspResult::WorkspaceSymbol { symbols } => Ok(symbols),
@@ -611,6 +786,176 @@ impl LspTool {
             _ => Err(ToolError::execution_failed("Unexpected LSP result type")),
         }
     }
+
+    /// Get code actions available for a range
+    ///
+    /// Each action is given an id and remembered until the next request, so
+    /// it can be applied with `apply_code_action`.
+    pub async fn code_actions(
+        &self,
+        path: &Path,
+        range: Range,
+    ) -> Result<Vec<CodeAction>, ToolError> {
+        let actions = match self
+            .execute_operation(LspOperation::CodeActions { range }, path, Some(range.start))
+            .await?
+        {
+            LspResult::CodeActions { actions } => actions,
+            _ => return Err(ToolError::execution_failed("Unexpected LSP result type")),
+        };
+
+        let mut cache = self.code_actions.lock().unwrap();
+        cache.clear();
+        Ok(actions
+            .into_iter()
+            .map(|mut action| {
+                let id = self.next_code_action_id.fetch_add(1, Ordering::Relaxed) + 1;
+                action.id = format!("action-{}", id);
+                cache.insert(action.id.clone(), (path.to_path_buf(), action.clone()));
+                action
+            })
+            .collect())
+    }
+
+    /// Apply a code action returned by `code_actions`
+    ///
+    /// The action's workspace edit is applied through `EditTool`. If the
+    /// action carries a command, it is then run via `workspace/executeCommand`.
+    /// Once an action is applied, the remaining actions are discarded since
+    /// their ranges may no longer match the files.
+    pub async fn apply_code_action(
+        &self,
+        action_id: &str,
+        context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let (path, action) = self
+            .code_actions
+            .lock()
+            .unwrap()
+            .get(action_id)
+            .cloned()
+            .ok_or_else(|| {
+                ToolError::invalid_params(format!(
+                    "Unknown code action: {}. Request code actions again first.",
+                    action_id
+                ))
+            })?;
+
+        if action.edit.is_none() && action.command.is_none() {
+            return Err(ToolError::execution_failed(format!(
+                "Code action '{}' has no edit or command",
+                action.title
+            )));
+        }
+
+        let edited = match &action.edit {
+            Some(edit) => self.apply_workspace_edit(edit, context).await?,
+            None => Vec::new(),
+        };
+        let command_result = match &action.command {
+            Some(command) => Some(self.execute_command(&path, command.clone()).await?),
+            None => None,
+        };
+        self.code_actions.lock().unwrap().clear();
+
+        let mut output = format!("Applied code action: {}\n", action.title);
+        for file in &edited {
+            output.push_str(&format!("  Edited {}\n", file.display()));
+        }
+        if let Some(command) = &action.command {
+            output.push_str(&format!("  Executed command {}\n", command.command));
+        }
+
+        Ok(ToolResult::success(output)
+            .with_metadata("operation", serde_json::json!("apply_code_action"))
+            .with_metadata("action_id", serde_json::json!(action_id))
+            .with_metadata("edited_files", serde_json::json!(edited))
+            .with_metadata(
+                "command_result",
+                serde_json::json!(command_result.flatten()),
+            ))
+    }
+
+    /// Run a server command (`workspace/executeCommand`)
+    pub async fn execute_command(
+        &self,
+        path: &Path,
+        command: LspCommand,
+    ) -> Result<Option<serde_json::Value>, ToolError> {
+        match self
+            .execute_operation_with_args(
+                LspOperation::ExecuteCommand,
+                path,
+                None,
+                LspArgs {
+                    command: Some(command),
+                    ..Default::default()
+                },
+            )
+            .await?
+        {
+            LspResult::ExecuteCommand { result } => Ok(result),
+            _ => Err(ToolError::execution_failed("Unexpected LSP result type")),
+        }
+    }
+
+    /// Apply a workspace edit through `EditTool`
+    ///
+    /// The new content of every file is computed before any file is written,
+    /// so an edit that does not fit the current content changes nothing. If
+    /// `EditTool` rejects or fails to write one of the files, the files
+    /// already written are restored to their original content.
+    async fn apply_workspace_edit(
+        &self,
+        edit: &WorkspaceEdit,
+        context: &ToolContext,
+    ) -> Result<Vec<PathBuf>, ToolError> {
+        let mut planned = Vec::new();
+        for (path, edits) in &edit.changes {
+            let path = resolve_path(&path.to_string_lossy(), context);
+            let original = std::fs::read_to_string(&path)?;
+            let updated = apply_text_edits(&original, edits)
+                .map_err(|e| ToolError::execution_failed(format!("{}: {}", path.display(), e)))?;
+            planned.push((path, original, updated));
+        }
+
+        let mut edited: Vec<(PathBuf, String)> = Vec::new();
+        for (path, original, updated) in planned {
+            if original == updated {
+                continue;
+            }
+            if let Err(e) = self
+                .edit_tool
+                .edit_file(&path, &original, &updated, context)
+                .await
+            {
+                for (path, original) in edited.iter().rev() {
+                    if let Err(restore_err) = std::fs::write(path, original) {
+                        tracing::warn!(
+                            "Failed to restore {} after a failed code action: {}",
+                            path.display(),
+                            restore_err
+                        );
+                    }
+                }
+                return Err(e);
+            }
+            edited.push((path, original));
+        }
+        Ok(edited.into_iter().map(|(path, _)| path).collect())
+    }
+
+    /// Files touched by a cached code action's workspace edit
+    fn code_action_files(&self, action_id: &str) -> Option<(CodeAction, Vec<PathBuf>)> {
+        let cache = self.code_actions.lock().unwrap();
+        let (_, action) = cache.get(action_id)?;
+        let files = action
+            .edit
+            .as_ref()
+            .map(|edit| edit.changes.keys().cloned().collect())
+            .unwrap_or_default();
+        Some((action.clone(), files))
+    }
 }
 
 #[async_trait]
@@ -622,7 +967,10 @@ impl Tool for LspTool {
     fn description(&self) -> &str {
         "Access Language Server Protocol features for code intelligence. \
          Supports go-to-definition, find-references, hover information, \
-         code completion, and diagnostics retrieval."
+         code completion, diagnostics retrieval, workspace-wide symbol \
+         search by name (workspace_symbol with a query), and code actions: \
+         code_actions lists quick-fixes and refactorings for a range, \
+         apply_code_action applies one by its action_id."
     }
 
     fn input_schema(&self) -> serde_json::Value {
@@ -634,13 +982,28 @@ impl Tool for LspTool {
                     "enum": [
                         "definition", "references", "hover", "completion", "diagnostics",
                         "document_symbol", "workspace_symbol", "implementation",
-                        "prepare_call_hierarchy", "incoming_calls", "outgoing_calls"
+                        "prepare_call_hierarchy", "incoming_calls", "outgoing_calls",
+                        "code_actions", "apply_code_action"
                     ],
                     "description": "The LSP operation to perform"
                 },
                 "path": {
                     "type": "string",
-                    "description": "Path to the file"
+                    "description": "Path to the file (for workspace_symbol, the workspace root)"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Symbol name to search for (required for workspace_symbol)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Number of workspace symbols to skip (default: 0)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum number of workspace symbols to return (default: 50)"
                 },
                 "line": {
                     "type": "integer",
@@ -649,6 +1012,18 @@ impl Tool for LspTool {
                 "character": {
                     "type": "integer",
                     "description": "Character offset (0-indexed, required for position-based operations)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "End line of the range for code_actions (0-indexed, default: line)"
+                },
+                "end_character": {
+                    "type": "integer",
+                    "description": "End character of the range for code_actions (0-indexed, default: character)"
+                },
+                "action_id": {
+                    "type": "string",
+                    "description": "Id of an action returned by code_actions (required for apply_code_action)"
                 }
             },
             "required": ["operation", "path"]
@@ -678,24 +1053,51 @@ impl Tool for LspTool {
             "prepare_call_hierarchy" => LspOperation::PrepareCallHierarchy,
             "incoming_calls" => LspOperation::IncomingCalls,
             "outgoing_calls" => LspOperation::OutgoingCalls,
+            "code_actions" => LspOperation::CodeActions {
+                range: parse_range(&params)?,
+            },
+            "apply_code_action" => LspOperation::ApplyCodeAction,
             _ => return Err(ToolError::invalid_params(format!(
                 "Invalid operation: {}. Must be one of: definition, references, hover, completion, diagnostics, \
-                 document_symbol, workspace_symbol, implementation, prepare_call_hierarchy, incoming_calls, outgoing_calls",
+                 document_symbol, workspace_symbol, implementation, prepare_call_hierarchy, incoming_calls, outgoing_calls, \
+                 code_actions, apply_code_action",
                 operation_str
             ))),
         };
 
+        if operation == LspOperation::ApplyCodeAction {
+            let action_id = params
+                .get("action_id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| {
+                    ToolError::invalid_params(
+                        "Missing required parameter: action_id (required for apply_code_action)",
+                    )
+                })?;
+            return self.apply_code_action(action_id, context).await;
+        }
+
+        if operation == LspOperation::WorkspaceSymbol {
+            let query = params
+                .get("query")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| {
+                    ToolError::invalid_params(
+                        "Missing required parameter: query (required for workspace_symbol)",
+                    )
+                })?;
+            return self
+                .execute_workspace_symbols(&params, query, context)
+                .await;
+        }
+
         // Parse path
         let path_str = params
             .get("path")
             .and_then(|v| v.as_str())
             .ok_or_else(|| ToolError::invalid_params("Missing required parameter: path"))?;
 
-        let path = if Path::new(path_str).is_absolute() {
-            PathBuf::from(path_str)
-        } else {
-            context.working_directory.join(path_str)
-        };
+        let path = resolve_path(path_str, context);
 
         // Check file extension support
         if !self.is_extension_supported(&path) {
@@ -708,9 +1110,7 @@ impl Tool for LspTool {
         // Parse position (required for most operations)
         let needs_position = !matches!(
             operation,
-            LspOperation::Diagnostics
-                | LspOperation::DocumentSymbol
-                | LspOperation::WorkspaceSymbol
+            LspOperation::Diagnostics | LspOperation::DocumentSymbol
         );
 
         let position = if needs_position {
@@ -735,7 +1135,12 @@ impl Tool for LspTool {
         };
 
         // Execute the operation
-        let result = self.execute_operation(operation, &path, position).await?;
+        let result = match operation {
+            LspOperation::CodeActions { range } => LspResult::CodeActions {
+                actions: self.code_actions(&path, range).await?,
+            },
+            operation => self.execute_operation(operation, &path, position).await?,
+        };
 
         // Format the output
         let output = format_lsp_result(&result, &path);
@@ -748,14 +1153,192 @@ impl Tool for LspTool {
 
     async fn check_permissions(
         &self,
-        _params: &serde_json::Value,
-        _context: &ToolContext,
+        params: &serde_json::Value,
+        context: &ToolContext,
     ) -> PermissionCheckResult {
-        // LSP operations are read-only, so they're always allowed
+        // Applying a code action edits files, so every file goes through
+        // EditTool's permission check and the user confirms the edit
+        if params.get("operation").and_then(|v| v.as_str()) == Some("apply_code_action") {
+            let Some((action, files)) = params
+                .get("action_id")
+                .and_then(|v| v.as_str())
+                .and_then(|id| self.code_action_files(id))
+            else {
+                return PermissionCheckResult::deny(
+                    "Unknown code action. Request code actions again first.",
+                );
+            };
+
+            for file in &files {
+                let edit_params = serde_json::json!({ "path": file });
+                let result = self
+                    .edit_tool
+                    .check_permissions(&edit_params, context)
+                    .await;
+                if result.is_denied() {
+                    return result;
+                }
+            }
+
+            let mut message = format!("Apply code action '{}'", action.title);
+            if !files.is_empty() {
+                let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+                message.push_str(&format!(", editing {}", names.join(", ")));
+            }
+            if let Some(command) = &action.command {
+                message.push_str(&format!(", and run command {}", command.command));
+            }
+            message.push('?');
+            return PermissionCheckResult::ask(message);
+        }
+
+        // Other LSP operations are read-only, so they're always allowed
         PermissionCheckResult::allow()
     }
 }
 
+impl LspTool {
+    /// Run a paginated workspace symbol search for the tool interface
+    async fn execute_workspace_symbols(
+        &self,
+        params: &serde_json::Value,
+        query: &str,
+        context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let root = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(|p| resolve_path(p, context))
+            .ok_or_else(|| ToolError::invalid_params("Missing required parameter: path"))?;
+        let offset = params.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|l| l.max(1) as usize)
+            .unwrap_or(DEFAULT_WORKSPACE_SYMBOL_PAGE_SIZE);
+
+        let symbols = self.workspace_symbols(&root, query).await?;
+        let total = symbols.len();
+        let page: Vec<WorkspaceSymbol> = symbols.into_iter().skip(offset).take(limit).collect();
+        let next_offset = offset + page.len();
+        let has_more = next_offset < total;
+
+        let result = LspResult::WorkspaceSymbol { symbols: page };
+        let mut output = format_lsp_result(&result, &root);
+        if has_more {
+            output.push_str(&format!(
+                "Showing {}-{} of {} symbols. Use offset={} to see more.\n",
+                offset + 1,
+                next_offset,
+                total,
+                next_offset
+            ));
+        }
+
+        Ok(ToolResult::success(output)
+            .with_metadata("operation", serde_json::json!("workspace_symbol"))
+            .with_metadata("query", serde_json::json!(query))
+            .with_metadata("path", serde_json::json!(root.display().to_string()))
+            .with_metadata("total", serde_json::json!(total))
+            .with_metadata("offset", serde_json::json!(offset))
+            .with_metadata("has_more", serde_json::json!(has_more))
+            .with_metadata("result", serde_json::to_value(&result).unwrap_or_default()))
+    }
+}
+
+/// Parse the range for `code_actions`; the end defaults to the start
+fn parse_range(params: &serde_json::Value) -> Result<Range, ToolError> {
+    let get = |name: &str| params.get(name).and_then(|v| v.as_u64()).map(|v| v as u32);
+    let start = Position::new(
+        get("line").ok_or_else(|| {
+            ToolError::invalid_params(
+                "Missing required parameter: line (required for this operation)",
+            )
+        })?,
+        get("character").ok_or_else(|| {
+            ToolError::invalid_params(
+                "Missing required parameter: character (required for this operation)",
+            )
+        })?,
+    );
+    let end = Position::new(
+        get("end_line").unwrap_or(start.line),
+        get("end_character").unwrap_or(start.character),
+    );
+    Ok(Range::new(start, end))
+}
+
+/// Convert an LSP position to a byte offset in `content`
+///
+/// LSP counts characters in UTF-16 code units. A character past the end of
+/// the line resolves to the end of the line.
+fn position_to_offset(content: &str, position: Position) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        line_start += content[line_start..].find('\n')? + 1;
+    }
+    let rest = &content[line_start..];
+    let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    let mut units = 0;
+    for (idx, ch) in line.char_indices() {
+        if units >= position.character as usize {
+            return Some(line_start + idx);
+        }
+        units += ch.len_utf16();
+    }
+    Some(line_start + line.len())
+}
+
+/// Apply text edits to `content`, returning the new content
+///
+/// Edits refer to the original content and must not overlap.
+fn apply_text_edits(content: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let mut spans = Vec::with_capacity(edits.len());
+    for edit in edits {
+        let start = position_to_offset(content, edit.range.start);
+        let end = position_to_offset(content, edit.range.end);
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => spans.push((start, end, &edit.new_text)),
+            _ => {
+                return Err(format!(
+                    "edit range {}:{}-{}:{} is outside the file",
+                    edit.range.start.line + 1,
+                    edit.range.start.character + 1,
+                    edit.range.end.line + 1,
+                    edit.range.end.character + 1
+                ))
+            }
+        }
+    }
+
+    // Stable sort keeps inserts at the same position in their original order
+    spans.sort_by_key(|(start, end, _)| (*start, *end));
+    if spans.windows(2).any(|pair| pair[0].1 > pair[1].0) {
+        return Err("overlapping edits".to_string());
+    }
+
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for (start, end, new_text) in spans {
+        output.push_str(&content[cursor..start]);
+        output.push_str(new_text);
+        cursor = end;
+    }
+    output.push_str(&content[cursor..]);
+    Ok(output)
+}
+
+/// Resolve a path parameter against the working directory
+fn resolve_path(path_str: &str, context: &ToolContext) -> PathBuf {
+    if Path::new(path_str).is_absolute() {
+        PathBuf::from(path_str)
+    } else {
+        context.working_directory.join(path_str)
+    }
+}
+
 /// Format LSP result for human-readable output
 fn format_lsp_result(result: &LspResult, query_path: &Path) -> String {
     match result {
@@ -974,6 +1557,31 @@ fn format_lsp_result(result: &LspResult, query_path: &Path) -> String {
                 output
             }
         }
+        LspResult::CodeActions { actions } => {
+            if actions.is_empty() {
+                "No code actions available".to_string()
+            } else {
+                let mut output = format!("Found {} code action(s):\n", actions.len());
+                for action in actions {
+                    let mut tags: Vec<&str> = action.kind.iter().map(String::as_str).collect();
+                    if action.is_preferred {
+                        tags.push("preferred");
+                    }
+                    let tags = if tags.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", tags.join(", "))
+                    };
+                    output.push_str(&format!("  [{}] {}{}\n", action.id, action.title, tags));
+                }
+                output.push_str("Use apply_code_action with an action_id to apply one.\n");
+                output
+            }
+        }
+        LspResult::ExecuteCommand { result } => match result {
+            Some(value) => format!("Command executed: {}", value),
+            None => "Command executed".to_string(),
+        },
     }
 }
 
@@ -1012,10 +1620,12 @@ fn format_document_symbols(output: &mut String, symbols: &[DocumentSymbol], inde
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::base::PermissionBehavior;
+    use crate::tools::file::ReadTool;
 
     /// Create a mock callback that returns definition locations
     fn mock_definition_callback(locations: Vec<Location>) -> LspCallback {
-        Arc::new(move |op, _path, _pos| {
+        Arc::new(move |op, _path, _pos, _args| {
             let locs = locations.clone();
             Box::pin(async move {
                 match op {
@@ -1028,7 +1638,7 @@ mod tests {
 
     /// Create a mock callback that returns references
     fn mock_references_callback(locations: Vec<Location>) -> LspCallback {
-        Arc::new(move |op, _path, _pos| {
+        Arc::new(move |op, _path, _pos, _args| {
             let locs = locations.clone();
             Box::pin(async move {
                 match op {
@@ -1041,7 +1651,7 @@ mod tests {
 
     /// Create a mock callback that returns hover info
     fn mock_hover_callback(info: Option<HoverInfo>) -> LspCallback {
-        Arc::new(move |op, _path, _pos| {
+        Arc::new(move |op, _path, _pos, _args| {
             let hover_info = info.clone();
             Box::pin(async move {
                 match op {
@@ -1054,7 +1664,7 @@ mod tests {
 
     /// Create a mock callback that returns completions
     fn mock_completion_callback(items: Vec<CompletionItem>) -> LspCallback {
-        Arc::new(move |op, _path, _pos| {
+        Arc::new(move |op, _path, _pos, _args| {
             let completion_items = items.clone();
             Box::pin(async move {
                 match op {
@@ -1069,7 +1679,7 @@ mod tests {
 
     /// Create a mock callback that returns diagnostics
     fn mock_diagnostics_callback(diagnostics: Vec<Diagnostic>) -> LspCallback {
-        Arc::new(move |op, _path, _pos| {
+        Arc::new(move |op, _path, _pos, _args| {
             let diags = diagnostics.clone();
             Box::pin(async move {
                 match op {
@@ -1083,7 +1693,7 @@ mod tests {
     /// Create a mock callback that returns an error
     fn mock_error_callback(error: &str) -> LspCallback {
         let err = error.to_string();
-        Arc::new(move |_op, _path, _pos| {
+        Arc::new(move |_op, _path, _pos, _args| {
             let e = err.clone();
             Box::pin(async move { Err(e) })
         })
@@ -1091,7 +1701,7 @@ mod tests {
 
     /// Create a mock callback that handles all operations
     fn mock_all_operations_callback() -> LspCallback {
-        Arc::new(|op, path, pos| {
+        Arc::new(|op, path, pos, _args| {
             Box::pin(async move {
                 match op {
                     LspOperation::Definition => Ok(LspResult::Definition {
@@ -1215,6 +1825,12 @@ mod tests {
                             )],
                         }],
                     }),
+                    LspOperation::CodeActions { .. } => {
+                        Ok(LspResult::CodeActions { actions: vec![] })
+                    }
+                    LspOperation::ApplyCodeAction | LspOperation::ExecuteCommand => {
+                        Err("Unexpected operation".to_string())
+                    }
                 }
             })
         })
@@ -1419,6 +2035,29 @@ mod tests {
         assert_eq!(result[0].message, "unused variable");
     }
 
+    #[tokio::test]
+    async fn test_diagnostics_are_published_to_store() {
+        let diagnostics = vec![Diagnostic {
+            range: Range::new(Position::new(5, 0), Position::new(5, 10)),
+            severity: Some(DiagnosticSeverity::Error),
+            code: None,
+            source: Some("rustc".to_string()),
+            message: "unused variable".to_string(),
+        }];
+        let store = Arc::new(DiagnosticsStore::new());
+        let tool = LspTool::new()
+            .with_callback(mock_diagnostics_callback(diagnostics))
+            .with_diagnostics_store(store.clone());
+
+        tool.diagnostics(Path::new("/path/to/file.rs"))
+            .await
+            .unwrap();
+
+        let published = store.get(Path::new("/path/to/file.rs"));
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].message, "unused variable");
+    }
+
     #[tokio::test]
     async fn test_lsp_without_callback() {
         let tool = LspTool::new();
@@ -1466,6 +2105,8 @@ mod tests {
         assert!(schema["properties"]["path"].is_object());
         assert!(schema["properties"]["line"].is_object());
         assert!(schema["properties"]["character"].is_object());
+        assert!(schema["properties"]["query"].is_object());
+        assert_eq!(schema["required"], serde_json::json!(["operation", "path"]));
     }
 
     #[tokio::test]
@@ -1730,13 +2371,129 @@ mod tests {
         let tool = LspTool::new().with_callback(callback);
 
         let result = tool
-            .workspace_symbols(Path::new("/path/to/file.rs"))
+            .workspace_symbols(Path::new("/path/to/project"), "MyStruct")
             .await
             .unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].name, "MyStruct");
     }
 
+    /// Mock server indexing a fixed set of workspace symbols
+    fn mock_workspace_symbol_server() -> LspCallback {
+        Arc::new(|op, root, _pos, args| {
+            Box::pin(async move {
+                let (LspOperation::WorkspaceSymbol, Some(query)) = (op, args.query) else {
+                    return Err("unsupported operation".to_string());
+                };
+                let index = [
+                    ("Config", SymbolKind::Struct, "src/config.rs", 10),
+                    ("ConfigError", SymbolKind::Enum, "src/config.rs", 40),
+                    ("load_config", SymbolKind::Function, "src/loader.rs", 5),
+                    ("parse_config", SymbolKind::Function, "src/parser.rs", 12),
+                    ("Session", SymbolKind::Struct, "src/session.rs", 3),
+                ];
+                let query = query.to_lowercase();
+                let symbols = index
+                    .iter()
+                    .filter(|(name, ..)| name.to_lowercase().contains(&query))
+                    .map(|(name, kind, file, line)| WorkspaceSymbol {
+                        name: name.to_string(),
+                        kind: *kind,
+                        location: Location::new(
+                            root.join(file),
+                            Range::new(Position::new(*line, 0), Position::new(*line, 10)),
+                        ),
+                        container_name: None,
+                    })
+                    .collect();
+                Ok(LspResult::WorkspaceSymbol { symbols })
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_workspace_symbols_against_mock_server() {
+        let tool = LspTool::new().with_callback(mock_workspace_symbol_server());
+
+        let symbols = tool
+            .workspace_symbols(Path::new("/project"), "config")
+            .await
+            .unwrap();
+        let found: Vec<(&str, SymbolKind)> =
+            symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Config", SymbolKind::Struct),
+                ("ConfigError", SymbolKind::Enum),
+                ("load_config", SymbolKind::Function),
+                ("parse_config", SymbolKind::Function),
+            ]
+        );
+        assert_eq!(
+            symbols[2].location.path,
+            PathBuf::from("/project/src/loader.rs")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workspace_symbols_min_query_length() {
+        let tool = LspTool::new()
+            .with_callback(mock_workspace_symbol_server())
+            .with_min_workspace_query_length(3);
+
+        let err = tool
+            .workspace_symbols(Path::new("/project"), " co ")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParams(_)));
+        assert!(err.to_string().contains("at least 3"));
+
+        assert_eq!(
+            tool.workspace_symbols(Path::new("/project"), "Ses")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lsp_tool_execute_workspace_symbol_pagination() {
+        let tool = LspTool::new().with_callback(mock_workspace_symbol_server());
+        let context = ToolContext::new(PathBuf::from("/project"));
+
+        let params = serde_json::json!({
+            "operation": "workspace_symbol",
+            "path": ".",
+            "query": "config",
+            "limit": 3
+        });
+        let result = tool.execute(params, &context).await.unwrap();
+        assert_eq!(result.metadata["total"], 4);
+        assert_eq!(result.metadata["has_more"], true);
+        let output = result.output.unwrap();
+        assert!(output.contains("Found 3 symbol(s)"));
+        assert!(output.contains("Use offset=3"));
+
+        let params = serde_json::json!({
+            "operation": "workspace_symbol",
+            "path": ".",
+            "query": "config",
+            "offset": 3,
+            "limit": 3
+        });
+        let result = tool.execute(params, &context).await.unwrap();
+        assert_eq!(result.metadata["has_more"], false);
+        assert!(result.output.unwrap().contains("parse_config"));
+
+        let missing_query = serde_json::json!({"operation": "workspace_symbol", "path": "."});
+        assert!(matches!(
+            tool.execute(missing_query, &context).await,
+            Err(ToolError::InvalidParams(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_goto_implementation_success() {
         let callback = mock_all_operations_callback();
@@ -1812,7 +2569,7 @@ mod tests {
 
         let params = serde_json::json!({
             "operation": "workspace_symbol",
-            "path": "file.rs"
+            "query": "MyStruct"
         });
 
         let result = tool.execute(params, &context).await.unwrap();
@@ -1988,6 +2745,190 @@ mod tests {
         assert!(json.contains("uri"));
     }
 
+    /// Mock server offering an "add import" quick-fix and a command-only action
+    fn mock_code_action_server(executed: Arc<Mutex<Vec<String>>>) -> LspCallback {
+        Arc::new(move |op, path, _pos, args| {
+            let executed = executed.clone();
+            Box::pin(async move {
+                match op {
+                    LspOperation::CodeActions { .. } => {
+                        let mut changes = BTreeMap::new();
+                        changes.insert(
+                            path.clone(),
+                            vec![TextEdit {
+                                range: Range::new(Position::new(0, 0), Position::new(0, 0)),
+                                new_text: "use std::collections::HashMap;\n\n".to_string(),
+                            }],
+                        );
+                        Ok(LspResult::CodeActions {
+                            actions: vec![
+                                CodeAction {
+                                    id: String::new(),
+                                    title: "Import `std::collections::HashMap`".to_string(),
+                                    kind: Some("quickfix".to_string()),
+                                    is_preferred: true,
+                                    edit: Some(WorkspaceEdit { changes }),
+                                    command: None,
+                                },
+                                CodeAction {
+                                    id: String::new(),
+                                    title: "Organize imports".to_string(),
+                                    kind: Some("source.organizeImports".to_string()),
+                                    is_preferred: false,
+                                    edit: None,
+                                    command: Some(LspCommand {
+                                        title: "Organize imports".to_string(),
+                                        command: "editor.organizeImports".to_string(),
+                                        arguments: vec![serde_json::json!(path)],
+                                    }),
+                                },
+                            ],
+                        })
+                    }
+                    LspOperation::ExecuteCommand => {
+                        let command = args.command.ok_or("missing command")?;
+                        executed.lock().unwrap().push(command.command);
+                        Ok(LspResult::ExecuteCommand { result: None })
+                    }
+                    _ => Err("unsupported operation".to_string()),
+                }
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_code_actions_apply_add_import_against_mock_server() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {\n    let map = HashMap::new();\n}\n").unwrap();
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let history = create_shared_history();
+        let tool = LspTool::new()
+            .with_callback(mock_code_action_server(executed.clone()))
+            .with_edit_tool(EditTool::new(history.clone()));
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+        ReadTool::new(history)
+            .execute(serde_json::json!({"path": "main.rs"}), &context)
+            .await
+            .unwrap();
+
+        let params = serde_json::json!({
+            "operation": "code_actions",
+            "path": "main.rs",
+            "line": 1,
+            "character": 14
+        });
+        let result = tool.execute(params, &context).await.unwrap();
+        let output = result.output.unwrap();
+        assert!(output.contains("Found 2 code action(s)"));
+        assert!(
+            output.contains("[action-1] Import `std::collections::HashMap` (quickfix, preferred)")
+        );
+
+        let params = serde_json::json!({"operation": "apply_code_action", "action_id": "action-1"});
+        let permission = tool.check_permissions(&params, &context).await;
+        assert_eq!(permission.behavior, PermissionBehavior::Ask);
+        assert!(permission.message.unwrap().contains("main.rs"));
+        let result = tool.execute(params.clone(), &context).await.unwrap();
+        assert!(result.output.unwrap().contains("Applied code action"));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "use std::collections::HashMap;\n\nfn main() {\n    let map = HashMap::new();\n}\n"
+        );
+        assert!(executed.lock().unwrap().is_empty());
+
+        // Applying invalidates the remaining actions
+        assert!(tool.check_permissions(&params, &context).await.is_denied());
+        assert!(matches!(
+            tool.execute(params, &context).await,
+            Err(ToolError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_apply_code_action_requires_read_by_default() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("main.rs");
+        let original = "fn main() {\n    let map = HashMap::new();\n}\n";
+        std::fs::write(&file, original).unwrap();
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let tool = LspTool::new().with_callback(mock_code_action_server(executed));
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+
+        let actions = tool
+            .code_actions(
+                &file,
+                Range::new(Position::new(1, 14), Position::new(1, 14)),
+            )
+            .await
+            .unwrap();
+        let import = actions.iter().find(|a| a.edit.is_some()).unwrap();
+
+        assert!(tool.apply_code_action(&import.id, &context).await.is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), original);
+    }
+
+    #[tokio::test]
+    async fn test_apply_code_action_executes_command() {
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let tool = LspTool::new().with_callback(mock_code_action_server(executed.clone()));
+        let context = ToolContext::new(PathBuf::from("/project"));
+
+        let actions = tool
+            .code_actions(
+                Path::new("/project/main.rs"),
+                Range::new(Position::new(0, 0), Position::new(0, 0)),
+            )
+            .await
+            .unwrap();
+        let organize = actions.iter().find(|a| a.edit.is_none()).unwrap();
+
+        let result = tool
+            .apply_code_action(&organize.id, &context)
+            .await
+            .unwrap();
+        assert!(result.output.unwrap().contains("editor.organizeImports"));
+        assert_eq!(*executed.lock().unwrap(), vec!["editor.organizeImports"]);
+    }
+
+    #[test]
+    fn test_apply_text_edits() {
+        let content = "let s = \"héllo 😀 world\";\nnext\n";
+        // 😀 is two UTF-16 code units, so "world" starts at character 18
+        let edits = vec![
+            TextEdit {
+                range: Range::new(Position::new(0, 18), Position::new(0, 23)),
+                new_text: "there".to_string(),
+            },
+            TextEdit {
+                range: Range::new(Position::new(1, 0), Position::new(1, 4)),
+                new_text: "last".to_string(),
+            },
+        ];
+        assert_eq!(
+            apply_text_edits(content, &edits).unwrap(),
+            "let s = \"héllo 😀 there\";\nlast\n"
+        );
+
+        let overlapping = vec![
+            TextEdit {
+                range: Range::new(Position::new(0, 0), Position::new(0, 5)),
+                new_text: String::new(),
+            },
+            TextEdit {
+                range: Range::new(Position::new(0, 3), Position::new(0, 8)),
+                new_text: String::new(),
+            },
+        ];
+        assert!(apply_text_edits(content, &overlapping).is_err());
+
+        let outside = vec![TextEdit {
+            range: Range::new(Position::new(5, 0), Position::new(5, 0)),
+            new_text: "x".to_string(),
+        }];
+        assert!(apply_text_edits(content, &outside).is_err());
+    }
+
     #[test]
     fn test_new_lsp_operation_serialization() {
         assert_eq!(
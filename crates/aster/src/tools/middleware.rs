@@ -0,0 +1,120 @@
+//! Tool Middleware Module
+//!
+//! Defines the `ToolMiddleware` trait for wrapping tool executions with
+//! cross-cutting concerns (timing, logging, redaction, policy checks).
+//!
+//! Middlewares registered on a `ToolRegistry` form an ordered pipeline:
+//! `before` hooks run in registration order, `after` hooks run in reverse
+//! order, so the first middleware is the outermost layer. A `before` hook
+//! may rewrite the parameters or short-circuit the call with its own result,
+//! in which case the tool is not executed and only the middlewares that
+//! already ran see the result in `after`.
+
+use async_trait::async_trait;
+
+use super::context::{ToolContext, ToolResult};
+use super::error::ToolError;
+use super::hooks::{HookContext, HookTrigger, ToolHookManager};
+
+/// Decision returned by `ToolMiddleware::before`
+#[derive(Debug)]
+pub enum MiddlewareAction {
+    /// Continue with the next middleware and eventually the tool
+    Continue,
+    /// Skip the tool and the remaining middlewares, returning this result
+    ShortCircuit(Result<ToolResult, ToolError>),
+}
+
+impl MiddlewareAction {
+    /// Short-circuit with a permission denial
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self::ShortCircuit(Err(ToolError::permission_denied(reason)))
+    }
+}
+
+/// Tool execution middleware
+///
+/// Both hooks default to pass-through, so implementations only override
+/// the side they need.
+#[async_trait]
+pub trait ToolMiddleware: Send + Sync {
+    /// Returns the middleware name, used for logging
+    fn name(&self) -> &str;
+
+    /// Called before the tool executes
+    ///
+    /// May modify `params` in place or short-circuit the call.
+    async fn before(
+        &self,
+        _tool_name: &str,
+        _params: &mut serde_json::Value,
+        _context: &ToolContext,
+    ) -> MiddlewareAction {
+        MiddlewareAction::Continue
+    }
+
+    /// Called after the tool executes (or after a later middleware short-circuits)
+    ///
+    /// Returns the result passed on to the previous middleware, allowing
+    /// it to be transformed.
+    async fn after(
+        &self,
+        _tool_name: &str,
+        _params: &serde_json::Value,
+        _context: &ToolContext,
+        result: Result<ToolResult, ToolError>,
+    ) -> Result<ToolResult, ToolError> {
+        result
+    }
+}
+
+/// Runs the existing tool hooks as a middleware
+///
+/// `PreExecution` hooks fire in `before`, `PostExecution` or `OnError`
+/// hooks fire in `after`. Hooks observe the call and never alter it.
+#[async_trait]
+impl ToolMiddleware for ToolHookManager {
+    fn name(&self) -> &str {
+        "tool_hooks"
+    }
+
+    async fn before(
+        &self,
+        tool_name: &str,
+        params: &mut serde_json::Value,
+        context: &ToolContext,
+    ) -> MiddlewareAction {
+        let hook_context = HookContext::new(tool_name.to_string(), params.clone(), context.clone());
+        if let Err(e) = self
+            .trigger_hooks(HookTrigger::PreExecution, &hook_context)
+            .await
+        {
+            tracing::warn!("Pre-execution hooks failed for '{}': {}", tool_name, e);
+        }
+        MiddlewareAction::Continue
+    }
+
+    async fn after(
+        &self,
+        tool_name: &str,
+        params: &serde_json::Value,
+        context: &ToolContext,
+        result: Result<ToolResult, ToolError>,
+    ) -> Result<ToolResult, ToolError> {
+        let hook_context = HookContext::new(tool_name.to_string(), params.clone(), context.clone());
+        let (trigger, hook_context) = match &result {
+            Ok(tool_result) => (
+                HookTrigger::PostExecution,
+                hook_context.with_result(tool_result.clone()),
+            ),
+            Err(err) => (
+                HookTrigger::OnError,
+                hook_context.with_error(err.to_string()),
+            ),
+        };
+        if let Err(e) = self.trigger_hooks(trigger, &hook_context).await {
+            tracing::warn!("Post-execution hooks failed for '{}': {}", tool_name, e);
+        }
+        result
+    }
+}
@@ -12,8 +12,10 @@
 // Core modules
 pub mod base;
 pub mod context;
+pub mod dry_run;
 pub mod error;
 pub mod hooks;
+pub mod middleware;
 pub mod registry;
 pub mod task;
 
@@ -21,7 +23,9 @@ pub mod task;
 pub mod analyze_image;
 pub mod ask;
 pub mod bash;
+pub mod diagnostics_tool;
 pub mod file;
+pub mod git_conflict_tool;
 pub mod kill_shell_tool;
 pub mod lsp;
 pub mod notebook_edit_tool;
@@ -47,7 +51,15 @@ pub use error::ToolError;
 pub use context::{ToolContext, ToolDefinition, ToolOptions, ToolResult};
 
 // Base trait and permission types
-pub use base::{PermissionBehavior, PermissionCheckResult, Tool};
+pub use base::{
+    coerce_to_schema, validate_against_schema, PermissionBehavior, PermissionCheckResult, Tool,
+};
+
+// Dry-run types
+pub use dry_run::{DryRunCall, DryRunDisposition, DryRunRecorder, DRY_RUN_METADATA_KEY};
+
+// Middleware types
+pub use middleware::{MiddlewareAction, ToolMiddleware};
 
 // Registry types
 pub use registry::{McpToolWrapper, PermissionRequestCallback, ToolRegistry};
@@ -81,15 +93,25 @@ pub use search::{
 // Ask tool
 pub use ask::{AskCallback, AskOption, AskResult, AskTool, DEFAULT_ASK_TIMEOUT_SECS};
 
+// Diagnostics tool
+pub use diagnostics_tool::{
+    DiagnosticsStore, DiagnosticsTool, DEFAULT_DIAGNOSTICS_SETTLE_TIMEOUT,
+    DEFAULT_MAX_WORKSPACE_DIAGNOSTICS,
+};
+
 // LSP tool
 pub use lsp::{
-    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, HoverInfo, Location,
-    LspCallback, LspOperation, LspResult, LspTool, Position, Range,
+    CodeAction, CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, HoverInfo,
+    Location, LspArgs, LspCallback, LspCommand, LspOperation, LspResult, LspTool, Position, Range,
+    TextEdit, WorkspaceEdit,
 };
 
 // Skill tool
 pub use crate::skills::SkillTool;
 
+// Git conflict tool
+pub use git_conflict_tool::GitConflictTool;
+
 // Task tools
 pub use kill_shell_tool::KillShellTool;
 pub use notebook_edit_tool::{NotebookCell, NotebookContent, NotebookEditInput, NotebookEditTool};
@@ -112,6 +134,10 @@ pub use analyze_image::{AnalyzeImageInput, AnalyzeImageResult, ImageDimensions};
 // Workflow integration
 pub use workflow_integration::{WorkflowIntegratedTool, WorkflowIntegratedToolBuilder};
 
+use std::sync::Arc;
+
+use crate::security::secrets::{SecretScanner, SecretScannerConfig};
+
 // =============================================================================
 // Tool Registration (Requirements: 11.3)
 // =============================================================================
@@ -123,10 +149,18 @@ pub struct ToolRegistrationConfig {
     pub ask_callback: Option<AskCallback>,
     /// Callback for LSPTool operations
     pub lsp_callback: Option<LspCallback>,
+    /// Store receiving published LSP diagnostics for DiagnosticsTool
+    ///
+    /// Created automatically when only an LSP callback is set.
+    pub diagnostics_store: Option<Arc<DiagnosticsStore>>,
     /// Whether to enable PDF reading in ReadTool
     pub pdf_enabled: bool,
     /// Whether to enable hook system
     pub hooks_enabled: bool,
+    /// Secret scanning for WriteTool, disabled when `None`
+    pub secret_scan: Option<SecretScannerConfig>,
+    /// Whether file tools reject paths outside the working directory
+    pub restrict_to_working_directory: bool,
 }
 
 impl std::fmt::Debug for ToolRegistrationConfig {
@@ -140,8 +174,14 @@ impl std::fmt::Debug for ToolRegistrationConfig {
                 "lsp_callback",
                 &self.lsp_callback.as_ref().map(|_| "<callback>"),
             )
+            .field("diagnostics_store", &self.diagnostics_store.is_some())
             .field("pdf_enabled", &self.pdf_enabled)
             .field("hooks_enabled", &self.hooks_enabled)
+            .field("secret_scan", &self.secret_scan)
+            .field(
+                "restrict_to_working_directory",
+                &self.restrict_to_working_directory,
+            )
             .finish()
     }
 }
@@ -151,8 +191,11 @@ impl Clone for ToolRegistrationConfig {
         Self {
             ask_callback: self.ask_callback.clone(),
             lsp_callback: self.lsp_callback.clone(),
+            diagnostics_store: self.diagnostics_store.clone(),
             pdf_enabled: self.pdf_enabled,
             hooks_enabled: self.hooks_enabled,
+            secret_scan: self.secret_scan.clone(),
+            restrict_to_working_directory: self.restrict_to_working_directory,
         }
     }
 }
@@ -175,6 +218,12 @@ impl ToolRegistrationConfig {
         self
     }
 
+    /// Set the store DiagnosticsTool reads published diagnostics from
+    pub fn with_diagnostics_store(mut self, store: Arc<DiagnosticsStore>) -> Self {
+        self.diagnostics_store = Some(store);
+        self
+    }
+
     /// Enable PDF reading
     pub fn with_pdf_enabled(mut self, enabled: bool) -> Self {
         self.pdf_enabled = enabled;
@@ -186,6 +235,18 @@ impl ToolRegistrationConfig {
         self.hooks_enabled = enabled;
         self
     }
+
+    /// Scan WriteTool content for secrets before writing
+    pub fn with_secret_scan(mut self, config: SecretScannerConfig) -> Self {
+        self.secret_scan = Some(config);
+        self
+    }
+
+    /// Confine file tools to the working directory
+    pub fn with_restrict_to_working_directory(mut self, restrict: bool) -> Self {
+        self.restrict_to_working_directory = restrict;
+        self
+    }
 }
 
 /// Register all native tools with the registry
@@ -197,8 +258,10 @@ impl ToolRegistrationConfig {
 /// - EditTool: Smart file editing
 /// - GlobTool: File search with glob patterns
 /// - GrepTool: Content search with regex
+/// - GitConflictTool: Merge conflict scanning and resolution
 /// - AskTool: User interaction (if callback provided)
 /// - LSPTool: Code intelligence (if callback provided)
+/// - DiagnosticsTool: LSP errors and warnings (if callback or diagnostics store provided)
 /// - SkillTool: Skill execution and management
 ///
 /// # Arguments
@@ -234,13 +297,20 @@ pub fn register_all_tools(
     registry.register(Box::new(BashTool::new()));
 
     // Register file tools with shared history
-    let read_tool = ReadTool::new(shared_history.clone()).with_pdf_enabled(config.pdf_enabled);
+    let read_tool = ReadTool::new(shared_history.clone())
+        .with_pdf_enabled(config.pdf_enabled)
+        .with_restrict_to_working_directory(config.restrict_to_working_directory);
     registry.register(Box::new(read_tool));
 
-    let write_tool = WriteTool::new(shared_history.clone());
+    let mut write_tool = WriteTool::new(shared_history.clone())
+        .with_restrict_to_working_directory(config.restrict_to_working_directory);
+    if let Some(secret_scan) = config.secret_scan {
+        write_tool = write_tool.with_secret_scanner(SecretScanner::new(secret_scan));
+    }
     registry.register(Box::new(write_tool));
 
-    let edit_tool = EditTool::new(shared_history.clone());
+    let edit_tool = EditTool::new(shared_history.clone())
+        .with_restrict_to_working_directory(config.restrict_to_working_directory);
     registry.register(Box::new(edit_tool));
 
     // Register search tools
@@ -253,12 +323,37 @@ pub fn register_all_tools(
         registry.register(Box::new(ask_tool));
     }
 
+    // LSPTool and DiagnosticsTool share one store so diagnostics fetched
+    // through the callback are visible to both
+    let diagnostics_store = config.diagnostics_store.or_else(|| {
+        config
+            .lsp_callback
+            .as_ref()
+            .map(|_| Arc::new(DiagnosticsStore::new()))
+    });
+
     // Register LSPTool if callback is provided
-    if let Some(callback) = config.lsp_callback {
-        let lsp_tool = LspTool::new().with_callback(callback);
+    if let Some(callback) = config.lsp_callback.clone() {
+        let lsp_edit_tool = EditTool::new(shared_history.clone())
+            .with_restrict_to_working_directory(config.restrict_to_working_directory);
+        let mut lsp_tool = LspTool::new()
+            .with_callback(callback)
+            .with_edit_tool(lsp_edit_tool);
+        if let Some(store) = &diagnostics_store {
+            lsp_tool = lsp_tool.with_diagnostics_store(store.clone());
+        }
         registry.register(Box::new(lsp_tool));
     }
 
+    // Register DiagnosticsTool if a callback or diagnostics store is provided
+    if let Some(store) = diagnostics_store {
+        let mut diagnostics_tool = DiagnosticsTool::new(store);
+        if let Some(callback) = config.lsp_callback {
+            diagnostics_tool = diagnostics_tool.with_lsp_callback(callback);
+        }
+        registry.register(Box::new(diagnostics_tool));
+    }
+
     // Register SkillTool
     registry.register(Box::new(SkillTool::new()));
 
@@ -268,6 +363,7 @@ pub fn register_all_tools(
     registry.register(Box::new(KillShellTool::new()));
     registry.register(Box::new(TodoWriteTool::new()));
     registry.register(Box::new(NotebookEditTool::new()));
+    registry.register(Box::new(GitConflictTool::new()));
 
     // Register Plan Mode tools
     registry.register(Box::new(EnterPlanModeTool::new()));
@@ -333,10 +429,33 @@ mod tests {
         assert!(registry.contains("WebSearch"));
         assert!(registry.contains("analyze_image"));
         assert!(registry.contains("three_stage_workflow"));
+        assert!(registry.contains("git_conflicts"));
 
         // AskTool and LSPTool should not be registered without callbacks
         assert!(!registry.contains("ask"));
         assert!(!registry.contains("lsp"));
+        assert!(!registry.contains("diagnostics"));
+    }
+
+    #[test]
+    fn test_lsp_callback_registers_diagnostics_tool() {
+        use std::future::Future;
+        use std::pin::Pin;
+
+        let mut registry = ToolRegistry::new();
+        let lsp_callback: LspCallback = Arc::new(|_operation, _path: PathBuf, _position, _args| {
+            Box::pin(async {
+                Ok(LspResult::Diagnostics {
+                    diagnostics: vec![],
+                })
+            }) as Pin<Box<dyn Future<Output = Result<LspResult, String>> + Send>>
+        });
+
+        let config = ToolRegistrationConfig::new().with_lsp_callback(lsp_callback);
+        register_all_tools(&mut registry, config);
+
+        assert!(registry.contains("lsp"));
+        assert!(registry.contains("diagnostics"));
     }
 
     #[test]
@@ -353,7 +472,7 @@ mod tests {
                 as Pin<Box<dyn Future<Output = Option<String>> + Send>>
         });
 
-        let lsp_callback: LspCallback = Arc::new(|_operation, _path: PathBuf, _position| {
+        let lsp_callback: LspCallback = Arc::new(|_operation, _path: PathBuf, _position, _args| {
             Box::pin(async { Ok(LspResult::Definition { locations: vec![] }) })
                 as Pin<Box<dyn Future<Output = Result<LspResult, String>> + Send>>
         });
@@ -361,6 +480,7 @@ mod tests {
         let config = ToolRegistrationConfig::new()
             .with_ask_callback(ask_callback)
             .with_lsp_callback(lsp_callback)
+            .with_diagnostics_store(Arc::new(DiagnosticsStore::new()))
             .with_pdf_enabled(true);
 
         let (_history, _hook_manager) = register_all_tools(&mut registry, config);
@@ -374,6 +494,7 @@ mod tests {
         assert!(registry.contains("grep"));
         assert!(registry.contains("ask"));
         assert!(registry.contains("lsp"));
+        assert!(registry.contains("diagnostics"));
         assert!(registry.contains("Skill"));
         assert!(registry.contains("Task"));
         assert!(registry.contains("TaskOutput"));
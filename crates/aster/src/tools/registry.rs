@@ -18,9 +18,14 @@ use std::time::Instant;
 
 use async_trait::async_trait;
 
-use super::base::{PermissionBehavior, Tool};
+use super::base::{coerce_to_schema, PermissionBehavior, Tool};
 use super::context::{ToolContext, ToolDefinition, ToolResult};
+use super::dry_run::{
+    mark_executed, synthetic_result, DryRunCall, DryRunDisposition, DryRunRecorder,
+    DRY_RUN_METADATA_KEY,
+};
 use super::error::ToolError;
+use super::middleware::{MiddlewareAction, ToolMiddleware};
 use crate::permission::{
     AuditLogEntry, AuditLogLevel, AuditLogger, PermissionContext, ToolPermissionManager,
 };
@@ -115,6 +120,10 @@ pub struct ToolRegistry {
     permission_manager: Option<Arc<ToolPermissionManager>>,
     /// Audit logger for recording tool executions
     audit_logger: Option<Arc<AuditLogger>>,
+    /// Middleware pipeline applied around every execution, outermost first
+    middlewares: Vec<Arc<dyn ToolMiddleware>>,
+    /// Dry-run recorder; when set, only read-only tools are executed
+    dry_run: Option<DryRunRecorder>,
 }
 
 impl Default for ToolRegistry {
@@ -131,6 +140,8 @@ impl ToolRegistry {
             mcp_tools: HashMap::new(),
             permission_manager: None,
             audit_logger: None,
+            middlewares: Vec::new(),
+            dry_run: None,
         }
     }
 
@@ -144,6 +155,8 @@ impl ToolRegistry {
             mcp_tools: HashMap::new(),
             permission_manager: Some(permission_manager),
             audit_logger: Some(audit_logger),
+            middlewares: Vec::new(),
+            dry_run: None,
         }
     }
 
@@ -166,6 +179,56 @@ impl ToolRegistry {
     pub fn audit_logger(&self) -> Option<&Arc<AuditLogger>> {
         self.audit_logger.as_ref()
     }
+
+    /// Append a middleware to the execution pipeline
+    ///
+    /// Middlewares added first wrap those added later.
+    pub fn add_middleware(&mut self, middleware: Arc<dyn ToolMiddleware>) {
+        self.middlewares.push(middleware);
+    }
+
+    /// Get the number of registered middlewares
+    pub fn middleware_count(&self) -> usize {
+        self.middlewares.len()
+    }
+
+    /// Enable dry-run mode
+    ///
+    /// Tools the recorder considers read-only still run; all other calls are
+    /// recorded and answered with a synthetic success result. Any previous
+    /// recording is discarded.
+    pub fn enable_dry_run(&mut self, recorder: DryRunRecorder) {
+        self.dry_run = Some(recorder);
+    }
+
+    /// Disable dry-run mode, returning every call seen while it was enabled
+    pub fn disable_dry_run(&mut self) -> Vec<DryRunCall> {
+        self.dry_run
+            .take()
+            .map(DryRunRecorder::into_calls)
+            .unwrap_or_default()
+    }
+
+    /// Check if dry-run mode is enabled
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    /// Get every call seen in dry-run mode, both executed and recorded
+    pub fn dry_run_calls(&self) -> Vec<DryRunCall> {
+        self.dry_run
+            .as_ref()
+            .map(DryRunRecorder::calls)
+            .unwrap_or_default()
+    }
+
+    /// Get the calls that were recorded but not executed in dry-run mode
+    pub fn intended_calls(&self) -> Vec<DryRunCall> {
+        self.dry_run
+            .as_ref()
+            .map(DryRunRecorder::intended_calls)
+            .unwrap_or_default()
+    }
 }
 
 // =============================================================================
@@ -372,14 +435,72 @@ impl ToolRegistry {
 // =============================================================================
 
 impl ToolRegistry {
+    /// Execute a tool by name through the middleware pipeline
+    ///
+    /// Runs each middleware's `before` hook in order, then the tool (see
+    /// `execute_tool`), then the `after` hooks in reverse order. A middleware
+    /// that short-circuits prevents execution; only the middlewares before it
+    /// see the result.
+    ///
+    /// # Arguments
+    /// * `name` - The tool name to execute
+    /// * `params` - The tool parameters
+    /// * `context` - The execution context
+    /// * `on_permission_request` - Optional callback for permission requests
+    pub async fn execute(
+        &self,
+        name: &str,
+        mut params: serde_json::Value,
+        context: &ToolContext,
+        on_permission_request: Option<PermissionRequestCallback>,
+    ) -> Result<ToolResult, ToolError> {
+        if self.middlewares.is_empty() {
+            return self
+                .execute_tool(name, params, context, on_permission_request)
+                .await;
+        }
+
+        let mut entered = 0;
+        let mut short_circuit = None;
+        for middleware in &self.middlewares {
+            match middleware.before(name, &mut params, context).await {
+                MiddlewareAction::Continue => entered += 1,
+                MiddlewareAction::ShortCircuit(result) => {
+                    tracing::debug!(
+                        "Middleware '{}' short-circuited tool '{}'",
+                        middleware.name(),
+                        name
+                    );
+                    short_circuit = Some(result);
+                    break;
+                }
+            }
+        }
+
+        let mut result = match short_circuit {
+            Some(result) => result,
+            None => {
+                self.execute_tool(name, params.clone(), context, on_permission_request)
+                    .await
+            }
+        };
+
+        for middleware in self.middlewares[..entered].iter().rev() {
+            result = middleware.after(name, &params, context, result).await;
+        }
+        result
+    }
+
     /// Execute a tool by name with permission checking and audit logging
     ///
     /// This method:
     /// 1. Looks up the tool by name
-    /// 2. Performs permission check (if permission manager is configured)
-    /// 3. Handles permission request callback for 'Ask' behavior
-    /// 4. Executes the tool
-    /// 5. Records audit log (if audit logger is configured)
+    /// 2. Coerces obvious type mismatches and validates the parameters
+    ///    against the tool's input schema
+    /// 3. Performs permission check (if permission manager is configured)
+    /// 4. Handles permission request callback for 'Ask' behavior
+    /// 5. Executes the tool, or records the call in dry-run mode
+    /// 6. Records audit log (if audit logger is configured)
     ///
     /// # Arguments
     /// * `name` - The tool name to execute
@@ -389,13 +510,14 @@ impl ToolRegistry {
     ///
     /// # Returns
     /// * `Ok(ToolResult)` - The execution result
-    /// * `Err(ToolError)` - If the tool is not found, permission denied, or execution fails
+    /// * `Err(ToolError)` - If the tool is not found, the parameters are invalid,
+    ///   permission is denied, or execution fails
     ///
     /// Requirements: 2.5, 2.6, 8.1, 8.2
-    pub async fn execute(
+    async fn execute_tool(
         &self,
         name: &str,
-        params: serde_json::Value,
+        mut params: serde_json::Value,
         context: &ToolContext,
         on_permission_request: Option<PermissionRequestCallback>,
     ) -> Result<ToolResult, ToolError> {
@@ -404,7 +526,21 @@ impl ToolRegistry {
         // Step 1: Look up the tool
         let tool = self.get(name).ok_or_else(|| ToolError::not_found(name))?;
 
-        // Step 2: Check tool-level permissions
+        // Step 2: Coerce and validate parameters against the input schema
+        let coerced = coerce_to_schema(&tool.input_schema(), &mut params);
+        if !coerced.is_empty() {
+            tracing::info!(
+                "Coerced arguments for tool '{}' at: {}",
+                name,
+                coerced.join(", ")
+            );
+        }
+        if let Err(err) = tool.validate_input(&params) {
+            self.log_tool_error(name, &params, context, &err, start_time.elapsed());
+            return Err(err);
+        }
+
+        // Step 3: Check tool-level permissions
         let permission_result = tool.check_permissions(&params, context).await;
 
         // Handle tool-level permission check result
@@ -457,7 +593,7 @@ impl ToolRegistry {
             }
         }
 
-        // Step 3: Check system-level permissions (if permission manager is configured)
+        // Step 4: Check system-level permissions (if permission manager is configured)
         if let Some(ref permission_manager) = self.permission_manager {
             let perm_context = self.create_permission_context(context);
             let params_map = self.params_to_hashmap(&params);
@@ -474,11 +610,33 @@ impl ToolRegistry {
             }
         }
 
-        // Step 4: Execute the tool
-        let params_to_use = permission_result.updated_params.unwrap_or(params.clone());
-        let result = tool.execute(params_to_use, context).await;
-
-        // Step 5: Log the execution
+        // Step 5: Execute the tool (in dry-run mode the call is recorded first and
+        // only read-only calls run; recorded calls still reach the audit log)
+        let params_to_use = match permission_result.updated_params {
+            Some(updated) => {
+                // Rewritten parameters must satisfy the schema just like the originals
+                if let Err(err) = tool.validate_input(&updated) {
+                    self.log_tool_error(name, &updated, context, &err, start_time.elapsed());
+                    return Err(err);
+                }
+                updated
+            }
+            None => params.clone(),
+        };
+        let result = match self
+            .dry_run
+            .as_ref()
+            .map(|d| d.record(name, &params_to_use))
+        {
+            Some(DryRunDisposition::Recorded) => Ok(synthetic_result(name)),
+            Some(DryRunDisposition::Executed) => tool
+                .execute(params_to_use, context)
+                .await
+                .map(mark_executed),
+            None => tool.execute(params_to_use, context).await,
+        };
+
+        // Step 6: Log the execution
         let duration = start_time.elapsed();
         match &result {
             Ok(tool_result) => {
@@ -551,7 +709,7 @@ impl ToolRegistry {
                 AuditLogLevel::Warn
             };
 
-            let entry = AuditLogEntry::new("tool_execution", tool_name)
+            let mut entry = AuditLogEntry::new("tool_execution", tool_name)
                 .with_level(level)
                 .with_parameters(self.params_to_hashmap(params))
                 .with_context(self.create_permission_context(context))
@@ -561,6 +719,9 @@ impl ToolRegistry {
                     "output_size",
                     serde_json::json!(result.output.as_ref().map(|s| s.len()).unwrap_or(0)),
                 );
+            if let Some(disposition) = result.metadata.get(DRY_RUN_METADATA_KEY) {
+                entry = entry.add_metadata(DRY_RUN_METADATA_KEY, disposition.clone());
+            }
 
             logger.log_tool_execution(entry);
         }
@@ -881,6 +1042,260 @@ mod tests {
         assert!(matches!(result.unwrap_err(), ToolError::NotFound(_)));
     }
 
+    #[tokio::test]
+    async fn test_registry_execute_rejects_invalid_params() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(crate::tools::BashTool::new()));
+
+        let context = create_test_context();
+        let params = serde_json::json!({"timeout": 0});
+
+        let result = registry.execute("bash", params, &context, None).await;
+        match result.unwrap_err() {
+            ToolError::InvalidParams(reason) => {
+                assert!(reason.contains("\"command\" is a required property"));
+                assert!(reason.contains("'/timeout'"));
+            }
+            other => panic!("Expected InvalidParams, got {:?}", other),
+        }
+    }
+
+    /// Approves every call but rewrites the parameters to the given value
+    struct RewritingTool(serde_json::Value);
+
+    #[async_trait]
+    impl Tool for RewritingTool {
+        fn name(&self) -> &str {
+            "rewriting"
+        }
+
+        fn description(&self) -> &str {
+            "Rewrites its parameters during the permission check"
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            TestTool::new("rewriting").input_schema()
+        }
+
+        async fn execute(
+            &self,
+            params: serde_json::Value,
+            _context: &ToolContext,
+        ) -> Result<ToolResult, ToolError> {
+            Ok(ToolResult::success(params.to_string()))
+        }
+
+        async fn check_permissions(
+            &self,
+            _params: &serde_json::Value,
+            _context: &ToolContext,
+        ) -> PermissionCheckResult {
+            PermissionCheckResult::allow().with_updated_params(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_execute_validates_updated_params() {
+        let context = create_test_context();
+        let params = serde_json::json!({"input": "original"});
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(RewritingTool(
+            serde_json::json!({"input": "rewritten"}),
+        )));
+        let result = registry
+            .execute("rewriting", params.clone(), &context, None)
+            .await
+            .unwrap();
+        assert!(result.output.unwrap().contains("rewritten"));
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(RewritingTool(serde_json::json!({"input": 42}))));
+        let result = registry.execute("rewriting", params, &context, None).await;
+        assert!(matches!(result, Err(ToolError::InvalidParams(_))));
+    }
+
+    #[tokio::test]
+    async fn test_registry_execute_refuses_lossy_coercion() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(crate::tools::BashTool::new()));
+
+        let context = create_test_context();
+        let params = serde_json::json!({"command": "echo hi", "timeout": "5.5"});
+
+        let result = registry.execute("bash", params, &context, None).await;
+        match result.unwrap_err() {
+            ToolError::InvalidParams(reason) => assert!(reason.contains("'/timeout'")),
+            other => panic!("Expected InvalidParams, got {:?}", other),
+        }
+    }
+
+    /// Middleware that records hook order and can deny or tag results
+    struct RecordingMiddleware {
+        name: String,
+        log: Arc<std::sync::Mutex<Vec<String>>>,
+        deny: bool,
+    }
+
+    impl RecordingMiddleware {
+        fn new(name: &str, log: &Arc<std::sync::Mutex<Vec<String>>>, deny: bool) -> Arc<Self> {
+            Arc::new(Self {
+                name: name.to_string(),
+                log: log.clone(),
+                deny,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ToolMiddleware for RecordingMiddleware {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn before(
+            &self,
+            _tool_name: &str,
+            params: &mut serde_json::Value,
+            _context: &ToolContext,
+        ) -> MiddlewareAction {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{}:before", self.name));
+            if self.deny {
+                return MiddlewareAction::deny(format!("{} denied", self.name));
+            }
+            let input = format!("{}+{}", params["input"].as_str().unwrap_or(""), self.name);
+            params["input"] = serde_json::json!(input);
+            MiddlewareAction::Continue
+        }
+
+        async fn after(
+            &self,
+            _tool_name: &str,
+            _params: &serde_json::Value,
+            _context: &ToolContext,
+            result: Result<ToolResult, ToolError>,
+        ) -> Result<ToolResult, ToolError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{}:after", self.name));
+            result.map(|mut r| {
+                r.output = r.output.map(|o| format!("{} [{}]", o, self.name));
+                r
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_middleware_ordering() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(TestTool::new("test_tool")));
+        registry.add_middleware(RecordingMiddleware::new("outer", &log, false));
+        registry.add_middleware(RecordingMiddleware::new("inner", &log, false));
+        assert_eq!(registry.middleware_count(), 2);
+
+        let context = create_test_context();
+        let params = serde_json::json!({"input": "hello"});
+        let result = registry
+            .execute("test_tool", params, &context, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.output,
+            Some("Processed: hello+outer+inner [inner] [outer]".to_string())
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["outer:before", "inner:before", "inner:after", "outer:after"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registry_middleware_short_circuit() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(TestTool::failing("test_tool")));
+        registry.add_middleware(RecordingMiddleware::new("outer", &log, false));
+        registry.add_middleware(RecordingMiddleware::new("guard", &log, true));
+        registry.add_middleware(RecordingMiddleware::new("inner", &log, false));
+
+        let context = create_test_context();
+        let params = serde_json::json!({"input": "hello"});
+        let result = registry.execute("test_tool", params, &context, None).await;
+
+        // The failing tool never runs; the denial reaches only the outer middleware
+        match result.unwrap_err() {
+            ToolError::PermissionDenied(reason) => assert_eq!(reason, "guard denied"),
+            other => panic!("Expected PermissionDenied, got {:?}", other),
+        }
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["outer:before", "guard:before", "outer:after"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registry_dry_run_records_write_without_performing_it() {
+        let temp = tempfile::tempdir().unwrap();
+        let target = temp.path().join("plan.txt");
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(crate::tools::WriteTool::new(
+            crate::tools::create_shared_history(),
+        )));
+        registry.register(Box::new(TestTool::new("test_tool")));
+        registry.enable_dry_run(DryRunRecorder::new(vec!["test_tool".to_string()]));
+        assert!(registry.is_dry_run());
+
+        let context = ToolContext::new(temp.path().to_path_buf());
+        let write_params = serde_json::json!({
+            "path": target.to_string_lossy(),
+            "content": "should not be written"
+        });
+        let write_result = registry
+            .execute("write", write_params.clone(), &context, None)
+            .await
+            .unwrap();
+        assert!(write_result.is_success());
+        assert_eq!(
+            write_result
+                .metadata
+                .get(crate::tools::DRY_RUN_METADATA_KEY),
+            Some(&serde_json::json!("recorded"))
+        );
+        assert!(!target.exists());
+
+        let read_result = registry
+            .execute(
+                "test_tool",
+                serde_json::json!({"input": "hello"}),
+                &context,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(read_result.output, Some("Processed: hello".to_string()));
+        assert_eq!(
+            read_result.metadata.get(crate::tools::DRY_RUN_METADATA_KEY),
+            Some(&serde_json::json!("executed"))
+        );
+
+        let intended = registry.intended_calls();
+        assert_eq!(intended.len(), 1);
+        assert_eq!(intended[0].tool_name, "write");
+        assert_eq!(intended[0].params, write_params);
+
+        let calls = registry.disable_dry_run();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].disposition, DryRunDisposition::Executed);
+        assert!(!registry.is_dry_run());
+    }
+
     #[tokio::test]
     async fn test_registry_execute_tool_failure() {
         let mut registry = ToolRegistry::new();
@@ -0,0 +1,573 @@
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{span, Id, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use super::observation_layer::JsonVisitor;
+
+const TRACE_PID: u64 = 1;
+/// Spans kept by default before the oldest ones are dropped
+pub const DEFAULT_MAX_SPANS: usize = 10_000;
+/// Longest recorded field value, in characters; longer values are truncated
+pub const MAX_FIELD_CHARS: usize = 256;
+/// Environment variable that enables the global layer (`1` or `true`)
+pub const TRACE_SPANS_ENV: &str = "ASTER_TRACE_SPANS";
+/// Span field that assigns a span and its descendants to a session
+const SESSION_ID_FIELD: &str = "session_id";
+
+static GLOBAL_LAYER: Lazy<ChromeTraceLayer> = Lazy::new(ChromeTraceLayer::new);
+
+/// A span as recorded by [`ChromeTraceLayer`]
+#[derive(Debug, Clone)]
+pub struct RecordedSpan {
+    pub name: String,
+    pub target: String,
+    /// Nanoseconds since the layer was created
+    pub start_ns: u64,
+    /// None while the span is still open
+    pub end_ns: Option<u64>,
+    /// Index of the parent span in the recording
+    pub parent: Option<usize>,
+    /// Session the span belongs to, taken from its own `session_id` field or
+    /// inherited from the parent
+    pub session_id: Option<String>,
+    pub fields: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    spans: Vec<RecordedSpan>,
+    /// tracing span id -> index into `spans`; ids are reused after close
+    open: HashMap<u64, usize>,
+}
+
+impl Recording {
+    /// Drop `count` of the oldest spans, preferring closed ones, and remap
+    /// parent links and open span indices. Open spans are only dropped when
+    /// there are not enough closed ones, so spans that never close can't grow
+    /// the recording past its limit. Children of a dropped span become roots.
+    fn evict(&mut self, count: usize) {
+        let mut keep = vec![true; self.spans.len()];
+        let mut remaining = count;
+        for closed in [true, false] {
+            for (index, span) in self.spans.iter().enumerate() {
+                if remaining == 0 {
+                    break;
+                }
+                if keep[index] && span.end_ns.is_some() == closed {
+                    keep[index] = false;
+                    remaining -= 1;
+                }
+            }
+        }
+
+        let mut remap = vec![None; self.spans.len()];
+        let mut next = 0;
+        for (old, &kept) in keep.iter().enumerate() {
+            if kept {
+                remap[old] = Some(next);
+                next += 1;
+            }
+        }
+
+        let mut keep = keep.into_iter();
+        self.spans.retain(|_| keep.next().unwrap_or(true));
+        for span in &mut self.spans {
+            span.parent = span.parent.and_then(|parent| remap[parent]);
+        }
+        self.open.retain(|_, index| match remap[*index] {
+            Some(new) => {
+                *index = new;
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+/// Records span timings so they can be exported in the Chrome trace format
+///
+/// The export loads in `chrome://tracing` and Perfetto. Each span becomes a
+/// begin/end event pair. Spans that nest inside their parent share its track;
+/// spans that overlap a sibling (concurrent tool calls, subagents) are moved
+/// to another track so every track stays properly nested.
+///
+/// At most `max_spans` spans are kept; when the limit is reached the oldest
+/// half is dropped, closed spans first. Field values are truncated to
+/// [`MAX_FIELD_CHARS`].
+///
+/// The global layer is only installed when [`TRACE_SPANS_ENV`] is set, see
+/// [`ChromeTraceLayer::global_if_enabled`].
+#[derive(Clone)]
+pub struct ChromeTraceLayer {
+    epoch: Instant,
+    max_spans: usize,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl Default for ChromeTraceLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChromeTraceLayer {
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            max_spans: DEFAULT_MAX_SPANS,
+            recording: Arc::new(Mutex::new(Recording::default())),
+        }
+    }
+
+    pub fn with_max_spans(mut self, max_spans: usize) -> Self {
+        self.max_spans = max_spans.max(2);
+        self
+    }
+
+    /// The process-wide layer installed by the CLI and server subscribers
+    pub fn global() -> &'static ChromeTraceLayer {
+        &GLOBAL_LAYER
+    }
+
+    /// The global layer, if span recording was enabled with [`TRACE_SPANS_ENV`]
+    pub fn global_if_enabled() -> Option<&'static ChromeTraceLayer> {
+        Self::is_enabled().then(Self::global)
+    }
+
+    /// Whether span recording is enabled for this process
+    pub fn is_enabled() -> bool {
+        std::env::var(TRACE_SPANS_ENV)
+            .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true"))
+            .unwrap_or(false)
+    }
+
+    pub fn spans(&self) -> Vec<RecordedSpan> {
+        self.recording.lock().unwrap().spans.clone()
+    }
+
+    /// The spans of one session, with parent links pointing into the result
+    pub fn session_spans(&self, session_id: &str) -> Vec<RecordedSpan> {
+        let spans = self.spans();
+        let mut remap = vec![None; spans.len()];
+        let mut selected = Vec::new();
+        for (index, span) in spans.into_iter().enumerate() {
+            if span.session_id.as_deref() == Some(session_id) {
+                remap[index] = Some(selected.len());
+                selected.push(span);
+            }
+        }
+        for span in &mut selected {
+            span.parent = span.parent.and_then(|parent| remap[parent]);
+        }
+        selected
+    }
+
+    pub fn clear(&self) {
+        let mut recording = self.recording.lock().unwrap();
+        recording.spans.clear();
+        recording.open.clear();
+    }
+
+    /// Write the recorded spans as Chrome trace JSON
+    ///
+    /// Spans that are still open are ended at the time of the export.
+    pub fn export_chrome_trace<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        let now = self.elapsed_ns();
+        export_chrome_trace(&self.spans(), now, writer)
+    }
+
+    /// Write the spans of one session as Chrome trace JSON
+    pub fn export_session_chrome_trace<W: Write>(
+        &self,
+        session_id: &str,
+        writer: W,
+    ) -> std::io::Result<()> {
+        let now = self.elapsed_ns();
+        export_chrome_trace(&self.session_spans(session_id), now, writer)
+    }
+
+    /// Fold the recorded spans into collapsed stacks for flamegraph tools
+    pub fn collapsed_stacks(&self) -> String {
+        super::flamegraph::collapsed_stacks(&self.spans(), self.elapsed_ns())
+    }
+
+    fn elapsed_ns(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+}
+
+impl<S> Layer<S> for ChromeTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let parent_id = ctx
+            .span(id)
+            .and_then(|span| span.parent())
+            .map(|parent| parent.id().into_u64());
+
+        let mut visitor = JsonVisitor::new();
+        attrs.record(&mut visitor);
+        cap_fields(&mut visitor.recorded_fields);
+
+        let mut recording = self.recording.lock().unwrap();
+        if recording.spans.len() >= self.max_spans {
+            recording.evict(self.max_spans / 2);
+        }
+        let parent = parent_id.and_then(|p| recording.open.get(&p).copied());
+        let session_id = session_field(&visitor.recorded_fields)
+            .or_else(|| parent.and_then(|p| recording.spans[p].session_id.clone()));
+        let index = recording.spans.len();
+        recording.spans.push(RecordedSpan {
+            name: attrs.metadata().name().to_string(),
+            target: attrs.metadata().target().to_string(),
+            start_ns: self.elapsed_ns(),
+            end_ns: None,
+            parent,
+            session_id,
+            fields: visitor.recorded_fields,
+        });
+        recording.open.insert(id.into_u64(), index);
+    }
+
+    fn on_record(&self, id: &Id, values: &span::Record<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = JsonVisitor::new();
+        values.record(&mut visitor);
+        cap_fields(&mut visitor.recorded_fields);
+
+        let mut recording = self.recording.lock().unwrap();
+        if let Some(&index) = recording.open.get(&id.into_u64()) {
+            let span = &mut recording.spans[index];
+            if let Some(session_id) = session_field(&visitor.recorded_fields) {
+                span.session_id = Some(session_id);
+            }
+            span.fields.extend(visitor.recorded_fields);
+        }
+    }
+
+    fn on_close(&self, id: Id, _ctx: Context<'_, S>) {
+        let end = self.elapsed_ns();
+        let mut recording = self.recording.lock().unwrap();
+        if let Some(index) = recording.open.remove(&id.into_u64()) {
+            recording.spans[index].end_ns = Some(end);
+        }
+    }
+}
+
+/// Truncate field values longer than [`MAX_FIELD_CHARS`]; large arrays and
+/// objects are kept as truncated JSON text
+fn cap_fields(fields: &mut serde_json::Map<String, Value>) {
+    for value in fields.values_mut() {
+        let text = match value {
+            Value::String(text) => std::mem::take(text),
+            Value::Array(_) | Value::Object(_) => {
+                let text = value.to_string();
+                if text.len() <= MAX_FIELD_CHARS {
+                    continue;
+                }
+                text
+            }
+            _ => continue,
+        };
+        *value = Value::String(truncate_chars(text));
+    }
+}
+
+fn truncate_chars(mut text: String) -> String {
+    if let Some((cut, _)) = text.char_indices().nth(MAX_FIELD_CHARS) {
+        text.truncate(cut);
+        text.push('…');
+    }
+    text
+}
+
+fn session_field(fields: &serde_json::Map<String, Value>) -> Option<String> {
+    fields
+        .get(SESSION_ID_FIELD)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Write spans as Chrome trace JSON, ending open spans at `now_ns`
+pub fn export_chrome_trace<W: Write>(
+    spans: &[RecordedSpan],
+    now_ns: u64,
+    writer: W,
+) -> std::io::Result<()> {
+    let end_of = |i: usize| spans[i].end_ns.unwrap_or(now_ns).max(spans[i].start_ns);
+
+    // Parents before children: earlier start first, longer span first on ties
+    let mut order: Vec<usize> = (0..spans.len()).collect();
+    order.sort_by_key(|&i| (spans[i].start_ns, Reverse(end_of(i))));
+
+    // Each track is a stack of (span, end) that are open at the current time
+    let mut tracks: Vec<Vec<(usize, u64)>> = Vec::new();
+    let mut placement: Vec<(usize, usize)> = vec![(0, 0); spans.len()];
+    for &i in &order {
+        let (start, end) = (spans[i].start_ns, end_of(i));
+        for stack in tracks.iter_mut() {
+            while stack.last().is_some_and(|&(_, e)| e <= start) {
+                stack.pop();
+            }
+        }
+
+        let nested_in_parent = spans[i].parent.and_then(|parent| {
+            let track = placement[parent].0;
+            match tracks[track].last() {
+                Some(&(top, top_end)) if top == parent && end <= top_end => Some(track),
+                _ => None,
+            }
+        });
+        let track = nested_in_parent
+            .or_else(|| tracks.iter().position(|stack| stack.is_empty()))
+            .unwrap_or_else(|| {
+                tracks.push(Vec::new());
+                tracks.len() - 1
+            });
+
+        placement[i] = (track, tracks[track].len());
+        tracks[track].push((i, end));
+    }
+
+    // (ts, is_begin, depth order, event); ends sort before begins at the same
+    // time, inner ends before outer ends, outer begins before inner begins
+    let mut events: Vec<(u64, bool, i64, Value)> = Vec::with_capacity(spans.len() * 2);
+    let mut track_names: Vec<Option<&str>> = vec![None; tracks.len()];
+    for &i in &order {
+        let span = &spans[i];
+        let (track, depth) = placement[i];
+        track_names[track].get_or_insert(span.name.as_str());
+        let tid = track as u64 + 1;
+        events.push((
+            span.start_ns,
+            true,
+            depth as i64,
+            json!({
+                "name": span.name,
+                "cat": span.target,
+                "ph": "B",
+                "ts": micros(span.start_ns),
+                "pid": TRACE_PID,
+                "tid": tid,
+                "args": span.fields,
+            }),
+        ));
+        events.push((
+            end_of(i),
+            false,
+            -(depth as i64),
+            json!({
+                "name": span.name,
+                "cat": span.target,
+                "ph": "E",
+                "ts": micros(end_of(i)),
+                "pid": TRACE_PID,
+                "tid": tid,
+            }),
+        ));
+    }
+    events.sort_by_key(|(ts, is_begin, depth, _)| (*ts, *is_begin, *depth));
+
+    let mut trace_events: Vec<Value> = track_names
+        .iter()
+        .enumerate()
+        .map(|(track, name)| {
+            json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": TRACE_PID,
+                "tid": track as u64 + 1,
+                "args": { "name": name.unwrap_or("track") },
+            })
+        })
+        .collect();
+    trace_events.extend(events.into_iter().map(|(_, _, _, event)| event));
+
+    serde_json::to_writer(
+        writer,
+        &json!({
+            "traceEvents": trace_events,
+            "displayTimeUnit": "ms",
+        }),
+    )
+    .map_err(std::io::Error::other)
+}
+
+fn micros(ns: u64) -> f64 {
+    ns as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::info_span;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_export_span_tree() {
+        let layer = ChromeTraceLayer::new();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let reply = info_span!("reply", session_id = "s1");
+            let _reply = reply.enter();
+            {
+                let _provider = info_span!("provider_request", model = "test").entered();
+            }
+            let tool = info_span!("tool_call", tool = "shell").entered();
+            {
+                let _subagent = info_span!("subagent").entered();
+            }
+            // Two tool calls running at the same time overlap without nesting
+            let concurrent = info_span!(parent: &reply, "tool_call", tool = "read");
+            drop(tool);
+            drop(concurrent);
+        });
+
+        let mut output = Vec::new();
+        layer.export_chrome_trace(&mut output).unwrap();
+        let trace: Value = serde_json::from_slice(&output).expect("valid JSON");
+        let events = trace["traceEvents"].as_array().unwrap();
+
+        let begins = events.iter().filter(|e| e["ph"] == "B").count();
+        let ends = events.iter().filter(|e| e["ph"] == "E").count();
+        assert_eq!((begins, ends), (5, 5));
+
+        // Begin/end events must nest on every track
+        let mut stacks: HashMap<u64, Vec<String>> = HashMap::new();
+        let mut last_ts = 0.0;
+        for event in events.iter().filter(|e| e["ph"] != "M") {
+            let ts = event["ts"].as_f64().unwrap();
+            assert!(ts >= last_ts, "events are sorted by time");
+            last_ts = ts;
+
+            let stack = stacks.entry(event["tid"].as_u64().unwrap()).or_default();
+            let name = event["name"].as_str().unwrap().to_string();
+            if event["ph"] == "B" {
+                stack.push(name);
+            } else {
+                assert_eq!(stack.pop(), Some(name), "end matches innermost begin");
+            }
+        }
+        assert!(stacks.values().all(|s| s.is_empty()));
+
+        let tid_of = |name: &str| {
+            events
+                .iter()
+                .find(|e| e["ph"] == "B" && e["name"] == name)
+                .map(|e| e["tid"].as_u64().unwrap())
+                .unwrap()
+        };
+        assert_eq!(tid_of("subagent"), tid_of("reply"));
+        let reply = events
+            .iter()
+            .find(|e| e["ph"] == "B" && e["name"] == "reply")
+            .unwrap();
+        assert_eq!(reply["args"]["session_id"], "s1");
+    }
+
+    #[test]
+    fn test_export_is_scoped_to_session() {
+        let layer = ChromeTraceLayer::new();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            for session in ["s1", "s2"] {
+                let _reply = info_span!("reply", session_id = session).entered();
+                let _tool = info_span!("tool_call").entered();
+            }
+            let _unrelated = info_span!("startup").entered();
+        });
+
+        let spans = layer.session_spans("s1");
+        let names: Vec<&str> = spans.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["reply", "tool_call"]);
+        assert_eq!(spans[1].parent, Some(0));
+
+        let mut output = Vec::new();
+        layer
+            .export_session_chrome_trace("s2", &mut output)
+            .unwrap();
+        let trace: Value = serde_json::from_slice(&output).unwrap();
+        let begins: Vec<&Value> = trace["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["ph"] == "B")
+            .collect();
+        assert_eq!(begins.len(), 2);
+        assert_eq!(begins[0]["args"]["session_id"], "s2");
+    }
+
+    #[test]
+    fn test_recording_is_bounded() {
+        let layer = ChromeTraceLayer::new().with_max_spans(10);
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _root = info_span!("reply", session_id = "s1").entered();
+            for _ in 0..100 {
+                let _tool = info_span!("tool_call").entered();
+            }
+        });
+
+        let spans = layer.spans();
+        assert!(spans.len() <= 10);
+        // The open root is never dropped and later children still link to it
+        assert_eq!(spans[0].name, "reply");
+        let last = spans.last().unwrap();
+        assert_eq!(last.parent, Some(0));
+        assert_eq!(last.session_id.as_deref(), Some("s1"));
+    }
+
+    #[test]
+    fn test_open_spans_are_evicted_when_nothing_closed() {
+        let layer = ChromeTraceLayer::new().with_max_spans(10);
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let open: Vec<_> = (0..100)
+                .map(|i| info_span!("stream", index = i).entered())
+                .collect();
+            assert!(layer.spans().len() <= 10);
+            drop(open);
+        });
+
+        let spans = layer.spans();
+        assert!(spans.len() <= 10);
+        // Only the newest spans remain and they were all closed
+        assert_eq!(spans.last().unwrap().fields["index"], 99);
+        assert!(spans.iter().all(|span| span.end_ns.is_some()));
+    }
+
+    #[test]
+    fn test_large_field_values_are_truncated() {
+        let layer = ChromeTraceLayer::new();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        let prompt = "x".repeat(10_000);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!(
+                "provider_request",
+                input = prompt.as_str(),
+                output = tracing::field::Empty
+            );
+            span.record("output", prompt.as_str());
+        });
+
+        let span = &layer.spans()[0];
+        for field in ["input", "output"] {
+            let value = span.fields[field].as_str().unwrap();
+            assert_eq!(value.chars().count(), MAX_FIELD_CHARS + 1);
+            assert!(value.ends_with('…'));
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use super::chrome_trace::RecordedSpan;
+
+/// Fold a span tree into collapsed stacks (`a;b;c count`)
+///
+/// Each line is a root-to-span path and the span's self time in
+/// microseconds, the input format of `inferno-flamegraph` and
+/// `flamegraph.pl`. Self time is the span's wall time minus the time covered
+/// by at least one child, so children that overlap each other (concurrent
+/// async work) are not subtracted twice. Identical paths are summed and
+/// spans still open are ended at `now_ns`.
+pub fn collapsed_stacks(spans: &[RecordedSpan], now_ns: u64) -> String {
+    let end_of = |i: usize| spans[i].end_ns.unwrap_or(now_ns).max(spans[i].start_ns);
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); spans.len()];
+    for (i, span) in spans.iter().enumerate() {
+        if let Some(parent) = span.parent {
+            children[parent].push(i);
+        }
+    }
+
+    let mut folded: BTreeMap<String, u64> = BTreeMap::new();
+    for i in 0..spans.len() {
+        let (start, end) = (spans[i].start_ns, end_of(i));
+        let mut intervals: Vec<(u64, u64)> = children[i]
+            .iter()
+            .map(|&c| (spans[c].start_ns.max(start), end_of(c).min(end)))
+            .filter(|(s, e)| s < e)
+            .collect();
+        let self_ns = (end - start) - covered(&mut intervals);
+
+        *folded.entry(stack_path(spans, i)).or_default() += self_ns;
+    }
+
+    folded
+        .into_iter()
+        .filter_map(|(path, ns)| {
+            let micros = ns / 1000;
+            (micros > 0).then(|| format!("{} {}\n", path, micros))
+        })
+        .collect()
+}
+
+/// Total length of the union of intervals
+fn covered(intervals: &mut [(u64, u64)]) -> u64 {
+    intervals.sort_unstable();
+    let mut total = 0;
+    let mut current: Option<(u64, u64)> = None;
+    for &(start, end) in intervals.iter() {
+        current = match current {
+            Some((s, e)) if start <= e => Some((s, e.max(end))),
+            Some((s, e)) => {
+                total += e - s;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    total + current.map_or(0, |(s, e)| e - s)
+}
+
+fn stack_path(spans: &[RecordedSpan], mut i: usize) -> String {
+    let mut frames = vec![frame_name(&spans[i].name)];
+    while let Some(parent) = spans[i].parent {
+        frames.push(frame_name(&spans[parent].name));
+        i = parent;
+    }
+    frames.reverse();
+    frames.join(";")
+}
+
+/// `;` separates frames and the last space separates the count
+fn frame_name(name: &str) -> String {
+    name.replace(';', ",").replace(' ', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(name: &str, start_us: u64, end_us: u64, parent: Option<usize>) -> RecordedSpan {
+        RecordedSpan {
+            name: name.to_string(),
+            target: "aster::test".to_string(),
+            start_ns: start_us * 1000,
+            end_ns: Some(end_us * 1000),
+            parent,
+            session_id: None,
+            fields: serde_json::Map::new(),
+        }
+    }
+
+    #[test]
+    fn test_collapsed_stacks_with_overlapping_children() {
+        let spans = vec![
+            span("session", 0, 100, None),
+            span("provider_request", 10, 40, Some(0)),
+            // Overlaps provider_request by 10us
+            span("tool_call", 30, 70, Some(0)),
+            span("subagent", 40, 60, Some(2)),
+            // A second call on the same path is summed
+            span("provider_request", 75, 80, Some(0)),
+        ];
+
+        assert_eq!(
+            collapsed_stacks(&spans, 0),
+            "session 35\n\
+             session;provider_request 35\n\
+             session;tool_call 20\n\
+             session;tool_call;subagent 20\n"
+        );
+    }
+
+    #[test]
+    fn test_open_spans_end_now() {
+        let mut spans = vec![span("session", 0, 0, None)];
+        spans[0].end_ns = None;
+        assert_eq!(collapsed_stacks(&spans, 5_000), "session 5\n");
+    }
+}
@@ -1,3 +1,5 @@
+pub mod chrome_trace;
+pub mod flamegraph;
 pub mod langfuse_layer;
 mod observation_layer;
 #[cfg(feature = "telemetry-otlp")]
@@ -8,6 +10,8 @@ pub mod otlp_layer;
 pub mod otlp_layer;
 pub mod rate_limiter;
 
+pub use chrome_trace::{export_chrome_trace, ChromeTraceLayer, RecordedSpan};
+pub use flamegraph::collapsed_stacks;
 pub use langfuse_layer::{create_langfuse_observer, LangfuseBatchManager};
 pub use observation_layer::{
     flatten_metadata, map_level, BatchManager, ObservationLayer, SpanData, SpanTracker,
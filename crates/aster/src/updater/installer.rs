@@ -3,7 +3,12 @@
 //! 提供更新下载、安装和回滚功能
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::signature::{SignatureVerification, SignatureVerifier};
+
+/// 更新包中可执行文件的名称
+const BINARY_NAME: &str = if cfg!(windows) { "aster.exe" } else { "aster" };
 
 /// 安装结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +17,9 @@ pub struct InstallResult {
     pub version: String,
     pub output: Option<String>,
     pub error: Option<String>,
+    /// 安装前的签名校验结果
+    #[serde(default)]
+    pub signature: SignatureVerification,
 }
 
 /// 下载进度
@@ -47,12 +55,15 @@ pub struct InstallOptions {
     pub show_progress: bool,
     /// 安装目录
     pub install_dir: Option<PathBuf>,
+    /// 签名或公证校验失败时仍然安装
+    pub allow_unsigned: bool,
 }
 
 /// 更新安装器
 pub struct Installer {
     download_dir: PathBuf,
     install_dir: PathBuf,
+    verifier: SignatureVerifier,
 }
 
 impl Installer {
@@ -65,6 +76,7 @@ impl Installer {
         Self {
             download_dir: base_dir.join("downloads"),
             install_dir: base_dir.join("bin"),
+            verifier: SignatureVerifier::new(),
         }
     }
 
@@ -73,9 +85,16 @@ impl Installer {
         Self {
             download_dir,
             install_dir,
+            verifier: SignatureVerifier::new(),
         }
     }
 
+    /// 设置签名校验器
+    pub fn with_verifier(mut self, verifier: SignatureVerifier) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
     /// 下载更新包
     pub async fn download(&self, url: &str, options: &InstallOptions) -> Result<PathBuf, String> {
         if options.dry_run {
@@ -110,6 +129,23 @@ impl Installer {
                 version: options.version.clone().unwrap_or_default(),
                 output: Some("Dry run completed".to_string()),
                 error: None,
+                signature: SignatureVerification::Skipped,
+            });
+        }
+
+        // 取出二进制后再校验签名和公证：校验的是将要安装的文件，而不是更新包
+        let staging_dir = self.download_dir.join("staging");
+        let binary = stage_binary(package_path, &staging_dir).await?;
+        let signature = self.verifier.verify(&binary, options.allow_unsigned).await;
+        if let SignatureVerification::Rejected { reason } = &signature {
+            tracing::error!("拒绝安装未签名或未公证的更新: {}", reason);
+            let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+            return Ok(InstallResult {
+                success: false,
+                version: options.version.clone().unwrap_or_default(),
+                output: None,
+                error: Some(reason.clone()),
+                signature,
             });
         }
 
@@ -121,14 +157,18 @@ impl Installer {
         // 备份当前版本
         self.backup_current(install_dir)?;
 
-        // 解压并安装（简化实现）
-        tracing::info!("安装更新: {:?} -> {:?}", package_path, install_dir);
+        // 安装校验过的二进制
+        tracing::info!("安装更新: {:?} -> {:?}", binary, install_dir);
+        let installed = tokio::fs::copy(&binary, install_dir.join(BINARY_NAME)).await;
+        let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+        installed.map_err(|e| format!("安装二进制失败: {}", e))?;
 
         Ok(InstallResult {
             success: true,
             version: options.version.clone().unwrap_or_default(),
             output: Some("Installation completed".to_string()),
             error: None,
+            signature,
         })
     }
 
@@ -145,6 +185,7 @@ impl Installer {
                 version: version.to_string(),
                 output: Some("Dry run completed".to_string()),
                 error: None,
+                signature: SignatureVerification::Skipped,
             });
         }
 
@@ -162,6 +203,7 @@ impl Installer {
             version: version.to_string(),
             output: Some(format!("Rolled back to version {}", version)),
             error: None,
+            signature: SignatureVerification::Skipped,
         })
     }
 
@@ -240,9 +282,67 @@ impl Default for Installer {
     }
 }
 
+/// 把更新包中的二进制放到暂存目录并返回其路径
+///
+/// `.tar.gz` / `.tgz` 包用 `tar` 解压后查找其中的 `aster` 可执行文件，
+/// 其他文件视为二进制本身。
+async fn stage_binary(package_path: &Path, staging_dir: &Path) -> Result<PathBuf, String> {
+    let _ = tokio::fs::remove_dir_all(staging_dir).await;
+    tokio::fs::create_dir_all(staging_dir)
+        .await
+        .map_err(|e| format!("创建暂存目录失败: {}", e))?;
+
+    let name = package_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if !(name.ends_with(".tar.gz") || name.ends_with(".tgz")) {
+        let binary = staging_dir.join(BINARY_NAME);
+        tokio::fs::copy(package_path, &binary)
+            .await
+            .map_err(|e| format!("复制更新包失败: {}", e))?;
+        return Ok(binary);
+    }
+
+    let output = tokio::process::Command::new("tar")
+        .arg("-xzf")
+        .arg(package_path)
+        .arg("-C")
+        .arg(staging_dir)
+        .output()
+        .await
+        .map_err(|e| format!("执行 tar 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "解压失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    find_binary(staging_dir).ok_or_else(|| format!("更新包中没有 {}", BINARY_NAME))
+}
+
+/// 在解压目录中查找可执行文件
+fn find_binary(dir: &Path) -> Option<PathBuf> {
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir).ok()?.flatten() {
+            let path = entry.path();
+            let file_type = entry.file_type().ok()?;
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_file() && entry.file_name() == BINARY_NAME {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::updater::signature::{CommandOutput, CommandRunner};
 
     #[test]
     fn test_installer_new() {
@@ -273,6 +373,7 @@ mod tests {
         assert!(!options.dry_run);
         assert!(!options.show_progress);
         assert!(options.install_dir.is_none());
+        assert!(!options.allow_unsigned);
     }
 
     #[test]
@@ -282,6 +383,7 @@ mod tests {
             version: "1.0.0".to_string(),
             output: Some("OK".to_string()),
             error: None,
+            signature: SignatureVerification::Verified,
         };
         assert!(result.success);
         assert_eq!(result.version, "1.0.0");
@@ -380,4 +482,174 @@ mod tests {
         // 应该去掉多余的 v
         assert!(path.to_string_lossy().contains("v1.0.0"));
     }
+
+    struct MockRunner {
+        codesign_ok: bool,
+        spctl_ok: bool,
+        calls: std::sync::Mutex<Vec<String>>,
+        targets: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl MockRunner {
+        fn new(codesign_ok: bool, spctl_ok: bool) -> std::sync::Arc<Self> {
+            std::sync::Arc::new(Self {
+                codesign_ok,
+                spctl_ok,
+                calls: std::sync::Mutex::new(Vec::new()),
+                targets: std::sync::Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl CommandRunner for MockRunner {
+        async fn run(&self, program: &str, args: &[&str]) -> std::io::Result<CommandOutput> {
+            self.calls.lock().unwrap().push(program.to_string());
+            if let Some(target) = args.last() {
+                self.targets.lock().unwrap().push(target.to_string());
+            }
+            let success = match program {
+                "codesign" => self.codesign_ok,
+                "spctl" => self.spctl_ok,
+                _ => false,
+            };
+            Ok(CommandOutput {
+                success,
+                stdout: String::new(),
+                stderr: if success {
+                    String::new()
+                } else {
+                    "rejected".to_string()
+                },
+            })
+        }
+    }
+
+    fn installer_with(runner: std::sync::Arc<MockRunner>, dir: &std::path::Path) -> Installer {
+        Installer::with_dirs(dir.join("downloads"), dir.join("bin"))
+            .with_verifier(SignatureVerifier::with_runner(runner))
+    }
+
+    /// A bare binary used as the update package
+    fn package(dir: &std::path::Path) -> PathBuf {
+        let path = dir.join("aster-update");
+        std::fs::write(&path, b"new aster").unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_install_verifies_signature() {
+        let temp = tempfile::tempdir().unwrap();
+        let runner = MockRunner::new(true, true);
+        let installer = installer_with(runner.clone(), temp.path());
+
+        let result = installer
+            .install(&package(temp.path()), &InstallOptions::default())
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.signature, SignatureVerification::Verified);
+        assert_eq!(*runner.calls.lock().unwrap(), vec!["codesign", "spctl"]);
+        assert_eq!(
+            std::fs::read(temp.path().join("bin").join(BINARY_NAME)).unwrap(),
+            b"new aster"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_verifies_extracted_binary() {
+        let temp = tempfile::tempdir().unwrap();
+        let contents = temp.path().join("contents").join("aster-1.2.0");
+        std::fs::create_dir_all(&contents).unwrap();
+        std::fs::write(contents.join(BINARY_NAME), b"packed aster").unwrap();
+        std::fs::write(contents.join("README.md"), b"readme").unwrap();
+        let tarball = temp.path().join("aster-1.2.0.tar.gz");
+        let status = std::process::Command::new("tar")
+            .arg("-czf")
+            .arg(&tarball)
+            .arg("-C")
+            .arg(temp.path().join("contents"))
+            .arg(".")
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let runner = MockRunner::new(true, true);
+        let installer = installer_with(runner.clone(), temp.path());
+        let result = installer
+            .install(&tarball, &InstallOptions::default())
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let targets = runner.targets.lock().unwrap().clone();
+        assert_eq!(targets.len(), 2);
+        for target in targets {
+            assert!(
+                target.ends_with(&format!("aster-1.2.0/{}", BINARY_NAME)),
+                "{}",
+                target
+            );
+        }
+        assert_eq!(
+            std::fs::read(temp.path().join("bin").join(BINARY_NAME)).unwrap(),
+            b"packed aster"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_unnotarized_binary() {
+        let temp = tempfile::tempdir().unwrap();
+        let installer = installer_with(MockRunner::new(true, false), temp.path());
+
+        let result = installer
+            .install(&package(temp.path()), &InstallOptions::default())
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("spctl"));
+        assert!(matches!(
+            result.signature,
+            SignatureVerification::Rejected { .. }
+        ));
+        assert!(!temp.path().join("bin").exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_allow_unsigned_overrides_rejection() {
+        let temp = tempfile::tempdir().unwrap();
+        let runner = MockRunner::new(false, true);
+        let installer = installer_with(runner.clone(), temp.path());
+        let options = InstallOptions {
+            allow_unsigned: true,
+            ..Default::default()
+        };
+
+        let result = installer
+            .install(&package(temp.path()), &options)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(matches!(
+            result.signature,
+            SignatureVerification::Overridden { .. }
+        ));
+        // codesign 失败后不再执行 spctl
+        assert_eq!(*runner.calls.lock().unwrap(), vec!["codesign"]);
+    }
+
+    #[tokio::test]
+    async fn test_signature_verifier_platform_default() {
+        let verifier = SignatureVerifier::new();
+        assert_eq!(verifier.is_enabled(), cfg!(target_os = "macos"));
+        if !verifier.is_enabled() {
+            assert_eq!(
+                verifier
+                    .verify(std::path::Path::new("/nonexistent"), false)
+                    .await,
+                SignatureVerification::Skipped
+            );
+        }
+    }
 }
@@ -66,6 +66,8 @@ pub struct UpdateOptions {
     pub beta: bool,
     pub canary: bool,
     pub show_progress: bool,
+    /// 签名或公证校验失败时仍然安装
+    pub allow_unsigned: bool,
 }
 
 /// 更新事件
@@ -228,6 +230,7 @@ impl UpdateManager {
             force: options.force,
             dry_run: options.dry_run,
             show_progress: options.show_progress,
+            allow_unsigned: options.allow_unsigned,
             ..Default::default()
         };
 
@@ -236,9 +239,19 @@ impl UpdateManager {
             .join("aster/downloads")
             .join(format!("aster-{}.tar.gz", std::env::consts::OS));
 
-        self.installer
+        let result = self
+            .installer
             .install(&package_path, &install_options)
             .await?;
+        if !result.success {
+            let message = result.error.unwrap_or_else(|| "安装失败".to_string());
+            *self.status.write().await = UpdateStatus::Error;
+            self.emit(UpdateEvent::Error {
+                message: message.clone(),
+            })
+            .await;
+            return Err(message);
+        }
 
         self.emit(UpdateEvent::Installed {
             version: target_version.to_string(),
@@ -5,6 +5,7 @@
 mod checker;
 mod installer;
 mod manager;
+mod signature;
 
 pub use checker::{
     check_for_updates as check_version, compare_versions, UpdateCheckResult, VersionInfo,
@@ -14,3 +15,6 @@ pub use manager::{
     check_for_updates, list_versions, perform_update, rollback_version, UpdateChannel,
     UpdateConfig, UpdateEvent, UpdateManager, UpdateOptions, UpdateStatus,
 };
+pub use signature::{
+    CommandOutput, CommandRunner, SignatureVerification, SignatureVerifier, SystemCommandRunner,
+};
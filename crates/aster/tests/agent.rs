@@ -454,6 +454,166 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod invalid_tool_arguments_tests {
+        use super::*;
+        use aster::agents::extension::{ExtensionConfig, PlatformExtensionContext};
+        use aster::agents::extension_manager_extension::MANAGE_EXTENSIONS_TOOL_NAME;
+        use aster::agents::SessionConfig;
+        use aster::conversation::message::{Message, MessageContent};
+        use aster::model::ModelConfig;
+        use aster::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+        use aster::providers::errors::ProviderError;
+        use aster::session::session_manager::SessionType;
+        use aster::session::SessionManager;
+        use async_trait::async_trait;
+        use rmcp::model::{CallToolRequestParam, ErrorCode, Tool};
+        use rmcp::object;
+        use std::path::PathBuf;
+        use std::sync::Mutex;
+
+        /// Calls manage_extensions with invalid arguments, then answers with text
+        #[derive(Default)]
+        struct InvalidArgumentsProvider {
+            requests: Mutex<Vec<Vec<Message>>>,
+        }
+
+        #[async_trait]
+        impl Provider for InvalidArgumentsProvider {
+            async fn complete(
+                &self,
+                _system_prompt: &str,
+                messages: &[Message],
+                _tools: &[Tool],
+            ) -> Result<(Message, ProviderUsage), ProviderError> {
+                let mut requests = self.requests.lock().unwrap();
+                requests.push(messages.to_vec());
+                let message = if requests.len() == 1 {
+                    Message::assistant().with_tool_request(
+                        "call_invalid",
+                        Ok(CallToolRequestParam {
+                            name: format!("extensionmanager__{MANAGE_EXTENSIONS_TOOL_NAME}").into(),
+                            arguments: Some(object!({ "action": 42 })),
+                        }),
+                    )
+                } else {
+                    Message::assistant().with_text("Done")
+                };
+                let usage = ProviderUsage::new(
+                    "mock-model".to_string(),
+                    Usage::new(Some(10), Some(5), Some(15)),
+                );
+                Ok((message, usage))
+            }
+
+            async fn complete_with_model(
+                &self,
+                _model_config: &ModelConfig,
+                system_prompt: &str,
+                messages: &[Message],
+                tools: &[Tool],
+            ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+                self.complete(system_prompt, messages, tools).await
+            }
+
+            fn get_model_config(&self) -> ModelConfig {
+                ModelConfig::new("mock-model").unwrap()
+            }
+
+            fn metadata() -> ProviderMetadata {
+                ProviderMetadata {
+                    name: "mock".to_string(),
+                    display_name: "Mock Provider".to_string(),
+                    description: "Mock provider for testing".to_string(),
+                    default_model: "mock-model".to_string(),
+                    known_models: vec![],
+                    model_doc_link: "".to_string(),
+                    config_keys: vec![],
+                }
+            }
+
+            fn get_name(&self) -> &str {
+                "mock-test"
+            }
+        }
+
+        #[tokio::test]
+        async fn test_invalid_tool_arguments_are_answered_in_the_loop() -> Result<()> {
+            let agent = Agent::new();
+            agent
+                .extension_manager
+                .set_context(PlatformExtensionContext {
+                    session_id: Some("test_session".to_string()),
+                    extension_manager: Some(Arc::downgrade(&agent.extension_manager)),
+                })
+                .await;
+            agent
+                .add_extension(ExtensionConfig::Platform {
+                    name: "extensionmanager".to_string(),
+                    description: "Extension Manager".to_string(),
+                    bundled: Some(true),
+                    available_tools: vec![],
+                })
+                .await?;
+
+            let provider = Arc::new(InvalidArgumentsProvider::default());
+            let session = SessionManager::create_session(
+                PathBuf::default(),
+                "invalid-tool-arguments-test".to_string(),
+                SessionType::Hidden,
+            )
+            .await?;
+            agent.update_provider(provider.clone(), &session.id).await?;
+
+            let session_config = SessionConfig {
+                id: session.id,
+                schedule_id: None,
+                max_turns: Some(5),
+                retry_config: None,
+                system_prompt: None,
+            };
+            let reply_stream = agent
+                .reply(
+                    Message::user().with_text("Enable the todo extension"),
+                    session_config,
+                    None,
+                )
+                .await?;
+            tokio::pin!(reply_stream);
+
+            let mut tool_errors = Vec::new();
+            while let Some(event) = reply_stream.next().await {
+                if let AgentEvent::Message(message) = event? {
+                    for content in &message.content {
+                        if let MessageContent::ToolResponse(response) = content {
+                            if let Err(error) = &response.tool_result {
+                                tool_errors.push((response.id.clone(), error.clone()));
+                            }
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(tool_errors.len(), 1);
+            let (id, error) = &tool_errors[0];
+            assert_eq!(id, "call_invalid");
+            assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+            assert!(error.message.contains("The tool was not run"));
+
+            // The model sees the error on the next turn and can correct itself
+            let requests = provider.requests.lock().unwrap();
+            assert_eq!(requests.len(), 2);
+            let answered = requests[1].iter().any(|message| {
+                message.content.iter().any(|content| {
+                    matches!(content, MessageContent::ToolResponse(response)
+                        if response.id == "call_invalid" && response.tool_result.is_err())
+                })
+            });
+            assert!(answered, "the correction was not sent back to the model");
+            Ok(())
+        }
+    }
+
     #[cfg(test)]
     mod extension_manager_tests {
         use super::*;
@@ -107,7 +107,7 @@ fn test_full_context_manager_workflow() {
 
     // Step 6: Import into new manager and verify consistency
     let mut new_manager = EnhancedContextManager::new(ContextConfig::default());
-    new_manager.import(export);
+    new_manager.import(export).unwrap();
 
     assert_eq!(new_manager.turn_count(), 3);
     assert_eq!(
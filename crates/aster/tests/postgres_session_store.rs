@@ -0,0 +1,138 @@
+//! Integration tests for `PostgresSessionStore`.
+//!
+//! Requires the `session-postgres` feature and a running Postgres instance:
+//! `ASTER_TEST_POSTGRES_URL=postgres://localhost/aster_test cargo test --features session-postgres`
+#![cfg(feature = "session-postgres")]
+
+use aster::conversation::message::Message;
+use aster::session::{MessageOrder, PostgresSessionStore, SessionStore, SessionType, TagMatch};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+async fn store() -> Option<PostgresSessionStore> {
+    let Ok(url) = std::env::var("ASTER_TEST_POSTGRES_URL") else {
+        println!("ASTER_TEST_POSTGRES_URL not set, skipping test");
+        return None;
+    };
+    Some(PostgresSessionStore::connect(&url).await.unwrap())
+}
+
+#[tokio::test]
+async fn test_session_round_trip() {
+    let Some(store) = store().await else {
+        return;
+    };
+
+    let session = store
+        .create_session(
+            PathBuf::from("/tmp/pg-test"),
+            "postgres round trip".to_string(),
+            SessionType::User,
+        )
+        .await
+        .unwrap();
+
+    store
+        .add_message(
+            &session.id,
+            &Message::user().with_text("how do I configure the zephyrine proxy?"),
+        )
+        .await
+        .unwrap();
+    store
+        .add_message(
+            &session.id,
+            &Message::assistant().with_text("set the zephyrine proxy url in config"),
+        )
+        .await
+        .unwrap();
+
+    let loaded = store.get_session(&session.id, true).await.unwrap();
+    assert_eq!(loaded.name, "postgres round trip");
+    assert_eq!(loaded.message_count, 2);
+    assert_eq!(loaded.conversation.unwrap().messages().len(), 2);
+
+    let page = store
+        .get_messages_paginated(&session.id, 1, 10, MessageOrder::Ascending)
+        .await
+        .unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.messages.len(), 1);
+    assert!(page.messages[0].as_concat_text().starts_with("set the"));
+    assert!(!page.has_more());
+
+    let listed = store.list_sessions().await.unwrap();
+    assert!(listed.iter().any(|s| s.id == session.id));
+
+    let matches = store
+        .search_chat_history("zephyrine proxy", Some(10), None, None, None)
+        .await
+        .unwrap();
+    let ours: Vec<_> = matches
+        .iter()
+        .filter(|m| m.session_id == session.id)
+        .collect();
+    assert_eq!(ours.len(), 2);
+    assert!(ours.iter().all(|m| m.relevance_score == 1.0));
+
+    let excluded = store
+        .search_chat_history("zephyrine", None, None, None, Some(session.id.clone()))
+        .await
+        .unwrap();
+    assert!(excluded.iter().all(|m| m.session_id != session.id));
+
+    let tags = vec!["postgres-test".to_string(), "proxy".to_string()];
+    store.add_session_tags(&session.id, &tags).await.unwrap();
+    let tagged = store
+        .list_sessions_by_tags(&tags, TagMatch::All)
+        .await
+        .unwrap();
+    assert!(tagged.iter().any(|s| s.id == session.id && s.tags == tags));
+
+    let copy = store
+        .copy_session(&session.id, "postgres copy".to_string())
+        .await
+        .unwrap();
+    assert_eq!(copy.message_count, 2);
+    assert_eq!(copy.tags, tags);
+
+    store.delete_session(&session.id).await.unwrap();
+    store.delete_session(&copy.id).await.unwrap();
+    assert!(store.get_session(&session.id, false).await.is_err());
+}
+
+#[tokio::test]
+async fn test_concurrent_creates_get_unique_ids() {
+    let Some(store) = store().await else {
+        return;
+    };
+    let store = Arc::new(store);
+
+    let handles: Vec<_> = (0..10)
+        .map(|i| {
+            let store = Arc::clone(&store);
+            tokio::spawn(async move {
+                store
+                    .create_session(
+                        PathBuf::from("/tmp/pg-test"),
+                        format!("concurrent {}", i),
+                        SessionType::User,
+                    )
+                    .await
+                    .unwrap()
+                    .id
+            })
+        })
+        .collect();
+
+    let mut ids = HashSet::new();
+    for handle in handles {
+        ids.insert(handle.await.unwrap());
+    }
+    assert_eq!(ids.len(), 10);
+
+    for id in ids {
+        store.delete_session(&id).await.unwrap();
+    }
+}
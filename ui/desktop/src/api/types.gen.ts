@@ -863,6 +863,7 @@ export type Session = {
     recipe?: Recipe | null;
     schedule_id?: string | null;
     session_type?: SessionType;
+    tags?: Array<string>;
     total_tokens?: number | null;
     updated_at: string;
     user_recipe_values?: {
@@ -163,6 +163,23 @@ pub async fn uninstall_extension(name: String) -> Result<(), String> {
 }
 
 
+// ============================================================================
+// 通知命令
+// ============================================================================
+
+/// 通知动作按钮被点击，路由回 Agent 注册的回调
+///
+/// 返回是否有回调被触发；未知的通知或动作只记录日志
+#[tauri::command]
+pub async fn handle_notification_action(
+    notification_id: String,
+    action: String,
+) -> Result<bool, String> {
+    Ok(aster::notifications::NotificationManager::global()
+        .handle_action(&notification_id, &action))
+}
+
+
 // ============================================================================
 // 服务器命令
 // ============================================================================
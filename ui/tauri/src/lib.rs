@@ -45,6 +45,7 @@ pub fn run() {
             commands::get_extensions,
             commands::install_extension,
             commands::uninstall_extension,
+            commands::handle_notification_action,
             commands::get_server_status,
             commands::start_server,
             commands::stop_server,